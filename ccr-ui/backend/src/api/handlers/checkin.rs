@@ -530,7 +530,7 @@ fn paginate_checkin_records(
     mut records: Vec<CheckinRecord>,
     query: &HistoryQuery,
 ) -> (Vec<CheckinRecord>, usize) {
-    records.sort_by(|a, b| b.checked_in_at.cmp(&a.checked_in_at));
+    records.sort_by_key(|r| std::cmp::Reverse(r.checked_in_at));
     let total = records.len();
 
     let use_limit = query.limit.is_some() && query.page.is_none() && query.page_size.is_none();
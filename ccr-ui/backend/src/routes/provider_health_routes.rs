@@ -1,9 +1,20 @@
 //! Provider Health 路由
 //!
-//! 提供 Provider 健康检查的 API 端点
+//! 提供 Provider 健康检查的 API 端点，检查结果写入健康历史
+//! （与 `ccr provider monitor` 共用 `~/.ccr/data.db`）
 
+use crate::models::api::ApiResponse;
 use crate::state::AppState;
-use axum::{Json, Router, routing::get, routing::post};
+use axum::{
+    Json, Router,
+    extract::{Path, Query},
+    routing::get,
+    routing::post,
+};
+use ccr::services::HealthMonitorService;
+use ccr::services::health_check::HealthCheckResult;
+use ccr::services::health_monitor::ProviderHealthHistory;
+use ccr::storage::health_store::ProviderHealthStats;
 use serde::{Deserialize, Serialize};
 
 /// 创建 provider-health 路由
@@ -11,6 +22,15 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/provider-health/test", post(test_provider))
         .route("/provider-health/test-all", get(test_all_providers))
+        .route("/provider-health/status", get(get_health_status))
+        .route("/provider-health/history/{name}", get(get_health_history))
+}
+
+/// 健康历史查询参数
+#[derive(Debug, Deserialize)]
+pub struct HealthHistoryQuery {
+    /// 返回的样本与状态变化数量（默认 50）
+    pub limit: Option<usize>,
 }
 
 /// 健康检查请求
@@ -100,6 +120,7 @@ async fn test_provider(Json(request): Json<HealthCheckRequest>) -> Json<HealthCh
             };
 
             let result = service.check(&request.name, &section).await;
+            record_result(&result);
 
             Json(HealthCheckResponse {
                 provider_name: result.provider_name,
@@ -181,6 +202,7 @@ async fn test_all_providers() -> Json<BatchHealthCheckResponse> {
         };
 
         let result = service.check(&config.name, &section).await;
+        record_result(&result);
 
         match result.status {
             HealthStatus::Healthy => healthy += 1,
@@ -216,3 +238,31 @@ async fn test_all_providers() -> Json<BatchHealthCheckResponse> {
         results,
     })
 }
+
+/// 当前平台所有启用 Provider 的健康统计（p50/p95 延迟、24h/7d 可用率、最近错误）
+async fn get_health_status() -> ApiResponse<Vec<ProviderHealthStats>> {
+    match HealthMonitorService::with_default().and_then(|service| service.status()) {
+        Ok(stats) => ApiResponse::success(stats),
+        Err(e) => ApiResponse::error(format!("无法读取健康历史: {}", e)),
+    }
+}
+
+/// 单个 Provider 的健康统计、最近样本与状态变化
+async fn get_health_history(
+    Path(name): Path<String>,
+    Query(query): Query<HealthHistoryQuery>,
+) -> ApiResponse<ProviderHealthHistory> {
+    let limit = query.limit.unwrap_or(50);
+    match HealthMonitorService::with_default().and_then(|service| service.history(&name, limit)) {
+        Ok(history) => ApiResponse::success(history),
+        Err(e) => ApiResponse::error(format!("无法读取健康历史: {}", e)),
+    }
+}
+
+/// 将检查结果写入健康历史（失败时仅记录日志）
+fn record_result(result: &HealthCheckResult) {
+    if let Err(e) = HealthMonitorService::with_default().and_then(|service| service.record(result))
+    {
+        tracing::warn!("记录 {} 的健康检查结果失败: {}", result.provider_name, e);
+    }
+}
@@ -1164,7 +1164,7 @@ fn build_daily_summaries(snapshots: &[BalanceSnapshot]) -> Vec<DailySummary> {
         })
         .collect();
 
-    daily.sort_by(|a, b| a.date.cmp(&b.date));
+    daily.sort_by_key(|d| d.date);
     daily
 }
 
@@ -90,6 +90,7 @@ pub async fn ws_handler(
 }
 
 /// 处理 WebSocket 连接
+#[allow(clippy::collapsible_match)] // Ping 分支的 if 带有发送副作用,不能合并为 guard
 async fn handle_socket(socket: WebSocket, state: Arc<WsState>) {
     let (mut sender, mut receiver) = socket.split();
 
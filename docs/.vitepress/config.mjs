@@ -84,7 +84,11 @@ export default defineConfig({
                     { text: 'list - 列出配置', link: '/reference/commands/list' },
                     { text: 'current - 当前配置', link: '/reference/commands/current' },
                     { text: 'switch - 切换配置', link: '/reference/commands/switch' },
+                    { text: 'exec - 以配置运行命令', link: '/reference/commands/exec' },
+                    { text: 'env / hook - Shell 集成', link: '/reference/commands/env' },
                     { text: 'validate - 验证配置', link: '/reference/commands/validate' },
+                    { text: 'doctor - 环境诊断', link: '/reference/commands/doctor' },
+                    { text: 'reconcile - 协调设置漂移', link: '/reference/commands/reconcile' },
                     { text: 'enable - 启用配置', link: '/reference/commands/enable' },
                     { text: 'disable - 禁用配置', link: '/reference/commands/disable' },
                     { text: 'clear - 清理配置', link: '/reference/commands/clear' },
@@ -96,6 +100,8 @@ export default defineConfig({
                   collapsed: true,
                   items: [
                     { text: 'history - 操作历史', link: '/reference/commands/history' },
+                    { text: 'undo - 撤销操作', link: '/reference/commands/undo' },
+                    { text: 'backup - 配置快照', link: '/reference/commands/backup' },
                     { text: 'export - 导出配置', link: '/reference/commands/export' },
                     { text: 'import - 导入配置', link: '/reference/commands/import' },
                     { text: 'clean - 清理备份', link: '/reference/commands/clean' }
@@ -139,6 +145,7 @@ export default defineConfig({
                 { text: 'Codex (Copilot)', link: '/reference/platforms/codex' },
                 { text: 'Gemini CLI', link: '/reference/platforms/gemini' },
                 { text: 'Factory Droid', link: '/reference/platforms/droid' },
+                { text: '自定义平台', link: '/reference/platforms/custom' },
                 { text: '平台迁移', link: '/reference/platforms/migration' }
               ]
             },
@@ -2,6 +2,7 @@
 //
 // 定义 CCR 命令行接口的结构，包括主结构和所有子命令
 
+use crate::services::auto_select_service::SELECT_POLICY_NAMES;
 use clap::{Parser, Subcommand};
 
 /// 🎯 Claude Code Configuration Switcher - 配置管理工具
@@ -107,9 +108,33 @@ pub enum Commands {
     ///
     /// 将 Claude Code 的配置切换到指定方案,自动备份当前配置并应用新配置
     /// 示例: ccr switch anthropic
+    ///       ccr switch client-relay --local   # 仅对当前项目生效
+    ///       ccr switch client-relay --dry-run # 预览 settings.json 变化
+    ///       ccr switch --auto --tag relay     # 切换到最快的可用中转
     Switch {
         /// 要切换到的配置方案名称(必须在配置文件中已定义)
-        config_name: String,
+        #[arg(required_unless_present = "auto")]
+        config_name: Option<String>,
+
+        /// 仅对当前项目生效: 写入项目根目录的 .ccr.toml 与 .claude/settings.local.json
+        #[arg(long)]
+        local: bool,
+
+        /// 演练: 显示 settings.json 将发生的变化，不写入任何文件
+        #[arg(long, conflicts_with = "local")]
+        dry_run: bool,
+
+        /// 自动选择: 并发探测启用的配置，切换到按策略最佳的可用配置
+        #[arg(long, conflicts_with_all = ["config_name", "local"])]
+        auto: bool,
+
+        /// 自动选择时只考虑带有该标签的配置
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// 自动选择策略: latency(延迟，默认), cost(模型单价), balance(均衡)
+        #[arg(long, value_parser = SELECT_POLICY_NAMES)]
+        prefer: Option<String>,
     },
 
     /// 添加新的配置方案
@@ -158,11 +183,53 @@ pub enum Commands {
     /// 检查配置文件格式是否正确,以及 Claude Code 设置文件是否有效
     Validate,
 
+    /// 诊断运行环境并可选自动修复
+    ///
+    /// 检查 CLI 工具、shell 环境变量、settings.json 漂移、文件权限、锁文件、配置模式与跨平台冲突
+    /// 示例: ccr doctor
+    ///       ccr doctor --fix
+    ///       ccr doctor --json
+    Doctor {
+        /// 执行所有可自动修复的项
+        #[arg(long)]
+        fix: bool,
+
+        /// 以 JSON 格式输出诊断报告
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 协调设置文件与当前 profile 的差异
+    ///
+    /// 逐项列出设置文件中被修改的值（如在 Claude Code 中运行 /model、手动编辑 settings.json），
+    /// 默认重新应用 profile，使用 --adopt 则将当前值写回 profile
+    /// 示例: ccr reconcile
+    ///       ccr reconcile --adopt
+    ///       ccr reconcile -p codex --dry-run
+    Reconcile {
+        /// 只协调指定平台（默认所有已启用平台）
+        #[arg(short, long)]
+        platform: Option<String>,
+
+        /// 将设置文件中的当前值写回 profile，而不是重新应用 profile
+        #[arg(long)]
+        adopt: bool,
+
+        /// 仅显示差异，不修改任何文件
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// 查看配置操作的历史记录
     ///
     /// 显示所有配置切换、导入导出等操作的审计日志,支持按类型筛选
     /// 示例: ccr history -l 50 -t switch
+    ///       ccr history revert 3f2a9c1d
     History {
+        /// 历史子命令（不传时显示历史记录）
+        #[command(subcommand)]
+        action: Option<super::subcommands::history::HistoryAction>,
+
         /// 显示最近 N 条记录(默认显示 20 条)
         #[arg(short, long, default_value_t = 20)]
         limit: usize,
@@ -170,11 +237,19 @@ pub enum Commands {
         /// 按操作类型筛选记录
         ///
         /// 可选值: switch(切换)、backup(备份)、restore(恢复)、
-        ///         validate(验证)、update(更新)
+        ///         validate(验证)、update(更新)、add(添加)、delete(删除)、
+        ///         enable(启用)、disable(禁用)、revert(撤销)
         #[arg(short = 't', long)]
         filter_type: Option<String>,
     },
 
+    /// 撤销最近一次操作
+    ///
+    /// 撤销最近一次尚未撤销的切换、添加、删除、启用或禁用操作，连续执行依次向前撤销
+    /// 之后的变更与其冲突时拒绝执行并说明原因
+    /// 示例: ccr undo  或  ccr -y undo
+    Undo,
+
     /// 启动轻量级 Legacy Web 界面 / Web API
     ///
     /// 主要用于兼容旧版 Web 界面和脚本/CI 等编程访问场景
@@ -260,6 +335,15 @@ pub enum Commands {
         force: bool,
     },
 
+    /// 🗄️ 配置快照管理
+    ///
+    /// 切换、删除、导入等操作前自动为所有配置文件创建去重快照，按 GFS 策略保留
+    /// 示例: ccr backup list
+    ///       ccr backup diff 3f2a9c1d
+    ///       ccr backup restore 3f2a9c1d
+    ///       ccr backup verify
+    Backup(crate::commands::BackupArgs),
+
     /// 清理过期的备份文件
     ///
     /// 删除 ~/.claude/backups/ 目录中的旧备份文件,释放磁盘空间
@@ -450,4 +534,43 @@ pub enum Commands {
     ///       ccr provider test my-config --verbose
     ///       ccr provider verify my-config
     Provider(crate::commands::provider_cmd::ProviderArgs),
+
+    /// 🔀 本地故障转移代理
+    ///
+    /// 在本机监听并将 Claude Code 请求按顺序转发到多个 profile,
+    /// 上游出现 5xx/429/超时时自动切换到下一个
+    /// 示例: ccr proxy start
+    ///       ccr proxy start -u main,backup
+    ///       ccr proxy status
+    #[cfg(feature = "web")]
+    Proxy(crate::commands::proxy_cmd::ProxyArgs),
+
+    /// ▶️ 以指定 profile 运行命令
+    ///
+    /// 只向子进程注入 profile 的环境变量,不修改全局设置文件
+    /// 示例: ccr exec relay -- claude
+    ///       ccr exec work --platform codex -- codex
+    ///       ccr exec backup --overlay -- droid
+    Exec(crate::commands::exec_cmd::ExecArgs),
+
+    /// 🐚 输出 profile 环境变量的 export / unset 语句
+    ///
+    /// 未指定 profile 时使用当前目录生效的 profile（项目固定优先）
+    /// 示例: eval "$(ccr env relay)"
+    ///       ccr env --shell fish | source
+    Env(crate::commands::env_cmd::EnvArgs),
+
+    /// 🪝 输出 shell 钩子脚本，进入 / 离开固定项目时自动更新环境变量
+    ///
+    /// 示例: eval "$(ccr hook zsh)"   # 加入 ~/.zshrc
+    ///       ccr hook fish | source  # 加入 config.fish
+    Hook(crate::commands::env_cmd::HookArgs),
+
+    /// 🔐 加密保险库
+    ///
+    /// 配置中以 auth_token = "vault:<名称>" 引用 token,切换时才解析为明文
+    /// 示例: ccr vault set anyrouter-main
+    ///       ccr vault migrate --dry-run
+    ///       ccr vault remember
+    Vault(crate::commands::vault_cmd::VaultArgs),
 }
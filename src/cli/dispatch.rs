@@ -2,11 +2,14 @@
 //
 // 将 CLI 命令路由到对应的处理函数
 
+use crate::cli::subcommands::HistoryAction;
 #[cfg(feature = "web")]
 use crate::cli::subcommands::{AllSyncAction, FolderAction};
 use crate::cli::{Cli, Commands};
 use crate::core::error::CcrError;
+use crate::core::journal::{Journal, RecoveryAction};
 use crate::help;
+use colored::Colorize;
 use std::result::Result;
 
 /// 命令分发器
@@ -15,9 +18,37 @@ pub struct CommandDispatcher;
 impl CommandDispatcher {
     /// 分发并执行命令
     pub async fn dispatch(cli: &Cli) -> Result<(), CcrError> {
+        Self::recover_interrupted_transactions();
         Self::dispatch_async(cli).await
     }
 
+    /// 🩹 恢复上次被中断的事务（如 `ccr switch` 写入中途进程被终止）
+    ///
+    /// 提示输出到 stderr，避免干扰 `ccr env` 等命令的标准输出
+    fn recover_interrupted_transactions() {
+        let recovered = match Journal::with_default().and_then(|journal| journal.recover()) {
+            Ok(recovered) => recovered,
+            Err(e) => {
+                tracing::warn!("⚠️ 恢复中断的事务失败: {}", e);
+                return;
+            }
+        };
+
+        for transaction in recovered {
+            let action = match transaction.action {
+                RecoveryAction::Completed => "已补全提交的写入",
+                RecoveryAction::RolledBack => "已恢复操作前的文件",
+            };
+            let message = format!(
+                "检测到上次中断的 {} 操作（开始于 {}），{}",
+                transaction.operation,
+                transaction.started_at.format("%Y-%m-%d %H:%M:%S"),
+                action
+            );
+            eprintln!("{} {}", "⚠".yellow().bold(), message.yellow());
+        }
+    }
+
     /// 异步分发并执行命令
     async fn dispatch_async(cli: &Cli) -> Result<(), CcrError> {
         let auto_yes = cli.auto_yes;
@@ -36,21 +67,67 @@ impl CommandDispatcher {
             Some(Commands::Temp) => crate::commands::temp_command().await,
 
             // 带参数命令
-            Some(Commands::Switch { config_name }) => {
-                crate::commands::switch_command(config_name).await
-            }
+            Some(Commands::Switch {
+                config_name,
+                local,
+                dry_run,
+                auto,
+                tag,
+                prefer,
+            }) => match config_name {
+                _ if !*auto && (tag.is_some() || prefer.is_some()) => Err(
+                    CcrError::ValidationError("--tag 与 --prefer 只能与 --auto 一起使用".into()),
+                ),
+                _ if *auto => {
+                    crate::commands::switch_auto_command(
+                        tag.as_deref(),
+                        prefer.as_deref().unwrap_or("latency"),
+                        *dry_run,
+                    )
+                    .await
+                }
+                Some(config_name) if *dry_run => {
+                    crate::commands::switch_dry_run_command(config_name).await
+                }
+                Some(config_name) if *local => {
+                    crate::commands::switch_local_command(config_name).await
+                }
+                Some(config_name) => crate::commands::switch_command(config_name).await,
+                None => Err(CcrError::ValidationError(
+                    "请指定配置名称或使用 --auto".into(),
+                )),
+            },
             Some(Commands::Delete { config_name, force }) => {
                 crate::commands::delete_command(config_name, auto_yes || *force).await
             }
             Some(Commands::Enable { config_name }) => {
                 crate::commands::enable_command(config_name).await
             }
+            Some(Commands::Doctor { fix, json }) => {
+                crate::commands::doctor_command(*fix, *json).await
+            }
+            Some(Commands::Reconcile {
+                platform,
+                adopt,
+                dry_run,
+            }) => {
+                crate::commands::reconcile_command(platform.as_deref(), *adopt, *dry_run, auto_yes)
+                    .await
+            }
             Some(Commands::Disable { config_name, force }) => {
                 crate::commands::disable_command(config_name, auto_yes || *force).await
             }
-            Some(Commands::History { limit, filter_type }) => {
-                crate::commands::history_command(Some(*limit), filter_type.clone()).await
-            }
+            Some(Commands::History {
+                action,
+                limit,
+                filter_type,
+            }) => match action {
+                Some(HistoryAction::Revert { id }) => {
+                    crate::commands::history_revert_command(id, auto_yes).await
+                }
+                None => crate::commands::history_command(Some(*limit), filter_type.clone()).await,
+            },
+            Some(Commands::Undo) => crate::commands::undo_command(auto_yes).await,
             Some(Commands::Update { check, branch }) => {
                 crate::commands::update_command(*check, branch).await
             }
@@ -74,6 +151,9 @@ impl CommandDispatcher {
                 crate::commands::import_command(input.clone(), mode, *backup, auto_yes || *force)
                     .await
             }
+            Some(Commands::Backup(args)) => {
+                crate::commands::backup_command(args.clone(), auto_yes).await
+            }
             Some(Commands::Clean {
                 days,
                 dry_run,
@@ -130,6 +210,14 @@ impl CommandDispatcher {
             Some(Commands::Provider(args)) => {
                 crate::commands::provider_cmd::execute(args.clone()).await
             }
+            #[cfg(feature = "web")]
+            Some(Commands::Proxy(args)) => crate::commands::proxy_cmd::execute(args.clone()).await,
+            Some(Commands::Exec(args)) => crate::commands::exec_cmd::execute(args.clone()).await,
+            Some(Commands::Env(args)) => crate::commands::env_cmd::execute_env(args.clone()).await,
+            Some(Commands::Hook(args)) => {
+                crate::commands::env_cmd::execute_hook(args.clone()).await
+            }
+            Some(Commands::Vault(args)) => crate::commands::vault_cmd::execute(args.clone()).await,
 
             // 无子命令时的处理
             None => Self::handle_no_subcommand(cli).await,
@@ -226,6 +314,9 @@ impl CommandDispatcher {
             SyncAction::Pull { force } => {
                 crate::commands::sync_cmd::sync_pull_command(*force).await
             }
+            SyncAction::Rekey { disable } => {
+                crate::commands::sync_cmd::sync_rekey_command(*disable).await
+            }
         }
     }
 
@@ -243,12 +334,27 @@ impl CommandDispatcher {
                 local_path,
                 remote_path,
                 description,
-            } => crate::commands::sync_cmd::sync_folder_add_command(
-                name,
-                local_path,
-                remote_path.as_ref(),
-                description.as_ref(),
-            ),
+                backend,
+                target,
+                branch,
+                bucket,
+                region,
+            } => {
+                let backend = crate::commands::sync_cmd::folder_backend_from_args(
+                    backend,
+                    target.as_deref(),
+                    branch.as_deref(),
+                    bucket.as_deref(),
+                    region.as_deref(),
+                )?;
+                crate::commands::sync_cmd::sync_folder_add_command(
+                    name,
+                    local_path,
+                    remote_path.as_ref(),
+                    description.as_ref(),
+                    backend,
+                )
+            }
             FolderAction::Remove { name } => {
                 crate::commands::sync_cmd::sync_folder_remove_command(name)
             }
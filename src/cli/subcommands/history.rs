@@ -0,0 +1,12 @@
+// 历史记录子命令
+#[derive(clap::Subcommand)]
+pub enum HistoryAction {
+    /// 撤销指定的历史操作，恢复到该操作之前的状态
+    ///
+    /// 支持切换、添加、删除、启用、禁用操作；之后的变更与其冲突时拒绝执行
+    /// 示例: ccr history revert 3f2a9c1d
+    Revert {
+        /// 历史记录 ID（可使用 `ccr history` 显示的前 8 位）
+        id: String,
+    },
+}
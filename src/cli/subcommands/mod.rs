@@ -4,12 +4,14 @@
 
 pub mod check;
 pub mod codex;
+pub mod history;
 pub mod platform;
 pub mod sync;
 pub mod ui;
 
 pub use check::CheckAction;
 pub use codex::{CodexAction, CodexAuthAction};
+pub use history::HistoryAction;
 pub use platform::PlatformAction;
 #[cfg(feature = "web")]
 pub use sync::{AllSyncAction, FolderAction, SyncAction};
@@ -47,7 +47,7 @@ pub enum SyncAction {
     /// 示例: ccr sync push --force
     /// 示例: ccr sync push --interactive  # 交互式选择内容
     Push {
-        /// 强制覆盖远程配置，不提示确认
+        /// 强制覆盖远程配置（包括远程领先或冲突的文件）
         #[arg(short, long)]
         force: bool,
 
@@ -61,11 +61,22 @@ pub enum SyncAction {
     /// 从 WebDAV 服务器下载配置文件到本地
     /// 示例: ccr sync pull --force
     Pull {
-        /// 强制覆盖本地配置，不提示确认
+        /// 不提示确认，冲突时一律采用远程内容
         #[arg(short, long)]
         force: bool,
     },
 
+    /// 启用或轮换端到端加密口令
+    ///
+    /// 用当前口令解密远程内容后以新口令重新加密上传
+    /// 示例: ccr sync rekey
+    /// 示例: ccr sync rekey --disable  # 关闭加密并以明文重新上传
+    Rekey {
+        /// 关闭加密，以明文重新上传
+        #[arg(long)]
+        disable: bool,
+    },
+
     /// 同步特定文件夹 (动态子命令)
     ///
     /// 对指定文件夹执行同步操作
@@ -96,6 +107,9 @@ pub enum FolderAction {
     ///
     /// 注册一个新文件夹用于同步
     /// 示例: ccr sync folder add claude ~/.claude
+    /// 示例: ccr sync folder add ccr ~/.ccr --backend git --target git@github.com:me/ccr-sync.git
+    /// 示例: ccr sync folder add ccr ~/.ccr --backend local --target ~/Dropbox/ccr-sync
+    /// 示例: ccr sync folder add ccr ~/.ccr --backend s3 --target http://127.0.0.1:9000 --bucket ccr
     Add {
         /// 文件夹名称（唯一标识）
         name: String,
@@ -110,6 +124,26 @@ pub enum FolderAction {
         /// 描述信息
         #[arg(short = 'd', long)]
         description: Option<String>,
+
+        /// 存储后端: webdav（默认）、local、git、s3
+        #[arg(long, default_value = "webdav", value_parser = ["webdav", "local", "git", "s3"])]
+        backend: String,
+
+        /// 后端目标: local 为目录路径，git 为仓库地址，s3 为服务地址
+        #[arg(long)]
+        target: Option<String>,
+
+        /// Git 分支（git 后端，默认 main）
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// S3 存储桶（s3 后端）
+        #[arg(long)]
+        bucket: Option<String>,
+
+        /// S3 区域（s3 后端，默认 us-east-1；凭证读取 AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY）
+        #[arg(long)]
+        region: Option<String>,
     },
 
     /// 删除同步文件夹注册
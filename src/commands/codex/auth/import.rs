@@ -38,7 +38,7 @@ fn scan_downloads_for_exports() -> Result<Vec<PathBuf>> {
         })
         .collect();
 
-    files.sort_by(|a, b| b.1.cmp(&a.1));
+    files.sort_by_key(|f| std::cmp::Reverse(f.1));
     Ok(files.into_iter().map(|(path, _)| path).collect())
 }
 
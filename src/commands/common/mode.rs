@@ -4,7 +4,7 @@
 use crate::core::error::{CcrError, Result};
 use crate::managers::{PlatformConfigManager, UnifiedConfig};
 use crate::models::{Platform, PlatformConfig, PlatformPaths};
-use crate::platforms::create_platform;
+use crate::platforms::{create_platform_by_name, platform_paths_by_name};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -47,14 +47,14 @@ impl ConfigMode {
 
     /// 获取当前平台路径
     pub fn current_platform_paths(&self) -> Result<PlatformPaths> {
-        let platform = self.current_platform_enum()?;
-        PlatformPaths::new(platform)
+        platform_paths_by_name(self.current_platform())
     }
 
     /// 获取当前平台实现
+    ///
+    /// 支持 manifest 声明的自定义平台
     pub fn current_platform_impl(&self) -> Result<Arc<dyn PlatformConfig>> {
-        let platform = self.current_platform_enum()?;
-        create_platform(platform)
+        create_platform_by_name(self.current_platform())
     }
 }
 
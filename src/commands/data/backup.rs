@@ -0,0 +1,541 @@
+// 🗄️ backup 命令实现 - 管理配置快照
+// 📸 快照由切换、删除、导入等操作自动创建，也可以手动创建
+//
+// 子命令:
+// - create   手动创建快照
+// - list     列出快照
+// - show     查看快照包含的文件
+// - diff     比较两个快照，或快照与当前文件
+// - restore  恢复快照（恢复前自动为当前状态创建快照）；
+//            参数为时间戳时从同步前的增量备份 (~/.ccr/backups/ccr) 恢复
+// - verify   校验所有快照的数据块
+// - prune    按保留策略清理旧快照
+
+use crate::core::error::{CcrError, Result};
+use crate::core::logging::ColorOutput;
+use crate::managers::settings_bundle::SettingsChange;
+use crate::managers::snapshot_store::{Snapshot, short_hash};
+use crate::services::{
+    FileDiff, MultiBackupService, RestoreFileStatus, RestorePlan, SnapshotService, diff_file,
+    is_backup_timestamp,
+};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, presets::UTF8_FULL};
+use std::path::Path;
+
+/// backup 命令参数
+#[derive(Args, Debug, Clone)]
+pub struct BackupArgs {
+    #[command(subcommand)]
+    pub command: BackupCommand,
+}
+
+/// backup 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum BackupCommand {
+    /// 为当前配置文件创建快照
+    ///
+    /// 示例: ccr backup create --reason before-cleanup
+    Create {
+        /// 快照原因（显示在列表中）
+        #[arg(short, long, default_value = "manual")]
+        reason: String,
+    },
+
+    /// 列出所有快照
+    List,
+
+    /// 查看快照包含的文件
+    ///
+    /// 示例: ccr backup show 3f2a9c1d
+    Show {
+        /// 快照 ID（可使用前 8 位）
+        id: String,
+    },
+
+    /// 比较两个快照；省略第二个 ID 时与当前文件比较
+    ///
+    /// 示例: ccr backup diff 3f2a9c1d
+    ///       ccr backup diff 3f2a9c1d 7c1e2b9a
+    Diff {
+        /// 旧快照 ID
+        from: String,
+
+        /// 新快照 ID（默认为当前文件）
+        to: Option<String>,
+    },
+
+    /// 恢复快照中的所有文件
+    ///
+    /// 恢复前会为当前状态创建快照，可再次恢复
+    /// 参数为时间戳时从同步前的增量备份恢复，可用 --only 选择来源
+    /// 示例: ccr backup restore 3f2a9c1d
+    ///       ccr backup restore 20250101_120000 --only claude,codex
+    Restore {
+        /// 快照 ID（可使用前 8 位）或增量备份时间戳
+        id: String,
+
+        /// 仅恢复指定来源（逗号分隔，仅适用于增量备份）
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<String>,
+    },
+
+    /// 校验所有快照的数据块是否完整
+    Verify,
+
+    /// 按保留策略清理旧快照
+    ///
+    /// 示例: ccr backup prune --dry-run
+    Prune {
+        /// 仅显示将要删除的快照
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// 🗄️ 执行 backup 命令
+pub async fn backup_command(args: BackupArgs, skip_confirmation: bool) -> Result<()> {
+    let service = SnapshotService::with_default()?;
+    match args.command {
+        BackupCommand::Create { reason } => cmd_create(&service, &reason),
+        BackupCommand::List => cmd_list(&service),
+        BackupCommand::Show { id } => cmd_show(&service, &id),
+        BackupCommand::Diff { from, to } => cmd_diff(&service, &from, to.as_deref()),
+        BackupCommand::Restore { id, only } if is_backup_timestamp(&id) => {
+            cmd_restore_incremental(&id, &only, skip_confirmation).await
+        }
+        BackupCommand::Restore { only, .. } if !only.is_empty() => Err(CcrError::ValidationError(
+            "--only 仅适用于增量备份时间戳（如 20250101_120000）".into(),
+        )),
+        BackupCommand::Restore { id, .. } => cmd_restore(&service, &id, skip_confirmation).await,
+        BackupCommand::Verify => cmd_verify(&service),
+        BackupCommand::Prune { dry_run } => cmd_prune(&service, dry_run),
+    }
+}
+
+/// 📸 创建快照
+fn cmd_create(service: &SnapshotService, reason: &str) -> Result<()> {
+    ColorOutput::title("创建配置快照");
+    println!();
+
+    let snapshot = service.capture_now(reason)?;
+    ColorOutput::success(&format!(
+        "已创建快照 {}（{} 个文件，{}）",
+        snapshot.short_id(),
+        snapshot.files.len(),
+        format_size(snapshot.total_size())
+    ));
+    Ok(())
+}
+
+/// 📋 列出快照
+fn cmd_list(service: &SnapshotService) -> Result<()> {
+    ColorOutput::title("配置快照");
+    println!();
+
+    let snapshots = service.store().list()?;
+    if snapshots.is_empty() {
+        ColorOutput::info("暂无快照，运行 'ccr backup create' 创建");
+        return print_incremental_backups();
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        Cell::new("ID").fg(Color::Cyan),
+        Cell::new("时间").fg(Color::Cyan),
+        Cell::new("原因").fg(Color::Cyan),
+        Cell::new("文件").fg(Color::Cyan),
+        Cell::new("大小").fg(Color::Cyan),
+    ]);
+    for snapshot in &snapshots {
+        table.add_row(vec![
+            Cell::new(snapshot.short_id()).fg(Color::Yellow),
+            Cell::new(snapshot.created_at.format("%Y-%m-%d %H:%M:%S")),
+            Cell::new(&snapshot.reason),
+            Cell::new(snapshot.files.len()),
+            Cell::new(format_size(snapshot.total_size())),
+        ]);
+    }
+    println!("{}", table);
+
+    let usage = service.store().usage()?;
+    let logical: u64 = snapshots.iter().map(Snapshot::total_size).sum();
+    println!();
+    ColorOutput::info(&format!(
+        "共 {} 个快照，{} 个数据块，占用 {}（去重前 {}）",
+        snapshots.len(),
+        usage.objects,
+        format_size(usage.bytes),
+        format_size(logical)
+    ));
+    ColorOutput::info(&format!(
+        "存储位置: {}",
+        display_path(service.store().root())
+    ));
+
+    print_incremental_backups()?;
+    Ok(())
+}
+
+/// 🔍 查看快照
+fn cmd_show(service: &SnapshotService, id: &str) -> Result<()> {
+    let snapshot = service.store().find(id)?;
+    ColorOutput::title(&format!("快照 {}", snapshot.short_id()));
+    println!();
+
+    ColorOutput::key_value("ID", &snapshot.id, 2);
+    ColorOutput::key_value(
+        "时间",
+        &snapshot.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        2,
+    );
+    ColorOutput::key_value("原因", &snapshot.reason, 2);
+    println!();
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        Cell::new("文件").fg(Color::Cyan),
+        Cell::new("大小").fg(Color::Cyan),
+        Cell::new("摘要").fg(Color::Cyan),
+        Cell::new("数据块").fg(Color::Cyan),
+    ]);
+    for file in &snapshot.files {
+        table.add_row(vec![
+            Cell::new(display_path(&file.path)),
+            Cell::new(format_size(file.size)),
+            Cell::new(short_hash(&file.hash)),
+            Cell::new(file.chunks.len()),
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+/// 🔍 比较快照
+fn cmd_diff(service: &SnapshotService, from: &str, to: Option<&str>) -> Result<()> {
+    let from = service.store().find(from)?;
+    let to = to.map(|id| service.store().find(id)).transpose()?;
+    let to_label = to
+        .as_ref()
+        .map(|s| s.short_id().to_string())
+        .unwrap_or_else(|| "当前文件".to_string());
+
+    ColorOutput::title(&format!("快照差异: {} → {}", from.short_id(), to_label));
+    println!();
+
+    let diffs = service.diff(&from, to.as_ref())?;
+    if diffs.is_empty() {
+        ColorOutput::success("没有差异");
+        return Ok(());
+    }
+
+    for diff in &diffs {
+        print_file_diff(diff);
+        println!();
+    }
+    ColorOutput::info(&format!("{} 个文件有差异", diffs.len()));
+    Ok(())
+}
+
+/// 🔄 恢复快照
+async fn cmd_restore(service: &SnapshotService, id: &str, skip_confirmation: bool) -> Result<()> {
+    let snapshot = service.store().find(id)?;
+    ColorOutput::title(&format!("恢复快照 {}", snapshot.short_id()));
+    println!();
+
+    ColorOutput::step("步骤 1/2: 检查变化");
+    let diffs = service.diff(&snapshot, None)?;
+    let changed: Vec<&FileDiff> = diffs
+        .iter()
+        .filter(|diff| snapshot.file(&diff.path).is_some())
+        .collect();
+    if changed.is_empty() {
+        ColorOutput::success("当前文件与快照相同，无需恢复");
+        return Ok(());
+    }
+    for diff in &changed {
+        println!("  {} {}", "~".yellow(), display_path(&diff.path));
+    }
+    println!();
+
+    if !skip_confirmation {
+        let prompt = format!("确认将 {} 个文件恢复到该快照?", changed.len());
+        let confirmed = tokio::task::spawn_blocking(move || -> Result<bool> {
+            Ok(ColorOutput::ask_confirmation(&prompt, false))
+        })
+        .await
+        .map_err(|e| CcrError::FileIoError(format!("读取用户输入失败: {e}")))??;
+
+        if !confirmed {
+            ColorOutput::info("已取消恢复");
+            return Ok(());
+        }
+    }
+
+    ColorOutput::step("步骤 2/2: 恢复文件");
+    let result = service.restore(&snapshot)?;
+    ColorOutput::success(&format!(
+        "已恢复快照 {}（{} 个文件）",
+        snapshot.short_id(),
+        result.restored.len()
+    ));
+    if let Some(safety) = &result.safety_snapshot {
+        ColorOutput::info(&format!(
+            "恢复前的状态已保存为快照 {}，可通过 'ccr backup restore {}' 还原",
+            safety.short_id(),
+            safety.short_id()
+        ));
+    }
+    Ok(())
+}
+
+/// 📦 列出同步前的增量备份（存在时）
+fn print_incremental_backups() -> Result<()> {
+    let backups = MultiBackupService::with_default()?.list_backups();
+    if backups.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    ColorOutput::title("同步前增量备份");
+    println!();
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        Cell::new("时间戳").fg(Color::Cyan),
+        Cell::new("变化的来源").fg(Color::Cyan),
+    ]);
+    for point in &backups {
+        table.add_row(vec![
+            Cell::new(&point.timestamp).fg(Color::Yellow),
+            Cell::new(point.sources.join(", ")),
+        ]);
+    }
+    println!("{}", table);
+    println!();
+    ColorOutput::info("恢复: ccr backup restore <时间戳> [--only claude,codex]");
+    Ok(())
+}
+
+/// 🔄 从增量备份恢复
+async fn cmd_restore_incremental(
+    timestamp: &str,
+    only: &[String],
+    skip_confirmation: bool,
+) -> Result<()> {
+    let service = MultiBackupService::with_default()?;
+    let plan = service.plan_restore(timestamp, only)?;
+    ColorOutput::title(&format!("恢复增量备份 {}", timestamp));
+    println!();
+
+    ColorOutput::step("步骤 1/2: 检查变化");
+    print_restore_plan(&plan);
+
+    let changed: Vec<_> = plan.changed_files().collect();
+    if changed.is_empty() {
+        ColorOutput::success("当前文件与备份相同，无需恢复");
+        return Ok(());
+    }
+    for file in &changed {
+        let old = std::fs::read(&file.target_path).ok();
+        let new = std::fs::read(&file.backup_path).ok();
+        if let Some(diff) = diff_file(file.target_path.clone(), old.as_deref(), new.as_deref()) {
+            print_file_diff(&diff);
+        }
+    }
+    println!();
+
+    if !skip_confirmation {
+        let prompt = format!("确认将 {} 个文件恢复到该备份?", changed.len());
+        let confirmed = tokio::task::spawn_blocking(move || -> Result<bool> {
+            Ok(ColorOutput::ask_confirmation(&prompt, false))
+        })
+        .await
+        .map_err(|e| CcrError::FileIoError(format!("读取用户输入失败: {e}")))??;
+
+        if !confirmed {
+            ColorOutput::info("已取消恢复");
+            return Ok(());
+        }
+    }
+
+    ColorOutput::step("步骤 2/2: 恢复文件");
+    let outcome = service.restore(&plan)?;
+    ColorOutput::success(&format!(
+        "已恢复备份 {}（{} 个文件）",
+        timestamp,
+        outcome.restored.len()
+    ));
+    if let Some(safety) = &outcome.safety_timestamp {
+        ColorOutput::info(&format!(
+            "恢复前的状态已保存在备份 {} 中，可通过 'ccr backup restore {}' 还原",
+            safety, safety
+        ));
+    }
+    Ok(())
+}
+
+/// 📋 显示增量备份中各来源的内容
+fn print_restore_plan(plan: &RestorePlan) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        Cell::new("来源").fg(Color::Cyan),
+        Cell::new("备份时间戳").fg(Color::Cyan),
+        Cell::new("位置").fg(Color::Cyan),
+        Cell::new("文件").fg(Color::Cyan),
+        Cell::new("新增").fg(Color::Cyan),
+        Cell::new("修改").fg(Color::Cyan),
+    ]);
+    for source in &plan.sources {
+        let count = |status| {
+            source
+                .files
+                .iter()
+                .filter(|file| file.status == status)
+                .count()
+        };
+        table.add_row(vec![
+            Cell::new(&source.name).fg(Color::Green),
+            Cell::new(&source.timestamp),
+            Cell::new(display_path(&source.target_path)),
+            Cell::new(source.files.len()),
+            Cell::new(count(RestoreFileStatus::Added)),
+            Cell::new(count(RestoreFileStatus::Modified)),
+        ]);
+    }
+    println!("{}", table);
+    println!();
+}
+
+/// ✅ 校验快照
+fn cmd_verify(service: &SnapshotService) -> Result<()> {
+    ColorOutput::title("校验配置快照");
+    println!();
+
+    let report = service.store().verify()?;
+    ColorOutput::key_value("快照", &report.snapshots.to_string(), 2);
+    ColorOutput::key_value("数据块", &report.objects.to_string(), 2);
+    if report.orphans > 0 {
+        ColorOutput::key_value(
+            "未引用",
+            &format!("{}（运行 'ccr backup prune' 清理）", report.orphans),
+            2,
+        );
+    }
+    println!();
+
+    if report.issues.is_empty() {
+        ColorOutput::success("所有快照完整");
+        return Ok(());
+    }
+
+    for issue in &report.issues {
+        ColorOutput::error(&format!(
+            "{} {}: {}",
+            issue.snapshot_id.get(..8).unwrap_or(&issue.snapshot_id),
+            display_path(&issue.path),
+            issue.problem
+        ));
+    }
+    Err(CcrError::BackupCorrupted(format!(
+        "{} 处问题",
+        report.issues.len()
+    )))
+}
+
+/// 🧹 清理快照
+fn cmd_prune(service: &SnapshotService, dry_run: bool) -> Result<()> {
+    ColorOutput::title(if dry_run {
+        "清理旧快照 (预览)"
+    } else {
+        "清理旧快照"
+    });
+    println!();
+
+    let policy = service.retention_policy();
+    ColorOutput::info(&format!(
+        "保留策略: 最近 {} 个，每小时 {}，每天 {}，每周 {}，每月 {}",
+        policy.keep_last, policy.hourly, policy.daily, policy.weekly, policy.monthly
+    ));
+
+    let result = service.store().prune(&policy, dry_run)?;
+    for snapshot in &result.removed {
+        println!(
+            "  🗑️ {} {} {}",
+            snapshot.short_id(),
+            snapshot.created_at.format("%Y-%m-%d %H:%M:%S"),
+            snapshot.reason.dimmed()
+        );
+    }
+    println!();
+
+    let summary = format!(
+        "{} 个快照，{} 个数据块，{}",
+        result.removed.len(),
+        result.removed_objects,
+        format_size(result.freed_bytes)
+    );
+    if dry_run {
+        ColorOutput::info(&format!("将删除 {}（未做任何修改）", summary));
+    } else {
+        ColorOutput::success(&format!("已删除 {}", summary));
+    }
+    Ok(())
+}
+
+/// 📄 显示单个文件的差异
+fn print_file_diff(diff: &FileDiff) {
+    let path = display_path(&diff.path);
+    match (diff.old_size, diff.new_size) {
+        (None, _) => println!("{} {}", "+".green(), path.green()),
+        (_, None) => println!("{} {}", "-".red(), path.red()),
+        (Some(old), Some(new)) => {
+            println!("{} {}", "~".yellow(), path.yellow());
+            if diff.changes.is_empty() {
+                println!(
+                    "    内容已变化 ({} → {})",
+                    format_size(old),
+                    format_size(new)
+                );
+            }
+        }
+    }
+    for change in &diff.changes {
+        print_change(change);
+    }
+}
+
+fn print_change(change: &SettingsChange) {
+    let render = |value: &serde_json::Value| value.to_string();
+    match (&change.old, &change.new) {
+        (None, Some(new)) => println!("    {} {}: {}", "+".green(), change.path, render(new)),
+        (Some(old), None) => println!("    {} {}: {}", "-".red(), change.path, render(old)),
+        (Some(old), Some(new)) => println!(
+            "    {} {}: {} → {}",
+            "~".yellow(),
+            change.path,
+            render(old),
+            render(new)
+        ),
+        (None, None) => {}
+    }
+}
+
+/// 🏠 将主目录显示为 ~
+fn display_path(path: &Path) -> String {
+    dirs::home_dir()
+        .and_then(|home| path.strip_prefix(home).ok())
+        .map(|rest| format!("~/{}", rest.display()))
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// 📏 人类可读的大小
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.2} MB", bytes as f64 / 1024.0 / 1024.0)
+    }
+}
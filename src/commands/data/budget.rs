@@ -4,7 +4,7 @@
 use crate::core::ColorOutput;
 use crate::core::error::{CcrError, Result};
 use crate::managers::{BudgetManager, CostTracker};
-use crate::models::budget::BudgetPeriod;
+use crate::models::budget::{BudgetPeriod, BudgetScope, LimitAction, ScopedLimits};
 use clap::{Args, Subcommand};
 use comfy_table::{Cell, CellAlignment, Color, ContentArrangement, Table};
 
@@ -31,6 +31,9 @@ pub enum BudgetSubcommand {
     ///   ccr budget set --weekly 50.0 --monthly 200.0
     ///   ccr budget set --warn-at 90
     ///   ccr budget set --enable
+    ///   ccr budget set --action fallback --fallback cheap
+    ///   ccr budget set --profile work --daily 5.0
+    ///   ccr budget set --project ~/code/app --monthly 50.0
     Set(SetArgs),
 
     /// 🗑️ 重置所有预算限制
@@ -67,6 +70,26 @@ pub struct SetArgs {
     /// ❌ 禁用预算控制
     #[arg(long)]
     pub disable: bool,
+
+    /// 🔔 超出限制时的动作: warn, log, none, block_paid, fallback, downgrade
+    #[arg(long)]
+    pub action: Option<LimitAction>,
+
+    /// 🔀 fallback 动作切换到的低成本 profile
+    #[arg(long)]
+    pub fallback: Option<String>,
+
+    /// 🆓 不受预算动作限制的免费 profile 列表（逗号分隔）
+    #[arg(long, value_delimiter = ',')]
+    pub free: Option<Vec<String>>,
+
+    /// 👤 将 --daily/--weekly/--monthly 应用到指定 profile
+    #[arg(long, conflicts_with = "project")]
+    pub profile: Option<String>,
+
+    /// 📁 将 --daily/--weekly/--monthly 应用到指定项目目录
+    #[arg(long)]
+    pub project: Option<std::path::PathBuf>,
 }
 
 /// 🗑️ 重置参数
@@ -90,6 +113,7 @@ pub async fn budget_command(args: BudgetArgs) -> Result<()> {
 async fn status_command() -> Result<()> {
     // 加载预算管理器和成本追踪器
     let budget_manager = BudgetManager::with_default()?;
+    crate::managers::transcript_collector::auto_collect();
     let storage_dir = CostTracker::default_storage_dir()?;
     let tracker = CostTracker::new(storage_dir)?;
 
@@ -157,6 +181,10 @@ async fn status_command() -> Result<()> {
                 BudgetPeriod::Weekly => "每周",
                 BudgetPeriod::Monthly => "每月",
             };
+            let period_str = match &warning.scope {
+                Some(scope) => format!("[{}] {}", scope, period_str),
+                None => period_str.to_string(),
+            };
 
             if warning.usage_percent >= 100.0 {
                 ColorOutput::error(&format!(
@@ -173,6 +201,11 @@ async fn status_command() -> Result<()> {
     }
 
     println!();
+    let config = budget_manager.get_config();
+    ColorOutput::info(&format!("超出限制时的动作: {}", config.on_limit_exceeded));
+    if let Some(fallback) = &config.fallback_profile {
+        ColorOutput::info(&format!("fallback profile: {}", fallback));
+    }
     ColorOutput::info(&format!(
         "最后更新: {}",
         status.last_updated.format("%Y-%m-%d %H:%M:%S")
@@ -181,6 +214,19 @@ async fn status_command() -> Result<()> {
     Ok(())
 }
 
+/// 描述范围预算限制
+fn describe_limits(limits: &ScopedLimits) -> String {
+    [
+        ("每日", limits.daily_limit),
+        ("每周", limits.weekly_limit),
+        ("每月", limits.monthly_limit),
+    ]
+    .iter()
+    .filter_map(|(label, limit)| limit.map(|l| format!("{} ${:.2}", label, l)))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
 /// 添加周期行到表格
 fn add_period_row(table: &mut Table, period: &str, current: f64, limit: Option<f64>) {
     let current_str = format!("${:.2}", current);
@@ -239,6 +285,74 @@ async fn set_command(args: SetArgs) -> Result<()> {
         changed = true;
     }
 
+    // 超出限制时的动作与 fallback profile
+    // 先设置 fallback profile，保证 `--action fallback --fallback x` 可一次完成
+    if let Some(fallback) = args.fallback {
+        manager.set_fallback_profile(Some(fallback.clone()))?;
+        ColorOutput::success(&format!("✅ fallback profile 已设置为: {}", fallback));
+        changed = true;
+    }
+
+    if let Some(action) = args.action {
+        manager.set_action(action)?;
+        ColorOutput::success(&format!("✅ 超出限制时的动作已设置为: {}", action));
+        changed = true;
+    }
+
+    if let Some(free) = args.free {
+        ColorOutput::success(&format!("✅ 免费 profile: {}", free.join(", ")));
+        manager.set_free_profiles(free)?;
+        changed = true;
+    }
+
+    // profile / 项目级别的预算限制
+    let scope = if let Some(profile) = args.profile {
+        Some(BudgetScope::Profile(profile))
+    } else if let Some(project) = args.project {
+        let project = std::fs::canonicalize(&project).unwrap_or(project);
+        Some(BudgetScope::Project(project.to_string_lossy().to_string()))
+    } else {
+        None
+    };
+
+    if let Some(scope) = scope {
+        let config = manager.get_config();
+        let mut limits = match &scope {
+            BudgetScope::Profile(name) => config.profiles.get(name),
+            BudgetScope::Project(path) => config.projects.get(path),
+        }
+        .cloned()
+        .unwrap_or_default();
+
+        if args.daily.is_none() && args.weekly.is_none() && args.monthly.is_none() {
+            return Err(CcrError::ValidationError(
+                "--profile/--project 需要配合 --daily、--weekly 或 --monthly 使用".to_string(),
+            ));
+        }
+        for limit in [args.daily, args.weekly, args.monthly]
+            .into_iter()
+            .flatten()
+        {
+            if limit < 0.0 {
+                return Err(CcrError::ValidationError("预算限制不能为负数".to_string()));
+            }
+        }
+        limits.daily_limit = args.daily.or(limits.daily_limit);
+        limits.weekly_limit = args.weekly.or(limits.weekly_limit);
+        limits.monthly_limit = args.monthly.or(limits.monthly_limit);
+
+        manager.set_scoped_limits(&scope, limits.clone())?;
+        ColorOutput::success(&format!(
+            "✅ {} 的预算限制已设置: {}",
+            scope,
+            describe_limits(&limits)
+        ));
+
+        println!();
+        ColorOutput::info("💡 提示: 使用 `ccr budget status` 查看当前预算状态");
+        return Ok(());
+    }
+
     // 设置预算限制
     if let Some(daily) = args.daily {
         manager.set_daily_limit(Some(daily))?;
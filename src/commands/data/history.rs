@@ -7,6 +7,7 @@ use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
 use crate::managers::history::OperationType;
 use crate::services::HistoryService;
+use crate::services::revert_service::short_id;
 use colored::*;
 
 /// 📚 显示操作历史
@@ -19,7 +20,7 @@ use colored::*;
 ///
 /// 参数:
 /// - limit: 显示记录数量(默认 20)
-/// - filter_type: 按操作类型筛选(switch/backup/restore/validate/update)
+/// - filter_type: 按操作类型筛选(switch/backup/restore/validate/update/add/delete/enable/disable/revert)
 pub async fn history_command(limit: Option<usize>, filter_type: Option<String>) -> Result<()> {
     ColorOutput::title("操作历史记录");
     println!();
@@ -36,9 +37,18 @@ pub async fn history_command(limit: Option<usize>, filter_type: Option<String>)
             "restore" => OperationType::Restore,
             "validate" => OperationType::Validate,
             "update" => OperationType::Update,
+            "add" => OperationType::Add,
+            "delete" => OperationType::Delete,
+            "enable" => OperationType::Enable,
+            "disable" => OperationType::Disable,
+            "revert" => OperationType::Revert,
+            "local_switch" => OperationType::LocalSwitch,
+            "auto_keep" => OperationType::AutoKeep,
             _ => {
                 ColorOutput::error(&format!("未知的操作类型: {}", type_str));
-                ColorOutput::info("支持的类型: switch, backup, restore, validate, update");
+                ColorOutput::info(
+                    "支持的类型: switch, backup, restore, validate, update, add, delete, enable, disable, revert, local_switch, auto_keep",
+                );
                 return Ok(());
             }
         };
@@ -82,6 +92,7 @@ pub async fn history_command(limit: Option<usize>, filter_type: Option<String>)
         };
 
         println!("{}. [{}] {} - {}", index + 1, time_str, op_str, result_str);
+        println!("   ID: {}", short_id(&entry.id).dimmed());
         println!("   操作者: {}", entry.actor);
 
         // 显示详情
@@ -94,6 +105,9 @@ pub async fn history_command(limit: Option<usize>, filter_type: Option<String>)
         if let Some(backup) = &entry.details.backup_path {
             println!("   备份: {}", backup);
         }
+        if let Some(extra) = &entry.details.extra {
+            println!("   说明: {}", extra);
+        }
 
         // 显示环境变量变化
         if !entry.env_changes.is_empty() {
@@ -111,6 +111,9 @@ pub async fn import_command(
         println!();
     }
 
+    // 📸 无论是否备份，导入前都为所有配置文件创建快照
+    crate::services::auto_snapshot("import");
+
     // 执行导入
     let step_msg = if backup {
         if skip_confirmation {
@@ -1,7 +1,8 @@
 //! 📦 data 命令模块
 //!
-//! 导出、导入、历史记录、统计等数据相关操作。
+//! 导出、导入、快照、历史记录、统计等数据相关操作。
 
+mod backup;
 #[cfg(feature = "web")]
 mod budget;
 mod export;
@@ -11,7 +12,9 @@ mod import;
 mod pricing;
 #[cfg(feature = "web")]
 mod stats;
+mod undo;
 
+pub use backup::{BackupArgs, backup_command};
 #[cfg(feature = "web")]
 pub use budget::{BudgetArgs, budget_command};
 pub use export::export_command;
@@ -21,3 +24,4 @@ pub use import::{ImportMode, import_command};
 pub use pricing::{PricingArgs, pricing_command};
 #[cfg(feature = "web")]
 pub use stats::{StatsArgs, stats_command};
+pub use undo::{history_revert_command, undo_command};
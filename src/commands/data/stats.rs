@@ -3,7 +3,7 @@
 
 use crate::core::ColorOutput;
 use crate::core::error::{CcrError, Result};
-use crate::managers::CostTracker;
+use crate::managers::{CostTracker, transcript_collector};
 use crate::models::stats::{CostRecord, TokenUsage};
 use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
 use clap::{Args, Subcommand};
@@ -35,6 +35,16 @@ pub enum StatsSubcommand {
     ///   ccr stats import costs.csv --format claude-hub
     Import(ImportArgs),
 
+    /// 🔄 从 Claude Code 会话日志采集成本记录
+    ///
+    /// 增量读取 ~/.claude/projects/**/*.jsonl，并按切换历史归属到 profile。
+    /// summary / budget status 会自动执行一次采集。
+    ///
+    /// 示例:
+    ///   ccr stats collect
+    ///   ccr stats collect --watch --interval 60
+    Collect(CollectArgs),
+
     /// 📤 导出统计数据
     ///
     /// 示例:
@@ -106,6 +116,18 @@ pub struct ImportArgs {
     pub skip_validation: bool,
 }
 
+/// 🔄 采集参数
+#[derive(Args, Clone)]
+pub struct CollectArgs {
+    /// 👀 持续监听会话日志（Ctrl+C 停止）
+    #[arg(long)]
+    pub watch: bool,
+
+    /// ⏱️ 监听模式下的采集间隔（秒）
+    #[arg(long, default_value = "30")]
+    pub interval: u64,
+}
+
 /// 📤 导出参数
 #[derive(Args, Clone)]
 pub struct ExportArgs {
@@ -154,6 +176,7 @@ pub async fn stats_command(args: StatsArgs, _color_output: &mut ColorOutput) ->
             summary_command(summary_args).await
         }
         StatsSubcommand::Import(import_args) => import_command(import_args).await,
+        StatsSubcommand::Collect(collect_args) => collect_command(collect_args).await,
         StatsSubcommand::Export(export_args) => export_command(export_args).await,
         StatsSubcommand::Clear(clear_args) => clear_command(clear_args).await,
     }
@@ -161,6 +184,7 @@ pub async fn stats_command(args: StatsArgs, _color_output: &mut ColorOutput) ->
 
 /// 📊 执行统计摘要命令
 async fn summary_command(args: SummaryArgs) -> Result<()> {
+    transcript_collector::auto_collect();
     let storage_dir = CostTracker::default_storage_dir()?;
     let tracker = CostTracker::new(storage_dir)?;
 
@@ -341,6 +365,60 @@ async fn import_command(args: ImportArgs) -> Result<()> {
     Ok(())
 }
 
+/// 🔄 执行采集命令
+async fn collect_command(args: CollectArgs) -> Result<()> {
+    ColorOutput::title("🔄 采集会话日志成本");
+    println!();
+
+    let report = tokio::task::spawn_blocking(transcript_collector::collect_default)
+        .await
+        .map_err(|e| CcrError::ConfigError(format!("采集任务失败: {}", e)))??;
+    ColorOutput::info(&format!("📄 扫描文件: {}", report.files_scanned));
+    ColorOutput::success(&format!("✅ 新增记录: {} 条", report.records_added));
+    if report.files_reset > 0 {
+        ColorOutput::warning(&format!(
+            "⚠️  {} 个会话日志被重写，已从头重新读取",
+            report.files_reset
+        ));
+    }
+
+    if !args.watch {
+        return Ok(());
+    }
+
+    println!();
+    ColorOutput::info(&format!(
+        "👀 每 {} 秒采集一次，按 Ctrl+C 停止",
+        args.interval.max(1)
+    ));
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(args.interval.max(1)));
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                match tokio::task::spawn_blocking(transcript_collector::collect_default).await {
+                    Ok(Ok(report)) if report.records_added > 0 => {
+                        ColorOutput::success(&format!(
+                            "[{}] 新增 {} 条记录",
+                            chrono::Local::now().format("%H:%M:%S"),
+                            report.records_added
+                        ));
+                    }
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => ColorOutput::warning(&format!("⚠️  采集失败: {}", e)),
+                    Err(e) => ColorOutput::warning(&format!("⚠️  采集任务失败: {}", e)),
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                println!();
+                ColorOutput::info("⏹️  已停止采集");
+                return Ok(());
+            }
+        }
+    }
+}
+
 /// 📤 执行导出命令
 async fn export_command(args: ExportArgs) -> Result<()> {
     ColorOutput::title("📤 导出统计数据");
@@ -665,6 +743,10 @@ fn parse_csv_line_standard(
         } else {
             None
         },
+        profile: parts
+            .get(16)
+            .filter(|p| !p.is_empty())
+            .map(|p| p.to_string()),
     })
 }
 
@@ -0,0 +1,90 @@
+// ↩️ undo / history revert 命令实现 - 撤销历史操作
+// 🔁 根据历史记录恢复到某个操作之前的状态
+//
+// 执行流程:
+// 1. 🔍 找到要撤销的历史记录（最近一次或指定 ID）
+// 2. ⚔️ 检查之后的变更是否冲突，冲突时拒绝并说明原因
+// 3. 🤔 确认后在同一事务中执行撤销并记录 Revert 历史
+
+use crate::core::error::{CcrError, Result};
+use crate::core::logging::ColorOutput;
+use crate::managers::history::HistoryEntry;
+use crate::services::RevertService;
+use crate::services::revert_service::short_id;
+use colored::Colorize;
+
+/// ↩️ 撤销最近一次尚未撤销的操作
+pub async fn undo_command(skip_confirmation: bool) -> Result<()> {
+    ColorOutput::title("撤销最近一次操作");
+    println!();
+
+    let service = RevertService::with_default()?;
+    let entry = service.last_revertable()?;
+    revert(&service, &entry, skip_confirmation).await
+}
+
+/// ↩️ 撤销指定的历史操作
+pub async fn history_revert_command(id: &str, skip_confirmation: bool) -> Result<()> {
+    ColorOutput::title(&format!("撤销历史操作: {}", id));
+    println!();
+
+    let service = RevertService::with_default()?;
+    let entry = service.find(id)?;
+    revert(&service, &entry, skip_confirmation).await
+}
+
+async fn revert(
+    service: &RevertService,
+    entry: &HistoryEntry,
+    skip_confirmation: bool,
+) -> Result<()> {
+    ColorOutput::step("步骤 1/2: 检查冲突");
+    println!(
+        "  操作: {} ({}, {})",
+        entry.operation.as_str().bright_yellow(),
+        short_id(&entry.id),
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S")
+    );
+    if let Some(from) = &entry.details.from_config {
+        println!("  从: {}", from);
+    }
+    if let Some(to) = &entry.details.to_config {
+        println!("  到: {}", to);
+    }
+    println!();
+
+    let plan = service.plan(entry)?;
+    ColorOutput::success("没有冲突");
+    for note in &plan.notes {
+        ColorOutput::warning(note);
+    }
+    println!();
+
+    ColorOutput::step(&format!("步骤 2/2: {}", plan.action.describe()));
+    if !skip_confirmation {
+        let confirmed = tokio::task::spawn_blocking(|| -> Result<bool> {
+            Ok(ColorOutput::ask_confirmation("确认撤销?", false))
+        })
+        .await
+        .map_err(|e| CcrError::FileIoError(format!("读取用户输入失败: {e}")))??;
+
+        if !confirmed {
+            ColorOutput::info("已取消撤销");
+            return Ok(());
+        }
+    }
+
+    let revert_entry = service.apply(&plan).await?;
+    ColorOutput::success(&format!(
+        "已撤销{}操作 {}",
+        entry.operation.as_str(),
+        short_id(&entry.id)
+    ));
+    ColorOutput::info(&format!(
+        "撤销已记录为 {}，运行 'ccr history' 查看",
+        short_id(&revert_entry.id)
+    ));
+    println!();
+
+    Ok(())
+}
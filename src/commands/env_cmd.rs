@@ -0,0 +1,379 @@
+//! 🐚 Shell 集成
+//!
+//! - `ccr env [profile] --shell <shell>`: 输出 profile 环境变量的 export / unset 语句
+//! - `ccr hook <shell>`: 输出 shell 钩子，进入 / 离开固定了 profile 的项目时自动更新环境变量
+//!
+//! 适用于通过 shell 环境变量而不是 settings.json 使用 CLI 的场景。
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::PlatformConfigManager;
+use crate::managers::project_config::{ProfileSource, resolve_effective_profile};
+use crate::models::PlatformConfig;
+use crate::platforms::{base, create_platform_by_name};
+use clap::Args;
+use indexmap::IndexMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::str::FromStr;
+
+/// 📌 钩子记录当前已应用的项目配置文件路径
+const PROJECT_MARKER_VAR: &str = "CCR_ENV_PROJECT";
+
+/// 🔄 钩子记录当前已应用的平台
+const PLATFORM_MARKER_VAR: &str = "CCR_ENV_PLATFORM";
+
+/// 支持的 shell 名称
+const SHELL_NAMES: [&str; 4] = ["bash", "zsh", "fish", "pwsh"];
+
+/// Env 命令参数
+#[derive(Args, Debug, Clone)]
+pub struct EnvArgs {
+    /// profile 名称（默认为当前目录生效的 profile: 项目固定优先，其次全局当前）
+    pub profile: Option<String>,
+
+    /// 输出格式（默认根据 $SHELL 检测）
+    #[arg(short, long, value_parser = SHELL_NAMES)]
+    pub shell: Option<String>,
+
+    /// 指定平台（默认为当前平台；项目固定了平台时使用项目平台）
+    #[arg(short, long)]
+    pub platform: Option<String>,
+
+    /// 由 `ccr hook` 安装的钩子调用: 仅在项目固定发生变化时输出
+    #[arg(long, hide = true)]
+    pub hook: bool,
+}
+
+/// Hook 命令参数
+#[derive(Args, Debug, Clone)]
+pub struct HookArgs {
+    /// 目标 shell
+    #[arg(value_parser = SHELL_NAMES)]
+    pub shell: String,
+}
+
+/// 🐚 Shell 类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Pwsh,
+}
+
+impl FromStr for Shell {
+    type Err = CcrError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "bash" | "sh" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "pwsh" | "powershell" => Ok(Shell::Pwsh),
+            _ => Err(CcrError::ValidationError(format!(
+                "不支持的 shell: {} (可选: {})",
+                s,
+                SHELL_NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+impl Shell {
+    /// 🔍 根据 $SHELL 检测，无法识别时使用 bash
+    fn detect() -> Self {
+        std::env::var("SHELL")
+            .ok()
+            .and_then(|path| {
+                Path::new(&path)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.parse().ok())
+            })
+            .unwrap_or(Shell::Bash)
+    }
+
+    /// 📤 设置环境变量的语句
+    pub fn export(self, name: &str, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => {
+                format!("export {}='{}'", name, value.replace('\'', r"'\''"))
+            }
+            Shell::Fish => format!(
+                "set -gx {} '{}'",
+                name,
+                value.replace('\\', r"\\").replace('\'', r"\'")
+            ),
+            Shell::Pwsh => format!("$env:{} = '{}'", name, value.replace('\'', "''")),
+        }
+    }
+
+    /// 🧹 清除环境变量的语句
+    pub fn unset(self, name: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("unset {}", name),
+            Shell::Fish => format!("set -e {}", name),
+            Shell::Pwsh => format!("Remove-Item Env:{} -ErrorAction SilentlyContinue", name),
+        }
+    }
+
+    /// 🪝 目录变化时调用 `ccr env --hook` 的钩子脚本
+    pub fn hook_script(self, ccr: &str) -> String {
+        match self {
+            Shell::Bash => format!(
+                r#"_ccr_hook() {{
+  if [ "$PWD" != "${{_CCR_LAST_PWD:-}}" ]; then
+    _CCR_LAST_PWD="$PWD"
+    eval "$(CCR_LOG_LEVEL=off {ccr} env --shell bash --hook)"
+  fi
+}}
+case ";${{PROMPT_COMMAND:-}};" in
+  *";_ccr_hook;"*) ;;
+  *) PROMPT_COMMAND="_ccr_hook${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}" ;;
+esac
+"#,
+                ccr = self.export_quote(ccr)
+            ),
+            Shell::Zsh => format!(
+                r#"_ccr_hook() {{
+  eval "$(CCR_LOG_LEVEL=off {ccr} env --shell zsh --hook)"
+}}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd _ccr_hook
+_ccr_hook
+"#,
+                ccr = self.export_quote(ccr)
+            ),
+            Shell::Fish => format!(
+                r#"function __ccr_hook --on-variable PWD
+    CCR_LOG_LEVEL=off {ccr} env --shell fish --hook | source
+end
+__ccr_hook
+"#,
+                ccr = self.export_quote(ccr)
+            ),
+            Shell::Pwsh => format!(
+                r#"$global:__CcrLastPwd = $null
+$global:__CcrOriginalPrompt = $function:prompt
+function global:prompt {{
+    if ($PWD.Path -ne $global:__CcrLastPwd) {{
+        $global:__CcrLastPwd = $PWD.Path
+        $env:CCR_LOG_LEVEL = 'off'
+        (& {ccr} env --shell pwsh --hook) -join "`n" | Invoke-Expression
+        Remove-Item Env:CCR_LOG_LEVEL -ErrorAction SilentlyContinue
+    }}
+    & $global:__CcrOriginalPrompt
+}}
+"#,
+                ccr = self.export_quote(ccr)
+            ),
+        }
+    }
+
+    /// 🔤 将 ccr 路径引用为 shell 字面量
+    fn export_quote(self, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("'{}'", value.replace('\'', r"'\''")),
+            Shell::Fish => format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'")),
+            Shell::Pwsh => format!("'{}'", value.replace('\'', "''")),
+        }
+    }
+}
+
+/// 执行 env 命令
+pub async fn execute_env(args: EnvArgs) -> Result<()> {
+    let shell = match &args.shell {
+        Some(name) => name.parse()?,
+        None => Shell::detect(),
+    };
+
+    let script = if args.hook {
+        hook_update(shell)?
+    } else {
+        let (platform_name, profile_name) = target_profile(&args)?;
+        let platform_config = create_platform_by_name(&platform_name)?;
+        render_env(shell, platform_config.as_ref(), &profile_name)?
+    };
+
+    print!("{}", script);
+    Ok(())
+}
+
+/// 执行 hook 命令
+pub async fn execute_hook(args: HookArgs) -> Result<()> {
+    let shell: Shell = args.shell.parse()?;
+    let ccr = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.to_str().map(String::from))
+        .unwrap_or_else(|| "ccr".to_string());
+
+    print!("{}", shell.hook_script(&ccr));
+    Ok(())
+}
+
+/// 🎯 确定目标平台与 profile
+fn target_profile(args: &EnvArgs) -> Result<(String, String)> {
+    let unified_config = PlatformConfigManager::with_default()?.load()?;
+
+    if let Some(profile) = &args.profile {
+        let platform = args
+            .platform
+            .clone()
+            .unwrap_or_else(|| unified_config.current_platform.clone());
+        return Ok((platform, profile.clone()));
+    }
+
+    let effective = resolve_effective_profile(&unified_config, &std::env::current_dir()?)?;
+    let platform = args.platform.clone().unwrap_or(effective.platform);
+    let profile = effective.profile.ok_or_else(|| {
+        CcrError::ValidationError(format!(
+            "平台 {} 没有当前 profile，请指定 profile 名称",
+            platform
+        ))
+    })?;
+    Ok((platform, profile))
+}
+
+/// 🌍 解析 profile 的环境变量（继承 + 密钥），拒绝模板
+fn profile_env(
+    platform_config: &dyn PlatformConfig,
+    profile_name: &str,
+) -> Result<IndexMap<String, String>> {
+    let profiles = platform_config.load_profiles()?;
+    let profile = base::resolve_profile(&profiles, profile_name)?;
+    if base::is_template_profile(&profile) {
+        return Err(CcrError::ValidationError(format!(
+            "配置 '{}' 是模板，不能直接使用",
+            profile_name
+        )));
+    }
+
+    base::validate_resolved_profile(&profiles, profile_name, |p| {
+        platform_config.validate_profile(p)
+    })?;
+    let profile = base::resolve_profile_secrets(&profile)?;
+    platform_config.profile_env(&profile)
+}
+
+/// 📜 输出 profile 的 export 语句，并 unset 平台中 profile 未设置的变量
+fn render_env(
+    shell: Shell,
+    platform_config: &dyn PlatformConfig,
+    profile_name: &str,
+) -> Result<String> {
+    let env = profile_env(platform_config, profile_name)?;
+
+    let mut script = String::new();
+    for name in platform_config.get_env_var_names() {
+        let line = match env.get(&name) {
+            Some(value) => shell.export(&name, value),
+            None => shell.unset(&name),
+        };
+        let _ = writeln!(script, "{}", line);
+    }
+    Ok(script)
+}
+
+/// 🪝 钩子调用: 进入 / 离开 / 切换固定项目时输出更新语句，其余情况不输出
+///
+/// - 进入固定项目: 应用项目 profile，记录项目配置路径与平台
+/// - 离开固定项目: 清除项目平台的变量，恢复全局当前 profile
+/// - 不在固定项目且之前也不在: 不修改 shell 环境
+fn hook_update(shell: Shell) -> Result<String> {
+    let unified_config = PlatformConfigManager::with_default()?.load()?;
+    let effective = resolve_effective_profile(&unified_config, &std::env::current_dir()?)?;
+
+    let applied_project = std::env::var(PROJECT_MARKER_VAR).ok();
+    let applied_platform = std::env::var(PLATFORM_MARKER_VAR).ok();
+    let project = match &effective.source {
+        ProfileSource::Project(path) => Some(path.display().to_string()),
+        ProfileSource::Global => None,
+    };
+
+    if project == applied_project {
+        return Ok(String::new());
+    }
+
+    let mut script = String::new();
+
+    // 先清除之前应用的平台变量（平台可能与目标不同）
+    if let Some(previous) = &applied_platform
+        && previous != &effective.platform
+        && let Ok(previous_config) = create_platform_by_name(previous)
+    {
+        for name in previous_config.get_env_var_names() {
+            let _ = writeln!(script, "{}", shell.unset(&name));
+        }
+    }
+
+    let platform_config = create_platform_by_name(&effective.platform)?;
+    match &effective.profile {
+        Some(profile) => script.push_str(&render_env(shell, platform_config.as_ref(), profile)?),
+        None => {
+            for name in platform_config.get_env_var_names() {
+                let _ = writeln!(script, "{}", shell.unset(&name));
+            }
+        }
+    }
+
+    match project {
+        Some(path) => {
+            let _ = writeln!(script, "{}", shell.export(PROJECT_MARKER_VAR, &path));
+            let _ = writeln!(
+                script,
+                "{}",
+                shell.export(PLATFORM_MARKER_VAR, &effective.platform)
+            );
+        }
+        None => {
+            let _ = writeln!(script, "{}", shell.unset(PROJECT_MARKER_VAR));
+            let _ = writeln!(script, "{}", shell.unset(PLATFORM_MARKER_VAR));
+        }
+    }
+
+    Ok(script)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_quoting() {
+        let value = "sk-'a'\\b";
+        assert_eq!(
+            Shell::Bash.export("TOKEN", value),
+            r"export TOKEN='sk-'\''a'\''\b'"
+        );
+        assert_eq!(
+            Shell::Fish.export("TOKEN", value),
+            r"set -gx TOKEN 'sk-\'a\'\\b'"
+        );
+        assert_eq!(
+            Shell::Pwsh.export("TOKEN", value),
+            r"$env:TOKEN = 'sk-''a''\b'"
+        );
+        assert_eq!(Shell::Zsh.unset("TOKEN"), "unset TOKEN");
+        assert_eq!(Shell::Fish.unset("TOKEN"), "set -e TOKEN");
+    }
+
+    #[test]
+    fn test_shell_names() {
+        for name in SHELL_NAMES {
+            assert!(name.parse::<Shell>().is_ok());
+        }
+        assert_eq!("powershell".parse::<Shell>().unwrap(), Shell::Pwsh);
+        assert!("tcsh".parse::<Shell>().is_err());
+    }
+
+    #[test]
+    fn test_hook_scripts_call_env_hook() {
+        for name in SHELL_NAMES {
+            let shell: Shell = name.parse().unwrap();
+            let script = shell.hook_script("/opt/ccr's bin/ccr");
+            assert!(script.contains(&format!("env --shell {} --hook", name)));
+            assert!(script.contains("ccr"));
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! ▶️ Exec 命令
+//!
+//! 以指定 profile 运行 CLI：只向子进程注入环境变量，不修改全局设置文件，
+//! 因此多个终端可以同时针对不同的中转工作。
+//!
+//! 对只读取设置文件的 CLI，`--overlay` 会临时应用 profile，子进程退出后恢复原文件。
+
+use crate::core::atomic_writer::AtomicWriter;
+use crate::core::error::{CcrError, Result};
+use crate::core::lock::{FileLock, LockManager};
+use crate::managers::PlatformConfigManager;
+use crate::models::{PlatformConfig, PlatformPaths, ProfileConfig};
+use crate::platforms::{base, create_platform_by_name};
+use clap::Args;
+use indexmap::IndexMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+
+/// ⏱️ 等待其他 overlay 结束的超时时间
+const OVERLAY_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Exec 命令参数
+#[derive(Args, Debug, Clone)]
+pub struct ExecArgs {
+    /// 要使用的 profile 名称
+    pub profile: String,
+
+    /// 指定平台（默认为当前平台）
+    #[arg(short, long)]
+    pub platform: Option<String>,
+
+    /// 临时将 profile 写入平台设置文件，子进程退出后恢复
+    ///
+    /// 用于只读取设置文件的 CLI；同一时间只能有一个 overlay 生效
+    #[arg(long)]
+    pub overlay: bool,
+
+    /// 要执行的命令及其参数（写在 `--` 之后）
+    #[arg(last = true, required = true, num_args = 1..)]
+    pub command: Vec<String>,
+}
+
+/// 执行 exec 命令
+///
+/// 子进程以非零状态退出时，ccr 以相同的退出码退出
+pub async fn execute(args: ExecArgs) -> Result<()> {
+    let platform_name = match &args.platform {
+        Some(platform) => platform.clone(),
+        None => {
+            PlatformConfigManager::with_default()?
+                .load()?
+                .current_platform
+        }
+    };
+    let platform_config = create_platform_by_name(&platform_name)?;
+    let profile = load_profile(platform_config.as_ref(), &args.profile)?;
+
+    let env = platform_config.profile_env(&profile)?;
+    if env.is_empty() && !args.overlay {
+        eprintln!(
+            "⚠ 平台 {} 不通过环境变量读取配置，可使用 --overlay 临时写入设置文件",
+            platform_name
+        );
+    }
+
+    let overlay = if args.overlay {
+        Some(SettingsOverlay::apply(
+            platform_config.as_ref(),
+            &platform_name,
+            &args.profile,
+        )?)
+    } else {
+        None
+    };
+
+    // 清除 profile 未设置的平台变量，避免继承父 shell 中其他中转的值
+    let unset: Vec<String> = platform_config
+        .get_env_var_names()
+        .into_iter()
+        .filter(|name| !env.contains_key(name))
+        .collect();
+
+    let status = run_child(&args.command, &env, &unset).await;
+    if let Some(overlay) = overlay {
+        overlay.restore()?;
+    }
+
+    let code = exit_code(status?);
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// 📖 加载并解析 profile（继承 + 密钥），拒绝模板
+fn load_profile(platform_config: &dyn PlatformConfig, name: &str) -> Result<ProfileConfig> {
+    let profiles = platform_config.load_profiles()?;
+    let profile = base::resolve_profile(&profiles, name)?;
+    if base::is_template_profile(&profile) {
+        return Err(CcrError::ValidationError(format!(
+            "配置 '{}' 是模板，不能直接使用",
+            name
+        )));
+    }
+
+    base::validate_resolved_profile(&profiles, name, |p| platform_config.validate_profile(p))?;
+    base::resolve_profile_secrets(&profile)
+}
+
+/// ▶️ 启动子进程并等待其退出
+async fn run_child(
+    command: &[String],
+    env: &IndexMap<String, String>,
+    unset: &[String],
+) -> Result<ExitStatus> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| CcrError::ValidationError("缺少要执行的命令".into()))?;
+
+    let mut child = Command::new(program);
+    child.args(args).envs(env);
+    for name in unset {
+        child.env_remove(name);
+    }
+
+    let mut child = child
+        .spawn()
+        .map_err(|e| CcrError::ExternalCommandError(format!("无法启动 '{}': {}", program, e)))?;
+    tracing::debug!("▶️ 已启动子进程 {} (pid {:?})", program, child.id());
+
+    wait_forwarding_signals(&mut child).await
+}
+
+/// 📡 等待子进程退出，期间转发终止类信号
+///
+/// Ctrl-C / Ctrl-\ 由终端发送给整个前台进程组，子进程已经收到，
+/// 父进程只需忽略它们（重复发送会让 claude 等 CLI 误判为连按两次 Ctrl-C）
+#[cfg(unix)]
+async fn wait_forwarding_signals(child: &mut Child) -> Result<ExitStatus> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut quit = signal(SignalKind::quit())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+
+    loop {
+        tokio::select! {
+            status = child.wait() => return Ok(status?),
+            _ = interrupt.recv() => {}
+            _ = quit.recv() => {}
+            _ = terminate.recv() => forward_signal(child, libc::SIGTERM),
+            _ = hangup.recv() => forward_signal(child, libc::SIGHUP),
+        }
+    }
+}
+
+/// 📡 等待子进程退出（Ctrl-C 由控制台直接发送给子进程）
+#[cfg(not(unix))]
+async fn wait_forwarding_signals(child: &mut Child) -> Result<ExitStatus> {
+    loop {
+        tokio::select! {
+            status = child.wait() => return Ok(status?),
+            _ = tokio::signal::ctrl_c() => {}
+        }
+    }
+}
+
+#[cfg(unix)]
+fn forward_signal(child: &Child, signal: libc::c_int) {
+    if let Some(pid) = child.id() {
+        tracing::debug!("📡 转发信号 {} 到子进程 {}", signal, pid);
+        // SAFETY: kill 只向仍在等待中的子进程发送信号，不涉及内存访问
+        unsafe {
+            libc::kill(pid as libc::pid_t, signal);
+        }
+    }
+}
+
+/// 🔢 子进程退出码（被信号终止时按 shell 惯例返回 128 + 信号值）
+fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    1
+}
+
+/// 🗂️ 临时设置覆盖
+///
+/// 记录 apply_profile 会写入的文件（设置文件、profiles.toml、注册表），
+/// 子进程退出后原样恢复；恢复失败或 ccr 被强制终止时需要重新 `ccr switch`。
+///
+/// 期间持有 overlay 锁，同一时间只有一个 overlay 生效；
+/// 恢复前文件已被其他进程修改（如子进程中执行了 `ccr switch`）时保留当前内容
+struct SettingsOverlay {
+    files: Vec<OverlayFile>,
+    restored: bool,
+    _lock: FileLock,
+}
+
+/// 📄 overlay 涉及的单个文件
+struct OverlayFile {
+    path: PathBuf,
+    /// 应用前的内容（None 表示文件不存在）
+    original: Option<Vec<u8>>,
+    /// overlay 写入后的内容，恢复前据此判断文件是否被他人修改
+    written: Option<Vec<u8>>,
+}
+
+impl SettingsOverlay {
+    /// 💾 快照相关文件并应用 profile
+    fn apply(
+        platform_config: &dyn PlatformConfig,
+        platform_name: &str,
+        profile_name: &str,
+    ) -> Result<Self> {
+        let lock = LockManager::with_default_path()?
+            .lock_resource("exec_overlay", OVERLAY_LOCK_TIMEOUT)
+            .map_err(|e| match e {
+                CcrError::LockTimeout(_) => {
+                    CcrError::LockTimeout("另一个 ccr exec --overlay 正在运行".into())
+                }
+                e => e,
+            })?;
+
+        let paths = PlatformPaths::for_name(platform_name)?;
+        let mut paths_to_track = platform_config.settings_files();
+        paths_to_track.extend([paths.profiles_file, paths.registry_file]);
+        paths_to_track.dedup();
+
+        let mut files = Vec::with_capacity(paths_to_track.len());
+        for path in paths_to_track {
+            let original = read_file(&path)?;
+            files.push(OverlayFile {
+                path,
+                written: original.clone(),
+                original,
+            });
+        }
+
+        let mut overlay = Self {
+            files,
+            restored: false,
+            _lock: lock,
+        };
+        // 应用失败时由 Drop 恢复已写入的文件
+        let applied = platform_config.apply_profile(profile_name);
+        for file in &mut overlay.files {
+            file.written = read_file(&file.path)?;
+        }
+        applied?;
+        tracing::debug!("🗂️ 已临时应用 profile '{}'", profile_name);
+
+        Ok(overlay)
+    }
+
+    /// ↩️ 恢复快照
+    fn restore(mut self) -> Result<()> {
+        self.restored = true;
+        Self::restore_files(&self.files)
+    }
+
+    fn restore_files(files: &[OverlayFile]) -> Result<()> {
+        for file in files {
+            let current = read_file(&file.path)?;
+            if current == file.original {
+                continue;
+            }
+            if current != file.written {
+                eprintln!(
+                    "⚠ {} 在 overlay 期间被修改，保留当前内容，未恢复",
+                    file.path.display()
+                );
+                continue;
+            }
+
+            match &file.original {
+                Some(content) => AtomicWriter::new(&file.path).write(content)?,
+                None => fs::remove_file(&file.path).map_err(|e| {
+                    CcrError::FileIoError(format!("恢复 {} 失败: {}", file.path.display(), e))
+                })?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SettingsOverlay {
+    fn drop(&mut self) {
+        if !self.restored
+            && let Err(e) = Self::restore_files(&self.files)
+        {
+            tracing::warn!("⚠️ 恢复临时设置失败: {}", e);
+        }
+    }
+}
+
+/// 📖 读取文件内容（不存在时为 None）
+fn read_file(path: &Path) -> Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(CcrError::FileIoError(format!(
+            "读取 {} 失败: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
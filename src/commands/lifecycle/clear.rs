@@ -126,12 +126,14 @@ pub async fn clear_command(force: bool) -> Result<()> {
     // 💾 备份当前设置
     ColorOutput::step("备份当前设置...");
     let backup_path = settings_manager.backup_async(Some("pre_clear")).await?;
+    crate::services::auto_snapshot("clear");
     ColorOutput::success(&format!("✅ 已备份到: {}", backup_path.display()));
 
     // 🧹 清空 ANTHROPIC_* 环境变量
     ColorOutput::step("清空 ANTHROPIC_* 环境变量...");
     let mut updated_settings = current_settings;
     updated_settings.clear_anthropic_vars();
+    updated_settings.apply_bundle(None);
 
     // 💾 保存更新后的设置
     ColorOutput::step("保存更新后的设置...");
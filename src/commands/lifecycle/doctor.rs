@@ -0,0 +1,121 @@
+// 🩺 doctor 命令实现 - 环境诊断
+// 🔍 检查 CLI 工具、shell 环境变量、设置漂移、文件权限、锁文件、配置模式与跨平台冲突
+
+use crate::core::error::Result;
+use crate::core::logging::ColorOutput;
+use crate::services::doctor_service::{
+    CheckKind, DoctorReport, DoctorService, Finding, FixOutcome, Severity,
+};
+use colored::*;
+
+/// 🩺 环境诊断
+///
+/// 执行流程:
+/// 1. 🔍 执行所有检查，每条结果带严重级别 (ok / info / warning / error)
+/// 2. 🔧 指定 --fix 时执行所有可自动修复的项，然后重新检查
+/// 3. 📊 按检查项分组输出，或以 JSON 输出完整报告
+pub async fn doctor_command(fix: bool, json: bool) -> Result<()> {
+    let service = DoctorService::with_default()?;
+    let mut report = service.run().await;
+
+    if fix {
+        let fixes = report.fixes();
+        if !fixes.is_empty() {
+            let outcomes: Vec<FixOutcome> = fixes
+                .into_iter()
+                .map(|fix| match service.apply_fix(&fix) {
+                    Ok(message) => FixOutcome {
+                        fix,
+                        success: true,
+                        message,
+                    },
+                    Err(e) => FixOutcome {
+                        fix,
+                        success: false,
+                        message: e.to_string(),
+                    },
+                })
+                .collect();
+            report = service.run().await;
+            report.fixes = outcomes;
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    print_report(&report, fix);
+    Ok(())
+}
+
+fn print_report(report: &DoctorReport, fixed: bool) {
+    ColorOutput::title("环境诊断");
+
+    if !report.fixes.is_empty() {
+        println!();
+        ColorOutput::step("自动修复");
+        for outcome in &report.fixes {
+            if outcome.success {
+                println!(
+                    "  {} {} - {}",
+                    "✓".green(),
+                    outcome.fix.describe(),
+                    outcome.message
+                );
+            } else {
+                println!(
+                    "  {} {} - {}",
+                    "✗".red(),
+                    outcome.fix.describe(),
+                    outcome.message.red()
+                );
+            }
+        }
+    }
+
+    let mut current: Option<CheckKind> = None;
+    for finding in &report.findings {
+        if current != Some(finding.check) {
+            println!();
+            ColorOutput::step(finding.check.label());
+            current = Some(finding.check);
+        }
+        print_finding(finding);
+    }
+
+    let summary = &report.summary;
+    println!();
+    println!(
+        "  {} 正常  {} 提示  {} 警告  {} 错误",
+        summary.ok.to_string().green(),
+        summary.info.to_string().cyan(),
+        summary.warning.to_string().yellow(),
+        summary.error.to_string().red()
+    );
+
+    if summary.fixable > 0 && !fixed {
+        println!();
+        ColorOutput::info(&format!(
+            "可修复: {} 项，运行 'ccr doctor --fix' 自动修复",
+            summary.fixable
+        ));
+    }
+}
+
+fn print_finding(finding: &Finding) {
+    let icon = match finding.severity {
+        Severity::Ok => "✓".green(),
+        Severity::Info => "ℹ".cyan(),
+        Severity::Warning => "⚠".yellow(),
+        Severity::Error => "✗".red(),
+    };
+    println!("  {} {}", icon, finding.title);
+    for detail in &finding.details {
+        println!("      {}", detail.dimmed());
+    }
+    if let Some(fix) = &finding.fix {
+        println!("      {} {}", "修复:".dimmed(), fix.describe().dimmed());
+    }
+}
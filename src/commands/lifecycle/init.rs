@@ -196,6 +196,8 @@ mod tests {
         let settings = GlobalSettings {
             skip_confirmation: false,
             tui_theme: None,
+            proxy: None,
+            backup_retention: None,
             #[allow(deprecated)]
             sync: Default::default(),
         };
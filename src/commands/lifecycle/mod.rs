@@ -4,12 +4,14 @@
 
 mod clean;
 mod clear;
+mod doctor;
 mod init;
 mod optimize;
 mod validate;
 
 pub use clean::clean_command;
 pub use clear::clear_command;
+pub use doctor::doctor_command;
 pub use init::init_command;
 pub use optimize::optimize_command;
 pub use validate::validate_command;
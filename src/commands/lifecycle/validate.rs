@@ -5,6 +5,7 @@
 
 use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
+use crate::managers::config::{CcsConfig, ConfigManager};
 use crate::services::{ConfigService, SettingsService};
 use crate::utils::Validatable;
 use colored::*;
@@ -15,7 +16,8 @@ use colored::*;
 /// 1. 📝 验证配置文件 (~/.ccs_config.toml)
 ///    - 文件是否存在
 ///    - 格式是否正确
-///    - 所有配置节是否有效
+///    - 所有配置节是否有效（按继承解析后的值）
+///    - 继承链与继承字段的来源
 ///    - 当前配置是否存在
 ///
 /// 2. 🌍 验证 Claude Code 设置 (~/.claude/settings.json)
@@ -44,6 +46,8 @@ pub async fn validate_command() -> Result<()> {
                 config_service.config_manager().config_path().display()
             ));
 
+            let config = config_service.load_config()?;
+
             // 显示验证结果
             println!();
             for (name, is_valid, error_msg) in &report.results {
@@ -57,6 +61,7 @@ pub async fn validate_command() -> Result<()> {
                     }
                     has_errors = true;
                 }
+                print_inheritance(&config, name);
             }
 
             println!();
@@ -72,7 +77,6 @@ pub async fn validate_command() -> Result<()> {
             // 验证当前配置
             println!();
             ColorOutput::step("当前配置验证");
-            let config = config_service.load_config()?;
             if config.sections.contains_key(&config.current_config) {
                 ColorOutput::success(&format!("当前配置 '{}' 存在", config.current_config));
             } else {
@@ -115,7 +119,16 @@ pub async fn validate_command() -> Result<()> {
             println!();
             ColorOutput::step("环境变量验证");
 
-            let env_status = settings.anthropic_env_status();
+            // 凭证可写入 ANTHROPIC_AUTH_TOKEN 或 ANTHROPIC_API_KEY (auth_mode = "api_key")
+            let auth_var = if settings.env.contains_key("ANTHROPIC_API_KEY") {
+                "ANTHROPIC_API_KEY"
+            } else {
+                "ANTHROPIC_AUTH_TOKEN"
+            };
+            let required_vars = ["ANTHROPIC_BASE_URL", auth_var, "ANTHROPIC_MODEL"];
+
+            let mut env_status: Vec<_> = settings.anthropic_env_status().into_iter().collect();
+            env_status.sort();
             for (var_name, value) in env_status {
                 match value {
                     Some(v) if !v.is_empty() => {
@@ -131,12 +144,12 @@ pub async fn validate_command() -> Result<()> {
                         has_warnings = true;
                     }
                     None => {
-                        // ANTHROPIC_SMALL_FAST_MODEL 是可选的
-                        if var_name.contains("SMALL_FAST_MODEL") {
-                            println!("  {} {}: 未设置(可选)", "○".dimmed(), var_name);
-                        } else {
+                        // ANTHROPIC_SMALL_FAST_MODEL 与模型映射等变量是可选的
+                        if required_vars.contains(&var_name.as_str()) {
                             println!("  {} {}: 未设置", "✗".red(), var_name);
                             has_errors = true;
+                        } else {
+                            println!("  {} {}: 未设置(可选)", "○".dimmed(), var_name);
                         }
                     }
                 }
@@ -164,6 +177,51 @@ pub async fn validate_command() -> Result<()> {
     generate_report(has_errors, has_warnings)
 }
 
+/// 🧬 显示配置节的继承链及继承字段的解析值与来源
+fn print_inheritance(config: &CcsConfig, name: &str) {
+    let Ok(resolved) = ConfigManager::resolve_section(config, name) else {
+        return;
+    };
+    if resolved.chain.len() < 2 {
+        return;
+    }
+
+    println!(
+        "      {} {}",
+        "继承链:".dimmed(),
+        resolved.chain.join(" → ").cyan()
+    );
+    for (field, origin) in resolved.inherited_fields() {
+        let value = match field {
+            "base_url" => resolved.section.base_url.clone(),
+            "auth_token" => resolved
+                .section
+                .auth_token
+                .as_deref()
+                .map(ColorOutput::mask_sensitive),
+            "model" => resolved.section.model.clone(),
+            "small_fast_model" => resolved.section.small_fast_model.clone(),
+            "provider" => resolved.section.provider.clone(),
+            "provider_type" => resolved
+                .section
+                .provider_type
+                .as_ref()
+                .map(|t| t.to_string_value().to_string()),
+            "account" => resolved.section.account.clone(),
+            "tags" => resolved.section.tags.as_ref().map(|tags| tags.join(", ")),
+            other => resolved.section.other.get(other).map(|v| v.to_string()),
+        }
+        .unwrap_or_else(|| "…".to_string());
+        println!(
+            "      {} {} = {} {}",
+            "·".dimmed(),
+            field,
+            value,
+            format!("(← {})", origin).dimmed()
+        );
+    }
+}
+
 fn generate_report(has_errors: bool, has_warnings: bool) -> Result<()> {
     println!();
     ColorOutput::title("验证总结");
@@ -22,8 +22,12 @@ pub mod profile;
 
 // 🔄 保留的独立命令（暂未迁移到子模块）
 pub mod check_cmd;
+pub mod env_cmd;
+pub mod exec_cmd;
 pub mod prompts_cmd;
 pub mod provider_cmd;
+#[cfg(feature = "web")]
+pub mod proxy_cmd;
 pub mod sessions_cmd;
 pub mod skills_cmd;
 #[cfg(feature = "web")]
@@ -34,6 +38,7 @@ pub mod temp_cmd;
 pub mod temp_token;
 pub mod ui;
 pub mod update;
+pub mod vault_cmd;
 
 // =============================================
 // 📤 公共 API 导出（保持向后兼容）
@@ -52,11 +57,15 @@ pub use profile::delete_command;
 pub use profile::disable_command;
 pub use profile::enable_command;
 pub use profile::list_command;
-pub use profile::switch_command;
+pub use profile::reconcile_command;
+pub use profile::{
+    switch_auto_command, switch_command, switch_dry_run_command, switch_local_command,
+};
 
 // 🔄 Lifecycle 命令
 pub use lifecycle::clean_command;
 pub use lifecycle::clear_command;
+pub use lifecycle::doctor_command;
 pub use lifecycle::init_command;
 pub use lifecycle::optimize_command;
 pub use lifecycle::validate_command;
@@ -64,6 +73,7 @@ pub use lifecycle::validate_command;
 // 📦 Data 命令
 pub use data::export_command;
 pub use data::history_command;
+pub use data::{BackupArgs, backup_command};
 #[cfg(feature = "web")]
 pub use data::{BudgetArgs, budget_command};
 pub use data::{ImportMode, import_command};
@@ -71,6 +81,8 @@ pub use data::{ImportMode, import_command};
 pub use data::{PricingArgs, pricing_command};
 #[cfg(feature = "web")]
 pub use data::{StatsArgs, stats_command};
+#[allow(unused_imports)]
+pub use data::{history_revert_command, undo_command};
 
 // 🔧 其他命令
 pub use check_cmd::check_conflicts_command;
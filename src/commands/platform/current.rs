@@ -8,10 +8,8 @@ use super::types::{PlatformInfoOutput, PlatformPathsOutput};
 use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
 use crate::managers::PlatformConfigManager;
-use crate::models::{Platform, PlatformPaths};
-use crate::platforms::create_platform;
+use crate::platforms::{create_platform_by_name, platform_paths_by_name};
 use colored::Colorize;
-use std::str::FromStr;
 
 /// 🔍 显示当前平台信息
 ///
@@ -33,22 +31,14 @@ pub async fn platform_current_command(json: bool) -> Result<()> {
     let registry = config.get_platform(current_platform)?;
 
     // 获取路径信息
-    let paths = if let Ok(platform) = Platform::from_str(current_platform) {
-        PlatformPaths::new(platform).ok()
-    } else {
-        None
-    };
+    let paths = platform_paths_by_name(current_platform).ok();
 
     // 获取 profile 列表
-    let profiles = if let Ok(platform) = Platform::from_str(current_platform) {
-        if let Ok(platform_impl) = create_platform(platform) {
-            platform_impl.list_profile_names().unwrap_or_else(|e| {
-                tracing::debug!("获取 profile 列表失败: {}", e);
-                Vec::new()
-            })
-        } else {
+    let profiles = if let Ok(platform_impl) = create_platform_by_name(current_platform) {
+        platform_impl.list_profile_names().unwrap_or_else(|e| {
+            tracing::debug!("获取 profile 列表失败: {}", e);
             Vec::new()
-        }
+        })
     } else {
         Vec::new()
     };
@@ -5,13 +5,12 @@
 #![allow(clippy::unused_async)]
 
 use super::types::{PlatformInfoOutput, PlatformPathsOutput};
-use crate::core::error::{CcrError, Result};
+use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
 use crate::managers::PlatformConfigManager;
-use crate::models::{Platform, PlatformPaths};
-use crate::platforms::create_platform;
+use crate::models::Platform;
+use crate::platforms::{create_platform_by_name, platform_paths_by_name};
 use colored::Colorize;
-use std::str::FromStr;
 
 /// ℹ️ 显示指定平台的详细信息
 ///
@@ -28,12 +27,16 @@ use std::str::FromStr;
 /// * `Ok(())` - 成功显示信息
 /// * `Err(CcrError::PlatformNotFound)` - 指定的平台不存在或未实现
 pub async fn platform_info_command(platform_name: &str, json: bool) -> Result<()> {
-    // 验证平台是否存在
-    let platform = Platform::from_str(platform_name)
-        .map_err(|_| CcrError::PlatformNotFound(platform_name.to_string()))?;
+    // 验证平台是否存在（内置平台或 manifest 声明的自定义平台）
+    let platform_impl = create_platform_by_name(platform_name)?;
+    let paths = platform_paths_by_name(platform_name)?;
 
-    let platform_impl = create_platform(platform)?;
-    let paths = PlatformPaths::new(platform)?;
+    // 自定义平台的设置文件由 manifest 声明
+    let settings_file = if platform_impl.platform_type() == Platform::Custom {
+        platform_impl.get_settings_path()
+    } else {
+        paths.settings_file.clone()
+    };
 
     // 检查是否为当前平台
     let manager = PlatformConfigManager::with_default()?;
@@ -93,7 +96,7 @@ pub async fn platform_info_command(platform_name: &str, json: bool) -> Result<()
     println!("  注册表: {}", paths.registry_file.display());
     println!("  平台目录: {}", paths.platform_dir.display());
     println!("  Profiles 文件: {}", paths.profiles_file.display());
-    println!("  设置文件: {}", paths.settings_file.display());
+    println!("  设置文件: {}", settings_file.display());
     println!("  历史文件: {}", paths.history_file.display());
     println!("  备份目录: {}", paths.backups_dir.display());
 
@@ -110,7 +113,7 @@ pub async fn platform_info_command(platform_name: &str, json: bool) -> Result<()
     );
     println!(
         "  设置文件: {}",
-        if paths.settings_file.exists() {
+        if settings_file.exists() {
             "存在 ✓".green()
         } else {
             "不存在 ✗".red()
@@ -8,11 +8,9 @@ use crate::core::error::{CcrError, Result};
 use crate::core::logging::ColorOutput;
 use crate::managers::PlatformConfigManager;
 use crate::managers::config::{CcsConfig, GlobalSettings};
-use crate::models::{Platform, PlatformPaths};
-use crate::platforms::create_platform;
+use crate::platforms::{create_platform_by_name, platform_paths_by_name};
 use indexmap::IndexMap;
 use std::fs;
-use std::str::FromStr;
 
 /// 🆕 初始化平台配置
 ///
@@ -39,12 +37,9 @@ use std::str::FromStr;
 pub async fn platform_init_command(platform_name: &str) -> Result<()> {
     ColorOutput::title(&format!("初始化平台: {}", platform_name));
 
-    // 验证平台是否存在
-    let platform = Platform::from_str(platform_name)
-        .map_err(|_| CcrError::PlatformNotFound(platform_name.to_string()))?;
-
-    let platform_impl = create_platform(platform)?;
-    let paths = PlatformPaths::new(platform)?;
+    // 验证平台是否存在（内置平台或 manifest 声明的自定义平台）
+    let platform_impl = create_platform_by_name(platform_name)?;
+    let paths = platform_paths_by_name(platform_name)?;
 
     println!();
     ColorOutput::info("正在创建平台目录结构...");
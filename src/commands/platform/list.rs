@@ -9,6 +9,7 @@ use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
 use crate::managers::PlatformConfigManager;
 use crate::platforms::PlatformRegistry;
+use crate::platforms::manifest::{self, ManifestPlatform};
 use colored::Colorize;
 use comfy_table::{
     Attribute, Cell, CellAlignment, Color as TableColor, ColumnConstraint, ContentArrangement,
@@ -19,7 +20,7 @@ use comfy_table::{
 ///
 /// 显示内容:
 /// - 🎯 当前激活的平台
-/// - 📋 所有注册的平台列表
+/// - 📋 所有注册的平台列表（含 manifest 声明的自定义平台）
 /// - 🔌 平台启用状态
 /// - ▶️ 当前 profile
 /// - 📝 平台描述
@@ -65,6 +66,26 @@ pub async fn platform_list_command(json: bool) -> Result<()> {
         });
     }
 
+    // 🧩 manifest 声明的自定义平台
+    for platform_name in manifest::list_manifest_platforms()? {
+        let registry_entry = config.platforms.get(&platform_name);
+        let description = registry_entry
+            .and_then(|e| e.description.clone())
+            .unwrap_or_else(|| match ManifestPlatform::load(&platform_name) {
+                Ok(platform) => format!("🧩 {}", platform.description()),
+                Err(e) => format!("🧩 manifest 无效: {}", e),
+            });
+
+        platforms_data.push(PlatformListItem {
+            is_current: platform_name == config.current_platform,
+            is_default: platform_name == config.default_platform,
+            enabled: registry_entry.map(|e| e.enabled).unwrap_or(false),
+            current_profile: registry_entry.and_then(|e| e.current_profile.clone()),
+            description,
+            name: platform_name,
+        });
+    }
+
     // 📤 输出格式选择
     if json {
         // JSON 输出
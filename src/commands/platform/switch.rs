@@ -4,13 +4,11 @@
 
 #![allow(clippy::unused_async)]
 
-use crate::core::error::{CcrError, Result};
+use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
 use crate::managers::PlatformConfigManager;
-use crate::models::Platform;
-use crate::platforms::create_platform;
+use crate::platforms::create_platform_by_name;
 use colored::Colorize;
-use std::str::FromStr;
 
 /// 🔄 切换当前平台
 ///
@@ -40,15 +38,13 @@ pub async fn platform_switch_command(platform_name: &str) -> Result<()> {
     let manager = PlatformConfigManager::with_default()?;
     let mut config = manager.load_or_create_default()?;
 
-    // 验证平台是否存在
-    let platform = Platform::from_str(platform_name)
-        .map_err(|_| CcrError::PlatformNotFound(platform_name.to_string()))?;
+    // 验证平台是否存在（内置平台或 manifest 声明的自定义平台）
+    let platform_impl = create_platform_by_name(platform_name)?;
 
     // 如果平台未注册，自动注册
     if !config.platforms.contains_key(platform_name) {
         ColorOutput::info(&format!("平台 '{}' 未注册，正在自动注册...", platform_name));
 
-        let platform_impl = create_platform(platform)?;
         let registry = crate::managers::PlatformConfigEntry {
             description: Some(platform_impl.platform_name().to_string()),
             ..Default::default()
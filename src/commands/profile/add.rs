@@ -8,6 +8,7 @@ use crate::commands::common::{prompt_optional, prompt_required, prompt_tags};
 use crate::core::error::{CcrError, Result};
 use crate::core::logging::ColorOutput;
 use crate::managers::config::{ConfigSection, ProviderType};
+use crate::managers::history::OperationType;
 use crate::services::ConfigService;
 use crate::utils::Validatable;
 use indexmap::IndexMap;
@@ -189,6 +190,7 @@ pub async fn add_command() -> Result<()> {
     ColorOutput::step("保存配置");
     service.add_config(name.clone(), section)?;
     ColorOutput::success(&format!("✓ 配置 '{}' 添加成功", name));
+    super::record_profile_change(OperationType::Add, &name, None).await;
     println!();
 
     ColorOutput::info("后续操作:");
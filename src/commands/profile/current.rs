@@ -4,24 +4,31 @@
 
 #![allow(clippy::unused_async)]
 
+use super::reconcile::print_drift;
 use crate::core::error::Result;
+use crate::core::lock::LockManager;
 use crate::core::logging::ColorOutput;
 use crate::managers::PlatformConfigManager;
-use crate::models::{Platform, PlatformPaths};
-use crate::platforms::create_platform;
-use crate::services::SettingsService;
+use crate::managers::project_config::{
+    ProfileSource, project_settings_path, resolve_effective_profile,
+};
+use crate::managers::settings::{ClaudeSettings, SettingsManager};
+use crate::models::Platform;
+use crate::platforms::{create_platform_by_name, platform_paths_by_name};
+use crate::services::{DriftService, SettingsService};
 use crate::utils::Validatable;
 use colored::Colorize;
 use comfy_table::{
     Attribute, Cell, Color as TableColor, ContentArrangement, Table, presets::UTF8_FULL,
 };
-use std::str::FromStr;
+use std::path::Path;
 
 /// 🔍 显示当前配置状态
 ///
 /// 显示内容分为三部分:
 /// 1. 🔄 平台信息
 ///    - 当前平台
+///    - 配置来源（项目 `.ccr.toml` 或全局注册表）
 ///    - 平台路径
 ///
 /// 2. 📝 配置文件信息
@@ -32,6 +39,9 @@ use std::str::FromStr;
 /// 3. 🌍 Claude Code 环境变量状态
 ///    - ANTHROPIC_* 环境变量当前值
 ///    - 设置验证状态
+///
+/// 4. 🧭 设置漂移
+///    - 各已启用平台设置文件与当前 profile 的逐项差异
 pub async fn current_command() -> Result<()> {
     ColorOutput::title("当前配置状态");
 
@@ -39,15 +49,22 @@ pub async fn current_command() -> Result<()> {
     let platform_config_mgr = PlatformConfigManager::with_default()?;
     let unified_config = platform_config_mgr.load()?;
 
+    // 🧭 解析生效的 profile（项目 .ccr.toml 优先于全局）
+    let cwd = std::env::current_dir().map_err(|e| {
+        crate::core::error::CcrError::ConfigError(format!("无法获取当前目录: {}", e))
+    })?;
+    let effective = resolve_effective_profile(&unified_config, &cwd)?;
+
     println!();
 
     // === 第零部分：平台信息 ===
     ColorOutput::step("🔄 平台信息");
     println!();
 
-    let platform_name = &unified_config.current_platform;
-    let platform = Platform::from_str(platform_name)?;
-    let paths = PlatformPaths::new(platform)?;
+    let platform_name = &effective.platform;
+    let platform_config = create_platform_by_name(platform_name)?;
+    let platform = platform_config.platform_type();
+    let paths = platform_paths_by_name(platform_name)?;
 
     let mut platform_table = Table::new();
     platform_table
@@ -69,6 +86,21 @@ pub async fn current_command() -> Result<()> {
             .add_attribute(Attribute::Bold),
     ]);
 
+    platform_table.add_row(vec![
+        Cell::new("配置来源").fg(TableColor::Yellow),
+        match &effective.source {
+            ProfileSource::Project(_) => Cell::new(effective.source.to_string())
+                .fg(TableColor::Magenta)
+                .add_attribute(Attribute::Bold),
+            ProfileSource::Global => Cell::new(format!(
+                "{} ({})",
+                effective.source,
+                platform_config_mgr.config_path().display()
+            ))
+            .fg(TableColor::Blue),
+        },
+    ]);
+
     platform_table.add_row(vec![
         Cell::new("平台目录"),
         Cell::new(paths.platform_dir.display().to_string()).fg(TableColor::Blue),
@@ -94,42 +126,22 @@ pub async fn current_command() -> Result<()> {
     ColorOutput::separator();
     println!();
 
-    // 从平台配置读取
-    let platform_config = create_platform(platform)?;
-
-    // 获取当前 profile
-    let current_profile = platform_config.get_current_profile()?.ok_or_else(|| {
+    // 获取生效的 profile
+    let current_profile = effective.profile.clone().ok_or_else(|| {
         crate::core::error::CcrError::ConfigError("未设置当前 profile".to_string())
     })?;
 
     // 加载 profiles
     let profiles = platform_config.load_profiles()?;
-    let profile = profiles.get(&current_profile).ok_or_else(|| {
-        crate::core::error::CcrError::ConfigSectionNotFound(current_profile.clone())
-    })?;
+    if !profiles.contains_key(&current_profile) {
+        return Err(crate::core::error::CcrError::ConfigSectionNotFound(
+            current_profile.clone(),
+        ));
+    }
 
-    // 转换为 ConfigSection
-    let current_section = crate::managers::config::ConfigSection {
-        description: profile.description.clone(),
-        base_url: profile.base_url.clone(),
-        auth_token: profile.auth_token.clone(),
-        model: profile.model.clone(),
-        small_fast_model: profile.small_fast_model.clone(),
-        provider: profile.provider.clone(),
-        provider_type: profile.provider_type.as_ref().and_then(|pt| {
-            use crate::managers::config::ProviderType;
-            match pt.as_str() {
-                "official_relay" => Some(ProviderType::OfficialRelay),
-                "third_party_model" => Some(ProviderType::ThirdPartyModel),
-                _ => None,
-            }
-        }),
-        account: profile.account.clone(),
-        tags: profile.tags.clone(),
-        usage_count: profile.usage_count,
-        enabled: profile.enabled,
-        other: indexmap::IndexMap::new(),
-    };
+    // 解析继承后的有效配置
+    let resolved = crate::platforms::base::resolve_profile_section(&profiles, &current_profile)?;
+    let current_section = resolved.section.clone();
 
     let current_name = current_profile;
     let config_file_path = paths.profiles_file.clone();
@@ -171,6 +183,14 @@ pub async fn current_command() -> Result<()> {
         Cell::new(current_section.display_description()),
     ]);
 
+    // 继承链
+    if resolved.chain.len() > 1 {
+        config_table.add_row(vec![
+            Cell::new("继承链").fg(TableColor::Yellow),
+            Cell::new(resolved.chain.join(" → ")).fg(TableColor::Cyan),
+        ]);
+    }
+
     // 提供商类型
     if let Some(provider_type) = &current_section.provider_type {
         let type_display = match provider_type.to_string_value() {
@@ -270,6 +290,13 @@ pub async fn current_command() -> Result<()> {
     // 根据当前平台获取环境变量名称
     let env_vars = platform_config.get_env_var_names();
 
+    // 📌 项目级 Claude 设置 (<repo>/.claude/settings.local.json)
+    let project_settings = if platform == Platform::Claude {
+        effective.project_root().and_then(load_project_settings)
+    } else {
+        None
+    };
+
     // 仅在有环境变量时显示
     if !env_vars.is_empty() {
         let platform_display = match platform {
@@ -279,20 +306,25 @@ pub async fn current_command() -> Result<()> {
             Platform::Qwen => "Qwen",
             Platform::IFlow => "IFlow",
             Platform::Droid => "Factory Droid",
+            Platform::Custom => platform_config.platform_name(),
         };
         ColorOutput::step(&format!("🌍 {} 环境变量状态", platform_display));
         println!();
 
-        // 对于 Claude 平台，从 settings.json 读取环境变量
+        // 对于 Claude 平台，从 settings.json 读取环境变量（项目级设置优先）
         // 对于其他平台，从系统环境变量读取
         let settings_env = if platform == Platform::Claude {
-            match SettingsService::with_default() {
-                Ok(service) => service
-                    .get_current_settings_async()
-                    .await
-                    .ok()
-                    .map(|s| s.env),
-                Err(_) => None,
+            if let Some(settings) = &project_settings {
+                Some(settings.env.clone())
+            } else {
+                match SettingsService::with_default() {
+                    Ok(service) => service
+                        .get_current_settings_async()
+                        .await
+                        .ok()
+                        .map(|s| s.env),
+                    Err(_) => None,
+                }
             }
         } else {
             None
@@ -317,7 +349,7 @@ pub async fn current_command() -> Result<()> {
         for var_name in &env_vars {
             // 优先从 settings.json 读取，如果没有则从系统环境变量读取
             let value = if let Some(ref env_map) = settings_env {
-                env_map.get(*var_name).cloned()
+                env_map.get(var_name).cloned()
             } else {
                 std::env::var(var_name).ok()
             };
@@ -356,7 +388,14 @@ pub async fn current_command() -> Result<()> {
     }
 
     // 验证设置（仅对 Claude 平台）
-    if platform == Platform::Claude {
+    if platform == Platform::Claude
+        && let Some(settings) = &project_settings
+    {
+        match settings.validate() {
+            Ok(_) => ColorOutput::success("✓ 项目级 Claude Code 设置验证通过"),
+            Err(e) => ColorOutput::warning(&format!("⚠ 项目级设置验证警告: {}", e)),
+        }
+    } else if platform == Platform::Claude {
         match SettingsService::with_default() {
             Ok(settings_service) => match settings_service.get_current_settings_async().await {
                 Ok(settings) => match settings.validate() {
@@ -373,5 +412,60 @@ pub async fn current_command() -> Result<()> {
         }
     }
 
+    // === 第四部分：设置漂移 ===
+    println!();
+    ColorOutput::step("🧭 设置漂移");
+    println!();
+    print_drift_section();
+
     Ok(())
 }
+
+/// 🧭 显示各已启用平台的设置文件与当前 profile 的差异
+fn print_drift_section() {
+    let drifts = match DriftService::with_default().and_then(|service| service.detect_all()) {
+        Ok(drifts) => drifts,
+        Err(e) => {
+            ColorOutput::warning(&format!("无法检测设置漂移: {}", e));
+            return;
+        }
+    };
+
+    let mut any_drift = false;
+    for drift in &drifts {
+        if let Some(error) = &drift.error {
+            ColorOutput::warning(&format!("{}: 无法检测漂移: {}", drift.platform, error));
+        } else if drift.has_drift() {
+            any_drift = true;
+            print_drift(drift);
+        } else if drift.profile.is_some() && drift.supported {
+            ColorOutput::success(&format!("✓ {}: 设置与当前 profile 一致", drift.platform));
+        }
+    }
+
+    if any_drift {
+        ColorOutput::info(
+            "运行 'ccr reconcile' 重新应用 profile，或 'ccr reconcile --adopt' 将当前值写回 profile",
+        );
+    }
+}
+
+/// 📖 读取项目级 Claude 设置
+///
+/// 文件不存在或无法解析时返回 None，回退到用户级设置
+fn load_project_settings(project_root: &Path) -> Option<ClaudeSettings> {
+    let settings_path = project_settings_path(project_root);
+    if !settings_path.is_file() {
+        return None;
+    }
+
+    let lock_manager = LockManager::with_default_path().ok()?;
+    SettingsManager::new(
+        &settings_path,
+        project_root.join(".claude").join("backups"),
+        lock_manager,
+    )
+    .load()
+    .map_err(|e| tracing::warn!("读取项目级设置失败 {:?}: {}", settings_path, e))
+    .ok()
+}
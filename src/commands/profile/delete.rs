@@ -5,6 +5,7 @@
 
 use crate::core::error::{CcrError, Result};
 use crate::core::logging::ColorOutput;
+use crate::managers::history::OperationType;
 use crate::services::ConfigService;
 use colored::Colorize;
 
@@ -88,7 +89,7 @@ pub async fn delete_command(config_name: &str, force: bool) -> Result<()> {
     // 3. 确认删除
     if !skip_confirmation {
         ColorOutput::step("步骤 3/3: 确认删除");
-        ColorOutput::warning("此操作不可恢复！");
+        ColorOutput::info("删除前会自动备份，可通过 'ccr undo' 恢复");
         println!();
 
         let config_name = config_name.to_string();
@@ -121,10 +122,13 @@ pub async fn delete_command(config_name: &str, force: bool) -> Result<()> {
     ColorOutput::separator();
     println!();
 
-    // 4. 执行删除
+    // 4. 执行删除（先备份，供 `ccr undo` 恢复）
+    let backup_path = service.backup_config(Some("delete"))?;
+    crate::services::auto_snapshot("delete");
     service.delete_config(config_name)?;
 
     ColorOutput::success(&format!("✓ 配置 '{}' 已删除", config_name));
+    super::record_profile_change(OperationType::Delete, config_name, Some(&backup_path)).await;
     println!();
 
     // 5. 后续提示
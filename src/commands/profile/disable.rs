@@ -5,6 +5,7 @@
 
 use crate::core::error::{CcrError, Result};
 use crate::core::logging::ColorOutput;
+use crate::managers::history::OperationType;
 use crate::services::config_service::ConfigService;
 use colored::Colorize;
 
@@ -60,8 +61,12 @@ pub async fn disable_command(config_name: &str, force: bool) -> Result<()> {
         println!();
     }
 
-    // 禁用配置
+    // 禁用配置（状态未变化时不记录历史）
+    let was_enabled = config_service.get_config(config_name)?.enabled;
     config_service.disable_config(config_name)?;
+    if was_enabled {
+        super::record_profile_change(OperationType::Disable, config_name, None).await;
+    }
 
     println!();
     ColorOutput::success(&format!("✓ 配置 '{}' 已禁用", config_name));
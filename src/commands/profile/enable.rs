@@ -5,6 +5,7 @@
 
 use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
+use crate::managers::history::OperationType;
 use crate::services::config_service::ConfigService;
 
 /// ✅ 启用指定配置
@@ -28,8 +29,12 @@ pub async fn enable_command(config_name: &str) -> Result<()> {
     // 创建配置服务
     let config_service = ConfigService::with_default()?;
 
-    // 启用配置
+    // 启用配置（状态未变化时不记录历史）
+    let was_enabled = config_service.get_config(config_name)?.enabled;
     config_service.enable_config(config_name)?;
+    if !was_enabled {
+        super::record_profile_change(OperationType::Enable, config_name, None).await;
+    }
 
     println!();
     ColorOutput::success(&format!("✓ 配置 '{}' 已启用", config_name));
@@ -7,8 +7,9 @@
 use crate::core::error::Result;
 use crate::core::logging::ColorOutput;
 use crate::managers::PlatformConfigManager;
+use crate::managers::config_validator::ConfigValidator;
 use crate::services::ConfigService;
-use crate::utils::Validatable;
+use crate::services::config_service::ConfigInfo;
 use colored::Colorize;
 use comfy_table::{
     Attribute, Cell, CellAlignment, Color as TableColor, ColumnConstraint, ContentArrangement,
@@ -22,6 +23,7 @@ use comfy_table::{
 /// - ⚙️ 配置文件路径
 /// - 🎯 默认配置和当前配置
 /// - 📋 所有配置节列表(带验证状态)
+/// - 🧬 继承的配置显示解析后的值，并标注值来自哪个父配置节
 /// - ▶️ 使用表格形式突出显示关键信息
 pub async fn list_command() -> Result<()> {
     ColorOutput::title("可用配置列表");
@@ -99,6 +101,7 @@ pub async fn list_command() -> Result<()> {
                 .fg(TableColor::Cyan),
         ]);
 
+    let validator = ConfigValidator::new();
     for config_info in &list.configs {
         // 状态列
         let status = if config_info.is_current {
@@ -107,17 +110,23 @@ pub async fn list_command() -> Result<()> {
                 .add_attribute(Attribute::Bold)
         } else if config_info.is_default {
             Cell::new("* 默认").fg(TableColor::Yellow)
+        } else if config_info.is_template {
+            Cell::new("模板").fg(TableColor::DarkGrey)
         } else {
             Cell::new("")
         };
 
-        // 配置名称
+        // 配置名称（继承的配置显示父配置节）
+        let name_display = match &config_info.extends {
+            Some(parent) => format!("{}\n↳ {}", config_info.name, parent),
+            None => config_info.name.clone(),
+        };
         let name_cell = if config_info.is_current {
-            Cell::new(&config_info.name)
+            Cell::new(name_display)
                 .fg(TableColor::Green)
                 .add_attribute(Attribute::Bold)
         } else {
-            Cell::new(&config_info.name)
+            Cell::new(name_display)
         };
 
         // 提供商信息
@@ -127,7 +136,12 @@ pub async fn list_command() -> Result<()> {
                 Some("third_party_model") => "[3rd]",
                 _ => "[?]",
             };
-            format!("{} {}", type_tag, provider)
+            format!(
+                "{} {}{}",
+                type_tag,
+                provider,
+                inherited_note(config_info, "provider")
+            )
         } else {
             "未分类".to_string()
         };
@@ -140,7 +154,12 @@ pub async fn list_command() -> Result<()> {
         } else {
             base_url.to_string()
         };
-        let base_url_cell = Cell::new(base_url_display).fg(TableColor::Blue);
+        let base_url_cell = Cell::new(format!(
+            "{}{}",
+            base_url_display,
+            inherited_note(config_info, "base_url")
+        ))
+        .fg(TableColor::Blue);
 
         // 模型信息
         let model_info = if let Some(model) = &config_info.model {
@@ -149,6 +168,7 @@ pub async fn list_command() -> Result<()> {
             } else {
                 model.clone()
             };
+            let model_short = format!("{}{}", model_short, inherited_note(config_info, "model"));
             if let Some(small) = &config_info.small_fast_model {
                 format!("{}\n(small: {})", model_short, small)
             } else {
@@ -174,9 +194,9 @@ pub async fn list_command() -> Result<()> {
             extra_info.join("\n")
         };
 
-        // 验证状态
-        let section = config.get_section(&config_info.name)?;
-        let validation_cell = match section.validate() {
+        // 验证状态（按继承解析后的有效值）
+        let validation_cell = match validator.validate_resolved_section(&config, &config_info.name)
+        {
             Ok(_) => Cell::new("OK")
                 .fg(TableColor::Green)
                 .add_attribute(Attribute::Bold),
@@ -251,6 +271,15 @@ pub async fn list_command() -> Result<()> {
     println!("  • 使用 'ccr platform current' 查看当前平台详情");
     println!("  • 使用 'ccr switch <名称>' 切换配置");
     println!("  • 🔄 = 官方中转  🤖 = 第三方模型");
+    println!("  • ↳ = 继承的父配置，(← 名称) = 该值继承自对应配置");
 
     Ok(())
 }
+
+/// 🧬 继承字段的来源标注，如 "\n(← relay)"
+fn inherited_note(config_info: &ConfigInfo, field: &str) -> String {
+    config_info
+        .inherited_origin(field)
+        .map(|origin| format!("\n(← {})", origin))
+        .unwrap_or_default()
+}
@@ -8,6 +8,7 @@ mod delete;
 mod disable;
 mod enable;
 mod list;
+mod reconcile;
 mod switch;
 
 pub use add::add_command;
@@ -16,4 +17,29 @@ pub use delete::delete_command;
 pub use disable::disable_command;
 pub use enable::enable_command;
 pub use list::list_command;
-pub use switch::switch_command;
+pub use reconcile::reconcile_command;
+pub use switch::{
+    switch_auto_command, switch_command, switch_dry_run_command, switch_local_command,
+};
+
+use crate::core::logging::ColorOutput;
+use crate::managers::history::OperationType;
+use crate::services::HistoryService;
+use std::path::Path;
+
+/// 📚 记录 profile 变更历史，供 `ccr undo` 撤销
+///
+/// 变更已经写入，记录失败只提示不报错
+async fn record_profile_change(operation: OperationType, name: &str, backup_path: Option<&Path>) {
+    let result = match HistoryService::with_default() {
+        Ok(service) => {
+            service
+                .record_profile_change_async(operation, name, backup_path)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        ColorOutput::warning(&format!("记录操作历史失败: {}", e));
+    }
+}
@@ -0,0 +1,139 @@
+// 🧭 reconcile 命令实现 - 协调设置漂移
+// 🔁 重新应用当前 profile，或 📥 将设置文件中的当前值写回 profile
+
+use crate::core::error::{CcrError, Result};
+use crate::core::logging::ColorOutput;
+use crate::services::{DriftService, PlatformDrift};
+use comfy_table::{
+    Attribute, Cell, Color as TableColor, ContentArrangement, Table, presets::UTF8_FULL,
+};
+
+/// 🧭 协调设置文件与当前 profile 的差异
+///
+/// 执行流程:
+/// 1. 🔍 检测指定平台（默认所有已启用平台）的漂移并逐项列出
+/// 2. ✋ 确认（-y 跳过）
+/// 3. 🔁 默认重新应用 profile；📥 `--adopt` 时将当前值写回 profile
+pub async fn reconcile_command(
+    platform: Option<&str>,
+    adopt: bool,
+    dry_run: bool,
+    skip_confirmation: bool,
+) -> Result<()> {
+    ColorOutput::title("协调设置漂移");
+    println!();
+
+    let drifts = match platform {
+        Some(platform) => vec![DriftService::detect(platform)?],
+        None => DriftService::with_default()?.detect_all()?,
+    };
+
+    let drifted: Vec<&PlatformDrift> = drifts.iter().filter(|d| d.has_drift()).collect();
+    for drift in &drifts {
+        if let Some(error) = &drift.error {
+            ColorOutput::warning(&format!("{}: 无法检测漂移: {}", drift.platform, error));
+        }
+    }
+    if drifted.is_empty() {
+        ColorOutput::success("所有平台的设置与当前 profile 一致");
+        return Ok(());
+    }
+
+    for drift in &drifted {
+        print_drift(drift);
+    }
+
+    if dry_run {
+        ColorOutput::info("预览模式，未修改任何文件");
+        return Ok(());
+    }
+
+    if !skip_confirmation {
+        let prompt = if adopt {
+            format!("将 {} 个平台的当前值写回 profile?", drifted.len())
+        } else {
+            format!("重新应用 {} 个平台的 profile?", drifted.len())
+        };
+        let confirmed = tokio::task::spawn_blocking(move || -> Result<bool> {
+            Ok(ColorOutput::ask_confirmation(&prompt, false))
+        })
+        .await
+        .map_err(|e| CcrError::FileIoError(format!("读取用户输入失败: {e}")))??;
+
+        if !confirmed {
+            ColorOutput::info("已取消");
+            return Ok(());
+        }
+    }
+
+    for drift in drifted {
+        let Some(profile) = &drift.profile else {
+            continue;
+        };
+        if adopt {
+            let remaining = DriftService::adopt(&drift.platform)?;
+            if remaining.is_empty() {
+                ColorOutput::success(&format!(
+                    "{}: 已将当前值写回 profile '{}'",
+                    drift.platform, profile
+                ));
+            } else {
+                let keys: Vec<&str> = remaining.iter().map(|e| e.key.as_str()).collect();
+                ColorOutput::warning(&format!(
+                    "{}: 以下设置项无法写回 profile '{}'（间接形式或继承的值）: {}",
+                    drift.platform,
+                    profile,
+                    keys.join(", ")
+                ));
+            }
+        } else {
+            DriftService::reapply(&drift.platform, profile, "reconcile")?;
+            ColorOutput::success(&format!(
+                "{}: 已重新应用 profile '{}'",
+                drift.platform, profile
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 📊 逐项显示一个平台的漂移（敏感值脱敏）
+pub(super) fn print_drift(drift: &PlatformDrift) {
+    let drift = drift.masked();
+    ColorOutput::step(&format!(
+        "{} (profile: {})",
+        drift.platform,
+        drift.profile.as_deref().unwrap_or("-")
+    ));
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::DynamicFullWidth)
+        .set_header(vec![
+            Cell::new("设置项")
+                .add_attribute(Attribute::Bold)
+                .fg(TableColor::Cyan),
+            Cell::new("profile 预期值")
+                .add_attribute(Attribute::Bold)
+                .fg(TableColor::Cyan),
+            Cell::new("当前值")
+                .add_attribute(Attribute::Bold)
+                .fg(TableColor::Cyan),
+        ]);
+
+    let value_cell = |value: &Option<String>, color: TableColor| match value {
+        Some(value) => Cell::new(value).fg(color),
+        None => Cell::new("(未设置)").fg(TableColor::DarkGrey),
+    };
+    for entry in &drift.entries {
+        table.add_row(vec![
+            Cell::new(&entry.key).fg(TableColor::Yellow),
+            value_cell(&entry.expected, TableColor::Green),
+            value_cell(&entry.live, TableColor::Red),
+        ]);
+    }
+    println!("{}", table);
+    println!();
+}
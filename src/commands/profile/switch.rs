@@ -9,27 +9,73 @@
 #![allow(clippy::unused_async)]
 
 use crate::core::error::{CcrError, Result};
+use crate::core::journal::Journal;
 use crate::core::logging::ColorOutput;
-use crate::managers::PlatformConfigManager;
 use crate::managers::config::ConfigSection;
 use crate::managers::history::{
     HistoryEntry, HistoryManager, OperationDetails, OperationResult, OperationType,
 };
+use crate::managers::project_config::{PROJECT_CONFIG_FILE, ProjectConfig, find_project_root};
 use crate::managers::settings::SettingsManager;
+use crate::managers::settings_bundle;
+use crate::managers::{BudgetManager, CostTracker, PlatformConfigManager, transcript_collector};
 use crate::models::Platform;
-use crate::platforms::create_platform;
+use crate::models::PlatformConfig;
+use crate::models::PlatformPaths;
+use crate::models::ProfileConfig;
+use crate::models::budget::BudgetEnforcement;
+use crate::platforms::{ClaudePlatform, base, create_platform_by_name};
+use crate::services::auto_select_service::{AutoSelectService, AutoSelection, SelectPolicy};
+use crate::services::{ConfigService, HealthMonitorService};
 use crate::utils::Validatable;
 use colored::Colorize;
 use comfy_table::{
     Attribute, Cell, Color as TableColor, ContentArrangement, Table, presets::UTF8_FULL,
 };
 use std::collections::HashMap;
-use std::str::FromStr;
+use std::path::Path;
+
+/// 🧬 解析目标 profile 的继承链，拒绝切换到模板
+fn resolve_target_profile(
+    profiles: &indexmap::IndexMap<String, ProfileConfig>,
+    config_name: &str,
+) -> Result<ProfileConfig> {
+    let resolved = base::resolve_profile_section(profiles, config_name).map_err(|e| {
+        ColorOutput::error(&format!("解析配置继承失败: {}", e));
+        e
+    })?;
+
+    if resolved.section.is_template() {
+        ColorOutput::error(&format!("配置 '{}' 是模板，只能被继承", config_name));
+        return Err(CcrError::ValidationError(format!(
+            "配置 '{}' 是模板，不能直接切换",
+            config_name
+        )));
+    }
+
+    if resolved.chain.len() > 1 {
+        ColorOutput::info(&format!("继承链: {}", resolved.chain.join(" → ")));
+    }
+
+    Ok(base::section_to_profile(&resolved.section))
+}
+
+/// 📝 附加到切换历史记录的决策说明（如 `--auto` 的选择依据）
+struct SwitchReason {
+    /// 写入 `details.extra`
+    summary: String,
+    /// 写入 `notes`
+    details: String,
+}
 
 /// 🔄 切换到指定配置
 ///
 /// 这是一个原子性操作,确保配置切换的完整性和可追溯性
 pub async fn switch_command(config_name: &str) -> Result<()> {
+    switch_with_reason(config_name, None).await
+}
+
+async fn switch_with_reason(config_name: &str, decision: Option<&SwitchReason>) -> Result<()> {
     ColorOutput::title(&format!("切换配置: {}", config_name));
     println!();
 
@@ -37,7 +83,6 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
     let platform_config_mgr = PlatformConfigManager::with_default()?;
     let unified_config = platform_config_mgr.load()?;
     let platform_name = &unified_config.current_platform;
-    let platform = Platform::from_str(platform_name)?;
 
     // 📖 步骤 1: 读取并校验目标配置
     ColorOutput::step("步骤 1/3: 读取配置文件");
@@ -45,14 +90,17 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
     ColorOutput::info(&format!("使用平台: {}", platform_name.bright_yellow()));
 
     // 从平台配置加载 profile
-    let platform_config = create_platform(platform)
-        .map_err(|e| CcrError::ConfigError(format!("创建平台 {} 失败: {}", platform_name, e)))?;
+    let platform_config = create_platform_by_name(platform_name).map_err(|e| match e {
+        CcrError::PlatformNotFound(_) => e,
+        e => CcrError::ConfigError(format!("创建平台 {} 失败: {}", platform_name, e)),
+    })?;
+    let platform = platform_config.platform_type();
 
     // 加载所有 profiles（后续会复用并更新 usage_count）
     let mut profiles = platform_config.load_profiles()?;
 
     // 查找目标 profile
-    let profile = profiles.get(config_name).ok_or_else(|| {
+    if !profiles.contains_key(config_name) {
         ColorOutput::error(&format!(
             "配置 '{}' 在平台 {} 中不存在",
             config_name, platform_name
@@ -65,31 +113,12 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
             "  • 或编辑配置文件: ~/.ccr/platforms/{}/profiles.toml",
             platform_name
         );
-        CcrError::ConfigSectionNotFound(config_name.to_string())
-    })?;
+        return Err(CcrError::ConfigSectionNotFound(config_name.to_string()));
+    }
 
-    // 转换 ProfileConfig 为 ConfigSection
-    let target_section = ConfigSection {
-        description: profile.description.clone(),
-        base_url: profile.base_url.clone(),
-        auth_token: profile.auth_token.clone(),
-        model: profile.model.clone(),
-        small_fast_model: profile.small_fast_model.clone(),
-        provider: profile.provider.clone(),
-        provider_type: profile.provider_type.as_ref().and_then(|pt| {
-            use crate::managers::config::ProviderType;
-            match pt.as_str() {
-                "official_relay" => Some(ProviderType::OfficialRelay),
-                "third_party_model" => Some(ProviderType::ThirdPartyModel),
-                _ => None,
-            }
-        }),
-        account: profile.account.clone(),
-        tags: profile.tags.clone(),
-        usage_count: profile.usage_count,
-        enabled: profile.enabled,
-        other: indexmap::IndexMap::new(),
-    };
+    // 解析继承后转换为 ConfigSection
+    let profile = resolve_target_profile(&profiles, config_name)?;
+    let target_section = base::profile_to_section(&profile)?;
 
     // 验证目标配置
     target_section.validate().map_err(|e| {
@@ -100,6 +129,45 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
     ColorOutput::success(&format!("✅ 目标配置 '{}' 验证通过", config_name));
     println!();
 
+    // 💰 预算检查: 超出预算时按配置拒绝、回退到低成本配置或降级模型
+    let project_dir = std::env::current_dir().ok();
+    let downgrade = match check_budget(config_name, &target_section, project_dir.as_deref())? {
+        BudgetEnforcement::Allow => None,
+        BudgetEnforcement::Block { reason } => {
+            ColorOutput::error(&format!("💰 {}", reason));
+            return Err(CcrError::BudgetExceeded(format!(
+                "已拒绝切换到付费配置 '{}': {}",
+                config_name, reason
+            )));
+        }
+        BudgetEnforcement::Fallback { profile, reason } => {
+            ColorOutput::warning(&format!(
+                "💰 {}，改为切换到低成本配置 '{}'",
+                reason, profile
+            ));
+            println!();
+            return Box::pin(switch_with_reason(&profile, decision)).await;
+        }
+        BudgetEnforcement::Downgrade { reason } => {
+            match (&target_section.small_fast_model, platform) {
+                (Some(small_model), Platform::Claude) => {
+                    ColorOutput::warning(&format!(
+                        "💰 {}，ANTHROPIC_MODEL 将降级为 {}",
+                        reason, small_model
+                    ));
+                    Some((small_model.clone(), reason))
+                }
+                _ => {
+                    ColorOutput::warning(&format!(
+                        "💰 {}，但目标配置不支持模型降级（仅 Claude 平台且需设置 small_fast_model）",
+                        reason
+                    ));
+                    None
+                }
+            }
+        }
+    };
+
     // 📊 记录旧的环境变量状态（仅 Claude 平台，无副作用）
     let (old_env, new_env_display): (
         HashMap<String, Option<String>>,
@@ -113,7 +181,10 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
             .unwrap_or_default();
 
         // 使用无副作用的方法获取新状态（不会打印日志）
-        let new = target_section.to_anthropic_env_status();
+        let mut new = target_section.to_anthropic_env_status();
+        if let Some((small_model, _)) = &downgrade {
+            new.insert("ANTHROPIC_MODEL".to_string(), Some(small_model.clone()));
+        }
         (old, new)
     } else {
         (HashMap::new(), HashMap::new())
@@ -127,59 +198,114 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
         String::new()
     });
 
-    // 📊 递增目标 profile 的使用次数（复用已加载的 profiles）
-    if let Some(profile) = profiles.get_mut(config_name) {
-        profile.usage_count = Some(profile.usage_count.unwrap_or(0) + 1);
-        tracing::debug!(
-            "📊 递增 profile '{}' 的使用次数: {}",
+    // 📸 切换前为所有配置文件创建快照（内容未变化时复用最新快照）
+    crate::services::auto_snapshot("switch");
+
+    // 📒 步骤 2、3 的写入组成一个事务，任一步失败都恢复切换前的文件
+    let history_manager = HistoryManager::with_default()?;
+    let mut journal_paths = platform_config.settings_files();
+    let paths = PlatformPaths::for_name(platform_name)?;
+    journal_paths.extend([
+        paths.profiles_file,
+        paths.registry_file,
+        history_manager.history_path().to_path_buf(),
+    ]);
+    let transaction = Journal::with_default()?.begin("switch", &journal_paths)?;
+
+    let applied: Result<()> = async {
+        // 📊 递增目标 profile 的使用次数（复用已加载的 profiles）
+        if let Some(profile) = profiles.get_mut(config_name) {
+            profile.usage_count = Some(profile.usage_count.unwrap_or(0) + 1);
+            tracing::debug!(
+                "📊 递增 profile '{}' 的使用次数: {}",
+                config_name,
+                profile.usage_count.unwrap_or(0)
+            );
+        }
+        // 保存更新后的 profiles（包含递增的 usage_count）
+        platform_config.save_profile(
             config_name,
-            profile.usage_count.unwrap_or(0)
-        );
-    }
-    // 保存更新后的 profiles（包含递增的 usage_count）
-    platform_config.save_profile(
-        config_name,
-        profiles.get(config_name).expect("配置名称应该存在"),
-    )?;
+            profiles.get(config_name).expect("配置名称应该存在"),
+        )?;
+
+        // 应用 profile (这会设置当前profile并保存settings)
+        platform_config.apply_profile(config_name)?;
+
+        // 💰 预算降级: 用 small_fast_model 覆盖主模型
+        if let Some((small_model, _)) = &downgrade {
+            let settings_manager = SettingsManager::with_default()?;
+            let mut settings = settings_manager.load()?;
+            settings
+                .env
+                .insert("ANTHROPIC_MODEL".to_string(), small_model.clone());
+            settings_manager.save_atomic(&settings)?;
+            ColorOutput::warning(&format!("💰 ANTHROPIC_MODEL 已降级为 {}", small_model));
+        }
 
-    // 应用 profile (这会设置当前profile并保存settings)
-    platform_config.apply_profile(config_name)?;
+        ColorOutput::success(&format!(
+            "✅ 平台 {} 的当前配置已设置为: {}",
+            platform_name, config_name
+        ));
 
-    ColorOutput::success(&format!(
-        "✅ 平台 {} 的当前配置已设置为: {}",
-        platform_name, config_name
-    ));
+        println!();
 
-    println!();
+        // 📚 步骤 3: 记录历史(包含环境变量变化的掩码记录)
+        ColorOutput::step("步骤 3/3: 记录操作历史");
 
-    // 📚 步骤 3: 记录历史(包含环境变量变化的掩码记录)
-    ColorOutput::step("步骤 3/3: 记录操作历史");
-    let history_manager = HistoryManager::with_default()?;
-
-    let mut history_entry = HistoryEntry::new(
-        OperationType::Switch,
-        OperationDetails {
-            from_config: if old_current.is_empty() {
-                None
-            } else {
-                Some(old_current.clone())
+        let mut history_entry = HistoryEntry::new(
+            OperationType::Switch,
+            OperationDetails {
+                from_config: if old_current.is_empty() {
+                    None
+                } else {
+                    Some(old_current.clone())
+                },
+                to_config: Some(config_name.to_string()),
+                backup_path: None,
+                extra: decision
+                    .map(|r| r.summary.clone())
+                    .into_iter()
+                    .chain(downgrade.as_ref().map(|(small_model, reason)| {
+                        format!("预算降级模型为 {}: {}", small_model, reason)
+                    }))
+                    .reduce(|a, b| format!("{}; {}", a, b)),
             },
-            to_config: Some(config_name.to_string()),
-            backup_path: None,
-            extra: None,
-        },
-        OperationResult::Success,
-    );
+            OperationResult::Success,
+        );
 
-    // 记录环境变量变化（仅 Claude 平台）
-    if platform == Platform::Claude {
-        for (var_name, new_value) in new_env_display.clone() {
-            let old_value = old_env.get(&var_name).and_then(|v| v.clone());
-            history_entry.add_env_change(var_name, old_value, new_value);
+        history_entry.platform = Some(platform_name.clone());
+        history_entry.notes = decision.map(|r| r.details.clone());
+
+        // 记录环境变量变化（仅 Claude 平台）
+        // 使用写入后的实际值: vault / ${env:…} 等密钥来源已解析，撤销时与 settings.json 比较
+        if platform == Platform::Claude {
+            let applied_env = SettingsManager::with_default()?
+                .load()?
+                .anthropic_env_status();
+            let mut names: Vec<&String> = applied_env.keys().chain(old_env.keys()).collect();
+            names.sort();
+            names.dedup();
+            for var_name in names {
+                let old_value = old_env.get(var_name).cloned().flatten();
+                let new_value = applied_env.get(var_name).cloned().flatten();
+                history_entry.add_env_change(var_name.clone(), old_value, new_value);
+            }
         }
-    }
 
-    history_manager.add_async(history_entry).await?;
+        history_manager.add_async(history_entry).await
+    }
+    .await;
+    match applied {
+        Ok(()) => transaction.commit()?,
+        Err(e) => {
+            if let Err(rollback_error) = transaction.rollback() {
+                ColorOutput::error(&format!("恢复切换前的文件失败: {}", rollback_error));
+            } else {
+                ColorOutput::warning("切换未完成，已恢复切换前的文件");
+            }
+            return Err(e);
+        }
+    }
     ColorOutput::success("✅ 操作历史已记录");
     println!();
 
@@ -312,12 +438,21 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
                 .fg(TableColor::Cyan),
         ]);
 
-    // 显示环境变量变化（动态获取平台环境变量）
-    let env_vars = platform_config.get_env_var_names();
+    // 显示环境变量变化（动态获取平台环境变量，再追加 env 表中的额外变量）
+    let mut env_vars = platform_config.get_env_var_names();
+    let mut extra_vars: Vec<String> = old_env
+        .keys()
+        .chain(new_env_display.keys())
+        .filter(|name| !env_vars.contains(name))
+        .cloned()
+        .collect();
+    extra_vars.sort();
+    extra_vars.dedup();
+    env_vars.extend(extra_vars);
 
     for var_name in env_vars {
-        let old_val = old_env.get(var_name).and_then(|v| v.as_ref());
-        let new_val = new_env_display.get(var_name).and_then(|v| v.as_ref());
+        let old_val = old_env.get(&var_name).and_then(|v| v.as_ref());
+        let new_val = new_env_display.get(&var_name).and_then(|v| v.as_ref());
 
         let is_sensitive = var_name.contains("TOKEN") || var_name.contains("KEY");
 
@@ -420,3 +555,367 @@ pub async fn switch_command(config_name: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// 🎯 自动选择并切换到最佳的可用配置 (--auto)
+///
+/// 并发探测当前平台启用的配置（可按标签筛选），剔除不可用的，按策略与优先级权重
+/// 选出最佳配置后走常规切换流程，选择依据写入历史记录。最佳配置已是当前配置时不做切换，
+/// 只记录一条 AutoKeep 历史（同样包含选择依据）
+pub async fn switch_auto_command(tag: Option<&str>, prefer: &str, dry_run: bool) -> Result<()> {
+    let policy: SelectPolicy = prefer.parse()?;
+    ColorOutput::title(&format!("自动选择配置 ({})", policy.display()));
+    println!();
+
+    let targets = HealthMonitorService::tagged_targets(tag)?;
+    ColorOutput::info(&format!("并发探测 {} 个候选配置...", targets.len()));
+    let selection = AutoSelectService::with_default()?
+        .select(targets, policy)
+        .await?;
+    println!();
+    print_selection(&selection);
+    println!();
+
+    let Some(winner) = selection.winner() else {
+        ColorOutput::error("所有候选配置均不可用，保持当前配置");
+        return Err(CcrError::NetworkError(selection.summary()));
+    };
+    ColorOutput::success(&format!("最佳配置: {}", winner.profile));
+    println!();
+
+    if dry_run {
+        return switch_dry_run_command(&winner.profile).await;
+    }
+
+    let current = ConfigService::with_default()?.load_config()?.current_config;
+    let reason = SwitchReason {
+        summary: selection.summary(),
+        details: selection.reasoning(),
+    };
+    if current == winner.profile {
+        ColorOutput::info(&format!("当前配置 '{}' 已是最佳选择，无需切换", current));
+        let mut entry = HistoryEntry::new(
+            OperationType::AutoKeep,
+            OperationDetails {
+                from_config: Some(current.clone()),
+                to_config: Some(current),
+                backup_path: None,
+                extra: Some(reason.summary),
+            },
+            OperationResult::Success,
+        );
+        entry.platform = Some(
+            PlatformConfigManager::with_default()?
+                .load()?
+                .current_platform,
+        );
+        entry.notes = Some(reason.details);
+        HistoryManager::with_default()?.add_async(entry).await?;
+        return Ok(());
+    }
+
+    switch_with_reason(&winner.profile, Some(&reason)).await
+}
+
+/// 📋 输出候选配置的探测结果与得分（最佳配置在第一行）
+fn print_selection(selection: &AutoSelection) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::DynamicFullWidth)
+        .set_header(vec![
+            Cell::new("配置").fg(TableColor::Cyan),
+            Cell::new("状态").fg(TableColor::Cyan),
+            Cell::new("延迟").fg(TableColor::Cyan),
+            Cell::new("模型单价").fg(TableColor::Cyan),
+            Cell::new("权重").fg(TableColor::Cyan),
+            Cell::new("得分").fg(TableColor::Cyan),
+            Cell::new("说明").fg(TableColor::Cyan),
+        ]);
+
+    let winner = selection.winner().map(|w| w.profile.as_str());
+    for candidate in &selection.candidates {
+        let name = if winner == Some(candidate.profile.as_str()) {
+            Cell::new(format!("✅ {}", candidate.profile))
+                .fg(TableColor::Green)
+                .add_attribute(Attribute::Bold)
+        } else {
+            Cell::new(&candidate.profile)
+        };
+        let status_color = if candidate.is_available() {
+            TableColor::Green
+        } else {
+            TableColor::Red
+        };
+        let dash = || "-".to_string();
+
+        table.add_row(vec![
+            name,
+            Cell::new(candidate.status.display()).fg(status_color),
+            Cell::new(
+                candidate
+                    .latency_ms
+                    .map_or_else(dash, |l| format!("{} ms", l)),
+            ),
+            Cell::new(
+                candidate
+                    .cost_per_mtok
+                    .map_or_else(dash, |c| format!("${:.2}/MTok", c)),
+            ),
+            Cell::new(candidate.priority),
+            Cell::new(candidate.score.map_or_else(dash, |s| format!("{:.3}", s))),
+            Cell::new(candidate.error.as_deref().unwrap_or("-")),
+        ]);
+    }
+
+    println!("{}", table);
+}
+
+/// 📌 项目级切换配置
+///
+/// 在项目根目录写入 `.ccr.toml` 固定 profile；Claude 平台同时写入
+/// `<repo>/.claude/settings.local.json`。用户级设置文件与全局当前 profile 保持不变
+pub async fn switch_local_command(config_name: &str) -> Result<()> {
+    ColorOutput::title(&format!("项目级切换配置: {}", config_name));
+    println!();
+
+    let platform_config_mgr = PlatformConfigManager::with_default()?;
+    let unified_config = platform_config_mgr.load()?;
+    let platform_name = &unified_config.current_platform;
+
+    let cwd = std::env::current_dir()
+        .map_err(|e| CcrError::ConfigError(format!("无法获取当前目录: {}", e)))?;
+    let project_root = find_project_root(&cwd);
+
+    // 📖 步骤 1: 读取并校验目标配置
+    ColorOutput::step("步骤 1/3: 读取配置文件");
+    ColorOutput::info(&format!("使用平台: {}", platform_name.bright_yellow()));
+    ColorOutput::info(&format!("项目目录: {}", project_root.display()));
+
+    let platform_config = create_platform_by_name(platform_name)?;
+    let platform = platform_config.platform_type();
+    let profiles = platform_config.load_profiles()?;
+    if !profiles.contains_key(config_name) {
+        ColorOutput::error(&format!(
+            "配置 '{}' 在平台 {} 中不存在",
+            config_name, platform_name
+        ));
+        return Err(CcrError::ConfigSectionNotFound(config_name.to_string()));
+    }
+
+    let profile = resolve_target_profile(&profiles, config_name)?;
+    platform_config.validate_profile(&profile).map_err(|e| {
+        ColorOutput::error(&format!("目标配置验证失败: {}", e));
+        e
+    })?;
+    ColorOutput::success(&format!("✅ 目标配置 '{}' 验证通过", config_name));
+    println!();
+
+    // 💰 预算检查: 与全局切换一致，按项目根目录匹配项目级预算
+    let target_section = base::profile_to_section(&profile)?;
+    let downgrade = match check_budget(config_name, &target_section, Some(&project_root))? {
+        BudgetEnforcement::Allow => None,
+        BudgetEnforcement::Block { reason } => {
+            ColorOutput::error(&format!("💰 {}", reason));
+            return Err(CcrError::BudgetExceeded(format!(
+                "已拒绝切换到付费配置 '{}': {}",
+                config_name, reason
+            )));
+        }
+        BudgetEnforcement::Fallback { profile, reason } => {
+            ColorOutput::warning(&format!("💰 {}，改为固定低成本配置 '{}'", reason, profile));
+            println!();
+            return Box::pin(switch_local_command(&profile)).await;
+        }
+        BudgetEnforcement::Downgrade { reason } => {
+            match (&target_section.small_fast_model, platform) {
+                (Some(small_model), Platform::Claude) => {
+                    ColorOutput::warning(&format!(
+                        "💰 {}，ANTHROPIC_MODEL 将降级为 {}",
+                        reason, small_model
+                    ));
+                    Some((small_model.clone(), reason))
+                }
+                _ => {
+                    ColorOutput::warning(&format!(
+                        "💰 {}，但目标配置不支持模型降级（仅 Claude 平台且需设置 small_fast_model）",
+                        reason
+                    ));
+                    None
+                }
+            }
+        }
+    };
+
+    // ✏️ 步骤 2: 写入项目配置
+    ColorOutput::step("步骤 2/3: 写入项目配置");
+
+    let project_file = project_root.join(PROJECT_CONFIG_FILE);
+    let old_pinned = if project_file.is_file() {
+        ProjectConfig::load(&project_file)
+            .ok()
+            .map(|project| project.profile)
+    } else {
+        None
+    };
+
+    ProjectConfig::new(config_name, Some(platform_name.clone())).save(&project_file)?;
+    ColorOutput::success(&format!("✅ 已写入 {}", project_file.display()));
+
+    let settings_path = if platform == Platform::Claude {
+        let path = ClaudePlatform::new()?.apply_profile_to_project(
+            config_name,
+            &project_root,
+            downgrade
+                .as_ref()
+                .map(|(small_model, _)| small_model.as_str()),
+        )?;
+        ColorOutput::success(&format!("✅ 已写入 {}", path.display()));
+        if let Some((small_model, _)) = &downgrade {
+            ColorOutput::warning(&format!("💰 ANTHROPIC_MODEL 已降级为 {}", small_model));
+        }
+        Some(path)
+    } else {
+        ColorOutput::warning(&format!(
+            "平台 {} 暂不支持项目级设置文件，仅记录项目固定的 profile",
+            platform_name
+        ));
+        None
+    };
+    println!();
+
+    // 📚 步骤 3: 记录历史
+    ColorOutput::step("步骤 3/3: 记录操作历史");
+    let history_manager = HistoryManager::with_default()?;
+    let history_entry = HistoryEntry::new(
+        OperationType::LocalSwitch,
+        OperationDetails {
+            from_config: old_pinned,
+            to_config: Some(config_name.to_string()),
+            backup_path: None,
+            extra: Some(
+                std::iter::once(format!("项目: {}", project_root.display()))
+                    .chain(downgrade.as_ref().map(|(small_model, reason)| {
+                        format!("预算降级模型为 {}: {}", small_model, reason)
+                    }))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+        },
+        OperationResult::Success,
+    );
+    history_manager.add_async(history_entry).await?;
+    ColorOutput::success("✅ 操作历史已记录");
+    println!();
+
+    ColorOutput::separator();
+    println!();
+    ColorOutput::success(&format!(
+        "🎉 项目 {} 已固定使用配置 {}",
+        project_root.display(),
+        config_name.bright_green().bold()
+    ));
+    ColorOutput::info("💡 在项目目录下运行 'ccr current' 查看生效的配置及其来源");
+    if settings_path.is_some() {
+        ColorOutput::warning("⚠ settings.local.json 包含认证令牌，请勿提交到版本库");
+    }
+
+    Ok(())
+}
+
+/// 🔍 切换演练 (--dry-run)
+///
+/// 解析并验证目标配置，显示 settings.json 将发生的变化（JSON 路径差异），不写入任何文件
+pub async fn switch_dry_run_command(config_name: &str) -> Result<()> {
+    ColorOutput::title(&format!("切换演练: {}", config_name));
+    println!();
+
+    let platform_config_mgr = PlatformConfigManager::with_default()?;
+    let unified_config = platform_config_mgr.load()?;
+    let platform_name = &unified_config.current_platform;
+    ColorOutput::info(&format!("使用平台: {}", platform_name.bright_yellow()));
+
+    let platform_config = create_platform_by_name(platform_name)?;
+    let profiles = platform_config.load_profiles()?;
+    if !profiles.contains_key(config_name) {
+        ColorOutput::error(&format!(
+            "配置 '{}' 在平台 {} 中不存在",
+            config_name, platform_name
+        ));
+        return Err(CcrError::ConfigSectionNotFound(config_name.to_string()));
+    }
+
+    let profile = resolve_target_profile(&profiles, config_name)?;
+    platform_config.validate_profile(&profile).map_err(|e| {
+        ColorOutput::error(&format!("目标配置验证失败: {}", e));
+        e
+    })?;
+    ColorOutput::success(&format!("✅ 目标配置 '{}' 验证通过", config_name));
+    println!();
+
+    if platform_config.platform_type() != Platform::Claude {
+        ColorOutput::warning(&format!("平台 {} 暂不支持预览设置文件变化", platform_name));
+        return Ok(());
+    }
+
+    let platform = ClaudePlatform::new()?;
+    let (current, next) = platform.preview_settings(config_name)?;
+    let changes = settings_bundle::diff(
+        &serde_json::to_value(&current)?,
+        &serde_json::to_value(&next)?,
+    );
+
+    ColorOutput::step(&format!(
+        "settings.json 变化预览 ({})",
+        platform.get_settings_path().display()
+    ));
+    println!();
+
+    if changes.is_empty() {
+        ColorOutput::info("设置文件不会发生变化");
+    }
+    for change in &changes {
+        let sensitive = change.path.starts_with("env.")
+            && (change.path.contains("TOKEN") || change.path.contains("KEY"));
+        let display = |value: &serde_json::Value| match value {
+            serde_json::Value::String(text) if sensitive => ColorOutput::mask_sensitive(text),
+            _ => value.to_string(),
+        };
+        match (&change.old, &change.new) {
+            (None, Some(new)) => println!("  {} {}: {}", "+".green(), change.path, display(new)),
+            (Some(old), None) => println!("  {} {}: {}", "-".red(), change.path, display(old)),
+            (Some(old), Some(new)) => println!(
+                "  {} {}: {} → {}",
+                "~".yellow(),
+                change.path,
+                display(old),
+                display(new)
+            ),
+            (None, None) => {}
+        }
+    }
+
+    println!();
+    ColorOutput::info("演练模式: 未写入任何文件，去掉 --dry-run 以执行切换");
+    Ok(())
+}
+
+/// 💰 切换前的预算判定
+///
+/// 预算未启用或动作仅为提示时直接放行，避免无谓地读取成本数据；
+/// `project_dir` 用于匹配项目级预算
+fn check_budget(
+    config_name: &str,
+    target: &ConfigSection,
+    project_dir: Option<&Path>,
+) -> Result<BudgetEnforcement> {
+    let budget_manager = BudgetManager::with_default()?;
+    let config = budget_manager.get_config();
+    if !config.enabled || !config.on_limit_exceeded.is_enforcing() {
+        return Ok(BudgetEnforcement::Allow);
+    }
+
+    transcript_collector::auto_collect();
+    let tracker = CostTracker::new(CostTracker::default_storage_dir()?)?;
+
+    budget_manager.enforce_switch(&tracker, config_name, target.tags.as_deref(), project_dir)
+}
@@ -3,11 +3,17 @@
 //! 提供 Provider 测试相关的 CLI 命令。
 
 use crate::core::ColorOutput;
-use crate::core::error::Result;
-use crate::services::ConfigService;
-use crate::services::health_check::{HealthCheckService, HealthStatus};
+use crate::core::error::{CcrError, Result};
+use crate::services::bench_service::{BenchResult, BenchService, DEFAULT_BENCH_PROMPT};
+use crate::services::health_check::{HealthCheckResult, HealthCheckService, HealthStatus};
+use crate::services::health_monitor::ProbeOutcome;
+use crate::services::{ConfigService, HealthMonitorService};
+use crate::storage::health_store::ProviderHealthStats;
+use chrono::{DateTime, Local, Utc};
 use clap::{Args, Subcommand};
 use comfy_table::{Cell, Color, Table, presets::UTF8_FULL};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Provider 命令参数
 #[derive(Args, Debug, Clone)]
@@ -38,6 +44,50 @@ pub enum ProviderCommand {
         /// Provider 名称
         name: String,
     },
+
+    /// 查看健康历史（p50/p95 延迟、24h/7d 可用率、最近错误）
+    Status {
+        /// Provider 名称（指定时显示该 Provider 的最近样本和状态变化）
+        name: Option<String>,
+
+        /// 以 JSON 格式输出
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 定期探测所有启用的 Provider 并记录健康历史
+    Monitor {
+        /// 探测间隔（秒）
+        #[arg(short, long, default_value_t = 300)]
+        interval: u64,
+
+        /// 只探测一轮后退出（适合 cron）
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// 流式基准测试（首 token 延迟、输出速度、总延迟、错误率）
+    Bench {
+        /// 只测试带有该标签的 Provider
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// 从文件读取测试提示词
+        #[arg(short, long)]
+        prompt: Option<PathBuf>,
+
+        /// 每个 Provider 的请求次数
+        #[arg(short = 'n', long, default_value_t = 3)]
+        runs: usize,
+
+        /// 输出格式: table, json, csv
+        #[arg(short, long, default_value = "table")]
+        format: String,
+
+        /// 导出到文件（需配合 --format json 或 csv）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 /// 执行 provider 命令
@@ -54,6 +104,27 @@ pub async fn execute(args: ProviderArgs) -> Result<()> {
             }
         }
         ProviderCommand::Verify { name } => cmd_verify(&name).await,
+        ProviderCommand::Status { name, json } => match name {
+            Some(n) => cmd_history(&n, json),
+            None => cmd_status(json),
+        },
+        ProviderCommand::Monitor { interval, once } => cmd_monitor(interval, once).await,
+        ProviderCommand::Bench {
+            tag,
+            prompt,
+            runs,
+            format,
+            output,
+        } => {
+            cmd_bench(
+                tag.as_deref(),
+                prompt.as_deref(),
+                runs,
+                &format,
+                output.as_deref(),
+            )
+            .await
+        }
     }
 }
 
@@ -78,16 +149,12 @@ async fn cmd_test(name: &str, verbose: bool) -> Result<()> {
                 ..Default::default()
             };
             let result = service.check(name, &section).await;
+            record_results(std::slice::from_ref(&result));
 
             // 显示结果
             println!();
 
-            let status_color = match result.status {
-                HealthStatus::Healthy => Color::Green,
-                HealthStatus::Degraded => Color::Yellow,
-                HealthStatus::Unhealthy => Color::Red,
-                HealthStatus::Unknown => Color::White,
-            };
+            let status_color = status_color(&result.status);
 
             let mut table = Table::new();
             table.load_preset(UTF8_FULL);
@@ -159,6 +226,7 @@ async fn cmd_test_all(verbose: bool) -> Result<()> {
         Cell::new("错误").fg(Color::Cyan),
     ]);
 
+    let mut results = Vec::new();
     for config in &config_list.configs {
         let section = crate::managers::config::ConfigSection {
             auth_token: config.auth_token.clone(),
@@ -168,19 +236,14 @@ async fn cmd_test_all(verbose: bool) -> Result<()> {
         };
         let result = service.check(&config.name, &section).await;
 
-        let status_color = match result.status {
-            HealthStatus::Healthy => Color::Green,
-            HealthStatus::Degraded => Color::Yellow,
-            HealthStatus::Unhealthy => Color::Red,
-            HealthStatus::Unknown => Color::White,
-        };
+        let status_color = status_color(&result.status);
 
         let latency_str = result
             .latency_ms
             .map(|l| format!("{} ms", l))
             .unwrap_or_else(|| "-".to_string());
 
-        let error_str = result.error.unwrap_or_else(|| "-".to_string());
+        let error_str = result.error.clone().unwrap_or_else(|| "-".to_string());
         let error_short = if error_str.len() > 30 {
             format!("{}...", &error_str[..27])
         } else {
@@ -193,9 +256,11 @@ async fn cmd_test_all(verbose: bool) -> Result<()> {
             Cell::new(latency_str),
             Cell::new(error_short),
         ]);
+        results.push(result);
     }
 
     println!("{}", table);
+    record_results(&results);
 
     if verbose {
         println!();
@@ -249,3 +314,385 @@ async fn cmd_verify(name: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// 查看所有 Provider 的健康统计
+fn cmd_status(json: bool) -> Result<()> {
+    let stats = HealthMonitorService::with_default()?.status()?;
+
+    if json {
+        println!("{}", to_json(&stats)?);
+        return Ok(());
+    }
+
+    if stats.is_empty() {
+        ColorOutput::warning("没有启用的 Provider");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec![
+        Cell::new("名称").fg(Color::Cyan),
+        Cell::new("状态").fg(Color::Cyan),
+        Cell::new("p50").fg(Color::Cyan),
+        Cell::new("p95").fg(Color::Cyan),
+        Cell::new("24h 可用率").fg(Color::Cyan),
+        Cell::new("7d 可用率").fg(Color::Cyan),
+        Cell::new("最近检查").fg(Color::Cyan),
+        Cell::new("最近错误").fg(Color::Cyan),
+    ]);
+
+    for s in &stats {
+        table.add_row(stats_row(s));
+    }
+
+    println!("{}", table);
+
+    if stats.iter().all(|s| s.last_checked_at.is_none()) {
+        println!();
+        ColorOutput::info("尚无健康历史，运行 'ccr provider monitor' 开始记录");
+    }
+
+    Ok(())
+}
+
+/// 查看单个 Provider 的健康历史
+fn cmd_history(name: &str, json: bool) -> Result<()> {
+    let history = HealthMonitorService::with_default()?.history(name, 20)?;
+
+    if json {
+        println!("{}", to_json(&history)?);
+        return Ok(());
+    }
+
+    ColorOutput::info(&format!("Provider 健康历史: {}", name));
+    println!();
+
+    let mut summary = Table::new();
+    summary.load_preset(UTF8_FULL);
+    summary.set_header(vec![
+        Cell::new("名称").fg(Color::Cyan),
+        Cell::new("状态").fg(Color::Cyan),
+        Cell::new("p50").fg(Color::Cyan),
+        Cell::new("p95").fg(Color::Cyan),
+        Cell::new("24h 可用率").fg(Color::Cyan),
+        Cell::new("7d 可用率").fg(Color::Cyan),
+        Cell::new("最近检查").fg(Color::Cyan),
+        Cell::new("最近错误").fg(Color::Cyan),
+    ]);
+    summary.add_row(stats_row(&history.stats));
+    println!("{}", summary);
+
+    if history.transitions.is_empty() {
+        println!();
+        ColorOutput::info("尚无健康历史，运行 'ccr provider monitor' 开始记录");
+        return Ok(());
+    }
+
+    println!();
+    ColorOutput::info("最近的状态变化:");
+    let mut transitions = Table::new();
+    transitions.load_preset(UTF8_FULL);
+    transitions.set_header(vec![
+        Cell::new("时间").fg(Color::Cyan),
+        Cell::new("变化").fg(Color::Cyan),
+        Cell::new("错误").fg(Color::Cyan),
+    ]);
+    for t in &history.transitions {
+        let from = t.from_status.as_ref().map_or("-", |s| s.display());
+        transitions.add_row(vec![
+            Cell::new(format_time(&t.changed_at)),
+            Cell::new(format!("{} → {}", from, t.to_status.display()))
+                .fg(status_color(&t.to_status)),
+            Cell::new(t.error.as_deref().unwrap_or("-")),
+        ]);
+    }
+    println!("{}", transitions);
+
+    Ok(())
+}
+
+/// 定期探测所有启用的 Provider
+async fn cmd_monitor(interval: u64, once: bool) -> Result<()> {
+    if interval == 0 {
+        return Err(CcrError::ValidationError("探测间隔必须大于 0 秒".into()));
+    }
+
+    let service = HealthMonitorService::with_default()?;
+    if once {
+        let outcomes = service.probe_all().await?;
+        service.prune()?;
+        print_round(&outcomes);
+        return Ok(());
+    }
+
+    ColorOutput::info(&format!(
+        "每 {} 秒探测一次所有启用的 Provider，按 Ctrl+C 停止",
+        interval
+    ));
+    service
+        .run(Duration::from_secs(interval), |outcomes| match outcomes {
+            Ok(outcomes) => print_round(outcomes),
+            Err(e) => ColorOutput::warning(&format!("本轮健康检查失败: {}", e)),
+        })
+        .await
+}
+
+/// 流式基准测试
+async fn cmd_bench(
+    tag: Option<&str>,
+    prompt: Option<&Path>,
+    runs: usize,
+    format: &str,
+    output: Option<&Path>,
+) -> Result<()> {
+    if runs == 0 {
+        return Err(CcrError::ValidationError("请求次数必须大于 0".into()));
+    }
+    match (format, output) {
+        ("json" | "csv", _) | ("table", None) => {}
+        ("table", Some(_)) => {
+            return Err(CcrError::ValidationError(
+                "导出到文件请使用 --format json 或 csv".into(),
+            ));
+        }
+        _ => {
+            return Err(CcrError::ValidationError(format!(
+                "不支持的格式: {}",
+                format
+            )));
+        }
+    }
+
+    let prompt = match prompt {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).map_err(|e| {
+                CcrError::FileIoError(format!("读取提示词文件 {} 失败: {}", path.display(), e))
+            })?;
+            if content.trim().is_empty() {
+                return Err(CcrError::ValidationError(format!(
+                    "提示词文件 {} 为空",
+                    path.display()
+                )));
+            }
+            content.trim().to_string()
+        }
+        None => DEFAULT_BENCH_PROMPT.to_string(),
+    };
+
+    let targets = HealthMonitorService::tagged_targets(tag)?;
+    if format == "table" {
+        ColorOutput::info(&format!(
+            "对 {} 个 Provider 各发送 {} 次流式请求...",
+            targets.len(),
+            runs
+        ));
+    }
+    let results = BenchService::new(prompt, runs).bench_all(targets).await?;
+
+    let content = match format {
+        "json" => to_json(&results)?,
+        "csv" => bench_to_csv(&results),
+        _ => {
+            print_bench_table(&results);
+            return Ok(());
+        }
+    };
+
+    if let Some(path) = output {
+        std::fs::write(path, content)?;
+        ColorOutput::success(&format!("已导出到: {}", path.display()));
+    } else {
+        println!("{}", content);
+    }
+    Ok(())
+}
+
+/// 输出基准测试排名表
+fn print_bench_table(results: &[BenchResult]) {
+    println!();
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec![
+        Cell::new("排名").fg(Color::Cyan),
+        Cell::new("名称").fg(Color::Cyan),
+        Cell::new("模型").fg(Color::Cyan),
+        Cell::new("首 token").fg(Color::Cyan),
+        Cell::new("输出速度").fg(Color::Cyan),
+        Cell::new("总延迟").fg(Color::Cyan),
+        Cell::new("错误率").fg(Color::Cyan),
+        Cell::new("最近错误").fg(Color::Cyan),
+    ]);
+
+    let ms = |v: Option<u64>| v.map_or_else(|| "-".to_string(), |v| format!("{} ms", v));
+    for r in results {
+        table.add_row(vec![
+            Cell::new(r.rank),
+            Cell::new(&r.profile),
+            Cell::new(&r.model),
+            Cell::new(ms(r.ttft_ms)),
+            Cell::new(
+                r.tokens_per_sec
+                    .map_or_else(|| "-".to_string(), |v| format!("{:.1} tok/s", v)),
+            ),
+            Cell::new(ms(r.total_ms)),
+            Cell::new(format!("{:.1}% ({}/{})", r.error_rate, r.errors, r.runs))
+                .fg(uptime_color(Some(100.0 - r.error_rate))),
+            Cell::new(
+                r.last_error
+                    .as_deref()
+                    .map_or_else(|| "-".to_string(), |e| truncate(e, 30)),
+            ),
+        ]);
+    }
+
+    println!("{}", table);
+}
+
+/// 导出基准测试结果为 CSV 格式（每个 Provider 一行）
+fn bench_to_csv(results: &[BenchResult]) -> String {
+    let opt = |v: Option<String>| v.unwrap_or_default();
+    let mut csv = String::from(
+        "rank,profile,model,base_url,runs,errors,error_rate,ttft_ms,tokens_per_sec,total_ms,output_tokens,last_error\n",
+    );
+    for r in results {
+        let row = [
+            r.rank.to_string(),
+            csv_field(&r.profile),
+            csv_field(&r.model),
+            csv_field(&r.base_url),
+            r.runs.to_string(),
+            r.errors.to_string(),
+            format!("{:.1}", r.error_rate),
+            opt(r.ttft_ms.map(|v| v.to_string())),
+            opt(r.tokens_per_sec.map(|v| format!("{:.1}", v))),
+            opt(r.total_ms.map(|v| v.to_string())),
+            opt(r.output_tokens.map(|v| v.to_string())),
+            opt(r.last_error.as_deref().map(csv_field)),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 输出一轮探测结果（状态变化高亮）
+fn print_round(outcomes: &[ProbeOutcome]) {
+    println!();
+    ColorOutput::step(&format!("{}", Local::now().format("%Y-%m-%d %H:%M:%S")));
+    for outcome in outcomes {
+        let result = &outcome.result;
+        let latency = result
+            .latency_ms
+            .map(|l| format!("{} ms", l))
+            .unwrap_or_else(|| "-".to_string());
+        let line = format!(
+            "{} {} ({}){}",
+            result.provider_name,
+            result.status.display(),
+            latency,
+            result
+                .error
+                .as_ref()
+                .map(|e| format!(": {}", e))
+                .unwrap_or_default()
+        );
+        match &outcome.transition {
+            Some(t) if t.from_status.is_some() => ColorOutput::warning(&format!(
+                "{} [状态变化: {} → {}]",
+                line,
+                t.from_status.as_ref().map_or("-", |s| s.display()),
+                t.to_status.display()
+            )),
+            _ => println!("  {}", line),
+        }
+    }
+}
+
+/// 将单次检查结果写入健康历史（失败时仅记录日志）
+fn record_results(results: &[HealthCheckResult]) {
+    let service = match HealthMonitorService::with_default() {
+        Ok(service) => service,
+        Err(e) => {
+            tracing::warn!("无法打开健康历史数据库: {}", e);
+            return;
+        }
+    };
+    for result in results {
+        if let Err(e) = service.record(result) {
+            tracing::warn!("记录 {} 的健康检查结果失败: {}", result.provider_name, e);
+        }
+    }
+}
+
+fn stats_row(s: &ProviderHealthStats) -> Vec<Cell> {
+    let ms = |v: Option<u64>| v.map_or_else(|| "-".to_string(), |v| format!("{} ms", v));
+    let pct = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{:.1}%", v));
+    let status = s.last_status.clone().unwrap_or(HealthStatus::Unknown);
+
+    vec![
+        Cell::new(&s.profile),
+        Cell::new(status.display()).fg(status_color(&status)),
+        Cell::new(ms(s.p50_ms)),
+        Cell::new(ms(s.p95_ms)),
+        Cell::new(pct(s.uptime_24h)).fg(uptime_color(s.uptime_24h)),
+        Cell::new(pct(s.uptime_7d)).fg(uptime_color(s.uptime_7d)),
+        Cell::new(
+            s.last_checked_at
+                .as_ref()
+                .map_or_else(|| "-".to_string(), format_time),
+        ),
+        Cell::new(match (&s.last_error, &s.last_error_at) {
+            (Some(e), Some(at)) => format!("{} ({})", truncate(e, 30), format_time(at)),
+            _ => "-".to_string(),
+        }),
+    ]
+}
+
+fn status_color(status: &HealthStatus) -> Color {
+    match status {
+        HealthStatus::Healthy => Color::Green,
+        HealthStatus::Degraded => Color::Yellow,
+        HealthStatus::Unhealthy => Color::Red,
+        HealthStatus::Unknown => Color::White,
+    }
+}
+
+fn uptime_color(uptime: Option<f64>) -> Color {
+    match uptime {
+        Some(u) if u >= 99.0 => Color::Green,
+        Some(u) if u >= 90.0 => Color::Yellow,
+        Some(_) => Color::Red,
+        None => Color::White,
+    }
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&Local)
+        .format("%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!(
+            "{}...",
+            text.chars().take(max_chars - 3).collect::<String>()
+        )
+    } else {
+        text.to_string()
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
@@ -0,0 +1,236 @@
+//! 🔀 Proxy 命令
+//!
+//! 启动本地故障转移代理,并查看运行中代理的上游状态。
+
+use crate::core::ColorOutput;
+use crate::core::error::{CcrError, Result};
+use crate::managers::config::ConfigManager;
+use crate::managers::settings::SettingsManager;
+use crate::proxy::{
+    ProxyConfig, ProxyServer, SettingsTakeover, UpstreamSnapshot, resolve_upstreams,
+};
+use crate::services::health_check::HealthStatus;
+use clap::{Args, Subcommand};
+use comfy_table::{Cell, Color, Table, presets::UTF8_FULL};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Proxy 命令参数
+#[derive(Args, Debug, Clone)]
+pub struct ProxyArgs {
+    #[command(subcommand)]
+    pub command: ProxyCommand,
+}
+
+/// Proxy 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProxyCommand {
+    /// 启动本地故障转移代理(前台运行,Ctrl+C 停止)
+    ///
+    /// 示例: ccr proxy start
+    ///       ccr proxy start -p 18080 -u main,backup
+    Start {
+        /// 监听端口(默认读取 [settings.proxy].port,否则 19530)
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// 监听地址(默认 127.0.0.1)
+        #[arg(long)]
+        host: Option<String>,
+
+        /// 上游 profile 列表,按故障转移顺序以逗号分隔
+        #[arg(short, long, value_delimiter = ',')]
+        upstreams: Vec<String>,
+
+        /// 不修改 settings.json(需手动将 ANTHROPIC_BASE_URL 指向代理)
+        #[arg(long)]
+        no_apply: bool,
+    },
+
+    /// 查看运行中代理的上游状态
+    Status {
+        /// 代理端口(默认读取 [settings.proxy].port)
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// 以 JSON 格式输出
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// 执行 proxy 命令
+pub async fn execute(args: ProxyArgs) -> Result<()> {
+    match args.command {
+        ProxyCommand::Start {
+            port,
+            host,
+            upstreams,
+            no_apply,
+        } => cmd_start(port, host, upstreams, no_apply).await,
+        ProxyCommand::Status { port, json } => cmd_status(port, json).await,
+    }
+}
+
+/// 📖 读取 [settings.proxy] 配置
+fn load_proxy_config(config_manager: &ConfigManager) -> Result<ProxyConfig> {
+    let config = config_manager.load_with_autofix()?;
+    Ok(config.settings.proxy.unwrap_or_default())
+}
+
+/// 🚀 启动代理
+async fn cmd_start(
+    port: Option<u16>,
+    host: Option<String>,
+    upstreams: Vec<String>,
+    no_apply: bool,
+) -> Result<()> {
+    ColorOutput::title("本地故障转移代理");
+    println!();
+
+    let config_manager = ConfigManager::with_default()?;
+    let ccs_config = config_manager.load_with_autofix()?;
+
+    let mut proxy_config = ccs_config.settings.proxy.clone().unwrap_or_default();
+    if let Some(port) = port {
+        proxy_config.port = port;
+    }
+    if let Some(host) = host {
+        proxy_config.host = host;
+    }
+    if !upstreams.is_empty() {
+        proxy_config.upstreams = upstreams;
+    }
+
+    let resolved = resolve_upstreams(&ccs_config, &proxy_config.upstreams)?;
+    let primary = resolved[0].section.clone();
+
+    let addr = format!("{}:{}", proxy_config.host, proxy_config.port);
+    let listener = TcpListener::bind(&addr)
+        .await
+        .map_err(|e| CcrError::NetworkError(format!("代理无法监听 {}: {}", addr, e)))?;
+
+    let server = ProxyServer::new(proxy_config.clone(), resolved)?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        Cell::new("顺序").fg(Color::Cyan),
+        Cell::new("上游").fg(Color::Cyan),
+        Cell::new("Base URL").fg(Color::Cyan),
+    ]);
+    for (index, upstream) in server.pool().upstreams().iter().enumerate() {
+        table.add_row(vec![
+            Cell::new(index + 1),
+            Cell::new(&upstream.name).fg(Color::Green),
+            Cell::new(upstream.base_url()),
+        ]);
+    }
+    println!("{}", table);
+    println!();
+
+    let takeover = if no_apply {
+        ColorOutput::info(&format!(
+            "未修改 settings.json,请手动设置 ANTHROPIC_BASE_URL={}",
+            proxy_config.base_url()
+        ));
+        None
+    } else {
+        let takeover = SettingsTakeover::apply(
+            SettingsManager::with_default()?,
+            &proxy_config.base_url(),
+            &primary,
+        )?;
+        ColorOutput::success(&format!(
+            "Claude Code 已指向代理: {}",
+            proxy_config.base_url()
+        ));
+        Some(takeover)
+    };
+
+    let health_monitor = server.spawn_health_monitor();
+
+    ColorOutput::success(&format!("🔀 代理已启动: http://{}", addr));
+    ColorOutput::info("⏹️ 按 Ctrl+C 停止代理并恢复设置");
+    println!();
+
+    let shutdown = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("监听 Ctrl+C 失败: {}", e);
+            std::future::pending::<()>().await;
+        }
+        ColorOutput::warning("⚠️ 收到 Ctrl+C,正在停止代理...");
+    };
+
+    let serve_result = server.serve(listener, shutdown).await;
+    health_monitor.abort();
+
+    if let Some(takeover) = takeover {
+        takeover.restore()?;
+        ColorOutput::success("已恢复代理启动前的 Claude Code 设置");
+    }
+
+    serve_result
+}
+
+/// 📊 查看代理状态
+async fn cmd_status(port: Option<u16>, json: bool) -> Result<()> {
+    let mut proxy_config = load_proxy_config(&ConfigManager::with_default()?)?;
+    if let Some(port) = port {
+        proxy_config.port = port;
+    }
+
+    let url = format!("{}/ccr/proxy/status", proxy_config.base_url());
+    let response = crate::core::http::HTTP_CLIENT
+        .get(&url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| {
+            CcrError::NetworkError(format!(
+                "无法连接代理 {}: {} (代理是否已通过 'ccr proxy start' 启动?)",
+                proxy_config.base_url(),
+                e
+            ))
+        })?;
+
+    let snapshots: Vec<UpstreamSnapshot> = response
+        .json()
+        .await
+        .map_err(|e| CcrError::NetworkError(format!("解析代理状态失败: {}", e)))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&snapshots)?);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        Cell::new("上游").fg(Color::Cyan),
+        Cell::new("健康").fg(Color::Cyan),
+        Cell::new("冷却").fg(Color::Cyan),
+        Cell::new("请求/失败").fg(Color::Cyan),
+        Cell::new("最近错误").fg(Color::Cyan),
+    ]);
+
+    for snapshot in &snapshots {
+        let health_color = match snapshot.health {
+            HealthStatus::Healthy => Color::Green,
+            HealthStatus::Degraded => Color::Yellow,
+            HealthStatus::Unhealthy => Color::Red,
+            HealthStatus::Unknown => Color::White,
+        };
+        table.add_row(vec![
+            Cell::new(&snapshot.name),
+            Cell::new(snapshot.health.display()).fg(health_color),
+            Cell::new(if snapshot.cooling_down { "🧊" } else { "-" }),
+            Cell::new(format!(
+                "{}/{}",
+                snapshot.total_requests, snapshot.total_failures
+            )),
+            Cell::new(snapshot.last_error.as_deref().unwrap_or("-")),
+        ]);
+    }
+
+    println!("{}", table);
+    Ok(())
+}
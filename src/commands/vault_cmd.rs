@@ -0,0 +1,316 @@
+//! 🔐 Vault 命令
+//!
+//! 管理加密保险库中的 token，并将配置中的明文 token 迁移为 `vault:<name>` 引用。
+
+use crate::core::ColorOutput;
+use crate::core::error::{CcrError, Result};
+use crate::managers::vault::{self, MigratedToken, VaultManager};
+use crate::platforms::base;
+use crate::services::SnapshotService;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use comfy_table::{Cell, Color, Table, presets::UTF8_FULL};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Vault 命令参数
+#[derive(Args, Debug, Clone)]
+pub struct VaultArgs {
+    #[command(subcommand)]
+    pub command: VaultCommand,
+}
+
+/// Vault 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum VaultCommand {
+    /// 保存或更新一个 token
+    ///
+    /// 示例: ccr vault set anyrouter-main
+    ///       然后在 profile 中使用 auth_token = "vault:anyrouter-main"
+    Set {
+        /// 密钥名称（字母、数字、'-'、'_'、'.'）
+        name: String,
+
+        /// token 内容（省略时隐藏输入，避免留在 shell 历史中）
+        #[arg(long)]
+        value: Option<String>,
+    },
+
+    /// 列出保险库中的密钥及引用它们的 profile
+    List,
+
+    /// 删除一个密钥
+    Remove {
+        /// 密钥名称
+        name: String,
+    },
+
+    /// 将配置中的明文 token 迁移到保险库，并清理旧备份中的明文
+    ///
+    /// 示例: ccr vault migrate --dry-run
+    Migrate {
+        /// 仅预览将要迁移和清理的内容
+        #[arg(long)]
+        dry_run: bool,
+
+        /// 不清理备份文件
+        #[arg(long)]
+        no_scrub: bool,
+    },
+
+    /// 将保险库口令保存到系统钥匙串（Secret Service / macOS Keychain）
+    Remember,
+
+    /// 更换保险库口令
+    Passwd,
+}
+
+/// 执行 vault 命令
+pub async fn execute(args: VaultArgs) -> Result<()> {
+    match args.command {
+        VaultCommand::Set { name, value } => cmd_set(&name, value),
+        VaultCommand::List => cmd_list(),
+        VaultCommand::Remove { name } => cmd_remove(&name),
+        VaultCommand::Migrate { dry_run, no_scrub } => cmd_migrate(dry_run, no_scrub),
+        VaultCommand::Remember => cmd_remember(),
+        VaultCommand::Passwd => cmd_passwd(),
+    }
+}
+
+/// ✏️ 保存 token
+fn cmd_set(name: &str, value: Option<String>) -> Result<()> {
+    let mut vault = VaultManager::open_default()?;
+    let secret = match value {
+        Some(value) => value,
+        None => dialoguer::Password::new()
+            .with_prompt(format!("{} 的 token", name))
+            .interact()
+            .map_err(|e| CcrError::VaultError(format!("读取 token 失败: {}", e)))?,
+    };
+
+    let existed = vault.get(name).is_some();
+    vault.insert(name, &secret)?;
+    vault.save()?;
+
+    ColorOutput::success(&format!(
+        "已{}密钥 '{}'",
+        if existed { "更新" } else { "保存" },
+        name
+    ));
+    ColorOutput::info(&format!(
+        "在 profile 中使用: auth_token = \"{}\"",
+        vault::vault_ref(name).cyan()
+    ));
+    Ok(())
+}
+
+/// 📋 列出密钥
+fn cmd_list() -> Result<()> {
+    let vault = VaultManager::open_default()?;
+    ColorOutput::title("🔐 保险库");
+    ColorOutput::key_value("文件", &vault.vault_path().display().to_string(), 2);
+    println!();
+
+    if vault.names().next().is_none() {
+        ColorOutput::info("保险库为空，运行 'ccr vault set <名称>' 或 'ccr vault migrate' 添加");
+        return Ok(());
+    }
+
+    let references = collect_references(&ccr_root(&vault), vault::legacy_config_path().as_deref());
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        Cell::new("名称").fg(Color::Cyan),
+        Cell::new("Token").fg(Color::Cyan),
+        Cell::new("引用的 Profile").fg(Color::Cyan),
+    ]);
+    for name in vault.names() {
+        let users = references.get(name).map(|u| u.join(", "));
+        table.add_row(vec![
+            Cell::new(name).fg(Color::Green),
+            Cell::new(ColorOutput::mask_sensitive(
+                vault.get(name).unwrap_or_default(),
+            ))
+            .fg(Color::DarkGrey),
+            match users {
+                Some(users) => Cell::new(users),
+                None => Cell::new("(未使用)").fg(Color::DarkGrey),
+            },
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+/// 🗑️ 删除密钥
+fn cmd_remove(name: &str) -> Result<()> {
+    let mut vault = VaultManager::open_default()?;
+    if !vault.remove(name) {
+        return Err(CcrError::VaultError(format!(
+            "保险库中不存在密钥 '{}'",
+            name
+        )));
+    }
+
+    let references = collect_references(&ccr_root(&vault), vault::legacy_config_path().as_deref());
+    vault.save()?;
+
+    ColorOutput::success(&format!("已删除密钥 '{}'", name));
+    if let Some(users) = references.get(name) {
+        ColorOutput::warning(&format!(
+            "以下 profile 仍引用该密钥，切换时将失败: {}",
+            users.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// 🚚 迁移明文 token
+fn cmd_migrate(dry_run: bool, no_scrub: bool) -> Result<()> {
+    ColorOutput::title(if dry_run {
+        "🚚 迁移明文 token 到保险库 (预览)"
+    } else {
+        "🚚 迁移明文 token 到保险库"
+    });
+    println!();
+
+    let mut vault = VaultManager::open_default()?;
+    let root = ccr_root(&vault);
+    let legacy = vault::legacy_config_path();
+
+    // 步骤 1: 配置文件中的明文 token → 保险库引用
+    ColorOutput::step("步骤 1/2: 迁移配置文件");
+    let mut migrated: Vec<MigratedToken> = Vec::new();
+    for (prefix, path) in vault::migration_sources(&root, legacy.as_deref()) {
+        migrated.extend(vault.migrate_profiles_file(&path, &prefix, dry_run)?);
+    }
+
+    if migrated.is_empty() {
+        ColorOutput::info("未发现明文 token");
+    } else {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("文件").fg(Color::Cyan),
+            Cell::new("Profile").fg(Color::Cyan),
+            Cell::new("引用").fg(Color::Cyan),
+        ]);
+        for item in &migrated {
+            table.add_row(vec![
+                Cell::new(display_path(&item.file, &root)),
+                Cell::new(&item.profile).fg(Color::Green),
+                Cell::new(vault::vault_ref(&item.vault_name)).fg(Color::Yellow),
+            ]);
+        }
+        println!("{}", table);
+    }
+    println!();
+
+    // 步骤 2: 备份中的明文 → 引用
+    ColorOutput::step("步骤 2/2: 清理配置备份");
+    let mut scrubbed = 0;
+    if no_scrub {
+        ColorOutput::info("已跳过 (--no-scrub)");
+    } else {
+        for path in vault::backup_files(&root, legacy.as_deref()) {
+            if vault.scrub_file(&path, dry_run)? {
+                println!("  🧹 {}", display_path(&path, &root));
+                scrubbed += 1;
+            }
+        }
+        // 快照存储中的 TOML 文件（settings.json 需要保留明文，不改写）
+        let snapshot_files =
+            SnapshotService::with_default()?
+                .store()
+                .rewrite(dry_run, |path, content| {
+                    if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+                        return None;
+                    }
+                    let text = std::str::from_utf8(content).ok()?;
+                    vault.scrub_text(text).map(String::into_bytes)
+                })?;
+        if snapshot_files > 0 {
+            println!("  🧹 配置快照中的 {} 个文件", snapshot_files);
+            scrubbed += snapshot_files;
+        }
+        if scrubbed == 0 {
+            ColorOutput::info("备份中未发现明文 token");
+        }
+    }
+    println!();
+
+    if dry_run {
+        ColorOutput::info(&format!(
+            "预览完成: 将迁移 {} 个 token，清理 {} 个备份文件（未做任何修改）",
+            migrated.len(),
+            scrubbed
+        ));
+    } else {
+        ColorOutput::success(&format!(
+            "迁移完成: 已迁移 {} 个 token，清理 {} 个备份文件",
+            migrated.len(),
+            scrubbed
+        ));
+        ColorOutput::info(
+            "settings.json 及其备份仍包含当前生效的 token（Claude Code 需要读取明文）",
+        );
+    }
+    Ok(())
+}
+
+/// 🔑 保存口令到系统钥匙串
+fn cmd_remember() -> Result<()> {
+    let vault = VaultManager::open_default()?;
+    vault.remember_passphrase()?;
+    ColorOutput::success("保险库口令已保存到系统钥匙串，之后切换配置无需再输入口令");
+    Ok(())
+}
+
+/// 🔑 更换口令
+fn cmd_passwd() -> Result<()> {
+    let mut vault = VaultManager::open_default()?;
+    let new_passphrase = dialoguer::Password::new()
+        .with_prompt("新的保险库口令")
+        .with_confirmation("再次输入确认", "两次输入的口令不一致")
+        .interact()
+        .map_err(|e| CcrError::VaultError(format!("读取保险库口令失败: {}", e)))?;
+    vault.change_passphrase(&new_passphrase)?;
+
+    ColorOutput::success("保险库口令已更换");
+    ColorOutput::info("如曾运行 'ccr vault remember'，请重新运行以更新钥匙串中的口令");
+    Ok(())
+}
+
+/// 📁 CCR 根目录（保险库文件所在目录）
+fn ccr_root(vault: &VaultManager) -> PathBuf {
+    vault
+        .vault_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// 🔍 统计各密钥被哪些 profile 引用: 名称 → ["平台/profile"]
+fn collect_references(root: &Path, legacy: Option<&Path>) -> BTreeMap<String, Vec<String>> {
+    let mut references: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (prefix, path) in vault::migration_sources(root, legacy) {
+        let Ok(profiles) = base::load_profiles_from_toml(&path) else {
+            continue;
+        };
+        for (profile, config) in profiles {
+            if let Some(name) = config.auth_token.as_deref().and_then(vault::vault_ref_name) {
+                references
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(format!("{}/{}", prefix, profile));
+            }
+        }
+    }
+    references
+}
+
+/// 📍 相对 CCR 根目录显示路径
+fn display_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| path.display().to_string())
+}
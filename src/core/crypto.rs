@@ -0,0 +1,151 @@
+// 🔐 CCR 口令加密
+// 📁 同步加密与密钥保险库共用的 Argon2id + AES-256-GCM 实现，各自使用不同的文件头区分格式
+//
+// 密文格式: 文件头(8) || salt(16) || nonce(12) || AES-256-GCM 密文
+// 密钥由 Argon2id(口令, salt) 派生；同一个 PassphraseCipher 加密的内容共用 salt，只需派生一次
+
+use crate::core::error::{CcrError, Result};
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit, OsRng, rand_core::RngCore},
+};
+use argon2::Argon2;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 文件头长度
+pub const MAGIC_SIZE: usize = 8;
+/// Salt 长度
+const SALT_SIZE: usize = 16;
+/// Nonce 长度 (96 bits = 12 bytes for GCM)
+const NONCE_SIZE: usize = 12;
+/// 密钥长度 (256 bits = 32 bytes)
+const KEY_SIZE: usize = 32;
+/// 加密头总长度
+const HEADER_SIZE: usize = MAGIC_SIZE + SALT_SIZE + NONCE_SIZE;
+
+/// 🔍 判断内容是否为使用指定文件头的加密格式
+pub fn is_sealed(magic: &[u8; MAGIC_SIZE], data: &[u8]) -> bool {
+    data.len() >= HEADER_SIZE && data.starts_with(magic)
+}
+
+/// 🔐 基于口令的加密器
+pub struct PassphraseCipher {
+    magic: &'static [u8; MAGIC_SIZE],
+    passphrase: String,
+    /// 加密使用的 salt
+    salt: [u8; SALT_SIZE],
+    /// 按 salt 缓存的派生密钥（解密不同 salt 的内容时复用）
+    keys: Mutex<HashMap<[u8; SALT_SIZE], Key<Aes256Gcm>>>,
+    /// 格式与内部错误的错误类型（口令错误统一为 InvalidPassphrase）
+    error: fn(String) -> CcrError,
+}
+
+impl PassphraseCipher {
+    /// 🏗️ 创建加密器（随机生成 salt）
+    pub fn new(
+        magic: &'static [u8; MAGIC_SIZE],
+        passphrase: &str,
+        error: fn(String) -> CcrError,
+    ) -> Self {
+        let mut salt = [0u8; SALT_SIZE];
+        OsRng.fill_bytes(&mut salt);
+
+        Self {
+            magic,
+            passphrase: passphrase.to_string(),
+            salt,
+            keys: Mutex::new(HashMap::new()),
+            error,
+        }
+    }
+
+    /// 🔍 判断内容是否为本加密器的格式
+    pub fn is_sealed(&self, data: &[u8]) -> bool {
+        is_sealed(self.magic, data)
+    }
+
+    /// 🔑 派生（或从缓存获取）指定 salt 对应的密钥
+    fn key_for(&self, salt: &[u8; SALT_SIZE]) -> Result<Key<Aes256Gcm>> {
+        let mut keys = self
+            .keys
+            .lock()
+            .map_err(|_| (self.error)("加密密钥缓存不可用".into()))?;
+
+        if let Some(key) = keys.get(salt) {
+            return Ok(*key);
+        }
+
+        let mut key_bytes = [0u8; KEY_SIZE];
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), salt, &mut key_bytes)
+            .map_err(|e| (self.error)(format!("派生加密密钥失败: {}", e)))?;
+        let key = *Key::<Aes256Gcm>::from_slice(&key_bytes);
+        keys.insert(*salt, key);
+        Ok(key)
+    }
+
+    /// 🔒 加密内容
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let cipher = Aes256Gcm::new(&self.key_for(&self.salt)?);
+
+        let mut nonce_bytes = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce_bytes);
+
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+            .map_err(|e| (self.error)(format!("加密失败: {}", e)))?;
+
+        let mut out = Vec::with_capacity(HEADER_SIZE + ciphertext.len());
+        out.extend_from_slice(self.magic);
+        out.extend_from_slice(&self.salt);
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// 🔓 解密内容
+    ///
+    /// 口令错误或内容被篡改时返回 `CcrError::InvalidPassphrase`
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !self.is_sealed(data) {
+            return Err((self.error)("内容不是有效的加密格式".into()));
+        }
+
+        let (salt, rest) = data[MAGIC_SIZE..].split_at(SALT_SIZE);
+        let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+        let mut salt_bytes = [0u8; SALT_SIZE];
+        salt_bytes.copy_from_slice(salt);
+
+        let cipher = Aes256Gcm::new(&self.key_for(&salt_bytes)?);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| CcrError::InvalidPassphrase("口令错误或内容已损坏".into()))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const MAGIC_A: &[u8; MAGIC_SIZE] = b"CCRTESTA";
+    const MAGIC_B: &[u8; MAGIC_SIZE] = b"CCRTESTB";
+
+    #[test]
+    fn test_formats_are_separated_by_magic() {
+        let a = PassphraseCipher::new(MAGIC_A, "pass", CcrError::SyncError);
+        let b = PassphraseCipher::new(MAGIC_B, "pass", CcrError::VaultError);
+
+        let sealed = a.encrypt(b"data").unwrap();
+        assert!(is_sealed(MAGIC_A, &sealed));
+        assert!(!b.is_sealed(&sealed));
+        assert_eq!(a.decrypt(&sealed).unwrap(), b"data");
+
+        // 其他格式的内容由对应的错误类型报告，而不是口令错误
+        assert!(matches!(
+            b.decrypt(&sealed).unwrap_err(),
+            CcrError::VaultError(_)
+        ));
+    }
+}
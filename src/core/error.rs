@@ -64,9 +64,21 @@ pub mod exit_codes {
     /// ✅ 验证错误
     pub const VALIDATION_ERROR: i32 = 90;
 
+    /// 💰 超出预算被拒绝
+    pub const BUDGET_EXCEEDED: i32 = 91;
+
     /// ☁️ 同步错误
     pub const SYNC_ERROR: i32 = 70;
 
+    /// 🔐 同步口令错误
+    pub const SYNC_PASSPHRASE_INVALID: i32 = 76;
+
+    /// 🔐 密钥保险库错误
+    pub const VAULT_ERROR: i32 = 77;
+
+    /// 🔑 动态密钥来源解析错误
+    pub const SECRET_RESOLUTION_ERROR: i32 = 78;
+
     /// 🏷️ 平台未找到
     pub const PLATFORM_NOT_FOUND: i32 = 60;
 
@@ -96,6 +108,12 @@ pub mod exit_codes {
 
     /// 🧰 外部命令错误
     pub const EXTERNAL_COMMAND_ERROR: i32 = 75;
+
+    /// ↩️ 撤销冲突
+    pub const REVERT_CONFLICT: i32 = 79;
+
+    /// 🗄️ 备份数据损坏
+    pub const BACKUP_CORRUPTED: i32 = 81;
 }
 
 /// ❌ CCR 错误类型枚举
@@ -159,11 +177,27 @@ pub enum CcrError {
     #[error("验证失败: {0}")]
     ValidationError(String),
 
+    /// 💰 超出预算,操作被拒绝
+    #[error("超出预算: {0}")]
+    BudgetExceeded(String),
+
     /// ☁️ 同步错误
     #[allow(dead_code)]
     #[error("同步错误: {0}")]
     SyncError(String),
 
+    /// 🔐 同步口令错误（无法解密远程内容）
+    #[error("同步口令错误: {0}")]
+    InvalidPassphrase(String),
+
+    /// 🔐 密钥保险库错误（解锁失败、引用的密钥不存在等）
+    #[error("保险库错误: {0}")]
+    VaultError(String),
+
+    /// 🔑 动态密钥来源解析失败（环境变量未设置、文件不可读、凭证命令失败等）
+    #[error("密钥解析失败: {0}")]
+    SecretResolutionError(String),
+
     /// 🏷️ 平台未找到
     #[error("平台 '{0}' 未找到")]
     PlatformNotFound(String),
@@ -203,6 +237,14 @@ pub enum CcrError {
     /// 🧰 外部命令错误
     #[error("外部命令错误: {0}")]
     ExternalCommandError(String),
+
+    /// ↩️ 撤销冲突（之后的变更与要撤销的操作冲突）
+    #[error("无法撤销: {0}")]
+    RevertConflict(String),
+
+    /// 🗄️ 备份快照数据缺失或摘要不匹配
+    #[error("备份数据损坏: {0}")]
+    BackupCorrupted(String),
 }
 
 impl CcrError {
@@ -227,7 +269,11 @@ impl CcrError {
             CcrError::FileIoError(_) => exit_codes::FILE_IO_ERROR,
             CcrError::HistoryError(_) => exit_codes::HISTORY_ERROR,
             CcrError::ValidationError(_) => exit_codes::VALIDATION_ERROR,
+            CcrError::BudgetExceeded(_) => exit_codes::BUDGET_EXCEEDED,
             CcrError::SyncError(_) => exit_codes::SYNC_ERROR,
+            CcrError::InvalidPassphrase(_) => exit_codes::SYNC_PASSPHRASE_INVALID,
+            CcrError::VaultError(_) => exit_codes::VAULT_ERROR,
+            CcrError::SecretResolutionError(_) => exit_codes::SECRET_RESOLUTION_ERROR,
             CcrError::PlatformNotFound(_) => exit_codes::PLATFORM_NOT_FOUND,
             CcrError::PlatformNotSupported(_) => exit_codes::PLATFORM_NOT_SUPPORTED,
             CcrError::ProfileNotFound(_) => exit_codes::PROFILE_NOT_FOUND,
@@ -238,6 +284,8 @@ impl CcrError {
             CcrError::UpdateError(_) => exit_codes::UPDATE_ERROR,
             CcrError::UiError(_) => exit_codes::UI_ERROR,
             CcrError::ExternalCommandError(_) => exit_codes::EXTERNAL_COMMAND_ERROR,
+            CcrError::RevertConflict(_) => exit_codes::REVERT_CONFLICT,
+            CcrError::BackupCorrupted(_) => exit_codes::BACKUP_CORRUPTED,
         }
     }
 
@@ -296,6 +344,45 @@ impl CcrError {
                     msg
                 )
             }
+            CcrError::BudgetExceeded(msg) => {
+                format!(
+                    "超出预算: {}\n\
+                    建议:\n\
+                    • 运行 'ccr budget status' 查看预算使用情况\n\
+                    • 切换到免费 profile,或使用 'ccr budget set' 调整限制",
+                    msg
+                )
+            }
+            CcrError::InvalidPassphrase(msg) => {
+                format!(
+                    "同步口令错误: {}\n\
+                    建议:\n\
+                    • 确认输入的口令与上传时使用的一致\n\
+                    • 非交互场景请检查环境变量 CCR_SYNC_PASSPHRASE\n\
+                    • 口令已轮换时请使用新的口令 ('ccr sync rekey')",
+                    msg
+                )
+            }
+            CcrError::VaultError(msg) => {
+                format!(
+                    "保险库错误: {}\n\
+                    建议:\n\
+                    • 运行 'ccr vault list' 查看已保存的密钥\n\
+                    • 非交互场景请设置环境变量 CCR_VAULT_PASSPHRASE\n\
+                    • 运行 'ccr vault remember' 将口令保存到系统钥匙串",
+                    msg
+                )
+            }
+            CcrError::SecretResolutionError(msg) => {
+                format!(
+                    "密钥解析失败: {}\n\
+                    建议:\n\
+                    • ${{env:VAR}} 需要在当前 shell 中导出对应的环境变量\n\
+                    • ${{file:~/path}} 指向的文件需存在且非空\n\
+                    • 手动运行 auth_token_cmd 中的命令确认能输出 token",
+                    msg
+                )
+            }
             CcrError::PlatformNotFound(name) => {
                 format!(
                     "平台 '{}' 未找到或未实现\n\n\
@@ -303,10 +390,12 @@ impl CcrError {
                     • claude  - Claude Code (✅ 已实现)\n\
                     • codex   - GitHub Copilot CLI (✅ 已实现)\n\
                     • gemini  - Google Gemini CLI (✅ 已实现)\n\
-                    • qwen    - Alibaba Qwen CLI (🚧 计划中)\n\
-                    • iflow   - iFlow CLI (🚧 计划中)\n\n\
+                    • qwen    - Alibaba Qwen CLI (✅ 已实现)\n\
+                    • iflow   - iFlow CLI (✅ 已实现)\n\
+                    • droid   - Factory Droid CLI (✅ 已实现)\n\n\
                     建议:\n\
                     • 运行 'ccr platform list' 查看所有平台\n\
+                    • 自定义平台需在 ~/.ccr/platforms/<平台名>/platform.toml 中声明\n\
                     • 运行 'ccr platform init <平台名>' 初始化平台\n\
                     • 查看文档: https://github.com/bahayonghang/ccr/blob/main/docs/platforms/",
                     name
@@ -318,10 +407,10 @@ impl CcrError {
                     当前已实现的平台:\n\
                     • claude  - Claude Code\n\
                     • codex   - GitHub Copilot CLI\n\
-                    • gemini  - Google Gemini CLI\n\n\
-                    计划中的平台:\n\
+                    • gemini  - Google Gemini CLI\n\
                     • qwen    - Alibaba Qwen CLI\n\
-                    • iflow   - iFlow CLI\n\n\
+                    • iflow   - iFlow CLI\n\
+                    • droid   - Factory Droid CLI\n\n\
                     建议:\n\
                     • 使用已实现的平台: 'ccr platform switch claude'\n\
                     • 查看平台列表: 'ccr platform list'\n\
@@ -360,6 +449,24 @@ impl CcrError {
                     msg
                 )
             }
+            CcrError::RevertConflict(msg) => {
+                format!(
+                    "无法撤销:\n{}\n\n\
+                    建议:\n\
+                    • 运行 'ccr history' 查看之后的操作，按从新到旧的顺序撤销\n\
+                    • 手动修改过的配置请直接使用 'ccr switch' 等命令调整",
+                    msg
+                )
+            }
+            CcrError::BackupCorrupted(msg) => {
+                format!(
+                    "备份数据损坏: {}\n\n\
+                    建议:\n\
+                    • 运行 'ccr backup verify' 查看受影响的快照\n\
+                    • 选择其他未受影响的快照恢复",
+                    msg
+                )
+            }
             _ => self.to_string(),
         }
     }
@@ -380,6 +487,16 @@ mod tests {
         assert_eq!(CcrError::SettingsError("test".into()).exit_code(), 20);
         assert_eq!(CcrError::FileIoError("test".into()).exit_code(), 51);
         assert_eq!(CcrError::UpdateError("test".into()).exit_code(), 73);
+        assert_eq!(CcrError::BudgetExceeded("test".into()).exit_code(), 91);
+        assert_eq!(CcrError::InvalidPassphrase("test".into()).exit_code(), 76);
+        assert_eq!(CcrError::VaultError("test".into()).exit_code(), 77);
+        assert_eq!(
+            CcrError::SecretResolutionError("test".into()).exit_code(),
+            78
+        );
+        assert_eq!(CcrError::RevertConflict("test".into()).exit_code(), 79);
+        assert_eq!(CcrError::BackupCorrupted("test".into()).exit_code(), 81);
+        assert_eq!(CcrError::HistoryError("test".into()).exit_code(), 80);
     }
 
     #[test]
@@ -0,0 +1,620 @@
+// 📒 事务日志 (write-ahead journal)
+// 🔄 把一次操作涉及的多个文件写入组合为事务: 失败时回滚，进程中断后在下次启动时完成或撤销
+//
+// 流程:
+// 1. begin    把涉及文件的原内容复制到 ~/.ccr/journal/<id>/，写入 ~/.ccr/journal/<id>.json（状态 pending）
+// 2. 调用方执行各自的文件写入
+// 3. commit   复制各文件的新内容并将状态改为 committed（提交点），随后删除日志
+// 4. rollback 用 AtomicWriter 写回原内容（原本不存在的文件被删除），随后删除日志
+//
+// 快照按字节复制，日志 JSON 只记录副本的文件名，非 UTF-8 文件同样可以恢复
+//
+// 启动恢复:
+// - pending   → 撤销: 写回原内容
+// - committed → 完成: 写回新内容
+//
+// 事务进行期间持有 journal 文件锁，同一时间只有一个事务；锁被占用时不做恢复，
+// 并跳过所属进程 (pid) 仍在运行的日志
+
+use crate::core::atomic_writer::AtomicWriter;
+use crate::core::error::{CcrError, Result};
+use crate::core::lock::{FileLock, LockManager};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// ⏱️ 开始事务时等待其他事务结束的超时时间
+const JOURNAL_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 🔒 事务日志锁的资源名
+const JOURNAL_LOCK: &str = "ccr_journal";
+
+/// 📌 事务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalState {
+    /// ✏️ 正在写入，中断时撤销
+    Pending,
+    /// ✅ 已提交，中断时补全新内容
+    Committed,
+}
+
+/// 📸 文件快照
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Snapshot {
+    /// 文件不存在
+    Missing,
+    /// 内容副本在快照目录中的文件名
+    Copy(String),
+}
+
+/// 📄 事务涉及的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalFile {
+    path: PathBuf,
+    before: Snapshot,
+    #[serde(default)]
+    after: Option<Snapshot>,
+}
+
+/// 📒 日志文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalRecord {
+    id: String,
+    operation: String,
+    started_at: DateTime<Local>,
+    /// 开始事务的进程
+    pid: u32,
+    state: JournalState,
+    files: Vec<JournalFile>,
+}
+
+/// 🩹 启动恢复对中断事务的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// ✅ 已提交的事务: 补全新内容
+    Completed,
+    /// ↩️ 未提交的事务: 恢复原内容
+    RolledBack,
+}
+
+/// 🩹 被恢复的中断事务
+#[derive(Debug, Clone)]
+pub struct RecoveredTransaction {
+    pub operation: String,
+    pub started_at: DateTime<Local>,
+    pub action: RecoveryAction,
+}
+
+/// 📒 事务日志管理器
+pub struct Journal {
+    journal_dir: PathBuf,
+    lock_manager: LockManager,
+}
+
+impl Journal {
+    /// 🏗️ 创建事务日志管理器
+    pub fn new<P: AsRef<Path>>(journal_dir: P, lock_manager: LockManager) -> Self {
+        Self {
+            journal_dir: journal_dir.as_ref().to_path_buf(),
+            lock_manager,
+        }
+    }
+
+    /// 🏠 使用默认目录 ~/.ccr/journal/（遵循 `CCR_ROOT`）
+    pub fn with_default() -> Result<Self> {
+        let ccr_root = if let Ok(custom_root) = std::env::var("CCR_ROOT") {
+            PathBuf::from(custom_root)
+        } else {
+            let home = dirs::home_dir()
+                .ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?;
+            home.join(".ccr")
+        };
+
+        Ok(Self::new(
+            ccr_root.join("journal"),
+            LockManager::with_default_path()?,
+        ))
+    }
+
+    /// ▶️ 开始事务: 快照涉及文件并写入 pending 日志
+    ///
+    /// 开始前先恢复遗留的中断事务，保证新事务的快照基于一致的状态
+    pub fn begin(&self, operation: &str, paths: &[PathBuf]) -> Result<Transaction> {
+        let lock = self
+            .lock_manager
+            .lock_resource(JOURNAL_LOCK, JOURNAL_LOCK_TIMEOUT)
+            .map_err(|e| match e {
+                CcrError::LockTimeout(_) => CcrError::LockTimeout("另一个 ccr 事务正在进行".into()),
+                e => e,
+            })?;
+        self.recover_locked()?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let journal_path = self.journal_dir.join(format!("{}.json", id));
+        let snapshots = snapshot_dir(&journal_path);
+
+        let mut files: Vec<JournalFile> = Vec::with_capacity(paths.len());
+        for path in paths {
+            if files.iter().any(|f| &f.path == path) {
+                continue;
+            }
+            files.push(JournalFile {
+                path: path.clone(),
+                before: take_snapshot(path, &snapshots, format!("{}.before", files.len()))?,
+                after: None,
+            });
+        }
+
+        let record = JournalRecord {
+            id,
+            operation: operation.to_string(),
+            started_at: Local::now(),
+            pid: std::process::id(),
+            state: JournalState::Pending,
+            files,
+        };
+        write_record(&journal_path, &record)?;
+        tracing::debug!("📒 开始事务 {} ({})", record.id, operation);
+
+        Ok(Transaction {
+            journal_path,
+            record,
+            finished: false,
+            _lock: lock,
+        })
+    }
+
+    /// 🩹 恢复中断的事务
+    ///
+    /// 其他进程的事务仍在进行时不做任何处理并返回空列表
+    pub fn recover(&self) -> Result<Vec<RecoveredTransaction>> {
+        if !self.journal_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let _lock = match self
+            .lock_manager
+            .lock_resource(JOURNAL_LOCK, Duration::ZERO)
+        {
+            Ok(lock) => lock,
+            Err(CcrError::LockTimeout(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        self.recover_locked()
+    }
+
+    /// 🩹 持有锁时恢复目录中的所有日志（最新的先处理）
+    fn recover_locked(&self) -> Result<Vec<RecoveredTransaction>> {
+        let entries = match fs::read_dir(&self.journal_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(CcrError::FileIoError(format!(
+                    "读取事务日志目录失败: {}",
+                    e
+                )));
+            }
+        };
+
+        let mut records = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                // 日志已删除但快照目录未清理（删除日志后中断）
+                if !path.with_extension("json").exists()
+                    && !path.with_extension("json.invalid").exists()
+                {
+                    let _ = fs::remove_dir_all(&path);
+                }
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<JournalRecord>(&content).map_err(|e| e.to_string())
+                });
+            match parsed {
+                Ok(record) => records.push((path, record)),
+                Err(e) => {
+                    // 损坏的日志无法恢复，改名保留以便排查
+                    tracing::warn!("⚠️ 无法解析事务日志 {}: {}", path.display(), e);
+                    let _ = fs::rename(&path, path.with_extension("json.invalid"));
+                }
+            }
+        }
+        records.sort_by_key(|(_, record)| std::cmp::Reverse(record.started_at));
+
+        let mut recovered = Vec::with_capacity(records.len());
+        for (path, record) in records {
+            // 持有锁时本进程不可能有其他进行中的事务；其他仍在运行的进程的日志不处理
+            if record.pid != std::process::id() && process_alive(record.pid) {
+                tracing::debug!(
+                    "⏭️ 跳过进程 {} 仍在运行的事务 {} ({})",
+                    record.pid,
+                    record.id,
+                    record.operation
+                );
+                continue;
+            }
+            let snapshots = snapshot_dir(&path);
+            let action = match record.state {
+                JournalState::Pending => {
+                    restore_files(&record.files, &snapshots, |f| Some(&f.before))?;
+                    RecoveryAction::RolledBack
+                }
+                JournalState::Committed => {
+                    restore_files(&record.files, &snapshots, |f| f.after.as_ref())?;
+                    RecoveryAction::Completed
+                }
+            };
+            remove_journal(&path)?;
+            tracing::info!(
+                "🩹 已恢复中断的事务 {} ({}): {:?}",
+                record.id,
+                record.operation,
+                action
+            );
+            recovered.push(RecoveredTransaction {
+                operation: record.operation,
+                started_at: record.started_at,
+                action,
+            });
+        }
+        Ok(recovered)
+    }
+}
+
+/// 🔄 进行中的事务
+///
+/// 未调用 `commit` 即被丢弃时（包括 `?` 提前返回）自动回滚
+pub struct Transaction {
+    journal_path: PathBuf,
+    record: JournalRecord,
+    finished: bool,
+    _lock: FileLock,
+}
+
+impl Transaction {
+    /// ✅ 提交: 记录新内容并写入 committed 状态，随后删除日志
+    pub fn commit(mut self) -> Result<()> {
+        let snapshots = snapshot_dir(&self.journal_path);
+        for (index, file) in self.record.files.iter_mut().enumerate() {
+            file.after = Some(take_snapshot(
+                &file.path,
+                &snapshots,
+                format!("{}.after", index),
+            )?);
+        }
+        self.record.state = JournalState::Committed;
+        write_record(&self.journal_path, &self.record)?;
+
+        self.finished = true;
+        remove_journal(&self.journal_path)?;
+        tracing::debug!("✅ 事务 {} 已提交", self.record.id);
+        Ok(())
+    }
+
+    /// ↩️ 回滚: 写回原内容，随后删除日志
+    ///
+    /// 写回失败时保留日志，下次启动时重试
+    pub fn rollback(mut self) -> Result<()> {
+        self.finished = true;
+        self.rollback_files()
+    }
+
+    fn rollback_files(&self) -> Result<()> {
+        restore_files(&self.record.files, &snapshot_dir(&self.journal_path), |f| {
+            Some(&f.before)
+        })?;
+        remove_journal(&self.journal_path)?;
+        tracing::debug!("↩️ 事务 {} 已回滚", self.record.id);
+        Ok(())
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if !self.finished
+            && let Err(e) = self.rollback_files()
+        {
+            tracing::warn!("⚠️ 回滚事务 {} 失败: {}", self.record.id, e);
+        }
+    }
+}
+
+/// 🔍 进程是否仍在运行
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: 信号 0 只检查进程是否存在，不会发送信号
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// 🔍 进程是否仍在运行（非 Unix 平台只依赖 journal 锁判断）
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    false
+}
+
+/// 📁 日志对应的快照目录: <id>.json → <id>/
+fn snapshot_dir(journal_path: &Path) -> PathBuf {
+    journal_path.with_extension("")
+}
+
+/// 📸 把文件当前内容按字节复制到快照目录
+fn take_snapshot(path: &Path, snapshots: &Path, name: String) -> Result<Snapshot> {
+    let content = match fs::read(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Snapshot::Missing),
+        Err(e) => {
+            return Err(CcrError::FileIoError(format!(
+                "读取 {} 失败: {}",
+                path.display(),
+                e
+            )));
+        }
+    };
+    AtomicWriter::new(snapshots.join(&name)).write(&content)?;
+    Ok(Snapshot::Copy(name))
+}
+
+/// ✍️ 将每个文件写回选定的快照（Missing 表示删除，None 表示不处理）
+fn restore_files(
+    files: &[JournalFile],
+    snapshots: &Path,
+    snapshot: impl Fn(&JournalFile) -> Option<&Snapshot>,
+) -> Result<()> {
+    for file in files {
+        match snapshot(file) {
+            Some(Snapshot::Copy(name)) => {
+                let copy = snapshots.join(name);
+                let content = fs::read(&copy).map_err(|e| {
+                    CcrError::FileIoError(format!("读取快照 {} 失败: {}", copy.display(), e))
+                })?;
+                AtomicWriter::new(&file.path).write(&content)?;
+            }
+            None => {}
+            Some(Snapshot::Missing) => match fs::remove_file(&file.path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(CcrError::FileIoError(format!(
+                        "删除 {} 失败: {}",
+                        file.path.display(),
+                        e
+                    )));
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+fn write_record(path: &Path, record: &JournalRecord) -> Result<()> {
+    let content = serde_json::to_string_pretty(record)?;
+    AtomicWriter::new(path).write_string(&content)
+}
+
+fn remove_journal(path: &Path) -> Result<()> {
+    fs::remove_file(path).map_err(|e| {
+        CcrError::FileIoError(format!("删除事务日志 {} 失败: {}", path.display(), e))
+    })?;
+    match fs::remove_dir_all(snapshot_dir(path)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => {
+            // 日志已删除，遗留的快照目录在下次恢复时清理
+            tracing::warn!("⚠️ 删除事务快照失败 {}: {}", path.display(), e);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn journal(dir: &TempDir) -> Journal {
+        Journal::new(
+            dir.path().join("journal"),
+            LockManager::new(dir.path().join("locks")),
+        )
+    }
+
+    fn journal_files(journal: &Journal) -> usize {
+        fs::read_dir(&journal.journal_dir)
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| ext == "json")
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_commit_keeps_writes_and_removes_journal() {
+        let dir = TempDir::new().unwrap();
+        let journal = journal(&dir);
+        let file = dir.path().join("settings.json");
+        fs::write(&file, "old").unwrap();
+
+        let tx = journal
+            .begin("switch", std::slice::from_ref(&file))
+            .unwrap();
+        assert_eq!(journal_files(&journal), 1);
+        fs::write(&file, "new").unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        assert_eq!(journal_files(&journal), 0);
+    }
+
+    #[test]
+    fn test_drop_without_commit_rolls_back() {
+        let dir = TempDir::new().unwrap();
+        let journal = journal(&dir);
+        let existing = dir.path().join("profiles.toml");
+        let created = dir.path().join("history.json");
+        fs::write(&existing, "old").unwrap();
+
+        {
+            let _tx = journal
+                .begin("switch", &[existing.clone(), created.clone()])
+                .unwrap();
+            fs::write(&existing, "half").unwrap();
+            fs::write(&created, "half").unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "old");
+        assert!(!created.exists());
+        assert_eq!(journal_files(&journal), 0);
+    }
+
+    #[test]
+    fn test_recover_interrupted_transactions() {
+        let dir = TempDir::new().unwrap();
+        let journal = journal(&dir);
+        let file = dir.path().join("settings.json");
+        fs::write(&file, "old").unwrap();
+
+        // 模拟进程在写入中途被终止: 日志保留，锁随进程释放
+        let mut tx = journal
+            .begin("switch", std::slice::from_ref(&file))
+            .unwrap();
+        fs::write(&file, "half").unwrap();
+        tx.finished = true;
+        drop(tx);
+
+        let recovered = journal.recover().unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].action, RecoveryAction::RolledBack);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old");
+
+        // 提交点之后中断: 补全新内容
+        let mut tx = journal
+            .begin("switch", std::slice::from_ref(&file))
+            .unwrap();
+        tx.record.state = JournalState::Committed;
+        fs::write(snapshot_dir(&tx.journal_path).join("0.after"), "new").unwrap();
+        tx.record.files[0].after = Some(Snapshot::Copy("0.after".into()));
+        write_record(&tx.journal_path, &tx.record).unwrap();
+        tx.finished = true;
+        drop(tx);
+
+        let recovered = journal.recover().unwrap();
+        assert_eq!(recovered[0].action, RecoveryAction::Completed);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        assert_eq!(journal_files(&journal), 0);
+    }
+
+    #[test]
+    fn test_snapshots_are_binary_copies_outside_journal() {
+        let dir = TempDir::new().unwrap();
+        let journal = journal(&dir);
+        let binary = dir.path().join("cache.bin");
+        let mut original = vec![0xff, 0xfe, 0x00];
+        original.extend_from_slice(b"journal-secret");
+        fs::write(&binary, &original).unwrap();
+
+        let tx = journal
+            .begin("restore", std::slice::from_ref(&binary))
+            .unwrap();
+        let record = fs::read_to_string(&tx.journal_path).unwrap();
+        assert!(
+            !record.contains("journal-secret"),
+            "日志中不应包含文件内容: {}",
+            record
+        );
+        fs::write(&binary, "half").unwrap();
+        tx.rollback().unwrap();
+        assert_eq!(fs::read(&binary).unwrap(), original);
+
+        // 提交后日志与快照目录都被删除
+        let tx = journal
+            .begin("restore", std::slice::from_ref(&binary))
+            .unwrap();
+        fs::write(&binary, [0xc3, 0x28]).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(fs::read(&binary).unwrap(), [0xc3, 0x28]);
+        assert_eq!(fs::read_dir(&journal.journal_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_recover_removes_orphaned_snapshots() {
+        let dir = TempDir::new().unwrap();
+        let journal = journal(&dir);
+        let orphan = journal.journal_dir.join("orphan");
+        fs::create_dir_all(&orphan).unwrap();
+        fs::write(orphan.join("0.before"), "old").unwrap();
+
+        assert!(journal.recover().unwrap().is_empty());
+        assert!(!orphan.exists());
+    }
+
+    #[test]
+    fn test_recover_skips_open_transactions() {
+        let dir = TempDir::new().unwrap();
+        let journal = journal(&dir);
+        let file = dir.path().join("settings.json");
+        fs::write(&file, "old").unwrap();
+
+        // 事务仍在进行: 恢复不应回滚其写入
+        let tx = journal
+            .begin("switch", std::slice::from_ref(&file))
+            .unwrap();
+        fs::write(&file, "new").unwrap();
+        assert!(journal.recover().unwrap().is_empty());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        assert_eq!(journal_files(&journal), 1);
+        tx.commit().unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_recover_skips_transactions_of_live_processes() {
+        let dir = TempDir::new().unwrap();
+        let journal = journal(&dir);
+        let file = dir.path().join("settings.json");
+        fs::write(&file, "new").unwrap();
+
+        // 锁已释放但所属进程仍在运行的日志保留
+        let mut tx = journal
+            .begin("switch", std::slice::from_ref(&file))
+            .unwrap();
+        fs::write(&file, "half").unwrap();
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        tx.record.pid = child.id();
+        write_record(&tx.journal_path, &tx.record).unwrap();
+        tx.finished = true;
+        drop(tx);
+
+        assert!(journal.recover().unwrap().is_empty());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "half");
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        let recovered = journal.recover().unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+    }
+}
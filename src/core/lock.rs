@@ -125,27 +125,28 @@ impl FileLock {
         let start = Instant::now();
         let mut retry_count = 0;
         loop {
-            match file.try_lock_exclusive() {
-                Ok(_) => {
+            // fs4 在锁被其他进程（或同进程的其他句柄）持有时返回 Ok(false)
+            let reason = match file.try_lock_exclusive() {
+                Ok(true) => {
                     tracing::debug!("成功获取文件锁: {:?}", lock_path);
                     return Ok(FileLock { file, lock_path });
                 }
-                Err(_) if start.elapsed() < timeout => {
-                    // 🎯 优化：使用指数退避策略，减少 CPU 消耗
-                    // 等待时间：50ms, 100ms, 200ms, 400ms...最多 400ms
-                    let wait_ms = 50 * (1 << retry_count).min(8);
-                    std::thread::sleep(Duration::from_millis(wait_ms));
-                    retry_count += 1;
-                    continue;
-                }
-                Err(e) => {
-                    return Err(CcrError::LockTimeout(format!(
-                        "获取文件锁超时 ({}): {:?}",
-                        e,
-                        lock_path.display()
-                    )));
-                }
+                Ok(false) => "锁被占用".to_string(),
+                Err(e) => e.to_string(),
+            };
+            if start.elapsed() >= timeout {
+                return Err(CcrError::LockTimeout(format!(
+                    "获取文件锁超时 ({}): {:?}",
+                    reason,
+                    lock_path.display()
+                )));
             }
+
+            // 🎯 优化：使用指数退避策略，减少 CPU 消耗
+            // 等待时间：50ms, 100ms, 200ms, 400ms...最多 400ms
+            let wait_ms = 50 * (1u64 << retry_count.min(3));
+            std::thread::sleep(Duration::from_millis(wait_ms));
+            retry_count += 1;
         }
     }
 }
@@ -253,6 +254,60 @@ impl LockManager {
     pub fn lock_history(&self, timeout: Duration) -> Result<FileLock> {
         self.lock_resource("ccr_history", timeout)
     }
+
+    /// 📁 锁文件目录
+    pub fn lock_dir(&self) -> &Path {
+        &self.lock_dir
+    }
+
+    /// 🔍 列出锁目录中的锁文件及其状态（目录不存在时为空）
+    ///
+    /// 锁文件在释放后不会删除，未被持有的锁文件只是上次使用的残留
+    pub fn inspect(&self) -> Result<Vec<LockFileStatus>> {
+        let entries = match fs::read_dir(&self.lock_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(CcrError::FileLockError(format!("无法读取锁目录: {}", e))),
+        };
+
+        let mut statuses = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("lock") {
+                continue;
+            }
+            let age = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .unwrap_or_default();
+            let held = match OpenOptions::new().read(true).write(true).open(&path) {
+                Ok(file) => {
+                    // fs4 在锁被占用时返回 Ok(false)
+                    let acquired = matches!(file.try_lock_exclusive(), Ok(true));
+                    if acquired {
+                        let _ = FileExt::unlock(&file);
+                    }
+                    !acquired
+                }
+                Err(_) => false,
+            };
+            statuses.push(LockFileStatus { path, held, age });
+        }
+        statuses.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(statuses)
+    }
+}
+
+/// 🔍 锁文件状态
+#[derive(Debug, Clone)]
+pub struct LockFileStatus {
+    pub path: PathBuf,
+    /// 是否有进程正持有该锁
+    pub held: bool,
+    /// 距上次获取锁的时间
+    pub age: Duration,
 }
 
 #[cfg(test)]
@@ -290,6 +345,19 @@ mod tests {
         assert!(lock2_result.is_err());
     }
 
+    #[test]
+    fn test_held_lock_is_not_acquired_again() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let lock_path = temp_dir.path().join("test.lock");
+
+        let lock = FileLock::new(&lock_path, Duration::from_secs(5)).unwrap();
+        let result = FileLock::new(&lock_path, Duration::ZERO);
+        assert!(matches!(result, Err(CcrError::LockTimeout(_))));
+
+        drop(lock);
+        assert!(FileLock::new(&lock_path, Duration::ZERO).is_ok());
+    }
+
     #[test]
     fn test_lock_manager() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -301,6 +369,30 @@ mod tests {
         // 锁在作用域结束时自动释放
     }
 
+    #[test]
+    fn test_inspect_locks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = LockManager::new(temp_dir.path().join("locks"));
+        assert!(manager.inspect().unwrap().is_empty());
+
+        let lock = manager.lock_history(Duration::from_secs(5)).unwrap();
+        drop(manager.lock_settings(Duration::from_secs(5)).unwrap());
+
+        let statuses = manager.inspect().unwrap();
+        assert_eq!(statuses.len(), 2);
+        let history = statuses
+            .iter()
+            .find(|s| s.path.ends_with("ccr_history.lock"))
+            .unwrap();
+        assert!(history.held);
+        let settings = statuses
+            .iter()
+            .find(|s| s.path.ends_with("claude_settings.lock"))
+            .unwrap();
+        assert!(!settings.held);
+        drop(lock);
+    }
+
     #[test]
     #[ignore = "时间相关测试，在不同系统调度下可能不稳定"]
     fn test_concurrent_locks() {
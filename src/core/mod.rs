@@ -6,16 +6,20 @@
 // - 🔒 文件锁机制
 // - 🎨 日志和彩色输出
 // - 📝 原子文件写入
+// - 🔐 口令加密（同步与保险库共用）
+// - 📒 多文件写入事务日志
 // - 📁 文件管理抽象
 // - 💾 统一文件 I/O
 // - 🗄️ 配置缓存
 
 pub mod atomic_writer;
 pub mod cache;
+pub mod crypto;
 pub mod error;
 pub mod file_manager;
 pub mod fileio;
 pub mod http;
+pub mod journal;
 pub mod lock;
 pub mod logging;
 
@@ -43,6 +47,8 @@ pub use fileio::{
 #[allow(unused_imports)]
 pub use http::HTTP_CLIENT;
 #[allow(unused_imports)]
+pub use journal::{Journal, RecoveredTransaction, RecoveryAction, Transaction};
+#[allow(unused_imports)]
 pub use lock::{CONFIG_LOCK, FileLock, LockManager};
 #[allow(unused_imports)]
 pub use logging::{ColorOutput, init_file_only_logger, init_logger};
@@ -136,6 +136,8 @@ pub fn print_top_help() {
     println!("  {}  # 查看所有配置", cmd("ccr list"));
     println!("  {}  # 切换配置", cmd("ccr switch <名称>"));
     println!("  {}  # 快捷切换(省略 switch)", cmd("ccr anthropic"));
+    println!("  {}  # 仅对当前项目生效", cmd("ccr switch <名称> --local"));
+    println!("  {}  # 切换到最快的可用配置", cmd("ccr switch --auto"));
     println!("  {}  # 打开 TUI 配置选择器", cmd("ccr"));
 
     // 多平台切换
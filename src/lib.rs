@@ -7,8 +7,8 @@
 //! - **Claude Code**: Anthropic 官方 CLI ([`platforms::ClaudePlatform`])
 //! - **Codex**: GitHub Copilot CLI ([`platforms::CodexPlatform`])
 //! - **Gemini**: Google Gemini CLI ([`platforms::GeminiPlatform`])
-//! - **Qwen**: 阿里通义千问 CLI (计划中)
-//! - **iFlow**: iFlow CLI (计划中)
+//! - **Qwen**: 阿里通义千问 CLI ([`platforms::QwenPlatform`])
+//! - **iFlow**: iFlow CLI ([`platforms::IFlowPlatform`])
 //!
 //! ## 核心特性
 //!
@@ -32,6 +32,7 @@
 //! - [`platforms`] - 各平台的具体实现
 //! - [`managers`] - 数据访问和持久化层
 //! - [`services`] - 业务逻辑和编排层
+//! - [`proxy`] - 本地故障转移代理
 //! - [`commands`] - CLI 命令实现
 //! - [`web`] - Web API 服务器
 //! - [`tui`] - 终端用户界面
@@ -132,6 +133,7 @@ pub mod core;
 pub mod managers;
 pub mod models;
 pub mod platforms;
+pub mod proxy;
 pub mod services;
 pub mod sessions;
 pub mod storage;
@@ -239,6 +241,7 @@ pub use models::pricing::PricingConfig;
 ///
 /// **工厂函数**:
 /// - [`create_platform`] - 根据平台类型创建实例的工厂函数
+/// - [`create_platform_by_name`] - 按名称创建实例（支持 manifest 声明的自定义平台）
 ///
 /// **平台注册表**:
 /// - [`PlatformRegistry`] - 平台注册表，管理所有可用平台信息
@@ -246,6 +249,9 @@ pub use models::pricing::PricingConfig;
 /// - [`PlatformInfo`] - 平台显示信息结构
 /// - [`PlatformStatus`] - 平台状态枚举
 ///
+/// **自定义平台**:
+/// - [`ManifestPlatform`] - 由 `~/.ccr/platforms/<name>/platform.toml` 驱动的通用实现
+///
 /// ## 示例
 ///
 /// ```rust,no_run
@@ -257,7 +263,8 @@ pub use models::pricing::PricingConfig;
 /// # Ok::<(), ccr::CcrError>(())
 /// ```
 pub use platforms::{
-    PlatformDetector, PlatformInfo, PlatformRegistry, PlatformStatus, create_platform,
+    ManifestPlatform, PlatformDetector, PlatformInfo, PlatformRegistry, PlatformStatus,
+    create_platform, create_platform_by_name,
 };
 
 /// 服务层 - 业务逻辑编排
@@ -13,6 +13,7 @@ mod help;
 mod managers;
 mod models;
 mod platforms;
+mod proxy;
 mod services;
 mod sessions;
 mod storage;
@@ -4,9 +4,11 @@
 use crate::core::error::{CcrError, Result};
 use crate::managers::CostTracker;
 use crate::models::budget::{
-    BudgetConfig, BudgetLimits, BudgetPeriod, BudgetStatus, BudgetWarning, PeriodCosts,
+    BudgetConfig, BudgetEnforcement, BudgetLimits, BudgetPeriod, BudgetScope, BudgetStatus,
+    BudgetWarning, LimitAction, PeriodCosts, ScopedLimits,
 };
-use chrono::{Datelike, Duration, Utc};
+use crate::models::stats::CostRecord;
+use chrono::{DateTime, Datelike, Duration, Utc};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -149,6 +151,40 @@ impl BudgetManager {
         self.config.daily_limit = None;
         self.config.weekly_limit = None;
         self.config.monthly_limit = None;
+        self.config.profiles.clear();
+        self.config.projects.clear();
+        self.save_config()
+    }
+
+    /// 设置超出限制时的动作
+    pub fn set_action(&mut self, action: LimitAction) -> Result<()> {
+        self.config.on_limit_exceeded = action;
+        self.save_config()
+    }
+
+    /// 设置 fallback profile
+    pub fn set_fallback_profile(&mut self, profile: Option<String>) -> Result<()> {
+        self.config.fallback_profile = profile;
+        self.save_config()
+    }
+
+    /// 设置免费 profile 列表
+    pub fn set_free_profiles(&mut self, profiles: Vec<String>) -> Result<()> {
+        self.config.free_profiles = profiles;
+        self.save_config()
+    }
+
+    /// 设置 profile / 项目级别的预算限制（全部为空时移除该范围）
+    pub fn set_scoped_limits(&mut self, scope: &BudgetScope, limits: ScopedLimits) -> Result<()> {
+        let (map, key) = match scope {
+            BudgetScope::Profile(name) => (&mut self.config.profiles, name),
+            BudgetScope::Project(path) => (&mut self.config.projects, path),
+        };
+        if limits.has_any_limit() {
+            map.insert(key.clone(), limits);
+        } else {
+            map.remove(key);
+        }
         self.save_config()
     }
 
@@ -177,45 +213,147 @@ impl BudgetManager {
         let current_costs = self.calculate_period_costs(tracker)?;
 
         // 检查预算限制并生成警告
-        let warnings = self.check_limits(&current_costs);
+        let mut warnings = self.check_limits(&current_costs, &self.global_limits(), None);
+
+        // 检查 profile / 项目级别的预算
+        if !self.config.profiles.is_empty() || !self.config.projects.is_empty() {
+            let records = self.read_budget_window(tracker)?;
+            for (name, limits) in &self.config.profiles {
+                let costs = scoped_costs(&records, |r| r.profile.as_deref() == Some(name.as_str()));
+                let scope = BudgetScope::Profile(name.clone());
+                warnings.extend(self.check_limits(&costs, &limits.as_limits(), Some(scope)));
+            }
+            for (path, limits) in &self.config.projects {
+                let costs = scoped_costs(&records, |r| project_matches(&r.project, path));
+                let scope = BudgetScope::Project(path.clone());
+                warnings.extend(self.check_limits(&costs, &limits.as_limits(), Some(scope)));
+            }
+        }
 
         Ok(BudgetStatus {
             enabled: true,
             current_costs,
-            limits: BudgetLimits {
-                daily: self.config.daily_limit,
-                weekly: self.config.weekly_limit,
-                monthly: self.config.monthly_limit,
-            },
+            limits: self.global_limits(),
             warnings,
             last_updated: Utc::now(),
         })
     }
 
+    /// 🚦 切换 profile 前的预算判定
+    ///
+    /// 检查全局预算、目标 profile 预算以及当前目录所在项目的预算，
+    /// 任一已超出（≥100%）且目标不是免费 profile 时，按 `on_limit_exceeded` 决定动作
+    pub fn enforce_switch(
+        &self,
+        tracker: &CostTracker,
+        target: &str,
+        target_tags: Option<&[String]>,
+        project_dir: Option<&Path>,
+    ) -> Result<BudgetEnforcement> {
+        let action = self.config.on_limit_exceeded;
+        if !self.config.enabled
+            || !action.is_enforcing()
+            || self.config.is_free_profile(target, target_tags)
+        {
+            return Ok(BudgetEnforcement::Allow);
+        }
+
+        let exceeded = self.exceeded_limits(tracker, target, project_dir)?;
+        let Some(worst) = exceeded.iter().max_by(|a, b| {
+            a.usage_percent
+                .partial_cmp(&b.usage_percent)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        }) else {
+            return Ok(BudgetEnforcement::Allow);
+        };
+
+        let reason = match &worst.scope {
+            Some(scope) => format!(
+                "{} {}预算已超出: ${:.2} / ${:.2}",
+                scope, worst.period, worst.current_cost, worst.limit
+            ),
+            None => format!(
+                "{}预算已超出: ${:.2} / ${:.2}",
+                worst.period, worst.current_cost, worst.limit
+            ),
+        };
+
+        Ok(match action {
+            LimitAction::BlockPaid => BudgetEnforcement::Block { reason },
+            LimitAction::Fallback => match &self.config.fallback_profile {
+                Some(profile) => BudgetEnforcement::Fallback {
+                    profile: profile.clone(),
+                    reason,
+                },
+                None => BudgetEnforcement::Block { reason },
+            },
+            LimitAction::Downgrade => BudgetEnforcement::Downgrade { reason },
+            LimitAction::Warn | LimitAction::Log | LimitAction::None => BudgetEnforcement::Allow,
+        })
+    }
+
+    /// 收集与本次切换相关的已超出限制
+    fn exceeded_limits(
+        &self,
+        tracker: &CostTracker,
+        target: &str,
+        project_dir: Option<&Path>,
+    ) -> Result<Vec<BudgetWarning>> {
+        let mut warnings = Vec::new();
+
+        if self.config.has_any_global_limit() {
+            let costs = self.calculate_period_costs(tracker)?;
+            warnings.extend(self.check_limits(&costs, &self.global_limits(), None));
+        }
+
+        let profile_limits = self.config.profiles.get(target);
+        let project_limits = project_dir.and_then(|dir| self.config.project_for_dir(dir));
+        if profile_limits.is_some() || project_limits.is_some() {
+            let records = self.read_budget_window(tracker)?;
+            if let Some(limits) = profile_limits {
+                let costs = scoped_costs(&records, |r| r.profile.as_deref() == Some(target));
+                let scope = BudgetScope::Profile(target.to_string());
+                warnings.extend(self.check_limits(&costs, &limits.as_limits(), Some(scope)));
+            }
+            if let Some((path, limits)) = project_limits {
+                let costs = scoped_costs(&records, |r| project_matches(&r.project, path));
+                let scope = BudgetScope::Project(path.to_string());
+                warnings.extend(self.check_limits(&costs, &limits.as_limits(), Some(scope)));
+            }
+        }
+
+        warnings.retain(BudgetWarning::is_exceeded);
+        Ok(warnings)
+    }
+
+    /// 全局预算限制
+    fn global_limits(&self) -> BudgetLimits {
+        BudgetLimits {
+            daily: self.config.daily_limit,
+            weekly: self.config.weekly_limit,
+            monthly: self.config.monthly_limit,
+        }
+    }
+
+    /// 读取覆盖所有预算周期（本周 + 本月）的成本记录
+    fn read_budget_window(&self, tracker: &CostTracker) -> Result<Vec<CostRecord>> {
+        let now = Utc::now();
+        let start = period_starts(now).into_iter().min().unwrap_or(now);
+        tracker.read_by_time_range(start, now)
+    }
+
     /// 计算当前周期成本
     fn calculate_period_costs(&self, tracker: &CostTracker) -> Result<PeriodCosts> {
         let now = Utc::now();
+        let [today_start, week_start, month_start] = period_starts(now);
 
         // 今日成本
-        let today_start = now
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .expect("无效的日期时间")
-            .and_utc();
         let today_stats = tracker.generate_stats(today_start, now)?;
 
         // 本周成本
-        let week_start = now - Duration::days(7);
         let week_stats = tracker.generate_stats(week_start, now)?;
 
         // 本月成本
-        let month_start = now
-            .date_naive()
-            .with_day(1)
-            .expect("无法设置日期为每月第一天")
-            .and_hms_opt(0, 0, 0)
-            .expect("无效的日期时间")
-            .and_utc();
         let month_stats = tracker.generate_stats(month_start, now)?;
 
         Ok(PeriodCosts {
@@ -226,11 +364,16 @@ impl BudgetManager {
     }
 
     /// 检查预算限制并生成警告
-    fn check_limits(&self, costs: &PeriodCosts) -> Vec<BudgetWarning> {
+    fn check_limits(
+        &self,
+        costs: &PeriodCosts,
+        limits: &BudgetLimits,
+        scope: Option<BudgetScope>,
+    ) -> Vec<BudgetWarning> {
         let mut warnings = Vec::new();
 
         // 检查每日限制
-        if let Some(daily_limit) = self.config.daily_limit {
+        if let Some(daily_limit) = limits.daily {
             let usage_percent = (costs.today / daily_limit) * 100.0;
             if usage_percent >= self.config.warn_at_percent as f64 {
                 warnings.push(BudgetWarning {
@@ -238,18 +381,22 @@ impl BudgetManager {
                     current_cost: costs.today,
                     limit: daily_limit,
                     usage_percent,
-                    message: self.generate_warning_message(
-                        BudgetPeriod::Daily,
-                        usage_percent,
-                        costs.today,
-                        daily_limit,
+                    message: scoped_message(
+                        scope.as_ref(),
+                        self.generate_warning_message(
+                            BudgetPeriod::Daily,
+                            usage_percent,
+                            costs.today,
+                            daily_limit,
+                        ),
                     ),
+                    scope: scope.clone(),
                 });
             }
         }
 
         // 检查每周限制
-        if let Some(weekly_limit) = self.config.weekly_limit {
+        if let Some(weekly_limit) = limits.weekly {
             let usage_percent = (costs.this_week / weekly_limit) * 100.0;
             if usage_percent >= self.config.warn_at_percent as f64 {
                 warnings.push(BudgetWarning {
@@ -257,18 +404,22 @@ impl BudgetManager {
                     current_cost: costs.this_week,
                     limit: weekly_limit,
                     usage_percent,
-                    message: self.generate_warning_message(
-                        BudgetPeriod::Weekly,
-                        usage_percent,
-                        costs.this_week,
-                        weekly_limit,
+                    message: scoped_message(
+                        scope.as_ref(),
+                        self.generate_warning_message(
+                            BudgetPeriod::Weekly,
+                            usage_percent,
+                            costs.this_week,
+                            weekly_limit,
+                        ),
                     ),
+                    scope: scope.clone(),
                 });
             }
         }
 
         // 检查每月限制
-        if let Some(monthly_limit) = self.config.monthly_limit {
+        if let Some(monthly_limit) = limits.monthly {
             let usage_percent = (costs.this_month / monthly_limit) * 100.0;
             if usage_percent >= self.config.warn_at_percent as f64 {
                 warnings.push(BudgetWarning {
@@ -276,12 +427,16 @@ impl BudgetManager {
                     current_cost: costs.this_month,
                     limit: monthly_limit,
                     usage_percent,
-                    message: self.generate_warning_message(
-                        BudgetPeriod::Monthly,
-                        usage_percent,
-                        costs.this_month,
-                        monthly_limit,
+                    message: scoped_message(
+                        scope.as_ref(),
+                        self.generate_warning_message(
+                            BudgetPeriod::Monthly,
+                            usage_percent,
+                            costs.this_month,
+                            monthly_limit,
+                        ),
                     ),
+                    scope: scope.clone(),
                 });
             }
         }
@@ -311,6 +466,62 @@ impl BudgetManager {
     }
 }
 
+/// 各预算周期的起始时间 (今日, 本周, 本月)
+fn period_starts(now: DateTime<Utc>) -> [DateTime<Utc>; 3] {
+    let today_start = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("无效的日期时间")
+        .and_utc();
+    let week_start = now - Duration::days(7);
+    let month_start = now
+        .date_naive()
+        .with_day(1)
+        .expect("无法设置日期为每月第一天")
+        .and_hms_opt(0, 0, 0)
+        .expect("无效的日期时间")
+        .and_utc();
+    [today_start, week_start, month_start]
+}
+
+/// 按过滤条件汇总各周期成本
+fn scoped_costs(records: &[CostRecord], filter: impl Fn(&CostRecord) -> bool) -> PeriodCosts {
+    let [today_start, week_start, month_start] = period_starts(Utc::now());
+    let mut costs = PeriodCosts {
+        today: 0.0,
+        this_week: 0.0,
+        this_month: 0.0,
+    };
+
+    for record in records.iter().filter(|r| filter(r)) {
+        let cost = record.cost.total_cost;
+        if record.timestamp >= today_start {
+            costs.today += cost;
+        }
+        if record.timestamp >= week_start {
+            costs.this_week += cost;
+        }
+        if record.timestamp >= month_start {
+            costs.this_month += cost;
+        }
+    }
+
+    costs
+}
+
+/// 记录的项目路径是否位于预算项目目录下
+fn project_matches(record_project: &str, project: &str) -> bool {
+    Path::new(record_project).starts_with(project)
+}
+
+/// 为范围预算的警告消息加上范围前缀
+fn scoped_message(scope: Option<&BudgetScope>, message: String) -> String {
+    match scope {
+        Some(scope) => format!("[{}] {}", scope, message),
+        None => message,
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -423,4 +634,161 @@ mod tests {
         let msg = manager.generate_warning_message(BudgetPeriod::Monthly, 105.0, 210.0, 200.0);
         assert!(msg.contains("超出限制"));
     }
+
+    fn cost_record(profile: &str, project: &str, total_cost: f64) -> CostRecord {
+        use crate::models::stats::{Cost, TokenUsage};
+
+        CostRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            session_id: None,
+            project: project.to_string(),
+            model: "claude-sonnet-4-5-20250929".to_string(),
+            token_usage: TokenUsage {
+                input_tokens: 1000,
+                output_tokens: 100,
+                cache_creation_tokens: None,
+                cache_read_tokens: None,
+            },
+            cost: Cost {
+                input_cost: total_cost,
+                output_cost: 0.0,
+                cache_cost: 0.0,
+                total_cost,
+            },
+            duration_ms: 0,
+            platform: Some("claude".to_string()),
+            description: None,
+            profile: Some(profile.to_string()),
+        }
+    }
+
+    fn enforcing_manager(temp_dir: &TempDir, action: LimitAction) -> BudgetManager {
+        let mut manager = BudgetManager::new(temp_dir.path().join("budget.toml")).unwrap();
+        manager.enable().unwrap();
+        manager
+            .set_fallback_profile(Some("cheap".to_string()))
+            .unwrap();
+        manager.set_action(action).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_enforce_switch_global_limit_actions() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker = CostTracker::new(temp_dir.path().join("stats")).unwrap();
+        tracker
+            .append_records(&[cost_record("work", "/code/app", 12.0)])
+            .unwrap();
+
+        let mut manager = enforcing_manager(&temp_dir, LimitAction::BlockPaid);
+        manager.set_daily_limit(Some(10.0)).unwrap();
+
+        let decision = manager
+            .enforce_switch(&tracker, "work", None, None)
+            .unwrap();
+        assert!(matches!(decision, BudgetEnforcement::Block { .. }));
+
+        // 免费 profile 与 fallback profile 不受限制
+        let free_tags = vec!["free".to_string()];
+        assert_eq!(
+            manager
+                .enforce_switch(&tracker, "glm", Some(&free_tags), None)
+                .unwrap(),
+            BudgetEnforcement::Allow
+        );
+        assert_eq!(
+            manager
+                .enforce_switch(&tracker, "cheap", None, None)
+                .unwrap(),
+            BudgetEnforcement::Allow
+        );
+
+        manager.set_action(LimitAction::Fallback).unwrap();
+        match manager
+            .enforce_switch(&tracker, "work", None, None)
+            .unwrap()
+        {
+            BudgetEnforcement::Fallback { profile, .. } => assert_eq!(profile, "cheap"),
+            other => panic!("unexpected decision: {:?}", other),
+        }
+
+        manager.set_action(LimitAction::Downgrade).unwrap();
+        assert!(matches!(
+            manager
+                .enforce_switch(&tracker, "work", None, None)
+                .unwrap(),
+            BudgetEnforcement::Downgrade { .. }
+        ));
+
+        // 仅提示类动作不影响切换
+        manager.set_action(LimitAction::Warn).unwrap();
+        assert_eq!(
+            manager
+                .enforce_switch(&tracker, "work", None, None)
+                .unwrap(),
+            BudgetEnforcement::Allow
+        );
+    }
+
+    #[test]
+    fn test_enforce_switch_profile_and_project_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker = CostTracker::new(temp_dir.path().join("stats")).unwrap();
+        tracker
+            .append_records(&[
+                cost_record("work", "/code/app", 6.0),
+                cost_record("personal", "/code/blog", 1.0),
+            ])
+            .unwrap();
+
+        let mut manager = enforcing_manager(&temp_dir, LimitAction::BlockPaid);
+        let limits = ScopedLimits {
+            daily_limit: Some(5.0),
+            ..Default::default()
+        };
+        manager
+            .set_scoped_limits(&BudgetScope::Profile("work".into()), limits.clone())
+            .unwrap();
+
+        // work 超出 profile 预算，personal 未超出
+        assert!(matches!(
+            manager
+                .enforce_switch(&tracker, "work", None, None)
+                .unwrap(),
+            BudgetEnforcement::Block { .. }
+        ));
+        assert_eq!(
+            manager
+                .enforce_switch(&tracker, "personal", None, None)
+                .unwrap(),
+            BudgetEnforcement::Allow
+        );
+
+        // 在 /code/app 项目内，任何付费 profile 都会因项目预算被拦截
+        manager
+            .set_scoped_limits(&BudgetScope::Project("/code/app".into()), limits)
+            .unwrap();
+        assert!(matches!(
+            manager
+                .enforce_switch(&tracker, "personal", None, Some(Path::new("/code/app/src")))
+                .unwrap(),
+            BudgetEnforcement::Block { .. }
+        ));
+        assert_eq!(
+            manager
+                .enforce_switch(&tracker, "personal", None, Some(Path::new("/code/blog")))
+                .unwrap(),
+            BudgetEnforcement::Allow
+        );
+
+        let status = manager.check_status(&tracker).unwrap();
+        let scopes: Vec<_> = status
+            .warnings
+            .iter()
+            .filter_map(|w| w.scope.clone())
+            .collect();
+        assert!(scopes.contains(&BudgetScope::Profile("work".into())));
+        assert!(scopes.contains(&BudgetScope::Project("/code/app".into())));
+    }
 }
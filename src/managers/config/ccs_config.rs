@@ -42,6 +42,7 @@ impl CcsConfig {
     }
 
     /// ▶️ 获取当前配置节
+    #[allow(dead_code)]
     pub fn get_current_section(&self) -> Result<&ConfigSection> {
         self.get_section(&self.current_config)
     }
@@ -0,0 +1,448 @@
+// 🧬 配置继承 (extends) 解析
+// 📦 子配置节通过 `extends = "<配置节>"` 继承父配置节，只覆盖自身设置的字段
+//
+// 规则:
+// - 🔗 支持多级继承，沿 extends 链向上查找第一个设置了该字段的配置节
+// - 🔒 description / usage_count / enabled 属于配置节自身，不参与继承
+// - 📦 额外字段 (other) 按键逐个继承，extends / template 标记除外
+// - 🌍 env / models / settings 表按表内的键合并，子配置节只需写出要覆盖的键
+// - 🔑 auth_token 与 auth_token_cmd 互为替代，先找到哪个就使用哪个
+// - 🔁 检测循环继承并报告完整链路
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::config::types::ConfigSection;
+use crate::managers::profile_env::{ENV_KEY, MODELS_KEY};
+use crate::managers::secret_source::AUTH_TOKEN_CMD_KEY;
+use crate::managers::settings_bundle::SETTINGS_KEY;
+use indexmap::IndexMap;
+
+/// 🔑 继承字段在配置节中的键名
+pub const EXTENDS_KEY: &str = "extends";
+
+/// 📐 模板标记在配置节中的键名 (`template = true`)
+pub const TEMPLATE_KEY: &str = "template";
+
+/// 🧬 解析后的配置节
+///
+/// - `section`: 合并后的有效配置（已移除 extends）
+/// - `chain`: 继承链，从自身开始依次为父、祖父…
+/// - `origins`: 字段 → 提供该值的配置节名称
+#[derive(Debug, Clone)]
+pub struct ResolvedSection {
+    pub section: ConfigSection,
+    pub chain: Vec<String>,
+    pub origins: IndexMap<String, String>,
+}
+
+impl ResolvedSection {
+    /// 📝 配置节自身名称
+    pub fn name(&self) -> &str {
+        &self.chain[0]
+    }
+
+    /// 🔍 获取字段值的来源配置节
+    #[allow(dead_code)]
+    pub fn origin(&self, field: &str) -> Option<&str> {
+        self.origins.get(field).map(String::as_str)
+    }
+
+    /// 🧬 字段是否继承自父配置节
+    #[allow(dead_code)]
+    pub fn is_inherited(&self, field: &str) -> bool {
+        self.origin(field)
+            .is_some_and(|origin| origin != self.name())
+    }
+
+    /// 📋 所有继承而来的字段 (字段, 来源配置节)
+    pub fn inherited_fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.origins
+            .iter()
+            .filter(|(_, origin)| origin.as_str() != self.name())
+            .map(|(field, origin)| (field.as_str(), origin.as_str()))
+    }
+}
+
+/// 🔗 构建继承链，检测缺失的父配置节与循环继承
+fn build_chain(sections: &IndexMap<String, ConfigSection>, name: &str) -> Result<Vec<String>> {
+    let mut current = sections
+        .get(name)
+        .ok_or_else(|| CcrError::ConfigSectionNotFound(name.to_string()))?;
+    let mut chain = vec![name.to_string()];
+
+    while let Some(parent) = current.extends() {
+        if chain.iter().any(|n| n == parent) {
+            chain.push(parent.to_string());
+            return Err(CcrError::ValidationError(format!(
+                "配置继承存在循环: {}",
+                chain.join(" → ")
+            )));
+        }
+
+        current = sections.get(parent).ok_or_else(|| {
+            CcrError::ValidationError(format!(
+                "配置 '{}' 继承的配置节 '{}' 不存在",
+                chain.last().map(String::as_str).unwrap_or(name),
+                parent
+            ))
+        })?;
+        chain.push(parent.to_string());
+    }
+
+    Ok(chain)
+}
+
+/// 📥 字段为空时从来源配置节继承，并记录来源
+fn inherit<T: Clone>(
+    target: &mut Option<T>,
+    source: &Option<T>,
+    field: &str,
+    from: &str,
+    origins: &mut IndexMap<String, String>,
+) {
+    if target.is_none()
+        && let Some(value) = source
+    {
+        *target = Some(value.clone());
+        origins.insert(field.to_string(), from.to_string());
+    }
+}
+
+/// 🌍 按表内的键合并 env / models / settings 表，已有的键不覆盖，来源记录为 `<表>.<键>`
+fn merge_table(
+    target: &mut IndexMap<String, toml::Value>,
+    key: &str,
+    source: &toml::Table,
+    from: &str,
+    origins: &mut IndexMap<String, String>,
+) {
+    let entry = target
+        .entry(key.to_string())
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    // 自身把该键写成非表的值时由校验报告，不再合并
+    let Some(table) = entry.as_table_mut() else {
+        return;
+    };
+    for (name, value) in source {
+        if !table.contains_key(name) {
+            table.insert(name.clone(), value.clone());
+            origins.insert(format!("{}.{}", key, name), from.to_string());
+        }
+    }
+}
+
+/// 👶 直接继承指定配置节的子配置节名称
+pub fn children_of(sections: &IndexMap<String, ConfigSection>, parent: &str) -> Vec<String> {
+    sections
+        .iter()
+        .filter(|(_, section)| section.extends() == Some(parent))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// 🧬 解析指定配置节的继承
+pub fn resolve_section(
+    sections: &IndexMap<String, ConfigSection>,
+    name: &str,
+) -> Result<ResolvedSection> {
+    let chain = build_chain(sections, name)?;
+    let own = &sections[name];
+
+    // 自身属性不参与继承
+    let mut resolved = ConfigSection {
+        description: own.description.clone(),
+        usage_count: own.usage_count,
+        enabled: own.enabled,
+        ..Default::default()
+    };
+    let mut origins = IndexMap::new();
+
+    // 从自身开始沿继承链向上，先到先得
+    for section_name in &chain {
+        let section = &sections[section_name.as_str()];
+        let from = section_name.as_str();
+
+        inherit(
+            &mut resolved.base_url,
+            &section.base_url,
+            "base_url",
+            from,
+            &mut origins,
+        );
+        if !resolved.other.contains_key(AUTH_TOKEN_CMD_KEY) {
+            inherit(
+                &mut resolved.auth_token,
+                &section.auth_token,
+                "auth_token",
+                from,
+                &mut origins,
+            );
+        }
+        inherit(
+            &mut resolved.model,
+            &section.model,
+            "model",
+            from,
+            &mut origins,
+        );
+        inherit(
+            &mut resolved.small_fast_model,
+            &section.small_fast_model,
+            "small_fast_model",
+            from,
+            &mut origins,
+        );
+        inherit(
+            &mut resolved.provider,
+            &section.provider,
+            "provider",
+            from,
+            &mut origins,
+        );
+        inherit(
+            &mut resolved.provider_type,
+            &section.provider_type,
+            "provider_type",
+            from,
+            &mut origins,
+        );
+        inherit(
+            &mut resolved.account,
+            &section.account,
+            "account",
+            from,
+            &mut origins,
+        );
+        inherit(
+            &mut resolved.tags,
+            &section.tags,
+            "tags",
+            from,
+            &mut origins,
+        );
+
+        let is_self = section_name == name;
+        for (key, value) in &section.other {
+            if key == EXTENDS_KEY || (key == TEMPLATE_KEY && !is_self) {
+                continue;
+            }
+            // 更近的配置节已直接设置 auth_token 时不再继承命令
+            if key == AUTH_TOKEN_CMD_KEY && origins.get("auth_token").is_some_and(|o| o != from) {
+                continue;
+            }
+            if (key == ENV_KEY || key == MODELS_KEY || key == SETTINGS_KEY)
+                && let Some(table) = value.as_table()
+            {
+                merge_table(&mut resolved.other, key, table, from, &mut origins);
+                continue;
+            }
+            if !resolved.other.contains_key(key) {
+                resolved.other.insert(key.clone(), value.clone());
+                origins.insert(key.clone(), section_name.clone());
+            }
+        }
+    }
+
+    Ok(ResolvedSection {
+        section: resolved,
+        chain,
+        origins,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn sections(content: &str) -> IndexMap<String, ConfigSection> {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_child_overrides_only_own_fields() {
+        let sections = sections(
+            r#"
+[relay]
+description = "共享中转"
+base_url = "https://relay.example.com"
+auth_token = "sk-shared"
+model = "claude-sonnet-4"
+region = "us"
+enabled = false
+
+[alice]
+extends = "relay"
+auth_token = "sk-alice"
+
+[alice-opus]
+extends = "alice"
+model = "claude-opus-4"
+"#,
+        );
+
+        let resolved = resolve_section(&sections, "alice-opus").unwrap();
+        assert_eq!(resolved.chain, vec!["alice-opus", "alice", "relay"]);
+        assert_eq!(
+            resolved.section.base_url.as_deref(),
+            Some("https://relay.example.com")
+        );
+        assert_eq!(resolved.section.auth_token.as_deref(), Some("sk-alice"));
+        assert_eq!(resolved.section.model.as_deref(), Some("claude-opus-4"));
+        assert_eq!(resolved.section.other["region"].as_str(), Some("us"));
+        assert!(resolved.section.extends().is_none());
+
+        // 自身属性不继承
+        assert!(resolved.section.description.is_none());
+        assert!(resolved.section.enabled.is_none());
+
+        assert_eq!(resolved.origin("base_url"), Some("relay"));
+        assert_eq!(resolved.origin("auth_token"), Some("alice"));
+        assert_eq!(resolved.origin("model"), Some("alice-opus"));
+        assert!(resolved.is_inherited("base_url"));
+        assert!(!resolved.is_inherited("model"));
+        assert_eq!(resolved.inherited_fields().count(), 3);
+    }
+
+    #[test]
+    fn test_section_without_extends() {
+        let sections = sections(
+            r#"
+[plain]
+base_url = "https://api.example.com"
+auth_token = "sk-plain"
+"#,
+        );
+
+        let resolved = resolve_section(&sections, "plain").unwrap();
+        assert_eq!(resolved.chain, vec!["plain"]);
+        assert_eq!(resolved.inherited_fields().count(), 0);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let sections = sections(
+            r#"
+[a]
+extends = "b"
+
+[b]
+extends = "c"
+
+[c]
+extends = "a"
+"#,
+        );
+
+        let err = resolve_section(&sections, "a").unwrap_err().to_string();
+        assert!(err.contains("a → b → c → a"), "{}", err);
+
+        let self_ref = super::tests::sections("[x]\nextends = \"x\"\n");
+        assert!(resolve_section(&self_ref, "x").is_err());
+    }
+
+    #[test]
+    fn test_missing_parent() {
+        let sections = sections("[child]\nextends = \"ghost\"\n");
+        let err = resolve_section(&sections, "child").unwrap_err().to_string();
+        assert!(err.contains("ghost"));
+
+        assert!(matches!(
+            resolve_section(&sections, "nope"),
+            Err(CcrError::ConfigSectionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_auth_token_and_command_are_alternatives() {
+        let sections = sections(
+            r#"
+[relay]
+base_url = "https://relay.example.com"
+auth_token = "sk-shared"
+
+[cmd-child]
+extends = "relay"
+auth_token_cmd = "pass show relay/x"
+
+[token-child]
+extends = "cmd-child"
+auth_token = "sk-own"
+"#,
+        );
+
+        let resolved = resolve_section(&sections, "cmd-child").unwrap();
+        assert!(resolved.section.auth_token.is_none());
+        assert_eq!(
+            resolved.section.other[AUTH_TOKEN_CMD_KEY].as_str(),
+            Some("pass show relay/x")
+        );
+
+        let resolved = resolve_section(&sections, "token-child").unwrap();
+        assert_eq!(resolved.section.auth_token.as_deref(), Some("sk-own"));
+        assert!(!resolved.section.other.contains_key(AUTH_TOKEN_CMD_KEY));
+    }
+
+    #[test]
+    fn test_template_flag_not_inherited() {
+        let sections = sections(
+            r#"
+[base]
+template = true
+base_url = "https://relay.example.com"
+
+[child]
+extends = "base"
+auth_token = "sk-child"
+"#,
+        );
+
+        assert!(
+            resolve_section(&sections, "base")
+                .unwrap()
+                .section
+                .is_template()
+        );
+        let child = resolve_section(&sections, "child").unwrap();
+        assert!(!child.section.is_template());
+        assert_eq!(children_of(&sections, "base"), vec!["child"]);
+    }
+
+    #[test]
+    fn test_env_and_models_merge_per_key() {
+        let sections = sections(
+            r#"
+[relay]
+base_url = "https://relay.example.com"
+auth_token = "sk-shared"
+
+[relay.env]
+API_TIMEOUT_MS = 600000
+DISABLE_TELEMETRY = 1
+
+[relay.models]
+opus = "glm-4.6"
+haiku = "glm-4.5-air"
+
+[alice]
+extends = "relay"
+
+[alice.env]
+API_TIMEOUT_MS = 900000
+
+[alice.models]
+haiku = "glm-4.5-flash"
+"#,
+        );
+
+        let resolved = resolve_section(&sections, "alice").unwrap();
+        let extras = resolved.section.profile_env().unwrap();
+        assert_eq!(extras.env["API_TIMEOUT_MS"], "900000");
+        assert_eq!(extras.env["DISABLE_TELEMETRY"], "1");
+        assert_eq!(extras.models.opus.as_deref(), Some("glm-4.6"));
+        assert_eq!(extras.models.haiku.as_deref(), Some("glm-4.5-flash"));
+
+        assert_eq!(resolved.origin("env.API_TIMEOUT_MS"), Some("alice"));
+        assert_eq!(resolved.origin("env.DISABLE_TELEMETRY"), Some("relay"));
+        assert_eq!(resolved.origin("models.opus"), Some("relay"));
+    }
+}
@@ -4,6 +4,7 @@
 use crate::core::error::{CcrError, Result};
 use crate::core::fileio;
 use crate::managers::config::ccs_config::CcsConfig;
+use crate::managers::config::inheritance::{self, ResolvedSection};
 use crate::managers::config::types::GlobalSettings;
 use indexmap::IndexMap;
 use std::path::{Path, PathBuf};
@@ -118,6 +119,20 @@ impl ConfigManager {
         self.file_handler.list_backups()
     }
 
+    // === 继承解析 ===
+
+    /// 🧬 解析配置节的继承 (extends)，返回合并后的有效配置及字段来源
+    ///
+    /// 父配置节缺失或存在循环继承时返回错误
+    pub fn resolve_section(config: &CcsConfig, name: &str) -> Result<ResolvedSection> {
+        inheritance::resolve_section(&config.sections, name)
+    }
+
+    /// 👶 获取直接继承指定配置节的子配置节
+    pub fn children_of(config: &CcsConfig, parent: &str) -> Vec<String> {
+        inheritance::children_of(&config.sections, parent)
+    }
+
     // === Unified 模式检测方法 ===
 
     /// 🔍 检测是否启用了统一模式
@@ -7,12 +7,15 @@
 //! - [`types`] - `ProviderType`, `ConfigSection`, `GlobalSettings`
 //! - [`ccs_config`] - `CcsConfig` 结构
 //! - [`manager`] - `ConfigManager`
+//! - [`inheritance`] - `extends` 继承解析与 `ResolvedSection`
 
 mod ccs_config;
+mod inheritance;
 mod manager;
 mod types;
 
 // 重新导出所有公共类型
 pub use ccs_config::CcsConfig;
+pub use inheritance::{EXTENDS_KEY, ResolvedSection, TEMPLATE_KEY};
 pub use manager::ConfigManager;
 pub use types::{ConfigSection, GlobalSettings, ProviderType};
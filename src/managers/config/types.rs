@@ -2,11 +2,19 @@
 // 📦 ProviderType, ConfigSection, GlobalSettings
 
 use crate::core::error::{CcrError, Result};
+use crate::managers::profile_env::{self, ProfileEnv};
+use crate::managers::secret_source;
+use crate::managers::settings_bundle;
+use crate::managers::snapshot_store::RetentionPolicy;
 use crate::managers::sync_config::SyncConfig;
+use crate::proxy::ProxyConfig;
 use crate::utils::{AutoCompletable, Validatable};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+/// ⚖️ 自动选择优先级权重在配置节中的键名 (`priority = 2.0`)
+pub const PRIORITY_KEY: &str = "priority";
+
 /// 🏢 提供商类型枚举
 ///
 /// 用于分类不同类型的 API 服务提供商
@@ -114,21 +122,26 @@ impl Validatable for ConfigSection {
             return Err(CcrError::ValidationError("base_url 不能为空".into()));
         }
 
-        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+        // ${env:..} / ${file:..} 形式在解析后才能检查协议
+        secret_source::validate_reference(base_url)?;
+        if !secret_source::is_indirect(base_url)
+            && !base_url.starts_with("http://")
+            && !base_url.starts_with("https://")
+        {
             return Err(CcrError::ValidationError(
                 "base_url 必须以 http:// 或 https:// 开头".into(),
             ));
         }
 
-        // 🔑 检查 auth_token
+        // 🔑 检查 auth_token（或 auth_token_cmd）
         let auth_token = self
-            .auth_token
-            .as_ref()
+            .effective_auth_token()
             .ok_or_else(|| CcrError::ValidationError("auth_token 不能为空".into()))?;
 
         if auth_token.trim().is_empty() {
             return Err(CcrError::ValidationError("auth_token 不能为空".into()));
         }
+        secret_source::validate_reference(&auth_token)?;
 
         // 🤖 检查 model(可选,如果提供了则不能为空)
         if let Some(model) = &self.model
@@ -137,6 +150,23 @@ impl Validatable for ConfigSection {
             return Err(CcrError::ValidationError("model 不能为空字符串".into()));
         }
 
+        // 🌍 检查 auth_mode / models / env
+        self.profile_env()?;
+
+        // ⚖️ 检查自动选择优先级权重
+        if let Some(value) = self.other.get(PRIORITY_KEY)
+            && !toml_number(value).is_some_and(|p| p > 0.0)
+        {
+            return Err(CcrError::ValidationError(format!(
+                "{} 必须是正数",
+                PRIORITY_KEY
+            )));
+        }
+
+        // 🎁 检查设置包（文件在切换时读取）
+        settings_bundle::inline_fragment(&self.other)?;
+        settings_bundle::bundle_path(&self.other)?;
+
         Ok(())
     }
 }
@@ -211,23 +241,119 @@ impl ConfigSection {
         self.usage_count = Some(count + 1);
     }
 
-    /// 📊 获取预期的 ANTHROPIC_* 环境变量状态（无副作用）
+    /// 🧬 获取继承的父配置节名称 (`extends = "<配置节>"`)
+    pub fn extends(&self) -> Option<&str> {
+        self.other
+            .get(super::inheritance::EXTENDS_KEY)
+            .and_then(|v| v.as_str())
+    }
+
+    /// 🧬 设置或清除继承的父配置节
+    pub fn set_extends(&mut self, parent: Option<&str>) {
+        match parent {
+            Some(parent) => {
+                self.other.insert(
+                    super::inheritance::EXTENDS_KEY.to_string(),
+                    toml::Value::String(parent.to_string()),
+                );
+            }
+            None => {
+                self.other.shift_remove(super::inheritance::EXTENDS_KEY);
+            }
+        }
+    }
+
+    /// 📐 是否为模板配置 (`template = true`)
+    ///
+    /// 模板只用于被继承，不能直接切换，也不要求字段完整
+    pub fn is_template(&self) -> bool {
+        self.other
+            .get(super::inheritance::TEMPLATE_KEY)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// ⚖️ `ccr switch --auto` 时的优先级权重，未设置或无效时为 1.0
+    ///
+    /// 权重越大越容易被选中：候选的得分除以该权重
+    pub fn priority(&self) -> f64 {
+        self.other
+            .get(PRIORITY_KEY)
+            .and_then(toml_number)
+            .filter(|p| *p > 0.0)
+            .unwrap_or(1.0)
+    }
+
+    /// 🔑 合并 auth_token 与 auth_token_cmd 后的 auth_token（未解析）
+    pub fn effective_auth_token(&self) -> Option<String> {
+        secret_source::effective_auth_token(
+            self.auth_token.as_deref(),
+            self.other
+                .get(secret_source::AUTH_TOKEN_CMD_KEY)
+                .and_then(|v| v.as_str()),
+        )
+    }
+
+    /// 🔐 返回解析了间接形式的副本，用于写入设置或发起请求
+    ///
+    /// 解析保险库引用 (vault:<name>)、`${env:..}` / `${file:..}` 以及 auth_token_cmd
+    pub fn with_resolved_secrets(&self) -> Result<ConfigSection> {
+        let mut resolved = self.clone();
+        resolved.auth_token = secret_source::resolve_optional(&self.effective_auth_token())?;
+        resolved.base_url = secret_source::resolve_optional(&self.base_url)?;
+        resolved
+            .other
+            .shift_remove(secret_source::AUTH_TOKEN_CMD_KEY);
+        Ok(resolved)
+    }
+
+    /// 🌍 解析 auth_mode / models / env 额外字段
+    pub fn profile_env(&self) -> Result<ProfileEnv> {
+        ProfileEnv::from_other(&self.other)
+    }
+
+    /// 🎁 加载设置包 (bundle 文件 + 内联 settings 表)，未设置时为 None
+    pub fn settings_bundle(&self) -> Result<Option<serde_json::Value>> {
+        settings_bundle::load(&self.other)
+    }
+
+    /// 🌍 应用到 Claude Code 的完整环境变量（按写入顺序）
+    ///
+    /// 无效的额外字段由 `validate` 报告，这里按未设置处理
+    pub fn claude_env(&self) -> IndexMap<String, String> {
+        let extras = self.profile_env().unwrap_or_default();
+        let mut env = IndexMap::new();
+
+        let fields = [
+            ("ANTHROPIC_BASE_URL", self.base_url.as_ref()),
+            (extras.auth_mode.env_var(), self.auth_token.as_ref()),
+            ("ANTHROPIC_MODEL", self.model.as_ref()),
+            ("ANTHROPIC_SMALL_FAST_MODEL", self.small_fast_model.as_ref()),
+        ];
+        for (name, value) in fields.into_iter().chain(extras.models.env_pairs()) {
+            if let Some(value) = value {
+                env.insert(name.to_string(), value.clone());
+            }
+        }
+        env.extend(extras.env);
+        env
+    }
+
+    /// 📊 获取预期的环境变量状态（无副作用）
     ///
     /// 根据配置节的字段值，返回应用后的环境变量状态
     /// 用于在不实际修改 settings 的情况下预览环境变量变化
     pub fn to_anthropic_env_status(&self) -> std::collections::HashMap<String, Option<String>> {
         use std::collections::HashMap;
 
-        let mut status = HashMap::new();
-
         // 映射关系与 ClaudeSettings::update_from_config 保持一致
-        status.insert("ANTHROPIC_BASE_URL".to_string(), self.base_url.clone());
-        status.insert("ANTHROPIC_AUTH_TOKEN".to_string(), self.auth_token.clone());
-        status.insert("ANTHROPIC_MODEL".to_string(), self.model.clone());
-        status.insert(
-            "ANTHROPIC_SMALL_FAST_MODEL".to_string(),
-            self.small_fast_model.clone(),
-        );
+        let mut status: HashMap<String, Option<String>> = profile_env::claude_env_var_names()
+            .into_iter()
+            .map(|name| (name.to_string(), None))
+            .collect();
+        for (name, value) in self.claude_env() {
+            status.insert(name, Some(value));
+        }
 
         status
     }
@@ -263,6 +389,13 @@ impl AutoCompletable for ConfigSection {
     }
 }
 
+/// 🔢 TOML 数值（整数或浮点数）转换为 f64
+fn toml_number(value: &toml::Value) -> Option<f64> {
+    value
+        .as_float()
+        .or_else(|| value.as_integer().map(|i| i as f64))
+}
+
 /// ⚙️ 全局设置结构
 ///
 /// 用于存储 CCR 的全局配置选项
@@ -276,6 +409,14 @@ pub struct GlobalSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tui_theme: Option<String>,
 
+    /// 🔀 本地故障转移代理配置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+
+    /// 🗄️ 备份快照保留策略（未设置时使用默认策略）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_retention: Option<RetentionPolicy>,
+
     /// ☁️ WebDAV 同步配置（已废弃，保留向后兼容）
     #[serde(skip_serializing_if = "Option::is_none")]
     #[deprecated(note = "Use SyncConfigManager to manage sync configuration")]
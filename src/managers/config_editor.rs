@@ -1,11 +1,23 @@
 //! 📝 配置编辑器模块
 //!
 //! 使用 toml_edit 进行配置文件编辑，保留格式和注释
+//!
+//! 🧬 删除、重命名配置节时同步维护 `extends` 继承关系
 
 use crate::core::error::{CcrError, Result};
+use crate::managers::config::{EXTENDS_KEY, TEMPLATE_KEY};
 use std::path::Path;
 use toml_edit::{DocumentMut, Item, Table};
 
+/// 🔒 配置节自身的属性，不参与继承，删除父配置节时不会展开到子配置节
+const NON_INHERITED_KEYS: &[&str] = &[
+    EXTENDS_KEY,
+    TEMPLATE_KEY,
+    "description",
+    "usage_count",
+    "enabled",
+];
+
 /// 配置编辑器
 ///
 /// 使用 toml_edit 进行配置文件编辑，保留原有格式
@@ -144,8 +156,34 @@ impl ConfigEditor {
     }
 
     /// 删除配置节
+    ///
+    /// 直接继承该配置节的子配置节会展开其继承的字段，并改为继承被删除配置节的父配置节，
+    /// 保证子配置节解析后的值不变
     pub fn remove_section(&mut self, name: &str) -> bool {
-        self.doc.remove(name).is_some()
+        let Some(removed) = self.doc.remove(name) else {
+            return false;
+        };
+        let Some(removed) = removed.as_table() else {
+            return true;
+        };
+        let grandparent = removed.get(EXTENDS_KEY).and_then(|v| v.as_str());
+
+        for child in self.children_of(name) {
+            if let Some(table) = self.doc[&child].as_table_mut() {
+                for (key, value) in removed.iter() {
+                    if !NON_INHERITED_KEYS.contains(&key) && !table.contains_key(key) {
+                        table[key] = value.clone();
+                    }
+                }
+                match grandparent {
+                    Some(parent) => set_preserving_decor(table, EXTENDS_KEY, parent),
+                    None => {
+                        table.remove(EXTENDS_KEY);
+                    }
+                }
+            }
+        }
+        true
     }
 
     /// 删除配置节中的键
@@ -169,6 +207,13 @@ impl ConfigEditor {
         // 获取旧配置节的值
         if let Some(value) = self.doc.remove(old_name) {
             self.doc[new_name] = value;
+
+            // 子配置节改为继承新名称
+            for child in self.children_of(old_name) {
+                if let Some(table) = self.doc[&child].as_table_mut() {
+                    set_preserving_decor(table, EXTENDS_KEY, new_name);
+                }
+            }
             Ok(())
         } else {
             Err(CcrError::ConfigError(format!("配置节 {} 不存在", old_name)))
@@ -208,6 +253,66 @@ impl ConfigEditor {
         )
     }
 
+    /// 🧬 获取配置节继承的父配置节
+    pub fn get_extends(&self, section: &str) -> Option<String> {
+        self.get_value(section, EXTENDS_KEY)
+    }
+
+    /// 🧬 设置或清除配置节继承的父配置节
+    ///
+    /// 父配置节必须存在，且不能形成循环继承
+    pub fn set_extends(&mut self, section: &str, parent: Option<&str>) -> Result<()> {
+        if !self.has_section(section) {
+            return Err(CcrError::ConfigError(format!("配置节 {} 不存在", section)));
+        }
+
+        let Some(parent) = parent else {
+            self.remove_key(section, EXTENDS_KEY);
+            return Ok(());
+        };
+
+        if !self.has_section(parent) {
+            return Err(CcrError::ValidationError(format!(
+                "配置 '{}' 继承的配置节 '{}' 不存在",
+                section, parent
+            )));
+        }
+
+        // 从父配置节向上查找，遇到自身即为循环
+        let mut chain = vec![section.to_string(), parent.to_string()];
+        let mut current = parent.to_string();
+        while let Some(next) = self.get_extends(&current) {
+            let cycle = next == section || chain[1..].contains(&next);
+            chain.push(next.clone());
+            if cycle {
+                return Err(CcrError::ValidationError(format!(
+                    "配置继承存在循环: {}",
+                    chain.join(" → ")
+                )));
+            }
+            current = next;
+        }
+
+        if let Some(table) = self.doc[section].as_table_mut() {
+            set_preserving_decor(table, EXTENDS_KEY, parent);
+        }
+        Ok(())
+    }
+
+    /// 👶 直接继承指定配置节的子配置节
+    pub fn children_of(&self, parent: &str) -> Vec<String> {
+        self.doc
+            .iter()
+            .filter(|(_, item)| {
+                item.as_table()
+                    .and_then(|t| t.get(EXTENDS_KEY))
+                    .and_then(|v| v.as_str())
+                    == Some(parent)
+            })
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
     /// 更新 settings 部分的 current_config
     pub fn set_current_config(&mut self, name: &str) -> Result<()> {
         self.set_value("settings", "current_config", name)
@@ -219,6 +324,15 @@ impl ConfigEditor {
     }
 }
 
+/// ✏️ 更新表中的字符串键，保留原值的行尾注释
+fn set_preserving_decor(table: &mut Table, key: &str, value: &str) {
+    let mut new_value = toml_edit::Value::from(value);
+    if let Some(old) = table.get(key).and_then(|item| item.as_value()) {
+        *new_value.decor_mut() = old.decor().clone();
+    }
+    table[key] = Item::Value(new_value);
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -292,4 +406,70 @@ base_url = "https://example.com"
         assert!(output.contains("# 配置文件头部注释"));
         assert!(output.contains("# API 配置"));
     }
+
+    const INHERITANCE: &str = r#"
+[relay]
+base_url = "https://relay.example.com"
+model = "claude-sonnet-4"
+
+[team]
+extends = "relay" # 团队共享
+auth_token = "sk-team"
+
+[alice]
+extends = "team"
+model = "claude-opus-4"
+"#;
+
+    #[test]
+    fn test_rename_updates_children() {
+        let mut editor = ConfigEditor::from_str(INHERITANCE).unwrap();
+        editor.rename_section("relay", "shared").unwrap();
+
+        assert_eq!(editor.get_extends("team"), Some("shared".to_string()));
+        assert!(editor.as_str().contains("extends = \"shared\" # 团队共享"));
+    }
+
+    #[test]
+    fn test_remove_parent_flattens_into_children() {
+        let mut editor = ConfigEditor::from_str(INHERITANCE).unwrap();
+        assert!(editor.remove_section("team"));
+
+        // alice 展开 team 的字段并改为继承 relay
+        assert_eq!(editor.get_extends("alice"), Some("relay".to_string()));
+        assert_eq!(
+            editor.get_value("alice", "auth_token"),
+            Some("sk-team".to_string())
+        );
+        assert_eq!(
+            editor.get_value("alice", "model"),
+            Some("claude-opus-4".to_string())
+        );
+
+        let config: crate::managers::config::CcsConfig = toml::from_str(&format!(
+            "default_config = \"alice\"\ncurrent_config = \"alice\"\n{}",
+            editor.as_str()
+        ))
+        .unwrap();
+        let resolved =
+            crate::managers::config::ConfigManager::resolve_section(&config, "alice").unwrap();
+        assert_eq!(
+            resolved.section.base_url.as_deref(),
+            Some("https://relay.example.com")
+        );
+    }
+
+    #[test]
+    fn test_set_extends_rejects_cycle() {
+        let mut editor = ConfigEditor::from_str(INHERITANCE).unwrap();
+
+        let err = editor.set_extends("relay", Some("alice")).unwrap_err();
+        assert!(err.to_string().contains("relay → alice → team → relay"));
+        assert!(editor.set_extends("alice", Some("ghost")).is_err());
+
+        editor.set_extends("alice", Some("relay")).unwrap();
+        assert_eq!(editor.get_extends("alice"), Some("relay".to_string()));
+        editor.set_extends("alice", None).unwrap();
+        assert_eq!(editor.get_extends("alice"), None);
+    }
 }
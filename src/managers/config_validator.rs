@@ -7,7 +7,7 @@
 // - 生成详细的验证报告
 
 use crate::core::error::Result;
-use crate::managers::config::{CcsConfig, ConfigSection};
+use crate::managers::config::{CcsConfig, ConfigManager, ConfigSection};
 use crate::utils::Validatable;
 use std::collections::HashMap;
 
@@ -92,19 +92,33 @@ impl ConfigValidator {
     /// ✅ 验证单个配置节
     ///
     /// 返回验证结果，如果验证失败返回错误消息
+    ///
+    /// `${env:VAR}`、`${file:~/path}`、`vault:<name>` 与 `auth_token_cmd` 只校验语法，
+    /// 不在验证时解析（真实值在切换 / 健康检查时解析）
     pub fn validate_section(&self, section: &ConfigSection) -> Result<()> {
         section.validate()
     }
 
+    /// 🧬 验证配置节继承解析后的有效配置
+    ///
+    /// 父配置节缺失或循环继承视为无效；模板只用于继承，不要求字段完整
+    pub fn validate_resolved_section(&self, config: &CcsConfig, name: &str) -> Result<()> {
+        let resolved = ConfigManager::resolve_section(config, name)?;
+        if resolved.section.is_template() {
+            return Ok(());
+        }
+        self.validate_section(&resolved.section)
+    }
+
     /// 📋 批量验证所有配置节
     ///
-    /// 验证配置中的所有配置节，返回详细的验证报告
+    /// 验证配置中的所有配置节（按继承解析后的有效值），返回详细的验证报告
     pub fn validate_all_sections(&self, config: &CcsConfig) -> ValidationReport {
         let mut report = ValidationReport::new();
         report.total_sections = config.sections.len();
 
-        for (name, section) in &config.sections {
-            match self.validate_section(section) {
+        for name in config.sections.keys() {
+            match self.validate_resolved_section(config, name) {
                 Ok(_) => {
                     report.valid_count += 1;
                     tracing::debug!("✅ 配置节 '{}' 验证通过", name);
@@ -317,6 +331,82 @@ mod tests {
         assert_eq!(report.success_rate(), 50.0);
     }
 
+    #[test]
+    fn test_validator_accepts_indirect_secrets() {
+        let validator = ConfigValidator::new();
+
+        let env_section = ConfigSection {
+            base_url: Some("${env:CCR_RELAY_URL}".into()),
+            auth_token: Some("${file:~/.secrets/relay}".into()),
+            ..Default::default()
+        };
+        assert!(validator.validate_section(&env_section).is_ok());
+
+        let mut cmd_section = ConfigSection {
+            base_url: Some("https://relay.example.com".into()),
+            ..Default::default()
+        };
+        cmd_section.other.insert(
+            "auth_token_cmd".into(),
+            toml::Value::String("pass show relay/x".into()),
+        );
+        assert!(validator.validate_section(&cmd_section).is_ok());
+
+        // 未闭合的占位符与非 http 的字面 URL 仍然无效
+        let broken = ConfigSection {
+            base_url: Some("${env:CCR_RELAY_URL".into()),
+            auth_token: Some("sk-test".into()),
+            ..Default::default()
+        };
+        assert!(validator.validate_section(&broken).is_err());
+        let literal = ConfigSection {
+            base_url: Some("relay.example.com".into()),
+            auth_token: Some("sk-test".into()),
+            ..Default::default()
+        };
+        assert!(validator.validate_section(&literal).is_err());
+    }
+
+    #[test]
+    fn test_validator_validates_resolved_sections() {
+        let validator = ConfigValidator::new();
+
+        let mut config = CcsConfig {
+            default_config: "valid".into(),
+            current_config: "valid".into(),
+            settings: GlobalSettings::default(),
+            sections: IndexMap::new(),
+        };
+
+        let mut template = ConfigSection {
+            base_url: Some("https://relay.example.com".into()),
+            ..Default::default()
+        };
+        template
+            .other
+            .insert("template".into(), toml::Value::Boolean(true));
+        config.set_section("base".into(), template);
+
+        // 只设置 auth_token 的子配置节继承 base_url 后有效
+        let mut child = ConfigSection {
+            auth_token: Some("sk-child".into()),
+            ..Default::default()
+        };
+        child.set_extends(Some("base"));
+        config.set_section("child".into(), child);
+
+        // 父配置节不存在
+        let mut orphan = create_valid_section();
+        orphan.set_extends(Some("ghost"));
+        config.set_section("orphan".into(), orphan);
+
+        let report = validator.validate_all_sections(&config);
+
+        assert_eq!(report.total_sections, 3);
+        assert_eq!(report.valid_count, 2);
+        assert!(report.invalid_sections["orphan"].contains("ghost"));
+    }
+
     #[test]
     fn test_validator_validate_consistency() {
         let validator = ConfigValidator::new();
@@ -106,6 +106,7 @@ impl CostTracker {
             duration_ms,
             platform,
             description,
+            profile: None,
         };
 
         // 保存到 CSV
@@ -114,6 +115,17 @@ impl CostTracker {
         Ok(record)
     }
 
+    /// 📥 追加已构建好的成本记录
+    ///
+    /// 与 [`CostTracker::record`] 不同，保留记录自带的 ID 与时间戳，
+    /// 并按记录时间写入对应月份的 CSV 文件（用于从会话日志自动采集）
+    pub fn append_records(&self, records: &[CostRecord]) -> Result<()> {
+        for record in records {
+            self.save_to_csv(record)?;
+        }
+        Ok(())
+    }
+
     /// 计算成本
     pub fn calculate_cost(&self, model: &str, usage: &TokenUsage) -> Result<Cost> {
         // 优先从 PricingManager 获取定价
@@ -134,7 +146,7 @@ impl CostTracker {
 
     /// 保存到 CSV 文件
     fn save_to_csv(&self, record: &CostRecord) -> Result<()> {
-        let csv_path = self.get_csv_path()?;
+        let csv_path = self.get_csv_path(record.timestamp);
 
         // 检查文件是否存在，如果不存在则创建并写入表头
         let file_exists = csv_path.exists();
@@ -149,14 +161,14 @@ impl CostTracker {
                 file,
                 "timestamp,id,session_id,project,platform,model,input_tokens,output_tokens,\
                 cache_read_tokens,cache_write_tokens,input_cost,output_cost,cache_cost,\
-                total_cost,duration_ms,description"
+                total_cost,duration_ms,description,profile"
             )?;
         }
 
         // 写入记录
         writeln!(
             file,
-            "{},{},{},{},{},{},{},{},{},{},{:.6},{:.6},{:.6},{:.6},{},{}",
+            "{},{},{},{},{},{},{},{},{},{},{:.6},{:.6},{:.6},{:.6},{},{},{}",
             record.timestamp.to_rfc3339(),
             record.id,
             record.session_id.as_deref().unwrap_or(""),
@@ -172,17 +184,17 @@ impl CostTracker {
             record.cost.cache_cost,
             record.cost.total_cost,
             record.duration_ms,
-            record.description.as_deref().unwrap_or("")
+            record.description.as_deref().unwrap_or(""),
+            record.profile.as_deref().unwrap_or("")
         )?;
 
         Ok(())
     }
 
-    /// 获取 CSV 文件路径（当前月份）
-    fn get_csv_path(&self) -> Result<PathBuf> {
-        let now = Utc::now();
-        let filename = format!("costs_{}.csv", now.format("%Y%m"));
-        Ok(self.storage_dir.join(filename))
+    /// 获取 CSV 文件路径（记录所在月份）
+    fn get_csv_path(&self, timestamp: DateTime<Utc>) -> PathBuf {
+        let filename = format!("costs_{}.csv", timestamp.format("%Y%m"));
+        self.storage_dir.join(filename)
     }
 
     /// 🎯 推断时间范围内需要读取的文件
@@ -263,7 +275,7 @@ impl CostTracker {
         }
 
         // 按时间排序
-        records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        records.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
 
         Ok(records)
     }
@@ -323,7 +335,7 @@ impl CostTracker {
         }
 
         // 按时间排序
-        records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        records.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
 
         Ok(records)
     }
@@ -383,6 +395,12 @@ impl CostTracker {
             Some(parts[15].to_string())
         };
 
+        // 旧版 CSV 没有 profile 列
+        let profile = parts
+            .get(16)
+            .filter(|p| !p.is_empty())
+            .map(|p| p.to_string());
+
         Ok(CostRecord {
             id: parts[1].to_string(),
             timestamp,
@@ -404,6 +422,7 @@ impl CostTracker {
             },
             duration_ms: parts[14].parse().unwrap_or(0),
             description,
+            profile,
         })
     }
 
@@ -8,12 +8,14 @@
 // - 📊 操作统计和筛选
 // - 🆔 UUID 唯一标识每个操作
 // - 📅 时间戳和操作者追踪
+// - 👤 全局 profile 激活记录（不随历史记录清理，供成本归属使用）
 
 use crate::core::error::{CcrError, Result};
 use crate::core::lock::LockManager;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::fs as async_fs;
@@ -34,6 +36,20 @@ pub enum OperationType {
     Validate,
     /// 🔄 更新
     Update,
+    /// ➕ 添加配置
+    Add,
+    /// ➖ 删除配置
+    Delete,
+    /// ✅ 启用配置
+    Enable,
+    /// ❌ 禁用配置
+    Disable,
+    /// ↩️ 撤销操作
+    Revert,
+    /// 📌 项目级切换（只写入项目设置文件，不改变全局当前配置）
+    LocalSwitch,
+    /// 🎯 自动选择保持当前配置（`switch --auto` 的最佳配置已是当前配置）
+    AutoKeep,
 }
 
 impl OperationType {
@@ -44,8 +60,27 @@ impl OperationType {
             OperationType::Restore => "恢复",
             OperationType::Validate => "验证",
             OperationType::Update => "更新",
+            OperationType::Add => "添加配置",
+            OperationType::Delete => "删除配置",
+            OperationType::Enable => "启用配置",
+            OperationType::Disable => "禁用配置",
+            OperationType::Revert => "撤销",
+            OperationType::LocalSwitch => "项目级切换",
+            OperationType::AutoKeep => "自动选择保持",
         }
     }
+
+    /// ↩️ 是否可以通过 `ccr undo` / `ccr history revert` 撤销
+    pub fn is_revertable(&self) -> bool {
+        matches!(
+            self,
+            OperationType::Switch
+                | OperationType::Add
+                | OperationType::Delete
+                | OperationType::Enable
+                | OperationType::Disable
+        )
+    }
 }
 
 /// 操作结果枚举
@@ -105,6 +140,12 @@ pub struct HistoryEntry {
     pub env_changes: Vec<EnvChange>,
     /// 操作结果
     pub result: OperationResult,
+    /// 操作所在平台（撤销时使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// 撤销的历史记录 ID（仅 Revert 操作）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverts: Option<String>,
     /// 备注
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
@@ -125,6 +166,8 @@ impl HistoryEntry {
             details,
             env_changes: Vec::new(),
             result,
+            platform: None,
+            reverts: None,
             notes: None,
         }
     }
@@ -148,6 +191,62 @@ impl HistoryEntry {
     }
 }
 
+/// 📄 profile 激活记录文件名（与历史文件位于同一目录）
+pub const ACTIVATION_LOG_FILE: &str = "ccr_profile_activations.jsonl";
+
+/// 👤 一次全局 profile 激活
+///
+/// 历史记录只保留最近 10 条，成本归属需要完整的激活时间线，
+/// 因此每次成功的全局切换（含撤销切换）另外追加到不清理的 JSONL 文件中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileActivation {
+    /// 激活时间
+    pub timestamp: DateTime<Local>,
+
+    /// 平台名称（旧版本记录可能为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+
+    /// 激活前的 profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+
+    /// 激活的 profile
+    pub profile: String,
+}
+
+impl ProfileActivation {
+    /// 从历史记录条目提取激活信息
+    ///
+    /// - ✅ 成功的全局切换
+    /// - ↩️ 撤销切换（from/to 均有值；撤销增删、启停只有其中之一）
+    /// - 🚫 项目级切换不改变全局配置（旧版本记录为 extra 以「项目级切换」开头的 Switch）
+    pub fn from_entry(entry: &HistoryEntry) -> Option<Self> {
+        if matches!(entry.result, OperationResult::Failure(_)) {
+            return None;
+        }
+        let details = &entry.details;
+        let activates = match entry.operation {
+            OperationType::Switch => !details
+                .extra
+                .as_deref()
+                .is_some_and(|extra| extra.starts_with(OperationType::LocalSwitch.as_str())),
+            OperationType::Revert => details.from_config.is_some(),
+            _ => false,
+        };
+        if !activates {
+            return None;
+        }
+
+        Some(Self {
+            timestamp: entry.timestamp,
+            platform: entry.platform.clone(),
+            from: details.from_config.clone(),
+            profile: details.to_config.clone()?,
+        })
+    }
+}
+
 /// 历史记录管理器
 pub struct HistoryManager {
     history_path: PathBuf,
@@ -183,6 +282,54 @@ impl HistoryManager {
         Ok(Self::new(history_path, lock_manager))
     }
 
+    /// 📁 获取历史文件路径
+    pub fn history_path(&self) -> &Path {
+        &self.history_path
+    }
+
+    /// 📁 profile 激活记录路径
+    pub fn activation_log_path(&self) -> PathBuf {
+        self.history_path.with_file_name(ACTIVATION_LOG_FILE)
+    }
+
+    /// 👤 加载全部 profile 激活记录（按写入顺序，跳过无法解析的行）
+    pub fn load_activations(&self) -> Result<Vec<ProfileActivation>> {
+        let path = self.activation_log_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| CcrError::HistoryError(format!("读取激活记录失败: {}", e)))?;
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(activation) => Some(activation),
+                Err(e) => {
+                    tracing::warn!("跳过无法解析的激活记录: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// 追加 profile 激活记录（调用方需持有历史记录锁）
+    fn append_activation(&self, entry: &HistoryEntry) -> Result<()> {
+        let Some(activation) = ProfileActivation::from_entry(entry) else {
+            return Ok(());
+        };
+
+        let line = serde_json::to_string(&activation)
+            .map_err(|e| CcrError::HistoryError(format!("序列化激活记录失败: {}", e)))?;
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.activation_log_path())
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| CcrError::HistoryError(format!("写入激活记录失败: {}", e)))
+    }
+
     /// 加载历史记录
     pub fn load(&self) -> Result<Vec<HistoryEntry>> {
         if !self.history_path.exists() {
@@ -281,7 +428,7 @@ impl HistoryManager {
         entries.push(entry.clone());
 
         // 🔄 自动清理：按时间倒序排序，只保留最近 10 条
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
         const MAX_HISTORY_ENTRIES: usize = 10;
         if entries.len() > MAX_HISTORY_ENTRIES {
             let removed_count = entries.len() - MAX_HISTORY_ENTRIES;
@@ -291,6 +438,7 @@ impl HistoryManager {
 
         // 保存
         self.save(&entries)?;
+        self.append_activation(&entry)?;
         tracing::info!(
             "✅ 历史记录已添加 (ID: {}, 总数: {} -> {})",
             entry.id,
@@ -319,7 +467,7 @@ impl HistoryManager {
 
         entries.push(entry.clone());
 
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
         const MAX_HISTORY_ENTRIES: usize = 10;
         if entries.len() > MAX_HISTORY_ENTRIES {
             let removed_count = entries.len() - MAX_HISTORY_ENTRIES;
@@ -328,6 +476,7 @@ impl HistoryManager {
         }
 
         self.save_async(&entries).await?;
+        self.append_activation(&entry)?;
         tracing::info!(
             "✅ 历史记录已添加 (ID: {}, 总数: {} -> {})",
             entry.id,
@@ -363,7 +512,7 @@ impl HistoryManager {
     /// 获取最近的 N 条记录
     pub fn get_recent(&self, limit: usize) -> Result<Vec<HistoryEntry>> {
         let mut entries = self.load()?;
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
         entries.truncate(limit);
         Ok(entries)
     }
@@ -371,7 +520,7 @@ impl HistoryManager {
     /// 异步获取最近的 N 条记录
     pub async fn get_recent_async(&self, limit: usize) -> Result<Vec<HistoryEntry>> {
         let mut entries = self.load_async().await?;
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
         entries.truncate(limit);
         Ok(entries)
     }
@@ -505,6 +654,9 @@ mod tests {
     fn test_operation_type() {
         assert_eq!(OperationType::Switch.as_str(), "切换配置");
         assert_eq!(OperationType::Backup.as_str(), "备份");
+        assert_eq!(OperationType::LocalSwitch.as_str(), "项目级切换");
+        assert!(!OperationType::LocalSwitch.is_revertable());
+        assert!(!OperationType::AutoKeep.is_revertable());
     }
 
     #[test]
@@ -686,4 +838,69 @@ mod tests {
         // 最旧的保留配置应该是 config-5
         assert_eq!(config_names[9], "config-5");
     }
+
+    #[test]
+    fn test_profile_activations_are_not_cleaned_up() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = HistoryManager::new(
+            temp_dir.path().join("history.json"),
+            LockManager::new(temp_dir.path().join("locks")),
+        );
+        let details =
+            |from: Option<&str>, to: Option<&str>, extra: Option<&str>| OperationDetails {
+                from_config: from.map(String::from),
+                to_config: to.map(String::from),
+                backup_path: None,
+                extra: extra.map(String::from),
+            };
+
+        for i in 0..12 {
+            manager
+                .add(HistoryEntry::new(
+                    OperationType::Switch,
+                    details(None, Some(&format!("config-{}", i)), None),
+                    OperationResult::Success,
+                ))
+                .unwrap();
+        }
+        // 不改变全局配置的操作不记录
+        for (operation, details) in [
+            (
+                OperationType::LocalSwitch,
+                details(None, Some("local"), None),
+            ),
+            (
+                OperationType::Switch,
+                details(None, Some("legacy-local"), Some("项目级切换: /work/demo")),
+            ),
+            (OperationType::Revert, details(None, Some("restored"), None)),
+            (OperationType::Add, details(None, Some("added"), None)),
+        ] {
+            manager
+                .add(HistoryEntry::new(
+                    operation,
+                    details,
+                    OperationResult::Success,
+                ))
+                .unwrap();
+        }
+        manager
+            .add(HistoryEntry::new(
+                OperationType::Revert,
+                details(Some("config-11"), Some("config-10"), None),
+                OperationResult::Success,
+            ))
+            .unwrap();
+
+        assert_eq!(manager.load().unwrap().len(), 10);
+        let profiles: Vec<_> = manager
+            .load_activations()
+            .unwrap()
+            .into_iter()
+            .map(|a| a.profile)
+            .collect();
+        assert_eq!(profiles.len(), 13);
+        assert_eq!(profiles[0], "config-0");
+        assert_eq!(profiles[12], "config-10");
+    }
 }
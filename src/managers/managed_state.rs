@@ -0,0 +1,155 @@
+// 📋 CCR 写入记录 (managed state)
+// 记录每个 settings.json 中由 CCR 写入的内容，下次切换时据此精确移除
+//
+// - 🌍 env: 上次切换写入的非 ANTHROPIC_* 变量
+// - 🎁 settings: 上次切换写入的设置包片段
+//
+// 保存在 ~/.ccr/managed_settings.json，按 settings.json 的路径区分（用户级与项目级互不影响）。
+// 旧版本记录在 settings.json 的 env.CCR_MANAGED_ENV / env.CCR_MANAGED_SETTINGS 中，
+// 会作为环境变量传给 Claude Code 的子进程；加载时迁移到这里并从 env 中移除
+
+use crate::core::atomic_writer::AtomicWriter;
+use crate::core::error::{CcrError, Result};
+use crate::models::PlatformPaths;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 📄 记录文件在 ~/.ccr 下的文件名
+pub const STATE_FILE: &str = "managed_settings.json";
+
+/// 📋 单个设置文件中由 CCR 写入的内容
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ManagedState {
+    /// 🌍 上次切换写入的非 ANTHROPIC_* 变量
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+
+    /// 🎁 上次切换写入的设置片段
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub settings: Map<String, Value>,
+}
+
+impl ManagedState {
+    pub fn is_empty(&self) -> bool {
+        self.env.is_empty() && self.settings.is_empty()
+    }
+}
+
+/// 💾 写入记录存储
+///
+/// 读写需在设置文件锁内进行（由 SettingsManager 保证）
+pub struct ManagedStateStore {
+    path: PathBuf,
+}
+
+impl ManagedStateStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// 🏠 使用默认路径: ~/.ccr/managed_settings.json
+    pub fn with_default() -> Result<Self> {
+        Ok(Self::new(PlatformPaths::get_ccr_root()?.join(STATE_FILE)))
+    }
+
+    /// 📁 记录文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 📖 读取指定设置文件的记录（无记录时为空）
+    pub fn get(&self, settings_path: &Path) -> Result<ManagedState> {
+        Ok(self
+            .load_all()?
+            .remove(&state_key(settings_path))
+            .unwrap_or_default())
+    }
+
+    /// 💾 保存指定设置文件的记录（为空时删除该项，未变化时不写入）
+    pub fn set(&self, settings_path: &Path, state: &ManagedState) -> Result<()> {
+        let mut all = self.load_all()?;
+        let key = state_key(settings_path);
+        if all.get(&key).cloned().unwrap_or_default() == *state {
+            return Ok(());
+        }
+
+        if state.is_empty() {
+            all.remove(&key);
+        } else {
+            all.insert(key, state.clone());
+        }
+
+        let content = serde_json::to_string_pretty(&all)
+            .map_err(|e| CcrError::SettingsError(format!("序列化写入记录失败: {}", e)))?;
+        AtomicWriter::new(&self.path).write_string(&content)?;
+        tracing::debug!("📋 已更新写入记录: {:?}", settings_path);
+        Ok(())
+    }
+
+    fn load_all(&self) -> Result<BTreeMap<String, ManagedState>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| CcrError::SettingsError(format!("读取写入记录失败: {}", e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| CcrError::SettingsError(format!("解析写入记录失败: {}", e)))
+    }
+}
+
+/// 🔑 记录键: 设置文件的绝对路径（目录存在时解析符号链接）
+fn state_key(settings_path: &Path) -> String {
+    let resolved = match (settings_path.parent(), settings_path.file_name()) {
+        (Some(parent), Some(name)) => fs::canonicalize(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| settings_path.to_path_buf()),
+        _ => settings_path.to_path_buf(),
+    };
+    resolved.display().to_string()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_state_is_keyed_by_settings_path() {
+        let dir = TempDir::new().unwrap();
+        let store = ManagedStateStore::new(dir.path().join(STATE_FILE));
+        let user = dir.path().join("settings.json");
+        let project = dir.path().join("project/.claude/settings.local.json");
+
+        let mut state = ManagedState {
+            env: vec!["API_TIMEOUT_MS".into()],
+            settings: Map::new(),
+        };
+        state
+            .settings
+            .insert("outputStyle".into(), Value::from("Explanatory"));
+        store.set(&user, &state).unwrap();
+        assert_eq!(store.get(&user).unwrap(), state);
+        assert!(store.get(&project).unwrap().is_empty());
+
+        // 清空后删除该项
+        store.set(&user, &ManagedState::default()).unwrap();
+        assert!(store.get(&user).unwrap().is_empty());
+        assert_eq!(fs::read_to_string(store.path()).unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_empty_state_is_not_written() {
+        let dir = TempDir::new().unwrap();
+        let store = ManagedStateStore::new(dir.path().join(STATE_FILE));
+        store
+            .set(&dir.path().join("settings.json"), &ManagedState::default())
+            .unwrap();
+        assert!(!store.path().exists());
+    }
+}
@@ -110,6 +110,11 @@ impl McpPresetManager {
             Platform::Qwen => home.join(".qwen"),
             Platform::IFlow => home.join(".iflow"),
             Platform::Droid => home.join(".factory"),
+            Platform::Custom => {
+                return Err(CcrError::PlatformNotSupported(
+                    "自定义平台不支持 MCP 预设".to_string(),
+                ));
+            }
         };
 
         let ccr_dir = home.join(".ccr");
@@ -157,6 +162,9 @@ impl McpPresetManager {
             Platform::Qwen => self.install_to_qwen(&preset.id, &server_spec),
             Platform::IFlow => self.install_to_iflow(&preset.id, &server_spec),
             Platform::Droid => self.install_to_droid(&preset.id, &server_spec),
+            Platform::Custom => Err(CcrError::PlatformNotSupported(
+                "自定义平台不支持 MCP 预设".to_string(),
+            )),
         }
     }
 
@@ -169,6 +177,9 @@ impl McpPresetManager {
             Platform::Qwen => self.install_to_qwen(name, spec),
             Platform::IFlow => self.install_to_iflow(name, spec),
             Platform::Droid => self.install_to_droid(name, spec),
+            Platform::Custom => Err(CcrError::PlatformNotSupported(
+                "自定义平台不支持 MCP 预设".to_string(),
+            )),
         }
     }
 
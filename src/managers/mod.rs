@@ -18,15 +18,23 @@ pub mod config_validator;
 pub mod conflict_checker;
 pub mod cost_tracker;
 pub mod history;
+pub mod managed_state;
 pub mod mcp_preset_manager;
 pub mod platform_config;
 pub mod pricing_manager;
+pub mod profile_env;
+pub mod project_config;
 pub mod prompts_manager;
+pub mod secret_source;
 pub mod settings;
+pub mod settings_bundle;
 pub mod skills_manager;
+pub mod snapshot_store;
 pub mod sync_config;
 pub mod sync_folder_manager;
 pub mod temp_override;
+pub mod transcript_collector;
+pub mod vault;
 
 // 重新导出常用类型（供外部使用）
 // 注意: 这些导出是为了库的公共 API，即使在模块内未使用也需要保留
@@ -52,10 +60,27 @@ pub use platform_config::{PlatformConfigEntry, PlatformConfigManager, UnifiedCon
 #[allow(unused_imports)]
 pub use pricing_manager::PricingManager;
 #[allow(unused_imports)]
+pub use profile_env::{AuthMode, ModelMapping, ProfileEnv};
+#[allow(unused_imports)]
+pub use project_config::{
+    EffectiveProfile, PROJECT_CONFIG_FILE, ProfileSource, ProjectConfig, find_project_root,
+    project_settings_path, resolve_effective_profile,
+};
+#[allow(unused_imports)]
+pub use secret_source::{AUTH_TOKEN_CMD_KEY, is_indirect, resolve_value};
+#[allow(unused_imports)]
 pub use settings::{CachedSettingsManager, ClaudeSettings, SettingsManager};
 #[allow(unused_imports)]
+pub use settings_bundle::SettingsChange;
+#[allow(unused_imports)]
+pub use snapshot_store::{RetentionPolicy, Snapshot, SnapshotFile, SnapshotStore};
+#[allow(unused_imports)]
 pub use sync_config::{SyncConfig, SyncConfigManager};
 #[allow(unused_imports)]
 pub use sync_folder_manager::SyncFolderManager;
 #[allow(unused_imports)]
 pub use temp_override::{TempOverride, TempOverrideManager};
+#[allow(unused_imports)]
+pub use transcript_collector::{CollectReport, ProfileTimeline, TranscriptCollector};
+#[allow(unused_imports)]
+pub use vault::{VaultManager, is_vault_ref, resolve_secret};
@@ -0,0 +1,342 @@
+// 🌍 Profile 额外环境变量与模型映射
+// 📦 让 Claude profile 在 base_url / auth_token / model 之外携带更多设置
+//
+// 配置节中的额外键:
+// - `auth_mode`   凭证写入方式: "auth_token"（默认, ANTHROPIC_AUTH_TOKEN）或 "api_key"（ANTHROPIC_API_KEY）
+// - `[<配置节>.models]`  模型档位映射: opus / sonnet / haiku / subagent
+// - `[<配置节>.env]`     任意额外环境变量，如 API_TIMEOUT_MS
+//
+// 已知变量会检查取值格式；由 CCR 管理的变量（ANTHROPIC_BASE_URL 等）不能在 env 表中设置，
+// 需使用对应字段，避免同一变量有两个来源
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::settings_bundle::MANAGED_SETTINGS_VAR;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// 🔑 额外环境变量表在配置节中的键名
+pub const ENV_KEY: &str = "env";
+
+/// 🤖 模型映射表在配置节中的键名
+pub const MODELS_KEY: &str = "models";
+
+/// 🔐 凭证写入方式在配置节中的键名
+pub const AUTH_MODE_KEY: &str = "auth_mode";
+
+/// 📋 旧版本在 settings.json 的 env 中记录写入变量的变量，加载时迁移到 ~/.ccr 并移除
+pub const MANAGED_ENV_VAR: &str = "CCR_MANAGED_ENV";
+
+/// 🤖 模型档位 → 环境变量
+const MODEL_VARS: [&str; 4] = [
+    "ANTHROPIC_DEFAULT_OPUS_MODEL",
+    "ANTHROPIC_DEFAULT_SONNET_MODEL",
+    "ANTHROPIC_DEFAULT_HAIKU_MODEL",
+    "CLAUDE_CODE_SUBAGENT_MODEL",
+];
+
+/// 🔒 由配置节字段管理、不能在 env 表中设置的变量 (变量, 对应字段)
+const RESERVED_VARS: [(&str, &str); 11] = [
+    ("ANTHROPIC_BASE_URL", "base_url"),
+    ("ANTHROPIC_AUTH_TOKEN", "auth_token"),
+    ("ANTHROPIC_API_KEY", "auth_token + auth_mode = \"api_key\""),
+    ("ANTHROPIC_MODEL", "model"),
+    ("ANTHROPIC_SMALL_FAST_MODEL", "small_fast_model"),
+    ("ANTHROPIC_DEFAULT_OPUS_MODEL", "models.opus"),
+    ("ANTHROPIC_DEFAULT_SONNET_MODEL", "models.sonnet"),
+    ("ANTHROPIC_DEFAULT_HAIKU_MODEL", "models.haiku"),
+    ("CLAUDE_CODE_SUBAGENT_MODEL", "models.subagent"),
+    (MANAGED_ENV_VAR, "(CCR 内部使用)"),
+    (MANAGED_SETTINGS_VAR, "(CCR 内部使用)"),
+];
+
+/// 🔢 取值必须为正整数的已知变量
+const POSITIVE_INT_VARS: [&str; 9] = [
+    "API_TIMEOUT_MS",
+    "BASH_DEFAULT_TIMEOUT_MS",
+    "BASH_MAX_TIMEOUT_MS",
+    "CLAUDE_CODE_MAX_OUTPUT_TOKENS",
+    "CLAUDE_CODE_API_KEY_HELPER_TTL_MS",
+    "MAX_THINKING_TOKENS",
+    "MAX_MCP_OUTPUT_TOKENS",
+    "MCP_TIMEOUT",
+    "MCP_TOOL_TIMEOUT",
+];
+
+/// 🔘 取值必须为开关 (0 / 1 / true / false) 的已知变量
+const FLAG_VARS: [&str; 9] = [
+    "CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC",
+    "CLAUDE_CODE_USE_BEDROCK",
+    "CLAUDE_CODE_USE_VERTEX",
+    "DISABLE_AUTOUPDATER",
+    "DISABLE_COST_WARNINGS",
+    "DISABLE_ERROR_REPORTING",
+    "DISABLE_NON_ESSENTIAL_MODEL_CALLS",
+    "DISABLE_PROMPT_CACHING",
+    "DISABLE_TELEMETRY",
+];
+
+/// 🌐 取值必须为 URL 的已知变量
+const URL_VARS: [&str; 2] = ["HTTP_PROXY", "HTTPS_PROXY"];
+
+/// 🔐 凭证写入方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthMode {
+    /// 写入 ANTHROPIC_AUTH_TOKEN（Bearer 认证，中转常用）
+    #[default]
+    AuthToken,
+    /// 写入 ANTHROPIC_API_KEY（x-api-key 认证）
+    ApiKey,
+}
+
+impl AuthMode {
+    /// 🌍 auth_token 写入的环境变量
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            AuthMode::AuthToken => "ANTHROPIC_AUTH_TOKEN",
+            AuthMode::ApiKey => "ANTHROPIC_API_KEY",
+        }
+    }
+}
+
+/// 🤖 模型档位映射 (`[<配置节>.models]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelMapping {
+    /// → ANTHROPIC_DEFAULT_OPUS_MODEL
+    pub opus: Option<String>,
+    /// → ANTHROPIC_DEFAULT_SONNET_MODEL
+    pub sonnet: Option<String>,
+    /// → ANTHROPIC_DEFAULT_HAIKU_MODEL
+    pub haiku: Option<String>,
+    /// → CLAUDE_CODE_SUBAGENT_MODEL
+    pub subagent: Option<String>,
+}
+
+impl ModelMapping {
+    /// 📋 (环境变量, 值)，未设置的档位为 None
+    pub fn env_pairs(&self) -> [(&'static str, Option<&String>); 4] {
+        [
+            (MODEL_VARS[0], self.opus.as_ref()),
+            (MODEL_VARS[1], self.sonnet.as_ref()),
+            (MODEL_VARS[2], self.haiku.as_ref()),
+            (MODEL_VARS[3], self.subagent.as_ref()),
+        ]
+    }
+}
+
+/// 🌍 从配置节额外字段解析出的环境设置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileEnv {
+    pub auth_mode: AuthMode,
+    pub models: ModelMapping,
+    /// 额外环境变量
+    pub env: IndexMap<String, String>,
+}
+
+impl ProfileEnv {
+    /// 🔍 从配置节的额外字段 (other) 解析并校验
+    pub fn from_other(other: &IndexMap<String, toml::Value>) -> Result<Self> {
+        Ok(Self {
+            auth_mode: parse_auth_mode(other.get(AUTH_MODE_KEY))?,
+            models: parse_models(other.get(MODELS_KEY))?,
+            env: parse_env(other.get(ENV_KEY))?,
+        })
+    }
+}
+
+/// 📋 Claude profile 可能写入的全部固定变量（不含 env 表中的自定义变量）
+pub fn claude_env_var_names() -> Vec<&'static str> {
+    RESERVED_VARS
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| *name != MANAGED_ENV_VAR && *name != MANAGED_SETTINGS_VAR)
+        .collect()
+}
+
+/// 🤖 模型映射变量对应的档位 (opus / sonnet / haiku / subagent)
+pub fn model_tier(var: &str) -> Option<&'static str> {
+    const TIERS: [&str; 4] = ["opus", "sonnet", "haiku", "subagent"];
+    MODEL_VARS
+        .iter()
+        .position(|name| *name == var)
+        .map(|index| TIERS[index])
+}
+
+/// 🔐 解析 auth_mode
+fn parse_auth_mode(value: Option<&toml::Value>) -> Result<AuthMode> {
+    match value.map(|v| v.as_str()) {
+        None | Some(Some("auth_token")) => Ok(AuthMode::AuthToken),
+        Some(Some("api_key")) => Ok(AuthMode::ApiKey),
+        _ => Err(CcrError::ValidationError(
+            "auth_mode 只能为 \"auth_token\" 或 \"api_key\"".into(),
+        )),
+    }
+}
+
+/// 🤖 解析 models 表
+fn parse_models(value: Option<&toml::Value>) -> Result<ModelMapping> {
+    let Some(value) = value else {
+        return Ok(ModelMapping::default());
+    };
+    let models: ModelMapping = value.clone().try_into().map_err(|e| {
+        CcrError::ValidationError(format!(
+            "models 格式错误（可用键: opus / sonnet / haiku / subagent）: {}",
+            e
+        ))
+    })?;
+
+    for (var, model) in models.env_pairs() {
+        if model.is_some_and(|m| m.trim().is_empty()) {
+            return Err(CcrError::ValidationError(format!(
+                "models 中 {} 对应的模型不能为空字符串",
+                var
+            )));
+        }
+    }
+    Ok(models)
+}
+
+/// 🌍 解析 env 表，数字与布尔值转换为字符串
+fn parse_env(value: Option<&toml::Value>) -> Result<IndexMap<String, String>> {
+    let Some(value) = value else {
+        return Ok(IndexMap::new());
+    };
+    let table = value
+        .as_table()
+        .ok_or_else(|| CcrError::ValidationError("env 必须是表，如 [<配置节>.env]".into()))?;
+
+    let mut env = IndexMap::new();
+    for (name, value) in table {
+        validate_env_name(name)?;
+        let value = match value {
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Integer(i) => i.to_string(),
+            toml::Value::Float(f) => f.to_string(),
+            toml::Value::Boolean(b) => if *b { "1" } else { "0" }.to_string(),
+            _ => {
+                return Err(CcrError::ValidationError(format!(
+                    "env.{} 的值必须是字符串、数字或布尔值",
+                    name
+                )));
+            }
+        };
+        validate_known_value(name, &value)?;
+        env.insert(name.clone(), value);
+    }
+    Ok(env)
+}
+
+/// 🏷️ 检查变量名格式，并拒绝由配置节字段管理的变量
+fn validate_env_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(CcrError::ValidationError(format!(
+            "env 中的变量名无效: '{}'（只能包含字母、数字和下划线，且不能以数字开头）",
+            name
+        )));
+    }
+
+    if let Some((_, field)) = RESERVED_VARS.iter().find(|(var, _)| *var == name) {
+        return Err(CcrError::ValidationError(format!(
+            "{} 由 CCR 管理，不能在 env 中设置，请使用 {}",
+            name, field
+        )));
+    }
+    Ok(())
+}
+
+/// ✅ 检查已知变量的取值格式，未知变量不做检查
+fn validate_known_value(name: &str, value: &str) -> Result<()> {
+    if POSITIVE_INT_VARS.contains(&name) && !value.parse::<u64>().is_ok_and(|n| n > 0) {
+        return Err(CcrError::ValidationError(format!(
+            "env.{} 必须是正整数，当前为 '{}'",
+            name, value
+        )));
+    }
+    if FLAG_VARS.contains(&name) && !matches!(value, "0" | "1" | "true" | "false") {
+        return Err(CcrError::ValidationError(format!(
+            "env.{} 只能为 0 / 1 / true / false，当前为 '{}'",
+            name, value
+        )));
+    }
+    if URL_VARS.contains(&name) && !(value.starts_with("http://") || value.starts_with("https://"))
+    {
+        return Err(CcrError::ValidationError(format!(
+            "env.{} 必须以 http:// 或 https:// 开头",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn other(content: &str) -> IndexMap<String, toml::Value> {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_parse_full_profile_env() {
+        let parsed = ProfileEnv::from_other(&other(
+            r#"
+auth_mode = "api_key"
+
+[models]
+opus = "glm-4.6"
+subagent = "glm-4.5-air"
+
+[env]
+API_TIMEOUT_MS = 600000
+DISABLE_TELEMETRY = true
+CUSTOM_HEADER = "x"
+"#,
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.auth_mode, AuthMode::ApiKey);
+        assert_eq!(parsed.models.opus.as_deref(), Some("glm-4.6"));
+        assert_eq!(parsed.models.sonnet, None);
+        assert_eq!(parsed.env.len(), 3);
+        assert_eq!(parsed.env["API_TIMEOUT_MS"], "600000");
+        assert_eq!(parsed.env["DISABLE_TELEMETRY"], "1");
+        assert_eq!(parsed.env["CUSTOM_HEADER"], "x");
+    }
+
+    #[test]
+    fn test_defaults_when_absent() {
+        let parsed = ProfileEnv::from_other(&IndexMap::new()).unwrap();
+        assert_eq!(parsed, ProfileEnv::default());
+        assert_eq!(parsed.auth_mode.env_var(), "ANTHROPIC_AUTH_TOKEN");
+    }
+
+    #[test]
+    fn test_rejects_invalid_entries() {
+        for content in [
+            "auth_mode = \"bearer\"",
+            "[models]\ngpt = \"x\"",
+            "[models]\nopus = \" \"",
+            "env = \"A=1\"",
+            "[env]\n\"1BAD\" = \"x\"",
+            "[env]\nANTHROPIC_BASE_URL = \"https://x\"",
+            "[env]\nCLAUDE_CODE_SUBAGENT_MODEL = \"x\"",
+            "[env]\nAPI_TIMEOUT_MS = \"soon\"",
+            "[env]\nAPI_TIMEOUT_MS = 0",
+            "[env]\nDISABLE_TELEMETRY = \"yes\"",
+            "[env]\nHTTPS_PROXY = \"proxy:8080\"",
+            "[env]\nLIST = [1, 2]",
+        ] {
+            assert!(
+                ProfileEnv::from_other(&other(content)).is_err(),
+                "应拒绝: {}",
+                content
+            );
+        }
+    }
+}
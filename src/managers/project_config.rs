@@ -0,0 +1,320 @@
+// 📌 项目级配置固定模块
+// 📝 负责 `.ccr.toml` 项目文件的发现、读取与写入
+//
+// 核心职责:
+// - 🔍 从当前目录向上查找 `.ccr.toml`
+// - 📌 记录项目固定的 profile（以及可选的平台）
+// - 📁 定位项目级 Claude 设置文件 `<repo>/.claude/settings.local.json`
+// - 🧭 解析当前生效的 profile 及其来源（项目 / 全局）
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::platform_config::UnifiedConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Value};
+
+/// 📄 项目配置文件名
+pub const PROJECT_CONFIG_FILE: &str = ".ccr.toml";
+
+/// 📌 项目配置 (`.ccr.toml`)
+///
+/// 示例:
+/// ```toml
+/// profile = "client-relay"
+/// platform = "claude"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    /// 📝 项目固定的 profile 名称
+    pub profile: String,
+
+    /// 🔄 项目固定的平台（未设置时使用全局当前平台）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+}
+
+impl ProjectConfig {
+    /// 🏗️ 创建新的项目配置
+    pub fn new(profile: impl Into<String>, platform: Option<String>) -> Self {
+        Self {
+            profile: profile.into(),
+            platform,
+        }
+    }
+
+    /// 📖 从文件加载项目配置
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| CcrError::ConfigError(format!("读取 {} 失败: {}", path.display(), e)))?;
+
+        let config: Self = toml::from_str(&content).map_err(|e| {
+            CcrError::ConfigFormatInvalid(format!("项目配置 {} 无效: {}", path.display(), e))
+        })?;
+
+        if config.profile.trim().is_empty() {
+            return Err(CcrError::ConfigFormatInvalid(format!(
+                "项目配置 {} 的 profile 不能为空",
+                path.display()
+            )));
+        }
+
+        Ok(config)
+    }
+
+    /// 💾 保存项目配置
+    ///
+    /// 文件已存在时仅更新 `profile` / `platform` 两个键，保留注释和其他内容
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut doc = if path.exists() {
+            fs::read_to_string(path)
+                .map_err(|e| CcrError::ConfigError(format!("读取 {} 失败: {}", path.display(), e)))?
+                .parse::<DocumentMut>()
+                .map_err(|e| {
+                    CcrError::ConfigFormatInvalid(format!(
+                        "项目配置 {} 无效: {}",
+                        path.display(),
+                        e
+                    ))
+                })?
+        } else {
+            let mut doc = DocumentMut::new();
+            doc.decor_mut()
+                .set_prefix("# CCR 项目配置: 在此目录下固定使用的 profile\n");
+            doc
+        };
+
+        set_value_preserving_decor(&mut doc, "profile", &self.profile);
+        match &self.platform {
+            Some(platform) => set_value_preserving_decor(&mut doc, "platform", platform),
+            None => {
+                doc.remove("platform");
+            }
+        }
+
+        fs::write(path, doc.to_string())
+            .map_err(|e| CcrError::ConfigError(format!("写入 {} 失败: {}", path.display(), e)))?;
+
+        tracing::debug!("📌 项目配置已保存: {:?}", path);
+        Ok(())
+    }
+
+    /// 🔍 从指定目录向上查找 `.ccr.toml`
+    ///
+    /// 返回找到的配置文件路径与内容；一直查找到文件系统根目录
+    pub fn discover(start_dir: &Path) -> Result<Option<(PathBuf, Self)>> {
+        for dir in start_dir.ancestors() {
+            let candidate = dir.join(PROJECT_CONFIG_FILE);
+            if candidate.is_file() {
+                let config = Self::load(&candidate)?;
+                return Ok(Some((candidate, config)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// ✏️ 更新顶层字符串键，保留原值的行尾注释
+fn set_value_preserving_decor(doc: &mut DocumentMut, key: &str, value: &str) {
+    let mut new_value = Value::from(value);
+    if let Some(old) = doc.get(key).and_then(|item| item.as_value()) {
+        *new_value.decor_mut() = old.decor().clone();
+    }
+    doc[key] = Item::Value(new_value);
+}
+
+/// 📁 查找项目根目录
+///
+/// 优先级:
+/// 1. 最近的包含 `.ccr.toml` 的目录
+/// 2. 最近的包含 `.git` 的目录
+/// 3. 起始目录本身
+pub fn find_project_root(start_dir: &Path) -> PathBuf {
+    if let Some(dir) = start_dir
+        .ancestors()
+        .find(|dir| dir.join(PROJECT_CONFIG_FILE).is_file())
+    {
+        return dir.to_path_buf();
+    }
+
+    start_dir
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(start_dir)
+        .to_path_buf()
+}
+
+/// 📁 项目级 Claude 设置文件路径: `<root>/.claude/settings.local.json`
+pub fn project_settings_path(project_root: &Path) -> PathBuf {
+    project_root.join(".claude").join("settings.local.json")
+}
+
+/// 🧭 profile 来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileSource {
+    /// 📌 来自项目配置文件 (`.ccr.toml` 路径)
+    Project(PathBuf),
+
+    /// 🌐 来自全局注册表 (`~/.ccr/config.toml`)
+    Global,
+}
+
+impl fmt::Display for ProfileSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileSource::Project(path) => write!(f, "📌 项目 ({})", path.display()),
+            ProfileSource::Global => write!(f, "🌐 全局"),
+        }
+    }
+}
+
+/// 🎯 当前生效的 profile
+#[derive(Debug, Clone)]
+pub struct EffectiveProfile {
+    /// 🔄 生效的平台
+    pub platform: String,
+
+    /// 📝 生效的 profile（全局未设置时为 None）
+    pub profile: Option<String>,
+
+    /// 🧭 来源
+    pub source: ProfileSource,
+}
+
+impl EffectiveProfile {
+    /// 📁 项目根目录（仅项目来源）
+    pub fn project_root(&self) -> Option<&Path> {
+        match &self.source {
+            ProfileSource::Project(path) => path.parent(),
+            ProfileSource::Global => None,
+        }
+    }
+}
+
+/// 🧭 解析指定目录下生效的 profile
+///
+/// 项目 `.ccr.toml` 优先于全局注册表中的 current_profile
+pub fn resolve_effective_profile(
+    unified_config: &UnifiedConfig,
+    cwd: &Path,
+) -> Result<EffectiveProfile> {
+    if let Some((path, project)) = ProjectConfig::discover(cwd)? {
+        return Ok(EffectiveProfile {
+            platform: project
+                .platform
+                .unwrap_or_else(|| unified_config.current_platform.clone()),
+            profile: Some(project.profile),
+            source: ProfileSource::Project(path),
+        });
+    }
+
+    let platform = unified_config.current_platform.clone();
+    let profile = unified_config
+        .platforms
+        .get(&platform)
+        .and_then(|entry| entry.current_profile.clone());
+
+    Ok(EffectiveProfile {
+        platform,
+        profile,
+        source: ProfileSource::Global,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::managers::platform_config::PlatformConfigEntry;
+    use tempfile::TempDir;
+
+    fn unified_config() -> UnifiedConfig {
+        let mut config = UnifiedConfig::default();
+        config.platforms.insert(
+            "claude".into(),
+            PlatformConfigEntry {
+                current_profile: Some("global".into()),
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_save_and_discover_from_subdirectory() {
+        let temp = TempDir::new().unwrap();
+        let nested = temp.path().join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+
+        let path = temp.path().join(PROJECT_CONFIG_FILE);
+        ProjectConfig::new("client", Some("claude".into()))
+            .save(&path)
+            .unwrap();
+
+        let (found, config) = ProjectConfig::discover(&nested).unwrap().unwrap();
+        assert_eq!(found, path);
+        assert_eq!(config, ProjectConfig::new("client", Some("claude".into())));
+        assert_eq!(find_project_root(&nested), temp.path());
+    }
+
+    #[test]
+    fn test_save_preserves_comments() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(PROJECT_CONFIG_FILE);
+        fs::write(
+            &path,
+            "# 客户计费中转\nprofile = \"old\" # 固定\nplatform = \"codex\"\n",
+        )
+        .unwrap();
+
+        ProjectConfig::new("new", None).save(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("# 客户计费中转"));
+        assert!(content.contains("profile = \"new\" # 固定"));
+        assert!(!content.contains("platform"));
+    }
+
+    #[test]
+    fn test_find_project_root_prefers_git() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".git")).unwrap();
+        let nested = temp.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_project_root(&nested), temp.path());
+        assert_eq!(
+            project_settings_path(temp.path()),
+            temp.path().join(".claude").join("settings.local.json")
+        );
+    }
+
+    #[test]
+    fn test_resolve_effective_profile() {
+        let temp = TempDir::new().unwrap();
+        let config = unified_config();
+
+        let effective = resolve_effective_profile(&config, temp.path()).unwrap();
+        assert_eq!(effective.source, ProfileSource::Global);
+        assert_eq!(effective.profile.as_deref(), Some("global"));
+        assert!(effective.project_root().is_none());
+
+        let path = temp.path().join(PROJECT_CONFIG_FILE);
+        ProjectConfig::new("pinned", None).save(&path).unwrap();
+
+        let effective = resolve_effective_profile(&config, temp.path()).unwrap();
+        assert_eq!(effective.source, ProfileSource::Project(path));
+        assert_eq!(effective.platform, "claude");
+        assert_eq!(effective.profile.as_deref(), Some("pinned"));
+        assert_eq!(effective.project_root(), Some(temp.path()));
+    }
+
+    #[test]
+    fn test_load_rejects_empty_profile() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(PROJECT_CONFIG_FILE);
+        fs::write(&path, "profile = \"\"\n").unwrap();
+        assert!(ProjectConfig::load(&path).is_err());
+    }
+}
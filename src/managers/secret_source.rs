@@ -0,0 +1,344 @@
+// 🔑 动态密钥来源
+// 📦 让配置文件中不出现明文 token，在切换 / 健康检查时才解析真实值
+//
+// 支持的形式:
+// - `${env:VAR}`        读取环境变量（可嵌入在字符串中，如 "https://${env:RELAY_HOST}/v1"）
+// - `${file:~/path}`    读取文件内容（去除首尾空白）
+// - `auth_token_cmd`    执行命令并使用其标准输出作为 auth_token（如 "pass show relay/x"）
+// - `vault:<name>`      密钥保险库引用（见 vault 模块）
+//
+// 文件与命令的解析结果在进程内缓存一小段时间，避免健康检查等批量操作反复执行命令
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::vault;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// 🔑 凭证命令在配置节中的键名
+pub const AUTH_TOKEN_CMD_KEY: &str = "auth_token_cmd";
+
+/// ⏱️ 文件 / 命令解析结果的缓存时长
+pub const SECRET_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// 🏷️ 占位符类型: env / file / cmd
+const ENV_KIND: &str = "env";
+const FILE_KIND: &str = "file";
+const CMD_KIND: &str = "cmd";
+
+/// 💾 解析缓存: 来源 → (解析时间, 值)
+static CACHE: LazyLock<Mutex<HashMap<String, (Instant, String)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 🧩 字符串中的一个占位符 `${kind:arg}`
+struct Placeholder<'a> {
+    /// 在原字符串中的字节范围
+    start: usize,
+    end: usize,
+    kind: &'a str,
+    arg: &'a str,
+}
+
+/// 🔍 查找字符串中的所有 `${env:..}` / `${file:..}` / `${cmd:..}` 占位符
+///
+/// 未闭合或参数为空的占位符返回错误
+fn find_placeholders(value: &str) -> Result<Vec<Placeholder<'_>>> {
+    let mut placeholders = Vec::new();
+    let mut offset = 0;
+
+    while let Some(pos) = value[offset..].find("${") {
+        let start = offset + pos;
+        let body_start = start + 2;
+        let Some((kind, _)) = value[body_start..].split_once(':') else {
+            offset = body_start;
+            continue;
+        };
+        if ![ENV_KIND, FILE_KIND, CMD_KIND].contains(&kind) {
+            offset = body_start;
+            continue;
+        }
+
+        let arg_start = body_start + kind.len() + 1;
+        let close = value[arg_start..].find('}').ok_or_else(|| {
+            CcrError::ValidationError(format!("占位符 '${{{}:' 缺少结尾的 '}}'", kind))
+        })?;
+        let arg = value[arg_start..arg_start + close].trim();
+        if arg.is_empty() {
+            return Err(CcrError::ValidationError(format!(
+                "占位符 '${{{}:}}' 缺少参数",
+                kind
+            )));
+        }
+
+        let end = arg_start + close + 1;
+        placeholders.push(Placeholder {
+            start,
+            end,
+            kind,
+            arg,
+        });
+        offset = end;
+    }
+
+    Ok(placeholders)
+}
+
+/// 🔍 值是否为间接形式（需要在使用时解析）
+pub fn is_indirect(value: &str) -> bool {
+    vault::is_vault_ref(value)
+        || find_placeholders(value)
+            .map(|placeholders| !placeholders.is_empty())
+            .unwrap_or(true)
+}
+
+/// ✅ 校验间接形式的语法（不执行解析）
+///
+/// `${cmd:..}` 只能作为完整的值出现
+pub fn validate_reference(value: &str) -> Result<()> {
+    let placeholders = find_placeholders(value)?;
+    if placeholders
+        .iter()
+        .any(|p| p.kind == CMD_KIND && (p.start != 0 || p.end != value.len()))
+    {
+        return Err(CcrError::ValidationError(
+            "命令来源不能与其他文本拼接，请使用 auth_token_cmd".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// 🔑 合并 auth_token 与 auth_token_cmd，返回统一的 auth_token 值
+///
+/// 两者同时设置时 auth_token 优先；仅设置命令时返回 `${cmd:<命令>}`
+pub fn effective_auth_token(
+    auth_token: Option<&str>,
+    auth_token_cmd: Option<&str>,
+) -> Option<String> {
+    match (auth_token, auth_token_cmd.map(str::trim)) {
+        (Some(token), _) => Some(token.to_string()),
+        (None, Some(cmd)) if !cmd.is_empty() => Some(format!("${{{}:{}}}", CMD_KIND, cmd)),
+        _ => None,
+    }
+}
+
+/// 🔓 解析配置值中的所有间接形式
+///
+/// - `vault:<name>` 解析保险库
+/// - `${cmd:..}` 执行命令（结果缓存）
+/// - `${env:..}` / `${file:..}` 逐个替换（文件内容缓存）
+pub fn resolve_value(value: &str) -> Result<String> {
+    if vault::is_vault_ref(value) {
+        return vault::resolve_secret(value);
+    }
+
+    validate_reference(value).map_err(|e| match e {
+        CcrError::ValidationError(msg) => CcrError::SecretResolutionError(msg),
+        e => e,
+    })?;
+    let placeholders = find_placeholders(value)?;
+    if placeholders.is_empty() {
+        return Ok(value.to_string());
+    }
+
+    let mut resolved = String::with_capacity(value.len());
+    let mut last = 0;
+    for placeholder in &placeholders {
+        resolved.push_str(&value[last..placeholder.start]);
+        let part = match placeholder.kind {
+            ENV_KIND => resolve_env(placeholder.arg)?,
+            FILE_KIND => cached(value_key(FILE_KIND, placeholder.arg), || {
+                resolve_file(placeholder.arg)
+            })?,
+            _ => cached(value_key(CMD_KIND, placeholder.arg), || {
+                run_credential_command(placeholder.arg)
+            })?,
+        };
+        resolved.push_str(&part);
+        last = placeholder.end;
+    }
+    resolved.push_str(&value[last..]);
+
+    Ok(resolved)
+}
+
+/// 🔓 解析可选配置值（None 保持 None）
+pub fn resolve_optional(value: &Option<String>) -> Result<Option<String>> {
+    value.as_deref().map(resolve_value).transpose()
+}
+
+/// 🧹 清空解析缓存
+#[allow(dead_code)]
+pub fn clear_cache() {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.clear();
+    }
+}
+
+fn value_key(kind: &str, arg: &str) -> String {
+    format!("{}:{}", kind, arg)
+}
+
+/// 💾 带 TTL 的缓存读取
+fn cached(key: String, resolve: impl FnOnce() -> Result<String>) -> Result<String> {
+    if let Ok(cache) = CACHE.lock()
+        && let Some((at, value)) = cache.get(&key)
+        && at.elapsed() < SECRET_CACHE_TTL
+    {
+        return Ok(value.clone());
+    }
+
+    let value = resolve()?;
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.insert(key, (Instant::now(), value.clone()));
+    }
+    Ok(value)
+}
+
+/// 🌍 读取环境变量
+fn resolve_env(name: &str) -> Result<String> {
+    match std::env::var(name) {
+        Ok(value) if !value.is_empty() => Ok(value),
+        Ok(_) => Err(CcrError::SecretResolutionError(format!(
+            "环境变量 {} 为空",
+            name
+        ))),
+        Err(_) => Err(CcrError::SecretResolutionError(format!(
+            "环境变量 {} 未设置",
+            name
+        ))),
+    }
+}
+
+/// 📄 读取文件内容（支持 ~/ 开头的路径）
+fn resolve_file(path: &str) -> Result<String> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .ok_or_else(|| CcrError::SecretResolutionError("无法获取用户主目录".into()))?
+            .join(rest),
+        None => PathBuf::from(path),
+    };
+
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        CcrError::SecretResolutionError(format!("读取 {} 失败: {}", path.display(), e))
+    })?;
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(CcrError::SecretResolutionError(format!(
+            "文件 {} 为空",
+            path.display()
+        )));
+    }
+    Ok(content.to_string())
+}
+
+/// 🧰 执行凭证命令，返回去除首尾空白的标准输出
+fn run_credential_command(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|e| CcrError::SecretResolutionError(format!("无法执行 '{}': {}", command, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CcrError::SecretResolutionError(format!(
+            "命令 '{}' 执行失败 ({}): {}",
+            command,
+            output.status,
+            stderr.trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // pass 等工具在第一行输出密码，其余行为附加信息
+    let value = stdout.lines().next().unwrap_or_default().trim();
+    if value.is_empty() {
+        return Err(CcrError::SecretResolutionError(format!(
+            "命令 '{}' 没有输出",
+            command
+        )));
+    }
+
+    tracing::debug!("🔑 已通过命令解析凭证: {}", command);
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_env_interpolation() {
+        // SAFETY: 测试使用唯一的变量名，不与其他测试共享
+        unsafe { std::env::set_var("CCR_TEST_SECRET_HOST", "relay.example.com") };
+
+        assert_eq!(
+            resolve_value("https://${env:CCR_TEST_SECRET_HOST}/v1").unwrap(),
+            "https://relay.example.com/v1"
+        );
+        assert!(matches!(
+            resolve_value("${env:CCR_TEST_SECRET_MISSING}"),
+            Err(CcrError::SecretResolutionError(_))
+        ));
+        assert_eq!(resolve_value("sk-plain").unwrap(), "sk-plain");
+    }
+
+    #[test]
+    fn test_file_source() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("token");
+        std::fs::write(&path, "sk-from-file\n").unwrap();
+
+        let value = format!("${{file:{}}}", path.display());
+        assert!(is_indirect(&value));
+        assert_eq!(resolve_value(&value).unwrap(), "sk-from-file");
+
+        let missing = format!("${{file:{}}}", temp.path().join("none").display());
+        assert!(resolve_value(&missing).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_source_and_cache() {
+        let temp = TempDir::new().unwrap();
+        let counter = temp.path().join("count");
+        let cmd = format!("echo x >> {} && echo sk-from-cmd", counter.display());
+
+        let token = effective_auth_token(None, Some(&cmd)).unwrap();
+        assert_eq!(resolve_value(&token).unwrap(), "sk-from-cmd");
+        assert_eq!(resolve_value(&token).unwrap(), "sk-from-cmd");
+        // 第二次命中缓存，命令只执行一次
+        assert_eq!(
+            std::fs::read_to_string(&counter).unwrap().lines().count(),
+            1
+        );
+
+        let failing = effective_auth_token(None, Some("exit 3")).unwrap();
+        assert!(matches!(
+            resolve_value(&failing),
+            Err(CcrError::SecretResolutionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_reference() {
+        assert!(validate_reference("${env:TOKEN}").is_ok());
+        assert!(validate_reference("https://${env:HOST}/v1").is_ok());
+        assert!(validate_reference("${env:TOKEN").is_err());
+        assert!(validate_reference("${file:}").is_err());
+        assert!(validate_reference("prefix-${cmd:pass show x}").is_err());
+        assert!(!is_indirect("sk-ant-123"));
+        assert!(is_indirect("vault:relay"));
+
+        assert_eq!(
+            effective_auth_token(Some("sk-a"), Some("pass show x")).as_deref(),
+            Some("sk-a")
+        );
+        assert_eq!(effective_auth_token(None, Some("  ")), None);
+    }
+}
@@ -14,6 +14,9 @@ use crate::core::cache::ConfigCache;
 use crate::core::error::{CcrError, Result};
 use crate::core::lock::LockManager;
 use crate::managers::config::ConfigSection;
+use crate::managers::managed_state::{ManagedState, ManagedStateStore};
+use crate::managers::profile_env::{self, MANAGED_ENV_VAR};
+use crate::managers::settings_bundle::{self, MANAGED_SETTINGS_VAR};
 use crate::utils::Validatable;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -27,8 +30,7 @@ use tokio::fs as async_fs;
 // 🎯 优化：定义常量避免重复分配字符串
 const ANTHROPIC_BASE_URL: &str = "ANTHROPIC_BASE_URL";
 const ANTHROPIC_AUTH_TOKEN: &str = "ANTHROPIC_AUTH_TOKEN";
-const ANTHROPIC_MODEL: &str = "ANTHROPIC_MODEL";
-const ANTHROPIC_SMALL_FAST_MODEL: &str = "ANTHROPIC_SMALL_FAST_MODEL";
+const ANTHROPIC_API_KEY: &str = "ANTHROPIC_API_KEY";
 
 /// 🎨 Claude Code 设置结构
 ///
@@ -37,6 +39,7 @@ const ANTHROPIC_SMALL_FAST_MODEL: &str = "ANTHROPIC_SMALL_FAST_MODEL";
 /// 字段说明:
 /// - 🌍 env: 环境变量映射(包含 ANTHROPIC_* 变量)
 /// - 📦 other: 其他未知字段(保持原样,向前兼容)
+/// - 📋 managed: CCR 写入记录(不写入 settings.json，由 SettingsManager 单独保存)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeSettings {
     /// 🌍 环境变量配置字典
@@ -46,6 +49,10 @@ pub struct ClaudeSettings {
     /// 📦 其他设置字段(扁平化存储,保持原样)
     #[serde(flatten)]
     pub other: HashMap<String, Value>,
+
+    /// 📋 上次切换由 CCR 写入的内容
+    #[serde(skip)]
+    pub managed: ManagedState,
 }
 
 impl ClaudeSettings {
@@ -54,73 +61,124 @@ impl ClaudeSettings {
         Self {
             env: HashMap::new(),
             other: HashMap::new(),
+            managed: ManagedState::default(),
         }
     }
 
     /// 🧹 清空所有 ANTHROPIC_ 前缀的环境变量
     ///
-    /// 保留其他环境变量,只删除 ANTHROPIC_* 相关的
+    /// 保留其他环境变量,只删除 ANTHROPIC_* 相关的,
+    /// 以及上次切换时写入的额外变量
     pub fn clear_anthropic_vars(&mut self) {
-        self.env.retain(|key, _| !key.starts_with("ANTHROPIC_"));
+        let managed = std::mem::take(&mut self.managed.env);
+        self.env
+            .retain(|key, _| !key.starts_with("ANTHROPIC_") && !managed.contains(key));
         tracing::debug!("🧹 清空所有 ANTHROPIC_* 环境变量");
     }
 
+    /// 📋 上次切换写入的非 ANTHROPIC_* 变量
+    pub fn managed_env_vars(&self) -> Vec<String> {
+        self.managed.env.clone()
+    }
+
     /// 🔄 从配置节更新环境变量
     ///
     /// 执行流程:
-    /// 1. 🧹 先清空所有旧的 ANTHROPIC_* 变量
+    /// 1. 🧹 先清空所有旧的 ANTHROPIC_* 变量及上次写入的额外变量
     /// 2. ➕ 根据配置节设置新的环境变量
+    /// 3. 📋 将非 ANTHROPIC_* 变量记录到 managed 中，供下次切换清除
     ///
     /// 映射关系:
     /// - base_url → ANTHROPIC_BASE_URL
-    /// - auth_token → ANTHROPIC_AUTH_TOKEN
+    /// - auth_token → ANTHROPIC_AUTH_TOKEN（auth_mode = "api_key" 时为 ANTHROPIC_API_KEY）
     /// - model → ANTHROPIC_MODEL
     /// - small_fast_model → ANTHROPIC_SMALL_FAST_MODEL
+    /// - models.* → ANTHROPIC_DEFAULT_*_MODEL / CLAUDE_CODE_SUBAGENT_MODEL
+    /// - env.* → 原样写入
     pub fn update_from_config(&mut self, section: &ConfigSection) {
         // 🧹 清空旧的 ANTHROPIC_* 变量
         self.clear_anthropic_vars();
 
-        // 🌐 设置 base_url
-        if let Some(base_url) = &section.base_url {
-            self.env
-                .insert(ANTHROPIC_BASE_URL.to_string(), base_url.clone());
+        let mut managed = Vec::new();
+        for (name, value) in section.claude_env() {
+            if !name.starts_with("ANTHROPIC_") {
+                managed.push(name.clone());
+            }
+            self.env.insert(name, value);
         }
 
-        // 🔑 设置 auth_token
-        if let Some(auth_token) = &section.auth_token {
-            self.env
-                .insert(ANTHROPIC_AUTH_TOKEN.to_string(), auth_token.clone());
-        }
+        self.managed.env = managed;
 
-        // 🤖 设置 model
-        if let Some(model) = &section.model {
-            self.env.insert(ANTHROPIC_MODEL.to_string(), model.clone());
-        }
+        tracing::info!("✅ 环境变量已从配置更新");
+    }
 
-        // ⚡ 设置 small_fast_model
-        if let Some(small_model) = &section.small_fast_model {
-            self.env
-                .insert(ANTHROPIC_SMALL_FAST_MODEL.to_string(), small_model.clone());
+    /// 🎁 上次切换写入的设置片段
+    pub fn managed_settings(&self) -> serde_json::Map<String, Value> {
+        self.managed.settings.clone()
+    }
+
+    /// 🚚 迁移旧版本记录在 env.CCR_MANAGED_ENV / env.CCR_MANAGED_SETTINGS 中的内容
+    ///
+    /// 这些变量会随 env 传给 Claude Code 的子进程，迁移后从 env 中移除
+    fn migrate_legacy_managed(&mut self) {
+        if let Some(list) = self.env.remove(MANAGED_ENV_VAR)
+            && self.managed.env.is_empty()
+        {
+            self.managed.env = list
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        if let Some(json) = self.env.remove(MANAGED_SETTINGS_VAR)
+            && self.managed.settings.is_empty()
+            && let Ok(Value::Object(map)) = serde_json::from_str::<Value>(&json)
+        {
+            self.managed.settings = map;
         }
+    }
 
-        tracing::info!("✅ 环境变量已从配置更新");
+    /// 🎁 应用设置包
+    ///
+    /// 先移除上次写入的片段，再深度合并新片段（None 表示只移除），
+    /// 并将实际写入的部分记录到 managed 中
+    pub fn apply_bundle(&mut self, fragment: Option<&Value>) {
+        let previous = self.managed_settings();
+        let mut root: serde_json::Map<String, Value> = self.other.drain().collect();
+
+        settings_bundle::unmerge(&mut root, &previous);
+        let owned = fragment
+            .and_then(Value::as_object)
+            .map(|fragment| settings_bundle::merge(&mut root, fragment))
+            .unwrap_or_default();
+
+        self.other = root.into_iter().collect();
+        self.managed.settings = owned;
+        tracing::debug!("🎁 设置包已更新");
     }
 
-    /// 📊 获取 ANTHROPIC_* 环境变量状态(用于展示)
+    /// 🔄 切换时应用配置节: 环境变量 + 设置包
+    pub fn apply_section(&mut self, section: &ConfigSection) -> Result<()> {
+        let bundle = section.settings_bundle()?;
+        self.update_from_config(section);
+        self.apply_bundle(bundle.as_ref());
+        Ok(())
+    }
+
+    /// 📊 获取 CCR 管理的环境变量状态(用于展示)
     ///
-    /// 返回所有 ANTHROPIC 相关变量的当前值或 None
+    /// 返回所有 ANTHROPIC 相关变量、模型映射变量及额外变量的当前值或 None
     pub fn anthropic_env_status(&self) -> HashMap<String, Option<String>> {
         let mut status = HashMap::new();
-        let vars = [
-            ANTHROPIC_BASE_URL,
-            ANTHROPIC_AUTH_TOKEN,
-            ANTHROPIC_MODEL,
-            ANTHROPIC_SMALL_FAST_MODEL,
-        ];
-
-        for var in vars {
+
+        for var in profile_env::claude_env_var_names() {
             status.insert(var.to_string(), self.env.get(var).cloned());
         }
+        for var in self.managed_env_vars() {
+            let value = self.env.get(&var).cloned();
+            status.insert(var, value);
+        }
 
         status
     }
@@ -137,9 +195,16 @@ impl Validatable for ClaudeSettings {
     ///
     /// 必需变量:
     /// - ANTHROPIC_BASE_URL
-    /// - ANTHROPIC_AUTH_TOKEN
+    /// - ANTHROPIC_AUTH_TOKEN 或 ANTHROPIC_API_KEY
     fn validate(&self) -> Result<()> {
-        let required_vars = [ANTHROPIC_BASE_URL, ANTHROPIC_AUTH_TOKEN];
+        let auth_var = if self.env.contains_key(ANTHROPIC_AUTH_TOKEN) {
+            ANTHROPIC_AUTH_TOKEN
+        } else if self.env.contains_key(ANTHROPIC_API_KEY) {
+            ANTHROPIC_API_KEY
+        } else {
+            ANTHROPIC_AUTH_TOKEN
+        };
+        let required_vars = [ANTHROPIC_BASE_URL, auth_var];
 
         for var in required_vars {
             match self.env.get(var) {
@@ -177,6 +242,8 @@ pub struct SettingsManager {
     settings_path: PathBuf,
     backup_dir: PathBuf,
     lock_manager: LockManager,
+    /// 📋 写入记录文件（None 时使用 ~/.ccr/managed_settings.json）
+    state_path: Option<PathBuf>,
 }
 
 impl SettingsManager {
@@ -190,9 +257,17 @@ impl SettingsManager {
             settings_path: settings_path.as_ref().to_path_buf(),
             backup_dir: backup_dir.as_ref().to_path_buf(),
             lock_manager,
+            state_path: None,
         }
     }
 
+    /// 📋 指定写入记录文件（默认 ~/.ccr/managed_settings.json）
+    #[allow(dead_code)]
+    pub fn with_state_path<P: AsRef<Path>>(mut self, state_path: P) -> Self {
+        self.state_path = Some(state_path.as_ref().to_path_buf());
+        self
+    }
+
     /// 🏠 使用默认路径创建管理器
     ///
     /// 默认路径:
@@ -241,6 +316,21 @@ impl SettingsManager {
         &self.settings_path
     }
 
+    /// 📋 写入记录存储
+    pub fn managed_store(&self) -> Result<ManagedStateStore> {
+        match &self.state_path {
+            Some(path) => Ok(ManagedStateStore::new(path)),
+            None => ManagedStateStore::with_default(),
+        }
+    }
+
+    /// 📋 附加写入记录，并迁移旧版本记录在 env 中的内容
+    fn attach_managed(&self, settings: &mut ClaudeSettings) -> Result<()> {
+        settings.managed = self.managed_store()?.get(&self.settings_path)?;
+        settings.migrate_legacy_managed();
+        Ok(())
+    }
+
     /// 📖 加载设置文件
     ///
     /// 执行步骤:
@@ -260,8 +350,9 @@ impl SettingsManager {
             .map_err(|e| CcrError::SettingsError(format!("读取设置文件失败: {}", e)))?;
 
         // 🔍 解析 JSON
-        let settings: ClaudeSettings = serde_json::from_str(&content)
+        let mut settings: ClaudeSettings = serde_json::from_str(&content)
             .map_err(|e| CcrError::SettingsError(format!("解析设置文件失败: {}", e)))?;
+        self.attach_managed(&mut settings)?;
 
         tracing::debug!("✅ 成功加载设置文件: {:?}", self.settings_path);
         Ok(settings)
@@ -282,8 +373,9 @@ impl SettingsManager {
             .await
             .map_err(|e| CcrError::SettingsError(format!("读取设置文件失败: {}", e)))?;
 
-        let settings: ClaudeSettings = serde_json::from_str(&content)
+        let mut settings: ClaudeSettings = serde_json::from_str(&content)
             .map_err(|e| CcrError::SettingsError(format!("解析设置文件失败: {}", e)))?;
+        self.attach_managed(&mut settings)?;
 
         tracing::debug!("✅ 成功加载设置文件: {:?}", self.settings_path);
         Ok(settings)
@@ -299,6 +391,7 @@ impl SettingsManager {
     /// 3. 📝 序列化为 JSON(美化格式)
     /// 4. 📄 写入临时文件
     /// 5. 🔄 原子替换(rename)
+    /// 6. 📋 保存 CCR 写入记录
     ///
     /// 原子性保证:
     /// - 使用 tempfile + persist 实现原子替换
@@ -333,6 +426,10 @@ impl SettingsManager {
             .persist(&self.settings_path)
             .map_err(|e| CcrError::SettingsError(format!("原子替换文件失败: {}", e)))?;
 
+        // 📋 保存写入记录
+        self.managed_store()?
+            .set(&self.settings_path, &settings.managed)?;
+
         tracing::info!("✅ 设置文件已原子保存: {:?}", self.settings_path);
         Ok(())
     }
@@ -349,6 +446,8 @@ impl SettingsManager {
             .write_string_async(&content)
             .await
             .map_err(|e| CcrError::SettingsError(format!("原子保存设置失败: {}", e)))?;
+        self.managed_store()?
+            .set(&self.settings_path, &settings.managed)?;
 
         tracing::info!("✅ 设置文件已原子保存: {:?}", self.settings_path);
         Ok(())
@@ -612,7 +711,7 @@ impl SettingsManager {
             }
         }
 
-        backups.sort_by(|a, b| b.1.cmp(&a.1));
+        backups.sort_by_key(|b| std::cmp::Reverse(b.1));
         Ok(backups.into_iter().map(|(path, _)| path).collect())
     }
 
@@ -924,6 +1023,53 @@ mod tests {
         assert!(settings.env.contains_key("OTHER_VAR"));
     }
 
+    #[test]
+    fn test_update_from_config_replaces_previous_extra_env() {
+        let mut config = create_test_config_section();
+        config.other = toml::from_str(
+            r#"
+auth_mode = "api_key"
+
+[models]
+opus = "glm-4.6"
+subagent = "glm-4.5-air"
+
+[env]
+API_TIMEOUT_MS = 600000
+"#,
+        )
+        .unwrap();
+
+        let mut settings = ClaudeSettings::new();
+        settings.env.insert("USER_VAR".into(), "keep".into());
+        settings.update_from_config(&config);
+
+        assert_eq!(settings.env["ANTHROPIC_API_KEY"], "sk-test-token");
+        assert!(!settings.env.contains_key("ANTHROPIC_AUTH_TOKEN"));
+        assert_eq!(settings.env["ANTHROPIC_DEFAULT_OPUS_MODEL"], "glm-4.6");
+        assert_eq!(settings.env["CLAUDE_CODE_SUBAGENT_MODEL"], "glm-4.5-air");
+        assert_eq!(settings.env["API_TIMEOUT_MS"], "600000");
+        assert_eq!(
+            settings.managed_env_vars(),
+            ["CLAUDE_CODE_SUBAGENT_MODEL", "API_TIMEOUT_MS"]
+        );
+        assert!(!settings.env.contains_key(MANAGED_ENV_VAR));
+        assert!(settings.validate().is_ok());
+
+        // 下次切换到没有额外变量的配置时，上次写入的变量全部清除
+        settings.update_from_config(&create_test_config_section());
+        assert_eq!(settings.env["ANTHROPIC_AUTH_TOKEN"], "sk-test-token");
+        for stale in [
+            "ANTHROPIC_API_KEY",
+            "ANTHROPIC_DEFAULT_OPUS_MODEL",
+            "CLAUDE_CODE_SUBAGENT_MODEL",
+            "API_TIMEOUT_MS",
+        ] {
+            assert!(!settings.env.contains_key(stale), "{} 未被清除", stale);
+        }
+        assert_eq!(settings.env["USER_VAR"], "keep");
+    }
+
     #[test]
     fn test_claude_settings_validate() {
         let mut settings = ClaudeSettings::new();
@@ -966,6 +1112,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_settings_manager_keeps_managed_state_outside_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        let state_path = temp_dir.path().join("managed_settings.json");
+        let manager = SettingsManager::new(
+            &settings_path,
+            temp_dir.path().join("backups"),
+            LockManager::new(temp_dir.path().join("locks")),
+        )
+        .with_state_path(&state_path);
+
+        let mut settings = ClaudeSettings::new();
+        settings.apply_bundle(Some(&serde_json::json!({"outputStyle": "Explanatory"})));
+        manager.save_atomic(&settings).unwrap();
+
+        let content = fs::read_to_string(&settings_path).unwrap();
+        assert!(!content.contains(MANAGED_SETTINGS_VAR));
+        assert!(state_path.exists());
+
+        // 重新加载后仍能移除上次写入的片段
+        let mut loaded = manager.load().unwrap();
+        assert_eq!(loaded.managed_settings().len(), 1);
+        loaded.apply_bundle(None);
+        manager.save_atomic(&loaded).unwrap();
+        assert!(
+            !fs::read_to_string(&settings_path)
+                .unwrap()
+                .contains("outputStyle")
+        );
+        assert!(manager.load().unwrap().managed.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_managed_env_is_migrated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        let manager = SettingsManager::new(
+            &settings_path,
+            temp_dir.path().join("backups"),
+            LockManager::new(temp_dir.path().join("locks")),
+        )
+        .with_state_path(temp_dir.path().join("managed_settings.json"));
+
+        // 旧版本把写入的变量记录在 env.CCR_MANAGED_ENV 中
+        fs::write(
+            &settings_path,
+            r#"{"env":{"API_TIMEOUT_MS":"600000","USER_VAR":"keep","CCR_MANAGED_ENV":"API_TIMEOUT_MS"}}"#,
+        )
+        .unwrap();
+
+        let mut settings = manager.load().unwrap();
+        assert!(!settings.env.contains_key(MANAGED_ENV_VAR));
+        assert_eq!(settings.managed_env_vars(), ["API_TIMEOUT_MS"]);
+
+        settings.update_from_config(&create_test_config_section());
+        manager.save_atomic(&settings).unwrap();
+        let content = fs::read_to_string(&settings_path).unwrap();
+        assert!(!content.contains("API_TIMEOUT_MS"));
+        assert!(!content.contains(MANAGED_ENV_VAR));
+        assert!(content.contains("USER_VAR"));
+    }
+
     #[test]
     fn test_settings_manager_backup_restore() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,364 @@
+// 🎁 设置包 (settings bundle)
+// 📦 切换 profile 时除凭证外，一并应用 permissions / hooks / statusLine / outputStyle 等 settings.json 片段
+//
+// 片段来源（按顺序深度合并，后者覆盖前者）:
+// - `bundle = "<文件>"`          JSON 文件；相对路径基于 ~/.ccr/bundles/，支持 ~/ 开头
+// - `[<配置节>.settings]`        配置节内联的 TOML 表
+//
+// 合并规则:
+// - 🧩 对象按键递归合并，数组追加不存在的元素，其余值直接覆盖
+// - 📋 实际写入的部分记录在 ~/.ccr/managed_settings.json 中（按 settings.json 路径区分），
+//   下次切换时先移除，用户之后修改过的值保持不变
+// - 🚫 片段不能包含 env（请使用 `[<配置节>.env]`）
+
+use crate::core::error::{CcrError, Result};
+use crate::models::PlatformPaths;
+use crate::utils::mask_if_sensitive;
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+
+/// 🧩 内联设置片段在配置节中的键名
+pub const SETTINGS_KEY: &str = "settings";
+
+/// 📄 设置包文件在配置节中的键名
+pub const BUNDLE_KEY: &str = "bundle";
+
+/// 📋 旧版本在 settings.json 的 env 中记录上次写入片段的变量，加载时迁移到 ~/.ccr 并移除
+pub const MANAGED_SETTINGS_VAR: &str = "CCR_MANAGED_SETTINGS";
+
+/// 🔍 JSON 差异中的一项变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsChange {
+    /// 点分路径，数组元素以 `[]` 结尾，如 `permissions.allow[]`
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// 🔍 解析配置节中的内联片段并校验
+pub fn inline_fragment(other: &IndexMap<String, toml::Value>) -> Result<Option<Value>> {
+    let Some(value) = other.get(SETTINGS_KEY) else {
+        return Ok(None);
+    };
+    if !value.is_table() {
+        return Err(CcrError::ValidationError(
+            "settings 必须是表，如 [<配置节>.settings]".into(),
+        ));
+    }
+    let fragment = serde_json::to_value(value)
+        .map_err(|e| CcrError::ValidationError(format!("settings 无法转换为 JSON: {}", e)))?;
+    validate_fragment(&fragment, SETTINGS_KEY)?;
+    Ok(Some(fragment))
+}
+
+/// 📄 设置包文件路径（未设置时为 None）
+pub fn bundle_path(other: &IndexMap<String, toml::Value>) -> Result<Option<PathBuf>> {
+    let Some(value) = other.get(BUNDLE_KEY) else {
+        return Ok(None);
+    };
+    let path = value
+        .as_str()
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| CcrError::ValidationError("bundle 必须是非空的文件路径".into()))?;
+
+    let path = if let Some(rest) = path.strip_prefix("~/") {
+        dirs::home_dir()
+            .ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?
+            .join(rest)
+    } else {
+        let path = PathBuf::from(path);
+        if path.is_absolute() {
+            path
+        } else {
+            PlatformPaths::get_ccr_root()?.join("bundles").join(path)
+        }
+    };
+    Ok(Some(path))
+}
+
+/// 🎁 加载完整片段: 设置包文件 + 内联表
+pub fn load(other: &IndexMap<String, toml::Value>) -> Result<Option<Value>> {
+    let mut fragment = match bundle_path(other)? {
+        Some(path) => {
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                CcrError::ConfigError(format!("读取设置包 {} 失败: {}", path.display(), e))
+            })?;
+            let value: Value = serde_json::from_str(&content).map_err(|e| {
+                CcrError::ValidationError(format!(
+                    "设置包 {} 不是有效的 JSON: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            validate_fragment(&value, &path.display().to_string())?;
+            Some(value)
+        }
+        None => None,
+    };
+
+    if let Some(inline) = inline_fragment(other)? {
+        match fragment.as_mut().and_then(Value::as_object_mut) {
+            Some(target) => {
+                merge(target, inline.as_object().into_iter().flatten());
+            }
+            None => fragment = Some(inline),
+        }
+    }
+    Ok(fragment)
+}
+
+/// ✅ 校验片段结构（只检查已知键的类型）
+fn validate_fragment(fragment: &Value, source: &str) -> Result<()> {
+    let invalid = |msg: &str| CcrError::ValidationError(format!("{}: {}", source, msg));
+
+    let object = fragment.as_object().ok_or_else(|| invalid("必须是对象"))?;
+    if object.contains_key("env") {
+        return Err(invalid("不能包含 env，请使用 [<配置节>.env]"));
+    }
+
+    if let Some(permissions) = object.get("permissions") {
+        let permissions = permissions
+            .as_object()
+            .ok_or_else(|| invalid("permissions 必须是对象"))?;
+        for key in ["allow", "deny", "ask"] {
+            if let Some(rules) = permissions.get(key)
+                && !rules
+                    .as_array()
+                    .is_some_and(|rules| rules.iter().all(Value::is_string))
+            {
+                return Err(invalid(&format!("permissions.{} 必须是字符串数组", key)));
+            }
+        }
+    }
+    for key in ["hooks", "statusLine"] {
+        if object.get(key).is_some_and(|v| !v.is_object()) {
+            return Err(invalid(&format!("{} 必须是对象", key)));
+        }
+    }
+    if object.get("outputStyle").is_some_and(|v| !v.is_string()) {
+        return Err(invalid("outputStyle 必须是字符串"));
+    }
+    Ok(())
+}
+
+/// 🧩 将片段深度合并到目标，返回实际写入的部分
+///
+/// 目标中已有且相同的值、数组中已有的元素不计入返回值，移除时不会误删用户自己的设置
+pub fn merge<'a>(
+    target: &mut Map<String, Value>,
+    fragment: impl IntoIterator<Item = (&'a String, &'a Value)>,
+) -> Map<String, Value> {
+    let mut owned = Map::new();
+
+    for (key, value) in fragment {
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(existing)), Value::Object(incoming)) => {
+                let written = merge(existing, incoming);
+                if !written.is_empty() {
+                    owned.insert(key.clone(), Value::Object(written));
+                }
+            }
+            (Some(Value::Array(existing)), Value::Array(incoming)) => {
+                let added: Vec<Value> = incoming
+                    .iter()
+                    .filter(|item| !existing.contains(item))
+                    .cloned()
+                    .collect();
+                if !added.is_empty() {
+                    existing.extend(added.iter().cloned());
+                    owned.insert(key.clone(), Value::Array(added));
+                }
+            }
+            (Some(existing), _) if existing == value => {}
+            _ => {
+                target.insert(key.clone(), value.clone());
+                owned.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    owned
+}
+
+/// 🧹 移除之前合并写入的部分，清空的对象 / 数组一并移除
+pub fn unmerge(target: &mut Map<String, Value>, owned: &Map<String, Value>) {
+    for (key, value) in owned {
+        let remove = match (target.get_mut(key), value) {
+            (Some(Value::Object(existing)), Value::Object(written)) => {
+                unmerge(existing, written);
+                existing.is_empty()
+            }
+            (Some(Value::Array(existing)), Value::Array(written)) => {
+                existing.retain(|item| !written.contains(item));
+                existing.is_empty()
+            }
+            (Some(existing), _) => existing == value,
+            (None, _) => false,
+        };
+        if remove {
+            target.remove(key);
+        }
+    }
+}
+
+/// 🔍 比较两个 JSON 值，返回按路径排列的变化
+///
+/// 对象按键递归比较，数组按元素比较增删
+pub fn diff(old: &Value, new: &Value) -> Vec<SettingsChange> {
+    let mut changes = Vec::new();
+    diff_at("", old, new, &mut changes);
+    changes
+}
+
+/// 🎭 按路径最后一段的键名对变化中的字符串值脱敏
+pub fn mask_change(change: &mut SettingsChange) {
+    let key = change
+        .path
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches("[]")
+        .to_uppercase();
+    for value in [&mut change.old, &mut change.new].into_iter().flatten() {
+        if let Value::String(s) = value {
+            *s = mask_if_sensitive(&key, s);
+        }
+    }
+}
+
+fn diff_at(path: &str, old: &Value, new: &Value, changes: &mut Vec<SettingsChange>) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                match (old.get(key), new.get(key)) {
+                    (Some(o), Some(n)) => diff_at(&child(key), o, n, changes),
+                    (o, n) => changes.push(SettingsChange {
+                        path: child(key),
+                        old: o.cloned(),
+                        new: n.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            let path = format!("{}[]", path);
+            for item in old_items.iter().filter(|i| !new_items.contains(i)) {
+                changes.push(SettingsChange {
+                    path: path.clone(),
+                    old: Some(item.clone()),
+                    new: None,
+                });
+            }
+            for item in new_items.iter().filter(|i| !old_items.contains(i)) {
+                changes.push(SettingsChange {
+                    path: path.clone(),
+                    old: None,
+                    new: Some(item.clone()),
+                });
+            }
+        }
+        (o, n) if o != n => changes.push(SettingsChange {
+            path: path.to_string(),
+            old: Some(o.clone()),
+            new: Some(n.clone()),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_merge_and_unmerge_keep_user_settings() {
+        let mut settings = object(json!({
+            "permissions": {"allow": ["Bash(ls:*)"]},
+            "theme": "dark",
+        }));
+        let fragment = object(json!({
+            "permissions": {"allow": ["Bash(ls:*)", "Bash(npm run test:*)"], "deny": ["Read(.env)"]},
+            "statusLine": {"type": "command", "command": "~/.claude/status.sh"},
+            "outputStyle": "Explanatory",
+        }));
+
+        let owned = merge(&mut settings, &fragment);
+        assert_eq!(
+            settings["permissions"]["allow"],
+            json!(["Bash(ls:*)", "Bash(npm run test:*)"])
+        );
+        // 用户已有的规则不计入写入部分
+        assert_eq!(
+            owned["permissions"]["allow"],
+            json!(["Bash(npm run test:*)"])
+        );
+
+        unmerge(&mut settings, &owned);
+        assert_eq!(
+            Value::Object(settings),
+            json!({"permissions": {"allow": ["Bash(ls:*)"]}, "theme": "dark"})
+        );
+    }
+
+    #[test]
+    fn test_unmerge_keeps_values_changed_by_user() {
+        let mut settings = Map::new();
+        let owned = merge(
+            &mut settings,
+            &object(json!({"outputStyle": "Explanatory"})),
+        );
+        settings.insert("outputStyle".into(), json!("Learning"));
+
+        unmerge(&mut settings, &owned);
+        assert_eq!(settings["outputStyle"], "Learning");
+    }
+
+    #[test]
+    fn test_validate_fragment() {
+        assert!(validate_fragment(&json!({"permissions": {"allow": ["Read"]}}), "t").is_ok());
+        for invalid in [
+            json!(["x"]),
+            json!({"env": {"A": "1"}}),
+            json!({"permissions": {"deny": "Read(.env)"}}),
+            json!({"permissions": {"allow": [1]}}),
+            json!({"hooks": []}),
+            json!({"statusLine": "script.sh"}),
+            json!({"outputStyle": 1}),
+        ] {
+            assert!(validate_fragment(&invalid, "t").is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_diff_paths() {
+        let changes = diff(
+            &json!({"env": {"A": "1", "B": "2"}, "permissions": {"allow": ["x"]}}),
+            &json!({"env": {"A": "3"}, "permissions": {"allow": ["x", "y"]}, "outputStyle": "s"}),
+        );
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["env.A", "env.B", "outputStyle", "permissions.allow[]"]
+        );
+        assert_eq!(changes[1].new, None);
+        assert_eq!(changes[3].new, Some(json!("y")));
+    }
+}
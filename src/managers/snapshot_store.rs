@@ -0,0 +1,717 @@
+// 🗄️ 快照存储 - 内容寻址、去重的统一备份
+// 📦 将 CCR 管理的配置文件保存为快照: 文件内容按块切分并以 blake3 摘要寻址，快照本身只是一份清单
+//
+// 目录结构 (~/.ccr/snapshots/):
+// - objects/<摘要前 2 位>/<摘要>   数据块，相同内容只保存一份
+// - manifests/<快照 ID>.json       快照清单（文件路径、大小、摘要、数据块列表）
+//
+// 保留策略 (GFS):
+// - 🕐 最近 keep_last 个快照全部保留
+// - 📅 每小时 / 每天 / 每周 / 每月各保留该周期内最新的快照，分别保留若干个周期
+// - 🧹 删除快照后清理不再被任何快照引用的数据块
+
+use crate::core::atomic_writer::AtomicWriter;
+use crate::core::error::{CcrError, Result};
+use crate::core::lock::LockManager;
+use crate::models::PlatformPaths;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 📏 数据块大小
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 🔒 快照存储锁
+const SNAPSHOT_LOCK: &str = "ccr_snapshots";
+const SNAPSHOT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 📄 快照中的一个文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    /// 原始绝对路径（恢复时写回此处）
+    pub path: PathBuf,
+    pub size: u64,
+    /// 整个文件内容的 blake3 摘要
+    pub hash: String,
+    /// 按顺序排列的数据块摘要
+    pub chunks: Vec<String>,
+}
+
+/// 📸 快照清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub created_at: DateTime<Local>,
+    /// 创建原因: manual / switch / delete / import / clear / revert / pre_restore ...
+    pub reason: String,
+    pub files: Vec<SnapshotFile>,
+}
+
+impl Snapshot {
+    /// 🏷️ 短 ID（前 8 位）
+    pub fn short_id(&self) -> &str {
+        self.id.get(..8).unwrap_or(&self.id)
+    }
+
+    /// 📏 文件总大小（去重前）
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+
+    /// 🔍 按路径查找文件
+    pub fn file(&self, path: &Path) -> Option<&SnapshotFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+
+    /// 🟰 与另一快照包含完全相同的文件内容
+    pub fn same_content(&self, other: &Snapshot) -> bool {
+        self.files.len() == other.files.len()
+            && self
+                .files
+                .iter()
+                .all(|f| other.file(&f.path).is_some_and(|o| o.hash == f.hash))
+    }
+}
+
+/// 📅 GFS 保留策略
+///
+/// 在 profiles.toml 的 `[settings.backup_retention]` 中配置，未设置的字段使用默认值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// 最近 N 个快照全部保留
+    pub keep_last: usize,
+    /// 保留最近 N 个小时中每小时最新的快照
+    pub hourly: usize,
+    /// 保留最近 N 天中每天最新的快照
+    pub daily: usize,
+    /// 保留最近 N 周中每周最新的快照
+    pub weekly: usize,
+    /// 保留最近 N 个月中每月最新的快照
+    pub monthly: usize,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: 10,
+            hourly: 24,
+            daily: 7,
+            weekly: 4,
+            monthly: 12,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// ✅ 计算需要保留的快照 ID
+    pub fn select(&self, snapshots: &[Snapshot]) -> HashSet<String> {
+        let mut sorted: Vec<&Snapshot> = snapshots.iter().collect();
+        sorted.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+
+        let mut keep: HashSet<String> = sorted
+            .iter()
+            .take(self.keep_last)
+            .map(|s| s.id.clone())
+            .collect();
+
+        let buckets: [(usize, &str); 4] = [
+            (self.hourly, "%Y-%m-%d %H"),
+            (self.daily, "%Y-%m-%d"),
+            (self.weekly, "%G-W%V"),
+            (self.monthly, "%Y-%m"),
+        ];
+        for (count, format) in buckets {
+            let mut seen = HashSet::new();
+            for snapshot in &sorted {
+                if seen.len() >= count {
+                    break;
+                }
+                if seen.insert(snapshot.created_at.format(format).to_string()) {
+                    keep.insert(snapshot.id.clone());
+                }
+            }
+        }
+
+        keep
+    }
+}
+
+/// 🧹 清理结果
+#[derive(Debug, Default)]
+pub struct PruneResult {
+    pub removed: Vec<Snapshot>,
+    pub removed_objects: usize,
+    pub freed_bytes: u64,
+}
+
+/// ⚠️ 完整性检查发现的问题
+#[derive(Debug, Clone)]
+pub struct VerifyIssue {
+    pub snapshot_id: String,
+    pub path: PathBuf,
+    pub problem: String,
+}
+
+/// 🔍 完整性检查报告
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub snapshots: usize,
+    pub objects: usize,
+    pub issues: Vec<VerifyIssue>,
+    /// 未被任何快照引用的数据块（可通过清理删除）
+    pub orphans: usize,
+}
+
+/// 📊 存储占用
+#[derive(Debug, Default)]
+pub struct StoreUsage {
+    pub objects: usize,
+    pub bytes: u64,
+}
+
+/// 🗄️ 快照存储
+pub struct SnapshotStore {
+    root: PathBuf,
+    lock_manager: LockManager,
+}
+
+impl SnapshotStore {
+    pub fn new<P: AsRef<Path>>(root: P, lock_manager: LockManager) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            lock_manager,
+        }
+    }
+
+    /// 🏠 默认位置: <CCR_ROOT 或 ~/.ccr>/snapshots
+    pub fn with_default() -> Result<Self> {
+        Ok(Self::new(
+            PlatformPaths::get_ccr_root()?.join("snapshots"),
+            LockManager::with_default_path()?,
+        ))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn objects_dir(&self) -> PathBuf {
+        self.root.join("objects")
+    }
+
+    fn manifests_dir(&self) -> PathBuf {
+        self.root.join("manifests")
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.objects_dir()
+            .join(hash.get(..2).unwrap_or(hash))
+            .join(hash)
+    }
+
+    fn manifest_path(&self, id: &str) -> PathBuf {
+        self.manifests_dir().join(format!("{}.json", id))
+    }
+
+    // === 创建与读取 ===
+
+    /// 📸 为指定文件创建快照（不存在的文件跳过）
+    pub fn create(&self, reason: &str, files: &[PathBuf]) -> Result<Snapshot> {
+        let _lock = self.lock()?;
+        let snapshot = self.build(reason, files)?;
+        self.write_manifest(&snapshot)?;
+
+        tracing::debug!(
+            "📸 已创建快照 {} ({} 个文件)",
+            snapshot.short_id(),
+            snapshot.files.len()
+        );
+        Ok(snapshot)
+    }
+
+    /// 📸 文件内容与最新快照相同时不创建新快照
+    pub fn create_if_changed(&self, reason: &str, files: &[PathBuf]) -> Result<Option<Snapshot>> {
+        let _lock = self.lock()?;
+        let snapshot = self.build(reason, files)?;
+        if self
+            .list()?
+            .first()
+            .is_some_and(|latest| latest.same_content(&snapshot))
+        {
+            return Ok(None);
+        }
+        self.write_manifest(&snapshot)?;
+
+        tracing::debug!(
+            "📸 已创建快照 {} ({} 个文件)",
+            snapshot.short_id(),
+            snapshot.files.len()
+        );
+        Ok(Some(snapshot))
+    }
+
+    /// 📦 读取文件并写入数据块，生成快照清单（不写入清单）
+    fn build(&self, reason: &str, files: &[PathBuf]) -> Result<Snapshot> {
+        let mut seen = HashSet::new();
+        let mut snapshot_files = Vec::new();
+        for path in files {
+            if !seen.insert(path.clone()) || !path.is_file() {
+                continue;
+            }
+            let content = fs::read(path).map_err(|e| {
+                CcrError::FileIoError(format!("读取 {} 失败: {}", path.display(), e))
+            })?;
+            snapshot_files.push(self.store_content(path, &content)?);
+        }
+
+        Ok(Snapshot {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: Local::now(),
+            reason: reason.to_string(),
+            files: snapshot_files,
+        })
+    }
+
+    /// 📋 所有快照（最新的在前），无法解析的清单会被跳过
+    pub fn list(&self) -> Result<Vec<Snapshot>> {
+        let entries = match fs::read_dir(self.manifests_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(CcrError::FileIoError(format!("读取快照目录失败: {}", e)));
+            }
+        };
+
+        let mut snapshots = Vec::new();
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<Snapshot>(&content).map_err(|e| e.to_string())
+                }) {
+                Ok(snapshot) => snapshots.push(snapshot),
+                Err(e) => tracing::warn!("跳过无法解析的快照清单 {:?}: {}", path, e),
+            }
+        }
+
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        Ok(snapshots)
+    }
+
+    /// 🔍 按 ID 或唯一的 ID 前缀查找快照
+    pub fn find(&self, id: &str) -> Result<Snapshot> {
+        let mut matches: Vec<Snapshot> = self
+            .list()?
+            .into_iter()
+            .filter(|s| s.id.starts_with(id))
+            .collect();
+
+        match matches.len() {
+            0 => Err(CcrError::ResourceNotFound(format!("快照 '{}'", id))),
+            1 => Ok(matches.remove(0)),
+            n => Err(CcrError::ValidationError(format!(
+                "快照 ID 前缀 '{}' 匹配到 {} 个快照，请输入更长的 ID",
+                id, n
+            ))),
+        }
+    }
+
+    /// 📖 读取快照中的文件内容并校验摘要
+    pub fn read_file(&self, file: &SnapshotFile) -> Result<Vec<u8>> {
+        let mut content = Vec::with_capacity(file.size as usize);
+        for hash in &file.chunks {
+            let chunk = fs::read(self.object_path(hash)).map_err(|e| {
+                CcrError::BackupCorrupted(format!(
+                    "{} 的数据块 {} 无法读取: {}",
+                    file.path.display(),
+                    short_hash(hash),
+                    e
+                ))
+            })?;
+            if blake3::hash(&chunk).to_hex().as_str() != hash {
+                return Err(CcrError::BackupCorrupted(format!(
+                    "{} 的数据块 {} 摘要不匹配",
+                    file.path.display(),
+                    short_hash(hash)
+                )));
+            }
+            content.extend_from_slice(&chunk);
+        }
+
+        if blake3::hash(&content).to_hex().as_str() != file.hash {
+            return Err(CcrError::BackupCorrupted(format!(
+                "{} 的内容摘要不匹配",
+                file.path.display()
+            )));
+        }
+        Ok(content)
+    }
+
+    // === 维护 ===
+
+    /// 🧹 按保留策略删除快照并清理不再引用的数据块
+    pub fn prune(&self, policy: &RetentionPolicy, dry_run: bool) -> Result<PruneResult> {
+        let _lock = self.lock()?;
+
+        let snapshots = self.list()?;
+        let keep = policy.select(&snapshots);
+        let (kept, removed): (Vec<Snapshot>, Vec<Snapshot>) =
+            snapshots.into_iter().partition(|s| keep.contains(&s.id));
+
+        if !dry_run {
+            for snapshot in &removed {
+                fs::remove_file(self.manifest_path(&snapshot.id)).map_err(|e| {
+                    CcrError::FileIoError(format!("删除快照 {} 失败: {}", snapshot.short_id(), e))
+                })?;
+            }
+        }
+
+        let (removed_objects, freed_bytes) = self.collect_garbage(&kept, dry_run)?;
+        Ok(PruneResult {
+            removed,
+            removed_objects,
+            freed_bytes,
+        })
+    }
+
+    /// 🔍 校验所有快照引用的数据块
+    pub fn verify(&self) -> Result<VerifyReport> {
+        let snapshots = self.list()?;
+        let objects = self.object_hashes()?;
+        let mut report = VerifyReport {
+            snapshots: snapshots.len(),
+            objects: objects.len(),
+            ..Default::default()
+        };
+
+        // 每个数据块只校验一次
+        let mut checked: HashMap<String, Option<String>> = HashMap::new();
+        let mut referenced = HashSet::new();
+        for snapshot in &snapshots {
+            for file in &snapshot.files {
+                let mut broken = false;
+                for hash in &file.chunks {
+                    referenced.insert(hash.clone());
+                    let problem = checked
+                        .entry(hash.clone())
+                        .or_insert_with(|| self.check_object(hash));
+                    if let Some(problem) = problem {
+                        broken = true;
+                        report.issues.push(VerifyIssue {
+                            snapshot_id: snapshot.id.clone(),
+                            path: file.path.clone(),
+                            problem: problem.clone(),
+                        });
+                    }
+                }
+                if !broken && let Err(e) = self.read_file(file) {
+                    report.issues.push(VerifyIssue {
+                        snapshot_id: snapshot.id.clone(),
+                        path: file.path.clone(),
+                        problem: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        report.orphans = objects.difference(&referenced).count();
+        Ok(report)
+    }
+
+    /// 📊 数据块数量与占用空间（去重后）
+    pub fn usage(&self) -> Result<StoreUsage> {
+        let mut usage = StoreUsage::default();
+        for hash in self.object_hashes()? {
+            usage.objects += 1;
+            usage.bytes += fs::metadata(self.object_path(&hash))
+                .map(|m| m.len())
+                .unwrap_or(0);
+        }
+        Ok(usage)
+    }
+
+    /// ✏️ 改写快照中的文件内容（如清除明文 token），返回改写的文件数
+    ///
+    /// `rewrite` 返回 Some 时替换内容；旧数据块不再被引用时一并删除
+    pub fn rewrite<F>(&self, dry_run: bool, rewrite: F) -> Result<usize>
+    where
+        F: Fn(&Path, &[u8]) -> Option<Vec<u8>>,
+    {
+        let _lock = self.lock()?;
+
+        let mut rewritten = 0;
+        let mut snapshots = self.list()?;
+        for snapshot in &mut snapshots {
+            let mut changed = false;
+            for file in &mut snapshot.files {
+                let content = self.read_file(file)?;
+                let Some(new_content) = rewrite(&file.path, &content) else {
+                    continue;
+                };
+                if new_content == content {
+                    continue;
+                }
+                rewritten += 1;
+                changed = true;
+                if !dry_run {
+                    *file = self.store_content(&file.path, &new_content)?;
+                }
+            }
+            if changed && !dry_run {
+                self.write_manifest(snapshot)?;
+            }
+        }
+
+        if !dry_run && rewritten > 0 {
+            self.collect_garbage(&snapshots, false)?;
+        }
+        Ok(rewritten)
+    }
+
+    // === 内部实现 ===
+
+    fn lock(&self) -> Result<crate::core::lock::FileLock> {
+        self.lock_manager
+            .lock_resource(SNAPSHOT_LOCK, SNAPSHOT_LOCK_TIMEOUT)
+    }
+
+    /// 📦 切分内容并写入缺失的数据块
+    fn store_content(&self, path: &Path, content: &[u8]) -> Result<SnapshotFile> {
+        let mut chunks = Vec::new();
+        for chunk in content.chunks(CHUNK_SIZE) {
+            let hash = blake3::hash(chunk).to_hex().to_string();
+            let object = self.object_path(&hash);
+            if !object.exists() {
+                if let Some(parent) = object.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| CcrError::FileIoError(format!("创建快照目录失败: {}", e)))?;
+                }
+                AtomicWriter::new(&object).write(chunk)?;
+            }
+            chunks.push(hash);
+        }
+
+        Ok(SnapshotFile {
+            path: path.to_path_buf(),
+            size: content.len() as u64,
+            hash: blake3::hash(content).to_hex().to_string(),
+            chunks,
+        })
+    }
+
+    fn write_manifest(&self, snapshot: &Snapshot) -> Result<()> {
+        fs::create_dir_all(self.manifests_dir())
+            .map_err(|e| CcrError::FileIoError(format!("创建快照目录失败: {}", e)))?;
+        let content = serde_json::to_string_pretty(snapshot)
+            .map_err(|e| CcrError::FileIoError(format!("序列化快照清单失败: {}", e)))?;
+        AtomicWriter::new(self.manifest_path(&snapshot.id)).write_string(&content)
+    }
+
+    /// ✅ 检查单个数据块，返回问题描述
+    fn check_object(&self, hash: &str) -> Option<String> {
+        match fs::read(self.object_path(hash)) {
+            Err(_) => Some(format!("数据块 {} 缺失", short_hash(hash))),
+            Ok(data) if blake3::hash(&data).to_hex().as_str() != hash => {
+                Some(format!("数据块 {} 已损坏", short_hash(hash)))
+            }
+            Ok(_) => None,
+        }
+    }
+
+    /// 📋 存储中的所有数据块摘要
+    fn object_hashes(&self) -> Result<HashSet<String>> {
+        let mut hashes = HashSet::new();
+        let Ok(prefixes) = fs::read_dir(self.objects_dir()) else {
+            return Ok(hashes);
+        };
+        for prefix in prefixes.flatten().map(|e| e.path()) {
+            let Ok(entries) = fs::read_dir(&prefix) else {
+                continue;
+            };
+            hashes.extend(
+                entries
+                    .flatten()
+                    .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                    .filter(|name| !name.starts_with('.')),
+            );
+        }
+        Ok(hashes)
+    }
+
+    /// 🗑️ 删除未被给定快照引用的数据块，返回 (数量, 字节数)
+    fn collect_garbage(&self, snapshots: &[Snapshot], dry_run: bool) -> Result<(usize, u64)> {
+        let referenced: HashSet<&str> = snapshots
+            .iter()
+            .flat_map(|s| &s.files)
+            .flat_map(|f| &f.chunks)
+            .map(String::as_str)
+            .collect();
+
+        let mut removed = 0;
+        let mut freed = 0;
+        for hash in self.object_hashes()? {
+            if referenced.contains(hash.as_str()) {
+                continue;
+            }
+            let path = self.object_path(&hash);
+            freed += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            removed += 1;
+            if !dry_run && let Err(e) = fs::remove_file(&path) {
+                tracing::warn!("删除数据块失败 {:?}: {}", path, e);
+            }
+        }
+        Ok((removed, freed))
+    }
+}
+
+/// 🏷️ 摘要前 12 位
+pub fn short_hash(hash: &str) -> &str {
+    hash.get(..12).unwrap_or(hash)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chrono::{Duration as ChronoDuration, TimeZone};
+    use tempfile::tempdir;
+
+    fn store(dir: &Path) -> SnapshotStore {
+        SnapshotStore::new(dir.join("snapshots"), LockManager::new(dir.join("locks")))
+    }
+
+    fn snapshot_at(id: &str, created_at: DateTime<Local>) -> Snapshot {
+        Snapshot {
+            id: id.to_string(),
+            created_at,
+            reason: "test".into(),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_create_dedups_and_reads_back() {
+        let dir = tempdir().unwrap();
+        let store = store(dir.path());
+        let small = dir.path().join("config.toml");
+        let large = dir.path().join("settings.json");
+        fs::write(&small, "current = \"a\"").unwrap();
+        let large_content: Vec<u8> = (0..CHUNK_SIZE * 2 + 10).map(|i| (i % 251) as u8).collect();
+        fs::write(&large, &large_content).unwrap();
+
+        let first = store
+            .create(
+                "test",
+                &[small.clone(), large.clone(), dir.path().join("missing")],
+            )
+            .unwrap();
+        assert_eq!(first.files.len(), 2);
+        assert_eq!(first.file(&large).unwrap().chunks.len(), 3);
+        let objects = store.usage().unwrap().objects;
+
+        // 只修改小文件时，大文件的数据块被复用
+        fs::write(&small, "current = \"b\"").unwrap();
+        let second = store
+            .create("test", &[small.clone(), large.clone()])
+            .unwrap();
+        assert_eq!(store.usage().unwrap().objects, objects + 1);
+        assert!(!first.same_content(&second));
+
+        assert_eq!(
+            store.read_file(second.file(&large).unwrap()).unwrap(),
+            large_content
+        );
+        assert_eq!(store.list().unwrap()[0].id, second.id);
+        assert_eq!(store.find(&first.id[..8]).unwrap().id, first.id);
+
+        // 内容未变化时不创建新快照
+        assert!(
+            store
+                .create_if_changed("test", &[small, large])
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(store.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let dir = tempdir().unwrap();
+        let store = store(dir.path());
+        let file = dir.path().join("profiles.toml");
+        fs::write(&file, "[a]\nbase_url = \"x\"").unwrap();
+        let snapshot = store.create("test", std::slice::from_ref(&file)).unwrap();
+        assert!(store.verify().unwrap().issues.is_empty());
+
+        let hash = &snapshot.files[0].chunks[0];
+        fs::write(store.object_path(hash), "tampered").unwrap();
+        let report = store.verify().unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert!(report.issues[0].problem.contains("已损坏"));
+        assert!(matches!(
+            store.read_file(&snapshot.files[0]),
+            Err(CcrError::BackupCorrupted(_))
+        ));
+    }
+
+    #[test]
+    fn test_retention_policy_gfs() {
+        let now = Local.with_ymd_and_hms(2026, 3, 15, 12, 0, 0).unwrap();
+        let snapshots: Vec<Snapshot> =
+            (0..60)
+                .map(|i| snapshot_at(&format!("day{}", i), now - ChronoDuration::days(i)))
+                .chain((1..4).map(|i| {
+                    snapshot_at(&format!("hour{}", i), now - ChronoDuration::minutes(i * 20))
+                }))
+                .collect();
+
+        let policy = RetentionPolicy {
+            keep_last: 2,
+            hourly: 0,
+            daily: 3,
+            weekly: 0,
+            monthly: 3,
+        };
+        let keep = policy.select(&snapshots);
+        // 最近 2 个 + 3 天各一个 + 3 个月各一个（与前者有重叠）
+        for id in ["day0", "hour1", "day1", "day2", "day15", "day43"] {
+            assert!(keep.contains(id), "{} 应保留", id);
+        }
+        assert_eq!(keep.len(), 6);
+    }
+
+    #[test]
+    fn test_prune_removes_unreferenced_objects() {
+        let dir = tempdir().unwrap();
+        let store = store(dir.path());
+        let file = dir.path().join("config.toml");
+        for i in 0..3 {
+            fs::write(&file, format!("version = {}", i)).unwrap();
+            store.create("test", std::slice::from_ref(&file)).unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            keep_last: 1,
+            hourly: 0,
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+        };
+        let preview = store.prune(&policy, true).unwrap();
+        assert_eq!(preview.removed.len(), 2);
+        assert_eq!(store.list().unwrap().len(), 3);
+
+        let result = store.prune(&policy, false).unwrap();
+        assert_eq!(result.removed_objects, 2);
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert_eq!(store.usage().unwrap().objects, 1);
+        assert!(store.verify().unwrap().issues.is_empty());
+    }
+}
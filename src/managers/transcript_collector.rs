@@ -0,0 +1,766 @@
+// 📥 CCR 会话日志成本采集器
+// 增量读取 ~/.claude/projects/**/*.jsonl 中的 usage 块，自动写入 CostTracker
+//
+// 核心功能:
+// - 📍 按文件记录已读取偏移量，只处理新追加的完整行
+// - 🔐 记录已读取前缀的哈希，文件被截断或重写时从头重新读取
+// - 🧹 同一条 assistant 消息的多个内容块只记录一次
+// - 👤 根据 profile 激活记录（全局切换与撤销切换），把每条记录归属到当时激活的 profile
+
+use crate::core::error::{CcrError, Result};
+use crate::core::lock::LockManager;
+use crate::managers::CostTracker;
+use crate::managers::history::ProfileActivation;
+use crate::models::stats::{Cost, CostRecord, TokenUsage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 🔐 用于检测文件重写的前缀长度
+const HEAD_HASH_BYTES: u64 = 4096;
+
+/// 📍 单个会话日志文件的读取游标
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCursor {
+    /// 已处理到的字节偏移量（总是位于行尾）
+    pub offset: u64,
+
+    /// 已处理前缀（最多 4KB）的 blake3 哈希
+    pub head_hash: String,
+
+    /// 最近一条已记录消息的去重键（跨次采集去重）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_message_key: Option<String>,
+}
+
+/// 📋 采集状态（持久化到 transcript_offsets.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CollectorState {
+    /// 文件路径 -> 读取游标
+    #[serde(default)]
+    pub files: BTreeMap<String, FileCursor>,
+}
+
+/// 📊 单次采集结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CollectReport {
+    /// 扫描的文件数
+    pub files_scanned: usize,
+
+    /// 新写入的成本记录数
+    pub records_added: usize,
+
+    /// 跳过的行数（非 usage 行、重复消息、解析失败）
+    pub lines_skipped: usize,
+
+    /// 因重写而从头读取的文件数
+    pub files_reset: usize,
+}
+
+/// 👤 profile 激活时间线
+///
+/// 由 Claude 平台的全局 profile 激活构建，用于查询任意时刻激活的 profile
+#[derive(Debug, Clone, Default)]
+pub struct ProfileTimeline {
+    /// (切换时间, 目标 profile)，按时间升序
+    switches: Vec<(DateTime<Utc>, String)>,
+
+    /// 早于首次已知切换时激活的 profile
+    initial: Option<String>,
+}
+
+impl ProfileTimeline {
+    /// 从激活记录构建时间线
+    ///
+    /// 其他平台的激活不影响 Claude Code 的会话。首次已知切换之前的记录归属到
+    /// 该次切换前的 profile；没有任何激活记录时归属到当前 profile
+    pub fn from_activations(activations: &[ProfileActivation], current: Option<String>) -> Self {
+        let mut activations: Vec<&ProfileActivation> = activations
+            .iter()
+            .filter(|a| a.platform.as_deref().is_none_or(|p| p == "claude"))
+            .collect();
+        activations.sort_by_key(|a| a.timestamp);
+
+        let initial = match activations.first() {
+            Some(first) => first.from.clone(),
+            None => current,
+        };
+        let mut switches: Vec<(DateTime<Utc>, String)> = activations
+            .into_iter()
+            .map(|a| (a.timestamp.with_timezone(&Utc), a.profile.clone()))
+            .collect();
+        switches.dedup();
+
+        Self { switches, initial }
+    }
+
+    /// 查询指定时刻激活的 profile
+    pub fn profile_at(&self, timestamp: DateTime<Utc>) -> Option<&str> {
+        let index = self.switches.partition_point(|(t, _)| *t <= timestamp);
+        match index.checked_sub(1) {
+            Some(i) => Some(self.switches[i].1.as_str()),
+            None => self.initial.as_deref(),
+        }
+    }
+}
+
+/// 📥 会话日志成本采集器
+pub struct TranscriptCollector {
+    /// 📁 Claude Code 会话日志目录 (~/.claude/projects)
+    projects_dir: PathBuf,
+
+    /// 📍 采集状态文件
+    state_path: PathBuf,
+
+    /// 🔒 防止多个 ccr 进程同时采集导致重复记录
+    lock_manager: LockManager,
+}
+
+impl TranscriptCollector {
+    /// 创建采集器
+    pub fn new<P: AsRef<Path>, S: AsRef<Path>>(
+        projects_dir: P,
+        state_path: S,
+        lock_manager: LockManager,
+    ) -> Self {
+        Self {
+            projects_dir: projects_dir.as_ref().to_path_buf(),
+            state_path: state_path.as_ref().to_path_buf(),
+            lock_manager,
+        }
+    }
+
+    /// 使用默认路径创建采集器
+    ///
+    /// ⚙️ **开发者注意**：
+    /// 可以通过环境变量 `CCR_CLAUDE_PROJECTS_DIR` 覆盖会话日志目录
+    pub fn with_default() -> Result<Self> {
+        let projects_dir = if let Ok(custom_dir) = std::env::var("CCR_CLAUDE_PROJECTS_DIR") {
+            PathBuf::from(custom_dir)
+        } else {
+            let home = dirs::home_dir()
+                .ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".to_string()))?;
+            home.join(".claude").join("projects")
+        };
+        let state_path = CostTracker::default_storage_dir()?.join("transcript_offsets.json");
+
+        Ok(Self::new(
+            projects_dir,
+            state_path,
+            LockManager::with_default_path()?,
+        ))
+    }
+
+    /// 加载采集状态
+    pub fn load_state(&self) -> Result<CollectorState> {
+        if !self.state_path.exists() {
+            return Ok(CollectorState::default());
+        }
+
+        let content = fs::read_to_string(&self.state_path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| CcrError::ConfigError(format!("解析采集状态失败: {}", e)))
+    }
+
+    /// 保存采集状态（先写临时文件再重命名）
+    fn save_state(&self, state: &CollectorState) -> Result<()> {
+        if let Some(parent) = self.state_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp_path = self.state_path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(state)?)?;
+        fs::rename(&tmp_path, &self.state_path)?;
+        Ok(())
+    }
+
+    /// 🔄 增量采集所有会话日志
+    ///
+    /// 每处理完一个文件先保存游标再写入记录：进程在两步之间中断时至多少记一批，
+    /// 不会在下次采集时重复记录；写入失败时回退游标，下次重新采集这些行
+    pub fn collect(
+        &self,
+        tracker: &CostTracker,
+        timeline: &ProfileTimeline,
+    ) -> Result<CollectReport> {
+        let mut report = CollectReport::default();
+        if !self.projects_dir.exists() {
+            tracing::debug!("会话日志目录不存在: {:?}", self.projects_dir);
+            return Ok(report);
+        }
+
+        let _lock = self
+            .lock_manager
+            .lock_resource("transcript_collector", Duration::from_secs(10))?;
+
+        let mut state = self.load_state()?;
+        let mut files = Vec::new();
+        collect_jsonl_files(&self.projects_dir, &mut files)?;
+        files.sort();
+
+        for path in files {
+            let key = path.to_string_lossy().to_string();
+            let mut cursor = state.files.get(&key).cloned().unwrap_or_default();
+
+            match self.collect_file(&path, &mut cursor, tracker, timeline) {
+                Ok((file_report, records)) => {
+                    let previous = state.files.get(&key).cloned();
+                    if previous.as_ref() != Some(&cursor) {
+                        state.files.insert(key.clone(), cursor);
+                        self.save_state(&state)?;
+                    }
+
+                    if let Err(e) = tracker.append_records(&records) {
+                        tracing::warn!("写入成本记录失败 {:?}: {}", path, e);
+                        match previous {
+                            Some(previous) => state.files.insert(key, previous),
+                            None => state.files.remove(&key),
+                        };
+                        self.save_state(&state)?;
+                        continue;
+                    }
+
+                    report.files_scanned += 1;
+                    report.records_added += file_report.records_added;
+                    report.lines_skipped += file_report.lines_skipped;
+                    report.files_reset += file_report.files_reset;
+                }
+                Err(e) => {
+                    tracing::warn!("采集会话日志失败 {:?}: {}", path, e);
+                }
+            }
+        }
+
+        tracing::debug!(
+            "会话日志采集完成: {} 个文件, 新增 {} 条记录",
+            report.files_scanned,
+            report.records_added
+        );
+        Ok(report)
+    }
+
+    /// 解析单个文件中新追加的完整行，推进游标并返回待写入的记录
+    fn collect_file(
+        &self,
+        path: &Path,
+        cursor: &mut FileCursor,
+        tracker: &CostTracker,
+        timeline: &ProfileTimeline,
+    ) -> Result<(CollectReport, Vec<CostRecord>)> {
+        let mut report = CollectReport::default();
+        let file_len = fs::metadata(path)?.len();
+
+        // 文件被截断或已处理前缀发生变化 -> 视为新文件
+        if cursor.offset > 0
+            && (file_len < cursor.offset || head_hash(path, cursor.offset)? != cursor.head_hash)
+        {
+            tracing::debug!("会话日志已被重写，从头读取: {:?}", path);
+            *cursor = FileCursor::default();
+            report.files_reset += 1;
+        }
+
+        if file_len == cursor.offset {
+            return Ok((report, Vec::new()));
+        }
+
+        let fallback_project = project_from_path(&self.projects_dir, path);
+        let mut reader = BufReader::new(File::open(path)?);
+        reader.seek(SeekFrom::Start(cursor.offset))?;
+
+        let mut seen: HashSet<String> = cursor.last_message_key.iter().cloned().collect();
+        let mut records = Vec::new();
+        let mut offset = cursor.offset;
+        let mut last_message_key = cursor.last_message_key.clone();
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            let bytes_read = reader.read_line(&mut line)?;
+            // 末尾不完整的行留到下次采集（Claude Code 可能正在写入）
+            if bytes_read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            offset += bytes_read as u64;
+
+            let Some(usage) = serde_json::from_str::<Value>(line.trim())
+                .ok()
+                .and_then(|json| UsageLine::parse(&json))
+            else {
+                report.lines_skipped += 1;
+                continue;
+            };
+
+            if !seen.insert(usage.message_key.clone()) {
+                report.lines_skipped += 1;
+                continue;
+            }
+
+            last_message_key = Some(usage.message_key.clone());
+            records.push(usage.into_record(&fallback_project, tracker, timeline));
+        }
+
+        report.records_added = records.len();
+
+        cursor.offset = offset;
+        cursor.head_hash = head_hash(path, offset)?;
+        cursor.last_message_key = last_message_key;
+        Ok((report, records))
+    }
+}
+
+/// 🔄 使用默认路径执行一次采集
+///
+/// 价格表优先使用 ~/.claude/pricing.toml，profile 归属来自 profile 激活记录
+pub fn collect_default() -> Result<CollectReport> {
+    let storage_dir = CostTracker::default_storage_dir()?;
+    let tracker = match crate::managers::PricingManager::with_default() {
+        Ok(pricing_manager) => CostTracker::with_pricing_manager(storage_dir, pricing_manager)?,
+        Err(e) => {
+            tracing::warn!("加载价格表失败，使用内置定价: {}", e);
+            CostTracker::new(storage_dir)?
+        }
+    };
+
+    // 激活记录之前写入的历史（旧版本）同样参与归属，相同的激活在构建时间线时去重
+    let history_manager = crate::managers::HistoryManager::with_default()?;
+    let mut activations = history_manager.load_activations()?;
+    activations.extend(
+        history_manager
+            .load()?
+            .iter()
+            .filter_map(ProfileActivation::from_entry),
+    );
+    let current = crate::platforms::base::get_current_profile_from_registry("claude")
+        .unwrap_or_else(|e| {
+            tracing::warn!("读取当前 profile 失败: {}", e);
+            None
+        });
+    let timeline = ProfileTimeline::from_activations(&activations, current);
+
+    TranscriptCollector::with_default()?.collect(&tracker, &timeline)
+}
+
+/// 🔄 查询成本数据前的自动采集
+///
+/// 采集失败只记录警告，不影响后续查询
+pub fn auto_collect() {
+    match collect_default() {
+        Ok(report) if report.records_added > 0 => {
+            tracing::info!("📥 已从会话日志采集 {} 条成本记录", report.records_added);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("⚠️ 自动采集会话日志成本失败: {}", e),
+    }
+}
+
+/// 📄 会话日志中的一条 usage 记录
+struct UsageLine {
+    id: String,
+    message_key: String,
+    timestamp: DateTime<Utc>,
+    session_id: Option<String>,
+    project: Option<String>,
+    model: String,
+    token_usage: TokenUsage,
+}
+
+impl UsageLine {
+    /// 解析 assistant 消息行（非 usage 行返回 None）
+    fn parse(json: &Value) -> Option<Self> {
+        let message = json.get("message")?;
+        let usage = message.get("usage")?;
+        let model = message.get("model").and_then(Value::as_str)?;
+        // Claude Code 本地生成的消息（如中断提示）不产生费用
+        if model == "<synthetic>" {
+            return None;
+        }
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+            .with_timezone(&Utc);
+
+        let tokens = |field: &str| {
+            usage
+                .get(field)
+                .and_then(Value::as_u64)
+                .map(|v| v.min(u32::MAX as u64) as u32)
+        };
+        let token_usage = TokenUsage {
+            input_tokens: tokens("input_tokens").unwrap_or(0),
+            output_tokens: tokens("output_tokens").unwrap_or(0),
+            cache_creation_tokens: tokens("cache_creation_input_tokens"),
+            cache_read_tokens: tokens("cache_read_input_tokens"),
+        };
+        if token_usage.total() == 0 {
+            return None;
+        }
+
+        let id = json
+            .get("uuid")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        // 同一条消息的每个内容块各占一行且 usage 相同，用 message.id + requestId 去重
+        let message_key = match message.get("id").and_then(Value::as_str) {
+            Some(message_id) => format!(
+                "{}:{}",
+                message_id,
+                json.get("requestId")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+            ),
+            None => id.clone(),
+        };
+
+        Some(Self {
+            id,
+            message_key,
+            timestamp,
+            session_id: json
+                .get("sessionId")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            project: json.get("cwd").and_then(Value::as_str).map(str::to_string),
+            model: model.to_string(),
+            token_usage,
+        })
+    }
+
+    /// 转换为成本记录（未知模型按零成本记录，保留 Token 数据）
+    fn into_record(
+        self,
+        fallback_project: &str,
+        tracker: &CostTracker,
+        timeline: &ProfileTimeline,
+    ) -> CostRecord {
+        let cost = tracker
+            .calculate_cost(&self.model, &self.token_usage)
+            .unwrap_or_else(|e| {
+                tracing::debug!("无法计算成本，按 0 记录: {}", e);
+                Cost {
+                    input_cost: 0.0,
+                    output_cost: 0.0,
+                    cache_cost: 0.0,
+                    total_cost: 0.0,
+                }
+            });
+
+        CostRecord {
+            id: self.id,
+            timestamp: self.timestamp,
+            session_id: self.session_id,
+            // CSV 以逗号分隔，路径中的逗号需要替换
+            project: self
+                .project
+                .as_deref()
+                .unwrap_or(fallback_project)
+                .replace(',', "_"),
+            model: self.model,
+            token_usage: self.token_usage,
+            cost,
+            duration_ms: 0,
+            platform: Some("claude".to_string()),
+            description: None,
+            profile: timeline.profile_at(self.timestamp).map(str::to_string),
+        }
+    }
+}
+
+/// 递归收集目录下的 .jsonl 文件
+fn collect_jsonl_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_jsonl_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 计算文件前 min(len, 4KB) 字节的哈希
+fn head_hash(path: &Path, len: u64) -> Result<String> {
+    let mut buffer = Vec::new();
+    File::open(path)?
+        .take(len.min(HEAD_HASH_BYTES))
+        .read_to_end(&mut buffer)?;
+    Ok(blake3::hash(&buffer).to_hex().to_string())
+}
+
+/// 从会话日志路径推断项目名（~/.claude/projects/<project>/<session>.jsonl）
+fn project_from_path(projects_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(projects_dir)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::managers::history::{
+        HistoryEntry, OperationDetails, OperationResult, OperationType,
+    };
+    use chrono::TimeZone;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn assistant_line(uuid: &str, message_id: &str, timestamp: &str, output: u32) -> String {
+        format!(
+            r#"{{"type":"assistant","uuid":"{uuid}","sessionId":"sess-1","cwd":"/work/demo","requestId":"req-{message_id}","timestamp":"{timestamp}","message":{{"id":"{message_id}","model":"claude-sonnet-4-5-20250929","usage":{{"input_tokens":100,"output_tokens":{output},"cache_read_input_tokens":20}}}}}}"#
+        )
+    }
+
+    fn switch_entry(profile: &str, timestamp: DateTime<Utc>) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(
+            OperationType::Switch,
+            OperationDetails {
+                from_config: None,
+                to_config: Some(profile.to_string()),
+                backup_path: None,
+                extra: None,
+            },
+            OperationResult::Success,
+        );
+        entry.timestamp = timestamp.with_timezone(&chrono::Local);
+        entry
+    }
+
+    fn timeline_from_history(entries: &[HistoryEntry]) -> ProfileTimeline {
+        let activations: Vec<ProfileActivation> = entries
+            .iter()
+            .filter_map(ProfileActivation::from_entry)
+            .collect();
+        ProfileTimeline::from_activations(&activations, None)
+    }
+
+    fn setup() -> (TempDir, TranscriptCollector, CostTracker, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let projects = temp.path().join("projects");
+        fs::create_dir_all(projects.join("-work-demo")).unwrap();
+        let collector = TranscriptCollector::new(
+            &projects,
+            temp.path().join("stats").join("transcript_offsets.json"),
+            LockManager::new(temp.path().join("locks")),
+        );
+        let tracker = CostTracker::new(temp.path().join("stats")).unwrap();
+        let transcript = projects.join("-work-demo").join("sess-1.jsonl");
+        (temp, collector, tracker, transcript)
+    }
+
+    fn append(path: &Path, content: &str) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_profile_timeline_lookup() {
+        let t = |h| Utc.with_ymd_and_hms(2025, 3, 1, h, 0, 0).unwrap();
+        let mut failed = switch_entry("broken", t(12));
+        failed.result = OperationResult::Failure("boom".into());
+        let timeline = timeline_from_history(&[
+            switch_entry("work", t(14)),
+            switch_entry("personal", t(10)),
+            failed,
+        ]);
+
+        assert_eq!(timeline.profile_at(t(9)), None);
+        assert_eq!(timeline.profile_at(t(10)), Some("personal"));
+        assert_eq!(timeline.profile_at(t(13)), Some("personal"));
+        assert_eq!(timeline.profile_at(t(15)), Some("work"));
+    }
+
+    #[test]
+    fn test_profile_timeline_follows_reverts() {
+        let t = |h| Utc.with_ymd_and_hms(2025, 3, 1, h, 0, 0).unwrap();
+        let mut revert = switch_entry("personal", t(12));
+        revert.operation = OperationType::Revert;
+        revert.details.from_config = Some("work".into());
+        // 撤销删除只恢复 profile，不改变激活的配置
+        let mut restore = switch_entry("restored", t(13));
+        restore.operation = OperationType::Revert;
+        let timeline = timeline_from_history(&[
+            switch_entry("personal", t(10)),
+            switch_entry("work", t(11)),
+            revert,
+            restore,
+        ]);
+
+        assert_eq!(timeline.profile_at(t(11)), Some("work"));
+        assert_eq!(timeline.profile_at(t(12)), Some("personal"));
+        assert_eq!(timeline.profile_at(t(14)), Some("personal"));
+    }
+
+    #[test]
+    fn test_profile_timeline_ignores_project_and_other_platform_switches() {
+        let t = |h| Utc.with_ymd_and_hms(2025, 3, 1, h, 0, 0).unwrap();
+        let mut local = switch_entry("local", t(11));
+        local.operation = OperationType::LocalSwitch;
+        let mut legacy_local = switch_entry("legacy-local", t(12));
+        legacy_local.details.extra = Some("项目级切换: /work/demo".into());
+        let mut codex = switch_entry("codex-profile", t(13));
+        codex.platform = Some("codex".into());
+        let mut claude = switch_entry("work", t(10));
+        claude.platform = Some("claude".into());
+        let timeline = timeline_from_history(&[claude, local, legacy_local, codex]);
+
+        assert_eq!(timeline.profile_at(t(14)), Some("work"));
+    }
+
+    #[test]
+    fn test_profile_timeline_fallback_before_first_switch() {
+        let t = |h| Utc.with_ymd_and_hms(2025, 3, 1, h, 0, 0).unwrap();
+        let activation = |from: Option<&str>, profile: &str, h| ProfileActivation {
+            timestamp: t(h).with_timezone(&chrono::Local),
+            platform: Some("claude".into()),
+            from: from.map(String::from),
+            profile: profile.into(),
+        };
+
+        // 早于首次已知切换: 归属到切换前的 profile
+        let timeline = ProfileTimeline::from_activations(
+            &[
+                activation(Some("work"), "personal", 12),
+                activation(Some("old"), "work", 10),
+            ],
+            Some("personal".into()),
+        );
+        assert_eq!(timeline.profile_at(t(9)), Some("old"));
+        assert_eq!(timeline.profile_at(t(11)), Some("work"));
+
+        // 没有任何激活记录: 归属到当前 profile
+        let timeline = ProfileTimeline::from_activations(&[], Some("current".into()));
+        assert_eq!(timeline.profile_at(t(9)), Some("current"));
+    }
+
+    #[test]
+    fn test_incremental_collect_with_dedup_and_attribution() {
+        let (_temp, collector, tracker, transcript) = setup();
+        let timeline = timeline_from_history(&[
+            switch_entry(
+                "personal",
+                Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            ),
+            switch_entry("work", Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()),
+        ]);
+
+        append(
+            &transcript,
+            &format!(
+                "{}\n{}\n{}\n{}\n",
+                r#"{"type":"user","uuid":"u0","timestamp":"2025-03-01T09:59:00Z","message":{"role":"user","content":"hi"}}"#,
+                assistant_line("a1", "msg_1", "2025-03-01T10:00:00Z", 50),
+                // 同一消息的第二个内容块
+                assistant_line("a2", "msg_1", "2025-03-01T10:00:01Z", 50),
+                assistant_line("a3", "msg_2", "2025-03-01T10:05:00Z", 10),
+            ),
+        );
+
+        let report = collector.collect(&tracker, &timeline).unwrap();
+        assert_eq!(report.files_scanned, 1);
+        assert_eq!(report.records_added, 2);
+        assert_eq!(report.lines_skipped, 2);
+
+        // 再次采集不会重复记录
+        let report = collector.collect(&tracker, &timeline).unwrap();
+        assert_eq!(report.records_added, 0);
+
+        // 追加一行不完整内容 + 一条新消息
+        append(
+            &transcript,
+            &format!(
+                "{}\n",
+                assistant_line("a4", "msg_3", "2025-03-01T13:00:00Z", 5)
+            ),
+        );
+        append(&transcript, r#"{"type":"assistant","uuid":"partial"#);
+        let report = collector.collect(&tracker, &timeline).unwrap();
+        assert_eq!(report.records_added, 1);
+
+        let mut records = tracker.read_all().unwrap();
+        records.sort_by_key(|r| r.timestamp);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].id, "a1");
+        assert_eq!(records[0].project, "/work/demo");
+        assert_eq!(records[0].session_id.as_deref(), Some("sess-1"));
+        assert_eq!(records[0].token_usage.cache_read_tokens, Some(20));
+        assert!(records[0].cost.total_cost > 0.0);
+        assert_eq!(records[0].profile.as_deref(), Some("personal"));
+        assert_eq!(records[2].profile.as_deref(), Some("work"));
+
+        let state = collector.load_state().unwrap();
+        let cursor = state.files.values().next().unwrap();
+        assert!(cursor.offset < fs::metadata(&transcript).unwrap().len());
+    }
+
+    #[test]
+    fn test_failed_append_keeps_cursor() {
+        let (temp, collector, tracker, transcript) = setup();
+        let timeline = ProfileTimeline::default();
+        append(
+            &transcript,
+            &format!(
+                "{}\n",
+                assistant_line("a1", "msg_1", "2025-03-01T10:00:00Z", 50)
+            ),
+        );
+
+        // 存储目录被文件占用，写入记录失败
+        let broken_dir = temp.path().join("broken");
+        let broken = CostTracker::new(broken_dir.clone()).unwrap();
+        fs::remove_dir(&broken_dir).unwrap();
+        fs::write(&broken_dir, "").unwrap();
+
+        let report = collector.collect(&broken, &timeline).unwrap();
+        assert_eq!(report.records_added, 0);
+        assert!(collector.load_state().unwrap().files.is_empty());
+
+        // 游标未推进，下次采集重新读取这些行
+        let report = collector.collect(&tracker, &timeline).unwrap();
+        assert_eq!(report.records_added, 1);
+        assert_eq!(tracker.read_all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rewritten_file_is_read_from_start() {
+        let (_temp, collector, tracker, transcript) = setup();
+        let timeline = ProfileTimeline::default();
+
+        append(
+            &transcript,
+            &format!(
+                "{}\n",
+                assistant_line("a1", "msg_1", "2025-03-01T10:00:00Z", 50)
+            ),
+        );
+        collector.collect(&tracker, &timeline).unwrap();
+
+        fs::write(
+            &transcript,
+            format!(
+                "{}\n",
+                assistant_line("b1", "msg_9", "2025-03-02T10:00:00Z", 7)
+            ),
+        )
+        .unwrap();
+        let report = collector.collect(&tracker, &timeline).unwrap();
+        assert_eq!(report.files_reset, 1);
+        assert_eq!(report.records_added, 1);
+        assert_eq!(tracker.read_all().unwrap().len(), 2);
+    }
+}
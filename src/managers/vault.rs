@@ -0,0 +1,747 @@
+// 🔐 CCR 密钥保险库
+// 📁 配置文件中只保存引用 (auth_token = "vault:anyrouter-main")，真实 token 加密存放在
+//    <CCR 根目录>/vault.enc，在 `ccr switch` 写入平台设置时才解析为明文
+//
+// 文件格式: Argon2id + AES-256-GCM 密文（core::crypto，文件头 CCRVAULT），明文为 JSON 对象 { 名称: token }
+// 旧版本写入的文件使用同步加密的文件头 CCRSYNC1，仍可打开，下次保存时改写为保险库格式
+//
+// 解锁口令来源（按优先级）:
+// 1. 环境变量 CCR_VAULT_PASSPHRASE（脚本 / CI）
+// 2. 系统钥匙串 (Linux Secret Service 的 secret-tool / macOS Keychain 的 security)
+// 3. 交互输入
+
+use crate::core::crypto::{self, MAGIC_SIZE, PassphraseCipher};
+use crate::core::error::{CcrError, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use toml_edit::{DocumentMut, Value};
+
+/// 🏷️ 保险库引用前缀
+pub const VAULT_REF_PREFIX: &str = "vault:";
+
+/// 🔑 保险库口令环境变量
+pub const VAULT_PASSPHRASE_ENV: &str = "CCR_VAULT_PASSPHRASE";
+
+/// 📄 保险库文件名
+const VAULT_FILE_NAME: &str = "vault.enc";
+
+/// 🏷️ 保险库文件头
+const VAULT_MAGIC: &[u8; MAGIC_SIZE] = b"CCRVAULT";
+
+/// 🏷️ 旧版本保险库文件头（与同步加密相同）
+const LEGACY_VAULT_MAGIC: &[u8; MAGIC_SIZE] = b"CCRSYNC1";
+
+/// 🔑 钥匙串中的服务 / 账户标识
+const KEYRING_SERVICE: &str = "ccr-vault";
+const KEYRING_ACCOUNT: &str = "ccr";
+
+/// ✂️ 清理备份时忽略过短的密钥，避免误替换普通文本
+const MIN_SCRUB_SECRET_LEN: usize = 8;
+
+/// 🔓 进程内已解锁的默认保险库（同一次命令中多次解析只解锁一次）
+static UNLOCKED: Mutex<Option<VaultManager>> = Mutex::new(None);
+
+/// 🔍 解析保险库引用，返回密钥名称（非引用时返回 None）
+pub fn vault_ref_name(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(VAULT_REF_PREFIX)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// 🔍 判断值是否为保险库引用
+pub fn is_vault_ref(value: &str) -> bool {
+    vault_ref_name(value).is_some()
+}
+
+/// 🏷️ 生成保险库引用
+pub fn vault_ref(name: &str) -> String {
+    format!("{}{}", VAULT_REF_PREFIX, name)
+}
+
+/// 🔓 解析配置值: 保险库引用解析为真实 token，其余值原样返回
+///
+/// 首次遇到引用时解锁默认保险库，之后在进程内复用
+pub fn resolve_secret(value: &str) -> Result<String> {
+    if !is_vault_ref(value) {
+        return Ok(value.to_string());
+    }
+
+    let mut unlocked = UNLOCKED
+        .lock()
+        .map_err(|_| CcrError::VaultError("保险库状态不可用".into()))?;
+    if unlocked.is_none() {
+        *unlocked = Some(VaultManager::open_default()?);
+    }
+    match unlocked.as_ref() {
+        Some(vault) => vault.resolve(value),
+        None => Err(CcrError::VaultError("保险库未解锁".into())),
+    }
+}
+
+/// 🔐 密钥保险库
+pub struct VaultManager {
+    vault_path: PathBuf,
+    passphrase: String,
+    secrets: BTreeMap<String, String>,
+}
+
+impl VaultManager {
+    /// 📁 默认保险库路径: <CCR_ROOT 或 ~/.ccr>/vault.enc
+    pub fn default_path() -> Result<PathBuf> {
+        let ccr_root = if let Ok(root) = std::env::var("CCR_ROOT") {
+            PathBuf::from(root)
+        } else {
+            dirs::home_dir()
+                .ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?
+                .join(".ccr")
+        };
+        Ok(ccr_root.join(VAULT_FILE_NAME))
+    }
+
+    /// 🔓 使用指定口令打开保险库（文件不存在时为空保险库，首次保存时创建）
+    pub fn open(vault_path: impl Into<PathBuf>, passphrase: &str) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(CcrError::VaultError("保险库口令不能为空".into()));
+        }
+
+        let vault_path = vault_path.into();
+        let secrets = if vault_path.exists() {
+            let data = fs::read(&vault_path)?;
+            let magic = if crypto::is_sealed(LEGACY_VAULT_MAGIC, &data) {
+                LEGACY_VAULT_MAGIC
+            } else {
+                VAULT_MAGIC
+            };
+            let plaintext = PassphraseCipher::new(magic, passphrase, CcrError::VaultError)
+                .decrypt(&data)
+                .map_err(|_| CcrError::VaultError("无法解锁保险库，口令错误或文件已损坏".into()))?;
+            serde_json::from_slice(&plaintext)
+                .map_err(|e| CcrError::VaultError(format!("保险库内容格式无效: {}", e)))?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            vault_path,
+            passphrase: passphrase.to_string(),
+            secrets,
+        })
+    }
+
+    /// 🔓 打开默认保险库，按 环境变量 → 钥匙串 → 交互输入 的顺序获取口令
+    pub fn open_default() -> Result<Self> {
+        let vault_path = Self::default_path()?;
+        let is_new = !vault_path.exists();
+        let passphrase = obtain_passphrase(is_new)?;
+        Self::open(vault_path, &passphrase)
+    }
+
+    /// 📁 保险库文件路径
+    pub fn vault_path(&self) -> &Path {
+        &self.vault_path
+    }
+
+    /// 📋 所有密钥名称（有序）
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.secrets.keys()
+    }
+
+    /// 🔍 读取密钥
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name).map(String::as_str)
+    }
+
+    /// ✏️ 写入密钥（仅内存，需调用 save 持久化）
+    pub fn insert(&mut self, name: &str, secret: &str) -> Result<()> {
+        validate_name(name)?;
+        if secret.is_empty() {
+            return Err(CcrError::VaultError("密钥内容不能为空".into()));
+        }
+        if is_vault_ref(secret) {
+            return Err(CcrError::VaultError("密钥内容不能是保险库引用".into()));
+        }
+        self.secrets.insert(name.to_string(), secret.to_string());
+        Ok(())
+    }
+
+    /// 🗑️ 删除密钥（仅内存，需调用 save 持久化），返回是否存在
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.secrets.remove(name).is_some()
+    }
+
+    /// 🔓 解析配置值（非引用时原样返回）
+    pub fn resolve(&self, value: &str) -> Result<String> {
+        match vault_ref_name(value) {
+            Some(name) => self.get(name).map(str::to_string).ok_or_else(|| {
+                CcrError::VaultError(format!(
+                    "保险库中不存在密钥 '{}'，请运行 'ccr vault set {}' 添加",
+                    name, name
+                ))
+            }),
+            None => Ok(value.to_string()),
+        }
+    }
+
+    /// 🔍 查找内容相同的已有密钥名称
+    pub fn find_by_secret(&self, secret: &str) -> Option<&str> {
+        self.secrets
+            .iter()
+            .find(|(_, value)| value.as_str() == secret)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// 💾 加密保存保险库（原子写入，Unix 下权限 0600）
+    pub fn save(&self) -> Result<()> {
+        let plaintext = serde_json::to_vec(&self.secrets)?;
+        let encrypted = PassphraseCipher::new(VAULT_MAGIC, &self.passphrase, CcrError::VaultError)
+            .encrypt(&plaintext)?;
+
+        if let Some(parent) = self.vault_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = self.vault_path.with_extension("enc.tmp");
+        fs::write(&temp_path, encrypted)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600))?;
+        }
+        fs::rename(&temp_path, &self.vault_path)?;
+
+        // 已解锁的进程内缓存以最新内容为准
+        if let Ok(mut unlocked) = UNLOCKED.lock() {
+            *unlocked = None;
+        }
+
+        tracing::debug!("🔐 保险库已保存: {:?}", self.vault_path);
+        Ok(())
+    }
+
+    /// 🔑 更换保险库口令（立即重新加密保存）
+    pub fn change_passphrase(&mut self, new_passphrase: &str) -> Result<()> {
+        if new_passphrase.is_empty() {
+            return Err(CcrError::VaultError("保险库口令不能为空".into()));
+        }
+        self.passphrase = new_passphrase.to_string();
+        self.save()
+    }
+
+    /// 🔑 将当前口令保存到系统钥匙串，之后无需再输入口令
+    pub fn remember_passphrase(&self) -> Result<()> {
+        keyring_store(&self.passphrase)
+    }
+
+    /// 🚚 将 profiles 文件中的明文 auth_token 移入保险库并替换为引用
+    ///
+    /// 使用 toml_edit 修改，保留原文件的格式与注释。新密钥命名为 `<prefix>-<profile>`，
+    /// 若保险库中已有相同 token 则复用原名称。
+    ///
+    /// # 参数
+    /// - path: profiles.toml（或旧版 ~/.ccs_config.toml）路径
+    /// - prefix: 密钥名称前缀（通常为平台名）
+    /// - dry_run: 仅预览，不写回文件（保险库内存中仍会记录，便于预览后续的备份清理）
+    pub fn migrate_profiles_file(
+        &mut self,
+        path: &Path,
+        prefix: &str,
+        dry_run: bool,
+    ) -> Result<Vec<MigratedToken>> {
+        let content = fs::read_to_string(path)?;
+        let mut doc = content.parse::<DocumentMut>().map_err(|e| {
+            CcrError::ConfigFormatInvalid(format!("解析 {} 失败: {}", path.display(), e))
+        })?;
+
+        let mut migrated = Vec::new();
+        for (profile, item) in doc.as_table_mut().iter_mut() {
+            let Some(table) = item.as_table_like_mut() else {
+                continue;
+            };
+            let Some(value) = table.get_mut("auth_token").and_then(|i| i.as_value_mut()) else {
+                continue;
+            };
+            let Some(token) = value.as_str().map(str::to_string) else {
+                continue;
+            };
+            if token.is_empty() || is_vault_ref(&token) {
+                continue;
+            }
+
+            let name = match self.find_by_secret(&token) {
+                Some(existing) => existing.to_string(),
+                None => {
+                    let name = self.unique_name(&format!("{}-{}", prefix, profile.get()));
+                    self.insert(&name, &token)?;
+                    name
+                }
+            };
+
+            let decor = value.decor().clone();
+            *value = Value::from(vault_ref(&name));
+            *value.decor_mut() = decor;
+
+            migrated.push(MigratedToken {
+                file: path.to_path_buf(),
+                profile: profile.get().to_string(),
+                vault_name: name,
+            });
+        }
+
+        if !dry_run && !migrated.is_empty() {
+            // 先持久化保险库，再替换配置中的明文，避免中途失败丢失 token
+            self.save()?;
+            fs::write(path, doc.to_string())?;
+        }
+        Ok(migrated)
+    }
+
+    /// 🧹 将文件中出现的保险库密钥替换为对应引用
+    ///
+    /// 仅处理 UTF-8 文本文件，返回文件是否包含明文密钥
+    pub fn scrub_file(&self, path: &Path, dry_run: bool) -> Result<bool> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(false);
+        };
+
+        let Some(scrubbed) = self.scrub_text(&content) else {
+            return Ok(false);
+        };
+        if !dry_run {
+            fs::write(path, scrubbed)?;
+        }
+        Ok(true)
+    }
+
+    /// 🧹 将文本中的明文密钥替换为 `vault:<名称>` 引用，不包含明文时返回 None
+    pub fn scrub_text(&self, content: &str) -> Option<String> {
+        let mut scrubbed = content.to_string();
+        for (name, secret) in &self.secrets {
+            if secret.len() >= MIN_SCRUB_SECRET_LEN {
+                scrubbed = scrubbed.replace(secret.as_str(), &vault_ref(name));
+            }
+        }
+        (scrubbed != content).then_some(scrubbed)
+    }
+
+    /// 🏷️ 生成不与现有密钥冲突的名称
+    fn unique_name(&self, base: &str) -> String {
+        let base = sanitize_name(base);
+        if !self.secrets.contains_key(&base) {
+            return base;
+        }
+        (2..)
+            .map(|i| format!("{}-{}", base, i))
+            .find(|candidate| !self.secrets.contains_key(candidate))
+            .unwrap_or(base)
+    }
+}
+
+/// 🚚 一条迁移记录
+#[derive(Debug, Clone)]
+pub struct MigratedToken {
+    pub file: PathBuf,
+    pub profile: String,
+    pub vault_name: String,
+}
+
+/// 📁 旧版 ~/.ccs_config.toml（设置了 CCR_ROOT 时不处理，避免影响真实主目录）
+pub fn legacy_config_path() -> Option<PathBuf> {
+    if std::env::var("CCR_ROOT").is_ok() {
+        return None;
+    }
+    dirs::home_dir().map(|home| home.join(".ccs_config.toml"))
+}
+
+/// 📋 收集需要迁移的 profiles 文件: (名称前缀, 路径)
+///
+/// 包含 <ccr_root>/platforms/*/profiles.toml 以及旧版 ~/.ccs_config.toml（存在时）
+pub fn migration_sources(ccr_root: &Path, legacy_config: Option<&Path>) -> Vec<(String, PathBuf)> {
+    let mut sources = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(ccr_root.join("platforms")) {
+        let mut platforms: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        platforms.sort();
+        for dir in platforms {
+            let profiles = dir.join("profiles.toml");
+            if let Some(platform) = dir.file_name().and_then(|n| n.to_str())
+                && profiles.is_file()
+            {
+                sources.push((platform.to_string(), profiles));
+            }
+        }
+    }
+
+    if let Some(legacy) = legacy_config.filter(|p| p.is_file()) {
+        sources.push(("legacy".to_string(), legacy.to_path_buf()));
+    }
+
+    sources
+}
+
+/// 📋 收集可能包含明文 token 的配置备份文件
+///
+/// - <ccr_root>/platforms/*/ 下的 profiles.toml.*.bak (ConfigFileHandler::backup)
+/// - <ccr_root>/backups/ 下的 TOML 备份（平台注册表备份与多目录备份中的 profiles）
+/// - 旧版 ~/.ccs_config.toml.*.bak
+///
+/// settings.json 备份不处理：Claude Code 只能读取明文，替换后将无法恢复
+pub fn backup_files(ccr_root: &Path, legacy_config: Option<&Path>) -> Vec<PathBuf> {
+    let is_toml_backup = |path: &Path| {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| name.contains(".toml"))
+    };
+
+    let mut files: Vec<PathBuf> = collect_files(&ccr_root.join("platforms"))
+        .into_iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "bak") && is_toml_backup(p))
+        .collect();
+    files.extend(
+        collect_files(&ccr_root.join("backups"))
+            .into_iter()
+            .filter(|p| is_toml_backup(p)),
+    );
+
+    if let Some(legacy) = legacy_config
+        && let (Some(dir), Some(file_name)) =
+            (legacy.parent(), legacy.file_name().and_then(|n| n.to_str()))
+        && let Ok(entries) = fs::read_dir(dir)
+    {
+        files.extend(entries.flatten().map(|e| e.path()).filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(file_name) && n.ends_with(".bak"))
+        }));
+    }
+
+    files.sort();
+    files
+}
+
+/// 📂 递归收集目录下的所有文件（目录不存在时为空）
+fn collect_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                files.extend(collect_files(&path));
+            } else if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// ✅ 校验密钥名称（字母、数字、`-`、`_`、`.`）
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(CcrError::VaultError(format!(
+            "无效的密钥名称 '{}'，仅允许字母、数字、'-'、'_'、'.'",
+            name
+        )))
+    }
+}
+
+/// 🧽 将任意字符串转换为合法的密钥名称
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// 🔑 获取保险库口令: 环境变量 → 钥匙串 → 交互输入（新建保险库时需确认）
+fn obtain_passphrase(is_new: bool) -> Result<String> {
+    if let Some(passphrase) = std::env::var(VAULT_PASSPHRASE_ENV)
+        .ok()
+        .filter(|p| !p.is_empty())
+    {
+        return Ok(passphrase);
+    }
+
+    if let Some(passphrase) = keyring_lookup() {
+        tracing::debug!("🔑 已从系统钥匙串获取保险库口令");
+        return Ok(passphrase);
+    }
+
+    let mut input = dialoguer::Password::new().with_prompt(if is_new {
+        "设置保险库口令"
+    } else {
+        "保险库口令"
+    });
+    if is_new {
+        input = input.with_confirmation("再次输入确认", "两次输入的口令不一致");
+    }
+    input
+        .interact()
+        .map_err(|e| CcrError::VaultError(format!("读取保险库口令失败: {}", e)))
+}
+
+/// 🔍 从系统钥匙串读取口令（工具不可用或未保存时返回 None）
+fn keyring_lookup() -> Option<String> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args([
+                "find-generic-password",
+                "-s",
+                KEYRING_SERVICE,
+                "-a",
+                KEYRING_ACCOUNT,
+                "-w",
+            ])
+            .stderr(Stdio::null())
+            .output()
+    } else {
+        Command::new("secret-tool")
+            .args([
+                "lookup",
+                "service",
+                KEYRING_SERVICE,
+                "account",
+                KEYRING_ACCOUNT,
+            ])
+            .stderr(Stdio::null())
+            .output()
+    }
+    .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    let passphrase = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    (!passphrase.is_empty()).then_some(passphrase)
+}
+
+/// 💾 将口令保存到系统钥匙串
+///
+/// 口令都通过 stdin 传入，避免出现在进程参数中（其他用户可通过 ps 看到）
+fn keyring_store(passphrase: &str) -> Result<()> {
+    use std::io::Write;
+
+    let (mut command, input) = if cfg!(target_os = "macos") {
+        // -w 放在最后且不带值时，security 提示输入口令并要求再次确认
+        let mut command = Command::new("security");
+        command.args([
+            "add-generic-password",
+            "-U",
+            "-s",
+            KEYRING_SERVICE,
+            "-a",
+            KEYRING_ACCOUNT,
+            "-w",
+        ]);
+        (command, format!("{}\n{}\n", passphrase, passphrase))
+    } else {
+        let mut command = Command::new("secret-tool");
+        command.args([
+            "store",
+            "--label=CCR vault",
+            "service",
+            KEYRING_SERVICE,
+            "account",
+            KEYRING_ACCOUNT,
+        ]);
+        (command, passphrase.to_string())
+    };
+
+    let result = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(input.as_bytes())?;
+            }
+            child.wait_with_output()
+        });
+
+    let output = result.map_err(|e| {
+        CcrError::VaultError(format!(
+            "无法访问系统钥匙串（需要 secret-tool 或 macOS security 命令）: {}",
+            e
+        ))
+    })?;
+    if !output.status.success() {
+        return Err(CcrError::VaultError(format!(
+            "保存口令到系统钥匙串失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_vault_ref_parsing() {
+        assert_eq!(
+            vault_ref_name("vault:anyrouter-main"),
+            Some("anyrouter-main")
+        );
+        assert_eq!(vault_ref_name("vault:"), None);
+        assert_eq!(vault_ref_name("sk-ant-123"), None);
+        assert_eq!(vault_ref("x"), "vault:x");
+        assert_eq!(resolve_secret("sk-plain").unwrap(), "sk-plain");
+    }
+
+    #[test]
+    fn test_vault_roundtrip_and_wrong_passphrase() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("vault.enc");
+
+        let mut vault = VaultManager::open(&path, "hunter22").unwrap();
+        vault.insert("anyrouter-main", "sk-ant-secret-123").unwrap();
+        vault.save().unwrap();
+
+        let raw = fs::read(&path).unwrap();
+        assert!(!raw.windows(6).any(|w| w == b"sk-ant"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let reopened = VaultManager::open(&path, "hunter22").unwrap();
+        assert_eq!(
+            reopened.resolve("vault:anyrouter-main").unwrap(),
+            "sk-ant-secret-123"
+        );
+        assert!(matches!(
+            reopened.resolve("vault:missing"),
+            Err(CcrError::VaultError(_))
+        ));
+
+        assert!(matches!(
+            VaultManager::open(&path, "wrong"),
+            Err(CcrError::VaultError(_))
+        ));
+    }
+
+    #[test]
+    fn test_vault_has_own_format_and_reads_legacy_files() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("vault.enc");
+
+        // 旧版本使用同步加密格式写入
+        let legacy = crate::sync::crypto::SyncCipher::new("hunter22")
+            .unwrap()
+            .encrypt(br#"{"main":"sk-ant-legacy-123"}"#)
+            .unwrap();
+        fs::write(&path, legacy).unwrap();
+
+        let vault = VaultManager::open(&path, "hunter22").unwrap();
+        assert_eq!(vault.get("main"), Some("sk-ant-legacy-123"));
+        vault.save().unwrap();
+
+        let raw = fs::read(&path).unwrap();
+        assert!(crypto::is_sealed(VAULT_MAGIC, &raw));
+        assert!(!crate::sync::crypto::is_encrypted(&raw));
+        assert_eq!(
+            VaultManager::open(&path, "hunter22").unwrap().get("main"),
+            Some("sk-ant-legacy-123")
+        );
+    }
+
+    #[test]
+    fn test_invalid_names_rejected() {
+        let temp = TempDir::new().unwrap();
+        let mut vault = VaultManager::open(temp.path().join("vault.enc"), "pw").unwrap();
+        assert!(vault.insert("has space", "sk-123").is_err());
+        assert!(vault.insert("ok", "vault:other").is_err());
+        assert_eq!(sanitize_name("claude-my profile"), "claude-my-profile");
+    }
+
+    #[test]
+    fn test_migrate_profiles_and_scrub_backups() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let platform_dir = root.join("platforms").join("claude");
+        fs::create_dir_all(&platform_dir).unwrap();
+
+        let profiles = platform_dir.join("profiles.toml");
+        let original = r#"default_config = "main"
+current_config = "main"
+
+# 主力账号
+[main]
+base_url = "https://api.example.com"
+auth_token = "sk-main-token-0001" # 生产
+
+[backup]
+base_url = "https://api.example.com"
+auth_token = "sk-main-token-0001"
+
+[already]
+auth_token = "vault:existing"
+"#;
+        fs::write(&profiles, original).unwrap();
+        let backup = platform_dir.join("profiles.toml.20240101_120000.bak");
+        fs::write(&backup, original).unwrap();
+        let settings_backup = root.join("backups").join("settings.json.bak");
+        fs::create_dir_all(settings_backup.parent().unwrap()).unwrap();
+        fs::write(&settings_backup, "\"sk-main-token-0001\"").unwrap();
+
+        let mut vault = VaultManager::open(root.join("vault.enc"), "pw").unwrap();
+
+        let sources = migration_sources(root, None);
+        assert_eq!(sources, vec![("claude".to_string(), profiles.clone())]);
+
+        // dry-run 不修改文件
+        let preview = vault
+            .migrate_profiles_file(&profiles, "claude", true)
+            .unwrap();
+        assert_eq!(preview.len(), 2);
+        assert_eq!(fs::read_to_string(&profiles).unwrap(), original);
+
+        let mut vault = VaultManager::open(root.join("vault.enc"), "pw").unwrap();
+        let migrated = vault
+            .migrate_profiles_file(&profiles, "claude", false)
+            .unwrap();
+        assert_eq!(migrated.len(), 2);
+        // 相同 token 复用同一个密钥
+        assert_eq!(migrated[0].vault_name, "claude-main");
+        assert_eq!(migrated[1].vault_name, "claude-main");
+
+        let updated = fs::read_to_string(&profiles).unwrap();
+        assert!(!updated.contains("sk-main-token-0001"));
+        assert!(updated.contains("auth_token = \"vault:claude-main\" # 生产"));
+        assert!(updated.contains("# 主力账号"));
+        assert!(updated.contains("auth_token = \"vault:existing\""));
+
+        let reopened = VaultManager::open(root.join("vault.enc"), "pw").unwrap();
+        assert_eq!(reopened.get("claude-main"), Some("sk-main-token-0001"));
+
+        let backups = backup_files(root, None);
+        assert_eq!(backups, vec![backup.clone()]);
+        assert!(reopened.scrub_file(&backup, false).unwrap());
+        assert!(
+            !fs::read_to_string(&backup)
+                .unwrap()
+                .contains("sk-main-token-0001")
+        );
+        assert!(!reopened.scrub_file(&backup, false).unwrap());
+    }
+}
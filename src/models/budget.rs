@@ -3,6 +3,8 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
 
 /// 💰 预算配置
 ///
@@ -32,6 +34,22 @@ pub struct BudgetConfig {
     /// 🔔 超出限制时的动作
     #[serde(default)]
     pub on_limit_exceeded: LimitAction,
+
+    /// 🔀 `fallback` 动作切换到的低成本 profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_profile: Option<String>,
+
+    /// 🆓 不受预算动作限制的免费 profile（也可在 profile 上添加 `free` 标签）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub free_profiles: Vec<String>,
+
+    /// 👤 按 profile 的预算限制
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ScopedLimits>,
+
+    /// 📁 按项目路径的预算限制
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, ScopedLimits>,
 }
 
 impl Default for BudgetConfig {
@@ -43,6 +61,61 @@ impl Default for BudgetConfig {
             monthly_limit: None,
             warn_at_percent: 80,
             on_limit_exceeded: LimitAction::Warn,
+            fallback_profile: None,
+            free_profiles: Vec::new(),
+            profiles: BTreeMap::new(),
+            projects: BTreeMap::new(),
+        }
+    }
+}
+
+/// 📊 profile / 项目级别的预算限制
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScopedLimits {
+    /// 📅 每日预算限制（美元，可选）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_limit: Option<f64>,
+
+    /// 📅 每周预算限制（美元，可选）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekly_limit: Option<f64>,
+
+    /// 📅 每月预算限制（美元，可选）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_limit: Option<f64>,
+}
+
+impl ScopedLimits {
+    /// 是否有任何预算限制
+    pub fn has_any_limit(&self) -> bool {
+        self.daily_limit.is_some() || self.weekly_limit.is_some() || self.monthly_limit.is_some()
+    }
+
+    /// 转换为预算限制展示结构
+    pub fn as_limits(&self) -> BudgetLimits {
+        BudgetLimits {
+            daily: self.daily_limit,
+            weekly: self.weekly_limit,
+            monthly: self.monthly_limit,
+        }
+    }
+}
+
+/// 🎯 预算限制作用范围
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "name", rename_all = "lowercase")]
+pub enum BudgetScope {
+    /// 单个 profile
+    Profile(String),
+    /// 项目目录
+    Project(String),
+}
+
+impl std::fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BudgetScope::Profile(name) => write!(f, "profile '{}'", name),
+            BudgetScope::Project(path) => write!(f, "项目 {}", path),
         }
     }
 }
@@ -53,7 +126,7 @@ fn default_warn_threshold() -> u8 {
 
 /// 🔔 超出限制时的动作
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum LimitAction {
     /// 仅警告
     #[default]
@@ -62,6 +135,22 @@ pub enum LimitAction {
     Log,
     /// 不做任何操作
     None,
+    /// 拒绝切换到付费 profile
+    BlockPaid,
+    /// 自动切换到 `fallback_profile`
+    Fallback,
+    /// 将 ANTHROPIC_MODEL 降级为 small_fast_model
+    Downgrade,
+}
+
+impl LimitAction {
+    /// 是否会改变 `ccr switch` 的行为（而不仅是提示）
+    pub fn is_enforcing(&self) -> bool {
+        matches!(
+            self,
+            LimitAction::BlockPaid | LimitAction::Fallback | LimitAction::Downgrade
+        )
+    }
 }
 
 impl std::fmt::Display for LimitAction {
@@ -70,10 +159,45 @@ impl std::fmt::Display for LimitAction {
             LimitAction::Warn => write!(f, "warn"),
             LimitAction::Log => write!(f, "log"),
             LimitAction::None => write!(f, "none"),
+            LimitAction::BlockPaid => write!(f, "block_paid"),
+            LimitAction::Fallback => write!(f, "fallback"),
+            LimitAction::Downgrade => write!(f, "downgrade"),
+        }
+    }
+}
+
+impl std::str::FromStr for LimitAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "warn" => Ok(LimitAction::Warn),
+            "log" => Ok(LimitAction::Log),
+            "none" => Ok(LimitAction::None),
+            "block_paid" => Ok(LimitAction::BlockPaid),
+            "fallback" => Ok(LimitAction::Fallback),
+            "downgrade" => Ok(LimitAction::Downgrade),
+            _ => Err(format!(
+                "未知的预算动作: {} (可选: warn, log, none, block_paid, fallback, downgrade)",
+                s
+            )),
         }
     }
 }
 
+/// 🚦 切换前的预算判定结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetEnforcement {
+    /// 允许切换
+    Allow,
+    /// 拒绝切换
+    Block { reason: String },
+    /// 改为切换到低成本 profile
+    Fallback { profile: String, reason: String },
+    /// 允许切换，但将 ANTHROPIC_MODEL 降级为 small_fast_model
+    Downgrade { reason: String },
+}
+
 /// 📊 预算状态
 ///
 /// 显示当前预算使用情况
@@ -141,6 +265,17 @@ pub struct BudgetWarning {
 
     /// 📝 警告消息
     pub message: String,
+
+    /// 🎯 作用范围（None 表示全局预算）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<BudgetScope>,
+}
+
+impl BudgetWarning {
+    /// 是否已超出限制（而非仅达到警告阈值）
+    pub fn is_exceeded(&self) -> bool {
+        self.usage_percent >= 100.0
+    }
 }
 
 /// 📅 预算周期类型
@@ -196,13 +331,63 @@ impl BudgetConfig {
             return Err("每月预算限制不能为负数".to_string());
         }
 
+        for (scope, limits) in self.profiles.iter().chain(self.projects.iter()) {
+            let negative = [
+                limits.daily_limit,
+                limits.weekly_limit,
+                limits.monthly_limit,
+            ]
+            .into_iter()
+            .flatten()
+            .any(|limit| limit < 0.0);
+            if negative {
+                return Err(format!("{} 的预算限制不能为负数", scope));
+            }
+        }
+
+        if self.on_limit_exceeded == LimitAction::Fallback
+            && self
+                .fallback_profile
+                .as_deref()
+                .is_none_or(|p| p.trim().is_empty())
+        {
+            return Err("fallback 动作需要设置 fallback_profile".to_string());
+        }
+
         Ok(())
     }
 
+    /// 是否设置了全局（每日/每周/每月）预算限制
+    pub fn has_any_global_limit(&self) -> bool {
+        self.daily_limit.is_some() || self.weekly_limit.is_some() || self.monthly_limit.is_some()
+    }
+
+    /// 🆓 是否为免费 profile（不受预算动作限制）
+    ///
+    /// 满足任一条件: 位于 `free_profiles`、是 `fallback_profile`、带有 `free` 标签
+    pub fn is_free_profile(&self, name: &str, tags: Option<&[String]>) -> bool {
+        self.free_profiles.iter().any(|p| p == name)
+            || self.fallback_profile.as_deref() == Some(name)
+            || tags.is_some_and(|tags| tags.iter().any(|t| t.eq_ignore_ascii_case("free")))
+    }
+
+    /// 📁 查找包含指定目录的项目预算（取路径最长的匹配）
+    pub fn project_for_dir(&self, dir: &Path) -> Option<(&str, &ScopedLimits)> {
+        self.projects
+            .iter()
+            .filter(|(project, _)| dir.starts_with(project.as_str()))
+            .max_by_key(|(project, _)| project.len())
+            .map(|(project, limits)| (project.as_str(), limits))
+    }
+
     /// 是否有任何预算限制
     #[allow(dead_code)]
     pub fn has_any_limit(&self) -> bool {
-        self.daily_limit.is_some() || self.weekly_limit.is_some() || self.monthly_limit.is_some()
+        self.daily_limit.is_some()
+            || self.weekly_limit.is_some()
+            || self.monthly_limit.is_some()
+            || self.profiles.values().any(ScopedLimits::has_any_limit)
+            || self.projects.values().any(ScopedLimits::has_any_limit)
     }
 }
 
@@ -272,6 +457,75 @@ mod tests {
         assert_eq!(LimitAction::Warn.to_string(), "warn");
         assert_eq!(LimitAction::Log.to_string(), "log");
         assert_eq!(LimitAction::None.to_string(), "none");
+        assert_eq!(LimitAction::BlockPaid.to_string(), "block_paid");
+    }
+
+    #[test]
+    fn test_limit_action_parse_and_serde() {
+        assert_eq!(
+            "block-paid".parse::<LimitAction>().unwrap(),
+            LimitAction::BlockPaid
+        );
+        assert_eq!(
+            "downgrade".parse::<LimitAction>().unwrap(),
+            LimitAction::Downgrade
+        );
+        assert!("explode".parse::<LimitAction>().is_err());
+
+        let config: BudgetConfig = toml::from_str(
+            r#"
+            enabled = true
+            on_limit_exceeded = "fallback"
+            fallback_profile = "cheap"
+
+            [profiles.work]
+            daily_limit = 5.0
+
+            [projects."/home/me/repo"]
+            monthly_limit = 20.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.on_limit_exceeded, LimitAction::Fallback);
+        assert!(config.validate().is_ok());
+        assert_eq!(config.profiles["work"].daily_limit, Some(5.0));
+        assert!(config.has_any_limit());
+
+        let round_trip: BudgetConfig =
+            toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(round_trip.projects, config.projects);
+    }
+
+    #[test]
+    fn test_fallback_requires_profile() {
+        let config = BudgetConfig {
+            on_limit_exceeded: LimitAction::Fallback,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_free_profile_and_project_lookup() {
+        let mut config = BudgetConfig {
+            fallback_profile: Some("cheap".into()),
+            free_profiles: vec!["local".into()],
+            ..Default::default()
+        };
+        assert!(config.is_free_profile("cheap", None));
+        assert!(config.is_free_profile("local", None));
+        assert!(config.is_free_profile("glm", Some(&["FREE".to_string()])));
+        assert!(!config.is_free_profile("opus", Some(&["paid".to_string()])));
+
+        config
+            .projects
+            .insert("/work".into(), ScopedLimits::default());
+        config
+            .projects
+            .insert("/work/repo".into(), ScopedLimits::default());
+        let (project, _) = config.project_for_dir(Path::new("/work/repo/src")).unwrap();
+        assert_eq!(project, "/work/repo");
+        assert!(config.project_for_dir(Path::new("/other")).is_none());
     }
 
     #[test]
@@ -24,8 +24,10 @@ use std::str::FromStr;
 /// - ✅ **Claude**: 完全支持（Claude Code）
 /// - ✅ **Codex**: 完全支持（GitHub Copilot CLI）
 /// - ✅ **Gemini**: 完全支持（Gemini CLI）
-/// - 🚧 **Qwen**: 计划支持（阿里通义千问 CLI）
-/// - 🚧 **IFlow**: 计划支持（iFlow CLI）
+/// - ✅ **Qwen**: 完全支持（阿里通义千问 CLI）
+/// - ✅ **IFlow**: 完全支持（iFlow CLI）
+/// - ✅ **Droid**: 完全支持（Factory Droid CLI）
+/// - 🧩 **Custom**: 由 `~/.ccr/platforms/<name>/platform.toml` 声明的自定义平台
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Platform {
@@ -35,12 +37,14 @@ pub enum Platform {
     Codex,
     /// Gemini CLI - Google Gemini CLI
     Gemini,
-    /// Qwen CLI - 阿里通义千问 CLI (未实现)
+    /// Qwen CLI - 阿里通义千问 CLI
     Qwen,
-    /// iFlow CLI - iFlow CLI (未实现)
+    /// iFlow CLI - iFlow CLI
     IFlow,
     /// Factory Droid CLI - Factory AI Droid CLI
     Droid,
+    /// 自定义平台 - 由 manifest 声明（名称见 `PlatformConfig::platform_name`）
+    Custom,
 }
 
 impl Platform {
@@ -53,6 +57,7 @@ impl Platform {
             Platform::Qwen => "Qwen CLI",
             Platform::IFlow => "iFlow CLI",
             Platform::Droid => "Factory Droid",
+            Platform::Custom => "Custom",
         }
     }
 
@@ -65,6 +70,7 @@ impl Platform {
             Platform::Qwen => "qwen",
             Platform::IFlow => "iflow",
             Platform::Droid => "droid",
+            Platform::Custom => "custom",
         }
     }
 
@@ -77,18 +83,26 @@ impl Platform {
             Platform::Qwen => "🌟",
             Platform::IFlow => "🌊",
             Platform::Droid => "🏭",
+            Platform::Custom => "🧩",
         }
     }
 
-    /// 检查平台是否已实现
+    /// 检查平台是否已实现（可以通过 `create_platform` 创建）
+    ///
+    /// Custom 只是 manifest 平台的类型标记，需要按名称通过 `create_platform_by_name` 加载
     pub fn is_implemented(&self) -> bool {
         matches!(
             self,
-            Platform::Claude | Platform::Codex | Platform::Gemini | Platform::Droid
+            Platform::Claude
+                | Platform::Codex
+                | Platform::Gemini
+                | Platform::Qwen
+                | Platform::IFlow
+                | Platform::Droid
         )
     }
 
-    /// 列出所有平台
+    /// 列出所有内置平台（不含 manifest 声明的自定义平台）
     pub fn all() -> Vec<Platform> {
         vec![
             Platform::Claude,
@@ -352,8 +366,12 @@ impl PlatformPaths {
     /// - Ok(PlatformPaths): 成功创建的路径结构
     /// - Err: 无法获取用户主目录或自定义根目录无效
     pub fn new(platform: Platform) -> Result<Self> {
+        Self::for_name(platform.short_name())
+    }
+
+    /// 🏗️ 按平台名称创建路径结构（用于 manifest 声明的自定义平台）
+    pub fn for_name(platform_name: &str) -> Result<Self> {
         let root = Self::get_ccr_root()?;
-        let platform_name = platform.short_name();
 
         Ok(Self {
             registry_file: root.join("config.toml"),
@@ -377,7 +395,7 @@ impl PlatformPaths {
     /// 优先级：
     /// 1. 环境变量 `CCR_ROOT`
     /// 2. 默认路径 `~/.ccr/`
-    fn get_ccr_root() -> Result<PathBuf> {
+    pub(crate) fn get_ccr_root() -> Result<PathBuf> {
         if let Ok(custom_root) = std::env::var("CCR_ROOT") {
             Ok(PathBuf::from(custom_root))
         } else {
@@ -469,7 +487,6 @@ pub trait PlatformConfig: Send + Sync {
     ///
     /// 返回平台特定的 settings.json 路径
     /// 例如：Claude 使用 ~/.claude/settings.json（硬编码路径）
-    #[allow(dead_code)]
     fn get_settings_path(&self) -> PathBuf;
 
     /// 应用指定的 profile
@@ -502,9 +519,45 @@ pub trait PlatformConfig: Send + Sync {
     ///
     /// # 返回
     /// 环境变量名称列表
-    fn get_env_var_names(&self) -> Vec<&'static str> {
+    fn get_env_var_names(&self) -> Vec<String> {
         vec![]
     }
+
+    /// 🌍 根据 profile 生成环境变量（`ccr exec` 注入子进程）
+    ///
+    /// 键取自 `get_env_var_names`，未设置的字段不返回；
+    /// 传入的 profile 应已解析继承与密钥，额外字段无效时返回错误
+    fn profile_env(&self, _profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        Ok(IndexMap::new())
+    }
+
+    /// 📄 apply_profile 可能写入的设置文件
+    ///
+    /// 用于 `ccr exec --overlay` 在子进程退出后恢复原内容
+    fn settings_files(&self) -> Vec<PathBuf> {
+        vec![self.get_settings_path()]
+    }
+
+    /// 🧭 应用 profile 后设置文件中由 CCR 管理的取值
+    ///
+    /// 用于漂移检测，键与 `live_values` 相同；默认与 `profile_env` 一致
+    fn expected_values(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        self.profile_env(profile)
+    }
+
+    /// 🧭 设置文件中与 profile 对应的当前取值（键与 `expected_values` 相同）
+    ///
+    /// 返回 None 表示该平台不支持漂移检测
+    fn live_values(&self, _profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        Ok(None)
+    }
+
+    /// 📥 将设置文件中的取值写回 profile（`ccr reconcile --adopt`）
+    ///
+    /// 键不对应 profile 字段或字段为间接形式（保险库引用等）时返回 false
+    fn adopt_value(&self, _profile: &mut ProfileConfig, _key: &str, _value: Option<&str>) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -532,8 +585,10 @@ mod tests {
         assert!(Platform::Claude.is_implemented());
         assert!(Platform::Codex.is_implemented());
         assert!(Platform::Gemini.is_implemented());
-        assert!(!Platform::Qwen.is_implemented());
-        assert!(!Platform::IFlow.is_implemented());
+        assert!(Platform::Qwen.is_implemented());
+        assert!(Platform::IFlow.is_implemented());
+        // manifest 平台只能按名称加载
+        assert!(!Platform::Custom.is_implemented());
     }
 
     #[test]
@@ -548,10 +603,10 @@ mod tests {
     #[test]
     fn test_platform_implemented() {
         let implemented = Platform::implemented();
-        assert_eq!(implemented.len(), 4);
+        assert_eq!(implemented.len(), 6);
         assert!(implemented.contains(&Platform::Claude));
         assert!(implemented.contains(&Platform::Droid));
-        assert!(!implemented.contains(&Platform::Qwen));
+        assert!(implemented.contains(&Platform::Qwen));
     }
 
     #[test]
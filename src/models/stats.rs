@@ -46,6 +46,10 @@ pub struct CostRecord {
     /// 📝 描述（可选）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// 👤 产生该记录时激活的 CCR profile（可选）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
 }
 
 /// 🎫 Token 使用情况
@@ -6,15 +6,24 @@
 // - 📖 从 TOML 文件加载 profiles
 // - 💾 保存 profiles 到 TOML 文件
 // - 🔄 更新 current_config 字段
+// - 🧬 解析 profile 继承 (extends)
+// - 🔐 应用前解析间接形式 (vault:<name>、${env:..}、${file:..}、auth_token_cmd)
+// - 📄 读写 JSON 设置文件 (保留未知字段)
+// - 🌍 profile 字段 → 环境变量
+// - 📥 设置文件取值 → profile 字段 (ccr reconcile --adopt)
 //
 // 设计目标: 消除 claude.rs, codex.rs, gemini.rs 中重复的 ~150 行代码
 
 use crate::core::error::{CcrError, Result};
 use crate::managers::PlatformConfigManager;
-use crate::managers::config::{CcsConfig, ConfigSection, GlobalSettings, ProviderType};
+use crate::managers::config::{
+    CcsConfig, ConfigManager, ConfigSection, GlobalSettings, ProviderType, ResolvedSection,
+};
+use crate::managers::secret_source;
 use crate::models::{PlatformPaths, ProfileConfig};
 use crate::utils::toml_json;
 use indexmap::IndexMap;
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use std::fs;
 use std::path::Path;
 
@@ -70,6 +79,93 @@ pub fn profile_to_section(profile: &ProfileConfig) -> Result<ConfigSection> {
     })
 }
 
+// ═══════════════════════════════════════════════════════════
+// 🧬 Profile 继承解析
+// ═══════════════════════════════════════════════════════════
+
+/// 🧬 解析 profile 的继承链，返回合并后的配置节及字段来源
+pub fn resolve_profile_section(
+    profiles: &IndexMap<String, ProfileConfig>,
+    name: &str,
+) -> Result<ResolvedSection> {
+    if !profiles.contains_key(name) {
+        return Err(CcrError::ProfileNotFound(name.to_string()));
+    }
+
+    let sections = profiles
+        .iter()
+        .map(|(key, profile)| Ok((key.clone(), profile_to_section(profile)?)))
+        .collect::<Result<IndexMap<_, _>>>()?;
+    let config = CcsConfig {
+        default_config: String::new(),
+        current_config: String::new(),
+        settings: GlobalSettings::default(),
+        sections,
+    };
+
+    ConfigManager::resolve_section(&config, name)
+}
+
+/// 🧬 解析 profile 的继承链，返回可直接应用的有效 profile
+pub fn resolve_profile(
+    profiles: &IndexMap<String, ProfileConfig>,
+    name: &str,
+) -> Result<ProfileConfig> {
+    let resolved = resolve_profile_section(profiles, name)?;
+    Ok(section_to_profile(&resolved.section))
+}
+
+/// 📐 profile 是否为模板 (`template = true`)
+pub fn is_template_profile(profile: &ProfileConfig) -> bool {
+    profile
+        .platform_data
+        .get(crate::managers::config::TEMPLATE_KEY)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// ✅ 校验 profile 继承后的有效配置（模板不要求字段完整）
+///
+/// `profiles` 应已包含待保存的 profile
+pub fn validate_resolved_profile(
+    profiles: &IndexMap<String, ProfileConfig>,
+    name: &str,
+    validate: impl FnOnce(&ProfileConfig) -> Result<()>,
+) -> Result<()> {
+    let mut resolved = resolve_profile(profiles, name)?;
+    if is_template_profile(&resolved) {
+        return Ok(());
+    }
+    // auth_token_cmd 在保存时不执行，视为已设置的间接 auth_token
+    resolved.auth_token = effective_auth_token(&resolved);
+    validate(&resolved)
+}
+
+/// 🔑 合并 auth_token 与 platform_data 中的 auth_token_cmd（未解析）
+pub fn effective_auth_token(profile: &ProfileConfig) -> Option<String> {
+    secret_source::effective_auth_token(
+        profile.auth_token.as_deref(),
+        profile
+            .platform_data
+            .get(secret_source::AUTH_TOKEN_CMD_KEY)
+            .and_then(|v| v.as_str()),
+    )
+}
+
+/// 🔐 解析 profile 中的间接形式，返回可直接写入平台设置的副本
+///
+/// 解析保险库引用、`${env:..}` / `${file:..}` 与 auth_token_cmd；
+/// 仅在应用 profile 时调用，profiles.toml 中始终保留原始写法
+pub fn resolve_profile_secrets(profile: &ProfileConfig) -> Result<ProfileConfig> {
+    let mut resolved = profile.clone();
+    resolved.auth_token = secret_source::resolve_optional(&effective_auth_token(profile))?;
+    resolved.base_url = secret_source::resolve_optional(&profile.base_url)?;
+    resolved
+        .platform_data
+        .shift_remove(secret_source::AUTH_TOKEN_CMD_KEY);
+    Ok(resolved)
+}
+
 // ═══════════════════════════════════════════════════════════
 // 📖 从 TOML 文件加载 profiles
 // ═══════════════════════════════════════════════════════════
@@ -306,6 +402,146 @@ pub fn get_current_profile_from_registry(platform_name: &str) -> Result<Option<S
     Ok(entry.current_profile.clone())
 }
 
+// ═══════════════════════════════════════════════════════════
+// 📄 JSON 设置文件 (保留未知字段)
+// ═══════════════════════════════════════════════════════════
+
+/// 📖 读取 JSON 设置文件为对象 (文件不存在时返回空对象)
+///
+/// 供只更新部分字段的平台使用 (Qwen, iFlow)，以保留 mcpServers 等其他设置
+pub fn load_json_settings(path: &Path, label: &str) -> Result<JsonMap<String, JsonValue>> {
+    if !path.exists() {
+        return Ok(JsonMap::new());
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| CcrError::SettingsError(format!("读取 {} 设置失败: {}", label, e)))?;
+    if content.trim().is_empty() {
+        return Ok(JsonMap::new());
+    }
+
+    match serde_json::from_str(&content) {
+        Ok(JsonValue::Object(map)) => Ok(map),
+        Ok(_) => Err(CcrError::ConfigFormatInvalid(format!(
+            "{} 设置文件顶层必须是 JSON 对象: {}",
+            label,
+            path.display()
+        ))),
+        Err(e) => Err(CcrError::ConfigFormatInvalid(format!(
+            "解析 {} 设置失败: {}",
+            label, e
+        ))),
+    }
+}
+
+/// 💾 写入 JSON 设置文件 (自动创建父目录)
+pub fn save_json_settings(
+    path: &Path,
+    settings: &JsonMap<String, JsonValue>,
+    label: &str,
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| CcrError::SettingsError(format!("创建 {} 设置目录失败: {}", label, e)))?;
+    }
+
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| CcrError::SettingsError(format!("序列化 {} 设置失败: {}", label, e)))?;
+
+    fs::write(path, content)
+        .map_err(|e| CcrError::SettingsError(format!("写入 {} 设置失败: {}", label, e)))?;
+
+    tracing::info!("✅ 已保存 {} 设置: {:?}", label, path);
+    Ok(())
+}
+
+/// 📂 获取 (必要时创建) 嵌套的 JSON 对象字段
+///
+/// 字段存在但不是对象时会被替换为空对象
+pub fn json_object_entry<'a>(
+    map: &'a mut JsonMap<String, JsonValue>,
+    key: &str,
+) -> &'a mut JsonMap<String, JsonValue> {
+    let entry = map
+        .entry(key.to_string())
+        .or_insert_with(|| JsonValue::Object(JsonMap::new()));
+    if !entry.is_object() {
+        *entry = JsonValue::Object(JsonMap::new());
+    }
+    match entry {
+        JsonValue::Object(obj) => obj,
+        _ => unreachable!("entry was just set to an object"),
+    }
+}
+
+/// 🔍 按路径读取嵌套的字符串字段，空字符串视为未设置
+pub fn json_string_at(map: &JsonMap<String, JsonValue>, path: &[&str]) -> Option<String> {
+    let (last, parents) = path.split_last()?;
+    let mut current = map;
+    for key in parents {
+        current = current.get(*key)?.as_object()?;
+    }
+    current
+        .get(*last)?
+        .as_str()
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// ✏️ 设置字符串字段，值为 None 时移除该字段
+pub fn set_json_field(map: &mut JsonMap<String, JsonValue>, key: &str, value: Option<&str>) {
+    match value {
+        Some(v) => {
+            map.insert(key.to_string(), JsonValue::from(v));
+        }
+        None => {
+            map.remove(key);
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// 🌍 环境变量
+// ═══════════════════════════════════════════════════════════
+
+/// 🌍 由 (环境变量名, 字段值) 构造环境变量表，跳过未设置或为空的字段
+pub fn env_from_fields(fields: &[(&str, Option<&String>)]) -> IndexMap<String, String> {
+    fields
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .filter(|v| !v.trim().is_empty())
+                .map(|v| (name.to_string(), v.clone()))
+        })
+        .collect()
+}
+
+/// 📥 将设置文件中的取值写回 profile 的基础字段
+///
+/// 支持 base_url / auth_token / model / small_fast_model；
+/// 字段当前为间接形式（保险库引用、`${env:..}`、auth_token_cmd）时保留原写法，返回 false
+pub fn adopt_profile_field(profile: &mut ProfileConfig, field: &str, value: Option<&str>) -> bool {
+    if field == "auth_token"
+        && profile
+            .platform_data
+            .contains_key(secret_source::AUTH_TOKEN_CMD_KEY)
+    {
+        return false;
+    }
+    let slot = match field {
+        "base_url" => &mut profile.base_url,
+        "auth_token" => &mut profile.auth_token,
+        "model" => &mut profile.model,
+        "small_fast_model" => &mut profile.small_fast_model,
+        _ => return false,
+    };
+    if slot.as_deref().is_some_and(secret_source::is_indirect) {
+        return false;
+    }
+    *slot = value.map(str::to_string);
+    true
+}
+
 // ═══════════════════════════════════════════════════════════
 // 🧪 测试
 // ═══════════════════════════════════════════════════════════
@@ -362,4 +598,47 @@ mod tests {
         let section3 = profile_to_section(&profile).unwrap();
         assert_eq!(section3.provider_type, None);
     }
+
+    #[test]
+    fn test_adopt_profile_field_keeps_indirect_values() {
+        let mut profile = ProfileConfig::new();
+        profile.auth_token = Some("vault:relay".to_string());
+        profile.model = Some("old-model".to_string());
+
+        assert!(adopt_profile_field(
+            &mut profile,
+            "model",
+            Some("new-model")
+        ));
+        assert_eq!(profile.model.as_deref(), Some("new-model"));
+        assert!(!adopt_profile_field(
+            &mut profile,
+            "auth_token",
+            Some("sk-live")
+        ));
+        assert_eq!(profile.auth_token.as_deref(), Some("vault:relay"));
+        assert!(!adopt_profile_field(&mut profile, "provider", Some("x")));
+
+        let settings = serde_json::json!({"security": {"auth": {"apiKey": "sk-1", "baseUrl": ""}}});
+        let map = settings.as_object().unwrap();
+        assert_eq!(
+            json_string_at(map, &["security", "auth", "apiKey"]).as_deref(),
+            Some("sk-1")
+        );
+        assert_eq!(json_string_at(map, &["security", "auth", "baseUrl"]), None);
+        assert_eq!(json_string_at(map, &["model", "name"]), None);
+    }
+
+    #[test]
+    fn test_env_from_fields_skips_unset() {
+        let token = "sk-test".to_string();
+        let blank = " ".to_string();
+        let env = env_from_fields(&[
+            ("TOKEN", Some(&token)),
+            ("MODEL", None),
+            ("BASE_URL", Some(&blank)),
+        ]);
+        assert_eq!(env.len(), 1);
+        assert_eq!(env["TOKEN"], "sk-test");
+    }
 }
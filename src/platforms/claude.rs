@@ -12,14 +12,20 @@
 // - 支持多平台配置
 
 use crate::core::error::{CcrError, Result};
+use crate::core::lock::LockManager;
 use crate::managers::PlatformConfigManager;
 use crate::managers::config::ConfigSection;
+use crate::managers::profile_env;
+use crate::managers::project_config::project_settings_path;
+use crate::managers::secret_source;
 use crate::managers::settings::{ClaudeSettings, SettingsManager};
+use crate::managers::settings_bundle::{self, SettingsChange};
 use crate::models::{Platform, PlatformConfig, PlatformPaths, ProfileConfig};
 use crate::platforms::base;
 use crate::utils::Validatable;
 use indexmap::IndexMap;
-use std::path::PathBuf;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
 
 /// 🤖 Claude Platform 实现
 ///
@@ -72,6 +78,97 @@ impl ClaudePlatform {
     fn load_profiles_from_file(&self) -> Result<IndexMap<String, ProfileConfig>> {
         base::load_profiles_from_toml(&self.paths.profiles_file)
     }
+
+    /// 📌 将 profile 应用到项目级设置文件
+    ///
+    /// 写入 `<project_root>/.claude/settings.local.json`，不修改用户级设置和注册表中的当前 profile；
+    /// `model_override` 为预算降级时替换 ANTHROPIC_MODEL 的模型
+    pub fn apply_profile_to_project(
+        &self,
+        name: &str,
+        project_root: &Path,
+        model_override: Option<&str>,
+    ) -> Result<PathBuf> {
+        let section = self.resolved_section(name)?;
+
+        let settings_path = project_settings_path(project_root);
+        let settings_manager = SettingsManager::new(
+            &settings_path,
+            project_root.join(".claude").join("backups"),
+            LockManager::with_default_path()?,
+        );
+
+        let mut settings = settings_manager
+            .load()
+            .unwrap_or_else(|_| ClaudeSettings::new());
+        settings.apply_section(&section)?;
+        if let Some(model) = model_override {
+            settings
+                .env
+                .insert("ANTHROPIC_MODEL".to_string(), model.to_string());
+        }
+        settings_manager.save_atomic(&settings)?;
+
+        tracing::info!(
+            "✅ 已应用 Claude 项目级 profile: {} ({:?})",
+            name,
+            settings_path
+        );
+        Ok(settings_path)
+    }
+
+    /// 🧬 解析继承与动态密钥后的配置节（已验证）
+    fn resolved_section(&self, name: &str) -> Result<ConfigSection> {
+        let profiles = self.load_profiles()?;
+        let profile = &base::resolve_profile_secrets(&base::resolve_profile(&profiles, name)?)?;
+
+        let section = Self::profile_to_section(profile)?;
+        section.validate()?;
+        Ok(section)
+    }
+
+    /// 🔍 预览应用 profile 前后的用户级设置，不写入任何文件
+    ///
+    /// 返回 (当前设置, 应用后的设置)
+    pub fn preview_settings(&self, name: &str) -> Result<(ClaudeSettings, ClaudeSettings)> {
+        let section = self.resolved_section(name)?;
+
+        let current = self
+            .settings_manager
+            .load()
+            .unwrap_or_else(|_| ClaudeSettings::new());
+        let mut next = current.clone();
+        next.apply_section(&section)?;
+        Ok((current, next))
+    }
+
+    /// 🔍 当前 settings.json 与应用 profile 后应有内容的差异（旧值为当前内容）
+    ///
+    /// 不解析动态密钥：预期值为间接形式（保险库引用、`${env:..}` 等）的变量只检查是否存在
+    pub fn settings_drift(&self, name: &str) -> Result<Vec<SettingsChange>> {
+        let profiles = self.load_profiles()?;
+        let mut profile = base::resolve_profile(&profiles, name)?;
+        profile.auth_token = base::effective_auth_token(&profile);
+        let section = Self::profile_to_section(&profile)?;
+
+        let current = self
+            .settings_manager
+            .load()
+            .unwrap_or_else(|_| ClaudeSettings::new());
+        let mut expected = current.clone();
+        expected.apply_section(&section)?;
+
+        Ok(settings_bundle::diff(
+            &serde_json::to_value(&current)?,
+            &serde_json::to_value(&expected)?,
+        )
+        .into_iter()
+        .filter(|change| match (&change.old, &change.new) {
+            (Some(_), Some(Value::String(new))) => !secret_source::is_indirect(new),
+            _ => true,
+        })
+        .collect())
+    }
 }
 
 impl PlatformConfig for ClaudePlatform {
@@ -88,11 +185,12 @@ impl PlatformConfig for ClaudePlatform {
     }
 
     fn save_profile(&self, name: &str, profile: &ProfileConfig) -> Result<()> {
-        // 先验证
-        self.validate_profile(profile)?;
-
         let mut profiles = self.load_profiles()?;
         profiles.insert(name.to_string(), profile.clone());
+
+        // 验证继承后的有效配置
+        base::validate_resolved_profile(&profiles, name, |p| self.validate_profile(p))?;
+
         self.save_profiles(&profiles)
     }
 
@@ -109,27 +207,18 @@ impl PlatformConfig for ClaudePlatform {
         self.settings_manager.settings_path().to_path_buf()
     }
 
-    fn apply_profile(&self, name: &str) -> Result<()> {
-        // 加载 profile
-        let profiles = self.load_profiles()?;
-        let profile = profiles
-            .get(name)
-            .ok_or_else(|| CcrError::ProfileNotFound(name.to_string()))?;
-
-        // 转换为 ConfigSection
-        let section = Self::profile_to_section(profile)?;
-
-        // 验证
-        section.validate()?;
-
-        // 加载当前设置
-        let mut settings = self
-            .settings_manager
-            .load()
-            .unwrap_or_else(|_| ClaudeSettings::new());
+    fn settings_files(&self) -> Vec<PathBuf> {
+        // CCR 写入记录与 settings.json 一同变化，需一起回滚 / 恢复
+        let mut files = vec![self.get_settings_path()];
+        if let Ok(store) = self.settings_manager.managed_store() {
+            files.push(store.path().to_path_buf());
+        }
+        files
+    }
 
-        // 更新设置
-        settings.update_from_config(&section);
+    fn apply_profile(&self, name: &str) -> Result<()> {
+        // 计算应用后的设置（环境变量 + 设置包）
+        let (_, settings) = self.preview_settings(name)?;
 
         // 原子保存
         self.settings_manager.save_atomic(&settings)?;
@@ -163,13 +252,100 @@ impl PlatformConfig for ClaudePlatform {
         base::get_current_profile_from_registry("claude")
     }
 
-    fn get_env_var_names(&self) -> Vec<&'static str> {
-        vec![
-            "ANTHROPIC_BASE_URL",
-            "ANTHROPIC_AUTH_TOKEN",
-            "ANTHROPIC_MODEL",
-            "ANTHROPIC_SMALL_FAST_MODEL",
-        ]
+    fn get_env_var_names(&self) -> Vec<String> {
+        profile_env::claude_env_var_names()
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    fn profile_env(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        // 与切换时写入 settings.json 的变量一致（含 models 映射与 env 表）
+        let section = Self::profile_to_section(profile)?;
+        // 无效的 auth_mode / models / env 直接报错，不按未设置处理
+        section.profile_env()?;
+        Ok(section.claude_env())
+    }
+
+    fn live_values(&self, _profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        if !self.settings_manager.settings_path().exists() {
+            return Ok(Some(IndexMap::new()));
+        }
+        let settings = self.settings_manager.load()?;
+
+        // 切换时会清除的变量: 所有 ANTHROPIC_*、固定变量与上次切换写入的变量
+        let managed = settings.managed_env_vars();
+        let fixed = profile_env::claude_env_var_names();
+        let mut live: IndexMap<String, String> = settings
+            .env
+            .iter()
+            .filter(|(name, _)| {
+                name.starts_with("ANTHROPIC_")
+                    || fixed.contains(&name.as_str())
+                    || managed.contains(name)
+            })
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        live.sort_keys();
+        Ok(Some(live))
+    }
+
+    fn adopt_value(&self, profile: &mut ProfileConfig, key: &str, value: Option<&str>) -> bool {
+        match key {
+            "ANTHROPIC_BASE_URL" => base::adopt_profile_field(profile, "base_url", value),
+            "ANTHROPIC_MODEL" => base::adopt_profile_field(profile, "model", value),
+            "ANTHROPIC_SMALL_FAST_MODEL" => {
+                base::adopt_profile_field(profile, "small_fast_model", value)
+            }
+            "ANTHROPIC_AUTH_TOKEN" | "ANTHROPIC_API_KEY" => {
+                // 凭证被移除时不清空 profile 中的 auth_token
+                let Some(value) = value else {
+                    return false;
+                };
+                if !base::adopt_profile_field(profile, "auth_token", Some(value)) {
+                    return false;
+                }
+                if key == "ANTHROPIC_API_KEY" {
+                    profile
+                        .platform_data
+                        .insert(profile_env::AUTH_MODE_KEY.into(), Value::from("api_key"));
+                } else {
+                    profile
+                        .platform_data
+                        .shift_remove(profile_env::AUTH_MODE_KEY);
+                }
+                true
+            }
+            _ => {
+                let (table, entry) = match profile_env::model_tier(key) {
+                    Some(tier) => (profile_env::MODELS_KEY, tier),
+                    None => (profile_env::ENV_KEY, key),
+                };
+                set_table_entry(&mut profile.platform_data, table, entry, value);
+                true
+            }
+        }
+    }
+}
+
+/// ✏️ 设置 profile 中 models / env 表的一项，值为 None 时移除，表为空时删除整个表
+fn set_table_entry(
+    data: &mut IndexMap<String, Value>,
+    table: &str,
+    key: &str,
+    value: Option<&str>,
+) {
+    let entry = data
+        .entry(table.to_string())
+        .or_insert_with(|| Value::Object(serde_json::Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(serde_json::Map::new());
+    }
+    if let Some(map) = entry.as_object_mut() {
+        base::set_json_field(map, key, value);
+        if map.is_empty() {
+            data.shift_remove(table);
+        }
     }
 }
 
@@ -208,6 +384,33 @@ mod tests {
         assert_eq!(section.base_url, section2.base_url);
     }
 
+    #[test]
+    fn test_profile_env_reports_invalid_extras() {
+        use crate::managers::config::ConfigSection;
+
+        let Ok(platform) = ClaudePlatform::new() else {
+            return;
+        };
+        let mut section = ConfigSection {
+            base_url: Some("https://api.test.com".to_string()),
+            auth_token: Some("sk-test".to_string()),
+            ..Default::default()
+        };
+
+        section.other = toml::from_str("[env]\nAPI_TIMEOUT_MS = 600000\n").unwrap();
+        let env = platform
+            .profile_env(&ClaudePlatform::section_to_profile(&section))
+            .unwrap();
+        assert_eq!(env["API_TIMEOUT_MS"], "600000");
+
+        section.other = toml::from_str("[env]\nAPI_TIMEOUT_MS = \"ten minutes\"\n").unwrap();
+        assert!(
+            platform
+                .profile_env(&ClaudePlatform::section_to_profile(&section))
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_platform_trait_impl() {
         if let Ok(platform) = ClaudePlatform::new() {
@@ -8,6 +8,7 @@
 // - 💾 仅支持 Unified 模式
 
 use crate::core::error::{CcrError, Result};
+use crate::managers::secret_source;
 use crate::models::{Platform, PlatformConfig, PlatformPaths, ProfileConfig};
 use crate::platforms::base;
 use crate::utils::Validatable;
@@ -402,7 +403,6 @@ impl CodexPlatform {
     }
 
     /// 📖 加载 Codex settings
-    #[allow(dead_code)]
     fn load_settings(&self) -> Result<CodexSettings> {
         let settings_path = Self::codex_settings_path()?;
         if !settings_path.exists() {
@@ -504,15 +504,15 @@ impl PlatformConfig for CodexPlatform {
     }
 
     fn save_profile(&self, name: &str, profile: &ProfileConfig) -> Result<()> {
-        // 先验证
-        self.validate_profile(profile)?;
-
         // 加载现有 profiles
         let mut profiles = self.load_profiles()?;
 
         // 添加/更新 profile
         profiles.insert(name.to_string(), profile.clone());
 
+        // 验证继承后的有效配置
+        base::validate_resolved_profile(&profiles, name, |p| self.validate_profile(p))?;
+
         // 保存
         self.save_profiles_to_file(&profiles)
     }
@@ -534,9 +534,7 @@ impl PlatformConfig for CodexPlatform {
     fn apply_profile(&self, name: &str) -> Result<()> {
         // 加载 profile
         let profiles = self.load_profiles()?;
-        let profile = profiles
-            .get(name)
-            .ok_or_else(|| CcrError::ProfileNotFound(name.to_string()))?;
+        let profile = &base::resolve_profile_secrets(&base::resolve_profile(&profiles, name)?)?;
 
         // 验证
         self.validate_profile(profile)?;
@@ -579,7 +577,10 @@ impl PlatformConfig for CodexPlatform {
             CcrError::ValidationError("Codex profile 缺少 base_url (api_endpoint)".into())
         })?;
 
-        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+        if !secret_source::is_indirect(base_url)
+            && !base_url.starts_with("http://")
+            && !base_url.starts_with("https://")
+        {
             return Err(CcrError::ValidationError(
                 "api_endpoint 必须以 http:// 或 https:// 开头".into(),
             ));
@@ -591,7 +592,10 @@ impl PlatformConfig for CodexPlatform {
         })?;
 
         if Self::is_github_profile(profile) {
-            Self::validate_github_token(token)?;
+            // 间接形式（保险库、${env:..} 等）在应用时才解析，此处无法校验格式
+            if !secret_source::is_indirect(token) {
+                Self::validate_github_token(token)?;
+            }
         } else if token.trim().is_empty() {
             return Err(CcrError::ValidationError(
                 "Codex profile 缺少有效的 API key".into(),
@@ -608,8 +612,90 @@ impl PlatformConfig for CodexPlatform {
         base::get_current_profile_from_registry("codex")
     }
 
-    fn get_env_var_names(&self) -> Vec<&'static str> {
-        vec!["OPENAI_API_KEY"]
+    fn get_env_var_names(&self) -> Vec<String> {
+        vec!["OPENAI_API_KEY".to_string()]
+    }
+
+    fn profile_env(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        // 官方配置使用 codex login 的凭证
+        if Self::is_official_profile(profile) {
+            return Ok(IndexMap::new());
+        }
+        Ok(base::env_from_fields(&[(
+            "OPENAI_API_KEY",
+            profile.auth_token.as_ref(),
+        )]))
+    }
+
+    fn settings_files(&self) -> Vec<PathBuf> {
+        [
+            Self::codex_settings_path(),
+            Self::codex_config_path(),
+            Self::codex_auth_path(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+    fn expected_values(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        Ok(if Self::is_official_profile(profile) {
+            IndexMap::new()
+        } else if Self::is_github_profile(profile) {
+            base::env_from_fields(&[("github.token", profile.auth_token.as_ref())])
+        } else {
+            base::env_from_fields(&[
+                ("base_url", profile.base_url.as_ref()),
+                ("OPENAI_API_KEY", profile.auth_token.as_ref()),
+            ])
+        })
+    }
+
+    fn live_values(&self, profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        let mut live = IndexMap::new();
+
+        if Self::is_github_profile(profile) && !Self::is_official_profile(profile) {
+            if let Ok(settings) = self.load_settings() {
+                live.insert("github.token".to_string(), settings.github.token);
+            }
+            return Ok(Some(live));
+        }
+
+        // 官方配置不应有自定义 provider；自定义配置读取 provider 的 base_url 与 auth.json
+        let config = Self::read_codex_config(&Self::codex_config_path()?)?;
+        let Some(provider_id) = config.get("model_provider").and_then(|v| v.as_str()) else {
+            return Ok(Some(live));
+        };
+        if Self::is_official_profile(profile) {
+            live.insert("model_provider".to_string(), provider_id.to_string());
+            return Ok(Some(live));
+        }
+
+        if let Some(base_url) = config
+            .get("model_providers")
+            .and_then(|providers| providers.get(provider_id))
+            .and_then(|provider| provider.get("base_url"))
+            .and_then(|v| v.as_str())
+        {
+            live.insert("base_url".to_string(), base_url.to_string());
+        }
+        let auth_path = Self::codex_auth_path()?;
+        if auth_path.exists() {
+            let auth = base::load_json_settings(&auth_path, "Codex auth.json")?;
+            if let Some(key) = base::json_string_at(&auth, &["OPENAI_API_KEY"]) {
+                live.insert("OPENAI_API_KEY".to_string(), key);
+            }
+        }
+        Ok(Some(live))
+    }
+
+    fn adopt_value(&self, profile: &mut ProfileConfig, key: &str, value: Option<&str>) -> bool {
+        match key {
+            "base_url" => base::adopt_profile_field(profile, "base_url", value),
+            "OPENAI_API_KEY" | "github.token" => {
+                base::adopt_profile_field(profile, "auth_token", value)
+            }
+            _ => false,
+        }
     }
 }
 
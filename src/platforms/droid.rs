@@ -193,11 +193,12 @@ impl PlatformConfig for DroidPlatform {
     }
 
     fn save_profile(&self, name: &str, profile: &ProfileConfig) -> Result<()> {
-        // 先验证
-        self.validate_profile(profile)?;
-
         let mut profiles = self.load_profiles()?;
         profiles.insert(name.to_string(), profile.clone());
+
+        // 验证继承后的有效配置
+        base::validate_resolved_profile(&profiles, name, |p| self.validate_profile(p))?;
+
         self.save_profiles(&profiles)
     }
 
@@ -216,9 +217,7 @@ impl PlatformConfig for DroidPlatform {
     fn apply_profile(&self, name: &str) -> Result<()> {
         // 加载 profile
         let profiles = self.load_profiles()?;
-        let profile = profiles
-            .get(name)
-            .ok_or_else(|| CcrError::ProfileNotFound(name.to_string()))?;
+        let profile = &base::resolve_profile_secrets(&base::resolve_profile(&profiles, name)?)?;
 
         // 转换为 ConfigSection 并验证
         let section = Self::profile_to_section(profile)?;
@@ -281,10 +280,41 @@ impl PlatformConfig for DroidPlatform {
         base::get_current_profile_from_registry("droid")
     }
 
-    fn get_env_var_names(&self) -> Vec<&'static str> {
+    fn get_env_var_names(&self) -> Vec<String> {
         // Droid 不使用环境变量，直接写入 settings.json
         vec![]
     }
+
+    fn expected_values(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        // 对应 settings.json 中 customModels[0] 的字段
+        Ok(base::env_from_fields(&[
+            ("baseUrl", profile.base_url.as_ref()),
+            ("apiKey", profile.auth_token.as_ref()),
+            ("model", profile.model.as_ref()),
+        ]))
+    }
+
+    fn live_values(&self, _profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        let settings = self.load_droid_settings()?;
+        let Some(custom_model) = settings.custom_models.first() else {
+            return Ok(Some(IndexMap::new()));
+        };
+        Ok(Some(base::env_from_fields(&[
+            ("baseUrl", Some(&custom_model.base_url)),
+            ("apiKey", Some(&custom_model.api_key)),
+            ("model", Some(&custom_model.model)),
+        ])))
+    }
+
+    fn adopt_value(&self, profile: &mut ProfileConfig, key: &str, value: Option<&str>) -> bool {
+        let field = match key {
+            "baseUrl" => "base_url",
+            "apiKey" => "auth_token",
+            "model" => "model",
+            _ => return false,
+        };
+        base::adopt_profile_field(profile, field, value)
+    }
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -8,6 +8,7 @@
 // - 💾 仅支持 Unified 模式
 
 use crate::core::error::{CcrError, Result};
+use crate::managers::secret_source;
 use crate::models::{Platform, PlatformConfig, PlatformPaths, ProfileConfig};
 use crate::platforms::base;
 use crate::utils::Validatable;
@@ -174,15 +175,15 @@ impl PlatformConfig for GeminiPlatform {
     }
 
     fn save_profile(&self, name: &str, profile: &ProfileConfig) -> Result<()> {
-        // 先验证
-        self.validate_profile(profile)?;
-
         // 加载现有 profiles
         let mut profiles = self.load_profiles()?;
 
         // 添加/更新 profile
         profiles.insert(name.to_string(), profile.clone());
 
+        // 验证继承后的有效配置
+        base::validate_resolved_profile(&profiles, name, |p| self.validate_profile(p))?;
+
         // 保存
         self.save_profiles_to_file(&profiles)
     }
@@ -204,9 +205,7 @@ impl PlatformConfig for GeminiPlatform {
     fn apply_profile(&self, name: &str) -> Result<()> {
         // 加载 profile
         let profiles = self.load_profiles()?;
-        let profile = profiles
-            .get(name)
-            .ok_or_else(|| CcrError::ProfileNotFound(name.to_string()))?;
+        let profile = &base::resolve_profile_secrets(&base::resolve_profile(&profiles, name)?)?;
 
         // 验证
         self.validate_profile(profile)?;
@@ -239,7 +238,10 @@ impl PlatformConfig for GeminiPlatform {
         let api_key = profile.auth_token.as_ref().ok_or_else(|| {
             CcrError::ValidationError("Gemini profile 缺少 auth_token (API key)".into())
         })?;
-        Self::validate_api_key(api_key)?;
+        // 间接形式（保险库、${env:..} 等）在应用时才解析，此处无法校验格式
+        if !secret_source::is_indirect(api_key) {
+            Self::validate_api_key(api_key)?;
+        }
 
         Ok(())
     }
@@ -248,8 +250,30 @@ impl PlatformConfig for GeminiPlatform {
         base::get_current_profile_from_registry("gemini")
     }
 
-    fn get_env_var_names(&self) -> Vec<&'static str> {
-        vec!["GEMINI_API_KEY"]
+    fn get_env_var_names(&self) -> Vec<String> {
+        vec!["GEMINI_API_KEY".to_string()]
+    }
+
+    fn profile_env(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        Ok(base::env_from_fields(&[(
+            "GEMINI_API_KEY",
+            profile.auth_token.as_ref(),
+        )]))
+    }
+
+    fn live_values(&self, _profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        let settings = base::load_json_settings(&self.paths.settings_file, "Gemini")?;
+        let api_key = base::json_string_at(&settings, &["google", "api_key"]);
+        Ok(Some(
+            api_key
+                .map(|key| ("GEMINI_API_KEY".to_string(), key))
+                .into_iter()
+                .collect(),
+        ))
+    }
+
+    fn adopt_value(&self, profile: &mut ProfileConfig, key: &str, value: Option<&str>) -> bool {
+        key == "GEMINI_API_KEY" && base::adopt_profile_field(profile, "auth_token", value)
     }
 }
 
@@ -1,32 +1,95 @@
-// 🌊 iFlow Platform 实现（Stub）
+// 🌊 iFlow Platform 实现
 // 📦 iFlow CLI 平台配置管理
 //
-// ⚠️ 注意：这是一个占位实现（stub），尚未完全实现
-//
 // 核心职责:
-// - 🚧 返回 NotImplemented 错误
-// - 📋 为未来实现预留接口
+// - 📋 管理 iFlow profiles
+// - ⚙️ 操作 ~/.iflow/settings.json（仅更新认证与模型字段，保留 mcpServers 等其他设置）
+// - 🔐 验证 API key 与接口地址
+//
+// 📁 配置结构 (Unified 模式):
+// - CCR 配置: `~/.ccr/platforms/iflow/profiles.toml`
+// - iFlow 设置: `~/.iflow/settings.json`（可通过 CCR_IFLOW_SETTINGS_PATH 覆盖）
 
 use crate::core::error::{CcrError, Result};
-use crate::models::{Platform, PlatformConfig, ProfileConfig};
+use crate::managers::secret_source;
+use crate::models::{Platform, PlatformConfig, PlatformPaths, ProfileConfig};
+use crate::platforms::base;
 use indexmap::IndexMap;
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use std::path::PathBuf;
 
-/// 🌊 iFlow Platform 实现（Stub）
+/// 🌐 iFlow 官方 API 地址
+const IFLOW_API_HOST: &str = "apis.iflow.cn";
+
+/// 🌊 iFlow Platform 实现
 ///
-/// ## 状态
-/// 🚧 未实现 - 计划在未来版本中支持
+/// ## 配置文件
+/// - Profiles: `~/.ccr/platforms/iflow/profiles.toml`
+/// - Settings: `~/.iflow/settings.json`
 ///
-/// ## 预期功能
-/// - 管理 iFlow CLI 配置 profiles
-/// - 支持 iFlow API
-/// - 验证 API key 格式
-pub struct IFlowPlatform;
+/// ## 字段映射
+/// - `auth_token` → `apiKey`
+/// - `base_url`   → `baseUrl`
+/// - `model`      → `modelName`
+/// - `platform_data.auth_type` → `selectedAuthType`
+///   （默认: 官方地址或未设置 base_url 时为 `iflow`，否则为 `openai-compatible`）
+pub struct IFlowPlatform {
+    paths: PlatformPaths,
+    settings_path: PathBuf,
+}
 
 impl IFlowPlatform {
     /// 🏗️ 创建新的 iFlow Platform 实例
+    ///
+    /// ⚙️ **开发者注意**:
+    /// 可以通过环境变量 `CCR_IFLOW_SETTINGS_PATH` 覆盖 settings.json 路径
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        let paths = PlatformPaths::new(Platform::IFlow)?;
+
+        let settings_path = if let Ok(custom_path) = std::env::var("CCR_IFLOW_SETTINGS_PATH") {
+            PathBuf::from(custom_path)
+        } else {
+            let home = dirs::home_dir()
+                .ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?;
+            home.join(".iflow").join("settings.json")
+        };
+
+        Ok(Self {
+            paths,
+            settings_path,
+        })
+    }
+
+    /// 💾 保存 profiles 到 TOML 文件
+    fn save_profiles(&self, profiles: &IndexMap<String, ProfileConfig>) -> Result<()> {
+        base::save_profiles_to_toml(&self.paths.profiles_file, profiles, "iflow", &self.paths)
+    }
+
+    /// 🔑 推断 selectedAuthType
+    fn auth_type(profile: &ProfileConfig) -> String {
+        if let Some(auth_type) = profile
+            .platform_data
+            .get("auth_type")
+            .and_then(|v| v.as_str())
+        {
+            return auth_type.to_string();
+        }
+
+        match &profile.base_url {
+            Some(url) if !url.contains(IFLOW_API_HOST) => "openai-compatible".to_string(),
+            _ => "iflow".to_string(),
+        }
+    }
+
+    /// 📋 将 profile 写入 iFlow settings（保留其他字段）
+    fn apply_to_settings(settings: &mut JsonMap<String, JsonValue>, profile: &ProfileConfig) {
+        settings.insert(
+            "selectedAuthType".into(),
+            JsonValue::from(Self::auth_type(profile)),
+        );
+        base::set_json_field(settings, "apiKey", profile.auth_token.as_deref());
+        base::set_json_field(settings, "baseUrl", profile.base_url.as_deref());
+        base::set_json_field(settings, "modelName", profile.model.as_deref());
     }
 }
 
@@ -40,73 +103,180 @@ impl PlatformConfig for IFlowPlatform {
     }
 
     fn load_profiles(&self) -> Result<IndexMap<String, ProfileConfig>> {
-        Err(CcrError::PlatformNotSupported(
-            "iFlow 平台尚未实现".to_string(),
-        ))
+        base::load_profiles_from_toml(&self.paths.profiles_file)
     }
 
-    fn save_profile(&self, _name: &str, _profile: &ProfileConfig) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "iFlow 平台尚未实现".to_string(),
-        ))
+    fn save_profile(&self, name: &str, profile: &ProfileConfig) -> Result<()> {
+        let mut profiles = self.load_profiles()?;
+        profiles.insert(name.to_string(), profile.clone());
+
+        // 验证继承后的有效配置
+        base::validate_resolved_profile(&profiles, name, |p| self.validate_profile(p))?;
+
+        self.save_profiles(&profiles)
     }
 
-    fn delete_profile(&self, _name: &str) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "iFlow 平台尚未实现".to_string(),
-        ))
+    fn delete_profile(&self, name: &str) -> Result<()> {
+        let mut profiles = self.load_profiles()?;
+        if profiles.shift_remove(name).is_none() {
+            return Err(CcrError::ProfileNotFound(name.to_string()));
+        }
+        self.save_profiles(&profiles)
     }
 
     fn get_settings_path(&self) -> PathBuf {
-        // 返回一个占位路径
-        PathBuf::from("~/.ccr/platforms/iflow/settings.json")
+        self.settings_path.clone()
     }
 
-    fn apply_profile(&self, _name: &str) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "iFlow 平台尚未实现".to_string(),
-        ))
+    fn apply_profile(&self, name: &str) -> Result<()> {
+        // 加载 profile
+        let profiles = self.load_profiles()?;
+        let profile = &base::resolve_profile_secrets(&base::resolve_profile(&profiles, name)?)?;
+
+        // 验证
+        self.validate_profile(profile)?;
+
+        // 更新 iFlow settings
+        let mut settings = base::load_json_settings(&self.settings_path, "iFlow")?;
+        Self::apply_to_settings(&mut settings, profile);
+        base::save_json_settings(&self.settings_path, &settings, "iFlow")?;
+
+        // 更新 profiles.toml 与注册表中的当前 profile
+        base::update_current_config(&self.paths.profiles_file, name)?;
+        base::update_registry_current_profile("iflow", name)?;
+
+        tracing::info!("✅ 已应用 iFlow profile: {}", name);
+        Ok(())
     }
 
-    fn validate_profile(&self, _profile: &ProfileConfig) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "iFlow 平台尚未实现".to_string(),
-        ))
+    fn validate_profile(&self, profile: &ProfileConfig) -> Result<()> {
+        // iFlow 需要: auth_token (apiKey)；base_url 可选（默认官方地址）
+        if profile
+            .auth_token
+            .as_ref()
+            .map(|s| s.trim().is_empty())
+            .unwrap_or(true)
+        {
+            return Err(CcrError::ValidationError(
+                "iFlow profile 缺少 auth_token (apiKey)".into(),
+            ));
+        }
+
+        if let Some(base_url) = &profile.base_url
+            && !secret_source::is_indirect(base_url)
+            && !base_url.starts_with("http://")
+            && !base_url.starts_with("https://")
+        {
+            return Err(CcrError::ValidationError(
+                "base_url 必须以 http:// 或 https:// 开头".into(),
+            ));
+        }
+
+        Ok(())
     }
 
     fn get_current_profile(&self) -> Result<Option<String>> {
-        Err(CcrError::PlatformNotSupported(
-            "iFlow 平台尚未实现".to_string(),
+        base::get_current_profile_from_registry("iflow")
+    }
+
+    fn get_env_var_names(&self) -> Vec<String> {
+        ["IFLOW_apiKey", "IFLOW_baseUrl", "IFLOW_modelName"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    fn profile_env(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        Ok(base::env_from_fields(&[
+            ("IFLOW_apiKey", profile.auth_token.as_ref()),
+            ("IFLOW_baseUrl", profile.base_url.as_ref()),
+            ("IFLOW_modelName", profile.model.as_ref()),
+        ]))
+    }
+
+    fn live_values(&self, _profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        let settings = base::load_json_settings(&self.settings_path, "iFlow")?;
+        Ok(Some(
+            [
+                ("IFLOW_apiKey", "apiKey"),
+                ("IFLOW_baseUrl", "baseUrl"),
+                ("IFLOW_modelName", "modelName"),
+            ]
+            .into_iter()
+            .filter_map(|(key, field)| {
+                base::json_string_at(&settings, &[field]).map(|value| (key.to_string(), value))
+            })
+            .collect(),
         ))
     }
+
+    fn adopt_value(&self, profile: &mut ProfileConfig, key: &str, value: Option<&str>) -> bool {
+        let field = match key {
+            "IFLOW_apiKey" => "auth_token",
+            "IFLOW_baseUrl" => "base_url",
+            "IFLOW_modelName" => "model",
+            _ => return false,
+        };
+        base::adopt_profile_field(profile, field, value)
+    }
 }
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    fn create_test_profile() -> ProfileConfig {
+        let mut profile = ProfileConfig::new();
+        profile.base_url = Some("https://apis.iflow.cn/v1".into());
+        profile.auth_token = Some("sk-iflow-test".into());
+        profile.model = Some("Qwen3-Coder".into());
+        profile
+    }
 
     #[test]
-    fn test_iflow_platform_stub() {
+    fn test_iflow_platform_basic() {
         let platform = IFlowPlatform::new().unwrap();
-
         assert_eq!(platform.platform_name(), "iflow");
         assert_eq!(platform.platform_type(), Platform::IFlow);
+    }
 
-        // 所有操作都应该返回 NotImplemented 错误
-        assert!(platform.load_profiles().is_err());
-        assert!(
-            platform
-                .save_profile("test", &ProfileConfig::default())
-                .is_err()
-        );
-        assert!(platform.delete_profile("test").is_err());
-        assert!(platform.apply_profile("test").is_err());
-        assert!(
-            platform
-                .validate_profile(&ProfileConfig::default())
-                .is_err()
-        );
-        assert!(platform.get_current_profile().is_err());
+    #[test]
+    fn test_auth_type_inference() {
+        let mut profile = create_test_profile();
+        assert_eq!(IFlowPlatform::auth_type(&profile), "iflow");
+
+        profile.base_url = Some("https://api.example.com/v1".into());
+        assert_eq!(IFlowPlatform::auth_type(&profile), "openai-compatible");
+
+        profile
+            .platform_data
+            .insert("auth_type".into(), json!("iflow"));
+        assert_eq!(IFlowPlatform::auth_type(&profile), "iflow");
+    }
+
+    #[test]
+    fn test_apply_to_settings_preserves_other_fields() {
+        let mut settings = json!({
+            "theme": "Default",
+            "selectedAuthType": "oauth-iflow",
+            "baseUrl": "https://old.example.com",
+            "mcpServers": { "fs": { "command": "npx" } }
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+
+        let mut profile = create_test_profile();
+        profile.base_url = None;
+        IFlowPlatform::apply_to_settings(&mut settings, &profile);
+
+        let settings = JsonValue::Object(settings);
+        assert_eq!(settings["selectedAuthType"], "iflow");
+        assert_eq!(settings["apiKey"], "sk-iflow-test");
+        assert_eq!(settings["modelName"], "Qwen3-Coder");
+        assert!(settings.get("baseUrl").is_none());
+        assert_eq!(settings["theme"], "Default");
+        assert_eq!(settings["mcpServers"]["fs"]["command"], "npx");
     }
 }
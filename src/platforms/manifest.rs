@@ -0,0 +1,850 @@
+// 🧩 Manifest 声明式平台
+// 📦 通过 `~/.ccr/platforms/<name>/platform.toml` 声明自定义 CLI 平台，无需修改 ccr 源码
+//
+// 核心职责:
+// - 📋 解析并校验 platform.toml (设置文件路径、格式、字段映射、环境变量、校验规则)
+// - ⚙️ 通用 PlatformConfig 实现: 将 ProfileConfig 字段写入 JSON/TOML 设置文件的指定键
+// - 🔍 发现 platforms/ 目录下声明的自定义平台
+//
+// 示例 manifest:
+// ```toml
+// [platform]
+// display_name = "Acme CLI"
+// settings_path = "~/.acme/config.json"
+// format = "json"
+// env_vars = ["ACME_API_KEY"]
+//
+// [fields]
+// auth_token = "auth.api_key"
+// base_url = "auth.endpoint"
+// model = "model.default"
+//
+// [defaults]
+// "auth.type" = "api-key"
+//
+// [validation]
+// required = ["auth_token", "base_url"]
+//
+// [validation.patterns]
+// auth_token = "^acme-[A-Za-z0-9]{16,}$"
+// ```
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::secret_source;
+use crate::models::{Platform, PlatformConfig, PlatformPaths, ProfileConfig};
+use crate::platforms::base;
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// 📄 Manifest 文件名
+pub const MANIFEST_FILE_NAME: &str = "platform.toml";
+
+/// 🔑 platform_data 字段前缀 (如 `platform_data.region`)
+const PLATFORM_DATA_PREFIX: &str = "platform_data.";
+
+/// 📋 可映射的 ProfileConfig 字段
+const MAPPABLE_FIELDS: &[&str] = &[
+    "base_url",
+    "auth_token",
+    "model",
+    "small_fast_model",
+    "provider",
+    "account",
+];
+
+/// 📄 设置文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+/// 🧩 平台 manifest (platform.toml)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformManifest {
+    /// 平台基本信息
+    pub platform: ManifestInfo,
+
+    /// ProfileConfig 字段 → 设置文件键路径 (以 `.` 分隔嵌套键)
+    #[serde(default)]
+    pub fields: IndexMap<String, String>,
+
+    /// 应用 profile 时固定写入的键值 (键路径 → 值)
+    #[serde(default)]
+    pub defaults: IndexMap<String, JsonValue>,
+
+    /// Profile 校验规则
+    #[serde(default)]
+    pub validation: ManifestValidation,
+}
+
+/// ℹ️ 平台基本信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestInfo {
+    /// 显示名称 (默认为目录名)
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    /// 平台描述
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// 设置文件路径 (支持 `~/` 前缀；相对路径相对于平台目录)
+    pub settings_path: String,
+
+    /// 设置文件格式
+    #[serde(default)]
+    pub format: SettingsFormat,
+
+    /// 平台读取的环境变量名称 (用于 `ccr current`/`ccr switch` 显示)
+    #[serde(default)]
+    pub env_vars: Vec<String>,
+}
+
+/// ✅ Profile 校验规则
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManifestValidation {
+    /// 必填字段
+    #[serde(default)]
+    pub required: Vec<String>,
+
+    /// 字段 → 正则表达式 (字段存在时必须匹配)
+    #[serde(default)]
+    pub patterns: IndexMap<String, String>,
+}
+
+impl PlatformManifest {
+    /// 📖 从文件加载并校验 manifest
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            CcrError::ConfigError(format!("读取平台 manifest 失败 {}: {}", path.display(), e))
+        })?;
+        let manifest: Self = toml::from_str(&content).map_err(|e| {
+            CcrError::ConfigFormatInvalid(format!(
+                "解析平台 manifest 失败 {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        manifest.check().map_err(|e| {
+            CcrError::ConfigFormatInvalid(format!("平台 manifest 无效 {}: {}", path.display(), e))
+        })?;
+        Ok(manifest)
+    }
+
+    /// 🔍 校验 manifest 自身的合法性
+    fn check(&self) -> std::result::Result<(), String> {
+        if self.platform.settings_path.trim().is_empty() {
+            return Err("platform.settings_path 不能为空".into());
+        }
+
+        for (field, key_path) in &self.fields {
+            check_field_name(field)?;
+            check_key_path(key_path)?;
+        }
+        for key_path in self.defaults.keys() {
+            check_key_path(key_path)?;
+        }
+        for field in &self.validation.required {
+            check_field_name(field)?;
+        }
+        for (field, pattern) in &self.validation.patterns {
+            check_field_name(field)?;
+            Regex::new(pattern).map_err(|e| format!("字段 '{}' 的校验正则无效: {}", field, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// 🔍 字段名必须是可映射的 ProfileConfig 字段或 `platform_data.<key>`
+fn check_field_name(field: &str) -> std::result::Result<(), String> {
+    let valid = MAPPABLE_FIELDS.contains(&field)
+        || field
+            .strip_prefix(PLATFORM_DATA_PREFIX)
+            .is_some_and(|key| !key.is_empty());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "未知字段 '{}'（可用: {}, platform_data.<键>）",
+            field,
+            MAPPABLE_FIELDS.join(", ")
+        ))
+    }
+}
+
+/// 🔍 键路径的每一段都不能为空
+fn check_key_path(key_path: &str) -> std::result::Result<(), String> {
+    if key_path.split('.').any(|segment| segment.trim().is_empty()) {
+        Err(format!("键路径 '{}' 无效", key_path))
+    } else {
+        Ok(())
+    }
+}
+
+/// 📋 读取 profile 中的字段值
+fn profile_field(profile: &ProfileConfig, field: &str) -> Option<JsonValue> {
+    let text = match field {
+        "base_url" => &profile.base_url,
+        "auth_token" => &profile.auth_token,
+        "model" => &profile.model,
+        "small_fast_model" => &profile.small_fast_model,
+        "provider" => &profile.provider,
+        "account" => &profile.account,
+        _ => {
+            return field
+                .strip_prefix(PLATFORM_DATA_PREFIX)
+                .and_then(|key| profile.platform_data.get(key))
+                .filter(|v| !v.is_null())
+                .cloned();
+        }
+    };
+    text.as_ref()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| JsonValue::from(s.as_str()))
+}
+
+/// 🧩 Manifest 驱动的通用平台实现
+pub struct ManifestPlatform {
+    name: String,
+    manifest: PlatformManifest,
+    paths: PlatformPaths,
+    settings_path: PathBuf,
+    patterns: Vec<(String, Regex)>,
+}
+
+impl ManifestPlatform {
+    /// 🏗️ 加载 `platforms/<name>/platform.toml` 声明的平台
+    ///
+    /// 内置平台名称不能被 manifest 覆盖；manifest 不存在时返回 PlatformNotFound
+    pub fn load(name: &str) -> Result<Self> {
+        if Platform::from_str(name).is_ok() || !is_valid_platform_name(name) {
+            return Err(CcrError::PlatformNotFound(name.to_string()));
+        }
+
+        let paths = PlatformPaths::for_name(name)?;
+        let manifest_path = paths.platform_dir.join(MANIFEST_FILE_NAME);
+        if !manifest_path.exists() {
+            return Err(CcrError::PlatformNotFound(name.to_string()));
+        }
+
+        let manifest = PlatformManifest::load(&manifest_path)?;
+        Self::from_manifest(name, manifest, paths)
+    }
+
+    /// 🏗️ 从已解析的 manifest 创建平台
+    pub fn from_manifest(
+        name: &str,
+        manifest: PlatformManifest,
+        paths: PlatformPaths,
+    ) -> Result<Self> {
+        let settings_path =
+            resolve_settings_path(&manifest.platform.settings_path, &paths.platform_dir)?;
+        let patterns = manifest
+            .validation
+            .patterns
+            .iter()
+            .map(|(field, pattern)| {
+                Regex::new(pattern)
+                    .map(|re| (field.clone(), re))
+                    .map_err(|e| CcrError::ConfigFormatInvalid(format!("校验正则无效: {}", e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name: name.to_string(),
+            manifest,
+            paths,
+            settings_path,
+            patterns,
+        })
+    }
+
+    /// 🏷️ 显示名称
+    pub fn display_name(&self) -> &str {
+        self.manifest
+            .platform
+            .display_name
+            .as_deref()
+            .unwrap_or(&self.name)
+    }
+
+    /// 📝 平台描述 (未声明时使用显示名称)
+    pub fn description(&self) -> &str {
+        self.manifest
+            .platform
+            .description
+            .as_deref()
+            .unwrap_or_else(|| self.display_name())
+    }
+
+    /// 💾 保存 profiles 到 TOML 文件
+    fn save_profiles(&self, profiles: &IndexMap<String, ProfileConfig>) -> Result<()> {
+        base::save_profiles_to_toml(&self.paths.profiles_file, profiles, &self.name, &self.paths)
+    }
+
+    /// ✏️ 将 profile 写入设置文件 (只修改 manifest 声明的键)
+    fn write_settings(&self, profile: &ProfileConfig) -> Result<()> {
+        let label = self.display_name().to_string();
+        let assignments = self.assignments(profile);
+
+        match self.manifest.platform.format {
+            SettingsFormat::Json => {
+                let mut settings = base::load_json_settings(&self.settings_path, &label)?;
+                for (key_path, value) in assignments {
+                    set_json_path(&mut settings, key_path, value);
+                }
+                base::save_json_settings(&self.settings_path, &settings, &label)
+            }
+            SettingsFormat::Toml => {
+                let mut doc = load_toml_document(&self.settings_path, &label)?;
+                for (key_path, value) in assignments {
+                    set_toml_path(doc.as_table_mut(), key_path, value)?;
+                }
+                save_toml_document(&self.settings_path, &doc, &label)
+            }
+        }
+    }
+
+    /// 📋 计算需要写入的键值 (None 表示移除该键)
+    fn assignments<'a>(&'a self, profile: &ProfileConfig) -> Vec<(&'a str, Option<JsonValue>)> {
+        let defaults = self
+            .manifest
+            .defaults
+            .iter()
+            .map(|(key_path, value)| (key_path.as_str(), Some(value.clone())));
+        let fields = self
+            .manifest
+            .fields
+            .iter()
+            .map(|(field, key_path)| (key_path.as_str(), profile_field(profile, field)));
+        defaults.chain(fields).collect()
+    }
+}
+
+impl PlatformConfig for ManifestPlatform {
+    fn platform_name(&self) -> &str {
+        &self.name
+    }
+
+    fn platform_type(&self) -> Platform {
+        Platform::Custom
+    }
+
+    fn load_profiles(&self) -> Result<IndexMap<String, ProfileConfig>> {
+        base::load_profiles_from_toml(&self.paths.profiles_file)
+    }
+
+    fn save_profile(&self, name: &str, profile: &ProfileConfig) -> Result<()> {
+        let mut profiles = self.load_profiles()?;
+        profiles.insert(name.to_string(), profile.clone());
+
+        // 验证继承后的有效配置
+        base::validate_resolved_profile(&profiles, name, |p| self.validate_profile(p))?;
+
+        self.save_profiles(&profiles)
+    }
+
+    fn delete_profile(&self, name: &str) -> Result<()> {
+        let mut profiles = self.load_profiles()?;
+        if profiles.shift_remove(name).is_none() {
+            return Err(CcrError::ProfileNotFound(name.to_string()));
+        }
+        self.save_profiles(&profiles)
+    }
+
+    fn get_settings_path(&self) -> PathBuf {
+        self.settings_path.clone()
+    }
+
+    fn apply_profile(&self, name: &str) -> Result<()> {
+        // 加载 profile
+        let profiles = self.load_profiles()?;
+        let profile = &base::resolve_profile_secrets(&base::resolve_profile(&profiles, name)?)?;
+
+        // 验证
+        self.validate_profile(profile)?;
+
+        // 写入设置文件
+        self.write_settings(profile)?;
+
+        // 更新 profiles.toml 与注册表中的当前 profile
+        base::update_current_config(&self.paths.profiles_file, name)?;
+        base::update_registry_current_profile(&self.name, name)?;
+
+        tracing::info!("✅ 已应用 {} profile: {}", self.name, name);
+        Ok(())
+    }
+
+    fn validate_profile(&self, profile: &ProfileConfig) -> Result<()> {
+        for field in &self.manifest.validation.required {
+            if profile_field(profile, field).is_none() {
+                return Err(CcrError::ValidationError(format!(
+                    "{} profile 缺少必填字段 {}",
+                    self.display_name(),
+                    field
+                )));
+            }
+        }
+
+        for (field, pattern) in &self.patterns {
+            let Some(JsonValue::String(value)) = profile_field(profile, field) else {
+                continue;
+            };
+            // 间接形式（保险库、${env:..} 等）在应用时才解析，保存时跳过格式校验
+            if field == "auth_token" && secret_source::is_indirect(&value) {
+                continue;
+            }
+            if !pattern.is_match(&value) {
+                return Err(CcrError::ValidationError(format!(
+                    "{} 字段 {} 不符合格式要求: {}",
+                    self.display_name(),
+                    field,
+                    pattern.as_str()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_current_profile(&self) -> Result<Option<String>> {
+        base::get_current_profile_from_registry(&self.name)
+    }
+
+    fn get_env_var_names(&self) -> Vec<String> {
+        self.manifest.platform.env_vars.clone()
+    }
+
+    fn expected_values(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        // 只比较 fields 中映射的字符串字段，键为设置文件键路径
+        Ok(self
+            .manifest
+            .fields
+            .iter()
+            .filter_map(|(field, key_path)| match profile_field(profile, field) {
+                Some(JsonValue::String(value)) => Some((key_path.clone(), value)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn live_values(&self, _profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        let label = self.display_name().to_string();
+        let key_paths = self.manifest.fields.values();
+        let live = match self.manifest.platform.format {
+            SettingsFormat::Json => {
+                let settings = base::load_json_settings(&self.settings_path, &label)?;
+                key_paths
+                    .filter_map(|key_path| {
+                        let path: Vec<&str> = key_path.split('.').collect();
+                        base::json_string_at(&settings, &path).map(|v| (key_path.clone(), v))
+                    })
+                    .collect()
+            }
+            SettingsFormat::Toml => {
+                let doc = load_toml_document(&self.settings_path, &label)?;
+                key_paths
+                    .filter_map(|key_path| {
+                        key_path
+                            .split('.')
+                            .try_fold(doc.as_item(), |item, key| item.get(key))
+                            .and_then(|item| item.as_str())
+                            .filter(|v| !v.is_empty())
+                            .map(|v| (key_path.clone(), v.to_string()))
+                    })
+                    .collect()
+            }
+        };
+        Ok(Some(live))
+    }
+
+    fn adopt_value(&self, profile: &mut ProfileConfig, key: &str, value: Option<&str>) -> bool {
+        self.manifest
+            .fields
+            .iter()
+            .find(|(_, key_path)| key_path.as_str() == key)
+            .is_some_and(|(field, _)| base::adopt_profile_field(profile, field, value))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// 🔍 平台发现
+// ═══════════════════════════════════════════════════════════
+
+/// 🔍 平台名称只允许小写字母、数字、'-' 和 '_'
+fn is_valid_platform_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// 📋 列出所有声明了 manifest 的自定义平台名称 (按名称排序)
+pub fn list_manifest_platforms() -> Result<Vec<String>> {
+    let platforms_dir = PlatformPaths::get_ccr_root()?.join("platforms");
+    let Ok(entries) = fs::read_dir(&platforms_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().join(MANIFEST_FILE_NAME).is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| Platform::from_str(name).is_err() && is_valid_platform_name(name))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+// ═══════════════════════════════════════════════════════════
+// 📄 设置文件读写
+// ═══════════════════════════════════════════════════════════
+
+/// 📁 解析设置文件路径
+fn resolve_settings_path(raw: &str, platform_dir: &Path) -> Result<PathBuf> {
+    if let Some(rest) = raw.strip_prefix("~/") {
+        let home =
+            dirs::home_dir().ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?;
+        return Ok(home.join(rest));
+    }
+
+    let path = PathBuf::from(raw);
+    if path.is_absolute() {
+        Ok(path)
+    } else {
+        Ok(platform_dir.join(path))
+    }
+}
+
+/// ✏️ 设置 JSON 键路径 (None 时移除)
+fn set_json_path(root: &mut JsonMap<String, JsonValue>, key_path: &str, value: Option<JsonValue>) {
+    let segments: Vec<&str> = key_path.split('.').collect();
+    let Some((last, parents)) = segments.split_last() else {
+        return;
+    };
+
+    let mut current = root;
+    for segment in parents {
+        if value.is_none() && !current.get(*segment).is_some_and(JsonValue::is_object) {
+            // 移除时不创建中间对象
+            return;
+        }
+        current = base::json_object_entry(current, segment);
+    }
+
+    match value {
+        Some(v) => {
+            current.insert(last.to_string(), v);
+        }
+        None => {
+            current.remove(*last);
+        }
+    }
+}
+
+/// 📖 读取 TOML 设置文件 (保留格式与注释)
+fn load_toml_document(path: &Path, label: &str) -> Result<toml_edit::DocumentMut> {
+    if !path.exists() {
+        return Ok(toml_edit::DocumentMut::new());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| CcrError::SettingsError(format!("读取 {} 设置失败: {}", label, e)))?;
+    content
+        .parse()
+        .map_err(|e| CcrError::ConfigFormatInvalid(format!("解析 {} 设置失败: {}", label, e)))
+}
+
+/// 💾 写入 TOML 设置文件 (自动创建父目录)
+fn save_toml_document(path: &Path, doc: &toml_edit::DocumentMut, label: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| CcrError::SettingsError(format!("创建 {} 设置目录失败: {}", label, e)))?;
+    }
+    fs::write(path, doc.to_string())
+        .map_err(|e| CcrError::SettingsError(format!("写入 {} 设置失败: {}", label, e)))?;
+
+    tracing::info!("✅ 已保存 {} 设置: {:?}", label, path);
+    Ok(())
+}
+
+/// ✏️ 设置 TOML 键路径 (None 时移除)
+fn set_toml_path(
+    root: &mut toml_edit::Table,
+    key_path: &str,
+    value: Option<JsonValue>,
+) -> Result<()> {
+    let segments: Vec<&str> = key_path.split('.').collect();
+    let Some((last, parents)) = segments.split_last() else {
+        return Ok(());
+    };
+
+    let mut current = root;
+    for segment in parents {
+        if value.is_none()
+            && !current
+                .get(segment)
+                .is_some_and(toml_edit::Item::is_table_like)
+        {
+            return Ok(());
+        }
+        let item = current
+            .entry(segment)
+            .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()));
+        if !item.is_table_like() {
+            *item = toml_edit::Item::Table(toml_edit::Table::new());
+        }
+        current = match item {
+            toml_edit::Item::Table(table) => table,
+            _ => {
+                return Err(CcrError::SettingsError(format!(
+                    "无法写入键 '{}': '{}' 是内联表",
+                    key_path, segment
+                )));
+            }
+        };
+    }
+
+    match value {
+        Some(v) => {
+            let converted = json_to_toml(&v).ok_or_else(|| {
+                CcrError::SettingsError(format!("键 '{}' 的值无法转换为 TOML", key_path))
+            })?;
+            match current.get_mut(last) {
+                // 保留原有的键装饰 (注释/空白)
+                Some(toml_edit::Item::Value(existing)) => {
+                    let decor = existing.decor().clone();
+                    *existing = converted;
+                    *existing.decor_mut() = decor;
+                }
+                _ => {
+                    current.insert(last, toml_edit::Item::Value(converted));
+                }
+            }
+        }
+        None => {
+            current.remove(last);
+        }
+    }
+    Ok(())
+}
+
+/// 🔄 JSON 值 → TOML 值 (null 无法表示)
+fn json_to_toml(value: &JsonValue) -> Option<toml_edit::Value> {
+    Some(match value {
+        JsonValue::Null => return None,
+        JsonValue::Bool(b) => (*b).into(),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64()?.into(),
+        },
+        JsonValue::String(s) => s.as_str().into(),
+        JsonValue::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(json_to_toml(item)?);
+            }
+            toml_edit::Value::Array(array)
+        }
+        JsonValue::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                table.insert(key, json_to_toml(item)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+// ═══════════════════════════════════════════════════════════
+// 🧪 测试
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const JSON_MANIFEST: &str = r#"
+[platform]
+display_name = "Acme CLI"
+settings_path = "settings.json"
+env_vars = ["ACME_API_KEY"]
+
+[fields]
+auth_token = "auth.api_key"
+base_url = "auth.endpoint"
+model = "model.default"
+"platform_data.region" = "region"
+
+[defaults]
+"auth.type" = "api-key"
+
+[validation]
+required = ["auth_token", "base_url"]
+
+[validation.patterns]
+auth_token = "^acme-[a-z0-9]{8,}$"
+base_url = "^https://"
+"#;
+
+    fn platform_from(temp: &TempDir, manifest: &str) -> ManifestPlatform {
+        let manifest: PlatformManifest = toml::from_str(manifest).unwrap();
+        manifest.check().unwrap();
+        let mut paths = PlatformPaths::for_name("acme").unwrap();
+        paths.platform_dir = temp.path().to_path_buf();
+        ManifestPlatform::from_manifest("acme", manifest, paths).unwrap()
+    }
+
+    fn create_test_profile() -> ProfileConfig {
+        let mut profile = ProfileConfig::new();
+        profile.base_url = Some("https://api.acme.dev".into());
+        profile.auth_token = Some("acme-12345678".into());
+        profile.model = Some("acme-large".into());
+        profile
+            .platform_data
+            .insert("region".into(), json!("eu-west"));
+        profile
+    }
+
+    #[test]
+    fn test_manifest_check_rejects_invalid_entries() {
+        let unknown_field = r#"
+[platform]
+settings_path = "s.json"
+[fields]
+nickname = "name"
+"#;
+        let manifest: PlatformManifest = toml::from_str(unknown_field).unwrap();
+        assert!(manifest.check().unwrap_err().contains("nickname"));
+
+        let bad_regex = r#"
+[platform]
+settings_path = "s.json"
+[validation.patterns]
+auth_token = "(["
+"#;
+        let manifest: PlatformManifest = toml::from_str(bad_regex).unwrap();
+        assert!(manifest.check().is_err());
+
+        let bad_key = r#"
+[platform]
+settings_path = "s.json"
+[fields]
+model = "model..name"
+"#;
+        let manifest: PlatformManifest = toml::from_str(bad_key).unwrap();
+        assert!(manifest.check().is_err());
+    }
+
+    #[test]
+    fn test_validate_profile_with_manifest_rules() {
+        let temp = TempDir::new().unwrap();
+        let platform = platform_from(&temp, JSON_MANIFEST);
+        let profile = create_test_profile();
+        assert!(platform.validate_profile(&profile).is_ok());
+
+        let mut missing = profile.clone();
+        missing.base_url = None;
+        assert!(platform.validate_profile(&missing).is_err());
+
+        let mut bad_token = profile.clone();
+        bad_token.auth_token = Some("sk-123".into());
+        assert!(platform.validate_profile(&bad_token).is_err());
+
+        let mut vault_ref = profile;
+        vault_ref.auth_token = Some("vault:acme".into());
+        assert!(platform.validate_profile(&vault_ref).is_ok());
+
+        assert_eq!(platform.get_env_var_names(), vec!["ACME_API_KEY"]);
+        assert_eq!(platform.display_name(), "Acme CLI");
+        assert_eq!(platform.platform_type(), Platform::Custom);
+    }
+
+    #[test]
+    fn test_write_json_settings_preserves_other_keys() {
+        let temp = TempDir::new().unwrap();
+        let platform = platform_from(&temp, JSON_MANIFEST);
+        let settings_path = temp.path().join("settings.json");
+        assert_eq!(platform.get_settings_path(), settings_path);
+
+        fs::write(
+            &settings_path,
+            r#"{"theme":"dark","model":{"default":"old","temperature":0.2}}"#,
+        )
+        .unwrap();
+
+        platform.write_settings(&create_test_profile()).unwrap();
+        let settings: JsonValue =
+            serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert_eq!(settings["auth"]["api_key"], "acme-12345678");
+        assert_eq!(settings["auth"]["endpoint"], "https://api.acme.dev");
+        assert_eq!(settings["auth"]["type"], "api-key");
+        assert_eq!(settings["model"]["default"], "acme-large");
+        assert_eq!(settings["model"]["temperature"], 0.2);
+        assert_eq!(settings["region"], "eu-west");
+        assert_eq!(settings["theme"], "dark");
+
+        // 字段为空时移除对应键
+        let mut profile = create_test_profile();
+        profile.model = None;
+        platform.write_settings(&profile).unwrap();
+        let settings: JsonValue =
+            serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert!(settings["model"].get("default").is_none());
+        assert_eq!(settings["model"]["temperature"], 0.2);
+    }
+
+    #[test]
+    fn test_write_toml_settings_preserves_comments() {
+        let temp = TempDir::new().unwrap();
+        let manifest = r#"
+[platform]
+settings_path = "config.toml"
+format = "toml"
+
+[fields]
+auth_token = "provider.api_key"
+model = "model"
+
+[defaults]
+"provider.retries" = 3
+"#;
+        let platform = platform_from(&temp, manifest);
+        let settings_path = temp.path().join("config.toml");
+        fs::write(
+            &settings_path,
+            "# Acme 配置\nmodel = \"old\" # 默认模型\n\n[provider]\nname = \"acme\"\n",
+        )
+        .unwrap();
+
+        platform.write_settings(&create_test_profile()).unwrap();
+        let content = fs::read_to_string(&settings_path).unwrap();
+        assert!(content.contains("# Acme 配置"));
+        assert!(content.contains("model = \"acme-large\" # 默认模型"));
+        assert!(content.contains("name = \"acme\""));
+        assert!(content.contains("api_key = \"acme-12345678\""));
+        assert!(content.contains("retries = 3"));
+    }
+
+    #[test]
+    fn test_builtin_names_are_not_manifest_platforms() {
+        assert!(matches!(
+            ManifestPlatform::load("claude"),
+            Err(CcrError::PlatformNotFound(_))
+        ));
+        assert!(matches!(
+            ManifestPlatform::load("Bad Name"),
+            Err(CcrError::PlatformNotFound(_))
+        ));
+    }
+}
@@ -3,11 +3,12 @@
 //
 // 核心职责:
 // - 🏭 平台工厂 - 根据平台类型创建实例
+// - 🧩 自定义平台 - 按名称加载 manifest 声明的平台
 // - 📋 平台注册表 - 管理所有可用平台
 // - 🔍 平台检测 - 检测系统中可用的平台
 
 use crate::core::error::{CcrError, Result};
-use crate::models::{Platform, PlatformConfig};
+use crate::models::{Platform, PlatformConfig, PlatformPaths};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -18,6 +19,7 @@ pub mod codex;
 pub mod droid;
 pub mod gemini;
 pub mod iflow;
+pub mod manifest;
 pub mod qwen;
 
 // 重新导出 base 模块的公共函数 (内部使用)
@@ -35,6 +37,7 @@ pub use codex::CodexPlatform;
 pub use droid::DroidPlatform;
 pub use gemini::GeminiPlatform;
 pub use iflow::IFlowPlatform;
+pub use manifest::ManifestPlatform;
 pub use qwen::QwenPlatform;
 
 /// 🏭 平台工厂函数
@@ -82,6 +85,40 @@ pub fn create_platform(platform: Platform) -> Result<Arc<dyn PlatformConfig>> {
             let droid = DroidPlatform::new()?;
             Ok(Arc::new(droid))
         }
+        Platform::Custom => Err(CcrError::PlatformNotSupported(
+            "custom (请使用 create_platform_by_name 按名称加载)".to_string(),
+        )),
+    }
+}
+
+/// 🏭 按平台名称创建平台实例
+///
+/// 内置平台名称优先；其他名称从 `~/.ccr/platforms/<name>/platform.toml` 加载
+///
+/// # 示例
+/// ```rust,no_run
+/// use ccr::create_platform_by_name;
+///
+/// let acme = create_platform_by_name("acme")?;
+/// let profiles = acme.load_profiles()?;
+/// # Ok::<(), ccr::CcrError>(())
+/// ```
+pub fn create_platform_by_name(name: &str) -> Result<Arc<dyn PlatformConfig>> {
+    match Platform::from_str(name) {
+        Ok(platform) => create_platform(platform),
+        Err(_) => Ok(Arc::new(ManifestPlatform::load(name)?)),
+    }
+}
+
+/// 📁 按平台名称获取平台路径（支持 manifest 声明的自定义平台）
+pub fn platform_paths_by_name(name: &str) -> Result<PlatformPaths> {
+    match Platform::from_str(name) {
+        Ok(platform) => PlatformPaths::new(platform),
+        Err(_) => {
+            // 确认 manifest 存在且有效，避免为任意名称生成路径
+            ManifestPlatform::load(name)?;
+            PlatformPaths::for_name(name)
+        }
     }
 }
 
@@ -232,8 +269,6 @@ impl PlatformDetector {
     /// 通过检查配置文件是否存在来判断平台是否已配置
     #[allow(dead_code)]
     pub fn detect_configured_platforms(&self) -> Result<Vec<Platform>> {
-        use crate::models::PlatformPaths;
-
         let mut configured = Vec::new();
 
         for &platform in &Platform::implemented() {
@@ -252,7 +287,6 @@ impl PlatformDetector {
     /// 读取 ~/.ccr/config.toml 中的 current_platform 字段
     #[allow(dead_code)]
     pub fn detect_current_platform(&self) -> Result<Option<Platform>> {
-        use crate::models::PlatformPaths;
         use std::fs;
 
         // 获取注册表路径
@@ -321,14 +355,13 @@ mod tests {
             "Gemini 平台应该成功创建"
         );
 
-        // Qwen 和 IFlow 是 stub 实现，也能成功创建（但方法会返回 PlatformNotSupported 错误）
         assert!(
             create_platform(Platform::Qwen).is_ok(),
-            "Qwen 平台应该成功创建（stub）"
+            "Qwen 平台应该成功创建"
         );
         assert!(
             create_platform(Platform::IFlow).is_ok(),
-            "IFlow 平台应该成功创建（stub）"
+            "IFlow 平台应该成功创建"
         );
     }
 
@@ -338,10 +371,12 @@ mod tests {
         assert_eq!(registry.all_platforms().len(), 6);
 
         let implemented = registry.implemented_platforms();
-        assert_eq!(implemented.len(), 4);
+        assert_eq!(implemented.len(), 6);
         assert!(implemented.contains(&Platform::Claude));
         assert!(implemented.contains(&Platform::Codex));
         assert!(implemented.contains(&Platform::Gemini));
+        assert!(implemented.contains(&Platform::Qwen));
+        assert!(implemented.contains(&Platform::IFlow));
         assert!(implemented.contains(&Platform::Droid));
     }
 
@@ -1,32 +1,84 @@
-// 🌟 Qwen Platform 实现（Stub）
-// 📦 阿里通义千问 CLI 平台配置管理
-//
-// ⚠️ 注意：这是一个占位实现（stub），尚未完全实现
+// 🌟 Qwen Platform 实现
+// 📦 阿里通义千问 Qwen Code CLI 平台配置管理
 //
 // 核心职责:
-// - 🚧 返回 NotImplemented 错误
-// - 📋 为未来实现预留接口
+// - 📋 管理 Qwen profiles
+// - ⚙️ 操作 ~/.qwen/settings.json（仅更新认证与模型字段，保留 mcpServers 等其他设置）
+// - 🔐 验证 OpenAI 兼容接口配置
+//
+// 📁 配置结构 (Unified 模式):
+// - CCR 配置: `~/.ccr/platforms/qwen/profiles.toml`
+// - Qwen 设置: `~/.qwen/settings.json`（可通过 CCR_QWEN_SETTINGS_PATH 覆盖）
 
 use crate::core::error::{CcrError, Result};
-use crate::models::{Platform, PlatformConfig, ProfileConfig};
+use crate::managers::secret_source;
+use crate::models::{Platform, PlatformConfig, PlatformPaths, ProfileConfig};
+use crate::platforms::base;
 use indexmap::IndexMap;
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use std::path::PathBuf;
 
-/// 🌟 Qwen Platform 实现（Stub）
+/// 🔑 Qwen 默认认证方式（OpenAI 兼容接口）
+const DEFAULT_AUTH_TYPE: &str = "openai";
+
+/// 🌟 Qwen Platform 实现
 ///
-/// ## 状态
-/// 🚧 未实现 - 计划在未来版本中支持
+/// ## 配置文件
+/// - Profiles: `~/.ccr/platforms/qwen/profiles.toml`
+/// - Settings: `~/.qwen/settings.json`
 ///
-/// ## 预期功能
-/// - 管理 Qwen CLI 配置 profiles
-/// - 支持阿里通义千问 API
-/// - 验证 API key 格式
-pub struct QwenPlatform;
+/// ## 字段映射
+/// - `auth_token` → `security.auth.apiKey`
+/// - `base_url`   → `security.auth.baseUrl`
+/// - `model`      → `model.name`
+/// - `platform_data.auth_type` → `security.auth.selectedType`（默认 `openai`）
+pub struct QwenPlatform {
+    paths: PlatformPaths,
+    settings_path: PathBuf,
+}
 
 impl QwenPlatform {
     /// 🏗️ 创建新的 Qwen Platform 实例
+    ///
+    /// ⚙️ **开发者注意**:
+    /// 可以通过环境变量 `CCR_QWEN_SETTINGS_PATH` 覆盖 settings.json 路径
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        let paths = PlatformPaths::new(Platform::Qwen)?;
+
+        let settings_path = if let Ok(custom_path) = std::env::var("CCR_QWEN_SETTINGS_PATH") {
+            PathBuf::from(custom_path)
+        } else {
+            let home = dirs::home_dir()
+                .ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?;
+            home.join(".qwen").join("settings.json")
+        };
+
+        Ok(Self {
+            paths,
+            settings_path,
+        })
+    }
+
+    /// 💾 保存 profiles 到 TOML 文件
+    fn save_profiles(&self, profiles: &IndexMap<String, ProfileConfig>) -> Result<()> {
+        base::save_profiles_to_toml(&self.paths.profiles_file, profiles, "qwen", &self.paths)
+    }
+
+    /// 📋 将 profile 写入 Qwen settings（保留其他字段）
+    fn apply_to_settings(settings: &mut JsonMap<String, JsonValue>, profile: &ProfileConfig) {
+        let auth_type = profile
+            .platform_data
+            .get("auth_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_AUTH_TYPE);
+
+        let auth = base::json_object_entry(base::json_object_entry(settings, "security"), "auth");
+        auth.insert("selectedType".into(), JsonValue::from(auth_type));
+        base::set_json_field(auth, "apiKey", profile.auth_token.as_deref());
+        base::set_json_field(auth, "baseUrl", profile.base_url.as_deref());
+
+        let model = base::json_object_entry(settings, "model");
+        base::set_json_field(model, "name", profile.model.as_deref());
     }
 }
 
@@ -40,73 +92,201 @@ impl PlatformConfig for QwenPlatform {
     }
 
     fn load_profiles(&self) -> Result<IndexMap<String, ProfileConfig>> {
-        Err(CcrError::PlatformNotSupported(
-            "Qwen 平台尚未实现".to_string(),
-        ))
+        base::load_profiles_from_toml(&self.paths.profiles_file)
     }
 
-    fn save_profile(&self, _name: &str, _profile: &ProfileConfig) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "Qwen 平台尚未实现".to_string(),
-        ))
+    fn save_profile(&self, name: &str, profile: &ProfileConfig) -> Result<()> {
+        let mut profiles = self.load_profiles()?;
+        profiles.insert(name.to_string(), profile.clone());
+
+        // 验证继承后的有效配置
+        base::validate_resolved_profile(&profiles, name, |p| self.validate_profile(p))?;
+
+        self.save_profiles(&profiles)
     }
 
-    fn delete_profile(&self, _name: &str) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "Qwen 平台尚未实现".to_string(),
-        ))
+    fn delete_profile(&self, name: &str) -> Result<()> {
+        let mut profiles = self.load_profiles()?;
+        if profiles.shift_remove(name).is_none() {
+            return Err(CcrError::ProfileNotFound(name.to_string()));
+        }
+        self.save_profiles(&profiles)
     }
 
     fn get_settings_path(&self) -> PathBuf {
-        // 返回一个占位路径
-        PathBuf::from("~/.ccr/platforms/qwen/settings.json")
+        self.settings_path.clone()
     }
 
-    fn apply_profile(&self, _name: &str) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "Qwen 平台尚未实现".to_string(),
-        ))
+    fn apply_profile(&self, name: &str) -> Result<()> {
+        // 加载 profile
+        let profiles = self.load_profiles()?;
+        let profile = &base::resolve_profile_secrets(&base::resolve_profile(&profiles, name)?)?;
+
+        // 验证
+        self.validate_profile(profile)?;
+
+        // 更新 Qwen settings
+        let mut settings = base::load_json_settings(&self.settings_path, "Qwen")?;
+        Self::apply_to_settings(&mut settings, profile);
+        base::save_json_settings(&self.settings_path, &settings, "Qwen")?;
+
+        // 更新 profiles.toml 与注册表中的当前 profile
+        base::update_current_config(&self.paths.profiles_file, name)?;
+        base::update_registry_current_profile("qwen", name)?;
+
+        tracing::info!("✅ 已应用 Qwen profile: {}", name);
+        Ok(())
     }
 
-    fn validate_profile(&self, _profile: &ProfileConfig) -> Result<()> {
-        Err(CcrError::PlatformNotSupported(
-            "Qwen 平台尚未实现".to_string(),
-        ))
+    fn validate_profile(&self, profile: &ProfileConfig) -> Result<()> {
+        // Qwen 需要: auth_token (API key)；base_url 可选（使用 DashScope 默认地址）
+        if profile
+            .auth_token
+            .as_ref()
+            .map(|s| s.trim().is_empty())
+            .unwrap_or(true)
+        {
+            return Err(CcrError::ValidationError(
+                "Qwen profile 缺少 auth_token (API key)".into(),
+            ));
+        }
+
+        if let Some(base_url) = &profile.base_url
+            && !secret_source::is_indirect(base_url)
+            && !base_url.starts_with("http://")
+            && !base_url.starts_with("https://")
+        {
+            return Err(CcrError::ValidationError(
+                "base_url 必须以 http:// 或 https:// 开头".into(),
+            ));
+        }
+
+        Ok(())
     }
 
     fn get_current_profile(&self) -> Result<Option<String>> {
-        Err(CcrError::PlatformNotSupported(
-            "Qwen 平台尚未实现".to_string(),
+        base::get_current_profile_from_registry("qwen")
+    }
+
+    fn get_env_var_names(&self) -> Vec<String> {
+        ["OPENAI_API_KEY", "OPENAI_BASE_URL", "OPENAI_MODEL"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    fn profile_env(&self, profile: &ProfileConfig) -> Result<IndexMap<String, String>> {
+        Ok(base::env_from_fields(&[
+            ("OPENAI_API_KEY", profile.auth_token.as_ref()),
+            ("OPENAI_BASE_URL", profile.base_url.as_ref()),
+            ("OPENAI_MODEL", profile.model.as_ref()),
+        ]))
+    }
+
+    fn live_values(&self, _profile: &ProfileConfig) -> Result<Option<IndexMap<String, String>>> {
+        let settings = base::load_json_settings(&self.settings_path, "Qwen")?;
+        Ok(Some(
+            [
+                ("OPENAI_API_KEY", &["security", "auth", "apiKey"][..]),
+                ("OPENAI_BASE_URL", &["security", "auth", "baseUrl"][..]),
+                ("OPENAI_MODEL", &["model", "name"][..]),
+            ]
+            .into_iter()
+            .filter_map(|(key, path)| {
+                base::json_string_at(&settings, path).map(|value| (key.to_string(), value))
+            })
+            .collect(),
         ))
     }
+
+    fn adopt_value(&self, profile: &mut ProfileConfig, key: &str, value: Option<&str>) -> bool {
+        let field = match key {
+            "OPENAI_API_KEY" => "auth_token",
+            "OPENAI_BASE_URL" => "base_url",
+            "OPENAI_MODEL" => "model",
+            _ => return false,
+        };
+        base::adopt_profile_field(profile, field, value)
+    }
 }
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    fn create_test_profile() -> ProfileConfig {
+        let mut profile = ProfileConfig::new();
+        profile.base_url = Some("https://dashscope.aliyuncs.com/compatible-mode/v1".into());
+        profile.auth_token = Some("sk-qwen-test".into());
+        profile.model = Some("qwen3-coder-plus".into());
+        profile
+    }
 
     #[test]
-    fn test_qwen_platform_stub() {
+    fn test_qwen_platform_basic() {
         let platform = QwenPlatform::new().unwrap();
-
         assert_eq!(platform.platform_name(), "qwen");
         assert_eq!(platform.platform_type(), Platform::Qwen);
+    }
 
-        // 所有操作都应该返回 NotImplemented 错误
-        assert!(platform.load_profiles().is_err());
-        assert!(
-            platform
-                .save_profile("test", &ProfileConfig::default())
-                .is_err()
+    #[test]
+    fn test_apply_to_settings_preserves_other_fields() {
+        let mut settings = json!({
+            "mcpServers": { "fs": { "command": "npx" } },
+            "security": { "auth": { "selectedType": "qwen-oauth" }, "folderTrust": true },
+            "model": { "name": "old", "maxSessionTurns": 10 }
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+
+        QwenPlatform::apply_to_settings(&mut settings, &create_test_profile());
+
+        let settings = JsonValue::Object(settings);
+        assert_eq!(settings["security"]["auth"]["selectedType"], "openai");
+        assert_eq!(settings["security"]["auth"]["apiKey"], "sk-qwen-test");
+        assert_eq!(
+            settings["security"]["auth"]["baseUrl"],
+            "https://dashscope.aliyuncs.com/compatible-mode/v1"
+        );
+        assert_eq!(settings["security"]["folderTrust"], true);
+        assert_eq!(settings["model"]["name"], "qwen3-coder-plus");
+        assert_eq!(settings["model"]["maxSessionTurns"], 10);
+        assert_eq!(settings["mcpServers"]["fs"]["command"], "npx");
+    }
+
+    #[test]
+    fn test_profile_env() {
+        let platform = QwenPlatform::new().unwrap();
+        let mut profile = create_test_profile();
+        profile.model = None;
+
+        let env = platform.profile_env(&profile).unwrap();
+        assert_eq!(env["OPENAI_API_KEY"], "sk-qwen-test");
+        assert_eq!(
+            env["OPENAI_BASE_URL"],
+            "https://dashscope.aliyuncs.com/compatible-mode/v1"
         );
-        assert!(platform.delete_profile("test").is_err());
-        assert!(platform.apply_profile("test").is_err());
+        assert!(!env.contains_key("OPENAI_MODEL"));
         assert!(
-            platform
-                .validate_profile(&ProfileConfig::default())
-                .is_err()
+            env.keys()
+                .all(|name| platform.get_env_var_names().contains(name))
         );
-        assert!(platform.get_current_profile().is_err());
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        let platform = QwenPlatform::new().unwrap();
+        let profile = create_test_profile();
+        assert!(platform.validate_profile(&profile).is_ok());
+
+        let mut no_key = profile.clone();
+        no_key.auth_token = None;
+        assert!(platform.validate_profile(&no_key).is_err());
+
+        let mut bad_url = profile;
+        bad_url.base_url = Some("dashscope.aliyuncs.com".into());
+        assert!(platform.validate_profile(&bad_url).is_err());
     }
 }
@@ -0,0 +1,140 @@
+// 🔀 CCR 本地故障转移代理配置
+// 📁 存储于平台 profiles.toml 的 [settings.proxy] 段
+//
+// 核心职责:
+// - 🌐 监听地址与端口
+// - 📋 上游 profile 的有序列表(故障转移顺序)
+// - ⏱️ 超时、冷却与健康检查间隔
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 🔀 本地代理配置
+///
+/// 示例:
+/// ```toml
+/// [settings.proxy]
+/// port = 19530
+/// upstreams = ["anyrouter_main", "anyrouter_backup", "glm"]
+/// timeout_secs = 30
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// 🌐 监听地址(默认仅本机: 127.0.0.1)
+    #[serde(default = "default_host")]
+    pub host: String,
+
+    /// 🔌 监听端口
+    #[serde(default = "default_port")]
+    pub port: u16,
+
+    /// 📋 上游 profile 名称(按优先级排序)
+    ///
+    /// 为空时使用所有启用的 profile,当前 profile 排在最前
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub upstreams: Vec<String>,
+
+    /// ⏱️ 等待上游响应头的超时(秒),超时即转移到下一个上游
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// 🧊 上游失败后的冷却时间(秒),冷却期内优先跳过
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+
+    /// 🏥 后台健康检查间隔(秒),0 表示仅在启动时检查一次
+    #[serde(default = "default_health_check_interval_secs")]
+    pub health_check_interval_secs: u64,
+}
+
+fn default_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_port() -> u16 {
+    19530
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_health_check_interval_secs() -> u64 {
+    300
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            host: default_host(),
+            port: default_port(),
+            upstreams: Vec::new(),
+            timeout_secs: default_timeout_secs(),
+            cooldown_secs: default_cooldown_secs(),
+            health_check_interval_secs: default_health_check_interval_secs(),
+        }
+    }
+}
+
+impl ProxyConfig {
+    /// ⏱️ 上游响应头超时
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.max(1))
+    }
+
+    /// 🧊 失败冷却时间
+    pub fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown_secs)
+    }
+
+    /// 🏥 健康检查间隔(None 表示不做周期检查)
+    pub fn health_check_interval(&self) -> Option<Duration> {
+        if self.health_check_interval_secs == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(self.health_check_interval_secs))
+        }
+    }
+
+    /// 🌐 代理对外暴露的 base URL(写入 ANTHROPIC_BASE_URL)
+    pub fn base_url(&self) -> String {
+        let host = if self.host == "0.0.0.0" {
+            "127.0.0.1"
+        } else {
+            self.host.as_str()
+        };
+        format!("http://{}:{}", host, self.port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_config_defaults_from_empty_table() {
+        let config: ProxyConfig = toml::from_str("").expect("空表应使用默认值");
+        assert_eq!(config, ProxyConfig::default());
+        assert_eq!(config.base_url(), "http://127.0.0.1:19530");
+        assert!(config.health_check_interval().is_some());
+    }
+
+    #[test]
+    fn test_proxy_config_parses_upstreams_in_order() {
+        let config: ProxyConfig = toml::from_str(
+            r#"
+            port = 18080
+            upstreams = ["b", "a"]
+            health_check_interval_secs = 0
+            "#,
+        )
+        .expect("解析失败");
+        assert_eq!(config.upstreams, vec!["b", "a"]);
+        assert_eq!(config.base_url(), "http://127.0.0.1:18080");
+        assert!(config.health_check_interval().is_none());
+    }
+}
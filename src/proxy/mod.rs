@@ -0,0 +1,24 @@
+// 🔀 CCR 本地故障转移代理模块
+// 🌐 在本机监听,将 Claude Code 的请求按顺序转发到多个上游 profile
+//
+// 模块组成:
+// - ⚙️ config - 代理配置([settings.proxy])
+// - 📋 upstream - 上游池与健康状态
+// - 🌐 server - HTTP 代理服务器(需要 web 特性)
+// - 🎯 takeover - 接管/恢复 Claude Code settings.json
+
+pub mod config;
+#[cfg(feature = "web")]
+pub mod server;
+pub mod takeover;
+pub mod upstream;
+
+#[allow(unused_imports)]
+pub use config::ProxyConfig;
+#[cfg(feature = "web")]
+#[allow(unused_imports)]
+pub use server::ProxyServer;
+#[allow(unused_imports)]
+pub use takeover::SettingsTakeover;
+#[allow(unused_imports)]
+pub use upstream::{Upstream, UpstreamPool, UpstreamSnapshot, resolve_upstreams};
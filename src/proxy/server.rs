@@ -0,0 +1,516 @@
+// 🌐 CCR 本地故障转移代理服务器
+// 🔀 将 Claude Code 的请求按顺序转发到多个上游 profile
+//
+// 转发规则:
+// - 📋 按上游池给出的顺序依次尝试
+// - 🔁 连接失败 / 超时 / 5xx / 429 时转移到下一个上游
+// - 🌊 响应体以流的方式透传(支持 SSE 流式输出)
+// - 🔑 认证头替换为上游 profile 的 auth_token
+
+use crate::core::error::{CcrError, Result};
+use crate::proxy::config::ProxyConfig;
+use crate::proxy::upstream::{Upstream, UpstreamPool};
+use crate::services::health_check::HealthCheckService;
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// 📦 请求体大小上限(需缓存请求体以便重试)
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// 🏷️ 标记实际处理请求的上游
+const UPSTREAM_HEADER: &str = "x-ccr-upstream";
+
+/// 🚫 不转发给上游的请求头
+const SKIPPED_REQUEST_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "connection",
+    "keep-alive",
+    "transfer-encoding",
+    "te",
+    "trailer",
+    "upgrade",
+    "proxy-authorization",
+    "proxy-connection",
+    "accept-encoding",
+    "authorization",
+    "x-api-key",
+];
+
+/// 🚫 不回传给客户端的响应头
+const SKIPPED_RESPONSE_HEADERS: &[&str] = &[
+    "content-length",
+    "connection",
+    "keep-alive",
+    "transfer-encoding",
+    "te",
+    "trailer",
+    "upgrade",
+];
+
+/// 🔀 代理共享状态
+#[derive(Clone)]
+struct ProxyState {
+    pool: Arc<UpstreamPool>,
+    client: reqwest::Client,
+    timeout: Duration,
+}
+
+/// 🌐 本地故障转移代理
+pub struct ProxyServer {
+    config: ProxyConfig,
+    pool: Arc<UpstreamPool>,
+    client: reqwest::Client,
+}
+
+impl ProxyServer {
+    /// 🏗️ 创建代理服务器
+    ///
+    /// `upstreams` 的顺序即故障转移优先级
+    pub fn new(config: ProxyConfig, upstreams: Vec<Upstream>) -> Result<Self> {
+        if upstreams.is_empty() {
+            return Err(CcrError::ValidationError(
+                "代理至少需要一个可用的上游 profile".into(),
+            ));
+        }
+
+        // 不设置整体超时: 流式响应可能持续数分钟,仅限制连接与响应头等待时间
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("ccr-proxy/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(config.timeout())
+            .pool_max_idle_per_host(5)
+            .build()
+            .map_err(|e| CcrError::NetworkError(format!("创建代理 HTTP 客户端失败: {}", e)))?;
+
+        let pool = Arc::new(UpstreamPool::new(upstreams, config.cooldown()));
+
+        Ok(Self {
+            config,
+            pool,
+            client,
+        })
+    }
+
+    /// 📋 上游池(供状态展示和健康检查使用)
+    pub fn pool(&self) -> Arc<UpstreamPool> {
+        Arc::clone(&self.pool)
+    }
+
+    /// 🧭 构建路由
+    ///
+    /// - `GET /ccr/proxy/status` - 上游状态快照
+    /// - 其他所有路径 - 转发到上游(包括 `/v1/messages`)
+    pub fn router(&self) -> Router {
+        let state = ProxyState {
+            pool: Arc::clone(&self.pool),
+            client: self.client.clone(),
+            timeout: self.config.timeout(),
+        };
+
+        Router::new()
+            .route("/ccr/proxy/status", get(handle_status))
+            .fallback(handle_forward)
+            .with_state(state)
+    }
+
+    /// 🏥 启动后台健康检查
+    ///
+    /// 启动时立即探测一次,之后按配置间隔周期探测;
+    /// 结果写入上游池,Unhealthy 的上游会被跳过
+    pub fn spawn_health_monitor(&self) -> JoinHandle<()> {
+        let pool = Arc::clone(&self.pool);
+        let interval = self.config.health_check_interval();
+        let timeout = self.config.timeout();
+
+        tokio::spawn(async move {
+            let service = HealthCheckService::new().with_timeout(timeout);
+            loop {
+                for upstream in pool.upstreams() {
+                    let result = service.check(&upstream.name, &upstream.section).await;
+                    pool.apply_health(&result);
+                }
+
+                match interval {
+                    Some(interval) => tokio::time::sleep(interval).await,
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// 🚀 在给定 listener 上运行代理,直到 `shutdown` 完成
+    pub async fn serve<F>(&self, listener: TcpListener, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(shutdown)
+            .await
+            .map_err(|e| CcrError::NetworkError(format!("代理服务器运行错误: {}", e)))
+    }
+}
+
+/// 📊 上游状态
+async fn handle_status(State(state): State<ProxyState>) -> impl IntoResponse {
+    Json(state.pool.snapshot())
+}
+
+/// 🔀 转发请求(带故障转移)
+async fn handle_forward(State(state): State<ProxyState>, request: Request) -> Response {
+    let (parts, body) = request.into_parts();
+
+    // 缓存请求体,以便在多个上游之间重试
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "invalid_request_error",
+                &format!("读取请求体失败: {}", e),
+            );
+        }
+    };
+
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    let forward_headers = filter_headers(&parts.headers, SKIPPED_REQUEST_HEADERS);
+
+    let candidates = state.pool.candidates();
+    let total = candidates.len();
+    let mut last_error = String::from("没有可用的上游");
+
+    for (index, upstream) in candidates.into_iter().enumerate() {
+        let is_last = index + 1 == total;
+        let url = format!("{}{}", upstream.base_url(), path_and_query);
+
+        tracing::debug!(
+            "🔀 转发 {} {} → {}",
+            parts.method,
+            path_and_query,
+            upstream.name
+        );
+
+        let request = state
+            .client
+            .request(parts.method.clone(), &url)
+            .headers(forward_headers.clone())
+            .header(
+                header::AUTHORIZATION,
+                format!("Bearer {}", upstream.auth_token()),
+            )
+            .header("x-api-key", upstream.auth_token())
+            .body(body.clone());
+
+        match tokio::time::timeout(state.timeout, request.send()).await {
+            Ok(Ok(response)) => {
+                let status = response.status();
+                if is_retryable(status) {
+                    let message = format!("上游 {} 返回 HTTP {}", upstream.name, status);
+                    tracing::warn!("⚠️ {}", message);
+                    state.pool.mark_failure(upstream, message.clone());
+
+                    // 最后一个上游的错误响应原样返回,保留上游的错误信息
+                    if is_last {
+                        return stream_response(response, &upstream.name);
+                    }
+                    last_error = message;
+                    continue;
+                }
+
+                state.pool.mark_success(upstream);
+                return stream_response(response, &upstream.name);
+            }
+            Ok(Err(e)) => {
+                let message = format!("上游 {} 请求失败: {}", upstream.name, e);
+                tracing::warn!("⚠️ {}", message);
+                state.pool.mark_failure(upstream, message.clone());
+                last_error = message;
+            }
+            Err(_) => {
+                let message = format!(
+                    "上游 {} 在 {} 秒内未响应",
+                    upstream.name,
+                    state.timeout.as_secs()
+                );
+                tracing::warn!("⚠️ {}", message);
+                state.pool.mark_failure(upstream, message.clone());
+                last_error = message;
+            }
+        }
+    }
+
+    error_response(StatusCode::BAD_GATEWAY, "api_error", &last_error)
+}
+
+/// 🔁 是否应转移到下一个上游
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 🧹 过滤请求/响应头
+fn filter_headers(headers: &HeaderMap, skipped: &[&str]) -> HeaderMap {
+    headers
+        .iter()
+        .filter(|(name, _)| !skipped.contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+/// 🌊 将上游响应以流的方式回传
+fn stream_response(response: reqwest::Response, upstream_name: &str) -> Response {
+    let status = response.status();
+    let headers = filter_headers(response.headers(), SKIPPED_RESPONSE_HEADERS);
+
+    let mut builder = Response::builder().status(status);
+    if let Some(target) = builder.headers_mut() {
+        target.extend(headers);
+        if let Ok(value) = HeaderValue::from_str(upstream_name) {
+            target.insert(HeaderName::from_static(UPSTREAM_HEADER), value);
+        }
+    }
+
+    builder
+        .body(Body::from_stream(response.bytes_stream()))
+        .unwrap_or_else(|e| {
+            error_response(
+                StatusCode::BAD_GATEWAY,
+                "api_error",
+                &format!("构建响应失败: {}", e),
+            )
+        })
+}
+
+/// ❌ 以 Anthropic API 的错误格式返回
+fn error_response(status: StatusCode, error_type: &str, message: &str) -> Response {
+    let body = serde_json::json!({
+        "type": "error",
+        "error": {
+            "type": error_type,
+            "message": format!("[ccr proxy] {}", message),
+        }
+    });
+    (status, Json(body)).into_response()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::managers::config::ConfigSection;
+    use crate::services::health_check::{HealthCheckResult, HealthStatus};
+    use axum::routing::post;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const SSE_BODY: &str = "event: message_start\ndata: {\"type\":\"message_start\"}\n\n\
+                            event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n";
+
+    /// 启动一个模拟上游,返回其 base URL
+    async fn spawn_mock(router: Router) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    /// 返回固定状态码并计数的上游
+    async fn spawn_failing(status: StatusCode, hits: Arc<AtomicUsize>) -> String {
+        let router = Router::new().route(
+            "/v1/messages",
+            post(move || {
+                let hits = Arc::clone(&hits);
+                async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    (status, "upstream failure")
+                }
+            }),
+        );
+        spawn_mock(router).await
+    }
+
+    /// 校验认证头并返回 SSE 流的上游
+    async fn spawn_streaming(expected_key: &'static str) -> String {
+        let router = Router::new().route(
+            "/v1/messages",
+            post(move |headers: HeaderMap| async move {
+                let key = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+                if key != Some(expected_key) {
+                    return (StatusCode::UNAUTHORIZED, "bad key").into_response();
+                }
+                (
+                    [(header::CONTENT_TYPE, "text/event-stream")],
+                    Body::from(SSE_BODY),
+                )
+                    .into_response()
+            }),
+        );
+        spawn_mock(router).await
+    }
+
+    fn upstream(name: &str, base_url: &str) -> Upstream {
+        Upstream::new(
+            name,
+            ConfigSection {
+                base_url: Some(base_url.to_string()),
+                auth_token: Some(format!("sk-{}", name)),
+                ..Default::default()
+            },
+        )
+    }
+
+    async fn start_proxy(upstreams: Vec<Upstream>) -> (String, Arc<UpstreamPool>) {
+        let config = ProxyConfig {
+            timeout_secs: 2,
+            health_check_interval_secs: 0,
+            ..Default::default()
+        };
+        let server = ProxyServer::new(config, upstreams).unwrap();
+        let pool = server.pool();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            server
+                .serve(listener, std::future::pending::<()>())
+                .await
+                .unwrap();
+        });
+        (format!("http://{}", addr), pool)
+    }
+
+    async fn post_message(proxy_url: &str) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{}/v1/messages", proxy_url))
+            .header("x-api-key", "ccr-proxy")
+            .json(&serde_json::json!({"model": "claude", "stream": true}))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failover_on_server_error_streams_backup_response() {
+        let primary_hits = Arc::new(AtomicUsize::new(0));
+        let primary =
+            spawn_failing(StatusCode::SERVICE_UNAVAILABLE, Arc::clone(&primary_hits)).await;
+        let backup = spawn_streaming("sk-backup").await;
+
+        let (proxy_url, pool) = start_proxy(vec![
+            upstream("primary", &primary),
+            upstream("backup", &backup),
+        ])
+        .await;
+
+        let response = post_message(&proxy_url).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.headers().get(UPSTREAM_HEADER).unwrap(), "backup");
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+        assert_eq!(response.text().await.unwrap(), SSE_BODY);
+        assert_eq!(primary_hits.load(Ordering::SeqCst), 1);
+
+        // 失败的上游进入冷却期,后续请求直接走备用上游
+        let response = post_message(&proxy_url).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(primary_hits.load(Ordering::SeqCst), 1);
+
+        let snapshot = pool.snapshot();
+        assert!(snapshot[0].cooling_down);
+        assert_eq!(snapshot[1].total_requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_on_rate_limit_and_connection_error() {
+        // 绑定后立即释放端口,模拟连接失败
+        let dead = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let limited_hits = Arc::new(AtomicUsize::new(0));
+        let limited = spawn_failing(StatusCode::TOO_MANY_REQUESTS, Arc::clone(&limited_hits)).await;
+        let backup = spawn_streaming("sk-backup").await;
+
+        let (proxy_url, _pool) = start_proxy(vec![
+            upstream("dead", &dead),
+            upstream("limited", &limited),
+            upstream("backup", &backup),
+        ])
+        .await;
+
+        let response = post_message(&proxy_url).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.headers().get(UPSTREAM_HEADER).unwrap(), "backup");
+        assert_eq!(limited_hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_upstream_is_skipped() {
+        let primary_hits = Arc::new(AtomicUsize::new(0));
+        let primary = spawn_failing(StatusCode::OK, Arc::clone(&primary_hits)).await;
+        let backup = spawn_streaming("sk-backup").await;
+
+        let (proxy_url, pool) = start_proxy(vec![
+            upstream("primary", &primary),
+            upstream("backup", &backup),
+        ])
+        .await;
+
+        pool.apply_health(&HealthCheckResult {
+            provider_name: "primary".into(),
+            base_url: primary.clone(),
+            status: HealthStatus::Unhealthy,
+            latency_ms: None,
+            error: Some("探测失败".into()),
+            model_available: false,
+            available_models: vec![],
+        });
+
+        let response = post_message(&proxy_url).await;
+        assert_eq!(response.headers().get(UPSTREAM_HEADER).unwrap(), "backup");
+        assert_eq!(primary_hits.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_all_upstreams_failing_returns_last_error() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let first = spawn_failing(StatusCode::BAD_GATEWAY, Arc::clone(&hits)).await;
+        let second = spawn_failing(StatusCode::SERVICE_UNAVAILABLE, Arc::clone(&hits)).await;
+
+        let (proxy_url, _pool) =
+            start_proxy(vec![upstream("first", &first), upstream("second", &second)]).await;
+
+        let response = post_message(&proxy_url).await;
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        let status: serde_json::Value = reqwest::get(format!("{}/ccr/proxy/status", proxy_url))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(status.as_array().unwrap().len(), 2);
+        assert_eq!(status[0]["total_failures"], 1);
+    }
+
+    #[test]
+    fn test_new_requires_upstreams() {
+        assert!(ProxyServer::new(ProxyConfig::default(), vec![]).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// 🎯 代理接管 Claude Code 设置
+// 📝 启动代理时将 settings.json 指向本地代理,退出时恢复原有 env 表与 CCR 写入记录
+
+use crate::core::error::Result;
+use crate::managers::config::ConfigSection;
+use crate::managers::managed_state::ManagedState;
+use crate::managers::settings::{ClaudeSettings, SettingsManager};
+use std::collections::HashMap;
+
+/// 🔑 写入 settings.json 的占位令牌(代理会替换为上游令牌)
+pub const PROXY_AUTH_TOKEN: &str = "ccr-proxy";
+
+/// 🎯 settings.json 接管句柄
+///
+/// 持有接管前的完整 env 表与写入记录,调用 [`SettingsTakeover::restore`] 时写回
+pub struct SettingsTakeover {
+    manager: SettingsManager,
+    previous_env: HashMap<String, String>,
+    previous_managed: ManagedState,
+}
+
+impl SettingsTakeover {
+    /// 🔀 将 Claude Code 指向本地代理
+    ///
+    /// 模型与 env 表等设置沿用首选上游,地址指向代理,认证令牌使用占位值
+    pub fn apply(
+        manager: SettingsManager,
+        proxy_base_url: &str,
+        primary: &ConfigSection,
+    ) -> Result<Self> {
+        if manager.settings_path().exists() {
+            manager.backup(Some("proxy"))?;
+        }
+
+        let mut settings = manager.load().unwrap_or_else(|_| ClaudeSettings::new());
+        let previous_env = settings.env.clone();
+        let previous_managed = settings.managed.clone();
+
+        settings.update_from_config(&ConfigSection {
+            base_url: Some(proxy_base_url.to_string()),
+            auth_token: Some(PROXY_AUTH_TOKEN.to_string()),
+            ..primary.clone()
+        });
+        manager.save_atomic(&settings)?;
+
+        tracing::info!("🎯 Claude Code 已指向本地代理: {}", proxy_base_url);
+        Ok(Self {
+            manager,
+            previous_env,
+            previous_managed,
+        })
+    }
+
+    /// ↩️ 恢复接管前的 env 表与写入记录
+    pub fn restore(self) -> Result<()> {
+        let mut settings = self
+            .manager
+            .load()
+            .unwrap_or_else(|_| ClaudeSettings::new());
+        settings.env = self.previous_env;
+        settings.managed = self.previous_managed;
+        self.manager.save_atomic(&settings)?;
+
+        tracing::info!("↩️ 已恢复代理接管前的 Claude Code 设置");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::core::lock::LockManager;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_apply_and_restore_round_trip() {
+        let temp = tempdir().unwrap();
+        let settings_path = temp.path().join("settings.json");
+        let make_manager = || {
+            SettingsManager::new(
+                &settings_path,
+                temp.path().join("backups"),
+                LockManager::new(temp.path().join("locks")),
+            )
+            .with_state_path(temp.path().join("managed_settings.json"))
+        };
+
+        let mut original = ClaudeSettings::new();
+        original.env.insert(
+            "ANTHROPIC_BASE_URL".into(),
+            "https://relay.example.com".into(),
+        );
+        original
+            .env
+            .insert("ANTHROPIC_AUTH_TOKEN".into(), "sk-original".into());
+        original.env.insert("OTHER_VAR".into(), "keep".into());
+        // 当前 profile 通过 env 表写入的变量
+        original
+            .env
+            .insert("API_TIMEOUT_MS".into(), "600000".into());
+        original.managed.env = vec!["API_TIMEOUT_MS".into()];
+        make_manager().save_atomic(&original).unwrap();
+
+        let primary: ConfigSection = toml::from_str(
+            r#"
+model = "claude-sonnet"
+
+[env]
+BASH_DEFAULT_TIMEOUT_MS = "120000"
+"#,
+        )
+        .unwrap();
+        let takeover =
+            SettingsTakeover::apply(make_manager(), "http://127.0.0.1:19530", &primary).unwrap();
+
+        let during = make_manager().load().unwrap();
+        assert_eq!(
+            during.env.get("ANTHROPIC_BASE_URL").unwrap(),
+            "http://127.0.0.1:19530"
+        );
+        assert_eq!(
+            during.env.get("ANTHROPIC_AUTH_TOKEN").unwrap(),
+            PROXY_AUTH_TOKEN
+        );
+        assert_eq!(during.env.get("ANTHROPIC_MODEL").unwrap(), "claude-sonnet");
+        assert_eq!(during.env.get("BASH_DEFAULT_TIMEOUT_MS").unwrap(), "120000");
+        assert_eq!(make_manager().list_backups().unwrap().len(), 1);
+
+        takeover.restore().unwrap();
+
+        let after = make_manager().load().unwrap();
+        assert_eq!(
+            after.env.get("ANTHROPIC_BASE_URL").unwrap(),
+            "https://relay.example.com"
+        );
+        assert_eq!(
+            after.env.get("ANTHROPIC_AUTH_TOKEN").unwrap(),
+            "sk-original"
+        );
+        assert!(!after.env.contains_key("ANTHROPIC_MODEL"));
+        assert_eq!(after.env.get("OTHER_VAR").unwrap(), "keep");
+        assert_eq!(after.env.get("API_TIMEOUT_MS").unwrap(), "600000");
+        assert!(!after.env.contains_key("BASH_DEFAULT_TIMEOUT_MS"));
+        assert_eq!(after.managed.env, vec!["API_TIMEOUT_MS".to_string()]);
+    }
+}
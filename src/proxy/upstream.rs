@@ -0,0 +1,362 @@
+// 📋 代理上游池
+// 🔀 维护上游 profile 的有序列表与运行时健康状态
+//
+// 状态来源:
+// - 🏥 HealthCheckService 的周期探测结果(Unhealthy 的上游被跳过)
+// - 🧊 转发失败后的冷却期(5xx / 429 / 超时)
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::config::{CcsConfig, ConfigManager, ConfigSection};
+use crate::services::health_check::{HealthCheckResult, HealthStatus};
+use crate::utils::Validatable;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 🔗 单个上游
+#[derive(Debug)]
+pub struct Upstream {
+    /// 📝 profile 名称
+    pub name: String,
+    /// ⚙️ profile 配置(提供 base_url 与 auth_token)
+    pub section: ConfigSection,
+    state: Mutex<UpstreamState>,
+}
+
+/// 📊 上游运行时状态
+#[derive(Debug, Clone)]
+struct UpstreamState {
+    health: HealthStatus,
+    cooldown_until: Option<Instant>,
+    consecutive_failures: u32,
+    total_requests: u64,
+    total_failures: u64,
+    last_error: Option<String>,
+}
+
+/// 📊 上游状态快照(用于 /ccr/proxy/status)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpstreamSnapshot {
+    pub name: String,
+    pub base_url: String,
+    pub health: HealthStatus,
+    pub cooling_down: bool,
+    pub consecutive_failures: u32,
+    pub total_requests: u64,
+    pub total_failures: u64,
+    pub last_error: Option<String>,
+}
+
+impl Upstream {
+    /// 🏗️ 创建上游
+    pub fn new(name: impl Into<String>, section: ConfigSection) -> Self {
+        Self {
+            name: name.into(),
+            section,
+            state: Mutex::new(UpstreamState {
+                health: HealthStatus::Unknown,
+                cooldown_until: None,
+                consecutive_failures: 0,
+                total_requests: 0,
+                total_failures: 0,
+                last_error: None,
+            }),
+        }
+    }
+
+    /// 🌐 上游 base URL(去除末尾斜杠)
+    pub fn base_url(&self) -> &str {
+        self.section
+            .base_url
+            .as_deref()
+            .unwrap_or("https://api.anthropic.com")
+            .trim_end_matches('/')
+    }
+
+    /// 🔑 上游认证令牌
+    pub fn auth_token(&self) -> &str {
+        self.section.auth_token.as_deref().unwrap_or_default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, UpstreamState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// ✅ 当前是否可用(非 Unhealthy 且不在冷却期)
+    fn is_available(&self, now: Instant) -> bool {
+        let state = self.state();
+        let cooling = state.cooldown_until.is_some_and(|until| until > now);
+        state.health != HealthStatus::Unhealthy && !cooling
+    }
+}
+
+/// 📋 上游池
+#[derive(Debug)]
+pub struct UpstreamPool {
+    upstreams: Vec<Upstream>,
+    cooldown: Duration,
+}
+
+impl UpstreamPool {
+    /// 🏗️ 创建上游池(顺序即故障转移优先级)
+    pub fn new(upstreams: Vec<Upstream>, cooldown: Duration) -> Self {
+        Self {
+            upstreams,
+            cooldown,
+        }
+    }
+
+    /// 📋 所有上游
+    pub fn upstreams(&self) -> &[Upstream] {
+        &self.upstreams
+    }
+
+    /// 🔀 本次请求的尝试顺序
+    ///
+    /// 可用上游按配置顺序排在前面;若全部不可用,则退化为按原顺序全部尝试,
+    /// 避免因健康检查误判导致完全不可用
+    pub fn candidates(&self) -> Vec<&Upstream> {
+        let now = Instant::now();
+        let (available, unavailable): (Vec<&Upstream>, Vec<&Upstream>) =
+            self.upstreams.iter().partition(|u| u.is_available(now));
+
+        if available.is_empty() {
+            unavailable
+        } else {
+            available
+        }
+    }
+
+    /// ✅ 记录一次成功转发
+    pub fn mark_success(&self, upstream: &Upstream) {
+        let mut state = upstream.state();
+        state.total_requests += 1;
+        state.consecutive_failures = 0;
+        state.cooldown_until = None;
+    }
+
+    /// ❌ 记录一次失败转发,并进入冷却期
+    pub fn mark_failure(&self, upstream: &Upstream, error: impl Into<String>) {
+        let mut state = upstream.state();
+        state.total_requests += 1;
+        state.total_failures += 1;
+        state.consecutive_failures += 1;
+        state.cooldown_until = Some(Instant::now() + self.cooldown);
+        state.last_error = Some(error.into());
+    }
+
+    /// 🏥 应用健康检查结果
+    pub fn apply_health(&self, result: &HealthCheckResult) {
+        if let Some(upstream) = self
+            .upstreams
+            .iter()
+            .find(|u| u.name == result.provider_name)
+        {
+            let mut state = upstream.state();
+            state.health = result.status.clone();
+            if result.status == HealthStatus::Unhealthy {
+                state.last_error = result.error.clone();
+            } else {
+                // 探测恢复健康时解除冷却
+                state.cooldown_until = None;
+            }
+        }
+    }
+
+    /// 📊 状态快照
+    pub fn snapshot(&self) -> Vec<UpstreamSnapshot> {
+        let now = Instant::now();
+        self.upstreams
+            .iter()
+            .map(|u| {
+                let state = u.state();
+                UpstreamSnapshot {
+                    name: u.name.clone(),
+                    base_url: u.base_url().to_string(),
+                    health: state.health.clone(),
+                    cooling_down: state.cooldown_until.is_some_and(|until| until > now),
+                    consecutive_failures: state.consecutive_failures,
+                    total_requests: state.total_requests,
+                    total_failures: state.total_failures,
+                    last_error: state.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// 📋 从配置文件解析上游列表
+///
+/// - `names` 非空: 按给定顺序使用,名称不存在时报错
+/// - `names` 为空: 使用所有启用的 profile,当前 profile 排在最前
+///
+/// profile 先展开继承 (extends) 再校验;模板、已禁用、校验失败(缺少 base_url / auth_token)
+/// 或密钥来源解析失败的 profile 会被跳过
+pub fn resolve_upstreams(config: &CcsConfig, names: &[String]) -> Result<Vec<Upstream>> {
+    let ordered: Vec<String> = if names.is_empty() {
+        let mut all: Vec<String> = config.list_sections().cloned().collect();
+        if let Some(pos) = all.iter().position(|n| n == &config.current_config) {
+            let current = all.remove(pos);
+            all.insert(0, current);
+        }
+        all
+    } else {
+        names.to_vec()
+    };
+
+    let mut upstreams = Vec::new();
+    for name in ordered {
+        let section = ConfigManager::resolve_section(config, &name)?.section;
+
+        if section.is_template() {
+            tracing::warn!("⏭️ 跳过模板 profile: {}", name);
+            continue;
+        }
+        if !section.is_enabled() {
+            tracing::warn!("⏭️ 跳过已禁用的上游 profile: {}", name);
+            continue;
+        }
+        if let Err(e) = section.validate() {
+            tracing::warn!("⏭️ 跳过无效的上游 profile {}: {}", name, e);
+            continue;
+        }
+
+        match section.with_resolved_secrets() {
+            Ok(resolved) => upstreams.push(Upstream::new(name, resolved)),
+            Err(e) => tracing::warn!("⏭️ 跳过密钥解析失败的上游 profile {}: {}", name, e),
+        }
+    }
+
+    if upstreams.is_empty() {
+        return Err(CcrError::ValidationError(
+            "没有可用的上游 profile (需要启用且包含 base_url 和 auth_token)".into(),
+        ));
+    }
+
+    Ok(upstreams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upstream(name: &str) -> Upstream {
+        Upstream::new(
+            name,
+            ConfigSection {
+                base_url: Some(format!("https://{}.example.com/", name)),
+                auth_token: Some(format!("sk-{}", name)),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn names(pool: &UpstreamPool) -> Vec<String> {
+        pool.candidates().iter().map(|u| u.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_failed_upstream_moves_out_of_rotation() {
+        let pool = UpstreamPool::new(vec![upstream("a"), upstream("b")], Duration::from_secs(60));
+        assert_eq!(names(&pool), vec!["a", "b"]);
+        assert_eq!(pool.upstreams()[0].base_url(), "https://a.example.com");
+
+        pool.mark_failure(&pool.upstreams()[0], "HTTP 503");
+        assert_eq!(names(&pool), vec!["b"]);
+
+        pool.mark_success(&pool.upstreams()[0]);
+        assert_eq!(names(&pool), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_unhealthy_upstream_skipped_until_recovered() {
+        let pool = UpstreamPool::new(vec![upstream("a"), upstream("b")], Duration::from_secs(60));
+        let mut result = HealthCheckResult {
+            provider_name: "a".into(),
+            base_url: "https://a.example.com".into(),
+            status: HealthStatus::Unhealthy,
+            latency_ms: None,
+            error: Some("连接失败".into()),
+            model_available: false,
+            available_models: vec![],
+        };
+        pool.apply_health(&result);
+        assert_eq!(names(&pool), vec!["b"]);
+
+        result.status = HealthStatus::Healthy;
+        pool.apply_health(&result);
+        assert_eq!(names(&pool), vec!["a", "b"]);
+    }
+
+    fn sample_config() -> CcsConfig {
+        let mut config: CcsConfig = toml::from_str(
+            r#"
+            default_config = "a"
+            current_config = "c"
+            "#,
+        )
+        .expect("解析失败");
+        for name in ["a", "b", "c"] {
+            config.set_section(name.to_string(), upstream(name).section);
+        }
+        config.get_section_mut("b").expect("b 存在").disable();
+        config
+    }
+
+    #[test]
+    fn test_resolve_upstreams_current_first_and_skips_disabled() {
+        let config = sample_config();
+        let resolved = resolve_upstreams(&config, &[]).expect("解析上游失败");
+        let names: Vec<&str> = resolved.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, vec!["c", "a"]);
+
+        let explicit = resolve_upstreams(&config, &["a".into(), "c".into()]).expect("解析失败");
+        assert_eq!(explicit[0].name, "a");
+
+        assert!(resolve_upstreams(&config, &["missing".into()]).is_err());
+        assert!(resolve_upstreams(&config, &["b".into()]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_upstreams_expands_extends_and_skips_templates() {
+        let config: CcsConfig = toml::from_str(
+            r#"
+            default_config = "child"
+            current_config = "child"
+
+            [base]
+            template = true
+            base_url = "https://base.example.com"
+            auth_token = "sk-base"
+
+            [child]
+            extends = "base"
+            model = "claude-sonnet"
+
+            [broken]
+            base_url = "https://broken.example.com"
+            auth_token = "${env:CCR_TEST_MISSING_UPSTREAM_TOKEN}"
+            "#,
+        )
+        .expect("解析失败");
+
+        let resolved = resolve_upstreams(&config, &[]).expect("解析上游失败");
+        let names: Vec<&str> = resolved.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, vec!["child"]);
+        assert_eq!(resolved[0].base_url(), "https://base.example.com");
+        assert_eq!(resolved[0].auth_token(), "sk-base");
+
+        assert!(resolve_upstreams(&config, &["base".into()]).is_err());
+    }
+
+    #[test]
+    fn test_all_unavailable_falls_back_to_full_order() {
+        let pool = UpstreamPool::new(vec![upstream("a"), upstream("b")], Duration::from_secs(60));
+        pool.mark_failure(&pool.upstreams()[0], "timeout");
+        pool.mark_failure(&pool.upstreams()[1], "timeout");
+        assert_eq!(names(&pool), vec!["a", "b"]);
+        assert!(pool.snapshot().iter().all(|s| s.cooling_down));
+    }
+}
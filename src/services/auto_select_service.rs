@@ -0,0 +1,347 @@
+//! 🎯 自动选择最佳 profile
+//!
+//! 并发探测候选 profile，剔除不可用的，再按策略（延迟 / 成本 / 均衡）与
+//! profile 的优先级权重 (`priority`) 选出最佳配置，供 `ccr switch --auto` 使用。
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::PricingManager;
+use crate::managers::config::ConfigSection;
+use crate::services::health_check::{HealthCheckResult, HealthCheckService, HealthStatus};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use tokio::task::JoinSet;
+
+/// 📋 可选的选择策略
+pub const SELECT_POLICY_NAMES: [&str; 3] = ["latency", "cost", "balance"];
+
+/// 🧭 选择策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectPolicy {
+    /// 延迟最低
+    Latency,
+    /// 模型单价最低
+    Cost,
+    /// 延迟与单价各占一半
+    Balance,
+}
+
+impl SelectPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SelectPolicy::Latency => "latency",
+            SelectPolicy::Cost => "cost",
+            SelectPolicy::Balance => "balance",
+        }
+    }
+
+    pub fn display(&self) -> &'static str {
+        match self {
+            SelectPolicy::Latency => "延迟优先",
+            SelectPolicy::Cost => "成本优先",
+            SelectPolicy::Balance => "均衡",
+        }
+    }
+}
+
+impl FromStr for SelectPolicy {
+    type Err = CcrError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "latency" => Ok(SelectPolicy::Latency),
+            "cost" => Ok(SelectPolicy::Cost),
+            "balance" => Ok(SelectPolicy::Balance),
+            _ => Err(CcrError::ValidationError(format!(
+                "不支持的选择策略: {} (可选: {})",
+                s,
+                SELECT_POLICY_NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+/// 🧪 单个候选 profile 的探测与评分结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    pub profile: String,
+    pub status: HealthStatus,
+    pub latency_ms: Option<u64>,
+    pub model: Option<String>,
+    /// 模型单价（输入 + 输出，美元/百万 Token），无定价时为 None
+    pub cost_per_mtok: Option<f64>,
+    /// 优先级权重
+    pub priority: f64,
+    /// 得分（越低越好），不可用的候选为 None
+    pub score: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl Candidate {
+    pub fn is_available(&self) -> bool {
+        self.status.is_up()
+    }
+
+    /// 📝 一行说明，用于历史记录
+    fn describe(&self) -> String {
+        let mut parts = vec![self.status.display().to_string()];
+        if let Some(latency) = self.latency_ms {
+            parts.push(format!("{} ms", latency));
+        }
+        if self.is_available() {
+            parts.push(match self.cost_per_mtok {
+                Some(cost) => format!("${:.2}/MTok", cost),
+                None => "无定价".to_string(),
+            });
+            if self.priority != 1.0 {
+                parts.push(format!("权重 {}", self.priority));
+            }
+            if let Some(score) = self.score {
+                parts.push(format!("得分 {:.3}", score));
+            }
+        } else if let Some(error) = &self.error {
+            parts.push(error.clone());
+        }
+        format!("{} ({})", self.profile, parts.join(", "))
+    }
+}
+
+/// 🏆 自动选择结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoSelection {
+    pub policy: SelectPolicy,
+    /// 可用候选按得分升序在前，不可用候选在后
+    pub candidates: Vec<Candidate>,
+}
+
+impl AutoSelection {
+    /// 🥇 得分最低的可用候选
+    pub fn winner(&self) -> Option<&Candidate> {
+        self.candidates.first().filter(|c| c.is_available())
+    }
+
+    pub fn available_count(&self) -> usize {
+        self.candidates.iter().filter(|c| c.is_available()).count()
+    }
+
+    /// 📝 决策摘要
+    pub fn summary(&self) -> String {
+        match self.winner() {
+            Some(winner) => format!(
+                "自动选择 (策略: {}): {}，{}/{} 个候选可用",
+                self.policy.as_str(),
+                winner.profile,
+                self.available_count(),
+                self.candidates.len()
+            ),
+            None => format!(
+                "自动选择 (策略: {}): {} 个候选均不可用",
+                self.policy.as_str(),
+                self.candidates.len()
+            ),
+        }
+    }
+
+    /// 📝 每个候选的探测结果与得分
+    pub fn reasoning(&self) -> String {
+        self.candidates
+            .iter()
+            .map(Candidate::describe)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// 🎯 自动选择服务
+pub struct AutoSelectService {
+    checker: HealthCheckService,
+    pricing: PricingManager,
+}
+
+impl AutoSelectService {
+    pub fn new(checker: HealthCheckService, pricing: PricingManager) -> Self {
+        Self { checker, pricing }
+    }
+
+    /// 🏠 使用默认价格表 (`~/.claude/pricing.toml`) 创建服务
+    pub fn with_default() -> Result<Self> {
+        Ok(Self::new(
+            HealthCheckService::new(),
+            PricingManager::with_default()?,
+        ))
+    }
+
+    /// 🔍 并发探测候选并按策略评分
+    pub async fn select(
+        &self,
+        targets: Vec<(String, ConfigSection)>,
+        policy: SelectPolicy,
+    ) -> Result<AutoSelection> {
+        let mut tasks = JoinSet::new();
+        for (index, (name, section)) in targets.iter().cloned().enumerate() {
+            let checker = self.checker.clone();
+            tasks.spawn(async move { (index, checker.check(&name, &section).await) });
+        }
+
+        let mut results = Vec::with_capacity(tasks.len());
+        while let Some(joined) = tasks.join_next().await {
+            results.push(
+                joined.map_err(|e| CcrError::NetworkError(format!("健康检查任务失败: {}", e)))?,
+            );
+        }
+        results.sort_by_key(|(index, _)| *index);
+        let results: Vec<HealthCheckResult> = results.into_iter().map(|(_, r)| r).collect();
+
+        let candidates = targets
+            .iter()
+            .zip(&results)
+            .map(|((name, section), result)| Candidate {
+                profile: name.clone(),
+                status: result.status.clone(),
+                latency_ms: result.latency_ms,
+                model: section.model.clone(),
+                cost_per_mtok: section
+                    .model
+                    .as_deref()
+                    .and_then(|model| self.pricing.get_or_default_pricing(model))
+                    .map(|p| p.input_price + p.output_price),
+                priority: section.priority(),
+                score: None,
+                error: result.error.clone(),
+            })
+            .collect();
+
+        Ok(AutoSelection {
+            policy,
+            candidates: rank(candidates, policy),
+        })
+    }
+}
+
+/// ⚖️ 为可用候选评分并排序
+///
+/// 延迟与单价分别除以可用候选中的最大值归一化到 [0, 1]，按策略取值
+/// （均衡策略取两者平均），再除以优先级权重；没有定价的模型按最贵处理
+fn rank(mut candidates: Vec<Candidate>, policy: SelectPolicy) -> Vec<Candidate> {
+    let available = || candidates.iter().filter(|c| c.is_available());
+    let max_latency = available().filter_map(|c| c.latency_ms).max().unwrap_or(0) as f64;
+    let max_cost = available()
+        .filter_map(|c| c.cost_per_mtok)
+        .fold(0.0, f64::max);
+
+    let normalize = |value: Option<f64>, max: f64| match value {
+        Some(_) if max <= 0.0 => 0.0,
+        Some(value) => value / max,
+        None => 1.0,
+    };
+
+    for candidate in candidates.iter_mut().filter(|c| c.is_available()) {
+        let latency = normalize(candidate.latency_ms.map(|l| l as f64), max_latency);
+        let cost = normalize(candidate.cost_per_mtok, max_cost);
+        let base = match policy {
+            SelectPolicy::Latency => latency,
+            SelectPolicy::Cost => cost,
+            SelectPolicy::Balance => (latency + cost) / 2.0,
+        };
+        candidate.score = Some(base / candidate.priority);
+    }
+
+    let latency = |c: &Candidate| c.latency_ms.unwrap_or(u64::MAX);
+    candidates.sort_by(|a, b| {
+        b.is_available()
+            .cmp(&a.is_available())
+            .then_with(|| match (a.score, b.score) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                _ => Ordering::Equal,
+            })
+            .then_with(|| latency(a).cmp(&latency(b)))
+            .then_with(|| a.profile.cmp(&b.profile))
+    });
+    candidates
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn candidate(profile: &str, latency: u64, cost: Option<f64>, priority: f64) -> Candidate {
+        Candidate {
+            profile: profile.into(),
+            status: HealthStatus::Healthy,
+            latency_ms: Some(latency),
+            model: None,
+            cost_per_mtok: cost,
+            priority,
+            score: None,
+            error: None,
+        }
+    }
+
+    fn names(selection: &[Candidate]) -> Vec<&str> {
+        selection.iter().map(|c| c.profile.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rank_by_policy_and_priority() {
+        let candidates = vec![
+            candidate("fast", 100, Some(90.0), 1.0),
+            candidate("cheap", 400, Some(10.0), 1.0),
+            candidate("unpriced", 200, None, 1.0),
+            Candidate {
+                status: HealthStatus::Unhealthy,
+                latency_ms: Some(5),
+                error: Some("HTTP 状态码: 502".into()),
+                ..candidate("down", 5, Some(1.0), 10.0)
+            },
+        ];
+
+        let latency = rank(candidates.clone(), SelectPolicy::Latency);
+        assert_eq!(names(&latency), ["fast", "unpriced", "cheap", "down"]);
+        assert_eq!(latency[3].score, None);
+
+        let cost = rank(candidates.clone(), SelectPolicy::Cost);
+        assert_eq!(names(&cost), ["cheap", "fast", "unpriced", "down"]);
+
+        // fast: (0.25 + 1.0) / 2, cheap: (1.0 + 0.11) / 2, unpriced: (0.5 + 1.0) / 2
+        let balance = rank(candidates.clone(), SelectPolicy::Balance);
+        assert_eq!(names(&balance), ["cheap", "fast", "unpriced", "down"]);
+
+        // 权重 5 让较慢的 cheap 在延迟策略下胜出
+        let mut weighted = candidates;
+        weighted[1].priority = 5.0;
+        let weighted = rank(weighted, SelectPolicy::Latency);
+        assert_eq!(weighted[0].profile, "cheap");
+    }
+
+    #[test]
+    fn test_selection_summary_and_reasoning() {
+        let selection = AutoSelection {
+            policy: SelectPolicy::Latency,
+            candidates: rank(
+                vec![
+                    candidate("alpha", 120, Some(18.0), 2.0),
+                    Candidate {
+                        status: HealthStatus::Unhealthy,
+                        error: Some("请求失败".into()),
+                        ..candidate("beta", 30, None, 1.0)
+                    },
+                ],
+                SelectPolicy::Latency,
+            ),
+        };
+
+        assert_eq!(selection.winner().unwrap().profile, "alpha");
+        assert!(selection.summary().contains("alpha，1/2 个候选可用"));
+        let reasoning = selection.reasoning();
+        assert!(reasoning.contains("权重 2"), "{reasoning}");
+        assert!(reasoning.contains("请求失败"), "{reasoning}");
+
+        assert!("fastest".parse::<SelectPolicy>().is_err());
+        assert_eq!(
+            "balance".parse::<SelectPolicy>().unwrap(),
+            SelectPolicy::Balance
+        );
+    }
+}
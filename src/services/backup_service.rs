@@ -134,7 +134,7 @@ impl BackupService {
 
         // 按修改时间倒序排列(最新的在前)
         // 注意：排序仍需串行，但扫描部分已并行化
-        backups.sort_by(|a, b| b.modified.cmp(&a.modified));
+        backups.sort_by_key(|b| std::cmp::Reverse(b.modified));
 
         Ok(backups)
     }
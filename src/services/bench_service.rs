@@ -0,0 +1,439 @@
+//! ⏱️ Provider 流式基准测试服务
+//!
+//! 向每个 profile 发送小型流式 `/v1/messages` 请求，测量首 token 延迟 (TTFT)、
+//! 输出速度 (tokens/s)、总延迟与错误率，并按结果排名。
+//!
+//! 不同 profile 并发测试，同一 profile 的多次请求依次发送，避免相互干扰。
+
+use crate::core::error::{CcrError, Result};
+use crate::core::http::HTTP_CLIENT;
+use crate::managers::config::ConfigSection;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tracing::{debug, info};
+
+/// 💬 默认测试提示词
+pub const DEFAULT_BENCH_PROMPT: &str = "用三句话介绍 Rust 的所有权机制。";
+
+/// 🤖 profile 未配置模型时使用的模型
+pub const DEFAULT_BENCH_MODEL: &str = "claude-haiku-4-5";
+
+/// 📏 每次请求的最大输出 token 数
+const BENCH_MAX_TOKENS: u32 = 256;
+
+/// 📊 单次请求的测量结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BenchSample {
+    /// 首 token 延迟（毫秒）
+    pub ttft_ms: Option<u64>,
+    /// 总延迟（毫秒）
+    pub total_ms: u64,
+    /// 输出 token 数
+    pub output_tokens: Option<u64>,
+    /// 输出速度（tokens/s，不含首 token 之前的等待）
+    pub tokens_per_sec: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// 🏁 单个 profile 的基准测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchResult {
+    /// 排名（从 1 开始）
+    pub rank: usize,
+    pub profile: String,
+    pub base_url: String,
+    pub model: String,
+    pub runs: usize,
+    pub errors: usize,
+    /// 错误率（百分比）
+    pub error_rate: f64,
+    /// 成功请求的首 token 延迟中位数（毫秒）
+    pub ttft_ms: Option<u64>,
+    /// 成功请求的输出速度中位数（tokens/s）
+    pub tokens_per_sec: Option<f64>,
+    /// 成功请求的总延迟中位数（毫秒）
+    pub total_ms: Option<u64>,
+    /// 成功请求的输出 token 数中位数
+    pub output_tokens: Option<u64>,
+    pub last_error: Option<String>,
+    pub samples: Vec<BenchSample>,
+}
+
+impl BenchResult {
+    fn from_samples(
+        profile: String,
+        base_url: String,
+        model: String,
+        samples: Vec<BenchSample>,
+    ) -> Self {
+        let ok: Vec<&BenchSample> = samples.iter().filter(|s| s.error.is_none()).collect();
+        let errors = samples.len() - ok.len();
+        let error_rate = if samples.is_empty() {
+            0.0
+        } else {
+            errors as f64 * 100.0 / samples.len() as f64
+        };
+
+        Self {
+            rank: 0,
+            profile,
+            base_url,
+            model,
+            runs: samples.len(),
+            errors,
+            error_rate,
+            ttft_ms: median(ok.iter().filter_map(|s| s.ttft_ms).map(|v| v as f64))
+                .map(|v| v.round() as u64),
+            tokens_per_sec: median(ok.iter().filter_map(|s| s.tokens_per_sec)),
+            total_ms: median(ok.iter().map(|s| s.total_ms as f64)).map(|v| v.round() as u64),
+            output_tokens: median(ok.iter().filter_map(|s| s.output_tokens).map(|v| v as f64))
+                .map(|v| v.round() as u64),
+            last_error: samples.iter().rev().find_map(|s| s.error.clone()),
+            samples,
+        }
+    }
+}
+
+/// ⏱️ 流式基准测试服务
+#[derive(Clone)]
+pub struct BenchService {
+    prompt: String,
+    runs: usize,
+    timeout: Duration,
+}
+
+impl BenchService {
+    pub fn new(prompt: impl Into<String>, runs: usize) -> Self {
+        Self {
+            prompt: prompt.into(),
+            runs,
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// 设置单次请求的超时时间
+    #[allow(dead_code)]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 🏁 并发测试所有 profile，返回排名后的结果
+    pub async fn bench_all(
+        &self,
+        targets: Vec<(String, ConfigSection)>,
+    ) -> Result<Vec<BenchResult>> {
+        let mut tasks = JoinSet::new();
+        for (name, section) in targets {
+            let service = self.clone();
+            tasks.spawn(async move { service.bench(&name, &section).await });
+        }
+
+        let mut results = Vec::with_capacity(tasks.len());
+        while let Some(joined) = tasks.join_next().await {
+            results.push(
+                joined.map_err(|e| CcrError::NetworkError(format!("基准测试任务失败: {}", e)))?,
+            );
+        }
+        Ok(rank(results))
+    }
+
+    /// 🏁 测试单个 profile（依次发送 `runs` 次请求）
+    pub async fn bench(&self, name: &str, config: &ConfigSection) -> BenchResult {
+        let model = config
+            .model
+            .clone()
+            .unwrap_or_else(|| DEFAULT_BENCH_MODEL.to_string());
+
+        // 🔑 解析保险库引用、${env:..} / ${file:..} 与 auth_token_cmd
+        let resolved = config.with_resolved_secrets();
+        let base_url = resolved
+            .as_ref()
+            .unwrap_or(config)
+            .base_url
+            .clone()
+            .unwrap_or_else(|| "https://api.anthropic.com".to_string());
+
+        info!("基准测试 Provider: {} ({}, {})", name, base_url, model);
+
+        let mut samples = Vec::with_capacity(self.runs);
+        for _ in 0..self.runs {
+            let sample = match &resolved {
+                Ok(section) => {
+                    let api_key = section.auth_token.clone().unwrap_or_default();
+                    self.run_once(&base_url, &api_key, &model).await
+                }
+                Err(e) => BenchSample {
+                    error: Some(format!("密钥解析失败: {}", e)),
+                    ..Default::default()
+                },
+            };
+            debug!("Provider {} 样本: {:?}", name, sample);
+            samples.push(sample);
+        }
+
+        BenchResult::from_samples(name.to_string(), base_url, model, samples)
+    }
+
+    /// 📡 发送一次流式请求并测量
+    async fn run_once(&self, base_url: &str, api_key: &str, model: &str) -> BenchSample {
+        let start = Instant::now();
+        let mut sample = match self.stream_messages(base_url, api_key, model, start).await {
+            Ok(sample) => sample,
+            Err(e) => BenchSample {
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        };
+        sample.total_ms = start.elapsed().as_millis() as u64;
+
+        if let (Some(ttft), Some(tokens)) = (sample.ttft_ms, sample.output_tokens) {
+            let generation_ms = sample.total_ms.saturating_sub(ttft);
+            if generation_ms > 0 {
+                sample.tokens_per_sec = Some(tokens as f64 * 1000.0 / generation_ms as f64);
+            }
+        }
+        sample
+    }
+
+    async fn stream_messages(
+        &self,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+        start: Instant,
+    ) -> Result<BenchSample> {
+        let url = format!("{}/v1/messages", base_url.trim_end_matches('/'));
+        let body = json!({
+            "model": model,
+            "max_tokens": BENCH_MAX_TOKENS,
+            "stream": true,
+            "messages": [{ "role": "user", "content": self.prompt }],
+        });
+
+        debug!("发送流式请求: {}", url);
+
+        let mut response = HTTP_CLIENT
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&body)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| CcrError::NetworkError(format!("请求失败: {}", e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(CcrError::NetworkError("API Key 无效".to_string()));
+        }
+        if !status.is_success() {
+            return Err(CcrError::NetworkError(format!("HTTP 状态码: {}", status)));
+        }
+
+        let mut stream = SseAccumulator::default();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| CcrError::NetworkError(format!("读取响应失败: {}", e)))?
+        {
+            stream.feed(&chunk, start.elapsed());
+            if stream.done {
+                break;
+            }
+        }
+        stream.finish()
+    }
+}
+
+/// 🧩 增量解析 Anthropic 流式响应中的 SSE 事件
+#[derive(Debug, Default)]
+struct SseAccumulator {
+    buffer: Vec<u8>,
+    ttft: Option<Duration>,
+    deltas: u64,
+    output_tokens: Option<u64>,
+    error: Option<String>,
+    done: bool,
+}
+
+impl SseAccumulator {
+    /// 追加一段响应数据，`elapsed` 为收到该数据时距请求开始的时间
+    fn feed(&mut self, chunk: &[u8], elapsed: Duration) {
+        self.buffer.extend(chunk.iter().filter(|b| **b != b'\r'));
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let event: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            self.handle_event(&String::from_utf8_lossy(&event), elapsed);
+        }
+    }
+
+    fn handle_event(&mut self, event: &str, elapsed: Duration) {
+        let mut name = None;
+        let mut data = String::new();
+        for line in event.lines() {
+            if let Some(value) = line.strip_prefix("event:") {
+                name = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("data:") {
+                data.push_str(value.trim_start());
+            }
+        }
+
+        let data: Value = serde_json::from_str(&data).unwrap_or(Value::Null);
+        let kind = data["type"].as_str().map(str::to_string).or(name);
+
+        match kind.as_deref() {
+            Some("content_block_delta") => {
+                self.ttft.get_or_insert(elapsed);
+                self.deltas += 1;
+            }
+            Some("message_start") => {
+                if let Some(tokens) = data["message"]["usage"]["output_tokens"].as_u64() {
+                    self.output_tokens = Some(tokens);
+                }
+            }
+            Some("message_delta") => {
+                if let Some(tokens) = data["usage"]["output_tokens"].as_u64() {
+                    self.output_tokens = Some(tokens);
+                }
+            }
+            Some("message_stop") => self.done = true,
+            Some("error") => {
+                let message = data["error"]["message"]
+                    .as_str()
+                    .unwrap_or("流式响应返回错误");
+                self.error = Some(message.to_string());
+                self.done = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> Result<BenchSample> {
+        if let Some(error) = self.error {
+            return Err(CcrError::NetworkError(error));
+        }
+        let Some(ttft) = self.ttft else {
+            return Err(CcrError::NetworkError("响应中没有生成内容".to_string()));
+        };
+
+        Ok(BenchSample {
+            ttft_ms: Some(ttft.as_millis() as u64),
+            // 未返回 usage 时以内容增量事件数估算
+            output_tokens: Some(self.output_tokens.filter(|t| *t > 0).unwrap_or(self.deltas)),
+            ..Default::default()
+        })
+    }
+}
+
+/// 🏆 排名：错误率低者优先，其次首 token 延迟、输出速度
+fn rank(mut results: Vec<BenchResult>) -> Vec<BenchResult> {
+    let ttft = |r: &BenchResult| r.ttft_ms.unwrap_or(u64::MAX);
+    let speed = |r: &BenchResult| r.tokens_per_sec.unwrap_or(0.0);
+
+    results.sort_by(|a, b| {
+        a.error_rate
+            .partial_cmp(&b.error_rate)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| ttft(a).cmp(&ttft(b)))
+            .then_with(|| speed(b).partial_cmp(&speed(a)).unwrap_or(Ordering::Equal))
+            .then_with(|| a.profile.cmp(&b.profile))
+    });
+    for (index, result) in results.iter_mut().enumerate() {
+        result.rank = index + 1;
+    }
+    results
+}
+
+fn median(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let mut values: Vec<f64> = values.collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn test_sse_accumulator_handles_split_events() {
+        let mut stream = SseAccumulator::default();
+        stream.feed(
+            b"event: message_start\r\ndata: {\"type\":\"message_start\",\"message\":{\"usage\":{\"output_tokens\":1}}}\r\n\r\nevent: content_block_delta\r\ndata: {\"type\":\"content_block_delta\",",
+            ms(10),
+        );
+        assert!(stream.ttft.is_none());
+        stream.feed(b"\"delta\":{\"text\":\"hi\"}}\n\n", ms(40));
+        stream.feed(
+            b"event: content_block_delta\ndata: {\"type\":\"content_block_delta\"}\n\nevent: message_delta\ndata: {\"type\":\"message_delta\",\"usage\":{\"output_tokens\":12}}\n\nevent: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+            ms(90),
+        );
+        assert!(stream.done);
+
+        let sample = stream.finish().unwrap();
+        assert_eq!(sample.ttft_ms, Some(40));
+        assert_eq!(sample.output_tokens, Some(12));
+
+        let mut failed = SseAccumulator::default();
+        failed.feed(
+            b"event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n",
+            ms(5),
+        );
+        assert!(
+            failed
+                .finish()
+                .unwrap_err()
+                .to_string()
+                .contains("Overloaded")
+        );
+        assert!(SseAccumulator::default().finish().is_err());
+    }
+
+    #[test]
+    fn test_results_ranked_by_error_rate_then_ttft() {
+        let ok = |ttft: u64| BenchSample {
+            ttft_ms: Some(ttft),
+            total_ms: ttft + 100,
+            output_tokens: Some(10),
+            tokens_per_sec: Some(100.0),
+            error: None,
+        };
+        let failed = BenchSample {
+            error: Some("HTTP 状态码: 502".into()),
+            ..Default::default()
+        };
+        let result = |name: &str, samples| {
+            BenchResult::from_samples(name.into(), String::new(), String::new(), samples)
+        };
+
+        let ranked = rank(vec![
+            result("flaky", vec![ok(50), failed.clone()]),
+            result("slow", vec![ok(300), ok(100), ok(200)]),
+            result("fast", vec![ok(80), ok(120)]),
+        ]);
+
+        let names: Vec<&str> = ranked.iter().map(|r| r.profile.as_str()).collect();
+        assert_eq!(names, ["fast", "slow", "flaky"]);
+        assert_eq!(ranked[0].rank, 1);
+        assert_eq!(ranked[0].ttft_ms, Some(100));
+        assert_eq!(ranked[1].ttft_ms, Some(200));
+        assert_eq!(ranked[2].error_rate, 50.0);
+        assert_eq!(ranked[2].last_error.as_deref(), Some("HTTP 状态码: 502"));
+    }
+}
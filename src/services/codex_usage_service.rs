@@ -123,7 +123,7 @@ impl CodexUsageService {
         }
 
         // 按时间排序
-        records.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        records.sort_by_key(|a| a.timestamp);
 
         Ok(records)
     }
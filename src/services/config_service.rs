@@ -6,6 +6,7 @@ use crate::core::lock::{CONFIG_LOCK, LockManager};
 use crate::managers::config::{CcsConfig, ConfigManager, ConfigSection};
 use crate::managers::config_validator::ConfigValidator;
 use crate::utils::Validatable;
+use indexmap::IndexMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -30,6 +31,60 @@ pub struct ConfigInfo {
     // === 🆕 使用统计和状态字段 ===
     pub usage_count: u32,
     pub enabled: bool,
+    // === 🧬 继承字段 ===
+    /// 继承的父配置节 (extends)
+    pub extends: Option<String>,
+    /// 是否为模板（只能被继承，不能切换）
+    pub is_template: bool,
+    /// 继承而来的字段 → 提供该值的配置节
+    pub inherited_from: IndexMap<String, String>,
+}
+
+impl ConfigInfo {
+    /// 🏗️ 从配置文件构建配置信息（展示继承解析后的有效值）
+    ///
+    /// 继承解析失败时回退为配置节自身的值，错误由验证流程报告
+    fn from_config(config: &CcsConfig, name: &str) -> Result<Self> {
+        let own = config.get_section(name)?;
+        let resolved = ConfigManager::resolve_section(config, name).ok();
+        let section = resolved.as_ref().map(|r| &r.section).unwrap_or(own);
+        let inherited_from = resolved
+            .as_ref()
+            .map(|r| {
+                r.inherited_fields()
+                    .map(|(field, origin)| (field.to_string(), origin.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            name: name.to_string(),
+            description: own.display_description().to_string(),
+            base_url: section.base_url.clone(),
+            auth_token: section.auth_token.clone(),
+            model: section.model.clone(),
+            small_fast_model: section.small_fast_model.clone(),
+            is_current: name == config.current_config,
+            is_default: name == config.default_config,
+            provider: section.provider.clone(),
+            provider_type: section
+                .provider_type
+                .as_ref()
+                .map(|t| t.to_string_value().to_string()),
+            account: section.account.clone(),
+            tags: section.tags.clone(),
+            usage_count: own.usage_count(),
+            enabled: own.is_enabled(),
+            extends: own.extends().map(str::to_string),
+            is_template: own.is_template(),
+            inherited_from,
+        })
+    }
+
+    /// 🧬 字段是否继承自父配置节，返回来源配置节
+    pub fn inherited_origin(&self, field: &str) -> Option<&str> {
+        self.inherited_from.get(field).map(String::as_str)
+    }
 }
 
 /// 📋 配置列表(用于展示)
@@ -103,30 +158,7 @@ impl ConfigService {
 
         let configs: Vec<ConfigInfo> = config
             .list_sections()
-            .filter_map(|name| {
-                config
-                    .get_section(name.as_str())
-                    .ok()
-                    .map(|section| ConfigInfo {
-                        name: name.clone(),
-                        description: section.display_description().to_string(),
-                        base_url: section.base_url.clone(),
-                        auth_token: section.auth_token.clone(),
-                        model: section.model.clone(),
-                        small_fast_model: section.small_fast_model.clone(),
-                        is_current: name == &config.current_config,
-                        is_default: name == &config.default_config,
-                        provider: section.provider.clone(),
-                        provider_type: section
-                            .provider_type
-                            .as_ref()
-                            .map(|t| t.to_string_value().to_string()),
-                        account: section.account.clone(),
-                        tags: section.tags.clone(),
-                        usage_count: section.usage_count(),
-                        enabled: section.is_enabled(),
-                    })
-            })
+            .filter_map(|name| ConfigInfo::from_config(&config, name).ok())
             .collect();
 
         Ok(ConfigList {
@@ -140,63 +172,20 @@ impl ConfigService {
     pub fn get_current(&self) -> Result<ConfigInfo> {
         let (_file_lock, _guard) = self.lock_config()?;
         let config = self.config_manager.load_with_autofix()?;
-        let section = config.get_current_section()?;
-
-        Ok(ConfigInfo {
-            name: config.current_config.clone(),
-            description: section.display_description().to_string(),
-            base_url: section.base_url.clone(),
-            auth_token: section.auth_token.clone(),
-            model: section.model.clone(),
-            small_fast_model: section.small_fast_model.clone(),
-            is_current: true,
-            is_default: config.current_config == config.default_config,
-            provider: section.provider.clone(),
-            provider_type: section
-                .provider_type
-                .as_ref()
-                .map(|t| t.to_string_value().to_string()),
-            account: section.account.clone(),
-            tags: section.tags.clone(),
-            usage_count: section.usage_count(),
-            enabled: section.is_enabled(),
-        })
+        ConfigInfo::from_config(&config, &config.current_config)
     }
 
     /// 🔍 获取指定配置信息
     pub fn get_config(&self, name: &str) -> Result<ConfigInfo> {
         let (_file_lock, _guard) = self.lock_config()?;
         let config = self.config_manager.load_with_autofix()?;
-        let section = config.get_section(name)?;
-
-        Ok(ConfigInfo {
-            name: name.to_string(),
-            description: section.display_description().to_string(),
-            base_url: section.base_url.clone(),
-            auth_token: section.auth_token.clone(),
-            model: section.model.clone(),
-            small_fast_model: section.small_fast_model.clone(),
-            is_current: name == config.current_config,
-            is_default: name == config.default_config,
-            provider: section.provider.clone(),
-            provider_type: section
-                .provider_type
-                .as_ref()
-                .map(|t| t.to_string_value().to_string()),
-            account: section.account.clone(),
-            tags: section.tags.clone(),
-            usage_count: section.usage_count(),
-            enabled: section.is_enabled(),
-        })
+        ConfigInfo::from_config(&config, name)
     }
 
     /// ➕ 添加新配置
     ///
     /// 🔐 **并发安全**: 使用跨进程锁 + CONFIG_LOCK 保护整个 RMW 序列
     pub fn add_config(&self, name: String, section: ConfigSection) -> Result<()> {
-        // 验证配置
-        section.validate()?;
-
         let (_file_lock, _guard) = self.lock_config()?;
         let mut config = self.config_manager.load_with_autofix()?;
 
@@ -205,7 +194,11 @@ impl ConfigService {
             return Err(CcrError::ConfigError(format!("配置 '{}' 已存在", name)));
         }
 
-        config.set_section(name, section);
+        config.set_section(name.clone(), section);
+
+        // 验证继承后的有效配置
+        Self::validate_resolved(&config, &name)?;
+
         self.config_manager.save(&config)?;
 
         Ok(())
@@ -220,9 +213,6 @@ impl ConfigService {
         new_name: String,
         section: ConfigSection,
     ) -> Result<()> {
-        // 验证配置
-        section.validate()?;
-
         let (_file_lock, _guard) = self.lock_config()?;
         let mut config = self.config_manager.load_with_autofix()?;
 
@@ -230,6 +220,11 @@ impl ConfigService {
         if old_name != new_name {
             config.remove_section(old_name)?;
 
+            // 子配置节改为继承新名称
+            for child in ConfigManager::children_of(&config, old_name) {
+                config.get_section_mut(&child)?.set_extends(Some(&new_name));
+            }
+
             // 更新引用
             if config.current_config == old_name {
                 config.current_config = new_name.clone();
@@ -239,7 +234,11 @@ impl ConfigService {
             }
         }
 
-        config.set_section(new_name, section);
+        config.set_section(new_name.clone(), section);
+
+        // 验证继承后的有效配置
+        Self::validate_resolved(&config, &new_name)?;
+
         self.config_manager.save(&config)?;
 
         Ok(())
@@ -260,6 +259,16 @@ impl ConfigService {
             return Err(CcrError::ValidationError("不能删除默认配置".into()));
         }
 
+        // 不允许删除仍被继承的配置
+        let children = ConfigManager::children_of(&config, name);
+        if !children.is_empty() {
+            return Err(CcrError::ValidationError(format!(
+                "配置 '{}' 被 {} 继承，请先修改它们的 extends",
+                name,
+                children.join(", ")
+            )));
+        }
+
         config.remove_section(name)?;
         self.config_manager.save(&config)?;
 
@@ -334,6 +343,15 @@ impl ConfigService {
         })
     }
 
+    /// ✅ 验证配置节继承后的有效配置（模板不要求字段完整）
+    fn validate_resolved(config: &CcsConfig, name: &str) -> Result<()> {
+        let resolved = ConfigManager::resolve_section(config, name)?;
+        if resolved.section.is_template() {
+            return Ok(());
+        }
+        resolved.section.validate()
+    }
+
     /// 📁 获取配置管理器
     pub fn config_manager(&self) -> &Arc<ConfigManager> {
         &self.config_manager
@@ -0,0 +1,705 @@
+// 🩺 环境诊断服务
+// 🔍 检查 ccr 与各 AI CLI 的运行环境，给出带严重级别的诊断结果与可选的自动修复
+//
+// 检查项:
+// - 🧰 CLI 工具: claude / codex / gemini / droid 是否在 PATH 中及其版本
+// - 🐚 Shell 环境变量: 导出的 ANTHROPIC_* / OPENAI_* 是否覆盖设置文件
+// - 🧭 设置漂移: settings.json 与当前 Claude profile 是否一致
+// - 🔐 文件权限: 含 token 的文件是否可被其他用户读取（仅 Unix）
+// - 🔒 锁文件: 长时间被持有的锁文件
+// - 🗂️ 配置模式: Unified / Legacy 配置与设置文件路径是否一致
+// - ⚔️ 跨平台冲突: ConflictChecker 的检查结果
+
+use crate::core::error::{CcrError, Result};
+use crate::core::lock::LockManager;
+use crate::managers::PlatformConfigManager;
+use crate::managers::conflict_checker::{ConflictChecker, ConflictSeverity};
+use crate::managers::settings::SettingsManager;
+use crate::managers::settings_bundle::{self, SettingsChange};
+use crate::managers::vault;
+use crate::models::{Platform, PlatformPaths};
+use crate::platforms::{ClaudePlatform, create_platform_by_name};
+use crate::services::{DriftService, SnapshotService};
+use crate::utils::mask_if_sensitive;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 🧰 需要检查的 CLI 工具: (平台, 可执行文件名)
+const CLI_TOOLS: [(Platform, &str); 4] = [
+    (Platform::Claude, "claude"),
+    (Platform::Codex, "codex"),
+    (Platform::Gemini, "gemini"),
+    (Platform::Droid, "droid"),
+];
+
+/// ⏱️ 获取 CLI 版本的超时时间
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 🔒 锁被持有超过该时间视为可能卡住
+const LOCK_HELD_WARNING: Duration = Duration::from_secs(10 * 60);
+
+/// 🚦 诊断结果的严重级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// 📋 检查项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    Cli,
+    ShellEnv,
+    Drift,
+    Permissions,
+    Locks,
+    Mode,
+    Conflicts,
+}
+
+impl CheckKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckKind::Cli => "CLI 工具",
+            CheckKind::ShellEnv => "Shell 环境变量",
+            CheckKind::Drift => "settings.json 漂移",
+            CheckKind::Permissions => "文件权限",
+            CheckKind::Locks => "锁文件",
+            CheckKind::Mode => "配置模式",
+            CheckKind::Conflicts => "跨平台冲突",
+        }
+    }
+}
+
+/// 🔧 可自动执行的修复
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FixAction {
+    /// 将文件权限收紧为 0600
+    RestrictPermissions { path: PathBuf },
+    /// 重新应用当前 profile
+    ReapplyProfile { platform: String, profile: String },
+}
+
+impl FixAction {
+    pub fn describe(&self) -> String {
+        match self {
+            FixAction::RestrictPermissions { path } => {
+                format!("将 {} 的权限设置为 600", path.display())
+            }
+            FixAction::ReapplyProfile { platform, profile } => {
+                format!("重新应用 {} 平台的 profile '{}'", platform, profile)
+            }
+        }
+    }
+}
+
+/// 📌 单条诊断结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub check: CheckKind,
+    pub severity: Severity,
+    pub title: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<FixAction>,
+}
+
+impl Finding {
+    fn new(check: CheckKind, severity: Severity, title: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity,
+            title: title.into(),
+            details: Vec::new(),
+            fix: None,
+        }
+    }
+
+    fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.details.push(detail.into());
+        self
+    }
+
+    fn fix(mut self, fix: FixAction) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// 🔧 修复执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FixOutcome {
+    #[serde(flatten)]
+    pub fix: FixAction,
+    pub success: bool,
+    pub message: String,
+}
+
+/// 📊 各级别的数量
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DoctorSummary {
+    pub ok: usize,
+    pub info: usize,
+    pub warning: usize,
+    pub error: usize,
+    pub fixable: usize,
+}
+
+/// 🩺 诊断报告
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub findings: Vec<Finding>,
+    pub summary: DoctorSummary,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<FixOutcome>,
+}
+
+impl DoctorReport {
+    fn new(findings: Vec<Finding>) -> Self {
+        let mut summary = DoctorSummary::default();
+        for finding in &findings {
+            match finding.severity {
+                Severity::Ok => summary.ok += 1,
+                Severity::Info => summary.info += 1,
+                Severity::Warning => summary.warning += 1,
+                Severity::Error => summary.error += 1,
+            }
+            if finding.fix.is_some() {
+                summary.fixable += 1;
+            }
+        }
+        Self {
+            findings,
+            summary,
+            fixes: Vec::new(),
+        }
+    }
+
+    /// 可自动修复的项
+    pub fn fixes(&self) -> Vec<FixAction> {
+        self.findings.iter().filter_map(|f| f.fix.clone()).collect()
+    }
+}
+
+/// 🩺 环境诊断服务
+pub struct DoctorService {
+    home: PathBuf,
+    ccr_root: PathBuf,
+    lock_manager: LockManager,
+}
+
+impl DoctorService {
+    pub fn new(home: PathBuf, ccr_root: PathBuf, lock_manager: LockManager) -> Self {
+        Self {
+            home,
+            ccr_root,
+            lock_manager,
+        }
+    }
+
+    /// 🏠 使用默认路径创建服务
+    pub fn with_default() -> Result<Self> {
+        let home =
+            dirs::home_dir().ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?;
+        Ok(Self::new(
+            home,
+            PlatformPaths::get_ccr_root()?,
+            LockManager::with_default_path()?,
+        ))
+    }
+
+    /// 🔍 执行所有检查
+    pub async fn run(&self) -> DoctorReport {
+        let mut findings = self.check_cli().await;
+        findings.extend(self.check_shell_env());
+        findings.extend(self.check_drift());
+        findings.extend(self.check_permissions());
+        findings.extend(self.check_locks());
+        findings.extend(self.check_mode());
+        findings.extend(self.check_conflicts());
+        DoctorReport::new(findings)
+    }
+
+    /// 🔧 执行一项修复
+    pub fn apply_fix(&self, fix: &FixAction) -> Result<String> {
+        match fix {
+            FixAction::RestrictPermissions { path } => restrict_permissions(path),
+            FixAction::ReapplyProfile { platform, profile } => {
+                DriftService::reapply(platform, profile, "doctor")?;
+                Ok("已重新应用".to_string())
+            }
+        }
+    }
+
+    // === 检查项 ===
+
+    /// 🧰 CLI 工具是否可用；已启用平台缺少 CLI 时为警告
+    async fn check_cli(&self) -> Vec<Finding> {
+        let enabled: Vec<String> = PlatformConfigManager::with_default()
+            .and_then(|manager| manager.load())
+            .map(|config| {
+                config
+                    .platforms
+                    .into_iter()
+                    .filter(|(_, entry)| entry.enabled)
+                    .map(|(name, _)| name)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut findings = Vec::new();
+        for (platform, binary) in CLI_TOOLS {
+            let finding = match find_executable(binary) {
+                Some(path) => {
+                    let version = cli_version(&path)
+                        .await
+                        .unwrap_or_else(|| "版本未知".to_string());
+                    Finding::new(
+                        CheckKind::Cli,
+                        Severity::Ok,
+                        format!("{} {}", binary, version),
+                    )
+                    .detail(path.display().to_string())
+                }
+                None if enabled.iter().any(|name| name == platform.short_name()) => Finding::new(
+                    CheckKind::Cli,
+                    Severity::Warning,
+                    format!("平台 {} 已启用，但 PATH 中找不到 {}", platform, binary),
+                ),
+                None => Finding::new(
+                    CheckKind::Cli,
+                    Severity::Info,
+                    format!("PATH 中没有 {}", binary),
+                ),
+            };
+            findings.push(finding);
+        }
+        findings
+    }
+
+    /// 🐚 shell 中导出的 ANTHROPIC_* / OPENAI_* 会覆盖设置文件中的值
+    fn check_shell_env(&self) -> Vec<Finding> {
+        let claude_env = SettingsManager::with_default()
+            .and_then(|manager| manager.load())
+            .map(|settings| settings.env)
+            .unwrap_or_default();
+        let codex_auth = read_json_strings(&self.home.join(".codex").join("auth.json"));
+
+        let mut vars: Vec<(String, String)> = std::env::vars()
+            .filter(|(name, _)| name.starts_with("ANTHROPIC_") || name.starts_with("OPENAI_"))
+            .collect();
+        vars.sort();
+
+        let mut findings = Vec::new();
+        for (name, value) in vars {
+            let (file, configured) = if name.starts_with("ANTHROPIC_") {
+                ("settings.json", claude_env.get(&name))
+            } else {
+                ("~/.codex/auth.json", codex_auth.get(&name))
+            };
+            let finding = match configured {
+                Some(configured) if *configured == value => continue,
+                Some(configured) => Finding::new(
+                    CheckKind::ShellEnv,
+                    Severity::Warning,
+                    format!("shell 中的 {} 覆盖了 {} 中的值", name, file),
+                )
+                .detail(format!("shell: {}", mask_if_sensitive(&name, &value)))
+                .detail(format!(
+                    "{}: {}",
+                    file,
+                    mask_if_sensitive(&name, configured)
+                )),
+                None => Finding::new(
+                    CheckKind::ShellEnv,
+                    Severity::Warning,
+                    format!("shell 中导出了 {}，{} 中没有该变量", name, file),
+                )
+                .detail(format!("shell: {}", mask_if_sensitive(&name, &value))),
+            };
+            findings.push(finding.detail(format!(
+                "运行 'unset {}'，或使用 'eval \"$(ccr env)\"' 导出当前 profile",
+                name
+            )));
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::new(
+                CheckKind::ShellEnv,
+                Severity::Ok,
+                "没有覆盖设置文件的 shell 环境变量",
+            ));
+        }
+        findings
+    }
+
+    /// 🧭 settings.json 是否与当前 Claude profile 一致
+    fn check_drift(&self) -> Vec<Finding> {
+        let current = create_platform_by_name("claude")
+            .and_then(|platform| platform.get_current_profile())
+            .ok()
+            .flatten();
+        let Some(profile) = current else {
+            return vec![Finding::new(
+                CheckKind::Drift,
+                Severity::Info,
+                "未设置当前 Claude profile，跳过",
+            )];
+        };
+
+        let drift = ClaudePlatform::new().and_then(|platform| platform.settings_drift(&profile));
+        let finding = match drift {
+            Ok(changes) if changes.is_empty() => Finding::new(
+                CheckKind::Drift,
+                Severity::Ok,
+                format!("settings.json 与 profile '{}' 一致", profile),
+            ),
+            Ok(changes) => {
+                let mut finding = Finding::new(
+                    CheckKind::Drift,
+                    Severity::Warning,
+                    format!(
+                        "settings.json 与 profile '{}' 不一致（{} 处）",
+                        profile,
+                        changes.len()
+                    ),
+                );
+                for mut change in changes {
+                    settings_bundle::mask_change(&mut change);
+                    finding = finding.detail(describe_change(&change));
+                }
+                finding.fix(FixAction::ReapplyProfile {
+                    platform: "claude".to_string(),
+                    profile,
+                })
+            }
+            Err(e) => Finding::new(
+                CheckKind::Drift,
+                Severity::Error,
+                format!("无法解析当前 profile '{}'", profile),
+            )
+            .detail(e.to_string()),
+        };
+        vec![finding]
+    }
+
+    /// 🔐 含 token 的文件不应被其他用户读取
+    #[cfg(unix)]
+    fn check_permissions(&self) -> Vec<Finding> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let registry = self.ccr_root.join("config.toml");
+        let files = SnapshotService::with_default()
+            .map(|service| service.tracked_files())
+            .unwrap_or_default();
+
+        let mut findings = Vec::new();
+        let mut checked = 0;
+        for path in files.into_iter().filter(|path| *path != registry) {
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            checked += 1;
+            let mode = metadata.permissions().mode() & 0o777;
+            if mode & 0o077 != 0 {
+                findings.push(
+                    Finding::new(
+                        CheckKind::Permissions,
+                        Severity::Warning,
+                        format!("{} 可被其他用户读取 ({:o})", path.display(), mode),
+                    )
+                    .fix(FixAction::RestrictPermissions { path }),
+                );
+            }
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::new(
+                CheckKind::Permissions,
+                Severity::Ok,
+                format!("{} 个含 token 的文件权限正常", checked),
+            ));
+        }
+        findings
+    }
+
+    #[cfg(not(unix))]
+    fn check_permissions(&self) -> Vec<Finding> {
+        vec![Finding::new(
+            CheckKind::Permissions,
+            Severity::Info,
+            "当前系统不检查文件权限",
+        )]
+    }
+
+    /// 🔒 长时间被持有的锁文件
+    ///
+    /// 锁文件释放后保留属正常现象，只报告不删除
+    fn check_locks(&self) -> Vec<Finding> {
+        let statuses = match self.lock_manager.inspect() {
+            Ok(statuses) => statuses,
+            Err(e) => {
+                return vec![
+                    Finding::new(CheckKind::Locks, Severity::Warning, "无法检查锁目录")
+                        .detail(e.to_string()),
+                ];
+            }
+        };
+
+        let mut findings = Vec::new();
+        for status in statuses {
+            let name = status
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if status.held && status.age > LOCK_HELD_WARNING {
+                findings.push(
+                    Finding::new(
+                        CheckKind::Locks,
+                        Severity::Warning,
+                        format!("锁 {} 已被持有 {} 分钟", name, status.age.as_secs() / 60),
+                    )
+                    .detail("可能有卡住的 ccr 进程，请检查后结束该进程"),
+                );
+            }
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::new(
+                CheckKind::Locks,
+                Severity::Ok,
+                format!("锁目录 {} 正常", self.lock_manager.lock_dir().display()),
+            ));
+        }
+        findings
+    }
+
+    /// 🗂️ Unified / Legacy 配置与设置文件路径是否一致
+    fn check_mode(&self) -> Vec<Finding> {
+        let unified = self.ccr_root.join("config.toml");
+        let legacy = vault::legacy_config_path().filter(|path| path.exists());
+
+        let mut findings = Vec::new();
+        match (unified.exists(), &legacy) {
+            (false, Some(legacy)) => findings.push(
+                Finding::new(
+                    CheckKind::Mode,
+                    Severity::Error,
+                    format!("只找到旧版配置 {}", legacy.display()),
+                )
+                .detail("运行 'ccr init' 创建 Unified 配置并迁移"),
+            ),
+            (false, None) => findings.push(
+                Finding::new(
+                    CheckKind::Mode,
+                    Severity::Error,
+                    format!("未找到 {}", unified.display()),
+                )
+                .detail("运行 'ccr init' 初始化配置"),
+            ),
+            (true, Some(legacy)) => findings.push(
+                Finding::new(
+                    CheckKind::Mode,
+                    Severity::Warning,
+                    format!("旧版配置 {} 仍然存在", legacy.display()),
+                )
+                .detail("Unified 模式下不再读取该文件，确认已迁移后可以删除"),
+            ),
+            (true, None) => {}
+        }
+
+        if let Ok(manager) = SettingsManager::with_default() {
+            let path = manager.settings_path().display().to_string();
+            if manager.detect_mode() == "Unified" {
+                findings.push(
+                    Finding::new(
+                        CheckKind::Mode,
+                        Severity::Warning,
+                        format!("设置文件位于平台配置目录: {}", path),
+                    )
+                    .detail("Claude Code 读取 ~/.claude/settings.json，请检查 CCR_SETTINGS_PATH"),
+                );
+            } else if std::env::var("CCR_SETTINGS_PATH").is_ok() {
+                findings.push(Finding::new(
+                    CheckKind::Mode,
+                    Severity::Info,
+                    format!("CCR_SETTINGS_PATH 指定设置文件: {}", path),
+                ));
+            }
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::new(
+                CheckKind::Mode,
+                Severity::Ok,
+                format!("Unified 模式 ({})", self.ccr_root.display()),
+            ));
+        }
+        findings
+    }
+
+    /// ⚔️ 跨平台环境变量冲突
+    fn check_conflicts(&self) -> Vec<Finding> {
+        let report = match ConflictChecker::new().check_conflicts() {
+            Ok(report) => report,
+            Err(e) => {
+                return vec![
+                    Finding::new(CheckKind::Conflicts, Severity::Warning, "冲突检查失败")
+                        .detail(e.to_string()),
+                ];
+            }
+        };
+
+        let mut findings: Vec<Finding> = report
+            .warnings
+            .into_iter()
+            .map(|warning| Finding::new(CheckKind::Conflicts, Severity::Warning, warning))
+            .collect();
+        for conflict in report.conflicts {
+            let severity = match conflict.severity {
+                ConflictSeverity::Critical => Severity::Error,
+                ConflictSeverity::Warning => Severity::Warning,
+                ConflictSeverity::Info => Severity::Info,
+            };
+            let mut finding = Finding::new(
+                CheckKind::Conflicts,
+                severity,
+                format!("{} 在多个平台中设置", conflict.key),
+            );
+            for value in &conflict.platforms {
+                finding = finding.detail(format!(
+                    "{}: {}",
+                    value.platform,
+                    mask_if_sensitive(&conflict.key.to_uppercase(), &value.value)
+                ));
+            }
+            findings.push(finding.detail(conflict.suggestion));
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::new(
+                CheckKind::Conflicts,
+                Severity::Ok,
+                "未发现跨平台冲突",
+            ));
+        }
+        findings
+    }
+}
+
+/// 🔍 在 PATH 中查找可执行文件
+fn find_executable(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    let candidates: Vec<String> = if cfg!(windows) {
+        ["exe", "cmd", "bat"]
+            .iter()
+            .map(|ext| format!("{}.{}", name, ext))
+            .collect()
+    } else {
+        vec![name.to_string()]
+    };
+    std::env::split_paths(&path)
+        .flat_map(|dir| candidates.iter().map(move |c| dir.join(c)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// 🏷️ 运行 `<cli> --version`，取输出的第一行
+async fn cli_version(path: &Path) -> Option<String> {
+    let output = tokio::time::timeout(
+        VERSION_TIMEOUT,
+        tokio::process::Command::new(path)
+            .arg("--version")
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// 📖 读取 JSON 对象中的字符串字段（文件不存在或无法解析时为空）
+fn read_json_strings(path: &Path) -> HashMap<String, String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|value| value.as_object().cloned())
+        .map(|object| {
+            object
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, value.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn describe_change(change: &SettingsChange) -> String {
+    let render = |value: &Option<serde_json::Value>| {
+        value
+            .as_ref()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "(未设置)".to_string())
+    };
+    format!(
+        "{}: {} → {}",
+        change.path,
+        render(&change.old),
+        render(&change.new)
+    )
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| CcrError::FileIoError(format!("修改 {} 权限失败: {}", path.display(), e)))?;
+    Ok("权限已设置为 600".to_string())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> Result<String> {
+    Ok("当前系统不支持，已跳过".to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_summary_counts_fixable() {
+        let report = DoctorReport::new(vec![
+            Finding::new(CheckKind::Cli, Severity::Ok, "claude 1.0"),
+            Finding::new(CheckKind::Permissions, Severity::Info, "权限过宽").fix(
+                FixAction::RestrictPermissions {
+                    path: PathBuf::from("/tmp/settings.json"),
+                },
+            ),
+            Finding::new(CheckKind::Mode, Severity::Error, "未找到配置"),
+        ]);
+        assert_eq!(report.summary.ok, 1);
+        assert_eq!(report.summary.info, 1);
+        assert_eq!(report.summary.error, 1);
+        assert_eq!(report.summary.fixable, 1);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["findings"][1]["check"], "permissions");
+        assert_eq!(json["findings"][1]["severity"], "info");
+        assert_eq!(json["findings"][1]["fix"]["action"], "restrict_permissions");
+        assert!(json["findings"][0].get("fix").is_none());
+    }
+}
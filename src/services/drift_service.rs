@@ -0,0 +1,268 @@
+// 🧭 设置漂移检测服务
+// 🔍 比较各平台设置文件中的当前取值与当前 profile 应用后应有的取值
+//
+// 在 Claude Code 中运行 /model、手动编辑 settings.json 等操作会让设置文件与
+// current_config 不再一致。本服务逐项列出差异，并支持两种协调方式:
+// - 🔁 重新应用 profile（以 profile 为准）
+// - 📥 采纳当前值写回 profile（以设置文件为准）
+
+use crate::core::error::{CcrError, Result};
+use crate::core::journal::Journal;
+use crate::managers::PlatformConfigManager;
+use crate::managers::secret_source;
+use crate::models::PlatformPaths;
+use crate::platforms::{base, create_platform_by_name};
+use crate::utils::mask_if_sensitive;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 📌 单个设置项的漂移
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriftEntry {
+    /// 设置项（环境变量名或设置文件键路径）
+    pub key: String,
+    /// profile 应用后应有的值
+    pub expected: Option<String>,
+    /// 设置文件中的当前值
+    pub live: Option<String>,
+}
+
+impl DriftEntry {
+    /// 🙈 敏感值脱敏后的副本
+    pub fn masked(&self) -> Self {
+        let name = self.key.to_uppercase();
+        let mask = |value: &Option<String>| value.as_ref().map(|v| mask_if_sensitive(&name, v));
+        Self {
+            key: self.key.clone(),
+            expected: mask(&self.expected),
+            live: mask(&self.live),
+        }
+    }
+}
+
+/// 🧭 单个平台的漂移检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformDrift {
+    pub platform: String,
+    /// 当前 profile（未设置时为 None，不做检测）
+    pub profile: Option<String>,
+    /// 平台是否支持漂移检测
+    pub supported: bool,
+    pub entries: Vec<DriftEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl PlatformDrift {
+    fn empty(platform: &str, profile: Option<String>) -> Self {
+        Self {
+            platform: platform.to_string(),
+            profile,
+            supported: true,
+            entries: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn has_drift(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    /// 🙈 敏感值脱敏后的副本
+    pub fn masked(&self) -> Self {
+        Self {
+            entries: self.entries.iter().map(DriftEntry::masked).collect(),
+            ..self.clone()
+        }
+    }
+}
+
+/// 🧭 设置漂移检测服务
+pub struct DriftService {
+    registry: PlatformConfigManager,
+}
+
+impl DriftService {
+    pub fn new(registry: PlatformConfigManager) -> Self {
+        Self { registry }
+    }
+
+    /// 🏠 使用默认注册表创建服务
+    pub fn with_default() -> Result<Self> {
+        Ok(Self::new(PlatformConfigManager::with_default()?))
+    }
+
+    /// 📋 注册表中已启用的平台
+    pub fn enabled_platforms(&self) -> Result<Vec<String>> {
+        Ok(self
+            .registry
+            .load()?
+            .platforms
+            .into_iter()
+            .filter(|(_, entry)| entry.enabled)
+            .map(|(name, _)| name)
+            .collect())
+    }
+
+    /// 🔍 检测所有已启用平台，单个平台失败时记录在结果的 error 中
+    pub fn detect_all(&self) -> Result<Vec<PlatformDrift>> {
+        Ok(self
+            .enabled_platforms()?
+            .iter()
+            .map(|platform| {
+                Self::detect(platform).unwrap_or_else(|e| PlatformDrift {
+                    error: Some(e.to_string()),
+                    ..PlatformDrift::empty(platform, None)
+                })
+            })
+            .collect())
+    }
+
+    /// 🔍 检测单个平台当前 profile 的漂移
+    ///
+    /// 不解析动态密钥：预期值为间接形式（保险库引用、`${env:..}` 等）的设置项只检查是否存在
+    pub fn detect(platform: &str) -> Result<PlatformDrift> {
+        let platform_config = create_platform_by_name(platform)?;
+        let Some(profile_name) = platform_config.get_current_profile()? else {
+            return Ok(PlatformDrift::empty(platform, None));
+        };
+
+        let profiles = platform_config.load_profiles()?;
+        let mut profile = base::resolve_profile(&profiles, &profile_name)?;
+        profile.auth_token = base::effective_auth_token(&profile);
+
+        let mut drift = PlatformDrift::empty(platform, Some(profile_name));
+        let Some(live) = platform_config.live_values(&profile)? else {
+            drift.supported = false;
+            return Ok(drift);
+        };
+        drift.entries = compare(&platform_config.expected_values(&profile)?, &live);
+        Ok(drift)
+    }
+
+    /// 🔁 重新应用 profile，使设置文件与 profile 一致
+    ///
+    /// 先创建配置快照，再在事务日志中写入设置文件与注册表
+    pub fn reapply(platform: &str, profile: &str, reason: &str) -> Result<()> {
+        crate::services::auto_snapshot(reason);
+        let platform_config = create_platform_by_name(platform)?;
+        let paths = PlatformPaths::for_name(platform)?;
+        let mut journal_paths = platform_config.settings_files();
+        journal_paths.extend([paths.profiles_file, paths.registry_file]);
+
+        let transaction = Journal::with_default()?.begin(reason, &journal_paths)?;
+        platform_config.apply_profile(profile)?;
+        transaction.commit()
+    }
+
+    /// 📥 将设置文件中的当前值写回当前 profile，返回写回后仍存在的漂移
+    ///
+    /// 值写入 profile 自身（覆盖继承的值）；间接形式的字段保留原写法，
+    /// 继承而来的值被移除等无法采纳的差异保留在返回结果中
+    pub fn adopt(platform: &str) -> Result<Vec<DriftEntry>> {
+        let drift = Self::detect(platform)?;
+        let Some(profile_name) = drift.profile.clone() else {
+            return Err(CcrError::ConfigError(format!(
+                "平台 {} 未设置当前 profile",
+                platform
+            )));
+        };
+        if !drift.has_drift() {
+            return Ok(Vec::new());
+        }
+
+        let platform_config = create_platform_by_name(platform)?;
+        let mut profile = platform_config
+            .load_profiles()?
+            .shift_remove(&profile_name)
+            .ok_or_else(|| CcrError::ProfileNotFound(profile_name.clone()))?;
+
+        let mut adopted = false;
+        for entry in &drift.entries {
+            adopted |= platform_config.adopt_value(&mut profile, &entry.key, entry.live.as_deref());
+        }
+
+        if adopted {
+            crate::services::auto_snapshot("reconcile");
+            let journal_paths: Vec<PathBuf> =
+                vec![PlatformPaths::for_name(platform)?.profiles_file];
+            let transaction = Journal::with_default()?.begin("reconcile", &journal_paths)?;
+            platform_config.save_profile(&profile_name, &profile)?;
+            transaction.commit()?;
+        }
+
+        Ok(Self::detect(platform)?.entries)
+    }
+}
+
+/// ⚖️ 逐项比较预期值与当前值（按预期值的顺序，之后是仅存在于设置文件中的项）
+fn compare(
+    expected: &IndexMap<String, String>,
+    live: &IndexMap<String, String>,
+) -> Vec<DriftEntry> {
+    let keys = expected
+        .keys()
+        .chain(live.keys().filter(|key| !expected.contains_key(*key)));
+
+    keys.filter_map(|key| {
+        let expected_value = expected.get(key);
+        let live_value = live.get(key);
+        let matches = match (expected_value, live_value) {
+            (Some(e), Some(_)) if secret_source::is_indirect(e) => true,
+            (e, l) => e == l,
+        };
+        (!matches).then(|| DriftEntry {
+            key: key.clone(),
+            expected: expected_value.cloned(),
+            live: live_value.cloned(),
+        })
+    })
+    .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, &str)]) -> IndexMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_compare_reports_changed_missing_and_extra_keys() {
+        let expected = map(&[
+            ("ANTHROPIC_BASE_URL", "https://alpha.example.com"),
+            ("ANTHROPIC_AUTH_TOKEN", "vault:alpha"),
+            ("ANTHROPIC_MODEL", "claude-sonnet"),
+        ]);
+        let live = map(&[
+            ("ANTHROPIC_BASE_URL", "https://alpha.example.com"),
+            ("ANTHROPIC_AUTH_TOKEN", "sk-resolved-123456"),
+            ("ANTHROPIC_SMALL_FAST_MODEL", "claude-haiku"),
+        ]);
+
+        let entries = compare(&expected, &live);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key, "ANTHROPIC_MODEL");
+        assert_eq!(entries[0].live, None);
+        assert_eq!(entries[1].key, "ANTHROPIC_SMALL_FAST_MODEL");
+        assert_eq!(entries[1].expected, None);
+
+        // 间接形式的凭证被移除时仍然报告
+        let entries = compare(&expected, &map(&[]));
+        assert!(entries.iter().any(|e| e.key == "ANTHROPIC_AUTH_TOKEN"));
+
+        let masked = DriftEntry {
+            key: "apiKey".into(),
+            expected: Some("sk-expected-123456".into()),
+            live: None,
+        }
+        .masked();
+        assert_ne!(masked.expected.as_deref(), Some("sk-expected-123456"));
+    }
+}
@@ -10,6 +10,7 @@ use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 /// 🏥 健康检查服务
+#[derive(Clone)]
 pub struct HealthCheckService {
     timeout: Duration,
 }
@@ -35,6 +36,7 @@ pub struct HealthCheckResult {
 
 /// 🚦 健康状态
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum HealthStatus {
     /// ✅ 健康
     Healthy,
@@ -67,6 +69,31 @@ impl HealthStatus {
             HealthStatus::Unknown => "gray",
         }
     }
+
+    /// 获取存储与 API 使用的字符串值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Degraded => "degraded",
+            HealthStatus::Unhealthy => "unhealthy",
+            HealthStatus::Unknown => "unknown",
+        }
+    }
+
+    /// 从字符串值解析，无法识别时返回 Unknown
+    pub fn from_str_safe(value: &str) -> Self {
+        match value {
+            "healthy" => HealthStatus::Healthy,
+            "degraded" => HealthStatus::Degraded,
+            "unhealthy" => HealthStatus::Unhealthy,
+            _ => HealthStatus::Unknown,
+        }
+    }
+
+    /// 端点是否可用（降级仍计为可用）
+    pub fn is_up(&self) -> bool {
+        matches!(self, HealthStatus::Healthy | HealthStatus::Degraded)
+    }
 }
 
 impl Default for HealthCheckService {
@@ -92,12 +119,21 @@ impl HealthCheckService {
 
     /// 测试单个 Provider
     pub async fn check(&self, name: &str, config: &ConfigSection) -> HealthCheckResult {
-        let base_url = config
+        // 🔑 解析保险库引用、${env:..} / ${file:..} 与 auth_token_cmd
+        let resolved = config.with_resolved_secrets().unwrap_or_else(|e| {
+            debug!("Provider {} 的密钥无法解析: {}", name, e);
+            ConfigSection {
+                auth_token: None,
+                ..config.clone()
+            }
+        });
+
+        let base_url = resolved
             .base_url
             .clone()
             .unwrap_or_else(|| "https://api.anthropic.com".to_string());
 
-        let api_key = config.auth_token.clone().unwrap_or_else(|| {
+        let api_key = resolved.auth_token.clone().unwrap_or_else(|| {
             debug!("Provider {} 未配置 API Key", name);
             String::new()
         });
@@ -279,4 +315,18 @@ mod tests {
         assert_eq!(HealthStatus::Healthy.display(), "✅ 健康");
         assert_eq!(HealthStatus::Unhealthy.display(), "❌ 不健康");
     }
+
+    #[test]
+    fn test_health_status_str_roundtrip() {
+        for status in [
+            HealthStatus::Healthy,
+            HealthStatus::Degraded,
+            HealthStatus::Unhealthy,
+            HealthStatus::Unknown,
+        ] {
+            assert_eq!(HealthStatus::from_str_safe(status.as_str()), status);
+        }
+        assert!(HealthStatus::Degraded.is_up());
+        assert!(!HealthStatus::Unhealthy.is_up());
+    }
 }
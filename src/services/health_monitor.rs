@@ -0,0 +1,212 @@
+//! 📡 Provider 健康监控服务
+//!
+//! 定期并发探测当前平台所有启用的 profile，将结果写入 SQLite，
+//! 并基于历史数据汇总 p50/p95 延迟、24h/7d 可用率与最近错误。
+
+use crate::core::error::{CcrError, Result};
+use crate::managers::PlatformConfigManager;
+use crate::managers::config::{ConfigManager, ConfigSection};
+use crate::services::ConfigService;
+use crate::services::health_check::{HealthCheckResult, HealthCheckService};
+use crate::storage::Database;
+use crate::storage::health_store::{
+    HealthSample, HealthStore, ProviderHealthStats, StatusTransition,
+};
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+/// 🗑️ 健康检查历史保留天数
+pub const HEALTH_RETENTION_DAYS: i64 = 30;
+
+/// 📜 单个 profile 的健康历史
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealthHistory {
+    pub stats: ProviderHealthStats,
+    /// 最近的样本（按时间降序）
+    pub samples: Vec<HealthSample>,
+    /// 最近的状态变化（按时间降序）
+    pub transitions: Vec<StatusTransition>,
+}
+
+/// 📡 一次探测的结果
+#[derive(Debug, Clone)]
+pub struct ProbeOutcome {
+    pub result: HealthCheckResult,
+    /// 本次探测引起的状态变化
+    pub transition: Option<StatusTransition>,
+}
+
+/// 📡 Provider 健康监控服务
+pub struct HealthMonitorService {
+    db: Database,
+    checker: HealthCheckService,
+}
+
+impl HealthMonitorService {
+    pub fn new(db: Database, checker: HealthCheckService) -> Self {
+        Self { db, checker }
+    }
+
+    /// 🏠 使用默认数据库 (`~/.ccr/data.db`) 创建服务
+    pub fn with_default() -> Result<Self> {
+        Ok(Self::new(
+            Database::init_default()?,
+            HealthCheckService::new(),
+        ))
+    }
+
+    /// 🎯 当前平台及其启用的 profile（已展开继承，跳过模板和禁用的 profile）
+    pub fn targets() -> Result<(String, Vec<(String, ConfigSection)>)> {
+        let platform = current_platform()?;
+        let config = ConfigService::with_default()?.load_config()?;
+
+        let mut targets = Vec::new();
+        for name in config.list_sections() {
+            let section = ConfigManager::resolve_section(&config, name)?.section;
+            if section.is_template() || section.enabled == Some(false) {
+                continue;
+            }
+            targets.push((name.clone(), section));
+        }
+        Ok((platform, targets))
+    }
+
+    /// 🏷️ 当前平台启用的 profile，可按标签筛选；没有匹配的 profile 时返回错误
+    pub fn tagged_targets(tag: Option<&str>) -> Result<Vec<(String, ConfigSection)>> {
+        let (_, mut targets) = Self::targets()?;
+        if let Some(tag) = tag {
+            targets.retain(|(_, section)| section.has_tag(tag));
+        }
+        if targets.is_empty() {
+            return Err(CcrError::ValidationError(match tag {
+                Some(tag) => format!("没有带标签 '{}' 的启用 profile", tag),
+                None => "没有启用的 profile".to_string(),
+            }));
+        }
+        Ok(targets)
+    }
+
+    /// 🔍 并发探测所有启用的 profile 并记录结果（按 profile 顺序返回）
+    pub async fn probe_all(&self) -> Result<Vec<ProbeOutcome>> {
+        let (platform, targets) = Self::targets()?;
+
+        let mut tasks = JoinSet::new();
+        for (index, (name, section)) in targets.into_iter().enumerate() {
+            let checker = self.checker.clone();
+            tasks.spawn(async move { (index, checker.check(&name, &section).await) });
+        }
+
+        let mut results = Vec::with_capacity(tasks.len());
+        while let Some(joined) = tasks.join_next().await {
+            results.push(
+                joined.map_err(|e| CcrError::NetworkError(format!("健康检查任务失败: {}", e)))?,
+            );
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        results
+            .into_iter()
+            .map(|(_, result)| {
+                let transition = self.record_for(&platform, &result)?;
+                Ok(ProbeOutcome { result, transition })
+            })
+            .collect()
+    }
+
+    /// 💾 记录当前平台的一次探测结果（如 `ccr provider test` 的单次检查）
+    pub fn record(&self, result: &HealthCheckResult) -> Result<Option<StatusTransition>> {
+        self.record_for(&current_platform()?, result)
+    }
+
+    fn record_for(
+        &self,
+        platform: &str,
+        result: &HealthCheckResult,
+    ) -> Result<Option<StatusTransition>> {
+        HealthStore::new(&self.db).record(&HealthSample {
+            platform: platform.to_string(),
+            profile: result.provider_name.clone(),
+            checked_at: Utc::now(),
+            status: result.status.clone(),
+            latency_ms: result.latency_ms,
+            error: result.error.clone(),
+        })
+    }
+
+    /// 🗑️ 清理超过保留期的健康历史
+    pub fn prune(&self) -> Result<usize> {
+        HealthStore::new(&self.db).prune(Utc::now() - ChronoDuration::days(HEALTH_RETENTION_DAYS))
+    }
+
+    /// 🔁 按固定间隔持续探测直到 Ctrl+C
+    ///
+    /// 每轮探测后清理超过保留期的历史；单轮失败（如配置文件正在被修改）不终止监控，
+    /// 结果和错误都交给 `on_round` 输出
+    pub async fn run(
+        &self,
+        interval: Duration,
+        mut on_round: impl FnMut(&Result<Vec<ProbeOutcome>>),
+    ) -> Result<()> {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = tokio::signal::ctrl_c() => {
+                    info!("收到中断信号，停止健康监控");
+                    return Ok(());
+                }
+            }
+
+            let outcomes = self.probe_all().await;
+            if let Err(e) = &outcomes {
+                warn!("健康检查失败: {}", e);
+            }
+            on_round(&outcomes);
+            self.prune()?;
+        }
+    }
+
+    /// 📊 当前平台所有启用 profile 的健康统计（包括尚无样本的 profile）
+    pub fn status(&self) -> Result<Vec<ProviderHealthStats>> {
+        let (platform, targets) = Self::targets()?;
+        let store = HealthStore::new(&self.db);
+        let now = Utc::now();
+        targets
+            .iter()
+            .map(|(name, _)| store.stats(&platform, name, now))
+            .collect()
+    }
+
+    /// 📜 当前平台单个 profile 的健康历史（模板没有健康记录，与 targets 一致视为不存在）
+    pub fn history(&self, profile: &str, limit: usize) -> Result<ProviderHealthHistory> {
+        let config = ConfigService::with_default()?.load_config()?;
+        if ConfigManager::resolve_section(&config, profile)?
+            .section
+            .is_template()
+        {
+            return Err(CcrError::ValidationError(format!(
+                "配置 '{}' 是模板，没有健康记录",
+                profile
+            )));
+        }
+        let platform = current_platform()?;
+        let store = HealthStore::new(&self.db);
+        Ok(ProviderHealthHistory {
+            stats: store.stats(&platform, profile, Utc::now())?,
+            samples: store.recent_samples(&platform, profile, limit)?,
+            transitions: store.transitions(&platform, profile, limit)?,
+        })
+    }
+}
+
+/// 🔄 注册表中的当前平台
+fn current_platform() -> Result<String> {
+    Ok(PlatformConfigManager::with_default()?
+        .load()?
+        .current_platform)
+}
@@ -2,7 +2,11 @@
 // 封装历史记录相关的业务逻辑
 
 use crate::core::error::Result;
-use crate::managers::history::{HistoryEntry, HistoryManager, HistoryStats, OperationType};
+use crate::managers::PlatformConfigManager;
+use crate::managers::history::{
+    HistoryEntry, HistoryManager, HistoryStats, OperationDetails, OperationResult, OperationType,
+};
+use std::path::Path;
 use std::sync::Arc;
 
 /// 📚 历史记录服务
@@ -44,6 +48,38 @@ impl HistoryService {
         self.history_manager.add_async(entry).await
     }
 
+    /// 📝 记录 profile 变更（添加 / 删除 / 启用 / 禁用），供 `ccr undo` 撤销
+    ///
+    /// 平台取当前平台；删除操作需传入删除前的配置备份
+    pub async fn record_profile_change_async(
+        &self,
+        operation: OperationType,
+        name: &str,
+        backup_path: Option<&Path>,
+    ) -> Result<()> {
+        let platform = PlatformConfigManager::with_default()?
+            .load()?
+            .current_platform;
+        let (from_config, to_config) = if operation == OperationType::Delete {
+            (Some(name.to_string()), None)
+        } else {
+            (None, Some(name.to_string()))
+        };
+
+        let mut entry = HistoryEntry::new(
+            operation,
+            OperationDetails {
+                from_config,
+                to_config,
+                backup_path: backup_path.map(|p| p.display().to_string()),
+                extra: None,
+            },
+            OperationResult::Success,
+        );
+        entry.platform = Some(platform);
+        self.history_manager.add_async(entry).await
+    }
+
     /// 📋 获取最近的记录
     ///
     /// # Arguments
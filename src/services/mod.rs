@@ -7,34 +7,57 @@
 // - 📝 提供事务性操作(备份+修改+历史记录)
 // - ✅ 统一错误处理和验证
 
+pub mod auto_select_service;
 pub mod backup_service;
+pub mod bench_service;
 pub mod codex_auth_service;
 pub mod codex_usage_service;
 pub mod config_service;
+pub mod doctor_service;
+pub mod drift_service;
 pub mod health_check;
+pub mod health_monitor;
 pub mod history_service;
 pub mod multi_backup_service;
+pub mod revert_service;
 pub mod settings_service;
+pub mod snapshot_service;
 pub mod sync_service;
 pub mod ui_service;
 pub mod validate_service;
 
 // Service 层为将来扩展准备,部分功能暂未在命令层使用
 #[allow(unused_imports)]
+pub use auto_select_service::{AutoSelectService, AutoSelection, SelectPolicy};
+#[allow(unused_imports)]
 pub use backup_service::BackupService;
 #[allow(unused_imports)]
+pub use bench_service::{BenchResult, BenchService};
+#[allow(unused_imports)]
 pub use codex_auth_service::CodexAuthService;
 #[allow(unused_imports)]
 pub use codex_usage_service::{CodexRollingUsage, CodexUsageService};
 #[allow(unused_imports)]
 pub use config_service::ConfigService;
 #[allow(unused_imports)]
+pub use doctor_service::{DoctorReport, DoctorService, FixAction, Severity};
+#[allow(unused_imports)]
+pub use drift_service::{DriftEntry, DriftService, PlatformDrift};
+#[allow(unused_imports)]
+pub use health_monitor::HealthMonitorService;
+#[allow(unused_imports)]
 pub use history_service::HistoryService;
 #[allow(unused_imports)]
-pub use multi_backup_service::MultiBackupService;
+pub use multi_backup_service::{
+    MultiBackupService, RestoreFileStatus, RestorePlan, is_backup_timestamp,
+};
+#[allow(unused_imports)]
+pub use revert_service::{RevertAction, RevertPlan, RevertService};
 #[allow(unused_imports)]
 pub use settings_service::SettingsService;
 #[allow(unused_imports)]
+pub use snapshot_service::{FileDiff, RestoreResult, SnapshotService, auto_snapshot, diff_file};
+#[allow(unused_imports)]
 pub use sync_service::SyncService;
 #[allow(unused_imports)]
 pub use ui_service::UiService;
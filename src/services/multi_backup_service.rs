@@ -1,15 +1,25 @@
 // 📦 多类型增量备份服务
 // 负责将 CCR 配置和各平台 CLI 配置按统一结构进行备份，并支持增量与并发安全
+//
+// 增量备份只复制发生变化的来源，因此某个时间戳的完整状态由
+// 每个来源在该时间戳之前（含）最近一次的备份组成
 
 #![allow(dead_code)]
 
+use crate::core::atomic_writer::AtomicWriter;
 use crate::core::error::{CcrError, Result};
+use crate::core::journal::Journal;
 use crate::core::lock::LockManager;
+use chrono::Timelike;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// 备份时间戳格式（用作目录名与备份文件名后缀）
+const TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
 /// 备份摘要条目
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupItemSummary {
@@ -22,9 +32,73 @@ pub struct BackupItemSummary {
 /// 备份运行摘要
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupSummary {
+    /// 本次备份的时间戳
+    #[serde(default)]
+    pub timestamp: String,
     pub items: Vec<BackupItemSummary>,
 }
 
+/// 一次备份（时间戳）及其中实际复制的来源
+#[derive(Debug, Clone)]
+pub struct BackupPoint {
+    pub timestamp: String,
+    pub sources: Vec<String>,
+}
+
+/// 恢复时单个文件的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreFileStatus {
+    /// 当前文件不存在，恢复时创建
+    Added,
+    /// 当前文件与备份不同，恢复时覆盖
+    Modified,
+    /// 与备份相同，无需写入
+    Unchanged,
+}
+
+/// 待恢复的单个文件
+#[derive(Debug, Clone)]
+pub struct RestoreFile {
+    pub backup_path: PathBuf,
+    pub target_path: PathBuf,
+    pub status: RestoreFileStatus,
+}
+
+/// 待恢复的单个来源
+#[derive(Debug, Clone)]
+pub struct RestoreSource {
+    pub name: String,
+    /// 实际使用的备份时间戳（未变化的来源取更早的备份）
+    pub timestamp: String,
+    pub target_path: PathBuf,
+    pub files: Vec<RestoreFile>,
+}
+
+/// 恢复计划
+#[derive(Debug, Clone)]
+pub struct RestorePlan {
+    pub timestamp: String,
+    pub sources: Vec<RestoreSource>,
+}
+
+impl RestorePlan {
+    /// 需要写入的文件（新增或已修改）
+    pub fn changed_files(&self) -> impl Iterator<Item = &RestoreFile> {
+        self.sources
+            .iter()
+            .flat_map(|source| &source.files)
+            .filter(|file| file.status != RestoreFileStatus::Unchanged)
+    }
+}
+
+/// 恢复结果
+#[derive(Debug, Clone)]
+pub struct RestoreOutcome {
+    /// 恢复前状态所在的备份时间戳（可再次恢复）
+    pub safety_timestamp: Option<String>,
+    pub restored: Vec<PathBuf>,
+}
+
 /// Manifest 记录源路径到最后一次备份的摘要（digest）
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 struct BackupManifest {
@@ -90,9 +164,11 @@ impl BackupManifest {
 /// 多类型增量备份服务
 pub struct MultiBackupService {
     ccr_root: PathBuf,         // ~/.ccr
+    home: PathBuf,             // 平台 CLI 配置目录所在的主目录
     backup_root: PathBuf,      // ~/.ccr/backups
     manifest_path: PathBuf,    // ~/.ccr/backups/multi_manifest.json
     lock_manager: LockManager, // 备份区域的锁目录
+    journal: Journal,          // 恢复时使用的事务日志
 }
 
 impl MultiBackupService {
@@ -100,34 +176,38 @@ impl MultiBackupService {
     ///
     /// 备份根目录为 CCR_ROOT/backups 或 ~/.ccr/backups
     pub fn with_default() -> Result<Self> {
-        let ccr_root = Self::detect_ccr_root()?;
-        let backup_root = ccr_root.join("backups");
-        let manifest_path = backup_root.join("multi_manifest.json");
-        let lock_dir = backup_root.join(".locks");
-        let lock_manager = LockManager::new(lock_dir);
-        Ok(Self {
-            ccr_root,
-            backup_root,
-            manifest_path,
-            lock_manager,
-        })
+        let mut service = Self::with_root(Self::detect_ccr_root()?)?;
+        service.journal = Journal::with_default()?;
+        Ok(service)
     }
 
     /// 使用指定 CCR 根目录构建服务（测试与自定义场景）
-    #[allow(dead_code)]
+    ///
+    /// 事务日志位于 `<ccr_root>/journal`，与备份共用锁目录
     pub fn with_root(ccr_root: PathBuf) -> Result<Self> {
+        let home =
+            dirs::home_dir().ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?;
         let backup_root = ccr_root.join("backups");
         let manifest_path = backup_root.join("multi_manifest.json");
         let lock_dir = backup_root.join(".locks");
-        let lock_manager = LockManager::new(lock_dir);
+        let lock_manager = LockManager::new(&lock_dir);
+        let journal = Journal::new(ccr_root.join("journal"), LockManager::new(lock_dir));
         Ok(Self {
             ccr_root,
+            home,
             backup_root,
             manifest_path,
             lock_manager,
+            journal,
         })
     }
 
+    /// 指定平台 CLI 配置目录（.claude、.codex 等）所在的主目录
+    pub fn with_home(mut self, home: PathBuf) -> Self {
+        self.home = home;
+        self
+    }
+
     /// 执行所有目标的备份（增量）
     pub fn backup_all(&self) -> Result<BackupSummary> {
         // 统一加锁，避免并发备份冲突
@@ -136,7 +216,7 @@ impl MultiBackupService {
             .lock_resource("multi_backup", std::time::Duration::from_secs(10))?;
 
         let mut manifest = BackupManifest::load(&self.manifest_path);
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let timestamp = self.next_timestamp();
 
         let sources = self.collect_sources()?;
 
@@ -170,13 +250,7 @@ impl MultiBackupService {
                                 e
                             )))
                         })?;
-                        let filename = src
-                            .source_path
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("config.toml");
-                        let target_file =
-                            target_dir.join(format!("{}.{}.bak", filename, timestamp));
+                        let target_file = self.backup_path(&src, &timestamp);
                         fs::copy(&src.source_path, &target_file).map_err(|e| {
                             CcrError::IoError(std::io::Error::other(format!(
                                 "复制备份文件失败: {}",
@@ -216,88 +290,250 @@ impl MultiBackupService {
         // 保存 manifest
         manifest.save(&self.manifest_path)?;
 
-        Ok(BackupSummary { items })
+        Ok(BackupSummary { timestamp, items })
     }
 
-    /// 收集待备份的源路径
-    fn collect_sources(&self) -> Result<Vec<BackupSource>> {
+    /// 本次备份的时间戳
+    ///
+    /// 同一秒内多次备份时顺延到最新备份之后，避免覆盖已有备份
+    fn next_timestamp(&self) -> String {
+        let now = chrono::Local::now().naive_local();
+        let now = now.with_nanosecond(0).unwrap_or(now);
+        let latest = self
+            .list_backups()
+            .first()
+            .and_then(|point| parse_timestamp(&point.timestamp));
+        let timestamp = match latest {
+            Some(latest) if latest >= now => latest + chrono::Duration::seconds(1),
+            _ => now,
+        };
+        timestamp.format(TIMESTAMP_FORMAT).to_string()
+    }
+
+    /// 列出所有备份（最新的在前）
+    pub fn list_backups(&self) -> Vec<BackupPoint> {
+        let mut points: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for src in self.source_definitions() {
+            for timestamp in self.source_timestamps(&src) {
+                points.entry(timestamp).or_default().push(src.name.clone());
+            }
+        }
+        points
+            .into_iter()
+            .rev()
+            .map(|(timestamp, sources)| BackupPoint { timestamp, sources })
+            .collect()
+    }
+
+    /// 可恢复的来源名称
+    pub fn source_names(&self) -> Vec<String> {
+        self.source_definitions()
+            .into_iter()
+            .map(|src| src.name)
+            .collect()
+    }
+
+    /// 生成恢复计划：列出备份时的文件并与当前文件比较
+    ///
+    /// `only` 为空时恢复所有来源
+    pub fn plan_restore(&self, timestamp: &str, only: &[String]) -> Result<RestorePlan> {
+        let definitions = self.source_definitions();
+        for name in only {
+            if !definitions.iter().any(|src| &src.name == name) {
+                return Err(CcrError::ValidationError(format!(
+                    "未知的备份来源 '{}'，可选: {}",
+                    name,
+                    self.source_names().join(", ")
+                )));
+            }
+        }
+        if !self
+            .list_backups()
+            .iter()
+            .any(|point| point.timestamp == timestamp)
+        {
+            return Err(CcrError::ResourceNotFound(format!("备份 {}", timestamp)));
+        }
+
         let mut sources = Vec::new();
-        let ccr_root = self.ccr_root.clone();
-        let home =
-            dirs::home_dir().ok_or_else(|| CcrError::ConfigError("无法获取用户主目录".into()))?;
+        for src in definitions
+            .iter()
+            .filter(|src| only.is_empty() || only.contains(&src.name))
+        {
+            // 增量备份：取该时间戳之前（含）最近一次的备份
+            let Some(source_timestamp) = self
+                .source_timestamps(src)
+                .into_iter()
+                .rev()
+                .find(|t| t.as_str() <= timestamp)
+            else {
+                continue;
+            };
+            let backup_path = self.backup_path(src, &source_timestamp);
+
+            let pairs = match src.kind {
+                SourceKind::File => vec![(backup_path, src.source_path.clone())],
+                SourceKind::Directory => {
+                    let mut files = Vec::new();
+                    collect_files_for_backup(&backup_path, &mut files)?;
+                    files.sort();
+                    files
+                        .into_iter()
+                        .filter_map(|file| {
+                            let target =
+                                src.source_path.join(file.strip_prefix(&backup_path).ok()?);
+                            Some((file, target))
+                        })
+                        .collect()
+                }
+            };
 
-        // 1) CCR config.toml（文件备份） → backups/ccr/config
-        let ccr_config = ccr_root.join("config.toml");
-        if ccr_config.exists() {
-            sources.push(BackupSource::new(
-                "ccr_config",
-                ccr_config,
-                SourceKind::File,
-                "ccr/config",
-            ));
+            let mut files = Vec::with_capacity(pairs.len());
+            for (backup_path, target_path) in pairs {
+                let status = restore_status(&backup_path, &target_path)?;
+                files.push(RestoreFile {
+                    backup_path,
+                    target_path,
+                    status,
+                });
+            }
+
+            sources.push(RestoreSource {
+                name: src.name.clone(),
+                timestamp: source_timestamp,
+                target_path: src.source_path.clone(),
+                files,
+            });
         }
 
-        // 2) .claude 或统一模式 platforms/claude（目录备份） → backups/ccr/.claude
-        let claude_dir = if home.join(".claude").exists() {
-            home.join(".claude")
-        } else {
-            ccr_root.join("platforms").join("claude")
-        };
-        if claude_dir.exists() {
-            sources.push(BackupSource::new(
-                "claude",
-                claude_dir,
-                SourceKind::Directory,
-                "ccr/.claude",
-            ));
+        if sources.is_empty() {
+            return Err(CcrError::ResourceNotFound(format!(
+                "备份 {} 中的 {}",
+                timestamp,
+                only.join(", ")
+            )));
         }
 
-        // 3) .gemini 或统一模式 platforms/gemini → backups/ccr/.gemini
-        let gemini_dir = if home.join(".gemini").exists() {
-            home.join(".gemini")
-        } else {
-            ccr_root.join("platforms").join("gemini")
-        };
-        if gemini_dir.exists() {
-            sources.push(BackupSource::new(
-                "gemini",
-                gemini_dir,
-                SourceKind::Directory,
-                "ccr/.gemini",
-            ));
+        Ok(RestorePlan {
+            timestamp: timestamp.to_string(),
+            sources,
+        })
+    }
+
+    /// 按计划恢复文件
+    ///
+    /// 先执行一次增量备份保存当前状态，再在事务中写入所有变化的文件；
+    /// 当前存在但备份中没有的文件保持不变
+    pub fn restore(&self, plan: &RestorePlan) -> Result<RestoreOutcome> {
+        let mut contents = Vec::new();
+        for file in plan.changed_files() {
+            let content = fs::read(&file.backup_path).map_err(|e| {
+                CcrError::FileIoError(format!(
+                    "读取备份文件 {} 失败: {}",
+                    file.backup_path.display(),
+                    e
+                ))
+            })?;
+            contents.push((file.target_path.clone(), content));
         }
 
-        // 4) .qwen 或统一模式 platforms/qwen → backups/ccr/.qwen
-        let qwen_dir = if home.join(".qwen").exists() {
-            home.join(".qwen")
+        // 安全备份：未发生变化时，当前状态即最新一次备份
+        let safety = self.backup_all()?;
+        let safety_timestamp = if safety.items.iter().any(|item| item.changed) {
+            Some(safety.timestamp)
         } else {
-            ccr_root.join("platforms").join("qwen")
+            self.list_backups()
+                .first()
+                .map(|point| point.timestamp.clone())
         };
-        if qwen_dir.exists() {
-            sources.push(BackupSource::new(
-                "qwen",
-                qwen_dir,
-                SourceKind::Directory,
-                "ccr/.qwen",
-            ));
+
+        let paths: Vec<PathBuf> = contents.iter().map(|(path, _)| path.clone()).collect();
+        let transaction = self.journal.begin("backup_restore", &paths)?;
+        for (path, content) in &contents {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    CcrError::FileIoError(format!("创建目录 {} 失败: {}", parent.display(), e))
+                })?;
+            }
+            AtomicWriter::new(path).write(content)?;
         }
+        transaction.commit()?;
 
-        // 5) .iflow 或统一模式 platforms/iflow → backups/ccr/.iflow
-        let iflow_dir = if home.join(".iflow").exists() {
-            home.join(".iflow")
-        } else {
-            ccr_root.join("platforms").join("iflow")
-        };
-        if iflow_dir.exists() {
+        Ok(RestoreOutcome {
+            safety_timestamp,
+            restored: paths,
+        })
+    }
+
+    /// 收集待备份的源路径（仅包含存在的来源）
+    fn collect_sources(&self) -> Result<Vec<BackupSource>> {
+        Ok(self
+            .source_definitions()
+            .into_iter()
+            .filter(|src| src.source_path.exists())
+            .collect())
+    }
+
+    /// 所有备份来源及其当前位置
+    fn source_definitions(&self) -> Vec<BackupSource> {
+        // 1) CCR config.toml（文件备份） → backups/ccr/config
+        let mut sources = vec![BackupSource::new(
+            "ccr_config",
+            self.ccr_root.join("config.toml"),
+            SourceKind::File,
+            "ccr/config",
+        )];
+
+        // 2) 各平台目录: ~/.<平台> 或统一模式 platforms/<平台>（目录备份） → backups/ccr/.<平台>
+        for platform in ["claude", "codex", "gemini", "qwen", "iflow"] {
+            let home_dir = self.home.join(format!(".{}", platform));
+            let dir = if home_dir.exists() {
+                home_dir
+            } else {
+                self.ccr_root.join("platforms").join(platform)
+            };
             sources.push(BackupSource::new(
-                "iflow",
-                iflow_dir,
+                platform,
+                dir,
                 SourceKind::Directory,
-                "ccr/.iflow",
+                &format!("ccr/.{}", platform),
             ));
         }
 
-        Ok(sources)
+        sources
+    }
+
+    /// 某个来源的所有备份时间戳（从旧到新）
+    fn source_timestamps(&self, src: &BackupSource) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.backup_root.join(src.target_subdir())) else {
+            return Vec::new();
+        };
+        let file_prefix = format!("{}.", src.file_name());
+        let mut timestamps: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                match src.kind {
+                    SourceKind::File => name
+                        .strip_prefix(&file_prefix)
+                        .and_then(|rest| rest.strip_suffix(".bak"))
+                        .map(str::to_string),
+                    SourceKind::Directory => entry.path().is_dir().then_some(name),
+                }
+            })
+            .filter(|timestamp| parse_timestamp(timestamp).is_some())
+            .collect();
+        timestamps.sort();
+        timestamps
+    }
+
+    /// 某个来源在指定时间戳的备份位置
+    fn backup_path(&self, src: &BackupSource, timestamp: &str) -> PathBuf {
+        let dir = self.backup_root.join(src.target_subdir());
+        match src.kind {
+            SourceKind::File => dir.join(format!("{}.{}.bak", src.file_name(), timestamp)),
+            SourceKind::Directory => dir.join(timestamp),
+        }
     }
 
     fn detect_ccr_root() -> Result<PathBuf> {
@@ -342,6 +578,41 @@ impl BackupSource {
     fn target_subdir(&self) -> &str {
         &self.target_subdir
     }
+
+    fn file_name(&self) -> &str {
+        self.source_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("config.toml")
+    }
+}
+
+/// 判断字符串是否为备份时间戳（如 20250101_120000）
+pub fn is_backup_timestamp(value: &str) -> bool {
+    parse_timestamp(value).is_some()
+}
+
+fn parse_timestamp(value: &str) -> Option<chrono::NaiveDateTime> {
+    if value.len() != 15 {
+        return None;
+    }
+    chrono::NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT).ok()
+}
+
+/// 比较备份文件与当前文件
+fn restore_status(backup_path: &Path, target_path: &Path) -> Result<RestoreFileStatus> {
+    if !target_path.exists() {
+        return Ok(RestoreFileStatus::Added);
+    }
+    let read = |path: &Path| {
+        fs::read(path)
+            .map_err(|e| CcrError::FileIoError(format!("读取文件 {} 失败: {}", path.display(), e)))
+    };
+    Ok(if read(backup_path)? == read(target_path)? {
+        RestoreFileStatus::Unchanged
+    } else {
+        RestoreFileStatus::Modified
+    })
 }
 
 /// 计算文件的 blake3 摘要
@@ -621,4 +892,94 @@ mod tests {
         let svc2 = MultiBackupService::with_root(ccr_root.clone()).unwrap();
         let _ = (svc.backup_all(), svc2.backup_all());
     }
+
+    /// 在临时目录中准备 config.toml、~/.claude 与 ~/.codex
+    fn setup_tree(home: &Path) -> MultiBackupService {
+        let ccr_root = home.join(".ccr");
+        fs::create_dir_all(&ccr_root).unwrap();
+        fs::write(
+            ccr_root.join("config.toml"),
+            "current_platform = 'claude'\n",
+        )
+        .unwrap();
+        fs::create_dir_all(home.join(".claude")).unwrap();
+        fs::write(home.join(".claude").join("settings.json"), "{\"v\":1}").unwrap();
+        fs::create_dir_all(home.join(".codex")).unwrap();
+        fs::write(home.join(".codex").join("config.toml"), "model = 'a'\n").unwrap();
+
+        MultiBackupService::with_root(ccr_root)
+            .unwrap()
+            .with_home(home.to_path_buf())
+    }
+
+    #[test]
+    fn test_restore_only_selected_sources() {
+        let tmp = tempdir().unwrap();
+        let home = tmp.path();
+        let svc = setup_tree(home);
+        let first = svc.backup_all().unwrap().timestamp;
+
+        let settings = home.join(".claude").join("settings.json");
+        let config = home.join(".ccr").join("config.toml");
+        fs::write(&settings, "{\"v\":2}").unwrap();
+        fs::write(home.join(".claude").join("new.json"), "{}").unwrap();
+        fs::write(&config, "current_platform = 'codex'\n").unwrap();
+
+        let plan = svc.plan_restore(&first, &["claude".to_string()]).unwrap();
+        assert_eq!(plan.sources.len(), 1);
+        let changed: Vec<_> = plan.changed_files().collect();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].target_path, settings);
+        assert_eq!(changed[0].status, RestoreFileStatus::Modified);
+
+        let outcome = svc.restore(&plan).unwrap();
+        assert_eq!(outcome.restored, vec![settings.clone()]);
+        assert_eq!(fs::read_to_string(&settings).unwrap(), "{\"v\":1}");
+        // 未选择的来源与备份中没有的文件保持不变
+        assert!(fs::read_to_string(&config).unwrap().contains("codex"));
+        assert!(home.join(".claude").join("new.json").exists());
+
+        // 安全备份保存了恢复前的状态，可以恢复回去
+        let safety = outcome.safety_timestamp.unwrap();
+        assert!(safety > first);
+        let plan = svc.plan_restore(&safety, &["claude".to_string()]).unwrap();
+        svc.restore(&plan).unwrap();
+        assert_eq!(fs::read_to_string(&settings).unwrap(), "{\"v\":2}");
+    }
+
+    #[test]
+    fn test_plan_combines_incremental_backups() {
+        let tmp = tempdir().unwrap();
+        let home = tmp.path();
+        let svc = setup_tree(home);
+        let first = svc.backup_all().unwrap().timestamp;
+
+        let codex_config = home.join(".codex").join("config.toml");
+        fs::write(&codex_config, "model = 'b'\n").unwrap();
+        let second = svc.backup_all().unwrap().timestamp;
+        assert!(second > first);
+
+        let backups = svc.list_backups();
+        assert_eq!(backups[0].timestamp, second);
+        assert_eq!(backups[0].sources, vec!["codex".to_string()]);
+
+        // 第二次备份时未变化的来源取第一次的备份
+        fs::remove_file(&codex_config).unwrap();
+        let plan = svc.plan_restore(&second, &[]).unwrap();
+        let claude = plan.sources.iter().find(|s| s.name == "claude").unwrap();
+        assert_eq!(claude.timestamp, first);
+        let codex = plan.sources.iter().find(|s| s.name == "codex").unwrap();
+        assert_eq!(codex.timestamp, second);
+        assert_eq!(codex.files[0].status, RestoreFileStatus::Added);
+        assert_eq!(plan.changed_files().count(), 1);
+
+        assert!(matches!(
+            svc.plan_restore(&second, &["unknown".to_string()]),
+            Err(CcrError::ValidationError(_))
+        ));
+        assert!(matches!(
+            svc.plan_restore("20000101_000000", &[]),
+            Err(CcrError::ResourceNotFound(_))
+        ));
+    }
 }
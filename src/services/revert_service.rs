@@ -0,0 +1,577 @@
+// ↩️ 撤销服务
+// 🔁 根据历史记录撤销 switch / add / delete / enable / disable 操作
+//
+// - `ccr undo` 撤销最近一次尚未撤销的操作，连续执行依次向前撤销
+// - `ccr history revert <id>` 撤销指定操作，恢复到该操作之前的状态
+//
+// 撤销前检查之后的变更是否冲突（当前状态与操作结果不一致、之后的操作修改了同一配置等），
+// 存在冲突时拒绝并说明原因；撤销本身作为一条 Revert 记录写入历史
+
+use crate::core::error::{CcrError, Result};
+use crate::core::journal::Journal;
+use crate::managers::PlatformConfigManager;
+use crate::managers::config::{ConfigManager, ConfigSection};
+use crate::managers::history::{
+    HistoryEntry, HistoryManager, OperationDetails, OperationResult, OperationType,
+};
+use crate::managers::secret_source;
+use crate::managers::settings::SettingsManager;
+use crate::models::{Platform, PlatformPaths};
+use crate::platforms::{base, create_platform_by_name};
+use crate::services::ConfigService;
+use crate::utils::mask_if_sensitive;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// 🔧 撤销需要执行的动作
+#[derive(Debug, Clone)]
+pub enum RevertAction {
+    /// 🔄 切换回操作前的配置
+    SwitchBack { from: String, to: String },
+    /// ➖ 删除操作添加的配置
+    RemoveProfile { name: String },
+    /// ➕ 从删除前的备份恢复配置
+    RestoreProfile {
+        name: String,
+        section: Box<ConfigSection>,
+    },
+    /// 🔓 恢复启用 / 禁用状态
+    SetEnabled { name: String, enabled: bool },
+}
+
+impl RevertAction {
+    /// 📝 动作说明
+    pub fn describe(&self) -> String {
+        match self {
+            RevertAction::SwitchBack { from, to } => format!("从 '{}' 切换回 '{}'", to, from),
+            RevertAction::RemoveProfile { name } => format!("删除配置 '{}'", name),
+            RevertAction::RestoreProfile { name, .. } => format!("从备份恢复配置 '{}'", name),
+            RevertAction::SetEnabled { name, enabled } => format!(
+                "{}配置 '{}'",
+                if *enabled {
+                    "重新启用"
+                } else {
+                    "重新禁用"
+                },
+                name
+            ),
+        }
+    }
+}
+
+/// 🧭 撤销计划
+#[derive(Debug, Clone)]
+pub struct RevertPlan {
+    /// 被撤销的历史记录
+    pub entry: HistoryEntry,
+    /// 操作所在平台
+    pub platform: String,
+    pub action: RevertAction,
+    /// ⚠️ 不阻止撤销的提示
+    pub notes: Vec<String>,
+}
+
+/// ↩️ 撤销服务
+pub struct RevertService {
+    history_manager: Arc<HistoryManager>,
+}
+
+impl RevertService {
+    /// 🏗️ 创建撤销服务
+    pub fn new(history_manager: Arc<HistoryManager>) -> Self {
+        Self { history_manager }
+    }
+
+    /// 🏠 使用默认历史记录管理器创建服务
+    pub fn with_default() -> Result<Self> {
+        Ok(Self::new(Arc::new(HistoryManager::with_default()?)))
+    }
+
+    /// 🔍 最近一次尚未撤销的可撤销操作
+    pub fn last_revertable(&self) -> Result<HistoryEntry> {
+        let entries = self.sorted_entries()?;
+        let reverted = reverted_ids(&entries);
+        entries
+            .iter()
+            .rev()
+            .find(|e| {
+                e.operation.is_revertable()
+                    && e.result == OperationResult::Success
+                    && e.platform.is_some()
+                    && !reverted.contains(e.id.as_str())
+            })
+            .cloned()
+            .ok_or_else(|| CcrError::ResourceNotFound("没有可撤销的操作".into()))
+    }
+
+    /// 🔍 按 ID 或唯一的 ID 前缀查找历史记录
+    pub fn find(&self, id: &str) -> Result<HistoryEntry> {
+        let mut matches: Vec<HistoryEntry> = self
+            .sorted_entries()?
+            .into_iter()
+            .filter(|e| e.id.starts_with(id))
+            .collect();
+        match matches.len() {
+            0 => Err(CcrError::ResourceNotFound(format!("历史记录 '{}'", id))),
+            1 => Ok(matches.remove(0)),
+            n => Err(CcrError::ValidationError(format!(
+                "ID 前缀 '{}' 匹配到 {} 条记录，请提供更长的 ID",
+                id, n
+            ))),
+        }
+    }
+
+    /// 🧭 生成撤销计划
+    ///
+    /// 存在冲突时返回 `RevertConflict`，消息中逐条列出原因
+    pub fn plan(&self, entry: &HistoryEntry) -> Result<RevertPlan> {
+        if !entry.operation.is_revertable() {
+            return Err(CcrError::ValidationError(format!(
+                "{} 操作不支持撤销（支持: 切换、添加、删除、启用、禁用）",
+                entry.operation.as_str()
+            )));
+        }
+        if entry.result != OperationResult::Success {
+            return Err(CcrError::ValidationError("只能撤销成功的操作".into()));
+        }
+
+        let entries = self.sorted_entries()?;
+        let reverted = reverted_ids(&entries);
+        if reverted.contains(entry.id.as_str()) {
+            return Err(CcrError::RevertConflict(format!(
+                "操作 {} 已被撤销",
+                short_id(&entry.id)
+            )));
+        }
+
+        let platform = entry.platform.clone().ok_or_else(|| {
+            CcrError::RevertConflict(
+                "该记录缺少平台信息（项目级切换或由旧版本写入），无法撤销".into(),
+            )
+        })?;
+        let current_platform = PlatformConfigManager::with_default()?
+            .load()?
+            .current_platform;
+        if current_platform != platform {
+            return Err(CcrError::RevertConflict(format!(
+                "该操作发生在平台 '{}'，当前平台为 '{}'，请先运行 'ccr platform switch {}'",
+                platform, current_platform, platform
+            )));
+        }
+
+        let mut conflicts = later_conflicts(entry, &entries, &reverted);
+        let mut notes = Vec::new();
+        let action = match entry.operation {
+            OperationType::Switch => {
+                self.plan_switch(entry, &platform, &mut conflicts, &mut notes)?
+            }
+            _ => Self::plan_profile_change(entry, &mut conflicts)?,
+        };
+
+        if !conflicts.is_empty() {
+            return Err(CcrError::RevertConflict(conflicts.join("\n")));
+        }
+        Ok(RevertPlan {
+            entry: entry.clone(),
+            platform,
+            action,
+            notes,
+        })
+    }
+
+    /// 🔄 切换: 当前配置与 settings.json 仍是该操作的结果时，切换回原配置
+    fn plan_switch(
+        &self,
+        entry: &HistoryEntry,
+        platform: &str,
+        conflicts: &mut Vec<String>,
+        notes: &mut Vec<String>,
+    ) -> Result<RevertAction> {
+        let (Some(from), Some(to)) = (&entry.details.from_config, &entry.details.to_config) else {
+            return Err(CcrError::RevertConflict(
+                "该切换没有记录切换前的配置，无法撤销".into(),
+            ));
+        };
+
+        let platform_config = create_platform_by_name(platform)?;
+        let current = platform_config.get_current_profile()?.unwrap_or_default();
+        if &current != to {
+            conflicts.push(format!(
+                "当前配置为 '{}'，不是该操作切换到的 '{}'",
+                current, to
+            ));
+        }
+
+        let profiles = platform_config.load_profiles()?;
+        match profiles.get(from) {
+            None => conflicts.push(format!("原配置 '{}' 已被删除", from)),
+            Some(profile) if profile.enabled == Some(false) => {
+                conflicts.push(format!("原配置 '{}' 已被禁用", from))
+            }
+            Some(profile) if base::is_template_profile(profile) => {
+                conflicts.push(format!("原配置 '{}' 已改为模板", from))
+            }
+            Some(_) => {}
+        }
+
+        if platform_config.platform_type() == Platform::Claude && !entry.env_changes.is_empty() {
+            // settings.json 在该操作之后被其他方式修改时拒绝，避免覆盖用户的改动
+            let settings = SettingsManager::with_default()?.load().ok();
+            let env = settings
+                .map(|s| s.anthropic_env_status())
+                .unwrap_or_default();
+            for change in &entry.env_changes {
+                let actual = masked(&env, &change.var_name);
+                if actual != change.new_value {
+                    conflicts.push(format!(
+                        "settings.json 中的 {} 在该操作之后被修改",
+                        change.var_name
+                    ));
+                }
+            }
+
+            // 原配置之后被编辑时仍可撤销，但结果与操作前不完全相同
+            // （密钥来源引用在切换时才解析，无法与记录的值比较，视为一致）
+            if conflicts.is_empty()
+                && let Ok(resolved) = base::resolve_profile_section(&profiles, from)
+            {
+                let expected = resolved.section.to_anthropic_env_status();
+                let changed: Vec<&str> = entry
+                    .env_changes
+                    .iter()
+                    .filter(|c| {
+                        let indirect = expected
+                            .get(&c.var_name)
+                            .cloned()
+                            .flatten()
+                            .is_some_and(|value| secret_source::is_indirect(&value));
+                        !indirect && masked(&expected, &c.var_name) != c.old_value
+                    })
+                    .map(|c| c.var_name.as_str())
+                    .collect();
+                if !changed.is_empty() {
+                    notes.push(format!(
+                        "配置 '{}' 的 {} 与切换前记录的值不同，将使用当前定义",
+                        from,
+                        changed.join(", ")
+                    ));
+                }
+            }
+        }
+
+        Ok(RevertAction::SwitchBack {
+            from: from.clone(),
+            to: to.clone(),
+        })
+    }
+
+    /// 📝 添加 / 删除 / 启用 / 禁用: 配置仍处于该操作之后的状态时反向执行
+    fn plan_profile_change(
+        entry: &HistoryEntry,
+        conflicts: &mut Vec<String>,
+    ) -> Result<RevertAction> {
+        let name = entry
+            .details
+            .to_config
+            .clone()
+            .or_else(|| entry.details.from_config.clone())
+            .ok_or_else(|| CcrError::RevertConflict("该记录没有配置名称，无法撤销".into()))?;
+        let config = ConfigService::with_default()?.load_config()?;
+        let section = config.sections.get(&name);
+
+        let action = match entry.operation {
+            OperationType::Add => {
+                match section {
+                    None => conflicts.push(format!("配置 '{}' 已不存在", name)),
+                    Some(_) => {
+                        if config.current_config == name {
+                            conflicts.push(format!("配置 '{}' 是当前配置", name));
+                        }
+                        if config.default_config == name {
+                            conflicts.push(format!("配置 '{}' 是默认配置", name));
+                        }
+                        let children = ConfigManager::children_of(&config, &name);
+                        if !children.is_empty() {
+                            conflicts.push(format!(
+                                "配置 '{}' 被 {} 继承",
+                                name,
+                                children.join(", ")
+                            ));
+                        }
+                    }
+                }
+                RevertAction::RemoveProfile { name }
+            }
+            OperationType::Delete => {
+                if section.is_some() {
+                    conflicts.push(format!("已存在同名配置 '{}'", name));
+                }
+                let backup = entry.details.backup_path.as_deref().ok_or_else(|| {
+                    CcrError::RevertConflict("该删除操作没有备份，无法恢复".into())
+                })?;
+                let section = load_backup_section(Path::new(backup), &name)?;
+                RevertAction::RestoreProfile {
+                    name,
+                    section: Box::new(section),
+                }
+            }
+            OperationType::Enable | OperationType::Disable => {
+                let enabled_after = entry.operation == OperationType::Enable;
+                match section {
+                    None => conflicts.push(format!("配置 '{}' 已不存在", name)),
+                    Some(section) if section.is_enabled() != enabled_after => {
+                        conflicts.push(format!(
+                            "配置 '{}' 当前已被{}",
+                            name,
+                            if enabled_after { "禁用" } else { "启用" }
+                        ))
+                    }
+                    Some(_) => {}
+                }
+                RevertAction::SetEnabled {
+                    name,
+                    enabled: !enabled_after,
+                }
+            }
+            _ => unreachable!("仅可撤销的操作会进入此分支"),
+        };
+        Ok(action)
+    }
+
+    /// ✅ 执行撤销并记录 Revert 历史
+    ///
+    /// 所有写入在同一个事务中完成，任一步失败都恢复原状
+    pub async fn apply(&self, plan: &RevertPlan) -> Result<HistoryEntry> {
+        let platform_config = create_platform_by_name(&plan.platform)?;
+        let paths = PlatformPaths::for_name(&plan.platform)?;
+        let mut journal_paths = platform_config.settings_files();
+        journal_paths.extend([
+            paths.profiles_file,
+            paths.registry_file,
+            self.history_manager.history_path().to_path_buf(),
+        ]);
+        super::auto_snapshot("revert");
+        let transaction = Journal::with_default()?.begin("revert", &journal_paths)?;
+
+        let is_claude = platform_config.platform_type() == Platform::Claude;
+        let env_before = if is_claude {
+            current_env_status()
+        } else {
+            HashMap::new()
+        };
+
+        let (from_config, to_config) = match &plan.action {
+            RevertAction::SwitchBack { from, to } => {
+                platform_config.apply_profile(from)?;
+                (Some(to.clone()), Some(from.clone()))
+            }
+            RevertAction::RemoveProfile { name } => {
+                ConfigService::with_default()?.delete_config(name)?;
+                (Some(name.clone()), None)
+            }
+            RevertAction::RestoreProfile { name, section } => {
+                ConfigService::with_default()?.add_config(name.clone(), (**section).clone())?;
+                (None, Some(name.clone()))
+            }
+            RevertAction::SetEnabled { name, enabled } => {
+                let service = ConfigService::with_default()?;
+                if *enabled {
+                    service.enable_config(name)?;
+                } else {
+                    service.disable_config(name)?;
+                }
+                (None, Some(name.clone()))
+            }
+        };
+
+        let mut revert_entry = HistoryEntry::new(
+            OperationType::Revert,
+            OperationDetails {
+                from_config,
+                to_config,
+                backup_path: None,
+                extra: Some(format!(
+                    "撤销 {} 操作 {}",
+                    plan.entry.operation.as_str(),
+                    short_id(&plan.entry.id)
+                )),
+            },
+            OperationResult::Success,
+        );
+        revert_entry.platform = Some(plan.platform.clone());
+        revert_entry.reverts = Some(plan.entry.id.clone());
+        if matches!(plan.action, RevertAction::SwitchBack { .. }) && is_claude {
+            let env_after = current_env_status();
+            let mut names: Vec<&String> = env_before.keys().chain(env_after.keys()).collect();
+            names.sort();
+            names.dedup();
+            for name in names {
+                let old = env_before.get(name).cloned().flatten();
+                let new = env_after.get(name).cloned().flatten();
+                if old != new {
+                    revert_entry.add_env_change(name.clone(), old, new);
+                }
+            }
+        }
+
+        self.history_manager.add_async(revert_entry.clone()).await?;
+        transaction.commit()?;
+        Ok(revert_entry)
+    }
+
+    /// 📋 按时间正序排列的历史记录
+    fn sorted_entries(&self) -> Result<Vec<HistoryEntry>> {
+        let mut entries = self.history_manager.load()?;
+        entries.sort_by_key(|e| e.timestamp);
+        Ok(entries)
+    }
+}
+
+/// 🔢 显示用的短 ID
+pub fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+/// 🔍 已被撤销的操作 ID
+fn reverted_ids(entries: &[HistoryEntry]) -> HashSet<&str> {
+    entries
+        .iter()
+        .filter_map(|e| e.reverts.as_deref())
+        .collect()
+}
+
+/// ⚔️ 之后尚未撤销的操作也修改了同一配置时，需要先撤销它们
+fn later_conflicts(
+    entry: &HistoryEntry,
+    entries: &[HistoryEntry],
+    reverted: &HashSet<&str>,
+) -> Vec<String> {
+    let touched = touched_profiles(entry);
+    entries
+        .iter()
+        .filter(|e| {
+            e.timestamp > entry.timestamp
+                && e.operation.is_revertable()
+                && e.result == OperationResult::Success
+                && e.platform == entry.platform
+                && !reverted.contains(e.id.as_str())
+        })
+        .filter_map(|later| {
+            let shared: Vec<&str> = touched_profiles(later)
+                .into_iter()
+                .filter(|name| touched.contains(name))
+                .collect();
+            (!shared.is_empty()).then(|| {
+                format!(
+                    "之后的{}操作 {} 也修改了 '{}'，请先撤销它",
+                    later.operation.as_str(),
+                    short_id(&later.id),
+                    shared.join("', '")
+                )
+            })
+        })
+        .collect()
+}
+
+fn touched_profiles(entry: &HistoryEntry) -> Vec<&str> {
+    [&entry.details.from_config, &entry.details.to_config]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect()
+}
+
+/// 🎭 按历史记录的掩码规则取值，便于与记录比较
+fn masked(env: &HashMap<String, Option<String>>, name: &str) -> Option<String> {
+    env.get(name)
+        .cloned()
+        .flatten()
+        .map(|value| mask_if_sensitive(name, &value))
+}
+
+fn current_env_status() -> HashMap<String, Option<String>> {
+    SettingsManager::with_default()
+        .and_then(|manager| manager.load())
+        .map(|settings| settings.anthropic_env_status())
+        .unwrap_or_default()
+}
+
+/// 📦 从删除前的备份中读取配置节
+fn load_backup_section(backup: &Path, name: &str) -> Result<ConfigSection> {
+    if !backup.exists() {
+        return Err(CcrError::RevertConflict(format!(
+            "删除前的备份 {} 已不存在",
+            backup.display()
+        )));
+    }
+    let config = ConfigManager::new(backup).load()?;
+    config.sections.get(name).cloned().ok_or_else(|| {
+        CcrError::RevertConflict(format!("备份 {} 中没有配置 '{}'", backup.display(), name))
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::core::lock::LockManager;
+    use tempfile::tempdir;
+
+    fn entry(operation: OperationType, from: Option<&str>, to: Option<&str>) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(
+            operation,
+            OperationDetails {
+                from_config: from.map(Into::into),
+                to_config: to.map(Into::into),
+                backup_path: None,
+                extra: None,
+            },
+            OperationResult::Success,
+        );
+        entry.platform = Some("claude".into());
+        entry
+    }
+
+    #[test]
+    fn test_later_conflicts_only_for_shared_profiles() {
+        let first = entry(OperationType::Switch, Some("alpha"), Some("beta"));
+        let unrelated = entry(OperationType::Disable, None, Some("gamma"));
+        let shared = entry(OperationType::Switch, Some("beta"), Some("gamma"));
+        let entries = vec![first.clone(), unrelated, shared.clone()];
+
+        let conflicts = later_conflicts(&first, &entries, &HashSet::new());
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].contains(short_id(&shared.id)));
+        assert!(conflicts[0].contains("'beta'"));
+
+        // 已撤销的操作不再构成冲突
+        let reverted = HashSet::from([shared.id.as_str()]);
+        assert!(later_conflicts(&first, &entries, &reverted).is_empty());
+    }
+
+    #[test]
+    fn test_last_revertable_skips_reverted_and_legacy_entries() {
+        let temp_dir = tempdir().unwrap();
+        let manager = Arc::new(HistoryManager::new(
+            temp_dir.path().join("history.json"),
+            LockManager::new(temp_dir.path().join("locks")),
+        ));
+        let service = RevertService::new(manager.clone());
+        assert!(service.last_revertable().is_err());
+
+        let add = entry(OperationType::Add, None, Some("alpha"));
+        manager.add(add.clone()).unwrap();
+        let switch = entry(OperationType::Switch, Some("alpha"), Some("beta"));
+        manager.add(switch.clone()).unwrap();
+        let mut revert = entry(OperationType::Revert, Some("beta"), Some("alpha"));
+        revert.reverts = Some(switch.id.clone());
+        manager.add(revert).unwrap();
+        let mut legacy = entry(OperationType::Enable, None, Some("gamma"));
+        legacy.platform = None;
+        manager.add(legacy).unwrap();
+
+        assert_eq!(service.last_revertable().unwrap().id, add.id);
+        assert_eq!(service.find(&add.id[..8]).unwrap().id, add.id);
+    }
+}
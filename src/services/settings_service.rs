@@ -57,8 +57,8 @@ impl SettingsService {
     ///
     /// # Process
     /// 1. 加载当前设置(或创建新设置)
-    /// 2. 清空旧的 ANTHROPIC_* 变量
-    /// 3. 从配置节设置新的环境变量
+    /// 2. 清空旧的 ANTHROPIC_* 变量与上次的设置包
+    /// 3. 从配置节设置新的环境变量并合并设置包
     /// 4. 原子保存设置文件
     pub fn apply_config(&self, section: &ConfigSection) -> Result<()> {
         let mut settings = self
@@ -66,7 +66,7 @@ impl SettingsService {
             .load()
             .unwrap_or_else(|_| ClaudeSettings::new());
 
-        settings.update_from_config(section);
+        settings.apply_section(&section.with_resolved_secrets()?)?;
         self.settings_manager.save_atomic(&settings)?;
 
         Ok(())
@@ -76,7 +76,7 @@ impl SettingsService {
     pub async fn apply_config_async(&self, section: &ConfigSection) -> Result<()> {
         let mut settings = self.settings_manager.load_async().await.unwrap_or_default();
 
-        settings.update_from_config(section);
+        settings.apply_section(&section.with_resolved_secrets()?)?;
         self.settings_manager.save_atomic_async(&settings).await?;
 
         Ok(())
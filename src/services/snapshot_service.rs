@@ -0,0 +1,286 @@
+// 🗄️ 快照服务
+// 📸 决定快照覆盖哪些文件，并协调快照存储、保留策略与事务日志
+//
+// 覆盖的文件:
+// - ~/.ccr/config.toml（平台注册表）
+// - ~/.ccr/platforms/*/profiles.toml 以及各平台切换时写入的设置文件（如 ~/.claude/settings.json）
+// - 旧版 ~/.ccs_config.toml 与其设置文件（存在时）
+
+use crate::core::atomic_writer::AtomicWriter;
+use crate::core::error::{CcrError, Result};
+use crate::core::journal::Journal;
+use crate::managers::config::ConfigManager;
+use crate::managers::settings::SettingsManager;
+use crate::managers::settings_bundle::{self, SettingsChange};
+use crate::managers::snapshot_store::{RetentionPolicy, Snapshot, SnapshotStore};
+use crate::managers::vault;
+use crate::models::PlatformPaths;
+use crate::platforms::create_platform_by_name;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 🔍 单个文件的差异
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    /// 旧内容大小（文件不存在时为 None）
+    pub old_size: Option<u64>,
+    /// 新内容大小（文件不存在时为 None）
+    pub new_size: Option<u64>,
+    /// 按 JSON 路径排列的变化，敏感值已脱敏；无法解析为 JSON / TOML 时为空
+    pub changes: Vec<SettingsChange>,
+}
+
+/// 🔄 恢复结果
+#[derive(Debug)]
+pub struct RestoreResult {
+    /// 恢复前自动创建的快照（当前内容与最新快照相同时为 None）
+    pub safety_snapshot: Option<Snapshot>,
+    pub restored: Vec<PathBuf>,
+}
+
+/// 🗄️ 快照服务
+pub struct SnapshotService {
+    store: SnapshotStore,
+    ccr_root: PathBuf,
+    legacy_config: Option<PathBuf>,
+}
+
+impl SnapshotService {
+    pub fn new(store: SnapshotStore, ccr_root: PathBuf, legacy_config: Option<PathBuf>) -> Self {
+        Self {
+            store,
+            ccr_root,
+            legacy_config,
+        }
+    }
+
+    /// 🏠 使用默认路径创建服务
+    pub fn with_default() -> Result<Self> {
+        Ok(Self::new(
+            SnapshotStore::with_default()?,
+            PlatformPaths::get_ccr_root()?,
+            vault::legacy_config_path(),
+        ))
+    }
+
+    pub fn store(&self) -> &SnapshotStore {
+        &self.store
+    }
+
+    /// 📋 快照覆盖的文件（包括当前不存在的文件）
+    pub fn tracked_files(&self) -> Vec<PathBuf> {
+        let mut files = vec![self.ccr_root.join("config.toml")];
+        for (platform, profiles) in
+            vault::migration_sources(&self.ccr_root, self.legacy_config.as_deref())
+        {
+            files.push(profiles);
+            if platform == "legacy" {
+                if let Ok(manager) = SettingsManager::with_default() {
+                    files.push(manager.settings_path().to_path_buf());
+                }
+            } else if let Ok(platform_config) = create_platform_by_name(&platform) {
+                files.extend(platform_config.settings_files());
+            }
+        }
+
+        let mut unique = Vec::new();
+        for file in files {
+            if !unique.contains(&file) {
+                unique.push(file);
+            }
+        }
+        unique
+    }
+
+    /// 📅 当前平台 profiles.toml 中配置的保留策略
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        ConfigManager::with_default()
+            .and_then(|manager| manager.load())
+            .ok()
+            .and_then(|config| config.settings.backup_retention)
+            .unwrap_or_default()
+    }
+
+    /// 📸 创建快照并按保留策略清理
+    ///
+    /// 内容与最新快照相同时不创建，返回 None
+    pub fn capture(&self, reason: &str) -> Result<Option<Snapshot>> {
+        let snapshot = self
+            .store
+            .create_if_changed(reason, &self.tracked_files())?;
+        if snapshot.is_some() {
+            self.apply_retention();
+        }
+        Ok(snapshot)
+    }
+
+    /// 📸 无论内容是否变化都创建快照（手动备份）
+    pub fn capture_now(&self, reason: &str) -> Result<Snapshot> {
+        let snapshot = self.store.create(reason, &self.tracked_files())?;
+        self.apply_retention();
+        Ok(snapshot)
+    }
+
+    fn apply_retention(&self) {
+        if let Err(e) = self.store.prune(&self.retention_policy(), false) {
+            tracing::warn!("清理旧快照失败: {}", e);
+        }
+    }
+
+    /// 🔄 将快照中的文件写回原位置
+    ///
+    /// 先校验快照内容并为当前状态创建快照，再在事务中写入所有文件；
+    /// 快照之后新出现的文件保持不变
+    pub fn restore(&self, snapshot: &Snapshot) -> Result<RestoreResult> {
+        let mut contents = Vec::new();
+        for file in &snapshot.files {
+            contents.push((file.path.clone(), self.store.read_file(file)?));
+        }
+
+        let safety_snapshot = self.capture("pre_restore")?;
+
+        let paths: Vec<PathBuf> = contents.iter().map(|(path, _)| path.clone()).collect();
+        let transaction = Journal::with_default()?.begin("restore", &paths)?;
+        for (path, content) in &contents {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    CcrError::FileIoError(format!("创建目录 {} 失败: {}", parent.display(), e))
+                })?;
+            }
+            AtomicWriter::new(path).write(content)?;
+        }
+        transaction.commit()?;
+
+        Ok(RestoreResult {
+            safety_snapshot,
+            restored: paths,
+        })
+    }
+
+    /// 🔍 比较两个快照；`to` 为 None 时与磁盘上的当前文件比较
+    pub fn diff(&self, from: &Snapshot, to: Option<&Snapshot>) -> Result<Vec<FileDiff>> {
+        let mut paths: Vec<PathBuf> = from.files.iter().map(|f| f.path.clone()).collect();
+        match to {
+            Some(to) => paths.extend(to.files.iter().map(|f| f.path.clone())),
+            None => paths.extend(self.tracked_files().into_iter().filter(|p| p.is_file())),
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut diffs = Vec::new();
+        for path in paths {
+            let old = from
+                .file(&path)
+                .map(|file| self.store.read_file(file))
+                .transpose()?;
+            let new = match to {
+                Some(to) => to
+                    .file(&path)
+                    .map(|file| self.store.read_file(file))
+                    .transpose()?,
+                None => fs::read(&path).ok(),
+            };
+            diffs.extend(diff_file(path, old.as_deref(), new.as_deref()));
+        }
+        Ok(diffs)
+    }
+}
+
+/// 📸 修改配置前自动创建快照，失败时只记录警告，不影响后续操作
+pub fn auto_snapshot(reason: &str) {
+    match SnapshotService::with_default().and_then(|service| service.capture(reason)) {
+        Ok(Some(snapshot)) => tracing::debug!("📸 {} 前已创建快照 {}", reason, snapshot.short_id()),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("{} 前创建快照失败: {}", reason, e),
+    }
+}
+
+/// 🔍 比较同一文件的两份内容（None 表示文件不存在），内容相同时返回 None
+///
+/// JSON / TOML 内容按键列出变化并对敏感值脱敏
+pub fn diff_file(path: PathBuf, old: Option<&[u8]>, new: Option<&[u8]>) -> Option<FileDiff> {
+    if old == new {
+        return None;
+    }
+
+    let changes = match (
+        old.and_then(|c| parse_structured(&path, c)),
+        new.and_then(|c| parse_structured(&path, c)),
+    ) {
+        (Some(old_value), Some(new_value)) => {
+            let mut changes = settings_bundle::diff(&old_value, &new_value);
+            for change in &mut changes {
+                settings_bundle::mask_change(change);
+            }
+            changes
+        }
+        _ => Vec::new(),
+    };
+
+    Some(FileDiff {
+        path,
+        old_size: old.map(|c| c.len() as u64),
+        new_size: new.map(|c| c.len() as u64),
+        changes,
+    })
+}
+
+/// 📖 按扩展名将 JSON / TOML 内容解析为 JSON 值
+fn parse_structured(path: &Path, content: &[u8]) -> Option<Value> {
+    let text = std::str::from_utf8(content).ok()?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(text).ok(),
+        Some("toml") => toml::from_str::<toml::Value>(text)
+            .ok()
+            .and_then(|value| serde_json::to_value(value).ok()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::core::lock::LockManager;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_diff_masks_secrets() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join(".ccr");
+        let profiles = root.join("platforms").join("custom").join("profiles.toml");
+        fs::create_dir_all(profiles.parent().unwrap()).unwrap();
+        fs::write(
+            &profiles,
+            "[a]\nbase_url = \"https://a.example.com\"\nauth_token = \"sk-old-123456\"\n",
+        )
+        .unwrap();
+
+        let service = SnapshotService::new(
+            SnapshotStore::new(root.join("snapshots"), LockManager::new(dir.path())),
+            root,
+            None,
+        );
+        assert!(service.tracked_files().contains(&profiles));
+        let snapshot = service
+            .store()
+            .create("test", &service.tracked_files())
+            .unwrap();
+
+        fs::write(
+            &profiles,
+            "[a]\nbase_url = \"https://b.example.com\"\nauth_token = \"sk-new-654321\"\n",
+        )
+        .unwrap();
+        let diffs = service.diff(&snapshot, None).unwrap();
+        assert_eq!(diffs.len(), 1);
+        let paths: Vec<&str> = diffs[0].changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.auth_token", "a.base_url"]);
+        assert_eq!(
+            diffs[0].changes[0].new,
+            Some(Value::String("sk-n...4321".into()))
+        );
+    }
+}
@@ -121,6 +121,7 @@ impl Session {
             Platform::Qwen => format!("qwen --resume {}", self.id),
             Platform::IFlow => format!("iflow --resume {}", self.id),
             Platform::Droid => format!("droid --resume {}", self.id),
+            Platform::Custom => format!("# {}", self.id),
         }
     }
 }
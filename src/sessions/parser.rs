@@ -25,7 +25,7 @@ impl SessionParser {
             Platform::Claude => Self::parse_claude(path),
             Platform::Codex => Self::parse_codex(path),
             Platform::Gemini => Self::parse_gemini(path),
-            Platform::Qwen | Platform::IFlow | Platform::Droid => {
+            Platform::Qwen | Platform::IFlow | Platform::Droid | Platform::Custom => {
                 Self::parse_generic(path, platform)
             }
         }
@@ -433,6 +433,7 @@ impl SessionParser {
             Platform::Qwen => home.join(".qwen").join("sessions"),
             Platform::IFlow => home.join(".iflow").join("sessions"),
             Platform::Droid => home.join(".factory").join("sessions"),
+            Platform::Custom => return None,
         };
 
         if path.exists() { Some(path) } else { None }
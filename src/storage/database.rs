@@ -118,6 +118,11 @@ impl Database {
             "002_create_search_history",
             Self::migration_002_create_search_history,
         )?;
+        self.run_migration(
+            &conn,
+            "003_create_provider_health",
+            Self::migration_003_create_provider_health,
+        )?;
 
         info!("数据库迁移完成");
         Ok(())
@@ -208,6 +213,47 @@ impl Database {
         Ok(())
     }
 
+    /// 迁移 003: 创建 provider 健康检查历史表
+    ///
+    /// - `provider_health_samples`: 每次探测的状态与延迟
+    /// - `provider_health_transitions`: 状态变化（首次探测也记录一次）
+    fn migration_003_create_provider_health(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS provider_health_samples (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                platform TEXT NOT NULL,
+                profile TEXT NOT NULL,
+                checked_at TEXT NOT NULL,
+                status TEXT NOT NULL,
+                latency_ms INTEGER,
+                error TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_provider_health_samples_profile
+                ON provider_health_samples(platform, profile, checked_at);
+            CREATE INDEX IF NOT EXISTS idx_provider_health_samples_checked_at
+                ON provider_health_samples(checked_at);
+
+            CREATE TABLE IF NOT EXISTS provider_health_transitions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                platform TEXT NOT NULL,
+                profile TEXT NOT NULL,
+                changed_at TEXT NOT NULL,
+                from_status TEXT,
+                to_status TEXT NOT NULL,
+                error TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_provider_health_transitions_profile
+                ON provider_health_transitions(platform, profile, changed_at);
+            "#,
+        )
+        .map_err(|e| CcrError::DatabaseError(format!("创建 provider 健康检查表失败: {}", e)))?;
+
+        Ok(())
+    }
+
     /// 获取数据库统计信息
     pub fn stats(&self) -> Result<DatabaseStats> {
         let conn = self.conn()?;
@@ -246,8 +292,11 @@ impl Database {
     pub fn clear_all(&self) -> Result<()> {
         warn!("清空所有数据库数据");
         let conn = self.conn()?;
-        conn.execute_batch("DELETE FROM sessions; DELETE FROM search_history;")
-            .map_err(|e| CcrError::DatabaseError(format!("清空数据失败: {}", e)))?;
+        conn.execute_batch(
+            "DELETE FROM sessions; DELETE FROM search_history; \
+             DELETE FROM provider_health_samples; DELETE FROM provider_health_transitions;",
+        )
+        .map_err(|e| CcrError::DatabaseError(format!("清空数据失败: {}", e)))?;
         Ok(())
     }
 }
@@ -298,7 +347,7 @@ mod tests {
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM migrations", [], |row| row.get(0))
             .unwrap();
-        assert!(count >= 2);
+        assert!(count >= 3);
     }
 
     #[test]
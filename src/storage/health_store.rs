@@ -0,0 +1,434 @@
+//! 🏥 Provider 健康检查历史存储
+//!
+//! 记录每次探测的延迟样本与状态变化，并汇总 p50/p95 延迟和可用率。
+
+use crate::core::error::{CcrError, Result};
+use crate::services::health_check::HealthStatus;
+use crate::storage::database::Database;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// 📈 单次探测样本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthSample {
+    /// 所属平台
+    pub platform: String,
+    /// Profile 名称
+    pub profile: String,
+    /// 探测时间
+    pub checked_at: DateTime<Utc>,
+    /// 探测结果
+    pub status: HealthStatus,
+    /// 延迟（毫秒）
+    pub latency_ms: Option<u64>,
+    /// 错误信息
+    pub error: Option<String>,
+}
+
+/// 🔀 状态变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub platform: String,
+    pub profile: String,
+    /// 变化时间
+    pub changed_at: DateTime<Utc>,
+    /// 之前的状态（首次探测时为 None）
+    pub from_status: Option<HealthStatus>,
+    /// 新状态
+    pub to_status: HealthStatus,
+    /// 变为新状态时的错误信息
+    pub error: Option<String>,
+}
+
+/// 📊 单个 profile 的健康统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealthStats {
+    pub platform: String,
+    pub profile: String,
+    /// 最近 24 小时的样本数
+    pub samples_24h: u64,
+    /// 最近 24 小时可用样本的延迟中位数（毫秒）
+    pub p50_ms: Option<u64>,
+    /// 最近 24 小时可用样本的 95 分位延迟（毫秒）
+    pub p95_ms: Option<u64>,
+    /// 最近 24 小时可用率（百分比，无样本时为 None）
+    pub uptime_24h: Option<f64>,
+    /// 最近 7 天可用率（百分比，无样本时为 None）
+    pub uptime_7d: Option<f64>,
+    /// 最近一次探测的状态
+    pub last_status: Option<HealthStatus>,
+    /// 最近一次探测时间
+    pub last_checked_at: Option<DateTime<Utc>>,
+    /// 最近一次错误
+    pub last_error: Option<String>,
+    /// 最近一次错误的时间
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+/// 🏥 Provider 健康检查历史存储层
+pub struct HealthStore<'a> {
+    db: &'a Database,
+}
+
+impl<'a> HealthStore<'a> {
+    /// 创建新的 HealthStore
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// 写入一个样本，状态与上一个样本不同时同时写入状态变化并返回
+    pub fn record(&self, sample: &HealthSample) -> Result<Option<StatusTransition>> {
+        let mut conn = self.db.conn()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| CcrError::DatabaseError(format!("开始事务失败: {}", e)))?;
+
+        let previous: Option<String> = tx
+            .query_row(
+                r#"
+                SELECT status FROM provider_health_samples
+                WHERE platform = ?1 AND profile = ?2
+                ORDER BY checked_at DESC, id DESC
+                LIMIT 1
+                "#,
+                rusqlite::params![sample.platform, sample.profile],
+                |row| row.get(0),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })
+            .map_err(|e| CcrError::DatabaseError(format!("查询上次健康状态失败: {}", e)))?;
+        let previous = previous.map(|s| HealthStatus::from_str_safe(&s));
+
+        tx.execute(
+            r#"
+            INSERT INTO provider_health_samples
+                (platform, profile, checked_at, status, latency_ms, error)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            rusqlite::params![
+                sample.platform,
+                sample.profile,
+                format_datetime(&sample.checked_at),
+                sample.status.as_str(),
+                sample.latency_ms.map(|l| l as i64),
+                sample.error,
+            ],
+        )
+        .map_err(|e| CcrError::DatabaseError(format!("写入健康检查样本失败: {}", e)))?;
+
+        let transition = (previous.as_ref() != Some(&sample.status)).then(|| StatusTransition {
+            platform: sample.platform.clone(),
+            profile: sample.profile.clone(),
+            changed_at: sample.checked_at,
+            from_status: previous,
+            to_status: sample.status.clone(),
+            error: sample.error.clone(),
+        });
+
+        if let Some(t) = &transition {
+            tx.execute(
+                r#"
+                INSERT INTO provider_health_transitions
+                    (platform, profile, changed_at, from_status, to_status, error)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                rusqlite::params![
+                    t.platform,
+                    t.profile,
+                    format_datetime(&t.changed_at),
+                    t.from_status.as_ref().map(HealthStatus::as_str),
+                    t.to_status.as_str(),
+                    t.error,
+                ],
+            )
+            .map_err(|e| CcrError::DatabaseError(format!("写入状态变化失败: {}", e)))?;
+        }
+
+        tx.commit()
+            .map_err(|e| CcrError::DatabaseError(format!("提交事务失败: {}", e)))?;
+        Ok(transition)
+    }
+
+    /// 汇总单个 profile 截至 `now` 的健康统计
+    pub fn stats(
+        &self,
+        platform: &str,
+        profile: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ProviderHealthStats> {
+        let day = self.samples_since(platform, profile, now - Duration::hours(24))?;
+        let week = self.samples_since(platform, profile, now - Duration::days(7))?;
+
+        let mut latencies: Vec<u64> = day
+            .iter()
+            .filter(|s| s.status.is_up())
+            .filter_map(|s| s.latency_ms)
+            .collect();
+        latencies.sort_unstable();
+
+        let last = self.latest(platform, profile, None)?;
+        let last_error = self.latest(platform, profile, Some("error IS NOT NULL"))?;
+
+        Ok(ProviderHealthStats {
+            platform: platform.to_string(),
+            profile: profile.to_string(),
+            samples_24h: day.len() as u64,
+            p50_ms: percentile(&latencies, 50.0),
+            p95_ms: percentile(&latencies, 95.0),
+            uptime_24h: uptime(&day),
+            uptime_7d: uptime(&week),
+            last_status: last.as_ref().map(|s| s.status.clone()),
+            last_checked_at: last.map(|s| s.checked_at),
+            last_error_at: last_error.as_ref().map(|s| s.checked_at),
+            last_error: last_error.and_then(|s| s.error),
+        })
+    }
+
+    /// 查询某个时间之后的样本（按时间升序）
+    pub fn samples_since(
+        &self,
+        platform: &str,
+        profile: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<HealthSample>> {
+        self.query_samples(
+            r#"
+            SELECT platform, profile, checked_at, status, latency_ms, error
+            FROM provider_health_samples
+            WHERE platform = ?1 AND profile = ?2 AND checked_at >= ?3
+            ORDER BY checked_at ASC, id ASC
+            "#,
+            rusqlite::params![platform, profile, format_datetime(&since)],
+        )
+    }
+
+    /// 查询最近的样本（按时间降序）
+    pub fn recent_samples(
+        &self,
+        platform: &str,
+        profile: &str,
+        limit: usize,
+    ) -> Result<Vec<HealthSample>> {
+        self.query_samples(
+            r#"
+            SELECT platform, profile, checked_at, status, latency_ms, error
+            FROM provider_health_samples
+            WHERE platform = ?1 AND profile = ?2
+            ORDER BY checked_at DESC, id DESC
+            LIMIT ?3
+            "#,
+            rusqlite::params![platform, profile, limit as i64],
+        )
+    }
+
+    /// 查询最近的状态变化（按时间降序）
+    pub fn transitions(
+        &self,
+        platform: &str,
+        profile: &str,
+        limit: usize,
+    ) -> Result<Vec<StatusTransition>> {
+        let conn = self.db.conn()?;
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT platform, profile, changed_at, from_status, to_status, error
+                FROM provider_health_transitions
+                WHERE platform = ?1 AND profile = ?2
+                ORDER BY changed_at DESC, id DESC
+                LIMIT ?3
+                "#,
+            )
+            .map_err(|e| CcrError::DatabaseError(format!("准备查询失败: {}", e)))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![platform, profile, limit as i64], |row| {
+                Ok(StatusTransition {
+                    platform: row.get(0)?,
+                    profile: row.get(1)?,
+                    changed_at: parse_datetime(&row.get::<_, String>(2)?),
+                    from_status: row
+                        .get::<_, Option<String>>(3)?
+                        .map(|s| HealthStatus::from_str_safe(&s)),
+                    to_status: HealthStatus::from_str_safe(&row.get::<_, String>(4)?),
+                    error: row.get(5)?,
+                })
+            })
+            .map_err(|e| CcrError::DatabaseError(format!("查询状态变化失败: {}", e)))?;
+
+        Ok(rows.flatten().collect())
+    }
+
+    /// 删除早于指定时间的样本与状态变化，返回删除的样本数
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<usize> {
+        let conn = self.db.conn()?;
+        let before = format_datetime(&before);
+        let removed = conn
+            .execute(
+                "DELETE FROM provider_health_samples WHERE checked_at < ?1",
+                [&before],
+            )
+            .map_err(|e| CcrError::DatabaseError(format!("清理健康检查样本失败: {}", e)))?;
+        conn.execute(
+            "DELETE FROM provider_health_transitions WHERE changed_at < ?1",
+            [&before],
+        )
+        .map_err(|e| CcrError::DatabaseError(format!("清理状态变化失败: {}", e)))?;
+
+        if removed > 0 {
+            debug!("已清理 {} 个过期健康检查样本", removed);
+        }
+        Ok(removed)
+    }
+
+    /// 最近一个（满足附加条件的）样本
+    fn latest(
+        &self,
+        platform: &str,
+        profile: &str,
+        condition: Option<&str>,
+    ) -> Result<Option<HealthSample>> {
+        let sql = format!(
+            r#"
+            SELECT platform, profile, checked_at, status, latency_ms, error
+            FROM provider_health_samples
+            WHERE platform = ?1 AND profile = ?2 {}
+            ORDER BY checked_at DESC, id DESC
+            LIMIT 1
+            "#,
+            condition.map(|c| format!("AND {}", c)).unwrap_or_default()
+        );
+        Ok(self
+            .query_samples(&sql, rusqlite::params![platform, profile])?
+            .pop())
+    }
+
+    fn query_samples(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<HealthSample>> {
+        let conn = self.db.conn()?;
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| CcrError::DatabaseError(format!("准备查询失败: {}", e)))?;
+
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok(HealthSample {
+                    platform: row.get(0)?,
+                    profile: row.get(1)?,
+                    checked_at: parse_datetime(&row.get::<_, String>(2)?),
+                    status: HealthStatus::from_str_safe(&row.get::<_, String>(3)?),
+                    latency_ms: row.get::<_, Option<i64>>(4)?.map(|l| l.max(0) as u64),
+                    error: row.get(5)?,
+                })
+            })
+            .map_err(|e| CcrError::DatabaseError(format!("查询健康检查样本失败: {}", e)))?;
+
+        Ok(rows.flatten().collect())
+    }
+}
+
+/// 最近秩法计算分位数（输入需已排序）
+fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// 可用样本占比（百分比）
+fn uptime(samples: &[HealthSample]) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let up = samples.iter().filter(|s| s.status.is_up()).count();
+    Some(up as f64 * 100.0 / samples.len() as f64)
+}
+
+/// 固定宽度的 UTC 时间格式，保证按字符串比较与按时间比较一致
+fn format_datetime(dt: &DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_datetime(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample(
+        at: DateTime<Utc>,
+        status: HealthStatus,
+        latency_ms: u64,
+        error: Option<&str>,
+    ) -> HealthSample {
+        HealthSample {
+            platform: "claude".into(),
+            profile: "alpha".into(),
+            checked_at: at,
+            status,
+            latency_ms: Some(latency_ms),
+            error: error.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_record_tracks_transitions_and_stats() {
+        let dir = tempdir().unwrap();
+        let db = Database::init(&dir.path().join("test.db")).unwrap();
+        let store = HealthStore::new(&db);
+        let now = Utc::now();
+
+        // 3 天前一次失败，之后 10 次成功（延迟 100..1000）和 1 次失败
+        let old = sample(
+            now - Duration::days(3),
+            HealthStatus::Unhealthy,
+            50,
+            Some("连接失败"),
+        );
+        assert!(store.record(&old).unwrap().is_some());
+        for i in 1..=10u64 {
+            let at = now - Duration::minutes(60 - i as i64);
+            let transition = store
+                .record(&sample(at, HealthStatus::Healthy, i * 100, None))
+                .unwrap();
+            assert_eq!(transition.is_some(), i == 1);
+        }
+        let failed = sample(now, HealthStatus::Unhealthy, 20, Some("HTTP 状态码: 502"));
+        let transition = store.record(&failed).unwrap().unwrap();
+        assert_eq!(transition.from_status, Some(HealthStatus::Healthy));
+
+        let stats = store.stats("claude", "alpha", now).unwrap();
+        assert_eq!(stats.samples_24h, 11);
+        assert_eq!(stats.p50_ms, Some(500));
+        assert_eq!(stats.p95_ms, Some(1000));
+        assert!((stats.uptime_24h.unwrap() - 1000.0 / 11.0).abs() < 1e-9);
+        assert!((stats.uptime_7d.unwrap() - 1000.0 / 12.0).abs() < 1e-9);
+        assert_eq!(stats.last_status, Some(HealthStatus::Unhealthy));
+        assert_eq!(stats.last_error.as_deref(), Some("HTTP 状态码: 502"));
+
+        assert_eq!(store.transitions("claude", "alpha", 10).unwrap().len(), 3);
+        assert_eq!(store.prune(now - Duration::days(1)).unwrap(), 1);
+        assert_eq!(
+            store.stats("claude", "alpha", now).unwrap().uptime_7d,
+            stats.uptime_24h
+        );
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        assert_eq!(percentile(&[], 50.0), None);
+        assert_eq!(percentile(&[7], 95.0), Some(7));
+        assert_eq!(percentile(&[1, 2, 3, 4], 50.0), Some(2));
+        assert_eq!(percentile(&[1, 2, 3, 4], 95.0), Some(4));
+    }
+}
@@ -6,6 +6,7 @@
 //!
 //! - [`database`] - 数据库连接管理和迁移
 //! - [`session_store`] - Session 存储层
+//! - [`health_store`] - Provider 健康检查历史
 //!
 //! ## 使用示例
 //!
@@ -20,7 +21,10 @@
 //! ```
 
 pub mod database;
+pub mod health_store;
 pub mod session_store;
 
 pub use database::Database;
+#[allow(unused_imports)]
+pub use health_store::HealthStore;
 pub use session_store::SessionStore;
@@ -0,0 +1,65 @@
+// 🔐 加密同步后端
+// 为任意后端（WebDAV / 本地目录 / Git / S3）包装端到端加密: 写入前加密，读取后解密
+//
+// 与 `ccr sync push` 使用相同的密文格式，未加密的远程条目原样读取（便于从明文迁移）
+
+use super::{BackendFuture, SyncBackend};
+use crate::sync::crypto::{SyncCipher, is_encrypted};
+use crate::sync::manifest::FileTree;
+
+/// 🔐 加密包装后端
+pub struct EncryptedBackend {
+    inner: Box<dyn SyncBackend>,
+    cipher: SyncCipher,
+}
+
+impl EncryptedBackend {
+    pub fn new(inner: Box<dyn SyncBackend>, cipher: SyncCipher) -> Self {
+        Self { inner, cipher }
+    }
+}
+
+impl SyncBackend for EncryptedBackend {
+    fn kind(&self) -> &'static str {
+        self.inner.kind()
+    }
+
+    fn location(&self) -> String {
+        self.inner.location()
+    }
+
+    fn is_single_file(&self) -> bool {
+        self.inner.is_single_file()
+    }
+
+    fn exists(&self) -> BackendFuture<'_, bool> {
+        self.inner.exists()
+    }
+
+    fn fetch(&self) -> BackendFuture<'_, FileTree> {
+        Box::pin(async move {
+            let mut tree = self.inner.fetch().await?;
+            for content in tree.values_mut() {
+                if is_encrypted(content) {
+                    *content = self.cipher.decrypt(content)?;
+                }
+            }
+            Ok(tree)
+        })
+    }
+
+    fn put<'a>(&'a self, rel_path: &'a str, content: Vec<u8>) -> BackendFuture<'a, ()> {
+        Box::pin(async move {
+            let sealed = self.cipher.encrypt(&content)?;
+            self.inner.put(rel_path, sealed).await
+        })
+    }
+
+    fn delete<'a>(&'a self, rel_path: &'a str) -> BackendFuture<'a, ()> {
+        self.inner.delete(rel_path)
+    }
+
+    fn commit<'a>(&'a self, message: &'a str) -> BackendFuture<'a, ()> {
+        self.inner.commit(message)
+    }
+}
@@ -0,0 +1,260 @@
+// 🌿 Git 同步后端
+// 通过系统 git 命令把同步内容存入一个 Git 仓库：每次上传生成一个提交并推送，
+// 远程仓库因此天然保留完整的同步历史
+//
+// 本地维护一个工作副本（默认 ~/.ccr/.sync_git/<url 哈希>），
+// 每轮操作开始前与远程分支对齐，写入后在 commit() 中提交并推送。
+
+use super::{BackendFuture, SyncBackend, normalize_prefix};
+use crate::core::error::{CcrError, Result};
+use crate::sync::manifest::{FileTree, read_tree};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Mutex;
+
+/// 👤 仓库未配置提交身份时使用的默认身份
+const FALLBACK_USER_NAME: &str = "ccr-sync";
+const FALLBACK_USER_EMAIL: &str = "ccr-sync@localhost";
+
+/// 🌿 Git 仓库后端
+pub struct GitBackend {
+    url: String,
+    branch: String,
+    /// 仓库内的子目录（空字符串表示仓库根目录）
+    subdir: String,
+    workdir: PathBuf,
+    /// 工作副本是否已与远程对齐（commit 后重置）
+    prepared: Mutex<bool>,
+}
+
+impl GitBackend {
+    /// 🏗️ 创建 Git 后端
+    ///
+    /// # 参数
+    /// - url: 远程仓库地址（任何 git 支持的 URL 或本地路径）
+    /// - branch: 同步使用的分支
+    /// - subdir: 仓库内存放内容的子目录（即文件夹的 remote_path）
+    /// - workdir: 本地工作副本目录
+    pub fn new(url: &str, branch: &str, subdir: &str, workdir: impl Into<PathBuf>) -> Self {
+        Self {
+            url: url.to_string(),
+            branch: branch.to_string(),
+            subdir: normalize_prefix(subdir).to_string(),
+            workdir: workdir.into(),
+            prepared: Mutex::new(false),
+        }
+    }
+
+    /// 📂 工作副本中同步内容所在目录
+    fn content_dir(&self) -> PathBuf {
+        self.subdir
+            .split('/')
+            .filter(|seg| !seg.is_empty())
+            .fold(self.workdir.clone(), |p, seg| p.join(seg))
+    }
+
+    fn entry_path(&self, rel_path: &str) -> PathBuf {
+        rel_path
+            .split('/')
+            .fold(self.content_dir(), |p, seg| p.join(seg))
+    }
+
+    /// 🔧 在工作副本中执行 git 命令，失败时返回带 stderr 的错误
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let output = self.run(&self.workdir, args)?;
+        if !output.status.success() {
+            return Err(CcrError::SyncError(format!(
+                "git {} 失败: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn run(&self, dir: &Path, args: &[&str]) -> Result<Output> {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .output()
+            .map_err(|e| {
+                CcrError::SyncError(format!("无法执行 git 命令（是否已安装 git？）: {}", e))
+            })
+    }
+
+    /// 🔄 确保工作副本存在并与远程分支对齐
+    ///
+    /// 读取操作 (force) 总是重新对齐；写入操作只在本轮尚未对齐时执行，
+    /// 以免丢弃尚未提交的写入
+    fn prepare(&self, force: bool) -> Result<()> {
+        let mut prepared = self
+            .prepared
+            .lock()
+            .map_err(|_| CcrError::SyncError("Git 工作副本状态不可用".into()))?;
+        if *prepared && !force {
+            return Ok(());
+        }
+
+        if !self.workdir.join(".git").exists() {
+            if let Some(parent) = self.workdir.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            tracing::debug!(
+                "🌿 克隆 Git 仓库: {} -> {}",
+                self.url,
+                self.workdir.display()
+            );
+            let parent = self.workdir.parent().unwrap_or(Path::new("."));
+            let workdir = self.workdir.to_string_lossy();
+            let output = self.run(parent, &["clone", "--quiet", &self.url, &workdir])?;
+            if !output.status.success() {
+                return Err(CcrError::SyncError(format!(
+                    "克隆 Git 仓库 {} 失败: {}",
+                    self.url,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+        } else {
+            self.git(&["remote", "set-url", "origin", &self.url])?;
+            self.git(&["fetch", "--quiet", "origin"])?;
+        }
+
+        let remote_ref = format!("refs/remotes/origin/{}", self.branch);
+        let has_remote_branch = self
+            .run(
+                &self.workdir,
+                &["rev-parse", "--verify", "--quiet", &remote_ref],
+            )?
+            .status
+            .success();
+
+        if has_remote_branch {
+            // 丢弃工作副本中的任何残留改动，以远程分支为准
+            self.git(&["checkout", "--quiet", "-B", &self.branch, &remote_ref])?;
+            self.git(&["reset", "--quiet", "--hard", &remote_ref])?;
+            self.git(&["clean", "--quiet", "-fd"])?;
+        } else {
+            // 空仓库或分支尚未创建：首次提交时创建
+            self.git(&[
+                "symbolic-ref",
+                "HEAD",
+                &format!("refs/heads/{}", self.branch),
+            ])?;
+        }
+
+        *prepared = true;
+        Ok(())
+    }
+
+    /// ✅ 提交暂存的改动并推送到远程分支
+    fn commit_and_push(&self, message: &str) -> Result<()> {
+        self.prepare(false)?;
+
+        self.git(&["add", "--all"])?;
+
+        let has_changes = !self
+            .run(&self.workdir, &["diff", "--cached", "--quiet"])?
+            .status
+            .success();
+
+        if has_changes {
+            let mut args: Vec<String> = Vec::new();
+            if self
+                .git(&["config", "user.email"])
+                .unwrap_or_default()
+                .is_empty()
+            {
+                args.extend([
+                    "-c".to_string(),
+                    format!("user.name={}", FALLBACK_USER_NAME),
+                    "-c".to_string(),
+                    format!("user.email={}", FALLBACK_USER_EMAIL),
+                ]);
+            }
+            args.extend(["commit", "--quiet", "-m", message].map(String::from));
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            self.git(&args)?;
+
+            self.git(&[
+                "push",
+                "--quiet",
+                "origin",
+                &format!("HEAD:refs/heads/{}", self.branch),
+            ])?;
+            tracing::info!("🌿 已推送同步提交到 {} ({})", self.url, self.branch);
+        }
+
+        // 下一轮操作重新与远程对齐
+        if let Ok(mut prepared) = self.prepared.lock() {
+            *prepared = false;
+        }
+        Ok(())
+    }
+}
+
+impl SyncBackend for GitBackend {
+    fn kind(&self) -> &'static str {
+        "git"
+    }
+
+    fn location(&self) -> String {
+        if self.subdir.is_empty() {
+            format!("{}#{}", self.url, self.branch)
+        } else {
+            format!("{}#{}:{}", self.url, self.branch, self.subdir)
+        }
+    }
+
+    fn exists(&self) -> BackendFuture<'_, bool> {
+        Box::pin(async move {
+            self.prepare(true)?;
+            let dir = self.content_dir();
+            Ok(dir.is_dir() && !read_tree(&dir)?.is_empty())
+        })
+    }
+
+    fn fetch(&self) -> BackendFuture<'_, FileTree> {
+        Box::pin(async move {
+            self.prepare(true)?;
+            let dir = self.content_dir();
+            if !dir.is_dir() {
+                return Ok(FileTree::new());
+            }
+            read_tree(&dir)
+        })
+    }
+
+    fn put<'a>(&'a self, rel_path: &'a str, content: Vec<u8>) -> BackendFuture<'a, ()> {
+        Box::pin(async move {
+            self.prepare(false)?;
+            let path = self.entry_path(rel_path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)
+                .map_err(|e| CcrError::SyncError(format!("写入 {} 失败: {}", path.display(), e)))
+        })
+    }
+
+    fn delete<'a>(&'a self, rel_path: &'a str) -> BackendFuture<'a, ()> {
+        Box::pin(async move {
+            self.prepare(false)?;
+            let path = self.entry_path(rel_path);
+            match std::fs::remove_file(&path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(CcrError::SyncError(format!(
+                    "删除 {} 失败: {}",
+                    path.display(),
+                    e
+                ))),
+            }
+        })
+    }
+
+    fn commit<'a>(&'a self, message: &'a str) -> BackendFuture<'a, ()> {
+        Box::pin(async move { self.commit_and_push(message) })
+    }
+}
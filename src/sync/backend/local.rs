@@ -0,0 +1,99 @@
+// 📂 本地目录同步后端
+// 将同步内容直接写入本机的一个目录（通常由 Syncthing / Dropbox / iCloud 等负责跨机同步）
+
+use super::{BackendFuture, SyncBackend};
+use crate::core::error::CcrError;
+use crate::sync::manifest::{FileTree, read_tree};
+use std::path::{Path, PathBuf};
+
+/// 📂 本地目录后端
+pub struct LocalDirBackend {
+    root: PathBuf,
+}
+
+impl LocalDirBackend {
+    /// 🏗️ 以指定目录为同步根创建后端（目录不存在时在首次写入时创建）
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn entry_path(&self, rel_path: &str) -> PathBuf {
+        rel_path
+            .split('/')
+            .fold(self.root.clone(), |p, seg| p.join(seg))
+    }
+}
+
+impl SyncBackend for LocalDirBackend {
+    fn kind(&self) -> &'static str {
+        "local"
+    }
+
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn exists(&self) -> BackendFuture<'_, bool> {
+        Box::pin(async move { Ok(self.root.is_dir()) })
+    }
+
+    fn fetch(&self) -> BackendFuture<'_, FileTree> {
+        Box::pin(async move {
+            if !self.root.is_dir() {
+                return Ok(FileTree::new());
+            }
+            read_tree(&self.root)
+        })
+    }
+
+    fn put<'a>(&'a self, rel_path: &'a str, content: Vec<u8>) -> BackendFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.entry_path(rel_path);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                    CcrError::SyncError(format!("创建目录失败 {}: {}", parent.display(), e))
+                })?;
+            }
+
+            // 先写临时文件再重命名，避免同步盘读到写了一半的文件
+            let temp_path = path.with_extension("ccr-tmp");
+            tokio::fs::write(&temp_path, content)
+                .await
+                .map_err(|e| CcrError::SyncError(format!("写入 {} 失败: {}", path.display(), e)))?;
+            tokio::fs::rename(&temp_path, &path)
+                .await
+                .map_err(|e| CcrError::SyncError(format!("写入 {} 失败: {}", path.display(), e)))
+        })
+    }
+
+    fn delete<'a>(&'a self, rel_path: &'a str) -> BackendFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.entry_path(rel_path);
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+                Err(e) => {
+                    return Err(CcrError::SyncError(format!(
+                        "删除 {} 失败: {}",
+                        path.display(),
+                        e
+                    )));
+                }
+            }
+
+            remove_empty_parents(&path, &self.root);
+            Ok(())
+        })
+    }
+}
+
+/// 🧹 删除文件后清理变空的父目录（不超出同步根）
+fn remove_empty_parents(path: &Path, root: &Path) {
+    let mut current = path.parent();
+    while let Some(dir) = current {
+        if dir == root || !dir.starts_with(root) || std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
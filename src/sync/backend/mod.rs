@@ -0,0 +1,267 @@
+// 🔌 CCR 同步后端
+// 📁 将同步存储抽象为 SyncBackend trait，每个同步文件夹可独立选择后端
+//
+// 内置实现:
+// - ☁️ WebDAV      (SyncService，默认)
+// - 📂 本地目录    (LocalDirBackend，适用于 Syncthing / Dropbox 等同步盘)
+// - 🌿 Git 仓库    (GitBackend，每次上传一个提交，天然带历史)
+// - 🪣 S3 兼容存储 (S3Backend，AWS S3 / MinIO / R2 等)
+//
+// 所有后端都以「相对路径 → 文件内容」的文件树为单位读写，
+// 与同步清单 (manifest) 的 FileTree 保持一致。
+//
+// 启用同步加密时，所有后端（含 WebDAV）统一由 EncryptedBackend 包装加密。
+
+mod encrypted;
+mod git;
+mod local;
+mod s3;
+mod webdav;
+
+pub use encrypted::EncryptedBackend;
+pub use git::GitBackend;
+pub use local::LocalDirBackend;
+pub use s3::S3Backend;
+
+use crate::core::error::{CcrError, Result};
+use crate::sync::config::SyncConfig;
+use crate::sync::crypto::SyncCipher;
+use crate::sync::folder::{SyncBackendConfig, SyncFolder, WebDavConfig};
+use crate::sync::manifest::{FileTree, read_tree};
+use crate::sync::service::{SyncService, get_ccr_sync_path};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+/// ⏳ 后端异步操作的返回类型（boxed future，保证 trait 可作为 `dyn` 使用）
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// 🔌 同步后端
+///
+/// 路径均为相对同步根的 `/` 分隔路径
+pub trait SyncBackend: Send + Sync {
+    /// 🏷️ 后端类型名称（webdav / local / git / s3）
+    fn kind(&self) -> &'static str;
+
+    /// 📍 远程位置描述（用于显示，并作为同步清单的远程标识）
+    fn location(&self) -> String;
+
+    /// 📄 远程是否为单文件模式（整个文件树只有一个条目，直接对应远程文件）
+    fn is_single_file(&self) -> bool {
+        false
+    }
+
+    /// 🔍 检查远程内容是否存在
+    fn exists(&self) -> BackendFuture<'_, bool>;
+
+    /// 📥 读取远程完整文件树（远程不存在时返回空树）
+    fn fetch(&self) -> BackendFuture<'_, FileTree>;
+
+    /// 🔼 写入单个条目（自动创建所需的父目录）
+    fn put<'a>(&'a self, rel_path: &'a str, content: Vec<u8>) -> BackendFuture<'a, ()>;
+
+    /// 🗑️ 删除单个条目（条目不存在时视为成功）
+    fn delete<'a>(&'a self, rel_path: &'a str) -> BackendFuture<'a, ()>;
+
+    /// ✅ 提交本次写入
+    ///
+    /// Git 后端在此生成提交并推送；其余后端写入即生效，无需处理
+    fn commit<'a>(&'a self, _message: &'a str) -> BackendFuture<'a, ()> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 🏭 为同步文件夹创建对应的后端
+///
+/// # 参数
+/// - folder: 同步文件夹配置（`remote_path` 在各后端中作为子路径 / 前缀）
+/// - webdav: 共享的 WebDAV 配置（仅 WebDAV 后端使用）
+/// - cipher: 同步加密器（None 表示明文同步），对所有后端生效
+pub async fn create_backend(
+    folder: &SyncFolder,
+    webdav: &WebDavConfig,
+    cipher: Option<SyncCipher>,
+) -> Result<Box<dyn SyncBackend>> {
+    let local_path = folder.expand_local_path()?;
+
+    let backend: Box<dyn SyncBackend> = match &folder.backend {
+        SyncBackendConfig::Webdav => {
+            let mut remote_path = folder.remote_path.clone();
+            if !is_file_target(&local_path) && !remote_path.ends_with('/') {
+                remote_path.push('/');
+            }
+
+            let sync_config = SyncConfig {
+                enabled: true,
+                webdav_url: webdav.url.clone(),
+                username: webdav.username.clone(),
+                password: webdav.password.clone(),
+                remote_path,
+                auto_sync: false,
+                encryption: false,
+            };
+            Box::new(SyncService::new(&sync_config).await?)
+        }
+        SyncBackendConfig::Local { path } => {
+            let root = crate::sync::folder::expand_path(path)?;
+            Box::new(LocalDirBackend::new(join_remote(
+                &root,
+                &folder.remote_path,
+            )))
+        }
+        SyncBackendConfig::Git { url, branch } => {
+            let workdir = git_workdir_for(url)?;
+            Box::new(GitBackend::new(url, branch, &folder.remote_path, workdir))
+        }
+        SyncBackendConfig::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key,
+            secret_key,
+        } => {
+            let access_key = credential(access_key, "AWS_ACCESS_KEY_ID")?;
+            let secret_key = credential(secret_key, "AWS_SECRET_ACCESS_KEY")?;
+            Box::new(S3Backend::new(
+                endpoint,
+                bucket,
+                region,
+                &access_key,
+                &secret_key,
+                &folder.remote_path,
+            )?)
+        }
+    };
+
+    Ok(match cipher {
+        Some(cipher) => Box::new(EncryptedBackend::new(backend, cipher)),
+        None => backend,
+    })
+}
+
+/// 🔼 将本地路径完整上传到后端（远程多余的条目会被删除）
+///
+/// # 返回
+/// 上传与删除的条目数量
+pub async fn push_tree(backend: &dyn SyncBackend, local_path: &Path) -> Result<(usize, usize)> {
+    let local = read_tree(local_path)?;
+    let remote = backend.fetch().await?;
+
+    let mut uploaded = 0;
+    for (rel, content) in &local {
+        if remote.get(rel) != Some(content) {
+            backend.put(rel, content.clone()).await?;
+            uploaded += 1;
+        }
+    }
+
+    let mut deleted = 0;
+    if !backend.is_single_file() {
+        for rel in remote.keys().filter(|rel| !local.contains_key(*rel)) {
+            backend.delete(rel).await?;
+            deleted += 1;
+        }
+    }
+
+    Ok((uploaded, deleted))
+}
+
+/// 🔽 将后端内容完整下载到本地路径
+///
+/// 单文件模式（或本地为文件）时直接写入该文件，否则按目录写入
+///
+/// # 返回
+/// 写入的文件数量
+pub async fn pull_tree(backend: &dyn SyncBackend, local_path: &Path) -> Result<usize> {
+    let remote = backend.fetch().await?;
+
+    if (backend.is_single_file() || local_path.is_file()) && remote.len() == 1 {
+        if let Some(content) = remote.values().next() {
+            write_file(local_path, content)?;
+        }
+        return Ok(1);
+    }
+
+    for (rel, content) in &remote {
+        let target = rel
+            .split('/')
+            .fold(local_path.to_path_buf(), |p, seg| p.join(seg));
+        write_file(&target, content)?;
+    }
+    Ok(remote.len())
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// 📄 判断同步目标是否为单个文件
+///
+/// 本地不存在时按文件名是否带扩展名推断
+fn is_file_target(local_path: &Path) -> bool {
+    if local_path.exists() {
+        local_path.is_file()
+    } else {
+        local_path.extension().is_some()
+    }
+}
+
+/// 📍 将文件夹的 remote_path 拼接到后端根目录下
+fn join_remote(root: &Path, remote_path: &str) -> PathBuf {
+    normalize_prefix(remote_path)
+        .split('/')
+        .filter(|seg| !seg.is_empty())
+        .fold(root.to_path_buf(), |p, seg| p.join(seg))
+}
+
+/// ✂️ 规范化远程子路径（去除首尾的 `/`）
+pub(crate) fn normalize_prefix(remote_path: &str) -> &str {
+    remote_path.trim_matches('/')
+}
+
+/// 📂 Git 后端的本地工作副本目录: <CCR 根目录>/.sync_git/<url 哈希>
+///
+/// 以 `.` 开头，不会被 CCR 根目录自身的同步收录
+fn git_workdir_for(url: &str) -> Result<PathBuf> {
+    let hash = blake3::hash(url.as_bytes()).to_hex();
+    Ok(get_ccr_sync_path()?.join(".sync_git").join(&hash[..16]))
+}
+
+/// 🔑 读取 S3 凭证（配置为空时回退到环境变量）
+fn credential(configured: &Option<String>, env_var: &str) -> Result<String> {
+    configured
+        .clone()
+        .filter(|v| !v.is_empty())
+        .or_else(|| std::env::var(env_var).ok().filter(|v| !v.is_empty()))
+        .ok_or_else(|| {
+            CcrError::SyncError(format!(
+                "缺少 S3 凭证: 请在文件夹配置中设置，或设置环境变量 {}",
+                env_var
+            ))
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_remote_and_file_target() {
+        let root = Path::new("/data/sync");
+        assert_eq!(
+            join_remote(root, "/ccr-sync/claude/"),
+            PathBuf::from("/data/sync/ccr-sync/claude")
+        );
+        assert_eq!(join_remote(root, "/"), PathBuf::from("/data/sync"));
+
+        let temp = tempfile::TempDir::new().unwrap();
+        assert!(!is_file_target(temp.path()));
+        assert!(is_file_target(&temp.path().join("missing.json")));
+        assert!(!is_file_target(&temp.path().join("missing-dir")));
+    }
+}
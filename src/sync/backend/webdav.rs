@@ -0,0 +1,36 @@
+// ☁️ WebDAV 同步后端
+// SyncService 本身即为 WebDAV 后端，这里将其已有能力适配到 SyncBackend trait
+
+use super::{BackendFuture, SyncBackend};
+use crate::sync::manifest::FileTree;
+use crate::sync::service::SyncService;
+
+impl SyncBackend for SyncService {
+    fn kind(&self) -> &'static str {
+        "webdav"
+    }
+
+    fn location(&self) -> String {
+        self.endpoint().to_string()
+    }
+
+    fn is_single_file(&self) -> bool {
+        !self.remote_path().ends_with('/')
+    }
+
+    fn exists(&self) -> BackendFuture<'_, bool> {
+        Box::pin(self.remote_exists())
+    }
+
+    fn fetch(&self) -> BackendFuture<'_, FileTree> {
+        Box::pin(self.fetch_snapshot())
+    }
+
+    fn put<'a>(&'a self, rel_path: &'a str, content: Vec<u8>) -> BackendFuture<'a, ()> {
+        Box::pin(self.push_entry(rel_path, content))
+    }
+
+    fn delete<'a>(&'a self, rel_path: &'a str) -> BackendFuture<'a, ()> {
+        Box::pin(self.delete_entry(rel_path))
+    }
+}
@@ -4,10 +4,17 @@
 use crate::core::error::{CcrError, Result};
 use crate::core::logging::ColorOutput;
 use crate::services::MultiBackupService;
+use crate::sync::backend::{SyncBackend, create_backend, pull_tree, push_tree};
 use crate::sync::config::{SyncConfig, SyncConfigManager};
 use crate::sync::content_selector::{SyncContentSelection, SyncContentSelector};
-use crate::sync::folder::SyncFolder;
+use crate::sync::crypto::{SyncCipher, passphrase_from_env};
+use crate::sync::folder::{SyncBackendConfig, SyncFolder};
 use crate::sync::folder_manager::SyncFolderManager;
+use crate::sync::manifest::{
+    FileStatus, FileSyncState, FileTree, SyncManifest, SyncStateStore, diff_trees, read_tree,
+};
+use crate::sync::merge::{ConflictChoice, MergeConflict};
+use crate::sync::reconcile::{ConflictResolver, PreferRemote, apply_pull, plan_push, record_push};
 use crate::sync::service::SyncService;
 use colored::Colorize;
 use comfy_table::{
@@ -38,6 +45,7 @@ fn show_sync_help() {
     );
     println!("  {}  上传配置文件到云端", "ccr sync push".cyan());
     println!("  {}  从云端下载配置文件", "ccr sync pull".cyan());
+    println!("  {}  启用或轮换端到端加密口令", "ccr sync rekey".cyan());
     println!("  {}  显示此帮助信息", "ccr sync help".cyan());
     println!();
 
@@ -101,6 +109,10 @@ fn show_sync_help() {
         "     {}",
         "ccr sync folder add conf ~/.ccr/config.toml -d \"主配置文件\"".dimmed()
     );
+    println!(
+        "     {}",
+        "ccr sync folder add ccr ~/.ccr --backend git --target <仓库地址>".dimmed()
+    );
     println!();
     println!("  {}  上传文件夹到云端", "3.".yellow());
     println!(
@@ -147,6 +159,14 @@ fn show_sync_help() {
         "  {}  指定文件夹描述 (folder add 命令)",
         "-d, --description".cyan()
     );
+    println!(
+        "  {}         选择存储后端 webdav/local/git/s3 (folder add 命令)",
+        "--backend".cyan()
+    );
+    println!(
+        "  {}          后端位置: 目录 / 仓库地址 / S3 服务地址 (folder add 命令)",
+        "--target".cyan()
+    );
     println!();
 
     println!("{}", "特性:".green().bold());
@@ -154,6 +174,9 @@ fn show_sync_help() {
     println!("  🔄 细粒度控制     - 可以单独启用/禁用文件夹");
     println!("  💾 自动备份       - 下载前自动备份本地内容");
     println!("  🔒 安全操作       - 支持 WebDAV 认证和 HTTPS");
+    println!("  🔀 三方合并       - 基于同步清单合并多台机器的修改");
+    println!("  🔐 端到端加密     - 口令派生密钥加密，服务器只能看到密文");
+    println!("  🔌 多种后端       - 每个文件夹可选 WebDAV、本地目录、Git 仓库或 S3");
     println!();
 
     println!("{}", "支持的 WebDAV 服务:".green().bold());
@@ -211,6 +234,11 @@ pub async fn sync_config_command() -> Result<()> {
         password: password.clone(),
         remote_path: remote_path.clone(),
         auto_sync: false,
+        // 保留已有的加密设置（口令轮换/启用通过 'ccr sync rekey'）
+        encryption: SyncConfigManager::with_default()
+            .and_then(|m| m.load())
+            .map(|c| c.encryption)
+            .unwrap_or(false),
     };
 
     // 测试连接
@@ -326,19 +354,59 @@ pub async fn sync_status_command() -> Result<()> {
             Cell::new(auto_sync_text).fg(auto_sync_color),
         ]);
 
+        // 🔐 端到端加密
+        let (encryption_text, encryption_color) = if sync_config.encryption {
+            ("✓ 开启", TableColor::Green)
+        } else {
+            ("✗ 关闭", TableColor::DarkGrey)
+        };
+        table.add_row(vec![
+            Cell::new("端到端加密"),
+            Cell::new(encryption_text).fg(encryption_color),
+        ]);
+
         println!("{}", table);
         println!();
 
+        let service = open_sync_service(&sync_config).await?;
+
         // 检查远程文件状态
         print!("🔍 正在检查远程状态...");
         std::io::Write::flush(&mut std::io::stdout()).expect("无法刷新标准输出");
 
-        let service = SyncService::new(&sync_config).await?;
         let exists = service.remote_exists().await?;
 
         print!("\r");
         if exists {
             println!("{}  {}", "✓".green().bold(), "远程内容存在".green());
+            println!();
+
+            // 🧾 逐文件对比本地、远程与上次同步的 base
+            let snapshot = load_sync_snapshot(&service, &sync_path).await?;
+            let changed: Vec<&FileStatus> = snapshot
+                .statuses
+                .iter()
+                .filter(|s| s.state != FileSyncState::InSync)
+                .collect();
+            let count = |state: FileSyncState| changed.iter().filter(|s| s.state == state).count();
+
+            println!(
+                "📊 文件状态: 已同步 {} / 本地领先 {} / 远程领先 {} / 冲突 {}",
+                (snapshot.statuses.len() - changed.len())
+                    .to_string()
+                    .green(),
+                count(FileSyncState::Ahead).to_string().cyan(),
+                count(FileSyncState::Behind).to_string().blue(),
+                count(FileSyncState::Conflicted).to_string().yellow()
+            );
+            if !changed.is_empty() {
+                print_file_status_table(&changed);
+                println!(
+                    "   💡 运行 {} 上传本地修改，{} 合并远程修改",
+                    "ccr sync push".cyan(),
+                    "ccr sync pull".cyan()
+                );
+            }
         } else {
             println!("{}  {}", "⚠".yellow().bold(), "远程内容不存在".yellow());
             println!("   💡 提示: 运行 {} 首次上传", "ccr sync push".cyan());
@@ -419,38 +487,52 @@ pub async fn sync_push_command_with_selection(
     println!("   → 远程路径: {}", sync_config.remote_path.cyan());
     println!();
 
-    let service = SyncService::new(&sync_config).await?;
+    let service = open_sync_service(&sync_config).await?;
 
-    if !force {
-        print!("🔍 正在检查远程状态...");
-        let _ = io::stdout().flush();
-
-        let exists = service.remote_exists().await?;
+    // 🔍 对比本地、远程与上次同步的 base
+    print!("🔍 正在对比本地与远程...");
+    let _ = io::stdout().flush();
+    let mut snapshot = load_sync_snapshot(&service, &sync_path).await?;
+    print!("\r");
 
-        print!("\r");
-        if exists {
-            println!("{}  {}", "⚠".yellow().bold(), "远程已存在同名内容".yellow());
-            println!();
-            print!("   是否覆盖远程配置？ {} ", "(y/N):".dimmed());
-            let _ = io::stdout().flush();
+    // 🎯 根据选择的内容类型过滤上传路径
+    let filtered_paths = content_selection.to_paths();
+    if filtered_paths.is_empty() {
+        ColorOutput::warning("未选择任何同步内容，操作取消");
+        return Ok(());
+    }
 
-            let mut confirm = String::new();
-            io::stdin().read_line(&mut confirm)?;
+    // 文件模式下文件树只包含同步文件本身，无需按内容过滤
+    let selected = if is_dir {
+        Some(filtered_paths.as_slice())
+    } else {
+        None
+    };
+    let plan = plan_push(&snapshot.statuses, &snapshot.local, selected, force);
 
-            if !confirm.trim().eq_ignore_ascii_case("y") {
-                println!();
-                println!("{}  已取消上传", "ℹ".blue().bold());
-                return Ok(());
-            }
-            println!();
-        } else {
-            println!(
-                "{}  {}",
-                "ℹ".blue().bold(),
-                "远程不存在，将创建新内容".blue()
-            );
-            println!();
+    if !plan.skipped.is_empty() {
+        ColorOutput::warning("以下文件远程有新的修改，已跳过上传以免覆盖:");
+        for status in &plan.skipped {
+            println!("   • {} ({})", status.path.yellow(), status.state);
         }
+        println!(
+            "   💡 先运行 {} 合并远程修改，或使用 {} 强制覆盖",
+            "ccr sync pull".cyan(),
+            "--force".cyan()
+        );
+        println!();
+    }
+
+    if plan.upload.is_empty() && plan.delete.is_empty() {
+        record_push(
+            &snapshot.store,
+            &mut snapshot.manifest,
+            &plan,
+            &snapshot.local,
+        )?;
+        ColorOutput::success("没有需要上传的本地修改");
+        println!();
+        return Ok(());
     }
 
     // 🧩 在上传前执行多类型增量备份（统一目录结构）
@@ -481,48 +563,46 @@ pub async fn sync_push_command_with_selection(
         println!();
     }
 
-    // 🎯 根据选择的内容类型过滤上传路径
-    let filtered_paths = content_selection.to_paths();
-    if filtered_paths.is_empty() {
-        ColorOutput::warning("未选择任何同步内容，操作取消");
-        return Ok(());
-    }
-
     println!("{}  将同步以下内容:", "🎯".blue());
-    for path in &filtered_paths {
-        println!("   • {}", path.cyan());
+    for path in &plan.upload {
+        println!("   ↑ {}", path.cyan());
+    }
+    for path in &plan.delete {
+        println!("   ✗ {}", path.red());
     }
     println!();
 
     print!("🚀 正在上传...");
     let _ = io::stdout().flush();
 
-    // 🎯 根据内容选择创建临时过滤目录进行同步
-    let temp_sync_path =
-        if filtered_paths.len() == 1 && filtered_paths[0] == "config.toml" && !is_dir {
-            // 如果是单个config文件且当前是文件模式，直接同步原文件
-            sync_path.clone()
-        } else {
-            // 需要创建临时目录包含选中的内容
-            create_temp_sync_directory(&sync_path, &filtered_paths, is_dir)?
-        };
-
-    print!("🚀 正在上传...");
-    let _ = io::stdout().flush();
-
-    service.push(&temp_sync_path, None).await?;
-
-    // 清理临时目录（如果需要）
-    if temp_sync_path != sync_path {
-        std::fs::remove_dir_all(&temp_sync_path).ok();
+    for path in &plan.upload {
+        if let Some(content) = snapshot.local.get(path) {
+            service.push_entry(path, content.clone()).await?;
+        }
     }
+    for path in &plan.delete {
+        service.delete_entry(path).await?;
+    }
+
+    // 🧾 更新同步清单
+    record_push(
+        &snapshot.store,
+        &mut snapshot.manifest,
+        &plan,
+        &snapshot.local,
+    )?;
 
     print!("\r");
-    if is_dir {
-        println!("{}  {}", "✓".green().bold(), "目录已成功上传到云端".green());
-    } else {
-        println!("{}  {}", "✓".green().bold(), "文件已成功上传到云端".green());
-    }
+    println!(
+        "{}  {}",
+        "✓".green().bold(),
+        format!(
+            "已上传 {} 个文件，删除 {} 个远程文件",
+            plan.upload.len(),
+            plan.delete.len()
+        )
+        .green()
+    );
     println!();
     println!("📊 同步信息:");
     println!("   • 本地路径: {}", sync_path.display().to_string().cyan());
@@ -534,6 +614,11 @@ pub async fn sync_push_command_with_selection(
 }
 
 /// 🔽 从云端下载配置
+///
+/// 基于同步清单进行三方合并:
+/// - 远程领先的文件直接应用
+/// - 两端都修改的 TOML 文件按键合并，真正的冲突逐个询问
+/// - force 模式下冲突一律采用远程内容，且不提示确认
 pub async fn sync_pull_command(force: bool) -> Result<()> {
     use colored::*;
 
@@ -553,17 +638,6 @@ pub async fn sync_pull_command(force: bool) -> Result<()> {
     let sync_path = get_ccr_sync_path()?;
     let is_dir = sync_path.is_dir();
 
-    // 💾 备份本地 config.toml 内容，避免在 pull 时被远程覆盖
-    let mut local_config_backup: Option<(PathBuf, String)> = None;
-    if is_dir {
-        let config_path = sync_path.join("config.toml");
-        if config_path.exists()
-            && let Ok(content) = std::fs::read_to_string(&config_path)
-        {
-            local_config_backup = Some((config_path, content));
-        }
-    }
-
     // 显示同步信息
     if is_dir {
         println!(
@@ -581,33 +655,14 @@ pub async fn sync_pull_command(force: bool) -> Result<()> {
     println!("   ← 远程路径: {}", sync_config.remote_path.cyan());
     println!();
 
-    // 备份本地配置
-    if !force {
-        println!(
-            "{}  {}",
-            "⚠".yellow().bold(),
-            "此操作将覆盖本地内容".yellow()
-        );
-        println!();
-        print!("   是否继续？本地内容将被备份 {} ", "(y/N):".dimmed());
-        let _ = io::stdout().flush();
-
-        let mut confirm = String::new();
-        io::stdin().read_line(&mut confirm)?;
-
-        if !confirm.trim().eq_ignore_ascii_case("y") {
-            println!();
-            println!("{}  已取消下载", "ℹ".blue().bold());
-            return Ok(());
-        }
-        println!();
-    }
-
-    // 🔍 检查远程是否存在（在备份前检查，避免不必要的备份）
-    let service = SyncService::new(&sync_config).await?;
-    let remote_exists = service.remote_exists().await?;
+    // 🔍 对比本地、远程与上次同步的 base
+    let service = open_sync_service(&sync_config).await?;
+    print!("🔍 正在对比本地与远程...");
+    let _ = io::stdout().flush();
+    let mut snapshot = load_sync_snapshot(&service, &sync_path).await?;
+    print!("\r");
 
-    if !remote_exists {
+    if snapshot.remote.is_empty() {
         println!();
         ColorOutput::error("远程目录不存在");
         println!();
@@ -617,42 +672,46 @@ pub async fn sync_pull_command(force: bool) -> Result<()> {
         return Err(CcrError::SyncError("远程内容不存在".to_string()));
     }
 
-    // 备份逻辑
-    if sync_path.exists() {
-        print!("💾 正在备份本地内容...");
-        let _ = io::stdout().flush();
-
-        // 如果是文件，使用 ConfigManager 的备份功能
-        // 如果是目录，创建带时间戳的 .bak 备份
-        let backup_path = if is_dir {
-            // 🏷️ 生成带时间戳的备份目录名，避免冲突
-            let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-            let backup_name = format!("{}.{}.bak", sync_path.display(), timestamp);
-            let backup = PathBuf::from(backup_name);
-
-            // 🔄 如果目标备份路径已存在（极少见），先删除
-            if backup.exists() {
-                std::fs::remove_dir_all(&backup)
-                    .map_err(|e| CcrError::SyncError(format!("删除旧备份失败: {}", e)))?;
-            }
+    let pending: Vec<&FileStatus> = snapshot
+        .statuses
+        .iter()
+        .filter(|s| matches!(s.state, FileSyncState::Behind | FileSyncState::Conflicted))
+        .collect();
+
+    if pending.is_empty() {
+        // 刷新 base，使已一致的文件在清单中对齐
+        apply_pull(
+            &sync_path,
+            &snapshot.store,
+            &mut snapshot.manifest,
+            &snapshot.local,
+            &snapshot.remote,
+            &mut PreferRemote,
+        )?;
+        ColorOutput::success("本地已是最新，没有需要下载的远程修改");
+        println!();
+        return Ok(());
+    }
 
-            // 📦 移动目录到备份位置（原子操作）
-            std::fs::rename(&sync_path, &backup)
-                .map_err(|e| CcrError::SyncError(format!("备份目录失败: {}", e)))?;
-            backup
-        } else {
-            // 对于单个配置文件，使用 ConfigManager 的备份功能
-            use crate::managers::config::ConfigManager;
-            let config_manager = ConfigManager::with_default()?;
-            config_manager.backup(Some("before_pull"))?
-        };
+    println!("{}  远程变更:", "📋".blue());
+    print_file_status_table(&pending);
+    println!();
 
-        print!("\r");
-        println!("{}  {}", "✓".green().bold(), "本地内容已备份".green());
-        println!(
-            "   📁 备份位置: {}",
-            backup_path.display().to_string().dimmed()
+    if !force {
+        print!(
+            "   是否应用以上远程变更？本地内容将先备份 {} ",
+            "(y/N):".dimmed()
         );
+        let _ = io::stdout().flush();
+
+        let mut confirm = String::new();
+        io::stdin().read_line(&mut confirm)?;
+
+        if !confirm.trim().eq_ignore_ascii_case("y") {
+            println!();
+            println!("{}  已取消下载", "ℹ".blue().bold());
+            return Ok(());
+        }
         println!();
     }
 
@@ -672,143 +731,293 @@ pub async fn sync_pull_command(force: bool) -> Result<()> {
         println!();
     }
 
-    print!("⬇️  正在从云端下载...");
-    let _ = io::stdout().flush();
-
-    service.pull(&sync_path).await?;
-
-    // 🔁 还原本地 config.toml，确保其不受远程内容影响
-    if let Some((config_path, content)) = local_config_backup
-        && let Some(parent) = config_path.parent()
-    {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            ColorOutput::warning(&format!("恢复本地 config.toml 目录失败: {}", e));
-        } else if let Err(e) = std::fs::write(&config_path, content) {
-            ColorOutput::warning(&format!("恢复本地 config.toml 失败: {}", e));
-        }
-    }
-
-    print!("\r");
-    if is_dir {
-        println!(
-            "{}  {}",
-            "✓".green().bold(),
-            "目录已从云端下载并应用".green()
-        );
+    let mut resolver: Box<dyn ConflictResolver> = if force {
+        Box::new(PreferRemote)
     } else {
+        Box::new(InteractiveResolver)
+    };
+    let report = apply_pull(
+        &sync_path,
+        &snapshot.store,
+        &mut snapshot.manifest,
+        &snapshot.local,
+        &snapshot.remote,
+        resolver.as_mut(),
+    )?;
+
+    println!();
+    println!("{}  {}", "✓".green().bold(), "远程变更已应用".green());
+    println!();
+    println!("📊 同步结果:");
+    println!("   • 已更新: {}", report.updated.len().to_string().cyan());
+    println!("   • 已删除: {}", report.deleted.len().to_string().cyan());
+    println!(
+        "   • 已合并: {} (解决冲突 {} 处)",
+        report.merged.len().to_string().cyan(),
+        report.resolved_conflicts
+    );
+    if !report.kept_local.is_empty() || !report.merged.is_empty() {
         println!(
-            "{}  {}",
-            "✓".green().bold(),
-            "文件已从云端下载并应用".green()
+            "   💡 本地仍有未上传的修改，运行 {} 同步到云端",
+            "ccr sync push".cyan()
         );
     }
     println!();
-    println!("📊 同步信息:");
-    println!("   • 本地路径: {}", sync_path.display().to_string().cyan());
-    println!("   • 远程路径: {}", sync_config.remote_path.cyan());
-    println!("   • 服务器: {}", sync_config.webdav_url.dimmed());
-    println!();
     println!("💡 下一步: 运行 {} 查看配置", "ccr list".cyan());
     println!();
 
     Ok(())
 }
 
-/// 🔼 上传配置到云端（向后兼容接口）
-///
-/// 默认使用 SyncContentSelection::default()（当前为平台目录）
-pub async fn sync_push_command(force: bool) -> Result<()> {
-    let default_selection = SyncContentSelection::default();
-    sync_push_command_with_selection(force, Some(default_selection)).await
+/// 🧾 同步对比快照
+struct SyncSnapshot {
+    store: SyncStateStore,
+    manifest: SyncManifest,
+    local: FileTree,
+    remote: FileTree,
+    statuses: Vec<FileStatus>,
 }
-fn create_temp_sync_directory(
-    original_path: &Path,
-    filtered_paths: &[String],
-    is_dir: bool,
-) -> Result<PathBuf> {
-    use tempfile::TempDir;
 
-    let temp_dir = TempDir::new().map_err(|e| {
-        CcrError::IoError(std::io::Error::other(format!("创建临时目录失败: {}", e)))
-    })?;
+/// 🔍 读取本地文件树、远程快照与同步清单并计算每个文件的状态
+async fn load_sync_snapshot(backend: &dyn SyncBackend, sync_path: &Path) -> Result<SyncSnapshot> {
+    let remote = backend.fetch().await?;
+    let local = read_tree(sync_path)?;
+    let store = SyncStateStore::for_root(sync_path);
+    let manifest = store.load_manifest(&backend.location())?;
+    let statuses = diff_trees(&manifest, &local, &remote);
+
+    Ok(SyncSnapshot {
+        store,
+        manifest,
+        local,
+        remote,
+        statuses,
+    })
+}
 
-    let temp_path = temp_dir.path().to_path_buf();
+/// 📊 打印文件同步状态表
+fn print_file_status_table(statuses: &[&FileStatus]) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["文件", "状态", "本地", "远程"]);
+
+    for status in statuses {
+        let color = match status.state {
+            FileSyncState::InSync => Color::Green,
+            FileSyncState::Ahead => Color::Cyan,
+            FileSyncState::Behind => Color::Blue,
+            FileSyncState::Conflicted => Color::Yellow,
+        };
+        table.add_row(vec![
+            Cell::new(&status.path),
+            Cell::new(status.state.to_string()).fg(color),
+            Cell::new(status.local.to_string()),
+            Cell::new(status.remote.to_string()),
+        ]);
+    }
 
-    // 保留临时目录的所有权，防止被自动删除
-    let temp_path_clone = temp_path.clone();
-    std::mem::forget(temp_dir);
+    println!("{}", table);
+}
 
-    if is_dir {
-        // 如果是目录模式，复制选中的内容到临时目录
-        let base_path = original_path;
-
-        for filtered_path in filtered_paths {
-            let source_path = base_path.join(filtered_path);
-            if source_path.exists() {
-                let target_path = temp_path.join(filtered_path);
-
-                if source_path.is_dir() {
-                    copy_directory_recursive(&source_path, &target_path)?;
-                } else {
-                    if let Some(parent) = target_path.parent() {
-                        std::fs::create_dir_all(parent)?;
-                    }
-                    std::fs::copy(&source_path, &target_path)?;
-                }
-            }
-        }
-    } else {
-        // 如果是文件模式，创建目录结构并复制选中的文件
-        let base_path = original_path
-            .parent()
-            .ok_or_else(|| CcrError::SyncError("无法获取配置文件父目录".into()))?;
-
-        for filtered_path in filtered_paths {
-            let source_path = if filtered_path == "config.toml" {
-                original_path.to_path_buf()
-            } else {
-                base_path.join(filtered_path)
-            };
+/// 🙋 交互式冲突解决（逐个询问保留本地还是采用远程）
+struct InteractiveResolver;
 
-            if source_path.exists() {
-                let target_path = temp_path.join(filtered_path);
+impl ConflictResolver for InteractiveResolver {
+    fn resolve_value(&mut self, file: &str, conflict: &MergeConflict) -> Result<ConflictChoice> {
+        use colored::*;
 
-                if let Some(parent) = target_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
+        let key = conflict.path.to_uppercase();
+        let describe = |value: Option<&toml::Value>| match value {
+            None => "(不存在)".to_string(),
+            Some(toml::Value::Table(_)) => "(表)".to_string(),
+            Some(v) => crate::utils::mask_if_sensitive(&key, &v.to_string()),
+        };
 
-                if source_path.is_dir() {
-                    copy_directory_recursive(&source_path, &target_path)?;
-                } else {
-                    std::fs::copy(&source_path, &target_path)?;
-                }
-            }
-        }
+        println!();
+        println!(
+            "{}  配置冲突: {} → {}",
+            "⚠".yellow().bold(),
+            file.cyan(),
+            conflict.path.bold()
+        );
+        println!("   上次同步: {}", describe(conflict.base.as_ref()).dimmed());
+        println!("   本地:     {}", describe(conflict.local.as_ref()));
+        println!("   远程:     {}", describe(conflict.remote.as_ref()));
+        prompt_conflict_choice()
     }
 
-    Ok(temp_path_clone)
+    fn resolve_file(&mut self, status: &FileStatus) -> Result<ConflictChoice> {
+        use colored::*;
+
+        println!();
+        println!(
+            "{}  文件冲突: {} (本地{} / 远程{})",
+            "⚠".yellow().bold(),
+            status.path.cyan(),
+            status.local,
+            status.remote
+        );
+        prompt_conflict_choice()
+    }
 }
 
-/// 📁 递归复制目录
-fn copy_directory_recursive(src: &Path, dst: &Path) -> Result<()> {
-    std::fs::create_dir_all(dst)?;
+/// ❓ 询问冲突的解决方式
+fn prompt_conflict_choice() -> Result<ConflictChoice> {
+    use colored::*;
 
-    for entry in std::fs::read_dir(src)? {
-        let entry = entry?;
-        let path = entry.path();
-        let file_name = entry.file_name();
-        let target_path = dst.join(&file_name);
+    loop {
+        print!(
+            "   保留本地 [l] / 采用远程 [r] / 取消 [q] {} ",
+            "(l/r/q):".dimmed()
+        );
+        let _ = io::stdout().flush();
 
-        if path.is_dir() {
-            copy_directory_recursive(&path, &target_path)?;
-        } else {
-            std::fs::copy(&path, &target_path)?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        match input.trim().to_lowercase().as_str() {
+            "l" | "local" => return Ok(ConflictChoice::KeepLocal),
+            "r" | "remote" => return Ok(ConflictChoice::UseRemote),
+            "q" | "quit" => return Err(CcrError::SyncError("已取消同步，未解决冲突".into())),
+            _ => ColorOutput::warning("请输入 l、r 或 q"),
         }
     }
+}
+
+/// 🔑 启用或轮换同步加密口令
+///
+/// 用当前口令下载并解密全部远程内容，再用新口令重新加密上传
+/// - 未启用加密时执行即为启用加密
+/// - disable 为 true 时以明文重新上传并关闭加密
+pub async fn sync_rekey_command(disable: bool) -> Result<()> {
+    use colored::*;
+
+    ColorOutput::title("🔑  同步加密口令");
+    println!();
+
+    let sync_manager = SyncConfigManager::with_default()?;
+    let mut sync_config = sync_manager.load()?;
+
+    if !sync_config.enabled {
+        return Err(CcrError::SyncError(
+            "同步功能未配置，请先运行 'ccr sync config'".into(),
+        ));
+    }
+
+    if disable && !sync_config.encryption {
+        ColorOutput::info("同步加密未启用，无需关闭");
+        return Ok(());
+    }
+
+    // 🔓 使用当前口令读取远程内容（口令错误会在这里失败，远程不受影响）
+    let current = open_sync_service(&sync_config).await?;
+    print!("⬇️  正在下载并解密远程内容...");
+    let _ = io::stdout().flush();
+    let remote = current.fetch_snapshot().await?;
+    print!("\r");
+    println!(
+        "{}  {}",
+        "✓".green().bold(),
+        format!("已读取 {} 个远程文件", remote.len()).green()
+    );
+    println!();
+
+    let mut target = SyncService::new(&sync_config).await?;
+    if !disable {
+        let passphrase = prompt_passphrase("新的同步口令", true)?;
+        target = target.with_cipher(SyncCipher::new(&passphrase)?);
+    }
+
+    print!("🚀 正在重新上传...");
+    let _ = io::stdout().flush();
+    for (path, content) in remote {
+        if let Err(e) = target.push_entry(&path, content).await {
+            println!();
+            ColorOutput::warning(
+                "部分远程文件已使用新设置上传，请修复问题后重新执行 'ccr sync rekey'",
+            );
+            return Err(e);
+        }
+    }
+    print!("\r");
+
+    sync_config.encryption = !disable;
+    sync_manager.save(&sync_config)?;
+
+    if disable {
+        println!("{}  {}", "✓".green().bold(), "已关闭端到端加密".green());
+    } else {
+        println!(
+            "{}  {}",
+            "✓".green().bold(),
+            "远程内容已使用新口令加密".green()
+        );
+        println!(
+            "   💡 其他机器需运行 {} 后使用新口令同步",
+            "ccr sync pull".cyan()
+        );
+    }
+    println!();
 
     Ok(())
 }
 
+/// 🔌 创建同步服务，启用加密时获取口令
+///
+/// 口令优先读取 CCR_SYNC_PASSPHRASE 环境变量，否则交互输入
+async fn open_sync_service(sync_config: &SyncConfig) -> Result<SyncService> {
+    let service = SyncService::new(sync_config).await?;
+    if !sync_config.encryption {
+        return Ok(service);
+    }
+
+    Ok(service.with_cipher(SyncCipher::new(&sync_passphrase()?)?))
+}
+
+/// 🔑 获取同步口令: 优先读取 CCR_SYNC_PASSPHRASE 环境变量，否则交互输入
+fn sync_passphrase() -> Result<String> {
+    match passphrase_from_env() {
+        Some(passphrase) => Ok(passphrase),
+        None => prompt_passphrase("同步口令", false),
+    }
+}
+
+/// 🔐 获取文件夹同步使用的口令
+///
+/// 与 `ccr sync push` 共用 sync.toml 中的加密开关，未启用加密时返回 None
+fn folder_sync_passphrase() -> Result<Option<String>> {
+    if !SyncConfigManager::with_default()?.load()?.encryption {
+        return Ok(None);
+    }
+    sync_passphrase().map(Some)
+}
+
+/// 🔐 为单个文件夹创建加密器（每个文件夹使用独立的 salt）
+fn folder_cipher(passphrase: Option<&str>) -> Result<Option<SyncCipher>> {
+    passphrase.map(SyncCipher::new).transpose()
+}
+
+/// 🔑 隐藏输入口令
+fn prompt_passphrase(prompt: &str, confirm: bool) -> Result<String> {
+    let mut input = dialoguer::Password::new().with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation("再次输入确认", "两次输入的口令不一致");
+    }
+    input
+        .interact()
+        .map_err(|e| CcrError::SyncError(format!("读取口令失败: {}", e)))
+}
+
+/// 🔼 上传配置到云端（向后兼容接口）
+///
+/// 默认使用 SyncContentSelection::default()（当前为平台目录）
+pub async fn sync_push_command(force: bool) -> Result<()> {
+    let default_selection = SyncContentSelection::default();
+    sync_push_command_with_selection(force, Some(default_selection)).await
+}
+
 // === 辅助函数 ===
 
 /// 🏠 获取 CCR 根目录路径
@@ -914,6 +1123,7 @@ pub fn sync_folder_list_command() -> Result<()> {
         Cell::new("状态").fg(Color::Cyan),
         Cell::new("本地路径").fg(Color::Cyan),
         Cell::new("远程路径").fg(Color::Cyan),
+        Cell::new("后端").fg(Color::Cyan),
         Cell::new("描述").fg(Color::Cyan),
     ]);
 
@@ -930,6 +1140,7 @@ pub fn sync_folder_list_command() -> Result<()> {
             status,
             Cell::new(&folder.local_path),
             Cell::new(&folder.remote_path),
+            Cell::new(folder.backend.kind()),
             Cell::new(&folder.description),
         ]);
     }
@@ -959,6 +1170,7 @@ pub fn sync_folder_add_command(
     local_path: &str,
     remote_path: Option<&String>,
     description: Option<&String>,
+    backend: SyncBackendConfig,
 ) -> Result<()> {
     ColorOutput::title(&format!("添加同步文件夹: {}", name));
     println!();
@@ -992,6 +1204,7 @@ pub fn sync_folder_add_command(
         .local_path(local_path)
         .remote_path(&final_remote_path)
         .enabled(true)
+        .backend(backend)
         .build()?;
 
     // 显示配置信息
@@ -1000,6 +1213,11 @@ pub fn sync_folder_add_command(
     println!("  描述:     {}", folder.description);
     println!("  本地路径: {}", folder.local_path);
     println!("  远程路径: {}", folder.remote_path);
+    println!(
+        "  存储后端: {} ({})",
+        folder.backend.kind(),
+        folder.backend.describe(&webdav_config)
+    );
     println!("  状态:     启用");
     println!();
 
@@ -1012,6 +1230,49 @@ pub fn sync_folder_add_command(
     Ok(())
 }
 
+/// 🔌 根据命令行参数构建文件夹的存储后端配置
+///
+/// # 参数
+/// - kind: webdav / local / git / s3
+/// - target: local 为目录路径，git 为仓库地址，s3 为服务地址
+pub fn folder_backend_from_args(
+    kind: &str,
+    target: Option<&str>,
+    branch: Option<&str>,
+    bucket: Option<&str>,
+    region: Option<&str>,
+) -> Result<SyncBackendConfig> {
+    let require_target = || {
+        target.map(str::to_string).ok_or_else(|| {
+            CcrError::ValidationError(format!("{} 后端需要通过 --target 指定位置", kind))
+        })
+    };
+
+    match kind {
+        "webdav" => Ok(SyncBackendConfig::Webdav),
+        "local" => Ok(SyncBackendConfig::Local {
+            path: require_target()?,
+        }),
+        "git" => Ok(SyncBackendConfig::Git {
+            url: require_target()?,
+            branch: branch.unwrap_or("main").to_string(),
+        }),
+        "s3" => Ok(SyncBackendConfig::S3 {
+            endpoint: require_target()?,
+            bucket: bucket.map(str::to_string).ok_or_else(|| {
+                CcrError::ValidationError("s3 后端需要通过 --bucket 指定存储桶".into())
+            })?,
+            region: region.unwrap_or("us-east-1").to_string(),
+            access_key: None,
+            secret_key: None,
+        }),
+        other => Err(CcrError::ValidationError(format!(
+            "未知的存储后端 '{}'，支持: webdav, local, git, s3",
+            other
+        ))),
+    }
+}
+
 /// ❌ 删除同步文件夹
 pub fn sync_folder_remove_command(name: &str) -> Result<()> {
     ColorOutput::title(&format!("删除同步文件夹: {}", name));
@@ -1073,6 +1334,11 @@ pub fn sync_folder_info_command(name: &str) -> Result<()> {
     println!("{}", "路径配置".bold());
     println!("  本地路径: {}", folder.local_path);
     println!("  远程路径: {}", folder.remote_path);
+    println!(
+        "  存储后端: {} ({})",
+        folder.backend.kind(),
+        folder.backend.describe(&manager.get_webdav_config()?)
+    );
 
     // 检查本地路径是否存在
     if let Ok(expanded) = folder.expand_local_path() {
@@ -1172,6 +1438,7 @@ pub async fn sync_all_push_command(force: bool) -> Result<()> {
     ColorOutput::info(&format!("准备上传 {} 个文件夹...", enabled_folders.len()));
     println!();
 
+    let passphrase = folder_sync_passphrase()?;
     let mut success_count = 0;
     let mut failed_count = 0;
     let mut failed_folders = Vec::new();
@@ -1185,7 +1452,7 @@ pub async fn sync_all_push_command(force: bool) -> Result<()> {
             folder.name.bold()
         );
 
-        match sync_folder_push_internal(folder, &manager, force).await {
+        match sync_folder_push_internal(folder, &manager, force, passphrase.as_deref()).await {
             Ok(_) => {
                 println!("   {}", "✓ 上传成功".green());
                 success_count += 1;
@@ -1246,6 +1513,7 @@ pub async fn sync_all_pull_command(force: bool) -> Result<()> {
     ColorOutput::info(&format!("准备下载 {} 个文件夹...", enabled_folders.len()));
     println!();
 
+    let passphrase = folder_sync_passphrase()?;
     let mut success_count = 0;
     let mut failed_count = 0;
     let mut failed_folders = Vec::new();
@@ -1259,7 +1527,7 @@ pub async fn sync_all_pull_command(force: bool) -> Result<()> {
             folder.name.bold()
         );
 
-        match sync_folder_pull_internal(folder, &manager, force).await {
+        match sync_folder_pull_internal(folder, &manager, force, passphrase.as_deref()).await {
             Ok(_) => {
                 println!("   {}", "✓ 下载成功".green());
                 success_count += 1;
@@ -1438,27 +1706,19 @@ async fn sync_folder_push_command(folder_name: &str) -> Result<()> {
 
     ColorOutput::info(&format!("本地路径: {}", local_path.display()));
     ColorOutput::info(&format!("远程路径: {}", folder.remote_path));
+    ColorOutput::info(&format!("存储后端: {}", folder.backend.kind()));
     println!();
 
-    // 🔧 获取 WebDAV 配置
+    // 🔌 创建存储后端
     let webdav_config = manager.get_webdav_config()?;
-
-    // 📝 构建 SyncConfig
-    let sync_config = SyncConfig {
-        enabled: true,
-        webdav_url: webdav_config.url.clone(),
-        username: webdav_config.username.clone(),
-        password: webdav_config.password.clone(),
-        remote_path: folder.remote_path.clone(),
-        auto_sync: false,
-    };
+    let cipher = folder_cipher(folder_sync_passphrase()?.as_deref())?;
+    let backend = create_backend(&folder, &webdav_config, cipher).await?;
 
     // 🔍 检查远程是否已存在
     print!("🔍 正在检查远程状态...");
     let _ = io::stdout().flush();
 
-    let service = SyncService::new(&sync_config).await?;
-    let exists = service.remote_exists().await?;
+    let exists = backend.exists().await?;
 
     print!("\r");
     if exists {
@@ -1485,24 +1745,31 @@ async fn sync_folder_push_command(folder_name: &str) -> Result<()> {
         println!();
     }
 
-    // 🚀 上传到云端
+    // 🚀 上传到远程
     print!("🚀 正在上传...");
     let _ = io::stdout().flush();
 
-    // 🎯 不传递 allowed_paths，让 SyncService 使用内部的排除逻辑
-    service.push(&local_path, None).await?;
+    let (uploaded, deleted) = push_tree(backend.as_ref(), &local_path).await?;
+    backend
+        .commit(&format!("ccr sync {} push", folder.name))
+        .await?;
 
     print!("\r");
     println!(
         "{}  {}",
         "✓".green().bold(),
-        "文件夹已成功上传到云端".green()
+        "文件夹已成功上传到远程".green()
     );
     println!();
     println!("📊 同步信息:");
     println!("   • 本地路径: {}", local_path.display().to_string().cyan());
-    println!("   • 远程路径: {}", folder.remote_path.cyan());
-    println!("   • 服务器: {}", webdav_config.url.dimmed());
+    println!("   • 远程位置: {}", backend.location().cyan());
+    println!(
+        "   • 存储后端: {} ({})",
+        backend.kind(),
+        folder.backend.describe(&webdav_config).dimmed()
+    );
+    println!("   • 上传文件: {}，删除远程文件: {}", uploaded, deleted);
     println!();
 
     Ok(())
@@ -1523,30 +1790,20 @@ async fn sync_folder_pull_command(folder_name: &str) -> Result<()> {
 
     ColorOutput::info(&format!("远程路径: {}", folder.remote_path));
     ColorOutput::info(&format!("本地路径: {}", local_path.display()));
+    ColorOutput::info(&format!("存储后端: {}", folder.backend.kind()));
     println!();
 
-    // 🔧 获取 WebDAV 配置
+    // 🔌 创建存储后端
     let webdav_config = manager.get_webdav_config()?;
-
-    // 📝 构建 SyncConfig
-    let sync_config = SyncConfig {
-        enabled: true,
-        webdav_url: webdav_config.url.clone(),
-        username: webdav_config.username.clone(),
-        password: webdav_config.password.clone(),
-        remote_path: folder.remote_path.clone(),
-        auto_sync: false,
-    };
+    let cipher = folder_cipher(folder_sync_passphrase()?.as_deref())?;
+    let backend = create_backend(&folder, &webdav_config, cipher).await?;
 
     // 🔍 检查远程是否存在
-    let service = SyncService::new(&sync_config).await?;
-    let remote_exists = service.remote_exists().await?;
-
-    if !remote_exists {
+    if !backend.exists().await? {
         println!();
-        ColorOutput::error("远程目录不存在");
+        ColorOutput::error("远程内容不存在");
         println!();
-        println!("   💡 提示: 首次使用需要先上传配置到云端");
+        println!("   💡 提示: 首次使用需要先上传配置到远程");
         println!(
             "   运行命令: {}",
             format!("ccr sync {} push", folder_name).cyan()
@@ -1580,17 +1837,7 @@ async fn sync_folder_pull_command(folder_name: &str) -> Result<()> {
         print!("💾 正在备份本地内容...");
         let _ = io::stdout().flush();
 
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let backup_name = format!("{}.{}.bak", local_path.display(), timestamp);
-        let backup_path = PathBuf::from(backup_name);
-
-        if backup_path.exists() {
-            std::fs::remove_dir_all(&backup_path)
-                .map_err(|e| CcrError::SyncError(format!("删除旧备份失败: {}", e)))?;
-        }
-
-        std::fs::rename(&local_path, &backup_path)
-            .map_err(|e| CcrError::SyncError(format!("备份失败: {}", e)))?;
+        let backup_path = backup_local_path(&local_path)?;
 
         print!("\r");
         println!("{}  {}", "✓".green().bold(), "本地内容已备份".green());
@@ -1601,23 +1848,28 @@ async fn sync_folder_pull_command(folder_name: &str) -> Result<()> {
         println!();
     }
 
-    // ⬇️ 从云端下载
-    print!("⬇️  正在从云端下载...");
+    // ⬇️ 从远程下载
+    print!("⬇️  正在从远程下载...");
     let _ = io::stdout().flush();
 
-    service.pull(&local_path).await?;
+    let written = pull_tree(backend.as_ref(), &local_path).await?;
 
     print!("\r");
     println!(
         "{}  {}",
         "✓".green().bold(),
-        "文件夹已从云端下载并应用".green()
+        "文件夹已从远程下载并应用".green()
     );
     println!();
     println!("📊 同步信息:");
     println!("   • 本地路径: {}", local_path.display().to_string().cyan());
-    println!("   • 远程路径: {}", folder.remote_path.cyan());
-    println!("   • 服务器: {}", webdav_config.url.dimmed());
+    println!("   • 远程位置: {}", backend.location().cyan());
+    println!(
+        "   • 存储后端: {} ({})",
+        backend.kind(),
+        folder.backend.describe(&webdav_config).dimmed()
+    );
+    println!("   • 下载文件: {}", written);
     println!();
 
     Ok(())
@@ -1632,6 +1884,7 @@ async fn sync_folder_status_command(folder_name: &str) -> Result<()> {
 
     let manager = SyncFolderManager::with_default()?;
     let folder = manager.get_folder(folder_name)?;
+    let webdav_config = manager.get_webdav_config()?;
 
     println!("{}", "基本信息".bold());
     println!("  名称:     {}", folder.name);
@@ -1645,21 +1898,13 @@ async fn sync_folder_status_command(folder_name: &str) -> Result<()> {
     );
     println!("  本地路径: {}", folder.local_path);
     println!("  远程路径: {}", folder.remote_path);
+    println!(
+        "  存储后端: {} ({})",
+        folder.backend.kind(),
+        folder.backend.describe(&webdav_config)
+    );
     println!();
 
-    // 🔧 获取 WebDAV 配置
-    let webdav_config = manager.get_webdav_config()?;
-
-    // 📝 构建 SyncConfig
-    let sync_config = SyncConfig {
-        enabled: true,
-        webdav_url: webdav_config.url.clone(),
-        username: webdav_config.username.clone(),
-        password: webdav_config.password.clone(),
-        remote_path: folder.remote_path.clone(),
-        auto_sync: false,
-    };
-
     println!("{}", "同步状态".bold());
 
     // ✅ 检查本地路径是否存在
@@ -1678,8 +1923,9 @@ async fn sync_folder_status_command(folder_name: &str) -> Result<()> {
     print!("  远程状态: 正在检查...");
     let _ = io::stdout().flush();
 
-    let service = SyncService::new(&sync_config).await?;
-    let remote_exists = service.remote_exists().await?;
+    // 仅检查远程是否存在，无需口令
+    let backend = create_backend(&folder, &webdav_config, None).await?;
+    let remote_exists = backend.exists().await?;
 
     print!("\r");
     if remote_exists {
@@ -1707,10 +1953,12 @@ async fn sync_folder_status_command(folder_name: &str) -> Result<()> {
 /// - folder: 要上传的文件夹配置
 /// - manager: SyncFolderManager 实例
 /// - force: 是否强制上传（跳过确认）
+/// - passphrase: 同步口令（未启用加密时为 None）
 async fn sync_folder_push_internal(
     folder: &SyncFolder,
     manager: &SyncFolderManager,
     force: bool,
+    passphrase: Option<&str>,
 ) -> Result<()> {
     // 🏠 展开本地路径
     let local_path = folder.expand_local_path()?;
@@ -1723,33 +1971,21 @@ async fn sync_folder_push_internal(
         )));
     }
 
-    // 🔧 获取 WebDAV 配置
+    // 🔌 创建存储后端
     let webdav_config = manager.get_webdav_config()?;
-
-    // 📝 构建 SyncConfig
-    let sync_config = SyncConfig {
-        enabled: true,
-        webdav_url: webdav_config.url.clone(),
-        username: webdav_config.username.clone(),
-        password: webdav_config.password.clone(),
-        remote_path: folder.remote_path.clone(),
-        auto_sync: false,
-    };
+    let backend = create_backend(folder, &webdav_config, folder_cipher(passphrase)?).await?;
 
     // 🔍 检查远程是否已存在（仅在非强制模式下）
-    if !force {
-        let service = SyncService::new(&sync_config).await?;
-        let exists = service.remote_exists().await?;
-
-        if exists {
-            // 在批量模式下跳过已存在的文件夹
-            return Err(CcrError::SyncError("远程已存在，使用 --force 覆盖".into()));
-        }
+    if !force && backend.exists().await? {
+        // 在批量模式下跳过已存在的文件夹
+        return Err(CcrError::SyncError("远程已存在，使用 --force 覆盖".into()));
     }
 
-    // 🚀 上传到云端
-    let service = SyncService::new(&sync_config).await?;
-    service.push(&local_path, None).await?;
+    // 🚀 上传到远程
+    push_tree(backend.as_ref(), &local_path).await?;
+    backend
+        .commit(&format!("ccr sync {} push", folder.name))
+        .await?;
 
     Ok(())
 }
@@ -1760,32 +1996,22 @@ async fn sync_folder_push_internal(
 /// - folder: 要下载的文件夹配置
 /// - manager: SyncFolderManager 实例
 /// - force: 是否强制下载（跳过确认）
+/// - passphrase: 同步口令（未启用加密时为 None）
 async fn sync_folder_pull_internal(
     folder: &SyncFolder,
     manager: &SyncFolderManager,
     force: bool,
+    passphrase: Option<&str>,
 ) -> Result<()> {
     // 🏠 展开本地路径
     let local_path = folder.expand_local_path()?;
 
-    // 🔧 获取 WebDAV 配置
+    // 🔌 创建存储后端
     let webdav_config = manager.get_webdav_config()?;
-
-    // 📝 构建 SyncConfig
-    let sync_config = SyncConfig {
-        enabled: true,
-        webdav_url: webdav_config.url.clone(),
-        username: webdav_config.username.clone(),
-        password: webdav_config.password.clone(),
-        remote_path: folder.remote_path.clone(),
-        auto_sync: false,
-    };
+    let backend = create_backend(folder, &webdav_config, folder_cipher(passphrase)?).await?;
 
     // 🔍 检查远程是否存在
-    let service = SyncService::new(&sync_config).await?;
-    let remote_exists = service.remote_exists().await?;
-
-    if !remote_exists {
+    if !backend.exists().await? {
         return Err(CcrError::SyncError("远程内容不存在".to_string()));
     }
 
@@ -1797,23 +2023,30 @@ async fn sync_folder_pull_internal(
 
     // 💾 备份本地文件夹（如果存在）
     if local_path.exists() {
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let backup_name = format!("{}.{}.bak", local_path.display(), timestamp);
-        let backup_path = PathBuf::from(backup_name);
+        backup_local_path(&local_path)?;
+    }
 
-        if backup_path.exists() {
-            std::fs::remove_dir_all(&backup_path)
-                .map_err(|e| CcrError::SyncError(format!("删除旧备份失败: {}", e)))?;
-        }
+    // ⬇️ 从远程下载
+    pull_tree(backend.as_ref(), &local_path).await?;
 
-        std::fs::rename(&local_path, &backup_path)
-            .map_err(|e| CcrError::SyncError(format!("备份失败: {}", e)))?;
+    Ok(())
+}
+
+/// 💾 将本地路径重命名为带时间戳的备份，返回备份路径
+fn backup_local_path(local_path: &Path) -> Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let backup_name = format!("{}.{}.bak", local_path.display(), timestamp);
+    let backup_path = PathBuf::from(backup_name);
+
+    if backup_path.exists() {
+        std::fs::remove_dir_all(&backup_path)
+            .map_err(|e| CcrError::SyncError(format!("删除旧备份失败: {}", e)))?;
     }
 
-    // ⬇️ 从云端下载
-    service.pull(&local_path).await?;
+    std::fs::rename(local_path, &backup_path)
+        .map_err(|e| CcrError::SyncError(format!("备份失败: {}", e)))?;
 
-    Ok(())
+    Ok(backup_path)
 }
 
 #[cfg(test)]
@@ -1830,6 +2063,7 @@ mod tests {
             password: "test_password".to_string(),
             remote_path: "/ccr/".to_string(), // 🆕 改为目录路径
             auto_sync: false,
+            encryption: false,
         };
 
         assert!(config.enabled);
@@ -50,6 +50,13 @@ pub struct SyncConfig {
     /// 启用后，每次配置操作后自动同步到云端
     #[serde(default)]
     pub auto_sync: bool,
+
+    /// 🔐 端到端加密
+    ///
+    /// 启用后上传前使用口令加密，下载后解密，WebDAV 服务器只能看到密文
+    /// 口令不会保存到配置中，由交互输入或 CCR_SYNC_PASSPHRASE 环境变量提供
+    #[serde(default)]
+    pub encryption: bool,
 }
 
 /// 默认远程路径
@@ -66,6 +73,7 @@ impl Default for SyncConfig {
             password: String::new(),
             remote_path: default_remote_path(),
             auto_sync: false,
+            encryption: false,
         }
     }
 }
@@ -199,6 +207,7 @@ mod tests {
             password: "test_password".to_string(),
             remote_path: "/ccr/".to_string(),
             auto_sync: false,
+            encryption: false,
         };
 
         // 保存
@@ -0,0 +1,107 @@
+// 🔐 CCR 同步加密
+// 📁 基于口令的端到端加密：上传前加密，下载后解密，WebDAV 服务器只能看到密文
+//
+// 密文格式见 core::crypto，文件头为 CCRSYNC1
+
+use crate::core::crypto::{self, MAGIC_SIZE, PassphraseCipher};
+use crate::core::error::{CcrError, Result};
+
+/// 🏷️ 加密文件头
+const MAGIC: &[u8; MAGIC_SIZE] = b"CCRSYNC1";
+
+/// 🔑 同步口令环境变量（非交互场景，如 Web API 或脚本）
+pub const PASSPHRASE_ENV: &str = "CCR_SYNC_PASSPHRASE";
+
+/// 🔍 判断内容是否为同步加密格式
+pub fn is_encrypted(data: &[u8]) -> bool {
+    crypto::is_sealed(MAGIC, data)
+}
+
+/// 🔑 从环境变量读取同步口令
+pub fn passphrase_from_env() -> Option<String> {
+    std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty())
+}
+
+/// 🔐 同步加密器
+pub struct SyncCipher {
+    inner: PassphraseCipher,
+}
+
+impl SyncCipher {
+    /// 🏗️ 从口令创建加密器（随机生成 salt）
+    pub fn new(passphrase: &str) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(CcrError::InvalidPassphrase("同步口令不能为空".into()));
+        }
+
+        Ok(Self {
+            inner: PassphraseCipher::new(MAGIC, passphrase, CcrError::SyncError),
+        })
+    }
+
+    /// 🔒 加密内容
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.inner.encrypt(plaintext)
+    }
+
+    /// 🔓 解密内容
+    ///
+    /// 口令错误或内容被篡改时返回 `CcrError::InvalidPassphrase`
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !is_encrypted(data) {
+            return Err(CcrError::SyncError("内容不是有效的同步加密格式".into()));
+        }
+        self.inner.decrypt(data).map_err(|e| match e {
+            CcrError::InvalidPassphrase(_) => {
+                CcrError::InvalidPassphrase("无法解密远程内容，口令错误或内容已损坏".into())
+            }
+            e => e,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let cipher = SyncCipher::new("correct horse").unwrap();
+        let plaintext = b"[anthropic]\nauth_token = \"sk-ant-secret\"\n";
+
+        let encrypted = cipher.encrypt(plaintext).unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(
+            !encrypted
+                .windows(b"sk-ant-secret".len())
+                .any(|w| w == b"sk-ant-secret")
+        );
+
+        // 同一口令的新实例（不同 salt）也能解密
+        let other = SyncCipher::new("correct horse").unwrap();
+        assert_eq!(other.decrypt(&encrypted).unwrap(), plaintext);
+        assert!(!is_encrypted(plaintext));
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_reported() {
+        let encrypted = SyncCipher::new("right").unwrap().encrypt(b"data").unwrap();
+        let err = SyncCipher::new("wrong")
+            .unwrap()
+            .decrypt(&encrypted)
+            .unwrap_err();
+        assert!(matches!(err, CcrError::InvalidPassphrase(_)));
+
+        // 篡改密文同样无法通过校验
+        let mut tampered = encrypted.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0xff;
+        assert!(
+            SyncCipher::new("right")
+                .unwrap()
+                .decrypt(&tampered)
+                .is_err()
+        );
+    }
+}
@@ -71,6 +71,136 @@ pub struct SyncFolder {
     /// 例如: ["*.log", ".locks/", "cache/"]
     #[serde(default)]
     pub exclude_patterns: Vec<String>,
+
+    /// 🔌 存储后端
+    ///
+    /// 默认使用共享的 WebDAV 配置；也可以为单个文件夹选择本地目录、Git 仓库或 S3
+    #[serde(default, skip_serializing_if = "SyncBackendConfig::is_webdav")]
+    pub backend: SyncBackendConfig,
+}
+
+/// 🔌 同步文件夹的存储后端配置
+///
+/// `remote_path` 在各后端中均作为子路径（Git 子目录 / S3 对象前缀）使用
+///
+/// ```toml
+/// [folders.backend]
+/// type = "git"
+/// url = "git@github.com:me/ccr-sync.git"
+/// branch = "main"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncBackendConfig {
+    /// ☁️ 共享的 WebDAV 服务器
+    #[default]
+    Webdav,
+
+    /// 📂 本地目录（如 Syncthing / Dropbox 同步盘）
+    Local {
+        /// 目录路径，支持 `~` 扩展
+        path: String,
+    },
+
+    /// 🌿 Git 仓库（每次上传生成一个提交）
+    Git {
+        /// 仓库地址
+        url: String,
+        /// 同步分支
+        #[serde(default = "default_git_branch")]
+        branch: String,
+    },
+
+    /// 🪣 S3 兼容对象存储
+    S3 {
+        /// 服务地址，如 `https://s3.amazonaws.com` 或 `http://127.0.0.1:9000`
+        endpoint: String,
+        /// 存储桶
+        bucket: String,
+        /// 区域
+        #[serde(default = "default_s3_region")]
+        region: String,
+        /// 访问密钥 ID（为空时读取 AWS_ACCESS_KEY_ID）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        access_key: Option<String>,
+        /// 访问密钥（为空时读取 AWS_SECRET_ACCESS_KEY）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret_key: Option<String>,
+    },
+}
+
+fn default_git_branch() -> String {
+    "main".to_string()
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+impl SyncBackendConfig {
+    /// 是否为默认的 WebDAV 后端
+    pub fn is_webdav(&self) -> bool {
+        matches!(self, Self::Webdav)
+    }
+
+    /// 🏷️ 后端类型名称
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Webdav => "webdav",
+            Self::Local { .. } => "local",
+            Self::Git { .. } => "git",
+            Self::S3 { .. } => "s3",
+        }
+    }
+
+    /// 📍 后端位置描述（用于显示）
+    pub fn describe(&self, webdav: &WebDavConfig) -> String {
+        match self {
+            Self::Webdav => webdav.url.clone(),
+            Self::Local { path } => path.clone(),
+            Self::Git { url, branch } => format!("{} ({})", url, branch),
+            Self::S3 {
+                endpoint, bucket, ..
+            } => format!("{}/{}", endpoint.trim_end_matches('/'), bucket),
+        }
+    }
+
+    /// ✅ 验证后端配置
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        match self {
+            Self::Webdav => {}
+            Self::Local { path } => {
+                if path.trim().is_empty() {
+                    errors.push("本地目录后端的 path 不能为空".to_string());
+                } else if let Err(e) = expand_path(path) {
+                    errors.push(format!("无法扩展后端路径 '{}': {}", path, e));
+                }
+            }
+            Self::Git { url, branch } => {
+                if url.trim().is_empty() {
+                    errors.push("Git 后端的 url 不能为空".to_string());
+                }
+                if branch.trim().is_empty() {
+                    errors.push("Git 后端的 branch 不能为空".to_string());
+                }
+            }
+            Self::S3 {
+                endpoint, bucket, ..
+            } => {
+                if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                    errors.push(format!(
+                        "S3 后端的 endpoint '{}' 必须以 http:// 或 https:// 开头",
+                        endpoint
+                    ));
+                }
+                if bucket.trim().is_empty() {
+                    errors.push("S3 后端的 bucket 不能为空".to_string());
+                }
+            }
+        }
+        errors
+    }
 }
 
 /// 默认启用状态
@@ -143,6 +273,9 @@ impl SyncFolder {
             errors.push("远程路径不能为空".to_string());
         }
 
+        // 检查存储后端
+        errors.extend(self.backend.validate());
+
         errors
     }
 
@@ -182,6 +315,7 @@ pub struct SyncFolderBuilder {
     enabled: bool,
     auto_sync: bool,
     exclude_patterns: Vec<String>,
+    backend: SyncBackendConfig,
 }
 
 impl SyncFolderBuilder {
@@ -195,6 +329,7 @@ impl SyncFolderBuilder {
             enabled: true,
             auto_sync: false,
             exclude_patterns: Vec::new(),
+            backend: SyncBackendConfig::default(),
         }
     }
 
@@ -248,6 +383,12 @@ impl SyncFolderBuilder {
         self
     }
 
+    /// 设置存储后端
+    pub fn backend(mut self, backend: SyncBackendConfig) -> Self {
+        self.backend = backend;
+        self
+    }
+
     /// 构建 SyncFolder
     ///
     /// # Errors
@@ -274,6 +415,7 @@ impl SyncFolderBuilder {
             enabled: self.enabled,
             auto_sync: self.auto_sync,
             exclude_patterns: self.exclude_patterns,
+            backend: self.backend,
         };
 
         // 验证配置
@@ -537,6 +679,7 @@ mod tests {
             enabled: true,
             auto_sync: false,
             exclude_patterns: vec![],
+            backend: SyncBackendConfig::default(),
         };
 
         let errors = folder.validate();
@@ -553,6 +696,7 @@ mod tests {
             enabled: true,
             auto_sync: false,
             exclude_patterns: vec![],
+            backend: SyncBackendConfig::default(),
         };
 
         let errors = folder.validate();
@@ -570,6 +714,7 @@ mod tests {
             enabled: true,
             auto_sync: false,
             exclude_patterns: vec![],
+            backend: SyncBackendConfig::default(),
         };
 
         let errors = folder.validate();
@@ -724,4 +869,49 @@ mod tests {
         assert_eq!(stats.enabled, 1);
         assert_eq!(stats.disabled, 1);
     }
+
+    #[test]
+    fn test_backend_config_serialization() {
+        // 未配置后端时默认为 WebDAV，且序列化时省略
+        let folder: SyncFolder = toml::from_str(
+            "name = \"claude\"\nlocal_path = \"~/.claude\"\nremote_path = \"/ccr-sync/claude\"\n",
+        )
+        .unwrap();
+        assert!(folder.backend.is_webdav());
+        assert!(!toml::to_string(&folder).unwrap().contains("backend"));
+
+        let folder: SyncFolder = toml::from_str(
+            r#"name = "ccr"
+local_path = "~/.ccr"
+remote_path = "/ccr-sync/ccr"
+
+[backend]
+type = "git"
+url = "git@example.com:me/ccr-sync.git"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            folder.backend,
+            SyncBackendConfig::Git {
+                url: "git@example.com:me/ccr-sync.git".to_string(),
+                branch: "main".to_string(),
+            }
+        );
+        assert!(folder.validate().is_empty());
+
+        let invalid = SyncFolder::builder()
+            .name("s3")
+            .local_path("~/.ccr")
+            .remote_path("/ccr")
+            .backend(SyncBackendConfig::S3 {
+                endpoint: "minio.local:9000".to_string(),
+                bucket: String::new(),
+                region: "us-east-1".to_string(),
+                access_key: None,
+                secret_key: None,
+            })
+            .build();
+        assert!(invalid.is_err());
+    }
 }
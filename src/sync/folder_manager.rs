@@ -734,6 +734,7 @@ mod tests {
             password: "password".to_string(),
             remote_path: "/ccr-sync".to_string(),
             auto_sync: false,
+            encryption: false,
         };
 
         let sync_config_manager =
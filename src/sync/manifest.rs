@@ -0,0 +1,419 @@
+// 🧾 CCR 同步清单
+// 📁 记录每个文件最近一次同步时的内容哈希（三方合并的 base）
+//
+// 核心功能:
+// - 🔐 计算文件内容哈希（blake3）
+// - 📦 以内容寻址方式保存上次同步的 base 内容
+// - 🔍 对比 base / 本地 / 远程，判断每个文件的同步状态
+
+use crate::core::error::{CcrError, Result};
+use crate::sync::service::should_exclude_from_sync;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 📂 同步状态目录名（位于同步根目录下，以 `.` 开头因此不会被上传）
+pub const SYNC_STATE_DIR: &str = ".sync_state";
+
+/// 📄 文件树：相对路径（使用 `/` 分隔）→ 文件内容
+pub type FileTree = BTreeMap<String, Vec<u8>>;
+
+/// 🔐 计算内容哈希
+pub fn hash_content(content: &[u8]) -> String {
+    blake3::hash(content).to_hex().to_string()
+}
+
+/// 📝 清单中的单个文件记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// 上次同步时的内容哈希
+    pub hash: String,
+    /// 上次同步时间
+    pub synced_at: DateTime<Utc>,
+}
+
+/// 🧾 同步清单
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncManifest {
+    /// 清单对应的远程位置（远程变更后旧的 base 不再可信）
+    #[serde(default)]
+    pub remote: String,
+    /// 每个文件的同步记录
+    #[serde(default)]
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+impl SyncManifest {
+    /// 🔍 获取文件的 base 哈希
+    pub fn base_hash(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(|e| e.hash.as_str())
+    }
+}
+
+/// 🔄 单个文件的同步状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSyncState {
+    /// 本地与远程一致
+    InSync,
+    /// 仅本地有改动，需要上传
+    Ahead,
+    /// 仅远程有改动，需要下载
+    Behind,
+    /// 两端均有不同改动
+    Conflicted,
+}
+
+impl fmt::Display for FileSyncState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileSyncState::InSync => write!(f, "✓ 已同步"),
+            FileSyncState::Ahead => write!(f, "↑ 本地领先"),
+            FileSyncState::Behind => write!(f, "↓ 远程领先"),
+            FileSyncState::Conflicted => write!(f, "⚠ 冲突"),
+        }
+    }
+}
+
+/// ✏️ 相对于 base 的改动类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Unchanged,
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl FileChange {
+    fn between(base: Option<&str>, current: Option<&str>) -> Self {
+        match (base, current) {
+            (None, None) => FileChange::Unchanged,
+            (None, Some(_)) => FileChange::Added,
+            (Some(_), None) => FileChange::Deleted,
+            (Some(b), Some(c)) if b == c => FileChange::Unchanged,
+            (Some(_), Some(_)) => FileChange::Modified,
+        }
+    }
+}
+
+impl fmt::Display for FileChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileChange::Unchanged => write!(f, "-"),
+            FileChange::Added => write!(f, "新增"),
+            FileChange::Modified => write!(f, "修改"),
+            FileChange::Deleted => write!(f, "删除"),
+        }
+    }
+}
+
+/// 📊 文件同步状态明细
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStatus {
+    pub path: String,
+    pub state: FileSyncState,
+    pub local: FileChange,
+    pub remote: FileChange,
+}
+
+/// 🔍 根据 base / 本地 / 远程哈希判断同步状态
+pub fn classify(base: Option<&str>, local: Option<&str>, remote: Option<&str>) -> FileSyncState {
+    if local == remote {
+        FileSyncState::InSync
+    } else if local == base {
+        FileSyncState::Behind
+    } else if remote == base {
+        FileSyncState::Ahead
+    } else {
+        FileSyncState::Conflicted
+    }
+}
+
+/// 📊 对比本地与远程文件树，得到每个文件的同步状态（按路径排序）
+pub fn diff_trees(manifest: &SyncManifest, local: &FileTree, remote: &FileTree) -> Vec<FileStatus> {
+    let paths: BTreeSet<&String> = manifest
+        .files
+        .keys()
+        .chain(local.keys())
+        .chain(remote.keys())
+        .collect();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let base = manifest.base_hash(path);
+            let local_hash = local.get(path).map(|c| hash_content(c));
+            let remote_hash = remote.get(path).map(|c| hash_content(c));
+
+            // 三端都不存在（仅残留在清单中）的记录无需展示
+            if local_hash.is_none() && remote_hash.is_none() {
+                return None;
+            }
+
+            Some(FileStatus {
+                path: path.clone(),
+                state: classify(base, local_hash.as_deref(), remote_hash.as_deref()),
+                local: FileChange::between(base, local_hash.as_deref()),
+                remote: FileChange::between(base, remote_hash.as_deref()),
+            })
+        })
+        .collect()
+}
+
+/// 📂 读取本地文件树
+///
+/// - 路径为文件时返回仅包含该文件的树（键为文件名）
+/// - 路径为目录时递归读取，沿用同步的排除规则
+/// - 路径不存在时返回空树
+pub fn read_tree(root: &Path) -> Result<FileTree> {
+    let mut tree = FileTree::new();
+
+    if root.is_file() {
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| CcrError::SyncError("无效的文件名".into()))?;
+        tree.insert(name, std::fs::read(root)?);
+    } else if root.is_dir() {
+        read_dir_into(root, "", &mut tree)?;
+    }
+
+    Ok(tree)
+}
+
+fn read_dir_into(dir: &Path, prefix: &str, tree: &mut FileTree) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if should_exclude_from_sync(&name) {
+            continue;
+        }
+
+        let rel = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let path = entry.path();
+
+        if path.is_dir() {
+            read_dir_into(&path, &rel, tree)?;
+        } else {
+            tree.insert(rel, std::fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// 📍 将文件树中的相对路径映射为本地路径
+pub fn local_path_for(root: &Path, rel: &str) -> PathBuf {
+    if root.is_file() {
+        root.to_path_buf()
+    } else {
+        rel.split('/')
+            .fold(root.to_path_buf(), |p, seg| p.join(seg))
+    }
+}
+
+/// 💾 同步状态存储
+///
+/// 目录结构:
+/// ```text
+/// <sync_root>/.sync_state/
+/// ├── manifest.json      # 同步清单
+/// └── objects/<hash>     # 上次同步的 base 内容（内容寻址）
+/// ```
+pub struct SyncStateStore {
+    dir: PathBuf,
+}
+
+impl SyncStateStore {
+    /// 🏗️ 为同步根路径创建状态存储（文件模式时放在文件所在目录）
+    pub fn for_root(root: &Path) -> Self {
+        let base = if root.is_file() {
+            root.parent().map(Path::to_path_buf).unwrap_or_default()
+        } else {
+            root.to_path_buf()
+        };
+        Self {
+            dir: base.join(SYNC_STATE_DIR),
+        }
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.dir.join("manifest.json")
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("objects").join(hash)
+    }
+
+    /// 📖 加载清单
+    ///
+    /// 清单记录的远程位置与当前不一致时返回空清单（视为首次同步）
+    pub fn load_manifest(&self, remote: &str) -> Result<SyncManifest> {
+        let path = self.manifest_path();
+        if !path.exists() {
+            return Ok(SyncManifest {
+                remote: remote.to_string(),
+                ..Default::default()
+            });
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let manifest: SyncManifest = serde_json::from_str(&content)
+            .map_err(|e| CcrError::SyncError(format!("解析同步清单失败: {}", e)))?;
+
+        if manifest.remote != remote {
+            tracing::info!(
+                "远程位置已变更 ({} -> {})，忽略旧的同步清单",
+                manifest.remote,
+                remote
+            );
+            return Ok(SyncManifest {
+                remote: remote.to_string(),
+                ..Default::default()
+            });
+        }
+
+        Ok(manifest)
+    }
+
+    /// 📖 读取文件的 base 内容
+    pub fn base_content(&self, manifest: &SyncManifest, path: &str) -> Option<Vec<u8>> {
+        let hash = manifest.base_hash(path)?;
+        std::fs::read(self.object_path(hash)).ok()
+    }
+
+    /// ✏️ 记录文件的同步结果（None 表示两端均已删除）
+    pub fn record(
+        &self,
+        manifest: &mut SyncManifest,
+        path: &str,
+        content: Option<&[u8]>,
+    ) -> Result<()> {
+        match content {
+            Some(content) => {
+                let hash = hash_content(content);
+                let object = self.object_path(&hash);
+                if !object.exists() {
+                    if let Some(parent) = object.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&object, content)?;
+                }
+                manifest.files.insert(
+                    path.to_string(),
+                    ManifestEntry {
+                        hash,
+                        synced_at: Utc::now(),
+                    },
+                );
+            }
+            None => {
+                manifest.files.remove(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// 💾 保存清单并清理不再引用的 base 内容
+    pub fn save_manifest(&self, manifest: &SyncManifest) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let content = serde_json::to_string_pretty(manifest)
+            .map_err(|e| CcrError::SyncError(format!("序列化同步清单失败: {}", e)))?;
+        let tmp = self.dir.join("manifest.json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, self.manifest_path())?;
+
+        let referenced: BTreeSet<&str> = manifest.files.values().map(|e| e.hash.as_str()).collect();
+        if let Ok(entries) = std::fs::read_dir(self.dir.join("objects")) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !referenced.contains(name.as_str()) {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let (a, b, c) = (Some("a"), Some("b"), Some("c"));
+        assert_eq!(classify(a, a, a), FileSyncState::InSync);
+        assert_eq!(classify(a, b, b), FileSyncState::InSync);
+        assert_eq!(classify(a, b, a), FileSyncState::Ahead);
+        assert_eq!(classify(a, a, b), FileSyncState::Behind);
+        assert_eq!(classify(a, b, c), FileSyncState::Conflicted);
+        // 无 base：两端内容不同即冲突
+        assert_eq!(classify(None, a, b), FileSyncState::Conflicted);
+        // 无 base：只有一端存在
+        assert_eq!(classify(None, a, None), FileSyncState::Ahead);
+        assert_eq!(classify(None, None, a), FileSyncState::Behind);
+        // 远程删除、本地未改
+        assert_eq!(classify(a, a, None), FileSyncState::Behind);
+        // 远程删除、本地修改
+        assert_eq!(classify(a, b, None), FileSyncState::Conflicted);
+    }
+
+    #[test]
+    fn test_diff_trees_and_store_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("platforms/claude")).unwrap();
+        std::fs::write(temp.path().join("config.toml"), "a = 1\n").unwrap();
+        std::fs::write(
+            temp.path().join("platforms/claude/profiles.toml"),
+            "x = 1\n",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("notes.bak"), "ignored").unwrap();
+
+        let store = SyncStateStore::for_root(temp.path());
+        let mut manifest = store.load_manifest("dav:/ccr/").unwrap();
+
+        let local = read_tree(temp.path()).unwrap();
+        assert_eq!(local.len(), 2);
+        assert!(local.contains_key("platforms/claude/profiles.toml"));
+
+        for (path, content) in &local {
+            store.record(&mut manifest, path, Some(content)).unwrap();
+        }
+        store.save_manifest(&manifest).unwrap();
+
+        // 状态目录不会出现在文件树中
+        assert_eq!(read_tree(temp.path()).unwrap().len(), 2);
+
+        let manifest = store.load_manifest("dav:/ccr/").unwrap();
+        assert_eq!(
+            store.base_content(&manifest, "config.toml").unwrap(),
+            b"a = 1\n"
+        );
+
+        let mut remote = local.clone();
+        remote.insert("config.toml".into(), b"a = 2\n".to_vec());
+        remote.insert("extra.toml".into(), b"y = 1\n".to_vec());
+
+        let statuses = diff_trees(&manifest, &local, &remote);
+        let by_path: BTreeMap<_, _> = statuses.iter().map(|s| (s.path.as_str(), s)).collect();
+        assert_eq!(by_path["config.toml"].state, FileSyncState::Behind);
+        assert_eq!(by_path["config.toml"].remote, FileChange::Modified);
+        assert_eq!(by_path["extra.toml"].state, FileSyncState::Behind);
+        assert_eq!(by_path["extra.toml"].remote, FileChange::Added);
+        assert_eq!(
+            by_path["platforms/claude/profiles.toml"].state,
+            FileSyncState::InSync
+        );
+
+        // 远程位置变化后清单失效
+        assert!(store.load_manifest("dav:/other/").unwrap().files.is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+// 🔀 TOML 三方合并
+// 📁 基于上次同步的 base 合并本地与远程的配置表
+//
+// 核心规则（逐键递归比较）:
+// - 两端一致            → 保持不变
+// - 仅远程修改          → 采用远程
+// - 仅本地修改          → 保留本地
+// - 两端都是表          → 递归合并（不同机器编辑不同 profile 可干净合并）
+// - 两端修改为不同的值  → 冲突，交给调用方决定
+//
+// 合并结果以本地文档为基础，使用 toml_edit 保留本地的格式与注释。
+
+use crate::core::error::{CcrError, Result};
+use std::collections::BTreeSet;
+use toml_edit::{DocumentMut, Item};
+
+/// 🏠 仅属于本机的顶层键（每台机器各自维护，合并时总是保留本地值）
+const MACHINE_LOCAL_KEYS: &[&str] = &["current_config", "current_platform"];
+
+/// ⚠️ 合并冲突
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// 冲突键路径，如 `anthropic.base_url`
+    pub path: String,
+    pub base: Option<toml::Value>,
+    pub local: Option<toml::Value>,
+    pub remote: Option<toml::Value>,
+}
+
+/// 🎯 冲突解决方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictChoice {
+    KeepLocal,
+    UseRemote,
+}
+
+/// 📊 合并结果
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    /// 合并后的 TOML 文本
+    pub merged: String,
+    /// 遇到的冲突及其解决方式
+    pub conflicts: Vec<(MergeConflict, ConflictChoice)>,
+}
+
+/// 🔀 三方合并 TOML 文本
+///
+/// # 参数
+/// - base: 上次同步时的内容（首次同步时传空字符串）
+/// - local / remote: 本地与远程的当前内容
+/// - resolve: 冲突回调，返回 Err 时中止合并
+pub fn merge_toml<F>(base: &str, local: &str, remote: &str, mut resolve: F) -> Result<MergeOutcome>
+where
+    F: FnMut(&MergeConflict) -> Result<ConflictChoice>,
+{
+    let base_table = parse_table(base, "base")?;
+    let local_table = parse_table(local, "本地")?;
+    let remote_table = parse_table(remote, "远程")?;
+
+    let mut doc = local
+        .parse::<DocumentMut>()
+        .map_err(|e| CcrError::SyncError(format!("解析本地 TOML 失败: {}", e)))?;
+
+    let mut conflicts = Vec::new();
+    merge_tables(
+        "",
+        Some(&base_table),
+        &local_table,
+        &remote_table,
+        doc.as_table_mut(),
+        &mut resolve,
+        &mut conflicts,
+    )?;
+
+    Ok(MergeOutcome {
+        merged: doc.to_string(),
+        conflicts,
+    })
+}
+
+fn parse_table(content: &str, side: &str) -> Result<toml::Table> {
+    toml::from_str(content)
+        .map_err(|e| CcrError::SyncError(format!("解析{} TOML 失败: {}", side, e)))
+}
+
+fn merge_tables<F>(
+    prefix: &str,
+    base: Option<&toml::Table>,
+    local: &toml::Table,
+    remote: &toml::Table,
+    out: &mut toml_edit::Table,
+    resolve: &mut F,
+    conflicts: &mut Vec<(MergeConflict, ConflictChoice)>,
+) -> Result<()>
+where
+    F: FnMut(&MergeConflict) -> Result<ConflictChoice>,
+{
+    // 本地键保持原有顺序，远程新增的键追加在后面
+    let mut keys: Vec<&String> = local.keys().collect();
+    let mut seen: BTreeSet<&String> = keys.iter().copied().collect();
+    for key in remote.keys().chain(base.into_iter().flat_map(|b| b.keys())) {
+        if seen.insert(key) {
+            keys.push(key);
+        }
+    }
+
+    for key in keys {
+        if prefix.is_empty() && MACHINE_LOCAL_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+
+        let b = base.and_then(|t| t.get(key));
+        let l = local.get(key);
+        let r = remote.get(key);
+
+        if l == r || r == b {
+            continue;
+        }
+
+        if l == b {
+            set_item(out, key, r)?;
+            continue;
+        }
+
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        if let (Some(toml::Value::Table(lt)), Some(toml::Value::Table(rt))) = (l, r)
+            && let Some(out_table) = out.get_mut(key).and_then(Item::as_table_mut)
+        {
+            let bt = b.and_then(toml::Value::as_table);
+            merge_tables(&path, bt, lt, rt, out_table, resolve, conflicts)?;
+            continue;
+        }
+
+        let conflict = MergeConflict {
+            path,
+            base: b.cloned(),
+            local: l.cloned(),
+            remote: r.cloned(),
+        };
+        let choice = resolve(&conflict)?;
+        if choice == ConflictChoice::UseRemote {
+            set_item(out, key, r)?;
+        }
+        conflicts.push((conflict, choice));
+    }
+
+    Ok(())
+}
+
+/// ✏️ 将语义值写入 toml_edit 表（None 表示删除）
+fn set_item(out: &mut toml_edit::Table, key: &str, value: Option<&toml::Value>) -> Result<()> {
+    match value {
+        None => {
+            out.remove(key);
+        }
+        Some(value) => {
+            out.insert(key, to_item(value)?);
+        }
+    }
+    Ok(())
+}
+
+/// 🔄 toml::Value → toml_edit::Item（表格保持为标准表而非内联表）
+fn to_item(value: &toml::Value) -> Result<Item> {
+    let mut wrapper = toml::Table::new();
+    wrapper.insert("v".to_string(), value.clone());
+    let text = toml::to_string(&wrapper)
+        .map_err(|e| CcrError::SyncError(format!("序列化 TOML 失败: {}", e)))?;
+    let mut doc = text
+        .parse::<DocumentMut>()
+        .map_err(|e| CcrError::SyncError(format!("解析 TOML 失败: {}", e)))?;
+    doc.as_table_mut()
+        .remove("v")
+        .ok_or_else(|| CcrError::SyncError("转换 TOML 值失败".into()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"default_config = "alpha"
+current_config = "alpha"
+
+[alpha]
+base_url = "https://a.example.com"
+auth_token = "sk-a"
+
+[beta]
+base_url = "https://b.example.com"
+auth_token = "sk-b"
+"#;
+
+    fn no_conflicts(c: &MergeConflict) -> Result<ConflictChoice> {
+        panic!("unexpected conflict at {}", c.path)
+    }
+
+    #[test]
+    fn test_profiles_edited_on_different_machines_merge_cleanly() {
+        let local = BASE
+            .replace("current_config = \"alpha\"", "current_config = \"beta\"")
+            .replace(
+                "base_url = \"https://a.example.com\"",
+                "# 本机注释\nbase_url = \"https://a2.example.com\"",
+            );
+        let remote = format!(
+            "{}\n[gamma]\nbase_url = \"https://c.example.com\"\n",
+            BASE.replace("auth_token = \"sk-b\"", "auth_token = \"sk-b2\"")
+                .replace("current_config = \"alpha\"", "current_config = \"gamma\"")
+        );
+
+        let outcome = merge_toml(BASE, &local, &remote, no_conflicts).unwrap();
+        assert!(outcome.conflicts.is_empty());
+
+        let merged: toml::Table = toml::from_str(&outcome.merged).unwrap();
+        assert_eq!(
+            merged["alpha"]["base_url"].as_str(),
+            Some("https://a2.example.com")
+        );
+        assert_eq!(merged["beta"]["auth_token"].as_str(), Some("sk-b2"));
+        assert_eq!(
+            merged["gamma"]["base_url"].as_str(),
+            Some("https://c.example.com")
+        );
+        // 本机键保留本地值
+        assert_eq!(merged["current_config"].as_str(), Some("beta"));
+        // 保留本地注释
+        assert!(outcome.merged.contains("# 本机注释"));
+    }
+
+    #[test]
+    fn test_real_conflict_uses_resolver() {
+        let local = BASE.replace("sk-a", "sk-local");
+        let remote = BASE.replace("sk-a", "sk-remote");
+
+        let mut seen = Vec::new();
+        let outcome = merge_toml(BASE, &local, &remote, |c| {
+            seen.push(c.path.clone());
+            Ok(ConflictChoice::UseRemote)
+        })
+        .unwrap();
+
+        assert_eq!(seen, vec!["alpha.auth_token".to_string()]);
+        let merged: toml::Table = toml::from_str(&outcome.merged).unwrap();
+        assert_eq!(merged["alpha"]["auth_token"].as_str(), Some("sk-remote"));
+
+        let kept = merge_toml(BASE, &local, &remote, |_| Ok(ConflictChoice::KeepLocal)).unwrap();
+        let merged: toml::Table = toml::from_str(&kept.merged).unwrap();
+        assert_eq!(merged["alpha"]["auth_token"].as_str(), Some("sk-local"));
+
+        // 回调返回错误时中止合并
+        let aborted = merge_toml(BASE, &local, &remote, |_| {
+            Err(CcrError::SyncError("已取消".into()))
+        });
+        assert!(aborted.is_err());
+    }
+
+    #[test]
+    fn test_remote_deletion_and_first_sync_without_base() {
+        let remote = BASE.replace(
+            "[beta]\nbase_url = \"https://b.example.com\"\nauth_token = \"sk-b\"\n",
+            "",
+        );
+        let outcome = merge_toml(BASE, BASE, &remote, no_conflicts).unwrap();
+        let merged: toml::Table = toml::from_str(&outcome.merged).unwrap();
+        assert!(!merged.contains_key("beta"));
+
+        // 首次同步（无 base）：两端各自独有的 profile 合并
+        let local = "[alpha]\nauth_token = \"sk-a\"\n";
+        let remote = "[beta]\nauth_token = \"sk-b\"\n";
+        let outcome = merge_toml("", local, remote, no_conflicts).unwrap();
+        let merged: toml::Table = toml::from_str(&outcome.merged).unwrap();
+        assert!(merged.contains_key("alpha") && merged.contains_key("beta"));
+    }
+}
@@ -3,9 +3,14 @@ pub mod commands;
 #[cfg(feature = "web")]
 pub mod content_selector;
 
+pub mod backend;
 pub mod config;
+pub mod crypto;
 pub mod folder;
 pub mod folder_manager;
+pub mod manifest;
+pub mod merge;
+pub mod reconcile;
 pub mod service;
 
 #[cfg(feature = "web")]
@@ -16,11 +21,21 @@ pub use commands::*;
 #[allow(unused_imports)]
 pub use content_selector::{SyncContentSelection, SyncContentSelector, SyncContentType};
 
+#[allow(unused_imports)]
+pub use backend::{SyncBackend, create_backend};
 #[allow(unused_imports)]
 pub use config::{SyncConfig, SyncConfigManager};
 #[allow(unused_imports)]
-pub use folder::{FolderStats, SyncFolder, SyncFoldersConfig, WebDavConfig};
+pub use crypto::{PASSPHRASE_ENV, SyncCipher, passphrase_from_env};
+#[allow(unused_imports)]
+pub use folder::{FolderStats, SyncBackendConfig, SyncFolder, SyncFoldersConfig, WebDavConfig};
 #[allow(unused_imports)]
 pub use folder_manager::SyncFolderManager;
 #[allow(unused_imports)]
+pub use manifest::{FileStatus, FileSyncState, SyncManifest, SyncStateStore};
+#[allow(unused_imports)]
+pub use merge::{ConflictChoice, MergeConflict, MergeOutcome, merge_toml};
+#[allow(unused_imports)]
+pub use reconcile::{ConflictResolver, PreferRemote, PullReport, PushPlan};
+#[allow(unused_imports)]
 pub use service::{SyncService, get_ccr_sync_path};
@@ -0,0 +1,354 @@
+// 🔄 同步协调
+// 📁 根据同步清单决定 push / pull 时每个文件的处理方式
+//
+// - 🔽 pull: 远程领先的文件直接应用，冲突的 TOML 文件三方合并，其余冲突逐个文件决定
+// - 🔼 push: 只上传本地领先的文件，远程领先或冲突的文件默认跳过，避免覆盖其他机器的修改
+
+use crate::core::error::Result;
+use crate::sync::manifest::{
+    FileChange, FileStatus, FileSyncState, FileTree, SyncManifest, SyncStateStore, diff_trees,
+    local_path_for,
+};
+use crate::sync::merge::{ConflictChoice, MergeConflict, merge_toml};
+use std::path::Path;
+
+/// 🎯 冲突解决器
+pub trait ConflictResolver {
+    /// TOML 文件中单个键的冲突
+    fn resolve_value(&mut self, file: &str, conflict: &MergeConflict) -> Result<ConflictChoice>;
+
+    /// 无法按键合并的整文件冲突（非 TOML、解析失败或一端已删除）
+    fn resolve_file(&mut self, status: &FileStatus) -> Result<ConflictChoice>;
+}
+
+/// ☁️ 冲突一律采用远程内容（`pull --force` 使用，等同于旧的覆盖行为）
+pub struct PreferRemote;
+
+impl ConflictResolver for PreferRemote {
+    fn resolve_value(&mut self, _file: &str, _conflict: &MergeConflict) -> Result<ConflictChoice> {
+        Ok(ConflictChoice::UseRemote)
+    }
+
+    fn resolve_file(&mut self, _status: &FileStatus) -> Result<ConflictChoice> {
+        Ok(ConflictChoice::UseRemote)
+    }
+}
+
+/// 📊 pull 结果
+#[derive(Debug, Default)]
+pub struct PullReport {
+    /// 已用远程内容更新的文件
+    pub updated: Vec<String>,
+    /// 随远程删除的本地文件
+    pub deleted: Vec<String>,
+    /// 三方合并的文件
+    pub merged: Vec<String>,
+    /// 本地领先或冲突时保留本地的文件（需要再次 push）
+    pub kept_local: Vec<String>,
+    /// 解决的键级冲突数量
+    pub resolved_conflicts: usize,
+}
+
+/// 🔽 将远程文件树协调到本地
+///
+/// 完成后清单中的 base 与远程一致，本地仍领先的文件会在下次 push 时上传
+pub fn apply_pull(
+    root: &Path,
+    store: &SyncStateStore,
+    manifest: &mut SyncManifest,
+    local: &FileTree,
+    remote: &FileTree,
+    resolver: &mut dyn ConflictResolver,
+) -> Result<PullReport> {
+    let mut report = PullReport::default();
+
+    for status in diff_trees(manifest, local, remote) {
+        let path = status.path.as_str();
+        let remote_content = remote.get(path);
+
+        match status.state {
+            FileSyncState::InSync => {}
+            FileSyncState::Ahead => report.kept_local.push(status.path.clone()),
+            FileSyncState::Behind => {
+                write_local(root, path, remote_content)?;
+                push_applied(&mut report, path, remote_content);
+            }
+            FileSyncState::Conflicted => {
+                let merged = match (local.get(path), remote_content) {
+                    (Some(l), Some(r)) if path.ends_with(".toml") => {
+                        let base = store.base_content(manifest, path).unwrap_or_default();
+                        try_merge(path, &base, l, r, resolver)?
+                    }
+                    _ => None,
+                };
+
+                if let Some((content, resolved)) = merged {
+                    write_local(root, path, Some(&content))?;
+                    report.merged.push(status.path.clone());
+                    report.resolved_conflicts += resolved;
+                } else if resolver.resolve_file(&status)? == ConflictChoice::UseRemote {
+                    write_local(root, path, remote_content)?;
+                    push_applied(&mut report, path, remote_content);
+                } else {
+                    report.kept_local.push(status.path.clone());
+                }
+            }
+        }
+
+        store.record(manifest, path, remote_content.map(Vec::as_slice))?;
+    }
+
+    store.save_manifest(manifest)?;
+    Ok(report)
+}
+
+/// 🔀 尝试按键合并，文本无法解析为 TOML 时返回 None 以退回整文件决策
+fn try_merge(
+    path: &str,
+    base: &[u8],
+    local: &[u8],
+    remote: &[u8],
+    resolver: &mut dyn ConflictResolver,
+) -> Result<Option<(Vec<u8>, usize)>> {
+    let (Ok(base), Ok(local), Ok(remote)) = (
+        std::str::from_utf8(base),
+        std::str::from_utf8(local),
+        std::str::from_utf8(remote),
+    ) else {
+        return Ok(None);
+    };
+
+    if toml::from_str::<toml::Table>(base).is_err()
+        || toml::from_str::<toml::Table>(local).is_err()
+        || toml::from_str::<toml::Table>(remote).is_err()
+    {
+        tracing::warn!("{} 无法解析为 TOML，改为整文件冲突处理", path);
+        return Ok(None);
+    }
+
+    let outcome = merge_toml(base, local, remote, |c| resolver.resolve_value(path, c))?;
+    Ok(Some((outcome.merged.into_bytes(), outcome.conflicts.len())))
+}
+
+fn push_applied(report: &mut PullReport, path: &str, content: Option<&Vec<u8>>) {
+    if content.is_some() {
+        report.updated.push(path.to_string());
+    } else {
+        report.deleted.push(path.to_string());
+    }
+}
+
+/// ✏️ 写入或删除本地文件
+fn write_local(root: &Path, rel: &str, content: Option<&Vec<u8>>) -> Result<()> {
+    let target = local_path_for(root, rel);
+    match content {
+        Some(content) => {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, content)?;
+        }
+        None => match std::fs::remove_file(&target) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        },
+    }
+    Ok(())
+}
+
+/// 📋 push 计划
+#[derive(Debug, Default)]
+pub struct PushPlan {
+    /// 需要上传的文件
+    pub upload: Vec<String>,
+    /// 需要从远程删除的文件（本地已删除）
+    pub delete: Vec<String>,
+    /// 已同步的文件
+    pub unchanged: Vec<String>,
+    /// 因远程领先或冲突而跳过的文件
+    pub skipped: Vec<FileStatus>,
+}
+
+/// 📋 生成 push 计划
+///
+/// # 参数
+/// - local: 本地文件树
+/// - selected: 选中的同步内容（相对路径或目录前缀），None 表示全部
+/// - force: 远程领先或冲突时也用本地内容覆盖远程
+pub fn plan_push(
+    statuses: &[FileStatus],
+    local: &FileTree,
+    selected: Option<&[String]>,
+    force: bool,
+) -> PushPlan {
+    let mut plan = PushPlan::default();
+
+    for status in statuses {
+        let is_selected = selected.is_none_or(|sel| {
+            sel.iter()
+                .any(|s| status.path == *s || status.path.starts_with(&format!("{}/", s)))
+        });
+        if !is_selected {
+            continue;
+        }
+
+        let overwrite = match status.state {
+            FileSyncState::InSync => {
+                plan.unchanged.push(status.path.clone());
+                continue;
+            }
+            FileSyncState::Ahead => true,
+            FileSyncState::Behind | FileSyncState::Conflicted => force,
+        };
+
+        if !overwrite {
+            plan.skipped.push(status.clone());
+        } else if local.contains_key(&status.path) {
+            plan.upload.push(status.path.clone());
+        } else if status.local == FileChange::Deleted {
+            plan.delete.push(status.path.clone());
+        }
+    }
+
+    plan
+}
+
+/// ✅ 记录 push 完成后的 base（上传/未变化的文件记录本地内容，已删除的移除）
+pub fn record_push(
+    store: &SyncStateStore,
+    manifest: &mut SyncManifest,
+    plan: &PushPlan,
+    local: &FileTree,
+) -> Result<()> {
+    for path in plan.upload.iter().chain(&plan.unchanged) {
+        store.record(manifest, path, local.get(path).map(Vec::as_slice))?;
+    }
+    for path in &plan.delete {
+        store.record(manifest, path, None)?;
+    }
+    store.save_manifest(manifest)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    struct PanicResolver;
+
+    impl ConflictResolver for PanicResolver {
+        fn resolve_value(&mut self, file: &str, c: &MergeConflict) -> Result<ConflictChoice> {
+            panic!("unexpected conflict {}:{}", file, c.path)
+        }
+        fn resolve_file(&mut self, status: &FileStatus) -> Result<ConflictChoice> {
+            panic!("unexpected file conflict {}", status.path)
+        }
+    }
+
+    fn tree(entries: &[(&str, &str)]) -> FileTree {
+        entries
+            .iter()
+            .map(|(p, c)| (p.to_string(), c.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pull_then_push_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let store = SyncStateStore::for_root(root);
+        let profiles = "platforms/claude/profiles.toml";
+
+        // 第一次同步：建立 base
+        let base = tree(&[
+            (profiles, "[a]\nmodel = \"m1\"\n\n[b]\nmodel = \"m1\"\n"),
+            ("notes.md", "hello\n"),
+        ]);
+        let mut manifest = store.load_manifest("remote").unwrap();
+        apply_pull(
+            root,
+            &store,
+            &mut manifest,
+            &FileTree::new(),
+            &base,
+            &mut PanicResolver,
+        )
+        .unwrap();
+        let local = crate::sync::manifest::read_tree(root).unwrap();
+        assert_eq!(local, base);
+
+        // 本机修改 a，另一台机器修改 b 并删除 notes.md
+        std::fs::write(
+            root.join(profiles),
+            "[a]\nmodel = \"local\"\n\n[b]\nmodel = \"m1\"\n",
+        )
+        .unwrap();
+        let remote = tree(&[(profiles, "[a]\nmodel = \"m1\"\n\n[b]\nmodel = \"remote\"\n")]);
+
+        let local = crate::sync::manifest::read_tree(root).unwrap();
+        let mut manifest = store.load_manifest("remote").unwrap();
+        let report = apply_pull(
+            root,
+            &store,
+            &mut manifest,
+            &local,
+            &remote,
+            &mut PanicResolver,
+        )
+        .unwrap();
+
+        assert_eq!(report.merged, vec![profiles.to_string()]);
+        assert_eq!(report.deleted, vec!["notes.md".to_string()]);
+        assert!(!root.join("notes.md").exists());
+        let merged: toml::Table =
+            toml::from_str(&std::fs::read_to_string(root.join(profiles)).unwrap()).unwrap();
+        assert_eq!(merged["a"]["model"].as_str(), Some("local"));
+        assert_eq!(merged["b"]["model"].as_str(), Some("remote"));
+
+        // 合并后本地领先，push 只上传该文件
+        let local = crate::sync::manifest::read_tree(root).unwrap();
+        let manifest = store.load_manifest("remote").unwrap();
+        let statuses = diff_trees(&manifest, &local, &remote);
+        let plan = plan_push(&statuses, &local, None, false);
+        assert_eq!(plan.upload, vec![profiles.to_string()]);
+        assert!(plan.skipped.is_empty() && plan.delete.is_empty());
+    }
+
+    #[test]
+    fn test_push_skips_behind_and_conflicted_unless_forced() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = SyncStateStore::for_root(temp.path());
+        let mut manifest = store.load_manifest("remote").unwrap();
+        let base = tree(&[
+            ("a.toml", "x = 1\n"),
+            ("b.toml", "y = 1\n"),
+            ("c.json", "{}"),
+        ]);
+        for (p, c) in &base {
+            store.record(&mut manifest, p, Some(c)).unwrap();
+        }
+
+        let local = tree(&[
+            ("a.toml", "x = 1\n"),
+            ("b.toml", "y = 2\n"),
+            ("d.toml", "z = 1\n"),
+        ]);
+        let remote = tree(&[
+            ("a.toml", "x = 9\n"),
+            ("b.toml", "y = 3\n"),
+            ("c.json", "{}"),
+        ]);
+        let statuses = diff_trees(&manifest, &local, &remote);
+
+        let plan = plan_push(&statuses, &local, None, false);
+        assert_eq!(plan.upload, vec!["d.toml".to_string()]);
+        assert_eq!(plan.delete, vec!["c.json".to_string()]);
+        let skipped: Vec<_> = plan.skipped.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(skipped, vec!["a.toml", "b.toml"]);
+
+        let forced = plan_push(&statuses, &local, Some(&["b.toml".to_string()]), true);
+        assert_eq!(forced.upload, vec!["b.toml".to_string()]);
+        assert!(forced.skipped.is_empty() && forced.delete.is_empty());
+    }
+}
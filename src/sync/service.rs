@@ -6,9 +6,12 @@
 // - 🔽 从 WebDAV 服务器下载配置
 // - 🔄 智能同步(基于时间戳)
 // - ✅ 连接测试
+// - 🔐 可选的端到端加密（上传前加密，下载后解密）
 
 use crate::core::error::{CcrError, Result};
 use crate::sync::config::SyncConfig;
+use crate::sync::crypto::{PASSPHRASE_ENV, SyncCipher, is_encrypted, passphrase_from_env};
+use crate::sync::manifest::{FileTree, read_tree};
 use reqwest_dav::list_cmd::ListEntity;
 use reqwest_dav::re_exports::reqwest::StatusCode;
 use reqwest_dav::{Auth, Client, ClientBuilder, Depth, Error as DavError};
@@ -22,6 +25,10 @@ use tokio::fs;
 pub struct SyncService {
     client: Client,
     remote_path: String,
+    /// 📍 远程标识（服务器地址 + 远程路径）
+    endpoint: String,
+    /// 🔐 加密器（None 表示明文同步）
+    cipher: Option<SyncCipher>,
 }
 
 #[allow(dead_code)]
@@ -50,9 +57,64 @@ impl SyncService {
         Ok(Self {
             client,
             remote_path: config.remote_path.clone(),
+            endpoint: format!("{}{}", config.webdav_url, config.remote_path),
+            cipher: None,
         })
     }
 
+    /// 📁 远程路径（以 `/` 结尾表示目录）
+    pub fn remote_path(&self) -> &str {
+        &self.remote_path
+    }
+
+    /// 📍 远程标识（服务器地址 + 远程路径）
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// 🔐 启用端到端加密
+    pub fn with_cipher(mut self, cipher: SyncCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// 🔐 按配置从环境变量读取口令并启用加密（用于 Web API 等非交互场景）
+    pub fn with_env_cipher(self, config: &SyncConfig) -> Result<Self> {
+        if !config.encryption {
+            return Ok(self);
+        }
+        let passphrase = passphrase_from_env().ok_or_else(|| {
+            CcrError::InvalidPassphrase(format!(
+                "已启用同步加密，请通过环境变量 {} 提供口令",
+                PASSPHRASE_ENV
+            ))
+        })?;
+        Ok(self.with_cipher(SyncCipher::new(&passphrase)?))
+    }
+
+    /// 🔒 按加密设置处理待上传的内容
+    fn seal(&self, content: Vec<u8>) -> Result<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(&content),
+            None => Ok(content),
+        }
+    }
+
+    /// 🔓 按加密设置处理下载的内容
+    ///
+    /// 未加密的远程文件原样返回（便于从明文同步平滑迁移）
+    fn open(&self, content: Vec<u8>) -> Result<Vec<u8>> {
+        if !is_encrypted(&content) {
+            return Ok(content);
+        }
+        match &self.cipher {
+            Some(cipher) => cipher.decrypt(&content),
+            None => Err(CcrError::InvalidPassphrase(
+                "远程内容已加密，但未提供同步口令".into(),
+            )),
+        }
+    }
+
     /// ✅ 测试连接
     ///
     /// 尝试列出远程目录以验证连接配置是否正确
@@ -116,6 +178,13 @@ impl SyncService {
             CcrError::SyncError(format!("读取本地文件失败 {}: {}", local_path.display(), e))
         })?;
 
+        self.put_content(remote_path, content).await
+    }
+
+    /// 🔼 上传内容到远程路径
+    async fn put_content(&self, remote_path: &str, content: Vec<u8>) -> Result<()> {
+        let content = self.seal(content)?;
+
         // 📁 确保远程目录存在
         self.ensure_remote_dir_for_file(remote_path).await?;
 
@@ -254,6 +323,7 @@ impl SyncService {
             .bytes()
             .await
             .map_err(|e| CcrError::SyncError(format!("读取响应内容失败: {}", e)))?;
+        let content = self.open(content.to_vec())?;
 
         // 📁 确保本地目录存在
         if let Some(parent) = local_path.parent() {
@@ -346,6 +416,56 @@ impl SyncService {
         Ok(())
     }
 
+    /// 📥 下载远程内容快照（用于与本地及同步清单对比）
+    ///
+    /// 远程不存在时返回空文件树
+    pub async fn fetch_snapshot(&self) -> Result<FileTree> {
+        if !self.remote_exists().await? {
+            return Ok(FileTree::new());
+        }
+
+        let temp_dir = tempfile::TempDir::new()
+            .map_err(|e| CcrError::SyncError(format!("创建临时目录失败: {}", e)))?;
+        let target = if self.remote_path.ends_with('/') {
+            temp_dir.path().to_path_buf()
+        } else {
+            temp_dir.path().join(extract_filename(&self.remote_path))
+        };
+
+        self.pull(&target).await?;
+        read_tree(&target)
+    }
+
+    /// 🔼 上传文件树中的单个条目
+    ///
+    /// # 参数
+    /// - rel_path: 相对同步根的路径（文件模式下忽略）
+    pub async fn push_entry(&self, rel_path: &str, content: Vec<u8>) -> Result<()> {
+        let remote_path = self.entry_remote_path(rel_path);
+        tracing::debug!("🔼 上传条目: {} -> {}", rel_path, remote_path);
+        self.put_content(&remote_path, content).await
+    }
+
+    /// 🗑️ 删除远程的单个条目
+    pub async fn delete_entry(&self, rel_path: &str) -> Result<()> {
+        let remote_path = self.entry_remote_path(rel_path);
+        tracing::debug!("🗑️ 删除远程条目: {}", remote_path);
+        match self.client.delete(&remote_path).await {
+            Ok(()) => Ok(()),
+            Err(DavError::Reqwest(e)) if e.status() == Some(StatusCode::NOT_FOUND) => Ok(()),
+            Err(e) => Err(self.map_dav_error(e, &format!("删除远程文件 {}", remote_path))),
+        }
+    }
+
+    /// 📍 计算条目的远程路径
+    fn entry_remote_path(&self, rel_path: &str) -> String {
+        if self.remote_path.ends_with('/') {
+            format!("{}{}", self.remote_path, rel_path)
+        } else {
+            self.remote_path.clone()
+        }
+    }
+
     /// 🔍 检查远程内容是否存在
     ///
     /// - 对于目录路径（以 `/` 结尾），使用 WebDAV `LIST/PROPFIND` 检查目录是否存在
@@ -526,8 +646,7 @@ fn extract_filename(href: &str) -> String {
 /// - CCR 内部目录 (.locks)
 /// - 备份目录 (backups, history 中的 .bak 文件)
 /// - 隐藏文件 (以 . 开头)
-#[allow(dead_code)]
-fn should_exclude_from_sync(name: &str) -> bool {
+pub(crate) fn should_exclude_from_sync(name: &str) -> bool {
     // 📝 排除规则列表
     let exclude_patterns = [
         // 临时文件
@@ -592,6 +711,7 @@ mod tests {
             password: "test_password".to_string(),
             remote_path: "/ccr/".to_string(), // 🆕 改为目录路径
             auto_sync: false,
+            encryption: false,
         }
     }
 
@@ -181,11 +181,7 @@ impl CodexAuthApp {
             KeyCode::PageDown | KeyCode::Char('l') => {
                 self.next_page();
             }
-            KeyCode::Enter => {
-                if self.switch_selected_account()? {
-                    return Ok(true);
-                }
-            }
+            KeyCode::Enter => return self.switch_selected_account(),
             KeyCode::Char('s') => {
                 if matches!(self.login_state, LoginState::LoggedInUnsaved) {
                     self.overlay = Some(Overlay::save_input());
@@ -3,7 +3,7 @@
 
 use crate::core::error::CcrError;
 use crate::managers::config::ConfigSection;
-use crate::services::ConfigService;
+use crate::services::{ConfigService, DriftService, PlatformDrift};
 use crate::web::{
     error_utils::{spawn_blocking_string, *},
     handlers::AppState,
@@ -17,7 +17,6 @@ use axum::{
 };
 use indexmap::IndexMap;
 use serde_json::json;
-use std::str::FromStr;
 use std::sync::Arc;
 
 /// 处理列出配置请求
@@ -31,13 +30,34 @@ pub async fn handle_list_configs(State(_state): State<AppState>) -> Response {
 
     let configs: Vec<ConfigItem> = configs_list.into_iter().map(ConfigItem::from).collect();
 
+    // 🧭 漂移检测失败不影响配置列表
+    let drift = spawn_blocking_string(current_platform_drift)
+        .await
+        .map_err(|e| tracing::warn!("检测设置漂移失败: {}", e))
+        .ok();
+
     success_response(ConfigListResponse {
         current_config: current_config_name,
         default_config: "-".to_string(),
         configs,
+        drift,
     })
 }
 
+/// 处理设置漂移查询请求（所有已启用平台，敏感值脱敏）
+pub async fn handle_get_drift(State(_state): State<AppState>) -> Response {
+    let result = spawn_blocking_string(|| {
+        let drifts = DriftService::with_default()?.detect_all()?;
+        Ok::<_, CcrError>(drifts.iter().map(PlatformDrift::masked).collect::<Vec<_>>())
+    })
+    .await;
+
+    match result {
+        Ok(drifts) => success_response(drifts),
+        Err(e) => internal_server_error(e),
+    }
+}
+
 /// 处理获取单个配置请求（返回完整信息，不掩码 token）
 /// 用于编辑功能，需要获取完整的 auth_token
 pub async fn handle_get_config(
@@ -200,6 +220,16 @@ fn get_platform_configs()
     }
 }
 
+/// 🧭 检测当前平台的设置漂移（敏感值脱敏）
+fn current_platform_drift() -> Result<PlatformDrift, CcrError> {
+    use crate::managers::PlatformConfigManager;
+
+    let current_platform = PlatformConfigManager::with_default()?
+        .load()?
+        .current_platform;
+    Ok(DriftService::detect(&current_platform)?.masked())
+}
+
 /// 🎯 Unified 模式配置获取
 fn get_unified_mode_configs(
     unified_path: Option<std::path::PathBuf>,
@@ -213,12 +243,8 @@ fn get_unified_mode_configs(
     let unified_config = platform_manager.load()?;
     let current_platform = unified_config.current_platform.clone();
 
-    let platform = match crate::models::Platform::from_str(&current_platform) {
-        Ok(p) => p,
-        Err(_) => return Err(CcrError::ConfigError("无效的平台".to_string())),
-    };
-
-    let platform_config = crate::platforms::create_platform(platform)?;
+    let platform_config = crate::platforms::create_platform_by_name(&current_platform)
+        .map_err(|_| CcrError::ConfigError("无效的平台".to_string()))?;
 
     // 🎯 处理平台加载错误 - 返回空配置而不是错误
     let profiles = match platform_config.load_profiles() {
@@ -241,26 +267,47 @@ fn get_unified_mode_configs(
         .and_then(|p| p.current_profile.clone())
         .unwrap_or_else(|| "-".to_string());
 
+    // 🧬 展示继承解析后的有效值，解析失败时回退为 profile 自身的值
     let configs: Vec<crate::services::config_service::ConfigInfo> = profiles
-        .into_iter()
-        .map(
-            |(name, profile)| crate::services::config_service::ConfigInfo {
+        .iter()
+        .map(|(name, own)| {
+            let resolved = crate::platforms::base::resolve_profile_section(&profiles, name).ok();
+            let profile = resolved
+                .as_ref()
+                .map(|r| crate::platforms::base::section_to_profile(&r.section))
+                .unwrap_or_else(|| own.clone());
+
+            crate::services::config_service::ConfigInfo {
                 name: name.clone(),
-                description: profile.description.unwrap_or_default(),
+                description: own.description.clone().unwrap_or_default(),
                 base_url: profile.base_url.clone(),
                 auth_token: profile.auth_token.clone(),
                 model: profile.model.clone(),
                 small_fast_model: profile.small_fast_model.clone(),
-                is_current: name == current_profile,
+                is_current: *name == current_profile,
                 is_default: false,
                 provider: profile.provider.clone(),
                 provider_type: profile.provider_type.clone(),
                 account: profile.account.clone(),
                 tags: profile.tags.clone(),
-                usage_count: profile.usage_count.unwrap_or(0),
-                enabled: profile.enabled.unwrap_or(true),
-            },
-        )
+                usage_count: own.usage_count.unwrap_or(0),
+                enabled: own.enabled.unwrap_or(true),
+                extends: own
+                    .platform_data
+                    .get(crate::managers::config::EXTENDS_KEY)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                is_template: crate::platforms::base::is_template_profile(own),
+                inherited_from: resolved
+                    .as_ref()
+                    .map(|r| {
+                        r.inherited_fields()
+                            .map(|(field, origin)| (field.to_string(), origin.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            }
+        })
         .collect();
 
     Ok((current_profile, configs))
@@ -301,10 +348,8 @@ fn get_single_config_unified(
     let unified_config = platform_manager.load()?;
     let current_platform = unified_config.current_platform.clone();
 
-    let platform = crate::models::Platform::from_str(&current_platform)
+    let platform_config = crate::platforms::create_platform_by_name(&current_platform)
         .map_err(|_| CcrError::ConfigError("无效的平台".to_string()))?;
-
-    let platform_config = crate::platforms::create_platform(platform)?;
     let profiles = platform_config.load_profiles()?;
 
     let current_profile = unified_config
@@ -2,7 +2,7 @@
 // 提供成本统计、预算管理、价格配置的 Web API
 
 use crate::core::error::CcrError;
-use crate::managers::{BudgetManager, CostTracker, PricingManager};
+use crate::managers::{BudgetManager, CostTracker, PricingManager, transcript_collector};
 use crate::models::stats::{CostRecord, ModelPricing};
 use crate::web::error_utils::{
     bad_request, empty_success_response, internal_server_error, spawn_blocking_string,
@@ -36,6 +36,7 @@ pub struct CostSummaryResponse {
 /// 获取成本摘要（今日/本周/本月）
 pub async fn handle_get_cost_summary(State(_state): State<AppState>) -> Response {
     match spawn_blocking_string(|| {
+        transcript_collector::auto_collect();
         let storage_dir = CostTracker::default_storage_dir()?;
         let tracker = CostTracker::new(storage_dir)?;
 
@@ -70,6 +71,7 @@ pub struct PeriodQuery {
 /// 获取指定期间的详细成本记录
 pub async fn handle_get_cost_details(Query(query): Query<PeriodQuery>) -> Response {
     match spawn_blocking_string(move || {
+        transcript_collector::auto_collect();
         let storage_dir = CostTracker::default_storage_dir()?;
         let tracker = CostTracker::new(storage_dir)?;
 
@@ -115,6 +117,7 @@ pub async fn handle_get_cost_details(Query(query): Query<PeriodQuery>) -> Respon
 /// 导出指定期间的成本数据为 CSV 格式
 pub async fn handle_export_costs(Query(query): Query<PeriodQuery>) -> Response {
     match spawn_blocking_string(move || {
+        transcript_collector::auto_collect();
         let storage_dir = CostTracker::default_storage_dir()?;
         let tracker = CostTracker::new(storage_dir)?;
 
@@ -171,6 +174,7 @@ pub struct ModelUsageItem {
 /// 按模型统计使用情况（当前月份）
 pub async fn handle_get_model_usage(State(_state): State<AppState>) -> Response {
     match spawn_blocking_string(|| {
+        transcript_collector::auto_collect();
         let storage_dir = CostTracker::default_storage_dir()?;
         let tracker = CostTracker::new(storage_dir)?;
 
@@ -247,6 +251,8 @@ pub struct BudgetWarning {
     pub current_cost: f64,
     pub limit: f64,
     pub usage_percent: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 /// GET /api/budget/status
@@ -255,6 +261,7 @@ pub struct BudgetWarning {
 pub async fn handle_get_budget_status(State(_state): State<AppState>) -> Response {
     match spawn_blocking_string(|| {
         let manager = BudgetManager::with_default()?;
+        transcript_collector::auto_collect();
         let storage_dir = CostTracker::default_storage_dir()?;
         let tracker = CostTracker::new(storage_dir)?;
 
@@ -270,6 +277,7 @@ pub async fn handle_get_budget_status(State(_state): State<AppState>) -> Respons
                 current_cost: w.current_cost,
                 limit: w.limit,
                 usage_percent: w.usage_percent,
+                scope: w.scope.as_ref().map(|scope| scope.to_string()),
             })
             .collect();
 
@@ -70,6 +70,8 @@ pub async fn handle_sync_config(Json(req): Json<SyncConfigRequest>) -> Response
         password: req.password,
         remote_path: req.remote_path,
         auto_sync: req.auto_sync,
+        // 加密设置只能通过 'ccr sync rekey' 修改，这里保留已有值
+        encryption: sync_manager.load().map(|c| c.encryption).unwrap_or(false),
     };
 
     // 测试连接
@@ -118,6 +120,12 @@ pub async fn handle_sync_push(Json(req): Json<SyncOperationRequest>) -> Response
         Err(e) => return internal_server_error(e.to_string()),
     };
 
+    // 🔐 启用加密时从环境变量读取口令
+    let service = match service.with_env_cipher(&sync_cfg) {
+        Ok(s) => s,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
     let push_result = if !req.force {
         // 如果远程存在且未强制，提示错误（UI 上可做确认弹窗）
         match service.remote_exists().await {
@@ -168,6 +176,12 @@ pub async fn handle_sync_pull(Json(_req): Json<SyncOperationRequest>) -> Respons
         Err(e) => return internal_server_error(e.to_string()),
     };
 
+    // 🔐 启用加密时从环境变量读取口令
+    let service = match service.with_env_cipher(&sync_cfg) {
+        Ok(s) => s,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
     match service.pull(&local_path).await {
         Ok(_) => success_response(SyncOperationResponse {
             message: "已成功从云端下载并应用".to_string(),
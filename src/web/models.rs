@@ -42,6 +42,9 @@ pub struct ConfigListResponse {
     pub current_config: String,
     pub default_config: String,
     pub configs: Vec<ConfigItem>,
+    /// 当前平台设置文件与当前 profile 的差异
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drift: Option<crate::services::PlatformDrift>,
 }
 
 /// 配置项
@@ -199,6 +199,7 @@ impl WebServer {
                 patch "/api/config/{name}/disable"      => crate::web::handlers::config_handlers::disable_config,
                 post "/api/export"                      => crate::web::handlers::config_handlers::handle_export,
                 post "/api/import"                      => crate::web::handlers::config_handlers::handle_import,
+                get  "/api/drift"                       => crate::web::handlers::config_handlers::handle_get_drift,
 
                 // Codex profiles
                 get  "/api/codex/profiles"              => crate::web::handlers::codex_handlers::handle_list_codex_profiles,
@@ -0,0 +1,246 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 配置快照 (backup) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `ccr switch` 前自动为所有配置文件创建快照，内容未变化时不重复创建
+// - `ccr backup diff` 按键显示变化并对 token 脱敏
+// - `ccr backup restore` 恢复所有文件，并先为当前状态创建安全快照
+// - `ccr backup verify` 发现损坏的数据块并以退出码 80 失败
+//
+// 共计: 4 个集成测试
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功并返回标准输出
+fn assert_success(output: Output, what: &str) -> String {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// 准备 alpha / beta 两个 Claude profile，当前为 alpha 并已应用
+fn setup(home: &Path) {
+    std::fs::create_dir_all(profiles_file(home).parent().unwrap()).unwrap();
+    std::fs::write(
+        profiles_file(home),
+        r#"
+default_config = "alpha"
+current_config = "alpha"
+
+[alpha]
+description = "Alpha 中转"
+base_url = "https://alpha.example.com"
+auth_token = "sk-alpha-123456"
+
+[beta]
+description = "Beta 中转"
+base_url = "https://beta.example.com"
+auth_token = "sk-beta-654321"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alpha"
+"#,
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(home.join(".claude")).unwrap();
+    std::fs::write(
+        settings_file(home),
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://alpha.example.com","ANTHROPIC_AUTH_TOKEN":"sk-alpha-123456"}}"#,
+    )
+    .unwrap();
+}
+
+fn profiles_file(home: &Path) -> PathBuf {
+    home.join(".ccr")
+        .join("platforms")
+        .join("claude")
+        .join("profiles.toml")
+}
+
+fn settings_file(home: &Path) -> PathBuf {
+    home.join(".claude").join("settings.json")
+}
+
+fn manifests_dir(home: &Path) -> PathBuf {
+    home.join(".ccr").join("snapshots").join("manifests")
+}
+
+fn read(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// 快照 ID 列表（按文件名排序）
+fn snapshot_ids(home: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(manifests_dir(home)) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .map(|entry| {
+            entry
+                .unwrap()
+                .path()
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    ids.sort();
+    ids
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 切换前自动快照
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_creates_deduplicated_snapshots() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    let ids = snapshot_ids(home.path());
+    assert_eq!(ids.len(), 1);
+
+    let list = assert_success(run_ccr(home.path(), &["backup", "list"]), "ccr backup list");
+    assert!(list.contains(&ids[0][..8]));
+    assert!(list.contains("switch"));
+
+    let show = assert_success(
+        run_ccr(home.path(), &["backup", "show", &ids[0][..8]]),
+        "ccr backup show",
+    );
+    assert!(show.contains("settings.json"));
+    assert!(show.contains("profiles.toml"));
+    assert!(show.contains("config.toml"));
+
+    // 手动恢复到 alpha 的内容后再切换：与已有快照相同，不重复创建
+    setup(home.path());
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    assert_eq!(snapshot_ids(home.path()).len(), 1);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 差异脱敏
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_backup_diff_masks_tokens() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    let id = snapshot_ids(home.path()).remove(0);
+
+    let diff = assert_success(
+        run_ccr(home.path(), &["backup", "diff", &id[..8]]),
+        "ccr backup diff",
+    );
+    assert!(diff.contains("env.ANTHROPIC_BASE_URL"));
+    assert!(diff.contains("https://beta.example.com"));
+    assert!(diff.contains("current_config"));
+    assert!(!diff.contains("sk-alpha-123456"));
+    assert!(!diff.contains("sk-beta-654321"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 恢复快照
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_backup_restore_creates_safety_snapshot() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    let id = snapshot_ids(home.path()).remove(0);
+
+    assert_success(
+        run_ccr(home.path(), &["-y", "backup", "restore", &id[..8]]),
+        "ccr backup restore",
+    );
+    assert!(read(&settings_file(home.path())).contains("https://alpha.example.com"));
+    assert!(read(&profiles_file(home.path())).contains("current_config = \"alpha\""));
+
+    // 恢复前的 beta 状态保存为安全快照，可以再恢复回去
+    let ids = snapshot_ids(home.path());
+    assert_eq!(ids.len(), 2);
+    let safety = ids.into_iter().find(|other| *other != id).unwrap();
+    assert_success(
+        run_ccr(home.path(), &["-y", "backup", "restore", &safety[..8]]),
+        "ccr backup restore (safety)",
+    );
+    assert!(read(&settings_file(home.path())).contains("https://beta.example.com"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 完整性校验
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_backup_verify_detects_corruption() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    assert_success(
+        run_ccr(home.path(), &["backup", "verify"]),
+        "ccr backup verify",
+    );
+
+    // 篡改一个数据块
+    let objects = home.path().join(".ccr").join("snapshots").join("objects");
+    let chunk = std::fs::read_dir(&objects)
+        .unwrap()
+        .flat_map(|dir| std::fs::read_dir(dir.unwrap().path()).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .next()
+        .unwrap();
+    std::fs::write(&chunk, b"tampered").unwrap();
+
+    let output = run_ccr(home.path(), &["backup", "verify"]);
+    assert_eq!(output.status.code(), Some(81));
+
+    // 损坏的快照拒绝恢复，不修改任何文件
+    let before = read(&settings_file(home.path()));
+    let id = snapshot_ids(home.path()).remove(0);
+    let output = run_ccr(home.path(), &["-y", "backup", "restore", &id[..8]]);
+    assert!(!output.status.success());
+    assert_eq!(read(&settings_file(home.path())), before);
+}
@@ -0,0 +1,256 @@
+#![cfg(unix)]
+#![allow(clippy::unwrap_used)]
+// 🧪 环境诊断 (doctor) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME、CCR_ROOT 与 PATH，不会影响真实配置
+//
+// 测试内容:
+// - PATH 中的 CLI 工具及其版本被识别，已启用平台缺少 CLI 时报告警告
+// - settings.json 与当前 profile 的漂移被检测并由 `--fix` 修复
+// - 可被其他用户读取的 token 文件由 `--fix` 收紧为 600
+// - shell 中覆盖 settings.json 的 ANTHROPIC_* 变量以脱敏形式报告
+//
+// 共计: 4 个集成测试
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT / PATH 下运行 ccr 命令
+///
+/// 清除宿主环境中的 ANTHROPIC_* / OPENAI_* 变量，再设置 `env` 中给出的变量
+fn run_ccr(home: &Path, args: &[&str], env: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccr"));
+    command
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env("PATH", home.join("bin"))
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .env_remove("CCR_LOCK_DIR");
+    for (name, _) in std::env::vars() {
+        if name.starts_with("ANTHROPIC_") || name.starts_with("OPENAI_") {
+            command.env_remove(name);
+        }
+    }
+    command.envs(env.iter().copied());
+    command.output().unwrap()
+}
+
+/// 运行 `ccr doctor --json` 并解析报告
+fn doctor_json(home: &Path, args: &[&str], env: &[(&str, &str)]) -> serde_json::Value {
+    let mut full = vec!["doctor", "--json"];
+    full.extend_from_slice(args);
+    let output = run_ccr(home, &full, env);
+    assert!(
+        output.status.success(),
+        "ccr doctor 失败:\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// 指定检查项的所有结果
+fn findings<'a>(report: &'a serde_json::Value, check: &str) -> Vec<&'a serde_json::Value> {
+    report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|finding| finding["check"] == check)
+        .collect()
+}
+
+/// 准备 alpha profile（已应用到 settings.json）和空的 bin 目录
+fn setup(home: &Path) {
+    std::fs::create_dir_all(profiles_file(home).parent().unwrap()).unwrap();
+    std::fs::write(
+        profiles_file(home),
+        r#"
+default_config = "alpha"
+current_config = "alpha"
+
+[alpha]
+description = "Alpha 中转"
+base_url = "https://alpha.example.com"
+auth_token = "sk-alpha-123456"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alpha"
+"#,
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(home.join(".claude")).unwrap();
+    std::fs::write(
+        settings_file(home),
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://alpha.example.com","ANTHROPIC_AUTH_TOKEN":"sk-alpha-123456"}}"#,
+    )
+    .unwrap();
+    std::fs::create_dir_all(home.join("bin")).unwrap();
+    set_mode(&profiles_file(home), 0o600);
+    set_mode(&settings_file(home), 0o600);
+}
+
+fn profiles_file(home: &Path) -> PathBuf {
+    home.join(".ccr")
+        .join("platforms")
+        .join("claude")
+        .join("profiles.toml")
+}
+
+fn settings_file(home: &Path) -> PathBuf {
+    home.join(".claude").join("settings.json")
+}
+
+fn set_mode(path: &Path, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: CLI 工具
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_doctor_detects_cli_versions() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    // 未安装 claude，但 claude 平台已启用
+    let report = doctor_json(home.path(), &[], &[]);
+    let cli = findings(&report, "cli");
+    assert_eq!(cli.len(), 4);
+    assert_eq!(cli[0]["severity"], "warning");
+    assert_eq!(cli[1]["severity"], "info");
+
+    let fake = home.path().join("bin").join("claude");
+    std::fs::write(&fake, "#!/bin/sh\necho '1.2.3 (Claude Code)'\n").unwrap();
+    set_mode(&fake, 0o755);
+
+    let report = doctor_json(home.path(), &[], &[]);
+    let cli = findings(&report, "cli");
+    assert_eq!(cli[0]["severity"], "ok");
+    assert!(cli[0]["title"].as_str().unwrap().contains("1.2.3"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: settings.json 漂移
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_doctor_fixes_settings_drift() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let report = doctor_json(home.path(), &[], &[]);
+    assert_eq!(findings(&report, "drift")[0]["severity"], "ok");
+
+    std::fs::write(
+        settings_file(home.path()),
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://other.example.com","ANTHROPIC_AUTH_TOKEN":"sk-other-999999"}}"#,
+    )
+    .unwrap();
+    set_mode(&settings_file(home.path()), 0o600);
+
+    let report = doctor_json(home.path(), &[], &[]);
+    let drift = findings(&report, "drift");
+    assert_eq!(drift[0]["severity"], "warning");
+    assert_eq!(drift[0]["fix"]["action"], "reapply_profile");
+    assert_eq!(drift[0]["fix"]["profile"], "alpha");
+    let details = drift[0]["details"].to_string();
+    assert!(details.contains("https://other.example.com"));
+    assert!(!details.contains("sk-other-999999"));
+    assert!(!details.contains("sk-alpha-123456"));
+
+    let report = doctor_json(home.path(), &["--fix"], &[]);
+    assert_eq!(report["fixes"][0]["action"], "reapply_profile");
+    assert_eq!(report["fixes"][0]["success"], true);
+    assert_eq!(findings(&report, "drift")[0]["severity"], "ok");
+    let settings = std::fs::read_to_string(settings_file(home.path())).unwrap();
+    assert!(settings.contains("https://alpha.example.com"));
+    assert!(settings.contains("sk-alpha-123456"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 文件权限
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_doctor_fix_restricts_token_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+    set_mode(&settings_file(home.path()), 0o644);
+
+    let report = doctor_json(home.path(), &[], &[]);
+    let permissions = findings(&report, "permissions");
+    assert_eq!(permissions.len(), 1);
+    assert_eq!(permissions[0]["severity"], "warning");
+    assert_eq!(permissions[0]["fix"]["action"], "restrict_permissions");
+    assert_eq!(report["summary"]["fixable"], 1);
+
+    // 文本输出提示可修复
+    let output = run_ccr(home.path(), &["doctor"], &[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("ccr doctor --fix"));
+
+    let report = doctor_json(home.path(), &["--fix"], &[]);
+    assert_eq!(findings(&report, "permissions")[0]["severity"], "ok");
+    let mode = std::fs::metadata(settings_file(home.path()))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: Shell 环境变量覆盖
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_doctor_reports_shadowing_shell_vars() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let report = doctor_json(home.path(), &[], &[]);
+    assert_eq!(findings(&report, "shell_env")[0]["severity"], "ok");
+
+    let report = doctor_json(
+        home.path(),
+        &[],
+        &[
+            ("ANTHROPIC_BASE_URL", "https://alpha.example.com"),
+            ("ANTHROPIC_AUTH_TOKEN", "sk-shell-abcdef"),
+        ],
+    );
+    let shell = findings(&report, "shell_env");
+    // 与 settings.json 相同的值不报告
+    assert_eq!(shell.len(), 1);
+    assert_eq!(shell[0]["severity"], "warning");
+    assert!(
+        shell[0]["title"]
+            .as_str()
+            .unwrap()
+            .contains("ANTHROPIC_AUTH_TOKEN")
+    );
+    let details = shell[0]["details"].to_string();
+    assert!(!details.contains("sk-shell-abcdef"));
+    assert!(!details.contains("sk-alpha-123456"));
+}
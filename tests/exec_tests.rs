@@ -0,0 +1,290 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 ccr exec 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - profile 的环境变量只注入子进程，全局 settings.json 不被创建或修改
+// - 子进程的退出码被原样转发，SIGTERM 被转发给子进程
+// - `--overlay` 在子进程运行期间写入设置文件，退出后恢复原内容
+// - overlay 期间被其他进程修改的文件保留当前内容，同一时间只有一个 overlay 生效
+//
+// 共计: 6 个集成测试
+#![cfg(unix)]
+
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 构造在隔离的 HOME / CCR_ROOT 下运行的 ccr 命令
+fn ccr(home: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccr"));
+    command
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env_remove("CCR_SETTINGS_PATH");
+    command
+}
+
+/// 在隔离环境下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    ccr(home, args).output().unwrap()
+}
+
+/// 准备两个 Claude profiles，当前为 main
+fn setup(home: &Path) {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        r#"
+default_config = "main"
+current_config = "main"
+
+[main]
+description = "主中转"
+base_url = "https://main.example.com"
+auth_token = "sk-main-123456"
+model = "claude-sonnet-4"
+
+[relay]
+description = "备用中转"
+base_url = "https://relay.example.com"
+auth_token = "sk-relay-123456"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "main"
+"#,
+    )
+    .unwrap();
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 环境变量只注入子进程
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_exec_injects_env_without_touching_settings() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = ccr(
+        home.path(),
+        &[
+            "exec",
+            "relay",
+            "--",
+            "sh",
+            "-c",
+            "echo \"$ANTHROPIC_BASE_URL|$ANTHROPIC_AUTH_TOKEN|${ANTHROPIC_MODEL:-unset}\"",
+        ],
+    )
+    .env("ANTHROPIC_MODEL", "leaked-from-shell")
+    .output()
+    .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "https://relay.example.com|sk-relay-123456|unset"
+    );
+    assert!(!home.path().join(".claude").join("settings.json").exists());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 退出码转发
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_exec_forwards_exit_code() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = run_ccr(home.path(), &["exec", "main", "--", "sh", "-c", "exit 7"]);
+    assert_eq!(output.status.code(), Some(7));
+
+    let output = run_ccr(home.path(), &["exec", "ghost", "--", "true"]);
+    assert!(!output.status.success());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: SIGTERM 转发
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_exec_forwards_sigterm() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let mut child = ccr(
+        home.path(),
+        &[
+            "exec",
+            "main",
+            "--",
+            "sh",
+            "-c",
+            "trap 'exit 42' TERM; sleep 10 & wait",
+        ],
+    )
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+    .unwrap();
+
+    std::thread::sleep(Duration::from_millis(1000));
+    let killed = Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+
+    assert_eq!(child.wait().unwrap().code(), Some(42));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 临时设置覆盖
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_exec_overlay_restores_settings() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let claude_dir = home.path().join(".claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    let original = r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"sk-main-123456"},"theme":"dark"}"#;
+    std::fs::write(claude_dir.join("settings.json"), original).unwrap();
+
+    let output = run_ccr(
+        home.path(),
+        &[
+            "exec",
+            "relay",
+            "--overlay",
+            "--",
+            "sh",
+            "-c",
+            "cat \"$HOME/.claude/settings.json\"",
+        ],
+    );
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("sk-relay-123456"));
+
+    assert_eq!(
+        std::fs::read_to_string(claude_dir.join("settings.json")).unwrap(),
+        original
+    );
+    let profiles = std::fs::read_to_string(
+        home.path()
+            .join(".ccr")
+            .join("platforms")
+            .join("claude")
+            .join("profiles.toml"),
+    )
+    .unwrap();
+    assert!(profiles.contains("current_config = \"main\""));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 5: overlay 期间被修改的文件不被覆盖
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_exec_overlay_keeps_files_changed_during_run() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let claude_dir = home.path().join(".claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    std::fs::write(
+        claude_dir.join("settings.json"),
+        r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"sk-main-123456"}}"#,
+    )
+    .unwrap();
+
+    // 子进程运行期间用户修改了 settings.json
+    let changed = r#"{"env":{},"theme":"light"}"#;
+    let output = run_ccr(
+        home.path(),
+        &[
+            "exec",
+            "relay",
+            "--overlay",
+            "--",
+            "sh",
+            "-c",
+            &format!(
+                "printf '%s' '{}' > \"$HOME/.claude/settings.json\"",
+                changed
+            ),
+        ],
+    );
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("未恢复"));
+
+    assert_eq!(
+        std::fs::read_to_string(claude_dir.join("settings.json")).unwrap(),
+        changed
+    );
+    // 未被修改的文件照常恢复
+    let profiles = std::fs::read_to_string(
+        home.path()
+            .join(".ccr")
+            .join("platforms")
+            .join("claude")
+            .join("profiles.toml"),
+    )
+    .unwrap();
+    assert!(profiles.contains("current_config = \"main\""));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 6: overlay 互斥
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_exec_overlays_are_exclusive() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let claude_dir = home.path().join(".claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    let original = r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"sk-main-123456"}}"#;
+    std::fs::write(claude_dir.join("settings.json"), original).unwrap();
+
+    let mut first = ccr(
+        home.path(),
+        &["exec", "relay", "--overlay", "--", "sleep", "8"],
+    )
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+    .unwrap();
+    std::thread::sleep(Duration::from_millis(1000));
+
+    let second = run_ccr(home.path(), &["exec", "relay", "--overlay", "--", "true"]);
+    assert!(!second.status.success());
+    assert!(String::from_utf8_lossy(&second.stderr).contains("另一个 ccr exec --overlay 正在运行"));
+
+    assert!(first.wait().unwrap().success());
+    assert_eq!(
+        std::fs::read_to_string(claude_dir.join("settings.json")).unwrap(),
+        original
+    );
+}
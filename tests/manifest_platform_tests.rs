@@ -0,0 +1,315 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 Manifest 声明式平台集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `ccr platform switch <自定义平台>` + `ccr switch` 写入 JSON 设置文件
+// - TOML 设置文件保留注释与未声明的键
+// - manifest 校验规则拒绝不合法的 profile
+// - 无效 manifest / 未声明的平台返回错误
+// - `ccr platform list --json` 列出自定义平台
+//
+// 共计: 5 个集成测试
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env_remove("CCR_SETTINGS_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功
+fn assert_success(output: &Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// 写入平台目录下的文件 (platform.toml / profiles.toml)
+fn write_platform_file(home: &Path, platform: &str, file: &str, content: &str) {
+    let dir = home.join(".ccr").join("platforms").join(platform);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(file), content).unwrap();
+}
+
+const ACME_MANIFEST: &str = r#"
+[platform]
+display_name = "Acme CLI"
+description = "内部 Acme 助手"
+settings_path = "~/.acme/settings.json"
+env_vars = ["ACME_API_KEY"]
+
+[fields]
+auth_token = "auth.apiKey"
+base_url = "auth.baseUrl"
+model = "model.name"
+"platform_data.region" = "region"
+
+[defaults]
+"auth.type" = "api-key"
+
+[validation]
+required = ["auth_token", "base_url"]
+
+[validation.patterns]
+auth_token = "^acme-[a-z0-9]{8,}$"
+base_url = "^https://"
+"#;
+
+const ACME_PROFILES: &str = r#"
+default_config = "prod"
+current_config = "prod"
+
+[prod]
+description = "生产环境"
+base_url = "https://api.acme.dev"
+auth_token = "acme-12345678"
+model = "acme-large"
+region = "eu-west"
+
+[staging]
+description = "预发环境"
+base_url = "https://staging.acme.dev"
+auth_token = "acme-87654321"
+
+[broken]
+description = "token 格式错误"
+base_url = "https://api.acme.dev"
+auth_token = "sk-not-acme"
+"#;
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: JSON 设置文件
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_manifest_platform_switch_json() {
+    let home = TempDir::new().unwrap();
+    write_platform_file(home.path(), "acme", "platform.toml", ACME_MANIFEST);
+    write_platform_file(home.path(), "acme", "profiles.toml", ACME_PROFILES);
+
+    let settings_path = home.path().join(".acme").join("settings.json");
+    std::fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &settings_path,
+        r#"{"theme":"dark","model":{"temperature":0.3}}"#,
+    )
+    .unwrap();
+
+    assert_success(
+        &run_ccr(home.path(), &["platform", "switch", "acme"]),
+        "ccr platform switch acme",
+    );
+    assert_success(
+        &run_ccr(home.path(), &["switch", "prod"]),
+        "ccr switch prod",
+    );
+
+    let settings: Value =
+        serde_json::from_str(&std::fs::read_to_string(&settings_path).unwrap()).unwrap();
+    assert_eq!(settings["auth"]["apiKey"], "acme-12345678");
+    assert_eq!(settings["auth"]["baseUrl"], "https://api.acme.dev");
+    assert_eq!(settings["auth"]["type"], "api-key");
+    assert_eq!(settings["model"]["name"], "acme-large");
+    assert_eq!(settings["model"]["temperature"], 0.3);
+    assert_eq!(settings["region"], "eu-west");
+    assert_eq!(settings["theme"], "dark");
+
+    // 切换到未设置 model/region 的 profile 时移除对应键
+    assert_success(
+        &run_ccr(home.path(), &["switch", "staging"]),
+        "ccr switch staging",
+    );
+    let settings: Value =
+        serde_json::from_str(&std::fs::read_to_string(&settings_path).unwrap()).unwrap();
+    assert_eq!(settings["auth"]["baseUrl"], "https://staging.acme.dev");
+    assert!(settings["model"].get("name").is_none());
+    assert!(settings.get("region").is_none());
+
+    let registry = std::fs::read_to_string(home.path().join(".ccr").join("config.toml")).unwrap();
+    assert!(registry.contains("current_platform = \"acme\""));
+    assert!(registry.contains("current_profile = \"staging\""));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: TOML 设置文件
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_manifest_platform_switch_toml_preserves_comments() {
+    let home = TempDir::new().unwrap();
+    write_platform_file(
+        home.path(),
+        "widget",
+        "platform.toml",
+        r#"
+[platform]
+settings_path = "~/.widget/config.toml"
+format = "toml"
+
+[fields]
+auth_token = "provider.token"
+base_url = "provider.url"
+model = "model"
+"#,
+    );
+    write_platform_file(
+        home.path(),
+        "widget",
+        "profiles.toml",
+        r#"
+default_config = "main"
+current_config = "main"
+
+[main]
+base_url = "https://widget.example.com"
+auth_token = "widget-token"
+model = "widget-pro"
+"#,
+    );
+
+    let settings_path = home.path().join(".widget").join("config.toml");
+    std::fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &settings_path,
+        "# Widget 配置\nmodel = \"old\" # 默认模型\n\n[ui]\ncolor = true\n",
+    )
+    .unwrap();
+
+    assert_success(
+        &run_ccr(home.path(), &["platform", "switch", "widget"]),
+        "ccr platform switch widget",
+    );
+    assert_success(
+        &run_ccr(home.path(), &["switch", "main"]),
+        "ccr switch main",
+    );
+
+    let content = std::fs::read_to_string(&settings_path).unwrap();
+    assert!(content.contains("# Widget 配置"));
+    assert!(content.contains("model = \"widget-pro\" # 默认模型"));
+    assert!(content.contains("[ui]\ncolor = true"));
+    let parsed: toml::Value = toml::from_str(&content).unwrap();
+    assert_eq!(parsed["provider"]["token"].as_str(), Some("widget-token"));
+    assert_eq!(
+        parsed["provider"]["url"].as_str(),
+        Some("https://widget.example.com")
+    );
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: manifest 校验规则
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_manifest_validation_rejects_profile() {
+    let home = TempDir::new().unwrap();
+    write_platform_file(home.path(), "acme", "platform.toml", ACME_MANIFEST);
+    write_platform_file(home.path(), "acme", "profiles.toml", ACME_PROFILES);
+
+    assert_success(
+        &run_ccr(home.path(), &["platform", "switch", "acme"]),
+        "ccr platform switch acme",
+    );
+
+    let output = run_ccr(home.path(), &["switch", "broken"]);
+    assert!(!output.status.success());
+    assert!(!home.path().join(".acme").join("settings.json").exists());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 无效 manifest 与未声明的平台
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_invalid_or_missing_manifest() {
+    let home = TempDir::new().unwrap();
+
+    // 未声明 manifest 的平台
+    let output = run_ccr(home.path(), &["platform", "switch", "ghost"]);
+    assert!(!output.status.success());
+
+    // 映射了未知字段
+    write_platform_file(
+        home.path(),
+        "bad",
+        "platform.toml",
+        r#"
+[platform]
+settings_path = "~/.bad/settings.json"
+
+[fields]
+nickname = "name"
+"#,
+    );
+    let output = run_ccr(home.path(), &["platform", "switch", "bad"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stderr.contains("nickname") || stdout.contains("nickname"));
+
+    // 内置平台名称不会被 manifest 覆盖
+    write_platform_file(
+        home.path(),
+        "claude",
+        "platform.toml",
+        "[platform]\nsettings_path = \"x.json\"\n",
+    );
+    let output = run_ccr(home.path(), &["platform", "info", "claude", "--json"]);
+    assert_success(&output, "ccr platform info claude");
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 5: 平台列表
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_platform_list_includes_manifest_platforms() {
+    let home = TempDir::new().unwrap();
+    write_platform_file(home.path(), "acme", "platform.toml", ACME_MANIFEST);
+
+    let output = run_ccr(home.path(), &["platform", "list", "--json"]);
+    assert_success(&output, "ccr platform list --json");
+
+    let list: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let acme = list["platforms"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "acme")
+        .expect("acme 应出现在平台列表中");
+    assert_eq!(acme["enabled"], false);
+    assert!(
+        acme["description"]
+            .as_str()
+            .unwrap()
+            .contains("内部 Acme 助手")
+    );
+
+    // 内置平台名称的目录不会重复列出
+    let claude_count = list["platforms"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|p| p["name"] == "claude")
+        .count();
+    assert_eq!(claude_count, 1);
+}
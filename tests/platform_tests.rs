@@ -65,13 +65,13 @@ fn test_platform_all() {
 #[test]
 fn test_platform_implemented() {
     let implemented = Platform::implemented();
-    assert_eq!(implemented.len(), 4);
+    assert_eq!(implemented.len(), 6);
     assert!(implemented.contains(&Platform::Claude));
     assert!(implemented.contains(&Platform::Codex));
     assert!(implemented.contains(&Platform::Gemini));
     assert!(implemented.contains(&Platform::Droid));
-    assert!(!implemented.contains(&Platform::Qwen));
-    assert!(!implemented.contains(&Platform::IFlow));
+    assert!(implemented.contains(&Platform::Qwen));
+    assert!(implemented.contains(&Platform::IFlow));
 }
 
 #[test]
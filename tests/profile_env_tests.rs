@@ -0,0 +1,235 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 Profile 额外环境变量与模型映射集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `[<profile>.env]` / `[<profile>.models]` / `auth_mode` 在切换时写入 settings.json
+// - 下次切换时清除上次写入的额外变量，用户自己设置的变量保持不变
+// - 已知变量取值错误或设置由 CCR 管理的变量时，校验与切换失败
+//
+// 共计: 3 个集成测试
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env_remove("CCR_SETTINGS_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功
+fn assert_success(output: &Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// 写入 Claude profiles，当前为 plain
+fn setup(home: &Path, profiles: &str) {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        format!(
+            r#"
+default_config = "plain"
+current_config = "plain"
+
+[plain]
+description = "普通中转"
+base_url = "https://plain.example.com"
+auth_token = "sk-plain-123456"
+{}"#,
+            profiles
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "plain"
+"#,
+    )
+    .unwrap();
+}
+
+/// 读取 Claude settings.json 的 env 部分
+fn settings_env(home: &Path) -> Value {
+    let settings: Value = serde_json::from_str(
+        &std::fs::read_to_string(home.join(".claude").join("settings.json")).unwrap(),
+    )
+    .unwrap();
+    settings["env"].clone()
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 切换时写入额外变量
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_applies_env_table_and_model_mapping() {
+    let home = TempDir::new().unwrap();
+    setup(
+        home.path(),
+        r#"
+[glm]
+description = "GLM 中转"
+base_url = "https://glm.example.com/api/anthropic"
+auth_token = "sk-glm-123456"
+auth_mode = "api_key"
+
+[glm.models]
+opus = "glm-4.6"
+sonnet = "glm-4.6"
+haiku = "glm-4.5-air"
+
+[glm.env]
+API_TIMEOUT_MS = 3000000
+CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC = true
+"#,
+    );
+
+    assert_success(&run_ccr(home.path(), &["switch", "glm"]), "ccr switch glm");
+    let env = settings_env(home.path());
+    assert_eq!(
+        env["ANTHROPIC_BASE_URL"],
+        "https://glm.example.com/api/anthropic"
+    );
+    assert_eq!(env["ANTHROPIC_API_KEY"], "sk-glm-123456");
+    assert!(env.get("ANTHROPIC_AUTH_TOKEN").is_none());
+    assert_eq!(env["ANTHROPIC_DEFAULT_OPUS_MODEL"], "glm-4.6");
+    assert_eq!(env["ANTHROPIC_DEFAULT_HAIKU_MODEL"], "glm-4.5-air");
+    assert_eq!(env["API_TIMEOUT_MS"], "3000000");
+    assert_eq!(env["CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC"], "1");
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 下次切换清除额外变量
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_next_switch_clears_stale_vars() {
+    let home = TempDir::new().unwrap();
+    setup(
+        home.path(),
+        r#"
+[slow]
+description = "慢速中转"
+base_url = "https://slow.example.com"
+auth_token = "sk-slow-123456"
+
+[slow.models]
+subagent = "claude-haiku-4-5"
+
+[slow.env]
+API_TIMEOUT_MS = 600000
+"#,
+    );
+
+    // 用户自己在 settings.json 中设置的变量
+    let claude_dir = home.path().join(".claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    std::fs::write(
+        claude_dir.join("settings.json"),
+        r#"{"env":{"MY_OWN_VAR":"keep"}}"#,
+    )
+    .unwrap();
+
+    assert_success(
+        &run_ccr(home.path(), &["switch", "slow"]),
+        "ccr switch slow",
+    );
+    let env = settings_env(home.path());
+    assert_eq!(env["API_TIMEOUT_MS"], "600000");
+    assert_eq!(env["CLAUDE_CODE_SUBAGENT_MODEL"], "claude-haiku-4-5");
+    // 写入记录保存在 ~/.ccr 中，不进入 env（否则会传给 Claude Code 的子进程）
+    assert!(env.get("CCR_MANAGED_ENV").is_none());
+    let state =
+        std::fs::read_to_string(home.path().join(".ccr").join("managed_settings.json")).unwrap();
+    assert!(state.contains("API_TIMEOUT_MS"));
+
+    assert_success(
+        &run_ccr(home.path(), &["switch", "plain"]),
+        "ccr switch plain",
+    );
+    let env = settings_env(home.path());
+    assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "sk-plain-123456");
+    assert!(env.get("API_TIMEOUT_MS").is_none());
+    assert!(env.get("CLAUDE_CODE_SUBAGENT_MODEL").is_none());
+    assert!(env.get("CCR_MANAGED_ENV").is_none());
+    assert_eq!(env["MY_OWN_VAR"], "keep");
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 已知变量校验
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_invalid_known_keys_are_rejected() {
+    let home = TempDir::new().unwrap();
+    setup(
+        home.path(),
+        r#"
+[bad-timeout]
+description = "错误的超时"
+base_url = "https://bad.example.com"
+auth_token = "sk-bad-123456"
+
+[bad-timeout.env]
+API_TIMEOUT_MS = "ten minutes"
+
+[bad-reserved]
+description = "重复设置 base_url"
+base_url = "https://bad.example.com"
+auth_token = "sk-bad-123456"
+
+[bad-reserved.env]
+ANTHROPIC_BASE_URL = "https://other.example.com"
+"#,
+    );
+
+    for (profile, expected) in [
+        ("bad-timeout", "API_TIMEOUT_MS"),
+        ("bad-reserved", "ANTHROPIC_BASE_URL"),
+    ] {
+        let output = run_ccr(home.path(), &["switch", profile]);
+        assert!(!output.status.success(), "切换到 {} 应失败", profile);
+        let combined = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(combined.contains(expected), "输出应提及 {}", expected);
+    }
+    assert!(!home.path().join(".claude").join("settings.json").exists());
+
+    let output = run_ccr(home.path(), &["validate"]);
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(combined.contains("env.API_TIMEOUT_MS 必须是正整数"));
+}
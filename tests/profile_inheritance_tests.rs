@@ -0,0 +1,204 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 配置继承 (extends) 与模板集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `ccr switch` 写入继承解析后的有效值
+// - `ccr list` / `ccr validate` 显示解析后的值与来源
+// - 循环继承与缺失的父配置节被报告为无效
+// - 模板不能直接切换，被继承的配置不能删除
+//
+// 共计: 4 个集成测试
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env_remove("CCR_SETTINGS_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功
+fn assert_success(output: &Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// 准备带继承关系的 Claude profiles
+fn setup(home: &Path, extra: &str) {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        format!(
+            r#"
+default_config = "alice"
+current_config = "alice"
+
+[relay]
+template = true
+description = "共享中转"
+base_url = "https://relay.example.com"
+model = "claude-sonnet-4"
+
+[alice]
+extends = "relay"
+auth_token = "sk-alice-123456"
+
+[alice-opus]
+extends = "alice"
+model = "claude-opus-4"
+{}"#,
+            extra
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alice"
+"#,
+    )
+    .unwrap();
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 切换到多级继承的配置
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_applies_resolved_values() {
+    let home = TempDir::new().unwrap();
+    setup(home.path(), "");
+
+    assert_success(
+        &run_ccr(home.path(), &["switch", "alice-opus"]),
+        "ccr switch alice-opus",
+    );
+
+    let settings: Value = serde_json::from_str(
+        &std::fs::read_to_string(home.path().join(".claude").join("settings.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        settings["env"]["ANTHROPIC_BASE_URL"],
+        "https://relay.example.com"
+    );
+    assert_eq!(settings["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-alice-123456");
+    assert_eq!(settings["env"]["ANTHROPIC_MODEL"], "claude-opus-4");
+
+    // 保存 usage_count 时保留 extends
+    let profiles = std::fs::read_to_string(
+        home.path()
+            .join(".ccr")
+            .join("platforms")
+            .join("claude")
+            .join("profiles.toml"),
+    )
+    .unwrap();
+    assert!(profiles.contains("extends = \"alice\""));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: list / validate 显示解析后的值与来源
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_list_and_validate_show_origins() {
+    let home = TempDir::new().unwrap();
+    setup(home.path(), "");
+
+    let output = run_ccr(home.path(), &["list"]);
+    assert_success(&output, "ccr list");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("↳ alice"));
+    assert!(stdout.contains("(← relay)"));
+    assert!(stdout.contains("模板"));
+
+    let output = run_ccr(home.path(), &["validate"]);
+    assert_success(&output, "ccr validate");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("alice-opus → alice → relay"));
+    assert!(stdout.contains("base_url = https://relay.example.com"));
+    assert!(!stdout.contains("sk-alice-123456"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 循环继承与缺失的父配置节
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_invalid_inheritance_reported() {
+    let home = TempDir::new().unwrap();
+    setup(
+        home.path(),
+        r#"
+[loop-a]
+extends = "loop-b"
+
+[loop-b]
+extends = "loop-a"
+
+[orphan]
+extends = "ghost"
+"#,
+    );
+
+    let output = run_ccr(home.path(), &["validate"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("loop-a → loop-b → loop-a"));
+    assert!(stdout.contains("ghost"));
+
+    let output = run_ccr(home.path(), &["switch", "loop-a"]);
+    assert!(!output.status.success());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 模板与被继承的配置
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_template_and_parent_protection() {
+    let home = TempDir::new().unwrap();
+    setup(home.path(), "");
+
+    let output = run_ccr(home.path(), &["switch", "relay"]);
+    assert!(!output.status.success());
+    assert!(!home.path().join(".claude").join("settings.json").exists());
+
+    // relay 被 alice 继承，不能删除
+    let output = run_ccr(home.path(), &["delete", "relay", "--force"]);
+    assert!(!output.status.success());
+    let profiles = std::fs::read_to_string(
+        home.path()
+            .join(".ccr")
+            .join("platforms")
+            .join("claude")
+            .join("profiles.toml"),
+    )
+    .unwrap();
+    assert!(profiles.contains("[relay]"));
+}
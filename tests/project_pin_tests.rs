@@ -0,0 +1,261 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 项目级 profile 固定集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `ccr switch --local` 写入 .ccr.toml 与 .claude/settings.local.json
+// - 用户级 settings.json 与全局当前 profile 保持不变
+// - `ccr current` 在项目子目录中报告项目来源，项目外报告全局来源
+// - `ccr switch --local` 遵守项目级预算（拒绝 / 回退）
+//
+// 共计: 4 个集成测试
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下、指定工作目录中运行 ccr 命令
+fn run_ccr(home: &Path, cwd: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .current_dir(cwd)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env_remove("CCR_SETTINGS_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功
+fn assert_success(output: &Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// 准备 Claude 平台的两个 profile 与一个带 .git 的仓库目录
+fn setup(home: &Path) -> std::path::PathBuf {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        r#"
+default_config = "internal"
+current_config = "internal"
+
+[internal]
+description = "内部中转"
+base_url = "https://internal.example.com"
+auth_token = "sk-internal-123456"
+model = "claude-sonnet-4"
+
+[client]
+description = "客户计费中转"
+base_url = "https://client.example.com"
+auth_token = "sk-client-abcdef"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "internal"
+"#,
+    )
+    .unwrap();
+
+    let repo = home.join("repo");
+    std::fs::create_dir_all(repo.join(".git")).unwrap();
+    std::fs::create_dir_all(repo.join("src").join("nested")).unwrap();
+    repo
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: switch --local
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_local_writes_project_files() {
+    let home = TempDir::new().unwrap();
+    let repo = setup(home.path());
+
+    let user_settings = home.path().join(".claude").join("settings.json");
+    std::fs::create_dir_all(user_settings.parent().unwrap()).unwrap();
+    std::fs::write(
+        &user_settings,
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://internal.example.com"}}"#,
+    )
+    .unwrap();
+
+    // 在子目录中执行，文件写入仓库根目录
+    let output = run_ccr(
+        home.path(),
+        &repo.join("src").join("nested"),
+        &["switch", "client", "--local"],
+    );
+    assert_success(&output, "ccr switch client --local");
+
+    let pin = std::fs::read_to_string(repo.join(".ccr.toml")).unwrap();
+    assert!(pin.contains("profile = \"client\""));
+    assert!(pin.contains("platform = \"claude\""));
+
+    let local: Value = serde_json::from_str(
+        &std::fs::read_to_string(repo.join(".claude").join("settings.local.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        local["env"]["ANTHROPIC_BASE_URL"],
+        "https://client.example.com"
+    );
+    assert_eq!(local["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-client-abcdef");
+
+    // 用户级设置与全局当前 profile 不受影响
+    let user: Value =
+        serde_json::from_str(&std::fs::read_to_string(&user_settings).unwrap()).unwrap();
+    assert_eq!(
+        user["env"]["ANTHROPIC_BASE_URL"],
+        "https://internal.example.com"
+    );
+    let registry = std::fs::read_to_string(home.path().join(".ccr").join("config.toml")).unwrap();
+    assert!(registry.contains("current_profile = \"internal\""));
+
+    // 历史中记录为项目级切换，而不是全局切换
+    let history: Value = serde_json::from_str(
+        &std::fs::read_to_string(home.path().join(".claude").join("ccr_history.json")).unwrap(),
+    )
+    .unwrap();
+    let entry = &history[0];
+    assert_eq!(entry["operation"], "LocalSwitch");
+    assert_eq!(entry["details"]["to_config"], "client");
+    assert!(
+        entry["details"]["extra"]
+            .as_str()
+            .unwrap()
+            .contains(repo.file_name().unwrap().to_str().unwrap())
+    );
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: current 报告生效来源
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_current_reports_project_source() {
+    let home = TempDir::new().unwrap();
+    let repo = setup(home.path());
+
+    assert_success(
+        &run_ccr(home.path(), &repo, &["switch", "client", "--local"]),
+        "ccr switch client --local",
+    );
+
+    let output = run_ccr(home.path(), &repo.join("src"), &["current"]);
+    assert_success(&output, "ccr current (项目内)");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("client.example.com"));
+    assert!(stdout.contains(".ccr.toml"));
+
+    let output = run_ccr(home.path(), home.path(), &["current"]);
+    assert_success(&output, "ccr current (项目外)");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("internal.example.com"));
+    assert!(!stdout.contains(".ccr.toml"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 不存在的 profile
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_local_unknown_profile() {
+    let home = TempDir::new().unwrap();
+    let repo = setup(home.path());
+
+    let output = run_ccr(home.path(), &repo, &["switch", "missing", "--local"]);
+    assert!(!output.status.success());
+    assert!(!repo.join(".ccr.toml").exists());
+    assert!(!repo.join(".claude").exists());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 项目级预算
+// ═══════════════════════════════════════════════════════════
+
+/// 写入预算配置与当月成本记录（仓库内已花费 $5）
+fn setup_project_budget(home: &Path, repo: &Path, action: &str) {
+    let claude_dir = home.join(".claude");
+    let stats_dir = claude_dir.join("stats");
+    std::fs::create_dir_all(&stats_dir).unwrap();
+    std::fs::write(
+        claude_dir.join("budget.toml"),
+        format!(
+            r#"
+enabled = true
+on_limit_exceeded = "{}"
+fallback_profile = "internal"
+
+[projects."{}"]
+daily_limit = 1.0
+"#,
+            action,
+            repo.display()
+        ),
+    )
+    .unwrap();
+
+    let now = chrono::Utc::now();
+    std::fs::write(
+        stats_dir.join(format!("costs_{}.csv", now.format("%Y%m"))),
+        format!(
+            "timestamp,id,session_id,project,platform,model,input_tokens,output_tokens,\
+            cache_read_tokens,cache_write_tokens,input_cost,output_cost,cache_cost,\
+            total_cost,duration_ms,description,profile\n\
+            {},rec-1,,{},claude,claude-sonnet-4,1000,100,0,0,5.0,0.0,0.0,5.0,0,,client\n",
+            now.to_rfc3339(),
+            repo.display()
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_switch_local_enforces_project_budget() {
+    let home = TempDir::new().unwrap();
+    let repo = setup(home.path());
+
+    // 超出项目预算时拒绝固定付费配置
+    setup_project_budget(home.path(), &repo, "block_paid");
+    let output = run_ccr(
+        home.path(),
+        &repo.join("src"),
+        &["switch", "client", "--local"],
+    );
+    assert!(!output.status.success());
+    assert!(!repo.join(".ccr.toml").exists());
+
+    // fallback 动作改为固定低成本配置
+    setup_project_budget(home.path(), &repo, "fallback");
+    let output = run_ccr(
+        home.path(),
+        &repo.join("src"),
+        &["switch", "client", "--local"],
+    );
+    assert_success(&output, "ccr switch client --local (fallback)");
+    let pin = std::fs::read_to_string(repo.join(".ccr.toml")).unwrap();
+    assert!(pin.contains("profile = \"internal\""));
+}
@@ -0,0 +1,308 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 Provider 流式基准测试集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，
+// 并对本地模拟的流式 /v1/messages (SSE) 端点进行测试，不会访问外部网络
+//
+// 测试内容:
+// - `ccr provider bench --tag` 按标签筛选并按首 token 延迟排名
+// - `--prompt` 读取提示词文件，`--format csv --output` 导出结果
+// - 表格输出与参数校验
+//
+// 共计: 3 个集成测试
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccr"));
+    command
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .env_remove("CCR_LOCK_DIR");
+    for (name, _) in std::env::vars() {
+        if name.starts_with("ANTHROPIC_")
+            || name.starts_with("OPENAI_")
+            || name.to_ascii_lowercase().ends_with("_proxy")
+        {
+            command.env_remove(name);
+        }
+    }
+    command.output().unwrap()
+}
+
+/// 运行 ccr 命令并断言成功，返回 stdout
+fn run_ok(home: &Path, args: &[&str]) -> String {
+    let output = run_ccr(home, args);
+    assert!(
+        output.status.success(),
+        "ccr {:?} 失败:\nstdout: {}\nstderr: {}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// 模拟端点的行为
+#[derive(Clone, Copy)]
+enum Mock {
+    /// 等待指定毫秒后开始流式输出 5 个 token
+    Stream { first_token_ms: u64 },
+    /// 返回 502
+    BadGateway,
+}
+
+/// 启动模拟的流式 /v1/messages 端点，收到的请求体记录在 `requests` 中
+fn start_mock_provider(mock: Mock, requests: Arc<Mutex<Vec<String>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { break };
+            let requests = requests.clone();
+            std::thread::spawn(move || handle(stream, mock, &requests));
+        }
+    });
+
+    endpoint
+}
+
+fn handle(mut stream: TcpStream, mock: Mock, requests: &Mutex<Vec<String>>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    };
+    let headers = String::from_utf8_lossy(&buf[..header_end]).to_ascii_lowercase();
+    let content_length: usize = headers
+        .lines()
+        .find_map(|l| l.strip_prefix("content-length:"))
+        .map_or(0, |v| v.trim().parse().unwrap());
+    while buf.len() < header_end + content_length {
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+    requests
+        .lock()
+        .unwrap()
+        .push(String::from_utf8_lossy(&buf[header_end..]).into_owned());
+
+    let Mock::Stream { first_token_ms } = mock else {
+        let body = r#"{"type":"error","error":{"type":"api_error","message":"upstream"}}"#;
+        let _ = stream.write_all(
+            format!(
+                "HTTP/1.1 502 Bad Gateway\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        );
+        return;
+    };
+
+    let _ = stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    );
+    let mut send = |event: &str, data: &str| {
+        let _ = stream.write_all(format!("event: {event}\ndata: {data}\n\n").as_bytes());
+        let _ = stream.flush();
+    };
+    send(
+        "message_start",
+        r#"{"type":"message_start","message":{"usage":{"input_tokens":12,"output_tokens":1}}}"#,
+    );
+    std::thread::sleep(Duration::from_millis(first_token_ms));
+    for _ in 0..5 {
+        send(
+            "content_block_delta",
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"ok "}}"#,
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    send(
+        "message_delta",
+        r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":5}}"#,
+    );
+    send("message_stop", r#"{"type":"message_stop"}"#);
+}
+
+/// 准备 fast / slow（标签 relay）与 broken（返回 502）三个 profile
+fn setup(home: &Path, requests: &Arc<Mutex<Vec<String>>>) {
+    let fast = start_mock_provider(Mock::Stream { first_token_ms: 0 }, requests.clone());
+    let slow = start_mock_provider(
+        Mock::Stream {
+            first_token_ms: 300,
+        },
+        requests.clone(),
+    );
+    let broken = start_mock_provider(Mock::BadGateway, requests.clone());
+
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "fast"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        format!(
+            r#"
+default_config = "fast"
+current_config = "fast"
+
+[slow]
+base_url = "{slow}"
+auth_token = "sk-slow-1234567890"
+model = "claude-slow"
+tags = ["relay"]
+
+[fast]
+base_url = "{fast}"
+auth_token = "sk-fast-1234567890"
+model = "claude-fast"
+tags = ["relay"]
+
+[broken]
+base_url = "{broken}"
+auth_token = "sk-broken-1234567890"
+"#
+        ),
+    )
+    .unwrap();
+}
+
+// ═══════════════════════════════════════════════════════════
+// 测试用例
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_bench_ranks_tagged_profiles_by_first_token_latency() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    setup(home, &requests);
+
+    let stdout = run_ok(
+        home,
+        &[
+            "provider", "bench", "--tag", "relay", "--runs", "2", "--format", "json",
+        ],
+    );
+    let results: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(results.len(), 2, "{stdout}");
+
+    let (fast, slow) = (&results[0], &results[1]);
+    assert_eq!(fast["profile"], "fast");
+    assert_eq!(fast["rank"], 1);
+    assert_eq!(slow["profile"], "slow");
+    assert_eq!(fast["runs"], 2);
+    assert_eq!(fast["error_rate"], 0.0);
+    assert_eq!(fast["output_tokens"], 5);
+    assert!(fast["tokens_per_sec"].as_f64().unwrap() > 0.0, "{fast}");
+    assert!(slow["ttft_ms"].as_u64().unwrap() >= 300, "{slow}");
+    assert!(fast["ttft_ms"].as_u64().unwrap() < slow["ttft_ms"].as_u64().unwrap());
+    assert!(slow["total_ms"].as_u64().unwrap() >= slow["ttft_ms"].as_u64().unwrap());
+    assert_eq!(slow["samples"].as_array().unwrap().len(), 2);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 4);
+    let body: serde_json::Value = serde_json::from_str(&requests[0]).unwrap();
+    assert_eq!(body["stream"], true);
+    assert!(body["model"] == "claude-fast" || body["model"] == "claude-slow");
+}
+
+#[test]
+fn test_bench_exports_csv_with_prompt_file() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    setup(home, &requests);
+
+    let prompt = home.join("prompt.txt");
+    std::fs::write(&prompt, "Say hello in five words.\n").unwrap();
+    let export = home.join("bench.csv");
+    run_ok(
+        home,
+        &[
+            "provider",
+            "bench",
+            "--runs",
+            "1",
+            "--prompt",
+            prompt.to_str().unwrap(),
+            "--format",
+            "csv",
+            "--output",
+            export.to_str().unwrap(),
+        ],
+    );
+
+    let csv = std::fs::read_to_string(&export).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4, "{csv}");
+    assert!(lines[0].starts_with("rank,profile,model"), "{csv}");
+    assert!(lines[1].starts_with("1,fast,claude-fast,"), "{csv}");
+    assert!(lines[3].starts_with("3,broken,"), "{csv}");
+    assert!(lines[3].contains(",100.0,"), "{csv}");
+    assert!(lines[3].contains("502"), "{csv}");
+
+    for request in requests.lock().unwrap().iter() {
+        let body: serde_json::Value = serde_json::from_str(request).unwrap();
+        assert_eq!(body["messages"][0]["content"], "Say hello in five words.");
+    }
+}
+
+#[test]
+fn test_bench_table_output_and_validation() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    setup(home, &requests);
+
+    let stdout = run_ok(home, &["provider", "bench", "-t", "relay", "-n", "1"]);
+    assert!(stdout.contains("排名"), "{stdout}");
+    assert!(stdout.contains("tok/s"), "{stdout}");
+    assert!(!stdout.contains("broken"), "{stdout}");
+
+    for args in [
+        &["provider", "bench", "--runs", "0"][..],
+        &["provider", "bench", "--tag", "missing"],
+        &["provider", "bench", "--format", "xml"],
+        &["provider", "bench", "--output", "bench.txt"],
+    ] {
+        let output = run_ccr(home, args);
+        assert!(!output.status.success(), "ccr {args:?} 应当失败");
+    }
+}
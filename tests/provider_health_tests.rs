@@ -0,0 +1,230 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 Provider 健康监控集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，
+// 并对本地模拟的 /v1/models 端点进行探测，不会访问外部网络
+//
+// 测试内容:
+// - `ccr provider monitor --once` 探测启用的 profile 并写入 SQLite 历史
+// - `ccr provider status` 汇总 p50/p95 延迟、可用率与最近错误
+// - 端点故障时记录状态变化
+//
+// 共计: 3 个集成测试
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccr"));
+    command
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .env_remove("CCR_LOCK_DIR");
+    for (name, _) in std::env::vars() {
+        if name.starts_with("ANTHROPIC_")
+            || name.starts_with("OPENAI_")
+            || name.to_ascii_lowercase().ends_with("_proxy")
+        {
+            command.env_remove(name);
+        }
+    }
+    command.output().unwrap()
+}
+
+/// 运行 ccr 命令并断言成功，返回 stdout
+fn run_ok(home: &Path, args: &[&str]) -> String {
+    let output = run_ccr(home, args);
+    assert!(
+        output.status.success(),
+        "ccr {:?} 失败:\nstdout: {}\nstderr: {}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// 启动模拟的 /v1/models 端点，`healthy` 为 false 时返回 502
+fn start_mock_provider(healthy: Arc<AtomicBool>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+
+            let (status, body) = if healthy.load(Ordering::SeqCst) {
+                ("200 OK", r#"{"data":[{"id":"claude-sonnet"}]}"#)
+            } else {
+                ("502 Bad Gateway", r#"{"error":"upstream"}"#)
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    endpoint
+}
+
+/// 返回一个没有服务监听的地址
+fn closed_endpoint() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+/// 准备 Claude 平台的 alpha（模拟端点）、beta（无法连接）与已禁用的 gamma
+fn setup(home: &Path, alpha_url: &str) {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alpha"
+"#,
+    )
+    .unwrap();
+
+    let closed = closed_endpoint();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        format!(
+            r#"
+default_config = "alpha"
+current_config = "alpha"
+
+[alpha]
+base_url = "{alpha_url}"
+auth_token = "sk-alpha-1234567890"
+model = "claude-sonnet"
+
+[beta]
+base_url = "{closed}"
+auth_token = "sk-beta-1234567890"
+
+[gamma]
+base_url = "{closed}"
+auth_token = "sk-gamma-1234567890"
+enabled = false
+"#
+        ),
+    )
+    .unwrap();
+}
+
+fn status_json(home: &Path) -> Vec<serde_json::Value> {
+    serde_json::from_str(&run_ok(home, &["provider", "status", "--json"])).unwrap()
+}
+
+fn stats_for<'a>(stats: &'a [serde_json::Value], profile: &str) -> &'a serde_json::Value {
+    stats.iter().find(|s| s["profile"] == profile).unwrap()
+}
+
+// ═══════════════════════════════════════════════════════════
+// 测试用例
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_monitor_records_history_for_enabled_profiles() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home, &start_mock_provider(Arc::new(AtomicBool::new(true))));
+
+    let stdout = run_ok(home, &["provider", "monitor", "--once"]);
+    assert!(stdout.contains("alpha"), "{stdout}");
+    assert!(!stdout.contains("gamma"), "{stdout}");
+    run_ok(home, &["provider", "monitor", "--once"]);
+
+    assert!(home.join(".ccr").join("data.db").exists());
+    let stats = status_json(home);
+    assert_eq!(stats.len(), 2, "{stats:?}");
+
+    let alpha = stats_for(&stats, "alpha");
+    assert_eq!(alpha["samples_24h"], 2);
+    assert_eq!(alpha["uptime_24h"], 100.0);
+    assert_eq!(alpha["last_status"], "healthy");
+    assert!(alpha["p50_ms"].is_u64() && alpha["p95_ms"].is_u64());
+    assert!(alpha["last_error"].is_null());
+
+    let beta = stats_for(&stats, "beta");
+    assert_eq!(beta["uptime_7d"], 0.0);
+    assert_eq!(beta["last_status"], "unhealthy");
+    assert!(beta["p50_ms"].is_null());
+    assert!(beta["last_error"].is_string());
+}
+
+#[test]
+fn test_status_reports_uptime_and_transitions_after_outage() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let healthy = Arc::new(AtomicBool::new(true));
+    setup(home, &start_mock_provider(healthy.clone()));
+
+    run_ok(home, &["provider", "monitor", "--once"]);
+    healthy.store(false, Ordering::SeqCst);
+    let stdout = run_ok(home, &["provider", "monitor", "--once"]);
+    assert!(stdout.contains("状态变化"), "{stdout}");
+
+    let alpha = stats_for(&status_json(home), "alpha").clone();
+    assert_eq!(alpha["uptime_24h"], 50.0);
+    assert_eq!(alpha["last_status"], "unhealthy");
+    assert!(
+        alpha["last_error"].as_str().unwrap().contains("502"),
+        "{alpha}"
+    );
+
+    let history: serde_json::Value =
+        serde_json::from_str(&run_ok(home, &["provider", "status", "alpha", "--json"])).unwrap();
+    let transitions = history["transitions"].as_array().unwrap();
+    assert_eq!(transitions.len(), 2);
+    assert_eq!(transitions[0]["from_status"], "healthy");
+    assert_eq!(transitions[0]["to_status"], "unhealthy");
+    assert_eq!(history["samples"].as_array().unwrap().len(), 2);
+
+    let table = run_ok(home, &["provider", "status"]);
+    assert!(table.contains("50.0%"), "{table}");
+}
+
+#[test]
+fn test_status_without_history_and_unknown_profile() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home, &closed_endpoint());
+
+    let stdout = run_ok(home, &["provider", "status"]);
+    assert!(stdout.contains("ccr provider monitor"), "{stdout}");
+
+    let output = run_ccr(home, &["provider", "status", "missing"]);
+    assert!(!output.status.success());
+}
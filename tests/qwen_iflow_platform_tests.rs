@@ -0,0 +1,442 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 Qwen / iFlow 平台集成测试
+//
+// ⚠️ **重要提示**: 库级测试修改全局环境变量 CCR_ROOT、CCR_QWEN_SETTINGS_PATH、
+// CCR_IFLOW_SETTINGS_PATH，通过 ENV_LOCK 串行执行；CLI 测试在独立进程中运行，
+// 使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - Qwen / iFlow profile 保存、加载、删除
+// - 应用 profile 写入 settings.json 并保留其他字段
+// - Profile 验证
+// - `ccr platform switch qwen` + `ccr switch` 完整流程
+// - `ccr platform switch iflow` + `ccr switch` 完整流程
+//
+// 共计: 8 个集成测试
+
+use ccr::{Platform, PlatformConfigManager, ProfileConfig, create_platform};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+/// 🔒 串行化修改环境变量的测试
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 创建临时测试环境，返回 (临时目录, Qwen settings 路径, iFlow settings 路径)
+fn setup_test_env() -> (TempDir, PathBuf, PathBuf) {
+    let temp_dir = TempDir::new().unwrap();
+    let qwen_settings = temp_dir.path().join(".qwen").join("settings.json");
+    let iflow_settings = temp_dir.path().join(".iflow").join("settings.json");
+
+    unsafe {
+        std::env::set_var("CCR_ROOT", temp_dir.path().join(".ccr"));
+        std::env::set_var("CCR_QWEN_SETTINGS_PATH", &qwen_settings);
+        std::env::set_var("CCR_IFLOW_SETTINGS_PATH", &iflow_settings);
+    }
+
+    (temp_dir, qwen_settings, iflow_settings)
+}
+
+/// 清理测试环境
+fn cleanup_test_env(temp_dir: TempDir) {
+    unsafe {
+        std::env::remove_var("CCR_ROOT");
+        std::env::remove_var("CCR_QWEN_SETTINGS_PATH");
+        std::env::remove_var("CCR_IFLOW_SETTINGS_PATH");
+    }
+    drop(temp_dir);
+}
+
+/// 创建测试用的 Qwen profile
+fn create_qwen_profile(name: &str) -> ProfileConfig {
+    let mut profile = ProfileConfig::new();
+    profile.description = Some(format!("Test Qwen profile: {}", name));
+    profile.base_url = Some("https://dashscope.aliyuncs.com/compatible-mode/v1".to_string());
+    profile.auth_token = Some("sk-qwen-1234567890abcdef".to_string());
+    profile.model = Some("qwen3-coder-plus".to_string());
+    profile.provider = Some("DashScope".to_string());
+    profile
+}
+
+/// 创建测试用的 iFlow profile
+fn create_iflow_profile(name: &str) -> ProfileConfig {
+    let mut profile = ProfileConfig::new();
+    profile.description = Some(format!("Test iFlow profile: {}", name));
+    profile.base_url = Some("https://apis.iflow.cn/v1".to_string());
+    profile.auth_token = Some("sk-iflow-1234567890abcdef".to_string());
+    profile.model = Some("Qwen3-Coder".to_string());
+    profile.provider = Some("iFlow".to_string());
+    profile
+}
+
+/// 读取 JSON 文件
+fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env_remove("CCR_QWEN_SETTINGS_PATH")
+        .env_remove("CCR_IFLOW_SETTINGS_PATH")
+        .env_remove("CCR_SETTINGS_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功
+fn assert_success(output: &std::process::Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// 写入 profiles.toml
+fn write_profiles(home: &Path, platform: &str, content: &str) {
+    let dir = home.join(".ccr").join("platforms").join(platform);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("profiles.toml"), content).unwrap();
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: Qwen profile 管理
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_qwen_profile_management() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (temp_dir, _, _) = setup_test_env();
+
+    let qwen = create_platform(Platform::Qwen).unwrap();
+    assert_eq!(qwen.platform_name(), "qwen");
+
+    qwen.save_profile("dashscope", &create_qwen_profile("dashscope"))
+        .unwrap();
+    let mut custom = create_qwen_profile("custom");
+    custom.base_url = Some("https://custom.example.com/v1".to_string());
+    qwen.save_profile("custom", &custom).unwrap();
+
+    let profiles = qwen.load_profiles().unwrap();
+    assert_eq!(profiles.len(), 2);
+    assert_eq!(
+        profiles.get("custom").unwrap().base_url,
+        Some("https://custom.example.com/v1".to_string())
+    );
+
+    // profiles 存储在统一目录下
+    let profiles_file = temp_dir
+        .path()
+        .join(".ccr")
+        .join("platforms")
+        .join("qwen")
+        .join("profiles.toml");
+    assert!(profiles_file.exists());
+
+    qwen.delete_profile("custom").unwrap();
+    assert_eq!(qwen.load_profiles().unwrap().len(), 1);
+    assert!(qwen.delete_profile("custom").is_err());
+
+    cleanup_test_env(temp_dir);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 应用 Qwen profile
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_qwen_apply_profile_preserves_settings() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (temp_dir, qwen_settings, _) = setup_test_env();
+
+    // 已有的 Qwen 设置（含 MCP 服务器）
+    std::fs::create_dir_all(qwen_settings.parent().unwrap()).unwrap();
+    std::fs::write(
+        &qwen_settings,
+        r#"{"mcpServers":{"fs":{"command":"npx"}},"ui":{"theme":"GitHub"}}"#,
+    )
+    .unwrap();
+
+    let manager = PlatformConfigManager::with_default().unwrap();
+    let mut config = manager.load_or_create_default().unwrap();
+    config
+        .register_platform("qwen".to_string(), ccr::PlatformConfigEntry::default())
+        .unwrap();
+    manager.save(&config).unwrap();
+
+    let qwen = create_platform(Platform::Qwen).unwrap();
+    assert_eq!(qwen.get_settings_path(), qwen_settings);
+    qwen.save_profile("dashscope", &create_qwen_profile("dashscope"))
+        .unwrap();
+    qwen.apply_profile("dashscope").unwrap();
+
+    let settings = read_json(&qwen_settings);
+    assert_eq!(settings["security"]["auth"]["selectedType"], "openai");
+    assert_eq!(
+        settings["security"]["auth"]["apiKey"],
+        "sk-qwen-1234567890abcdef"
+    );
+    assert_eq!(
+        settings["security"]["auth"]["baseUrl"],
+        "https://dashscope.aliyuncs.com/compatible-mode/v1"
+    );
+    assert_eq!(settings["model"]["name"], "qwen3-coder-plus");
+    assert_eq!(settings["mcpServers"]["fs"]["command"], "npx");
+    assert_eq!(settings["ui"]["theme"], "GitHub");
+
+    assert_eq!(
+        qwen.get_current_profile().unwrap(),
+        Some("dashscope".to_string())
+    );
+
+    cleanup_test_env(temp_dir);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 应用 iFlow profile
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_iflow_apply_profile_preserves_settings() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (temp_dir, _, iflow_settings) = setup_test_env();
+
+    std::fs::create_dir_all(iflow_settings.parent().unwrap()).unwrap();
+    std::fs::write(
+        &iflow_settings,
+        r#"{"theme":"Default","selectedAuthType":"oauth-iflow"}"#,
+    )
+    .unwrap();
+
+    let manager = PlatformConfigManager::with_default().unwrap();
+    let mut config = manager.load_or_create_default().unwrap();
+    config
+        .register_platform("iflow".to_string(), ccr::PlatformConfigEntry::default())
+        .unwrap();
+    manager.save(&config).unwrap();
+
+    let iflow = create_platform(Platform::IFlow).unwrap();
+    iflow
+        .save_profile("official", &create_iflow_profile("official"))
+        .unwrap();
+    let mut compatible = create_iflow_profile("compatible");
+    compatible.base_url = Some("https://api.example.com/v1".to_string());
+    iflow.save_profile("compatible", &compatible).unwrap();
+
+    iflow.apply_profile("official").unwrap();
+    let settings = read_json(&iflow_settings);
+    assert_eq!(settings["selectedAuthType"], "iflow");
+    assert_eq!(settings["apiKey"], "sk-iflow-1234567890abcdef");
+    assert_eq!(settings["baseUrl"], "https://apis.iflow.cn/v1");
+    assert_eq!(settings["modelName"], "Qwen3-Coder");
+    assert_eq!(settings["theme"], "Default");
+
+    // 第三方 OpenAI 兼容接口
+    iflow.apply_profile("compatible").unwrap();
+    let settings = read_json(&iflow_settings);
+    assert_eq!(settings["selectedAuthType"], "openai-compatible");
+    assert_eq!(settings["baseUrl"], "https://api.example.com/v1");
+    assert_eq!(
+        iflow.get_current_profile().unwrap(),
+        Some("compatible".to_string())
+    );
+
+    cleanup_test_env(temp_dir);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: Profile 验证
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_qwen_iflow_profile_validation() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (temp_dir, _, _) = setup_test_env();
+
+    for platform in [Platform::Qwen, Platform::IFlow] {
+        let platform_impl = create_platform(platform).unwrap();
+        let valid = create_qwen_profile("valid");
+        assert!(platform_impl.validate_profile(&valid).is_ok());
+
+        let mut no_token = valid.clone();
+        no_token.auth_token = Some("  ".to_string());
+        assert!(platform_impl.validate_profile(&no_token).is_err());
+        assert!(platform_impl.save_profile("bad", &no_token).is_err());
+
+        let mut bad_url = valid.clone();
+        bad_url.base_url = Some("ftp://example.com".to_string());
+        assert!(platform_impl.validate_profile(&bad_url).is_err());
+
+        // 保险库引用在保存时保持原样
+        let mut vault_ref = valid;
+        vault_ref.auth_token = Some("vault:qwen-main".to_string());
+        assert!(platform_impl.validate_profile(&vault_ref).is_ok());
+    }
+
+    // 应用不存在的 profile
+    let qwen = create_platform(Platform::Qwen).unwrap();
+    assert!(qwen.apply_profile("missing").is_err());
+
+    cleanup_test_env(temp_dir);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 5: 平台隔离
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_qwen_iflow_platform_isolation() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (temp_dir, qwen_settings, iflow_settings) = setup_test_env();
+
+    let qwen = create_platform(Platform::Qwen).unwrap();
+    let iflow = create_platform(Platform::IFlow).unwrap();
+    qwen.save_profile("shared", &create_qwen_profile("shared"))
+        .unwrap();
+    iflow
+        .save_profile("shared", &create_iflow_profile("shared"))
+        .unwrap();
+
+    qwen.delete_profile("shared").unwrap();
+    assert!(qwen.load_profiles().unwrap().is_empty());
+    assert!(iflow.load_profiles().unwrap().contains_key("shared"));
+
+    assert_ne!(qwen.get_settings_path(), iflow.get_settings_path());
+    assert!(!qwen_settings.exists());
+    assert!(!iflow_settings.exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 6: `ccr platform switch qwen` + `ccr switch`
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_cli_switch_qwen_workflow() {
+    let home = TempDir::new().unwrap();
+    let qwen_settings = home.path().join(".qwen").join("settings.json");
+    std::fs::create_dir_all(qwen_settings.parent().unwrap()).unwrap();
+    std::fs::write(&qwen_settings, r#"{"mcpServers":{"fs":{"command":"npx"}}}"#).unwrap();
+
+    let output = run_ccr(home.path(), &["platform", "switch", "qwen"]);
+    assert_success(&output, "ccr platform switch qwen");
+
+    write_profiles(
+        home.path(),
+        "qwen",
+        r#"
+default_config = "dashscope"
+current_config = "dashscope"
+
+[dashscope]
+description = "DashScope"
+base_url = "https://dashscope.aliyuncs.com/compatible-mode/v1"
+auth_token = "sk-qwen-1234567890abcdef"
+model = "qwen3-coder-plus"
+
+[openrouter]
+description = "OpenRouter"
+base_url = "https://openrouter.ai/api/v1"
+auth_token = "sk-or-1234567890abcdef"
+model = "qwen/qwen3-coder"
+"#,
+    );
+
+    let output = run_ccr(home.path(), &["switch", "openrouter"]);
+    assert_success(&output, "ccr switch openrouter");
+
+    let settings = read_json(&qwen_settings);
+    assert_eq!(
+        settings["security"]["auth"]["baseUrl"],
+        "https://openrouter.ai/api/v1"
+    );
+    assert_eq!(
+        settings["security"]["auth"]["apiKey"],
+        "sk-or-1234567890abcdef"
+    );
+    assert_eq!(settings["model"]["name"], "qwen/qwen3-coder");
+    assert_eq!(settings["mcpServers"]["fs"]["command"], "npx");
+
+    // 注册表记录当前 profile，Claude 设置未被触碰
+    let registry = std::fs::read_to_string(home.path().join(".ccr").join("config.toml")).unwrap();
+    assert!(registry.contains("current_platform = \"qwen\""));
+    assert!(registry.contains("current_profile = \"openrouter\""));
+    assert!(!home.path().join(".claude").join("settings.json").exists());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 7: `ccr platform switch iflow` + `ccr switch`
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_cli_switch_iflow_workflow() {
+    let home = TempDir::new().unwrap();
+
+    let output = run_ccr(home.path(), &["platform", "switch", "iflow"]);
+    assert_success(&output, "ccr platform switch iflow");
+
+    write_profiles(
+        home.path(),
+        "iflow",
+        r#"
+default_config = "official"
+current_config = "official"
+
+[official]
+description = "iFlow 官方"
+base_url = "https://apis.iflow.cn/v1"
+auth_token = "sk-iflow-1234567890abcdef"
+model = "Qwen3-Coder"
+"#,
+    );
+
+    let output = run_ccr(home.path(), &["switch", "official"]);
+    assert_success(&output, "ccr switch official");
+
+    // settings.json 不存在时自动创建
+    let settings = read_json(&home.path().join(".iflow").join("settings.json"));
+    assert_eq!(settings["selectedAuthType"], "iflow");
+    assert_eq!(settings["apiKey"], "sk-iflow-1234567890abcdef");
+    assert_eq!(settings["baseUrl"], "https://apis.iflow.cn/v1");
+    assert_eq!(settings["modelName"], "Qwen3-Coder");
+
+    // usage_count 写回 profiles.toml
+    let profiles = std::fs::read_to_string(
+        home.path()
+            .join(".ccr")
+            .join("platforms")
+            .join("iflow")
+            .join("profiles.toml"),
+    )
+    .unwrap();
+    assert!(profiles.contains("usage_count = 1"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 8: 切换到不存在的 profile
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_cli_switch_qwen_missing_profile() {
+    let home = TempDir::new().unwrap();
+
+    let output = run_ccr(home.path(), &["platform", "switch", "qwen"]);
+    assert_success(&output, "ccr platform switch qwen");
+
+    let output = run_ccr(home.path(), &["switch", "missing"]);
+    assert!(!output.status.success());
+    assert!(!home.path().join(".qwen").join("settings.json").exists());
+}
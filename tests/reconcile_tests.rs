@@ -0,0 +1,250 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 设置漂移 (reconcile) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - 手动修改 settings.json 后 `ccr status` 逐项显示漂移，敏感值脱敏
+// - `ccr reconcile` 重新应用 profile
+// - `ccr reconcile --adopt` 将当前值写回 profile
+// - 非 Claude 平台 (Qwen) 的漂移检测与 `--dry-run` 预览
+//
+// 共计: 4 个集成测试
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccr"));
+    command
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .env_remove("CCR_LOCK_DIR")
+        .env_remove("CCR_QWEN_SETTINGS_PATH");
+    for (name, _) in std::env::vars() {
+        if name.starts_with("ANTHROPIC_") || name.starts_with("OPENAI_") {
+            command.env_remove(name);
+        }
+    }
+    command.output().unwrap()
+}
+
+/// 运行 ccr 命令并断言成功，返回 stdout
+fn run_ok(home: &Path, args: &[&str]) -> String {
+    let output = run_ccr(home, args);
+    assert!(
+        output.status.success(),
+        "ccr {:?} 失败:\nstdout: {}\nstderr: {}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn profiles_file(home: &Path, platform: &str) -> PathBuf {
+    home.join(".ccr")
+        .join("platforms")
+        .join(platform)
+        .join("profiles.toml")
+}
+
+fn claude_settings(home: &Path) -> PathBuf {
+    home.join(".claude").join("settings.json")
+}
+
+fn read_json(path: &Path) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn write_json(path: &Path, value: &serde_json::Value) {
+    std::fs::write(path, serde_json::to_string_pretty(value).unwrap()).unwrap();
+}
+
+/// 准备已启用的 Claude 与 Qwen 平台，并将 Claude 的 alpha profile 应用到 settings.json
+fn setup(home: &Path) {
+    std::fs::create_dir_all(home.join(".ccr")).unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alpha"
+
+[qwen]
+enabled = true
+current_profile = "dashscope"
+"#,
+    )
+    .unwrap();
+
+    for (platform, content) in [
+        (
+            "claude",
+            r#"
+[alpha]
+base_url = "https://alpha.example.com"
+auth_token = "sk-alpha-1234567890"
+model = "claude-sonnet"
+"#,
+        ),
+        (
+            "qwen",
+            r#"
+[dashscope]
+base_url = "https://dashscope.example.com/v1"
+auth_token = "sk-qwen-1234567890"
+model = "qwen3-coder-plus"
+"#,
+        ),
+    ] {
+        let path = profiles_file(home, platform);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    std::fs::create_dir_all(home.join(".claude")).unwrap();
+    std::fs::write(claude_settings(home), "{}").unwrap();
+    run_ok(home, &["-y", "switch", "alpha"]);
+}
+
+/// 模拟在 Claude Code 中运行 /model 并手动替换 token
+fn edit_claude_settings(home: &Path) {
+    let mut settings = read_json(&claude_settings(home));
+    settings["env"]["ANTHROPIC_MODEL"] = "claude-opus".into();
+    settings["env"]["ANTHROPIC_AUTH_TOKEN"] = "sk-other-9876543210".into();
+    write_json(&claude_settings(home), &settings);
+}
+
+/// 写入与 dashscope profile 一致、但 baseUrl 被改动的 Qwen 设置
+fn write_drifted_qwen_settings(home: &Path) -> PathBuf {
+    let path = home.join(".qwen").join("settings.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    write_json(
+        &path,
+        &serde_json::json!({
+            "security": {
+                "auth": {
+                    "selectedType": "openai",
+                    "apiKey": "sk-qwen-1234567890",
+                    "baseUrl": "https://hand-edited.example.com/v1"
+                }
+            },
+            "model": { "name": "qwen3-coder-plus" },
+            "mcpServers": { "fs": { "command": "mcp-fs" } }
+        }),
+    );
+    path
+}
+
+// ═══════════════════════════════════════════════════════════
+// 测试用例
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_status_shows_drift_field_by_field_with_masked_tokens() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home);
+
+    let stdout = run_ok(home, &["status"]);
+    assert!(
+        stdout.contains("claude: 设置与当前 profile 一致"),
+        "{stdout}"
+    );
+
+    edit_claude_settings(home);
+    let stdout = run_ok(home, &["status"]);
+    assert!(stdout.contains("ANTHROPIC_MODEL"), "{stdout}");
+    assert!(stdout.contains("claude-opus"), "{stdout}");
+    assert!(stdout.contains("ANTHROPIC_AUTH_TOKEN"), "{stdout}");
+    assert!(!stdout.contains("sk-other-9876543210"), "{stdout}");
+    assert!(stdout.contains("ccr reconcile"), "{stdout}");
+}
+
+#[test]
+fn test_reconcile_reapplies_profile() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home);
+    edit_claude_settings(home);
+
+    run_ok(home, &["-y", "reconcile", "-p", "claude"]);
+
+    let env = read_json(&claude_settings(home))["env"].clone();
+    assert_eq!(env["ANTHROPIC_MODEL"], "claude-sonnet");
+    assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "sk-alpha-1234567890");
+    let profiles = std::fs::read_to_string(profiles_file(home, "claude")).unwrap();
+    assert!(
+        profiles.contains(r#"model = "claude-sonnet""#),
+        "{profiles}"
+    );
+
+    let stdout = run_ok(home, &["reconcile", "-p", "claude"]);
+    assert!(stdout.contains("一致"), "{stdout}");
+}
+
+#[test]
+fn test_reconcile_adopt_writes_live_values_into_profile() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home);
+    edit_claude_settings(home);
+
+    run_ok(home, &["-y", "reconcile", "-p", "claude", "--adopt"]);
+
+    let profiles = std::fs::read_to_string(profiles_file(home, "claude")).unwrap();
+    assert!(profiles.contains(r#"model = "claude-opus""#), "{profiles}");
+    assert!(
+        profiles.contains(r#"auth_token = "sk-other-9876543210""#),
+        "{profiles}"
+    );
+    let env = read_json(&claude_settings(home))["env"].clone();
+    assert_eq!(env["ANTHROPIC_MODEL"], "claude-opus");
+
+    let stdout = run_ok(home, &["status"]);
+    assert!(
+        stdout.contains("claude: 设置与当前 profile 一致"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_reconcile_detects_drift_on_other_platforms() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home);
+    let qwen_settings = write_drifted_qwen_settings(home);
+
+    let stdout = run_ok(home, &["reconcile", "--dry-run"]);
+    assert!(stdout.contains("qwen (profile: dashscope)"), "{stdout}");
+    assert!(stdout.contains("OPENAI_BASE_URL"), "{stdout}");
+    assert!(stdout.contains("hand-edited.example.com"), "{stdout}");
+    assert!(!stdout.contains("claude (profile"), "{stdout}");
+    assert_eq!(
+        read_json(&qwen_settings)["security"]["auth"]["baseUrl"],
+        "https://hand-edited.example.com/v1"
+    );
+
+    run_ok(home, &["-y", "reconcile"]);
+
+    let settings = read_json(&qwen_settings);
+    assert_eq!(
+        settings["security"]["auth"]["baseUrl"],
+        "https://dashscope.example.com/v1"
+    );
+    assert_eq!(settings["mcpServers"]["fs"]["command"], "mcp-fs");
+}
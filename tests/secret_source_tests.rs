@@ -0,0 +1,183 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 动态密钥来源集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `${env:VAR}` / `${file:~/path}` 在切换时解析并写入 settings.json
+// - `auth_token_cmd` 执行命令获取 token，配置文件中不出现明文
+// - 解析失败时切换失败并返回专用退出码，settings.json 不被写入
+//
+// 共计: 3 个集成测试
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env_remove("CCR_SETTINGS_PATH")
+        .envs(envs.iter().copied())
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功
+fn assert_success(output: &Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// 准备使用间接密钥的 Claude profiles
+fn setup(home: &Path) {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        r#"
+default_config = "from-env"
+current_config = "from-env"
+
+[from-env]
+description = "环境变量"
+base_url = "https://${env:CCR_IT_RELAY_HOST}/v1"
+auth_token = "${env:CCR_IT_RELAY_TOKEN}"
+
+[from-file]
+description = "文件"
+base_url = "https://relay.example.com"
+auth_token = "${file:~/.secrets/relay}"
+
+[from-cmd]
+description = "命令"
+base_url = "https://relay.example.com"
+auth_token_cmd = "echo sk-from-cmd-123456"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "from-env"
+"#,
+    )
+    .unwrap();
+}
+
+/// 读取 Claude settings.json 的 env 部分
+fn settings_env(home: &Path) -> Value {
+    let settings: Value = serde_json::from_str(
+        &std::fs::read_to_string(home.join(".claude").join("settings.json")).unwrap(),
+    )
+    .unwrap();
+    settings["env"].clone()
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 环境变量与文件来源
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_resolves_env_and_file_sources() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let envs = [
+        ("CCR_IT_RELAY_HOST", "relay.example.com"),
+        ("CCR_IT_RELAY_TOKEN", "sk-from-env-123456"),
+    ];
+    assert_success(
+        &run_ccr(home.path(), &["switch", "from-env"], &envs),
+        "ccr switch from-env",
+    );
+    let env = settings_env(home.path());
+    assert_eq!(env["ANTHROPIC_BASE_URL"], "https://relay.example.com/v1");
+    assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "sk-from-env-123456");
+
+    let secrets = home.path().join(".secrets");
+    std::fs::create_dir_all(&secrets).unwrap();
+    std::fs::write(secrets.join("relay"), "sk-from-file-123456\n").unwrap();
+    assert_success(
+        &run_ccr(home.path(), &["switch", "from-file"], &[]),
+        "ccr switch from-file",
+    );
+    assert_eq!(
+        settings_env(home.path())["ANTHROPIC_AUTH_TOKEN"],
+        "sk-from-file-123456"
+    );
+
+    // 配置文件中仍然只有引用
+    let profiles = std::fs::read_to_string(
+        home.path()
+            .join(".ccr")
+            .join("platforms")
+            .join("claude")
+            .join("profiles.toml"),
+    )
+    .unwrap();
+    assert!(profiles.contains("${env:CCR_IT_RELAY_TOKEN}"));
+    assert!(!profiles.contains("sk-from-env-123456"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 凭证命令
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(unix)]
+#[test]
+fn test_switch_runs_credential_command() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = run_ccr(home.path(), &["validate"], &[]);
+    assert_success(&output, "ccr validate");
+
+    assert_success(
+        &run_ccr(home.path(), &["switch", "from-cmd"], &[]),
+        "ccr switch from-cmd",
+    );
+    assert_eq!(
+        settings_env(home.path())["ANTHROPIC_AUTH_TOKEN"],
+        "sk-from-cmd-123456"
+    );
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 解析失败
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_unresolved_secret_fails_switch() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = run_ccr(home.path(), &["switch", "from-env"], &[]);
+    assert!(!output.status.success());
+    assert_eq!(output.status.code(), Some(78));
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(combined.contains("CCR_IT_RELAY_TOKEN"));
+    assert!(!home.path().join(".claude").join("settings.json").exists());
+}
@@ -0,0 +1,241 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 设置包 (settings bundle) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - 切换时深度合并 bundle 文件与内联 settings 表，用户已有的设置保持不变
+// - 切换到其他 profile 时移除上一个设置包写入的部分
+// - `ccr switch --dry-run` 显示 JSON 路径差异且不写入任何文件
+// - 写入记录保存在 ~/.ccr 中，旧版本 env.CCR_MANAGED_SETTINGS 记录被迁移并移除
+//
+// 共计: 4 个集成测试
+
+use serde_json::{Value, json};
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功并返回标准输出
+fn assert_success(output: Output, what: &str) -> String {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// 准备 plain / client 两个 profile，client 使用设置包；settings.json 中已有用户设置
+fn setup(home: &Path) {
+    let ccr_root = home.join(".ccr");
+    let platform_dir = ccr_root.join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        r#"
+default_config = "plain"
+current_config = "plain"
+
+[plain]
+description = "普通中转"
+base_url = "https://plain.example.com"
+auth_token = "sk-plain-123456"
+
+[client]
+description = "客户 A"
+base_url = "https://client.example.com"
+auth_token = "sk-client-123456"
+bundle = "client-a.json"
+
+[client.settings]
+outputStyle = "Explanatory"
+
+[client.settings.permissions]
+deny = ["Read(./secrets/**)"]
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        ccr_root.join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "plain"
+"#,
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(ccr_root.join("bundles")).unwrap();
+    std::fs::write(
+        ccr_root.join("bundles").join("client-a.json"),
+        r#"{
+  "permissions": {"allow": ["Bash(ls:*)", "Bash(npm run test:*)"]},
+  "statusLine": {"type": "command", "command": "~/.claude/client-status.sh"}
+}"#,
+    )
+    .unwrap();
+
+    let claude_dir = home.join(".claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    std::fs::write(
+        claude_dir.join("settings.json"),
+        r#"{
+  "env": {"ANTHROPIC_BASE_URL": "https://plain.example.com", "ANTHROPIC_AUTH_TOKEN": "sk-plain-123456"},
+  "permissions": {"allow": ["Bash(ls:*)"]},
+  "theme": "dark"
+}"#,
+    )
+    .unwrap();
+}
+
+/// 读取 Claude settings.json
+fn settings(home: &Path) -> Value {
+    serde_json::from_str(
+        &std::fs::read_to_string(home.join(".claude").join("settings.json")).unwrap(),
+    )
+    .unwrap()
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 切换时合并设置包
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_merges_bundle() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(
+        run_ccr(home.path(), &["switch", "client"]),
+        "ccr switch client",
+    );
+    let settings = settings(home.path());
+    assert_eq!(
+        settings["permissions"],
+        json!({
+            "allow": ["Bash(ls:*)", "Bash(npm run test:*)"],
+            "deny": ["Read(./secrets/**)"]
+        })
+    );
+    assert_eq!(
+        settings["statusLine"]["command"],
+        "~/.claude/client-status.sh"
+    );
+    assert_eq!(settings["outputStyle"], "Explanatory");
+    assert_eq!(settings["theme"], "dark");
+    assert_eq!(
+        settings["env"]["ANTHROPIC_BASE_URL"],
+        "https://client.example.com"
+    );
+
+    // 写入记录不进入 settings.json 的 env（否则会传给 Claude Code 的子进程）
+    assert!(settings["env"].get("CCR_MANAGED_SETTINGS").is_none());
+    let state =
+        std::fs::read_to_string(home.path().join(".ccr").join("managed_settings.json")).unwrap();
+    assert!(state.contains("settings.json"));
+    assert!(state.contains("Explanatory"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 下次切换移除上一个设置包
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_next_switch_removes_previous_bundle() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+    let original = settings(home.path());
+
+    assert_success(
+        run_ccr(home.path(), &["switch", "client"]),
+        "ccr switch client",
+    );
+    assert_success(
+        run_ccr(home.path(), &["switch", "plain"]),
+        "ccr switch plain",
+    );
+
+    assert_eq!(settings(home.path()), original);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 演练模式
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_dry_run_shows_diff_without_writing() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+    let settings_path = home.path().join(".claude").join("settings.json");
+    let before = std::fs::read_to_string(&settings_path).unwrap();
+
+    let stdout = assert_success(
+        run_ccr(home.path(), &["switch", "client", "--dry-run"]),
+        "ccr switch --dry-run",
+    );
+    assert!(stdout.contains("+ permissions.allow[]: \"Bash(npm run test:*)\""));
+    assert!(stdout.contains("+ outputStyle: \"Explanatory\""));
+    assert!(stdout.contains("~ env.ANTHROPIC_BASE_URL"));
+    assert!(!stdout.contains("sk-client-123456"));
+    assert!(!stdout.contains("CCR_MANAGED_SETTINGS"));
+
+    assert_eq!(std::fs::read_to_string(&settings_path).unwrap(), before);
+    let profiles = std::fs::read_to_string(
+        home.path()
+            .join(".ccr")
+            .join("platforms")
+            .join("claude")
+            .join("profiles.toml"),
+    )
+    .unwrap();
+    assert!(profiles.contains("current_config = \"plain\""));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 迁移旧版本写入记录
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_legacy_managed_settings_are_migrated() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    // 旧版本把上次写入的片段记录在 env.CCR_MANAGED_SETTINGS 中
+    let settings_path = home.path().join(".claude").join("settings.json");
+    let mut legacy = settings(home.path());
+    legacy["outputStyle"] = json!("Explanatory");
+    legacy["env"]["CCR_MANAGED_SETTINGS"] = json!(r#"{"outputStyle":"Explanatory"}"#);
+    std::fs::write(&settings_path, legacy.to_string()).unwrap();
+
+    assert_success(
+        run_ccr(home.path(), &["switch", "plain"]),
+        "ccr switch plain",
+    );
+
+    let settings = settings(home.path());
+    assert!(settings.get("outputStyle").is_none());
+    assert!(settings["env"].get("CCR_MANAGED_SETTINGS").is_none());
+    assert_eq!(settings["theme"], "dark");
+}
@@ -0,0 +1,266 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 Shell 集成 (ccr env / ccr hook) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `ccr env <profile> --shell` 输出可被 shell 执行的 export / unset 语句
+// - 未指定 profile 时使用项目固定的 profile
+// - 钩子模式只在进入 / 离开固定项目时输出
+// - `ccr hook <shell>` 输出对应 shell 的钩子脚本
+//
+// 共计: 4 个集成测试
+
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 构造在隔离的 HOME / CCR_ROOT 下运行的 ccr 命令
+fn ccr(home: &Path, cwd: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccr"));
+    command
+        .args(args)
+        .current_dir(cwd)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_ENV_PROJECT")
+        .env_remove("CCR_ENV_PLATFORM");
+    command
+}
+
+/// 断言命令成功并返回标准输出
+fn stdout_of(output: Output) -> String {
+    assert!(
+        output.status.success(),
+        "命令失败:\nstdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// 准备两个 Claude profiles，全局当前为 main
+fn setup(home: &Path) {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        r#"
+default_config = "main"
+current_config = "main"
+
+[main]
+description = "主中转"
+base_url = "https://main.example.com"
+auth_token = "sk-main-123456"
+model = "claude-sonnet-4"
+
+[relay]
+description = "项目中转"
+base_url = "https://relay.example.com"
+auth_token = "sk-relay-123456"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "main"
+"#,
+    )
+    .unwrap();
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 输出可执行的 export / unset 语句
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(unix)]
+#[test]
+fn test_env_output_is_evaluable() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let script = stdout_of(
+        ccr(
+            home.path(),
+            home.path(),
+            &["env", "relay", "--shell", "bash"],
+        )
+        .output()
+        .unwrap(),
+    );
+    assert!(script.contains("export ANTHROPIC_AUTH_TOKEN='sk-relay-123456'"));
+    assert!(script.contains("unset ANTHROPIC_MODEL"));
+
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(format!(
+            "{}\necho \"$ANTHROPIC_BASE_URL|${{ANTHROPIC_MODEL:-unset}}\"",
+            script
+        ))
+        .env("ANTHROPIC_MODEL", "stale-model")
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "https://relay.example.com|unset"
+    );
+
+    let fish = stdout_of(
+        ccr(
+            home.path(),
+            home.path(),
+            &["env", "relay", "--shell", "fish"],
+        )
+        .output()
+        .unwrap(),
+    );
+    assert!(fish.contains("set -gx ANTHROPIC_AUTH_TOKEN 'sk-relay-123456'"));
+    assert!(fish.contains("set -e ANTHROPIC_MODEL"));
+
+    let pwsh = stdout_of(
+        ccr(
+            home.path(),
+            home.path(),
+            &["env", "relay", "--shell", "pwsh"],
+        )
+        .output()
+        .unwrap(),
+    );
+    assert!(pwsh.contains("$env:ANTHROPIC_AUTH_TOKEN = 'sk-relay-123456'"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 默认使用项目固定的 profile
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_env_defaults_to_pinned_profile() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let project = home.path().join("project");
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    std::fs::write(project.join(".ccr.toml"), "profile = \"relay\"\n").unwrap();
+
+    let script = stdout_of(
+        ccr(
+            home.path(),
+            &project.join("src"),
+            &["env", "--shell", "zsh"],
+        )
+        .output()
+        .unwrap(),
+    );
+    assert!(script.contains("export ANTHROPIC_AUTH_TOKEN='sk-relay-123456'"));
+
+    let script = stdout_of(
+        ccr(home.path(), home.path(), &["env", "--shell", "zsh"])
+            .output()
+            .unwrap(),
+    );
+    assert!(script.contains("export ANTHROPIC_AUTH_TOKEN='sk-main-123456'"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 钩子只在进入 / 离开固定项目时输出
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_hook_mode_reacts_to_pin_changes() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let project = home.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join(".ccr.toml"), "profile = \"relay\"\n").unwrap();
+    let marker = project.join(".ccr.toml").display().to_string();
+
+    // 不在固定项目中: 不修改环境
+    let script = stdout_of(
+        ccr(
+            home.path(),
+            home.path(),
+            &["env", "--shell", "bash", "--hook"],
+        )
+        .output()
+        .unwrap(),
+    );
+    assert!(script.is_empty());
+
+    // 进入固定项目: 应用项目 profile 并记录标记
+    let script = stdout_of(
+        ccr(home.path(), &project, &["env", "--shell", "bash", "--hook"])
+            .output()
+            .unwrap(),
+    );
+    assert!(script.contains("export ANTHROPIC_AUTH_TOKEN='sk-relay-123456'"));
+    assert!(script.contains(&format!("export CCR_ENV_PROJECT='{}'", marker)));
+
+    // 仍在同一项目中: 不重复输出
+    let script = stdout_of(
+        ccr(home.path(), &project, &["env", "--shell", "bash", "--hook"])
+            .env("CCR_ENV_PROJECT", &marker)
+            .env("CCR_ENV_PLATFORM", "claude")
+            .output()
+            .unwrap(),
+    );
+    assert!(script.is_empty());
+
+    // 离开固定项目: 恢复全局 profile 并清除标记
+    let script = stdout_of(
+        ccr(
+            home.path(),
+            home.path(),
+            &["env", "--shell", "bash", "--hook"],
+        )
+        .env("CCR_ENV_PROJECT", &marker)
+        .env("CCR_ENV_PLATFORM", "claude")
+        .output()
+        .unwrap(),
+    );
+    assert!(script.contains("export ANTHROPIC_AUTH_TOKEN='sk-main-123456'"));
+    assert!(script.contains("unset CCR_ENV_PROJECT"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 钩子脚本
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_hook_scripts() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let zsh = stdout_of(
+        ccr(home.path(), home.path(), &["hook", "zsh"])
+            .output()
+            .unwrap(),
+    );
+    assert!(zsh.contains("add-zsh-hook chpwd _ccr_hook"));
+    assert!(zsh.contains("env --shell zsh --hook"));
+
+    let bash = stdout_of(
+        ccr(home.path(), home.path(), &["hook", "bash"])
+            .output()
+            .unwrap(),
+    );
+    assert!(bash.contains("PROMPT_COMMAND"));
+
+    let output = ccr(home.path(), home.path(), &["hook", "tcsh"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
@@ -0,0 +1,314 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 自动选择切换 (ccr switch --auto) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，
+// 并对本地模拟的 /v1/models 端点进行探测，不会访问外部网络
+//
+// 测试内容:
+// - `--auto --tag` 剔除不可用的候选，切换到延迟最低的配置并记录选择依据
+// - `--prefer cost` 按模型单价选择，最佳配置已是当前配置时不切换，只记录选择依据
+// - 候选全部不可用时保持当前配置，`--dry-run` 不写入文件
+//
+// 共计: 3 个集成测试
+
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::time::Duration;
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccr"));
+    command
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .env_remove("CCR_LOCK_DIR");
+    for (name, _) in std::env::vars() {
+        if name.starts_with("ANTHROPIC_")
+            || name.starts_with("OPENAI_")
+            || name.to_ascii_lowercase().ends_with("_proxy")
+        {
+            command.env_remove(name);
+        }
+    }
+    command.output().unwrap()
+}
+
+/// 运行 ccr 命令并断言成功，返回 stdout
+fn run_ok(home: &Path, args: &[&str]) -> String {
+    let output = run_ccr(home, args);
+    assert!(
+        output.status.success(),
+        "ccr {:?} 失败:\nstdout: {}\nstderr: {}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// 启动模拟的 /v1/models 端点，每次响应前等待 `delay_ms` 毫秒
+fn start_mock_provider(delay_ms: u64) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 1024];
+                while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut chunk) {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                    }
+                }
+
+                std::thread::sleep(Duration::from_millis(delay_ms));
+                let body = r#"{"data":[{"id":"claude-3-5-haiku-20241022"},{"id":"claude-opus-4-5-20251101"}]}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            });
+        }
+    });
+
+    endpoint
+}
+
+/// 返回一个没有服务监听的地址
+fn closed_endpoint() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+/// 准备 Claude 平台的配置，当前配置为 alpha
+///
+/// - alpha: 较慢 (300ms)，低价模型，标签 relay
+/// - beta: 快，高价模型，标签 relay
+/// - gamma: 无法连接，标签 relay
+/// - official: 快，但没有 relay 标签
+fn setup(home: &Path, relay_reachable: bool) {
+    let (alpha, beta) = if relay_reachable {
+        (start_mock_provider(300), start_mock_provider(0))
+    } else {
+        (closed_endpoint(), closed_endpoint())
+    };
+    let gamma = closed_endpoint();
+    let official = start_mock_provider(0);
+
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alpha"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        format!(
+            r#"
+default_config = "alpha"
+current_config = "alpha"
+
+[alpha]
+base_url = "{alpha}"
+auth_token = "sk-alpha-1234567890"
+model = "claude-3-5-haiku-20241022"
+tags = ["relay"]
+
+[beta]
+base_url = "{beta}"
+auth_token = "sk-beta-1234567890"
+model = "claude-opus-4-5-20251101"
+tags = ["relay"]
+
+[gamma]
+base_url = "{gamma}"
+auth_token = "sk-gamma-1234567890"
+tags = ["relay"]
+
+[official]
+base_url = "{official}"
+auth_token = "sk-official-1234567890"
+"#
+        ),
+    )
+    .unwrap();
+
+    let claude_dir = home.join(".claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    std::fs::write(
+        claude_dir.join("settings.json"),
+        format!(
+            r#"{{"env":{{"ANTHROPIC_BASE_URL":"{alpha}","ANTHROPIC_AUTH_TOKEN":"sk-alpha-1234567890"}}}}"#
+        ),
+    )
+    .unwrap();
+}
+
+fn settings_base_url(home: &Path) -> String {
+    let settings: Value = serde_json::from_str(
+        &std::fs::read_to_string(home.join(".claude").join("settings.json")).unwrap(),
+    )
+    .unwrap();
+    settings["env"]["ANTHROPIC_BASE_URL"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+fn profile_base_url(home: &Path, profile: &str) -> String {
+    let profiles: toml::Value = toml::from_str(
+        &std::fs::read_to_string(
+            home.join(".ccr")
+                .join("platforms")
+                .join("claude")
+                .join("profiles.toml"),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    profiles[profile]["base_url"].as_str().unwrap().to_string()
+}
+
+/// 读取历史记录（最新的在前）
+fn history(home: &Path) -> Vec<Value> {
+    let path = home.join(".claude").join("ccr_history.json");
+    if !path.exists() {
+        return Vec::new();
+    }
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+// ═══════════════════════════════════════════════════════════
+// 测试用例
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_auto_switches_to_fastest_healthy_tagged_profile() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home, true);
+
+    let stdout = run_ok(home, &["switch", "--auto", "--tag", "relay"]);
+    assert!(stdout.contains("最佳配置: beta"), "{stdout}");
+    assert!(!stdout.contains("official"), "{stdout}");
+    assert_eq!(settings_base_url(home), profile_base_url(home, "beta"));
+
+    let entry = &history(home)[0];
+    assert_eq!(entry["operation"], "Switch");
+    assert_eq!(entry["details"]["from_config"], "alpha");
+    assert_eq!(entry["details"]["to_config"], "beta");
+    let extra = entry["details"]["extra"].as_str().unwrap();
+    assert!(extra.contains("策略: latency"), "{extra}");
+    assert!(extra.contains("2/3 个候选可用"), "{extra}");
+    let notes = entry["notes"].as_str().unwrap();
+    assert!(notes.starts_with("beta ("), "{notes}");
+    assert!(notes.contains("gamma (❌ 不健康"), "{notes}");
+}
+
+#[test]
+fn test_auto_prefers_cheapest_model_and_skips_when_already_current() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home, true);
+
+    let stdout = run_ok(
+        home,
+        &["switch", "--auto", "--tag", "relay", "--prefer", "cost"],
+    );
+    assert!(stdout.contains("最佳配置: alpha"), "{stdout}");
+    assert!(stdout.contains("无需切换"), "{stdout}");
+    let entries = history(home);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["operation"], "AutoKeep");
+    assert_eq!(entries[0]["details"]["to_config"], "alpha");
+    assert!(
+        entries[0]["details"]["extra"]
+            .as_str()
+            .unwrap()
+            .contains("策略: cost")
+    );
+    assert!(entries[0]["notes"].as_str().unwrap().starts_with("alpha ("));
+
+    // 优先级权重让较贵的 beta 胜出
+    let profiles = home
+        .join(".ccr")
+        .join("platforms")
+        .join("claude")
+        .join("profiles.toml");
+    let content = std::fs::read_to_string(&profiles).unwrap().replace(
+        "model = \"claude-opus-4-5-20251101\"",
+        "model = \"claude-opus-4-5-20251101\"\npriority = 100",
+    );
+    std::fs::write(&profiles, content).unwrap();
+
+    let stdout = run_ok(
+        home,
+        &["switch", "--auto", "--tag", "relay", "--prefer", "cost"],
+    );
+    assert!(stdout.contains("最佳配置: beta"), "{stdout}");
+    let entry = &history(home)[0];
+    assert_eq!(entry["details"]["to_config"], "beta");
+    assert!(
+        entry["details"]["extra"]
+            .as_str()
+            .unwrap()
+            .contains("策略: cost")
+    );
+    assert!(entry["notes"].as_str().unwrap().contains("权重 100"));
+}
+
+#[test]
+fn test_auto_keeps_current_when_no_candidate_is_healthy() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    setup(home, false);
+    let before = settings_base_url(home);
+
+    let output = run_ccr(home, &["switch", "--auto", "--tag", "relay"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("均不可用"));
+    assert_eq!(settings_base_url(home), before);
+    assert!(history(home).is_empty());
+
+    // 不带标签时 official 可用；--dry-run 只预览
+    let stdout = run_ok(home, &["switch", "--auto", "--dry-run"]);
+    assert!(stdout.contains("最佳配置: official"), "{stdout}");
+    assert!(stdout.contains("演练模式"), "{stdout}");
+    assert_eq!(settings_base_url(home), before);
+
+    assert!(
+        !run_ccr(home, &["switch", "alpha", "--prefer", "cost"])
+            .status
+            .success()
+    );
+    assert!(
+        !run_ccr(home, &["switch", "alpha", "--auto"])
+            .status
+            .success()
+    );
+}
@@ -0,0 +1,253 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 切换事务日志集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - 切换成功后不遗留事务日志
+// - 切换中途失败时恢复 settings.json、profiles.toml 与注册表
+// - 启动时撤销未提交的中断事务，补全已提交的中断事务
+//
+// 共计: 3 个集成测试
+
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 一个已退出进程的 pid（模拟被终止的 ccr 进程）
+fn exited_pid() -> u32 {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+    child.wait().unwrap();
+    pid
+}
+
+/// 准备 alpha / beta 两个 Claude profile，当前为 alpha 并已应用
+fn setup(home: &Path) {
+    let platform_dir = home.join(".ccr").join("platforms").join("claude");
+    std::fs::create_dir_all(&platform_dir).unwrap();
+    std::fs::write(
+        platform_dir.join("profiles.toml"),
+        r#"
+default_config = "alpha"
+current_config = "alpha"
+
+[alpha]
+description = "Alpha 中转"
+base_url = "https://alpha.example.com"
+auth_token = "sk-alpha-123456"
+
+[beta]
+description = "Beta 中转"
+base_url = "https://beta.example.com"
+auth_token = "sk-beta-123456"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alpha"
+"#,
+    )
+    .unwrap();
+
+    let claude_dir = home.join(".claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    std::fs::write(
+        claude_dir.join("settings.json"),
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://alpha.example.com","ANTHROPIC_AUTH_TOKEN":"sk-alpha-123456"}}"#,
+    )
+    .unwrap();
+}
+
+/// 切换涉及的文件
+fn tracked_files(home: &Path) -> Vec<PathBuf> {
+    vec![
+        home.join(".claude").join("settings.json"),
+        home.join(".ccr")
+            .join("platforms")
+            .join("claude")
+            .join("profiles.toml"),
+        home.join(".ccr").join("config.toml"),
+    ]
+}
+
+fn read_all(files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .map(|f| std::fs::read_to_string(f).unwrap())
+        .collect()
+}
+
+fn journal_dir(home: &Path) -> PathBuf {
+    home.join(".ccr").join("journal")
+}
+
+/// 写入日志快照目录中的内容副本
+fn write_snapshot(home: &Path, id: &str, name: &str, content: &str) {
+    let dir = journal_dir(home).join(id);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(name), content).unwrap();
+}
+
+fn journal_count(home: &Path) -> usize {
+    std::fs::read_dir(journal_dir(home))
+        .map(|entries| entries.count())
+        .unwrap_or(0)
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 切换成功
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_switch_leaves_no_journal() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = run_ccr(home.path(), &["switch", "beta"]);
+    assert!(
+        output.status.success(),
+        "切换失败: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let settings = std::fs::read_to_string(&tracked_files(home.path())[0]).unwrap();
+    assert!(settings.contains("https://beta.example.com"));
+    assert_eq!(journal_count(home.path()), 0);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 中途失败时回滚
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_failed_switch_rolls_back_all_files() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+    let files = tracked_files(home.path());
+    let before = read_all(&files);
+
+    // 损坏的历史文件使最后一步（记录历史）失败，此时设置与配置已经写入
+    std::fs::write(
+        home.path().join(".claude").join("ccr_history.json"),
+        "not json",
+    )
+    .unwrap();
+
+    let output = run_ccr(home.path(), &["switch", "beta"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("已恢复切换前的文件"));
+
+    assert_eq!(read_all(&files), before);
+    assert_eq!(journal_count(home.path()), 0);
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 启动时恢复中断的事务
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_interrupted_transactions_are_recovered_on_start() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+    let files = tracked_files(home.path());
+    let before = read_all(&files);
+    std::fs::create_dir_all(journal_dir(home.path())).unwrap();
+    let pid = exited_pid();
+
+    // 未提交: 模拟切换写入一半时进程被终止
+    std::fs::write(&files[0], r#"{"env":{"ANTHROPIC_BASE_URL":"half"}}"#).unwrap();
+    for (index, content) in before.iter().enumerate() {
+        write_snapshot(
+            home.path(),
+            "pending-switch",
+            &format!("{}.before", index),
+            content,
+        );
+    }
+    let pending = json!({
+        "id": "pending-switch",
+        "operation": "switch",
+        "started_at": "2026-01-01T10:00:00+08:00",
+        "pid": pid,
+        "state": "pending",
+        "files": files.iter().enumerate().map(|(index, path)| json!({
+            "path": path,
+            "before": { "copy": format!("{}.before", index) },
+        })).collect::<Vec<_>>(),
+    });
+    std::fs::write(
+        journal_dir(home.path()).join("pending-switch.json"),
+        pending.to_string(),
+    )
+    .unwrap();
+
+    let output = run_ccr(home.path(), &["current"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("已恢复操作前的文件"));
+    assert_eq!(read_all(&files), before);
+    assert_eq!(journal_count(home.path()), 0);
+
+    // 已提交: 补全日志中记录的新内容
+    write_snapshot(home.path(), "committed-switch", "0.before", &before[0]);
+    write_snapshot(
+        home.path(),
+        "committed-switch",
+        "0.after",
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://beta.example.com"}}"#,
+    );
+    let committed = json!({
+        "id": "committed-switch",
+        "operation": "switch",
+        "started_at": "2026-01-01T11:00:00+08:00",
+        "pid": pid,
+        "state": "committed",
+        "files": [{
+            "path": files[0],
+            "before": { "copy": "0.before" },
+            "after": { "copy": "0.after" },
+        }],
+    });
+    std::fs::write(
+        journal_dir(home.path()).join("committed-switch.json"),
+        committed.to_string(),
+    )
+    .unwrap();
+
+    let output = run_ccr(home.path(), &["current"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("已补全提交的写入"));
+    assert!(
+        std::fs::read_to_string(&files[0])
+            .unwrap()
+            .contains("https://beta.example.com")
+    );
+    assert_eq!(journal_count(home.path()), 0);
+}
@@ -0,0 +1,391 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 CCR 同步后端集成测试
+//
+// 测试内容:
+// - 本地目录后端（Syncthing / Dropbox 场景）
+// - Git 后端（本地 bare 仓库，每次上传一个提交）
+// - S3 后端（进程内的 MinIO 风格最小实现）
+// - 加密包装后端（远程只保存密文）
+//
+// 每个后端都走同一套流程: 上传目录 → 从另一端完整拉取 → 修改/删除后再次上传
+
+use ccr::sync::backend::{
+    EncryptedBackend, GitBackend, LocalDirBackend, S3Backend, SyncBackend, pull_tree, push_tree,
+};
+use ccr::sync::crypto::{SyncCipher, is_encrypted};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 创建一个模拟的 ~/.ccr 目录
+fn create_source_dir() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("config.toml"),
+        "[anthropic]\nbase_url = \"https://api.anthropic.com\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.path().join("platforms/claude")).unwrap();
+    std::fs::write(
+        dir.path().join("platforms/claude/profiles.toml"),
+        "[default]\nmodel = \"sonnet\"\n",
+    )
+    .unwrap();
+    // 应被排除的文件
+    std::fs::write(dir.path().join("settings.json.bak"), "old").unwrap();
+    dir
+}
+
+fn read(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// 通用流程: 上传 → 拉取到新目录 → 修改与删除 → 再次上传并验证远程
+async fn exercise_backend(writer: &dyn SyncBackend, reader: &dyn SyncBackend) {
+    let source = create_source_dir();
+
+    assert!(!writer.exists().await.unwrap());
+    assert!(writer.fetch().await.unwrap().is_empty());
+
+    let (uploaded, deleted) = push_tree(writer, source.path()).await.unwrap();
+    writer.commit("first push").await.unwrap();
+    assert_eq!((uploaded, deleted), (2, 0));
+    assert!(reader.exists().await.unwrap());
+
+    // 从另一端拉取
+    let target = TempDir::new().unwrap();
+    let restored = target.path().join("ccr");
+    assert_eq!(pull_tree(reader, &restored).await.unwrap(), 2);
+    assert_eq!(
+        read(&restored.join("platforms/claude/profiles.toml")),
+        "[default]\nmodel = \"sonnet\"\n"
+    );
+    assert!(!restored.join("settings.json.bak").exists());
+
+    // 修改一个文件、删除一个文件后再次上传
+    std::fs::write(source.path().join("config.toml"), "[anthropic]\n").unwrap();
+    std::fs::remove_dir_all(source.path().join("platforms")).unwrap();
+    let (uploaded, deleted) = push_tree(writer, source.path()).await.unwrap();
+    writer.commit("second push").await.unwrap();
+    assert_eq!((uploaded, deleted), (1, 1));
+
+    let remote = reader.fetch().await.unwrap();
+    assert_eq!(remote.len(), 1);
+    assert_eq!(remote["config.toml"], b"[anthropic]\n");
+
+    // 没有变化时不产生任何写入
+    assert_eq!(push_tree(writer, source.path()).await.unwrap(), (0, 0));
+    writer.commit("noop push").await.unwrap();
+}
+
+// ═══════════════════════════════════════════════════════════
+// 本地目录后端
+// ═══════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn test_local_dir_backend_roundtrip() {
+    let shared = TempDir::new().unwrap();
+    let root = shared.path().join("Dropbox/ccr-sync/ccr");
+    let writer = LocalDirBackend::new(&root);
+    let reader = LocalDirBackend::new(&root);
+
+    exercise_backend(&writer, &reader).await;
+
+    // 删除文件后空目录被清理
+    assert!(!root.join("platforms").exists());
+    assert_eq!(writer.kind(), "local");
+}
+
+#[tokio::test]
+async fn test_local_dir_backend_single_file() {
+    let shared = TempDir::new().unwrap();
+    let backend = LocalDirBackend::new(shared.path().join("settings"));
+
+    let source = TempDir::new().unwrap();
+    let file = source.path().join("settings.json");
+    std::fs::write(&file, "{\"a\":1}").unwrap();
+    push_tree(&backend, &file).await.unwrap();
+
+    // 本地为已存在的文件时直接覆盖该文件
+    let target = TempDir::new().unwrap();
+    let restored = target.path().join("settings.json");
+    std::fs::write(&restored, "{}").unwrap();
+    pull_tree(&backend, &restored).await.unwrap();
+    assert_eq!(read(&restored), "{\"a\":1}");
+}
+
+#[tokio::test]
+async fn test_encrypted_backend_stores_ciphertext() {
+    let shared = TempDir::new().unwrap();
+    let root = shared.path().join("ccr");
+    let encrypted = |root: &Path| {
+        EncryptedBackend::new(
+            Box::new(LocalDirBackend::new(root)),
+            SyncCipher::new("correct horse").unwrap(),
+        )
+    };
+    let writer = encrypted(&root);
+    let reader = encrypted(&root);
+
+    exercise_backend(&writer, &reader).await;
+
+    // 远程目录中只有密文
+    let raw = std::fs::read(root.join("config.toml")).unwrap();
+    assert!(is_encrypted(&raw));
+    assert!(!String::from_utf8_lossy(&raw).contains("anthropic"));
+
+    // 口令错误时无法读取
+    let wrong = EncryptedBackend::new(
+        Box::new(LocalDirBackend::new(&root)),
+        SyncCipher::new("wrong").unwrap(),
+    );
+    assert!(wrong.fetch().await.is_err());
+}
+
+// ═══════════════════════════════════════════════════════════
+// Git 后端
+// ═══════════════════════════════════════════════════════════
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[tokio::test]
+async fn test_git_backend_commits_each_push() {
+    let temp = TempDir::new().unwrap();
+    let bare = temp.path().join("remote.git");
+    std::fs::create_dir_all(&bare).unwrap();
+    git(&bare, &["init", "--quiet", "--bare"]);
+    let url = bare.to_string_lossy().to_string();
+
+    let writer = GitBackend::new(&url, "main", "/ccr-sync/ccr", temp.path().join("work-a"));
+    let reader = GitBackend::new(&url, "main", "/ccr-sync/ccr", temp.path().join("work-b"));
+
+    exercise_backend(&writer, &reader).await;
+
+    // 两次有效上传对应两个提交，无变化的上传不产生提交
+    let log = git(&bare, &["log", "--format=%s", "main"]);
+    assert_eq!(
+        log.lines().collect::<Vec<_>>(),
+        vec!["second push", "first push"]
+    );
+
+    // 内容存放在 remote_path 对应的子目录下
+    let files = git(&bare, &["ls-tree", "-r", "--name-only", "main"]);
+    assert_eq!(files, "ccr-sync/ccr/config.toml");
+    assert!(writer.location().ends_with("#main:ccr-sync/ccr"));
+}
+
+// ═══════════════════════════════════════════════════════════
+// S3 后端（MinIO 风格的进程内替身）
+// ═══════════════════════════════════════════════════════════
+
+type Objects = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+/// 启动最小 S3 服务: 支持 PUT / GET / DELETE 对象与 ListObjectsV2（路径风格）
+async fn start_fake_s3(bucket: &'static str) -> (String, Objects) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let objects: Objects = Arc::new(Mutex::new(HashMap::new()));
+
+    let store = objects.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                break;
+            };
+            let store = store.clone();
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let header_end = loop {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    if n == 0 {
+                        return;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+
+                let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+                let mut lines = head.lines();
+                let mut request_line = lines.next().unwrap().split(' ');
+                let method = request_line.next().unwrap().to_string();
+                let target = request_line.next().unwrap().to_string();
+                let headers: HashMap<String, String> = lines
+                    .filter_map(|l| l.split_once(':'))
+                    .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+                    .collect();
+
+                let length: usize = headers
+                    .get("content-length")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0);
+                let mut body = buf[header_end..].to_vec();
+                while body.len() < length {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    body.extend_from_slice(&chunk[..n]);
+                }
+
+                let (status, response) =
+                    handle_s3(bucket, &store, &method, &target, &headers, body);
+                let reply = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    response.len()
+                );
+                stream.write_all(reply.as_bytes()).await.unwrap();
+                stream.write_all(&response).await.unwrap();
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+
+    (endpoint, objects)
+}
+
+fn handle_s3(
+    bucket: &str,
+    store: &Objects,
+    method: &str,
+    target: &str,
+    headers: &HashMap<String, String>,
+    body: Vec<u8>,
+) -> (&'static str, Vec<u8>) {
+    let authorized = headers
+        .get("authorization")
+        .is_some_and(|a| a.starts_with("AWS4-HMAC-SHA256 Credential=minio-access/"))
+        && headers.contains_key("x-amz-date")
+        && headers.contains_key("x-amz-content-sha256");
+    if !authorized {
+        return (
+            "403 Forbidden",
+            b"<Error><Code>AccessDenied</Code><Message>Access Denied</Message></Error>".to_vec(),
+        );
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = percent_decode(path);
+    let Some(rest) = path.strip_prefix(&format!("/{}", bucket)) else {
+        return (
+            "404 Not Found",
+            b"<Error><Code>NoSuchBucket</Code></Error>".to_vec(),
+        );
+    };
+    let key = rest.trim_start_matches('/');
+    let mut objects = store.lock().unwrap();
+
+    match (method, key.is_empty()) {
+        ("GET", true) => {
+            let params: HashMap<String, String> = query
+                .split('&')
+                .filter_map(|p| p.split_once('='))
+                .map(|(k, v)| (k.to_string(), percent_decode(v)))
+                .collect();
+            assert_eq!(params.get("list-type").map(String::as_str), Some("2"));
+            let prefix = params.get("prefix").cloned().unwrap_or_default();
+            let mut keys: Vec<&String> =
+                objects.keys().filter(|k| k.starts_with(&prefix)).collect();
+            keys.sort();
+            let contents: String = keys
+                .iter()
+                .map(|k| format!("<Contents><Key>{}</Key></Contents>", k))
+                .collect();
+            let xml = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?><ListBucketResult><Name>{}</Name>\
+                 <Prefix>{}</Prefix>{}<IsTruncated>false</IsTruncated></ListBucketResult>",
+                bucket, prefix, contents
+            );
+            ("200 OK", xml.into_bytes())
+        }
+        ("GET", false) => match objects.get(key) {
+            Some(content) => ("200 OK", content.clone()),
+            None => (
+                "404 Not Found",
+                b"<Error><Code>NoSuchKey</Code></Error>".to_vec(),
+            ),
+        },
+        ("PUT", false) => {
+            objects.insert(key.to_string(), body);
+            ("200 OK", Vec::new())
+        }
+        ("DELETE", false) => {
+            objects.remove(key);
+            ("204 No Content", Vec::new())
+        }
+        _ => ("405 Method Not Allowed", Vec::new()),
+    }
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+            out.push(u8::from_str_radix(hex, 16).unwrap());
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).unwrap()
+}
+
+#[tokio::test]
+async fn test_s3_backend_against_local_stand_in() {
+    let (endpoint, objects) = start_fake_s3("ccr-bucket").await;
+
+    let writer = S3Backend::new(
+        &endpoint,
+        "ccr-bucket",
+        "us-east-1",
+        "minio-access",
+        "minio-secret",
+        "/ccr-sync/ccr",
+    )
+    .unwrap();
+    let reader = S3Backend::new(
+        &endpoint,
+        "ccr-bucket",
+        "us-east-1",
+        "minio-access",
+        "minio-secret",
+        "ccr-sync/ccr/",
+    )
+    .unwrap();
+
+    exercise_backend(&writer, &reader).await;
+
+    let keys: Vec<String> = objects.lock().unwrap().keys().cloned().collect();
+    assert_eq!(keys, vec!["ccr-sync/ccr/config.toml".to_string()]);
+
+    // 错误的凭证被拒绝
+    let denied = S3Backend::new(&endpoint, "ccr-bucket", "us-east-1", "wrong", "x", "ccr").unwrap();
+    let err = denied.fetch().await.unwrap_err();
+    assert!(err.to_string().contains("AccessDenied"));
+}
@@ -0,0 +1,165 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 同步文件夹 (ccr sync <folder>) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - sync.toml 启用加密时，`ccr sync <folder> push` 只向远程写入密文，pull 还原明文
+// - `ccr sync all push` 同样遵循加密设置
+//
+// 共计: 2 个集成测试
+
+use ccr::sync::crypto::is_encrypted;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+const SECRET: &str = "sk-folder-secret-123456";
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令（口令通过环境变量提供）
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env("CCR_SYNC_PASSPHRASE", "correct horse")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .env_remove("CCR_SYNC_CONFIG_PATH")
+        .env_remove("CCR_SYNC_FOLDERS_CONFIG")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功并返回标准输出
+fn assert_success(output: Output, what: &str) -> String {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// 启用同步加密，并注册一个使用本地目录后端的文件夹
+///
+/// 返回 (本地文件夹, 远程目录)
+fn setup(home: &Path) -> (PathBuf, PathBuf) {
+    let ccr_root = home.join(".ccr");
+    std::fs::create_dir_all(&ccr_root).unwrap();
+    std::fs::write(
+        ccr_root.join("sync.toml"),
+        r#"
+enabled = true
+webdav_url = "https://dav.example.com/dav/"
+username = "user"
+password = "pass"
+remote_path = "/ccr/"
+encryption = true
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        ccr_root.join("sync_folders.toml"),
+        r#"
+[webdav]
+url = "https://dav.example.com/dav/"
+username = "user"
+password = "pass"
+"#,
+    )
+    .unwrap();
+
+    let local = home.join("notes");
+    std::fs::create_dir_all(local.join("sub")).unwrap();
+    std::fs::write(local.join("token.txt"), SECRET).unwrap();
+    std::fs::write(local.join("sub/more.txt"), SECRET).unwrap();
+
+    let remote = home.join("remote");
+    assert_success(
+        run_ccr(
+            home,
+            &[
+                "sync",
+                "folder",
+                "add",
+                "notes",
+                local.to_str().unwrap(),
+                "--backend",
+                "local",
+                "--target",
+                remote.to_str().unwrap(),
+            ],
+        ),
+        "sync folder add",
+    );
+    (local, remote)
+}
+
+/// 断言远程目录中的每个文件都是密文且不含明文内容
+fn assert_remote_encrypted(remote: &Path) {
+    let mut files = Vec::new();
+    let mut dirs = vec![remote.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    assert_eq!(files.len(), 2, "远程文件: {:?}", files);
+    for file in files {
+        let raw = std::fs::read(&file).unwrap();
+        assert!(is_encrypted(&raw), "{} 不是密文", file.display());
+        assert!(!String::from_utf8_lossy(&raw).contains(SECRET));
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// 加密同步
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_folder_push_writes_ciphertext_when_encryption_enabled() {
+    let home = TempDir::new().unwrap();
+    let (local, remote) = setup(home.path());
+
+    assert_success(run_ccr(home.path(), &["sync", "notes", "push"]), "push");
+    assert_remote_encrypted(&remote);
+
+    // 拉取时解密还原明文
+    std::fs::remove_dir_all(&local).unwrap();
+    assert_success(run_ccr(home.path(), &["sync", "notes", "pull"]), "pull");
+    assert_eq!(
+        std::fs::read_to_string(local.join("token.txt")).unwrap(),
+        SECRET
+    );
+    assert_eq!(
+        std::fs::read_to_string(local.join("sub/more.txt")).unwrap(),
+        SECRET
+    );
+}
+
+#[test]
+fn test_all_push_writes_ciphertext_when_encryption_enabled() {
+    let home = TempDir::new().unwrap();
+    let (_local, remote) = setup(home.path());
+
+    assert_success(
+        run_ccr(home.path(), &["sync", "all", "push", "--force"]),
+        "all push",
+    );
+    assert_remote_encrypted(&remote);
+}
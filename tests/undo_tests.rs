@@ -0,0 +1,270 @@
+#![allow(clippy::unwrap_used)]
+// 🧪 撤销 (undo / history revert) 集成测试
+//
+// 所有测试在独立的 ccr 进程中运行，使用临时 HOME 与 CCR_ROOT，不会影响真实配置
+//
+// 测试内容:
+// - `ccr undo` 撤销切换，恢复 settings.json 与当前配置并记录撤销历史
+// - `ccr undo` 从删除前的备份恢复被删除的配置
+// - 之后的变更与要撤销的操作冲突时拒绝执行且不修改任何文件
+// - `ccr history revert <id>` 撤销较早的操作，不影响之后无关的变更
+// - 目标配置的 token 来自 ${env:…} 时同样可以撤销切换
+//
+// 共计: 5 个集成测试
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// ═══════════════════════════════════════════════════════════
+// 测试辅助函数
+// ═══════════════════════════════════════════════════════════
+
+/// 在隔离的 HOME / CCR_ROOT 下运行 ccr 命令
+fn run_ccr(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccr"))
+        .args(args)
+        .env("HOME", home)
+        .env("CCR_ROOT", home.join(".ccr"))
+        .env("CCR_LOG_LEVEL", "off")
+        .env("CCR_TEST_BETA_TOKEN", "sk-beta-from-env")
+        .env_remove("CCR_SETTINGS_PATH")
+        .env_remove("CCR_HISTORY_PATH")
+        .output()
+        .unwrap()
+}
+
+/// 断言命令执行成功并返回标准输出
+fn assert_success(output: Output, what: &str) -> String {
+    assert!(
+        output.status.success(),
+        "{} 失败:\nstdout: {}\nstderr: {}",
+        what,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// 准备 alpha / beta / gamma 三个 Claude profile，当前为 alpha 并已应用
+fn setup(home: &Path) {
+    std::fs::create_dir_all(profiles_file(home).parent().unwrap()).unwrap();
+    std::fs::write(
+        profiles_file(home),
+        r#"
+default_config = "alpha"
+current_config = "alpha"
+
+[alpha]
+description = "Alpha 中转"
+base_url = "https://alpha.example.com"
+auth_token = "sk-alpha-123456"
+
+[beta]
+description = "Beta 中转"
+base_url = "https://beta.example.com"
+auth_token = "sk-beta-123456"
+
+[gamma]
+description = "Gamma 中转"
+base_url = "https://gamma.example.com"
+auth_token = "sk-gamma-123456"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.join(".ccr").join("config.toml"),
+        r#"
+default_platform = "claude"
+current_platform = "claude"
+
+[claude]
+enabled = true
+current_profile = "alpha"
+"#,
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(home.join(".claude")).unwrap();
+    std::fs::write(
+        settings_file(home),
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://alpha.example.com","ANTHROPIC_AUTH_TOKEN":"sk-alpha-123456"}}"#,
+    )
+    .unwrap();
+}
+
+fn profiles_file(home: &Path) -> PathBuf {
+    home.join(".ccr")
+        .join("platforms")
+        .join("claude")
+        .join("profiles.toml")
+}
+
+fn settings_file(home: &Path) -> PathBuf {
+    home.join(".claude").join("settings.json")
+}
+
+fn read(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// 读取历史记录（最新的在前）
+fn history(home: &Path) -> Vec<Value> {
+    let content = read(&home.join(".claude").join("ccr_history.json"));
+    serde_json::from_str(&content).unwrap()
+}
+
+/// 指定操作类型的最新一条历史记录 ID
+fn latest_id(home: &Path, operation: &str) -> String {
+    history(home)
+        .iter()
+        .find(|e| e["operation"] == operation)
+        .map(|e| e["id"].as_str().unwrap().to_string())
+        .unwrap()
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 1: 撤销切换
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_undo_switch_restores_previous_profile() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    let switch_id = latest_id(home.path(), "Switch");
+
+    assert_success(run_ccr(home.path(), &["-y", "undo"]), "ccr undo");
+    assert!(read(&settings_file(home.path())).contains("https://alpha.example.com"));
+    assert!(read(&profiles_file(home.path())).contains("current_config = \"alpha\""));
+
+    let revert = &history(home.path())[0];
+    assert_eq!(revert["operation"], "Revert");
+    assert_eq!(revert["reverts"], switch_id.as_str());
+    assert_eq!(revert["details"]["from_config"], "beta");
+    assert_eq!(revert["details"]["to_config"], "alpha");
+
+    // 撤销记录本身不可再被撤销
+    let output = run_ccr(home.path(), &["-y", "undo"]);
+    assert!(!output.status.success());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 2: 撤销删除
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_undo_delete_restores_profile_from_backup() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(
+        run_ccr(home.path(), &["-y", "delete", "beta"]),
+        "ccr delete beta",
+    );
+    assert!(!read(&profiles_file(home.path())).contains("[beta]"));
+
+    assert_success(run_ccr(home.path(), &["-y", "undo"]), "ccr undo");
+    let profiles = read(&profiles_file(home.path()));
+    assert!(profiles.contains("[beta]"));
+    assert!(profiles.contains("https://beta.example.com"));
+    assert!(profiles.contains("current_config = \"alpha\""));
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 3: 冲突时拒绝撤销
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_revert_refuses_on_conflict() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    let first_switch = latest_id(home.path(), "Switch");
+    assert_success(
+        run_ccr(home.path(), &["switch", "gamma"]),
+        "ccr switch gamma",
+    );
+
+    let settings = read(&settings_file(home.path()));
+    let profiles = read(&profiles_file(home.path()));
+
+    // 之后切换到了 gamma，撤销第一次切换（beta -> alpha）会覆盖这次变更
+    let output = run_ccr(
+        home.path(),
+        &["-y", "history", "revert", &first_switch[..8]],
+    );
+    assert!(!output.status.success());
+    assert_eq!(output.status.code(), Some(79));
+    let all = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(all.contains("无法撤销"), "{}", all);
+
+    assert_eq!(read(&settings_file(home.path())), settings);
+    assert_eq!(read(&profiles_file(home.path())), profiles);
+    assert!(
+        history(home.path())
+            .iter()
+            .all(|e| e["operation"] != "Revert")
+    );
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 4: 按 ID 撤销较早的操作
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_history_revert_by_id() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    assert_success(
+        run_ccr(home.path(), &["-y", "disable", "gamma"]),
+        "ccr disable gamma",
+    );
+    let disable_id = latest_id(home.path(), "Disable");
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+
+    assert_success(
+        run_ccr(home.path(), &["-y", "history", "revert", &disable_id[..8]]),
+        "ccr history revert",
+    );
+
+    let profiles = read(&profiles_file(home.path()));
+    assert!(!profiles.contains("enabled = false"), "{}", profiles);
+    // 之后无关的切换保持不变
+    assert!(profiles.contains("current_config = \"beta\""));
+    assert!(read(&settings_file(home.path())).contains("https://beta.example.com"));
+    assert_eq!(history(home.path())[0]["reverts"], disable_id.as_str());
+}
+
+// ═══════════════════════════════════════════════════════════
+// 集成测试 5: 撤销切换到使用密钥来源的配置
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn test_undo_switch_to_profile_with_secret_source() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+    let profiles = read(&profiles_file(home.path())).replace(
+        "auth_token = \"sk-beta-123456\"",
+        "auth_token = \"${env:CCR_TEST_BETA_TOKEN}\"",
+    );
+    std::fs::write(profiles_file(home.path()), profiles).unwrap();
+
+    assert_success(run_ccr(home.path(), &["switch", "beta"]), "ccr switch beta");
+    assert!(read(&settings_file(home.path())).contains("sk-beta-from-env"));
+
+    // 历史中记录的是解析后的 token（掩码），与 settings.json 一致，不应报告冲突
+    assert_success(run_ccr(home.path(), &["-y", "undo"]), "ccr undo");
+    let settings = read(&settings_file(home.path()));
+    assert!(settings.contains("sk-alpha-123456"), "{}", settings);
+    assert!(read(&profiles_file(home.path())).contains("current_config = \"alpha\""));
+    assert_eq!(history(home.path())[0]["operation"], "Revert");
+}